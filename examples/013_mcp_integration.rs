@@ -162,6 +162,7 @@ except KeyboardInterrupt:
         env_vars: std::collections::HashMap::new(),
         startup_timeout_ms: 30000,
         max_message_size: Some(1024 * 1024), // 1MB
+        ..Default::default()
     }
 }
 
@@ -18,6 +18,7 @@ async fn configure_stdio_mcp_server() -> Result<(), Box<dyn std::error::Error>>
         working_dir: None,
         startup_timeout_ms: 10_000,
         max_message_size: Some(16 * 1024 * 1024),
+        ..Default::default()
     };
 
     // Create transport and MCP client
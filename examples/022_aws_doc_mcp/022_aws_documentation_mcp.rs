@@ -58,6 +58,7 @@ async fn create_aws_docs_mcp_client() -> Result<MCPClient, Box<dyn std::error::E
         working_dir: None,
         startup_timeout_ms: 30_000, // Give Docker time to start
         max_message_size: Some(16 * 1024 * 1024),
+        ..Default::default()
     };
 
     // Create MCP client with extended timeouts for Docker
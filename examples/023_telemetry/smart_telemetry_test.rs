@@ -41,6 +41,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         agent_id: Some("test-agent".to_string()),
         log_level: LogLevel::DEBUG,
         content_capture: true,
+        content_anonymization: None,
+        skip_log_group_check: false,
     };
     println!("   Enabled: {}", cloudwatch_explicit.is_enabled());
     println!("   Service Name: {:?}", cloudwatch_explicit.service_name());
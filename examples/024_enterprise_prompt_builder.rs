@@ -1839,6 +1839,7 @@ impl CallbackHandler for StreamingGuiCallback {
                         tool_name,
                         output,
                         error,
+                        error_source,
                         duration,
                         ..
                     } => {
@@ -1846,6 +1847,7 @@ impl CallbackHandler for StreamingGuiCallback {
                             self.on_tool(ToolEvent::Failed {
                                 name: tool_name,
                                 error: err,
+                                source: error_source,
                                 duration,
                             })
                             .await
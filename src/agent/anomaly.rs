@@ -0,0 +1,527 @@
+//! Behavioral anomaly detection for runaway loops and prompt injection.
+//!
+//! A [`BehaviorMonitor`] watches every model response of an agent's executions
+//! and flags behavior that rarely comes from the task itself:
+//!
+//! - [`AnomalyKind::ToolCallSpike`]: a response requests many more tool calls
+//!   than the agent's recent responses did
+//! - [`AnomalyKind::RepeatedRequest`]: the model requests the same tool calls
+//!   with the same input over and over within one execution
+//! - [`AnomalyKind::OutputExplosion`]: a response is many times longer than
+//!   the agent's recent responses
+//!
+//! Each anomaly is reported as a
+//! [`CallbackEvent::BehaviorAnomaly`](crate::agent::callbacks::CallbackEvent::BehaviorAnomaly)
+//! and handled by its [`AnomalyAction`]: logged, passed to an
+//! [`AnomalyApprover`] that decides whether the execution may continue, or
+//! tripping the monitor's circuit breaker. A tripped monitor stops the
+//! execution with [`TerminationReason::BehaviorAnomaly`] and fails every
+//! later execution of the agents sharing it until [`BehaviorMonitor::reset`]
+//! is called.
+//!
+//! ```no_run
+//! use stood::agent::anomaly::{AnomalyAction, AnomalyKind, BehaviorMonitor, BehaviorMonitorConfig};
+//! use stood::agent::Agent;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let monitor = BehaviorMonitor::new(
+//!     BehaviorMonitorConfig::new()
+//!         .with_max_identical_requests(3)
+//!         .with_action(AnomalyKind::OutputExplosion, AnomalyAction::Warn),
+//! );
+//! let mut agent = Agent::builder()
+//!     .with_behavior_monitor(monitor.clone())
+//!     .build()
+//!     .await?;
+//!
+//! let result = agent.execute("Triage the new support tickets").await?;
+//! if let Some(anomaly) = monitor.tripped() {
+//!     eprintln!("Agent halted: {}", anomaly);
+//!     // After investigating
+//!     monitor.reset();
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Spikes and explosions are measured against a rolling window of the
+//! monitor's recent responses, so they are only detected once
+//! [`BehaviorMonitorConfig::min_samples`] responses have been seen.
+//!
+//! [`TerminationReason::BehaviorAnomaly`]: crate::agent::stop::TerminationReason::BehaviorAnomaly
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::llm::traits::ChatResponse;
+
+/// Kind of behavioral anomaly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnomalyKind {
+    /// Far more tool calls in one response than usual
+    ToolCallSpike,
+    /// The same tool calls requested repeatedly in one execution
+    RepeatedRequest,
+    /// A response far longer than usual
+    OutputExplosion,
+}
+
+impl fmt::Display for AnomalyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ToolCallSpike => write!(f, "tool call spike"),
+            Self::RepeatedRequest => write!(f, "repeated request"),
+            Self::OutputExplosion => write!(f, "output explosion"),
+        }
+    }
+}
+
+/// A detected anomaly
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    /// What was observed, e.g. the tool call count and the usual count
+    pub detail: String,
+    /// Event loop cycle of the response (1-based)
+    pub cycle: u32,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in cycle {}: {}", self.kind, self.cycle, self.detail)
+    }
+}
+
+/// What to do when an anomaly is detected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnomalyAction {
+    /// Log it and continue
+    Warn,
+    /// Ask the [`AnomalyApprover`]; stop the execution if it refuses or
+    /// there is none
+    RequireApproval,
+    /// Stop the execution and fail later ones until the monitor is reset
+    #[default]
+    Trip,
+}
+
+/// Decides whether an execution may continue after an anomaly, e.g. by
+/// asking an operator
+#[async_trait]
+pub trait AnomalyApprover: Send + Sync + fmt::Debug {
+    /// Return `true` to continue the execution
+    async fn approve(&self, anomaly: &Anomaly) -> bool;
+}
+
+/// Thresholds and actions of a [`BehaviorMonitor`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BehaviorMonitorConfig {
+    /// Responses kept to compute the usual tool call count and output length
+    pub window: usize,
+    /// Responses needed in the window before spikes and explosions are detected
+    pub min_samples: usize,
+    /// A response with this many times the usual tool calls is a spike
+    pub tool_call_spike_factor: f64,
+    /// Fewer tool calls than this are never a spike
+    pub min_tool_call_spike: usize,
+    /// Identical tool call requests allowed in one execution
+    pub max_identical_requests: usize,
+    /// A response with this many times the usual output tokens is an explosion
+    pub output_explosion_factor: f64,
+    /// Fewer output tokens than this are never an explosion
+    pub min_output_explosion: u32,
+    /// Action for anomalies without an override
+    pub action: AnomalyAction,
+    /// Actions for specific kinds of anomaly
+    pub actions: HashMap<AnomalyKind, AnomalyAction>,
+}
+
+impl Default for BehaviorMonitorConfig {
+    fn default() -> Self {
+        Self {
+            window: 50,
+            min_samples: 5,
+            tool_call_spike_factor: 4.0,
+            min_tool_call_spike: 8,
+            max_identical_requests: 3,
+            output_explosion_factor: 5.0,
+            min_output_explosion: 4_000,
+            action: AnomalyAction::default(),
+            actions: HashMap::new(),
+        }
+    }
+}
+
+impl BehaviorMonitorConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+
+    /// Flag responses with `factor` times the usual tool calls, and at least `min`
+    pub fn with_tool_call_spike(mut self, factor: f64, min: usize) -> Self {
+        self.tool_call_spike_factor = factor;
+        self.min_tool_call_spike = min;
+        self
+    }
+
+    pub fn with_max_identical_requests(mut self, max: usize) -> Self {
+        self.max_identical_requests = max.max(1);
+        self
+    }
+
+    /// Flag responses with `factor` times the usual output tokens, and at least `min`
+    pub fn with_output_explosion(mut self, factor: f64, min: u32) -> Self {
+        self.output_explosion_factor = factor;
+        self.min_output_explosion = min;
+        self
+    }
+
+    /// Action for all kinds of anomaly without an override
+    pub fn with_default_action(mut self, action: AnomalyAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Action for anomalies of `kind`
+    pub fn with_action(mut self, kind: AnomalyKind, action: AnomalyAction) -> Self {
+        self.actions.insert(kind, action);
+        self
+    }
+
+    /// The action for anomalies of `kind`
+    pub fn action_for(&self, kind: AnomalyKind) -> AnomalyAction {
+        self.actions.get(&kind).copied().unwrap_or(self.action)
+    }
+}
+
+/// What the monitor decided about one model response
+#[derive(Debug, Default)]
+pub(crate) struct Verdict {
+    /// Anomalies detected, with the action taken
+    pub anomalies: Vec<(Anomaly, AnomalyAction)>,
+    /// The anomaly that stops the execution
+    pub stop: Option<Anomaly>,
+}
+
+#[derive(Debug, Default)]
+struct MonitorState {
+    /// Tool call count and output tokens of recent responses
+    samples: VecDeque<(usize, u32)>,
+    /// Times each set of tool calls was requested in the current execution
+    requests: HashMap<u64, usize>,
+    tripped: Option<Anomaly>,
+    history: Vec<Anomaly>,
+}
+
+/// Watches model responses for anomalous agent behavior
+///
+/// Clones share the same state, so one monitor can watch several agents and
+/// trip them all.
+#[derive(Clone)]
+pub struct BehaviorMonitor {
+    config: Arc<BehaviorMonitorConfig>,
+    approver: Option<Arc<dyn AnomalyApprover>>,
+    state: Arc<Mutex<MonitorState>>,
+}
+
+impl fmt::Debug for BehaviorMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BehaviorMonitor")
+            .field("config", &self.config)
+            .field("approver", &self.approver)
+            .field("tripped", &self.tripped())
+            .finish()
+    }
+}
+
+impl Default for BehaviorMonitor {
+    fn default() -> Self {
+        Self::new(BehaviorMonitorConfig::default())
+    }
+}
+
+impl BehaviorMonitor {
+    pub fn new(config: BehaviorMonitorConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            approver: None,
+            state: Arc::new(Mutex::new(MonitorState::default())),
+        }
+    }
+
+    /// Ask `approver` about anomalies whose action is [`AnomalyAction::RequireApproval`]
+    pub fn with_approver(mut self, approver: Arc<dyn AnomalyApprover>) -> Self {
+        self.approver = Some(approver);
+        self
+    }
+
+    pub fn config(&self) -> &BehaviorMonitorConfig {
+        &self.config
+    }
+
+    /// The anomaly that tripped the circuit breaker, if it is open
+    pub fn tripped(&self) -> Option<Anomaly> {
+        self.state().tripped.clone()
+    }
+
+    /// Close the circuit breaker and forget the rolling window
+    pub fn reset(&self) {
+        let mut state = self.state();
+        state.tripped = None;
+        state.samples.clear();
+        state.requests.clear();
+    }
+
+    /// Every anomaly detected so far, oldest first
+    pub fn anomalies(&self) -> Vec<Anomaly> {
+        self.state().history.clone()
+    }
+
+    /// Detect anomalies in a model response and record it in the rolling window
+    pub fn observe(&self, cycle: u32, response: &ChatResponse) -> Vec<Anomaly> {
+        let config = &self.config;
+        let tool_calls = response.tool_calls.len();
+        let output_tokens = response
+            .usage
+            .as_ref()
+            .map(|usage| usage.output_tokens)
+            .unwrap_or_else(|| (response.content.len() / 4) as u32);
+        let anomaly = |kind, detail| Anomaly {
+            kind,
+            detail,
+            cycle,
+            detected_at: Utc::now(),
+        };
+
+        let mut state = self.state();
+        let mut anomalies = Vec::new();
+        if state.samples.len() >= config.min_samples.max(1) {
+            let count = state.samples.len() as f64;
+            let usual_calls =
+                state.samples.iter().map(|(calls, _)| *calls).sum::<usize>() as f64 / count;
+            let usual_tokens = state
+                .samples
+                .iter()
+                .map(|(_, tokens)| *tokens as f64)
+                .sum::<f64>()
+                / count;
+
+            if tool_calls >= config.min_tool_call_spike
+                && tool_calls as f64 > config.tool_call_spike_factor * usual_calls.max(1.0)
+            {
+                anomalies.push(anomaly(
+                    AnomalyKind::ToolCallSpike,
+                    format!(
+                        "{} tool calls requested, usually {:.1}",
+                        tool_calls, usual_calls
+                    ),
+                ));
+            }
+            if output_tokens >= config.min_output_explosion
+                && output_tokens as f64 > config.output_explosion_factor * usual_tokens.max(1.0)
+            {
+                anomalies.push(anomaly(
+                    AnomalyKind::OutputExplosion,
+                    format!(
+                        "{} output tokens, usually {:.0}",
+                        output_tokens, usual_tokens
+                    ),
+                ));
+            }
+        }
+
+        if tool_calls > 0 {
+            let times = state
+                .requests
+                .entry(request_fingerprint(response))
+                .or_default();
+            *times += 1;
+            if *times > config.max_identical_requests {
+                let names: Vec<&str> = response
+                    .tool_calls
+                    .iter()
+                    .map(|call| call.name.as_str())
+                    .collect();
+                anomalies.push(anomaly(
+                    AnomalyKind::RepeatedRequest,
+                    format!(
+                        "{} requested with the same input {} times",
+                        names.join(", "),
+                        times
+                    ),
+                ));
+            }
+        }
+
+        state.samples.push_back((tool_calls, output_tokens));
+        while state.samples.len() > config.window.max(1) {
+            state.samples.pop_front();
+        }
+        state.history.extend(anomalies.iter().cloned());
+        anomalies
+    }
+
+    /// Forget the requests of the previous execution
+    pub(crate) fn start_execution(&self) {
+        self.state().requests.clear();
+    }
+
+    /// Observe `response` and apply the action of each anomaly
+    pub(crate) async fn inspect(&self, cycle: u32, response: &ChatResponse) -> Verdict {
+        let mut verdict = Verdict::default();
+        for anomaly in self.observe(cycle, response) {
+            let action = self.config.action_for(anomaly.kind);
+            tracing::warn!("Behavior anomaly ({:?}): {}", action, anomaly);
+            match action {
+                AnomalyAction::Warn => {}
+                AnomalyAction::RequireApproval => {
+                    let approved = match &self.approver {
+                        Some(approver) => approver.approve(&anomaly).await,
+                        None => false,
+                    };
+                    if approved {
+                        // Don't ask again about the same repeated request
+                        if anomaly.kind == AnomalyKind::RepeatedRequest {
+                            self.state().requests.remove(&request_fingerprint(response));
+                        }
+                    } else if verdict.stop.is_none() {
+                        verdict.stop = Some(anomaly.clone());
+                    }
+                }
+                AnomalyAction::Trip => {
+                    self.state().tripped.get_or_insert_with(|| anomaly.clone());
+                    if verdict.stop.is_none() {
+                        verdict.stop = Some(anomaly.clone());
+                    }
+                }
+            }
+            verdict.anomalies.push((anomaly, action));
+        }
+        verdict
+    }
+
+    fn state(&self) -> MutexGuard<'_, MonitorState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Hash of the tool calls of `response`, ignoring their ids and order
+fn request_fingerprint(response: &ChatResponse) -> u64 {
+    let mut calls: Vec<String> = response
+        .tool_calls
+        .iter()
+        .map(|call| format!("{}:{}", call.name, call.input))
+        .collect();
+    calls.sort();
+    let mut hasher = DefaultHasher::new();
+    calls.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::traits::{ToolCall, Usage};
+    use serde_json::json;
+
+    fn response(tool_calls: usize, output_tokens: u32) -> ChatResponse {
+        ChatResponse {
+            content: String::new(),
+            tool_calls: (0..tool_calls)
+                .map(|i| ToolCall {
+                    id: format!("call_{}", i),
+                    name: "search".to_string(),
+                    input: json!({"query": i}),
+                })
+                .collect(),
+            thinking: None,
+            usage: Some(Usage::new(100, output_tokens)),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_spikes_and_explosions_need_a_baseline() {
+        let monitor = BehaviorMonitor::new(BehaviorMonitorConfig::new().with_min_samples(3));
+        // No baseline yet
+        assert!(monitor.observe(1, &response(20, 50_000)).is_empty());
+        monitor.reset();
+        for cycle in 1..=3 {
+            assert!(monitor.observe(cycle, &response(1, 300)).is_empty());
+        }
+
+        let kinds: Vec<AnomalyKind> = monitor
+            .observe(4, &response(12, 9_000))
+            .into_iter()
+            .map(|anomaly| anomaly.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [AnomalyKind::ToolCallSpike, AnomalyKind::OutputExplosion]
+        );
+        assert_eq!(monitor.anomalies().len(), 2);
+    }
+
+    #[derive(Debug)]
+    struct Refuse;
+
+    #[async_trait]
+    impl AnomalyApprover for Refuse {
+        async fn approve(&self, _anomaly: &Anomaly) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_requests_trip_the_breaker() {
+        let monitor = BehaviorMonitor::new(BehaviorMonitorConfig::new());
+        for cycle in 1..=3 {
+            assert!(monitor
+                .inspect(cycle, &response(2, 100))
+                .await
+                .stop
+                .is_none());
+        }
+        let verdict = monitor.inspect(4, &response(2, 100)).await;
+        let stop = verdict.stop.unwrap();
+        assert_eq!(stop.kind, AnomalyKind::RepeatedRequest);
+        assert_eq!(
+            stop.detail,
+            "search, search requested with the same input 4 times"
+        );
+        assert_eq!(monitor.tripped(), Some(stop));
+
+        monitor.reset();
+        assert!(monitor.tripped().is_none());
+
+        // Refused approval stops the execution without tripping
+        let monitor = BehaviorMonitor::new(
+            BehaviorMonitorConfig::new()
+                .with_max_identical_requests(1)
+                .with_default_action(AnomalyAction::RequireApproval),
+        )
+        .with_approver(Arc::new(Refuse));
+        monitor.inspect(1, &response(1, 100)).await;
+        monitor.start_execution();
+        assert!(monitor.inspect(1, &response(1, 100)).await.stop.is_none());
+        assert!(monitor.inspect(2, &response(1, 100)).await.stop.is_some());
+        assert!(monitor.tripped().is_none());
+    }
+}
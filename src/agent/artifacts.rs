@@ -0,0 +1,235 @@
+//! Machine-readable outputs produced by tools during an execution.
+//!
+//! Tools that generate files, charts or reports add them as [`Artifact`]s to
+//! the [`ArtifactCollector`] in [`AgentContext::artifacts`]. The artifacts of
+//! an execution are returned in [`AgentResult::artifacts`], next to the
+//! natural-language response, so callers do not have to parse them out of
+//! the text.
+//!
+//! ```no_run
+//! # use stood::agent::AgentContext;
+//! use stood::agent::artifacts::Artifact;
+//!
+//! # fn in_tool(agent_context: Option<&AgentContext>, csv: String) {
+//! // Inside Tool::execute
+//! if let Some(ctx) = agent_context {
+//!     ctx.artifacts.push(Artifact::text("sales.csv", "text/csv", csv).with_tool("sales_report"));
+//! }
+//! # }
+//!
+//! # async fn caller(mut agent: stood::agent::Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let result = agent.execute("Export last month's sales").await?;
+//! for artifact in &result.artifacts {
+//!     println!("{} ({}, {} bytes)", artifact.name, artifact.mime_type, artifact.size());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Artifacts referring to a [`path`](ArtifactContent::Path) inside the
+//! execution's [`Workspace`](crate::tools::Workspace) are deleted with it;
+//! copy them elsewhere or return their contents instead.
+//!
+//! [`AgentContext::artifacts`]: crate::agent::AgentContext::artifacts
+//! [`AgentResult::artifacts`]: crate::agent::AgentResult::artifacts
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Where the content of an [`Artifact`] is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactContent {
+    /// Text content
+    Text(String),
+    /// Binary content, serialized as base64
+    Data(#[serde(with = "base64_bytes")] Vec<u8>),
+    /// File written by the tool
+    Path(PathBuf),
+    /// Object stored elsewhere, e.g. `s3://bucket/key`
+    Uri(String),
+}
+
+/// An output produced by a tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// File name or label of the artifact
+    pub name: String,
+    /// MIME type of the content, e.g. `image/png`
+    pub mime_type: String,
+    /// The content or where to find it
+    pub content: ArtifactContent,
+    /// Tool that produced the artifact, if set
+    pub tool_name: Option<String>,
+    /// When the artifact was added
+    pub created_at: DateTime<Utc>,
+}
+
+impl Artifact {
+    fn new(
+        name: impl Into<String>,
+        mime_type: impl Into<String>,
+        content: ArtifactContent,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            mime_type: mime_type.into(),
+            content,
+            tool_name: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Artifact with text content
+    pub fn text(
+        name: impl Into<String>,
+        mime_type: impl Into<String>,
+        text: impl Into<String>,
+    ) -> Self {
+        Self::new(name, mime_type, ArtifactContent::Text(text.into()))
+    }
+
+    /// Artifact with binary content
+    pub fn data(name: impl Into<String>, mime_type: impl Into<String>, data: Vec<u8>) -> Self {
+        Self::new(name, mime_type, ArtifactContent::Data(data))
+    }
+
+    /// Artifact stored in a file
+    pub fn path(
+        name: impl Into<String>,
+        mime_type: impl Into<String>,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        Self::new(name, mime_type, ArtifactContent::Path(path.into()))
+    }
+
+    /// Artifact stored at a URI, such as an S3 object
+    pub fn uri(
+        name: impl Into<String>,
+        mime_type: impl Into<String>,
+        uri: impl Into<String>,
+    ) -> Self {
+        Self::new(name, mime_type, ArtifactContent::Uri(uri.into()))
+    }
+
+    /// Record the tool that produced the artifact
+    pub fn with_tool(mut self, tool_name: impl Into<String>) -> Self {
+        self.tool_name = Some(tool_name.into());
+        self
+    }
+
+    /// Size of the content in bytes (of the file for path artifacts, 0 if it
+    /// cannot be read or is stored at a URI)
+    pub fn size(&self) -> u64 {
+        match &self.content {
+            ArtifactContent::Text(text) => text.len() as u64,
+            ArtifactContent::Data(data) => data.len() as u64,
+            ArtifactContent::Path(path) => std::fs::metadata(path).map_or(0, |m| m.len()),
+            ArtifactContent::Uri(_) => 0,
+        }
+    }
+
+    /// The content as bytes, reading the file for path artifacts
+    ///
+    /// Fails for URI artifacts, which have to be fetched by the caller.
+    pub fn bytes(&self) -> std::io::Result<Vec<u8>> {
+        match &self.content {
+            ArtifactContent::Text(text) => Ok(text.as_bytes().to_vec()),
+            ArtifactContent::Data(data) => Ok(data.clone()),
+            ArtifactContent::Path(path) => std::fs::read(path),
+            ArtifactContent::Uri(uri) => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("artifact content is stored at {}", uri),
+            )),
+        }
+    }
+}
+
+/// Artifacts added by the tools of one execution
+///
+/// Clones add to the same list.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactCollector {
+    artifacts: Arc<Mutex<Vec<Artifact>>>,
+}
+
+impl ArtifactCollector {
+    /// Empty collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an artifact
+    pub fn push(&self, artifact: Artifact) {
+        self.lock().push(artifact);
+    }
+
+    /// Number of artifacts added
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no artifact was added
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Copy of the artifacts added so far
+    pub fn snapshot(&self) -> Vec<Artifact> {
+        self.lock().clone()
+    }
+
+    /// Remove and return all artifacts
+    pub fn take(&self) -> Vec<Artifact> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Artifact>> {
+        self.artifacts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+mod base64_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collector_and_serialization() {
+        let collector = ArtifactCollector::new();
+        collector
+            .clone()
+            .push(Artifact::data("chart.png", "image/png", vec![0, 1, 2]).with_tool("plot"));
+        collector.push(Artifact::text("notes.md", "text/markdown", "# Notes"));
+        assert_eq!(collector.len(), 2);
+
+        let artifacts = collector.take();
+        assert!(collector.is_empty());
+        assert_eq!(artifacts[0].size(), 3);
+        assert_eq!(artifacts[1].bytes().unwrap(), b"# Notes");
+
+        let json = serde_json::to_value(&artifacts[0]).unwrap();
+        assert_eq!(json["content"], serde_json::json!({"data": "AAEC"}));
+        assert_eq!(json["tool_name"], "plot");
+        let parsed: Artifact = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, artifacts[0]);
+    }
+}
@@ -0,0 +1,438 @@
+//! Best-of-N sampling for agent executions.
+//!
+//! [`ExecuteOptions::best_of`] runs the same prompt as N independent executions in
+//! parallel (optionally spread across different models) and picks one candidate
+//! with a [`CandidateSelector`]. The winning candidate's conversation becomes the
+//! agent's conversation; all candidates are returned for inspection.
+//!
+//! ```no_run
+//! use stood::agent::{Agent, CandidateSelector, ExecuteOptions};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut agent = Agent::builder().build().await?;
+//! let result = agent
+//!     .execute_with_options(
+//!         "Write a haiku about Rust",
+//!         ExecuteOptions::best_of(3, CandidateSelector::ConfidenceProxy),
+//!     )
+//!     .await?;
+//!
+//! println!("Winner: {}", result.response());
+//! for candidate in &result.candidates {
+//!     println!("{} scored {:?}", candidate.model_id, candidate.score);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::agent::deadline::Deadline;
+use crate::agent::language::{LanguageTag, ResponseLanguage};
+use crate::agent::result::AgentResult;
+use crate::agent::router::ModelRoute;
+use crate::agent::Agent;
+use crate::llm::traits::LlmModel;
+use crate::tools::ToolFixtures;
+use crate::types::tools::ToolChoice;
+
+/// Scoring function for custom candidate selection; higher scores win
+pub type CandidateScorer = Arc<dyn Fn(&Candidate, &[Candidate]) -> f64 + Send + Sync>;
+
+/// Strategy for choosing the best of several candidates
+#[derive(Clone)]
+pub enum CandidateSelector {
+    /// Confidence proxy based on agreement between candidates.
+    ///
+    /// Providers do not expose token log-probabilities, so confidence is
+    /// approximated by self-consistency: the candidate whose response overlaps most
+    /// with the other candidates wins, with a penalty for failed tool calls.
+    ConfidenceProxy,
+
+    /// Ask a separate judge agent to pick the best response
+    Judge {
+        /// The judge agent instance
+        judge_agent: Box<Agent>,
+        /// Instructions describing what makes a response best
+        criteria: String,
+    },
+
+    /// Score each candidate with a custom function
+    Custom(CandidateScorer),
+}
+
+impl std::fmt::Debug for CandidateSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConfidenceProxy => write!(f, "ConfidenceProxy"),
+            Self::Judge { criteria, .. } => f
+                .debug_struct("Judge")
+                .field("criteria", criteria)
+                .finish_non_exhaustive(),
+            Self::Custom(_) => write!(f, "Custom(<fn>)"),
+        }
+    }
+}
+
+impl CandidateSelector {
+    /// Select with a judge agent
+    pub fn judge(judge_agent: Agent, criteria: impl Into<String>) -> Self {
+        Self::Judge {
+            judge_agent: Box::new(judge_agent),
+            criteria: criteria.into(),
+        }
+    }
+
+    /// Select with a custom scoring function
+    pub fn custom<F>(scorer: F) -> Self
+    where
+        F: Fn(&Candidate, &[Candidate]) -> f64 + Send + Sync + 'static,
+    {
+        Self::Custom(Arc::new(scorer))
+    }
+
+    /// Get the name of the selector for logging
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ConfidenceProxy => "confidence_proxy",
+            Self::Judge { .. } => "judge",
+            Self::Custom(_) => "custom",
+        }
+    }
+}
+
+/// Best-of-N sampling settings
+#[derive(Debug, Clone)]
+pub struct BestOfConfig {
+    /// Number of candidates to generate
+    pub n: usize,
+    /// How the winner is chosen
+    pub selector: CandidateSelector,
+    /// Models to use, assigned to candidates round-robin (empty: the agent's model)
+    pub models: Vec<ModelRoute>,
+}
+
+/// Per-call execution options for [`Agent::execute_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteOptions {
+    /// Best-of-N sampling (a single execution when unset)
+    pub best_of: Option<BestOfConfig>,
+    /// Tool choice for the first model call, overriding the agent's setting
+    pub tool_choice: Option<ToolChoice>,
+    /// Text the assistant's response starts with
+    pub prefill: Option<String>,
+    /// Simulate tool calls with these fixtures instead of executing them
+    pub tool_fixtures: Option<ToolFixtures>,
+    /// Language to respond in, overriding the agent's setting
+    pub response_language: Option<ResponseLanguage>,
+    /// Time by which the execution must finish (see [`crate::agent::deadline`])
+    pub deadline: Option<Deadline>,
+}
+
+impl ExecuteOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `n` candidates in parallel and keep the one chosen by `selector`
+    pub fn best_of(n: usize, selector: CandidateSelector) -> Self {
+        Self {
+            best_of: Some(BestOfConfig {
+                n: n.max(1),
+                selector,
+                models: Vec::new(),
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Add a model to sample from; candidates are assigned to models round-robin
+    pub fn with_model<M: LlmModel>(mut self, model: M) -> Self {
+        if let Some(config) = self.best_of.as_mut() {
+            config.models.push(ModelRoute::from_model(&model));
+        }
+        self
+    }
+
+    /// Force how the model chooses tools on the first model call
+    ///
+    /// `ToolChoice::tool("plan")` makes the model start by calling `plan`;
+    /// `ToolChoice::Any` makes it call some tool. Later calls are unaffected.
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Start the assistant's response with `prefill`, which the model continues
+    ///
+    /// Steers the output format, e.g. `{` for a JSON object. The prefill is
+    /// part of the response. Not all models accept a prefill; some reject one
+    /// that ends with whitespace.
+    pub fn with_prefill(mut self, prefill: impl Into<String>) -> Self {
+        self.prefill = Some(prefill.into());
+        self
+    }
+
+    /// Simulate tool calls instead of executing them
+    ///
+    /// Tools answer with their [`Tool::simulate`](crate::tools::Tool::simulate)
+    /// result or a generic simulated result, so prompts and plans can be tested
+    /// against destructive tools. See [`crate::tools::simulation`].
+    pub fn dry_run_tools(mut self, dry_run: bool) -> Self {
+        self.tool_fixtures = match (dry_run, self.tool_fixtures.take()) {
+            (true, fixtures) => Some(fixtures.unwrap_or_default()),
+            (false, _) => None,
+        };
+        self
+    }
+
+    /// Fail with [`StoodError::DeadlineExceeded`](crate::StoodError::DeadlineExceeded)
+    /// if the execution has not finished by `deadline`
+    ///
+    /// Model calls, tool calls and retry backoffs are cut short to fit in the
+    /// time left. See [`crate::agent::deadline`].
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Simulate tool calls, answering from `fixtures` where they have a result
+    pub fn with_tool_fixtures(mut self, fixtures: ToolFixtures) -> Self {
+        self.tool_fixtures = Some(fixtures);
+        self
+    }
+
+    /// Respond in `language`, retrying once if the response is in another one
+    ///
+    /// See [`crate::agent::language`].
+    pub fn response_language(mut self, language: LanguageTag) -> Self {
+        self.response_language = Some(ResponseLanguage::Fixed(language));
+        self
+    }
+
+    /// Respond in the language the prompt is written in, when it can be detected
+    pub fn match_user_language(mut self) -> Self {
+        self.response_language = Some(ResponseLanguage::MatchUser);
+        self
+    }
+}
+
+/// One completed (or failed) candidate execution
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// Position of this candidate in the fan-out
+    pub index: usize,
+    /// Model that produced this candidate
+    pub model_id: String,
+    /// Execution result, or the error message if the execution failed
+    pub result: std::result::Result<AgentResult, String>,
+    /// Score assigned by the selector (judge selection does not score)
+    pub score: Option<f64>,
+}
+
+impl Candidate {
+    /// Response text, if the execution succeeded
+    pub fn response(&self) -> Option<&str> {
+        self.result.as_ref().ok().map(|r| r.response.as_str())
+    }
+}
+
+/// Result of a best-of-N execution
+#[derive(Debug, Clone)]
+pub struct BestOfResult {
+    /// Index of the winning candidate in `candidates`
+    pub winner: usize,
+    /// All candidates in fan-out order
+    pub candidates: Vec<Candidate>,
+    /// Name of the selector that chose the winner
+    pub selector: String,
+}
+
+impl BestOfResult {
+    /// Result of the winning candidate
+    pub fn winner(&self) -> &AgentResult {
+        self.candidates[self.winner]
+            .result
+            .as_ref()
+            .expect("winner is always a successful candidate")
+    }
+
+    /// Response text of the winning candidate
+    pub fn response(&self) -> &str {
+        &self.winner().response
+    }
+}
+
+/// Score every successful candidate by agreement with the others
+pub(crate) fn confidence_scores(candidates: &[Candidate]) -> Vec<Option<f64>> {
+    let word_sets: Vec<Option<HashSet<String>>> = candidates
+        .iter()
+        .map(|c| c.response().map(word_set))
+        .collect();
+
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| {
+            let words = word_sets[i].as_ref()?;
+            let result = candidate.result.as_ref().ok()?;
+            let others: Vec<f64> = word_sets
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .filter_map(|(_, other)| other.as_ref())
+                .map(|other| jaccard(words, other))
+                .collect();
+            let agreement = if others.is_empty() {
+                1.0
+            } else {
+                others.iter().sum::<f64>() / others.len() as f64
+            };
+            let penalty = 0.1 * result.tools_failed.len() as f64
+                + if result.success { 0.0 } else { 0.5 }
+                + if words.is_empty() { 1.0 } else { 0.0 };
+            Some(agreement - penalty)
+        })
+        .collect()
+}
+
+/// Lowercased words of `text`, without surrounding punctuation
+pub(crate) fn word_set(text: &str) -> HashSet<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.trim_matches(|ch: char| !ch.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Overlap of two word sets, from 0 (disjoint) to 1 (identical)
+pub(crate) fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Build the prompt asking a judge to choose among successful candidates
+pub(crate) fn judge_prompt(prompt: &str, criteria: &str, candidates: &[Candidate]) -> String {
+    let mut text = format!(
+        "Several responses were generated for the same request. {}\n\n\
+         Request:\n{}\n\n",
+        criteria, prompt
+    );
+    for candidate in candidates {
+        if let Some(response) = candidate.response() {
+            text.push_str(&format!(
+                "<response number=\"{}\">\n{}\n</response>\n\n",
+                candidate.index + 1,
+                response
+            ));
+        }
+    }
+    text.push_str("Reply with only the number of the best response.");
+    text
+}
+
+/// Parse the judge's choice into a candidate index
+pub(crate) fn parse_judge_choice(response: &str, candidates: &[Candidate]) -> Option<usize> {
+    response
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|s| s.parse::<usize>().ok())
+        .filter_map(|n| n.checked_sub(1))
+        .find(|&index| {
+            candidates
+                .get(index)
+                .is_some_and(|c| c.response().is_some())
+        })
+}
+
+/// Index of the highest-scoring successful candidate
+pub(crate) fn best_scored(candidates: &[Candidate]) -> Option<usize> {
+    candidates
+        .iter()
+        .filter(|c| c.result.is_ok())
+        .filter_map(|c| c.score.map(|s| (c.index, s)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(index: usize, response: Option<&str>) -> Candidate {
+        let result = match response {
+            Some(text) => Ok(AgentResult {
+                response: text.to_string(),
+                success: true,
+                ..AgentResult::default()
+            }),
+            None => Err("model error".to_string()),
+        };
+        Candidate {
+            index,
+            model_id: "model".to_string(),
+            result,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn test_confidence_proxy_prefers_consensus() {
+        let mut candidates = vec![
+            candidate(0, Some("The capital of France is Paris")),
+            candidate(1, Some("Paris is the capital of France")),
+            candidate(2, Some("I think it might be Lyon")),
+            candidate(3, None),
+        ];
+        let scores = confidence_scores(&candidates);
+        assert!(scores[3].is_none());
+        for (candidate, score) in candidates.iter_mut().zip(scores) {
+            candidate.score = score;
+        }
+        let winner = best_scored(&candidates).unwrap();
+        assert!(winner == 0 || winner == 1);
+    }
+
+    #[test]
+    fn test_judge_choice_parsing() {
+        let candidates = vec![
+            candidate(0, Some("a")),
+            candidate(1, None),
+            candidate(2, Some("c")),
+        ];
+        assert_eq!(parse_judge_choice("3", &candidates), Some(2));
+        assert_eq!(
+            parse_judge_choice("Response 1 is best", &candidates),
+            Some(0)
+        );
+        // Failed candidates and out-of-range numbers are skipped
+        assert_eq!(parse_judge_choice("2", &candidates), None);
+        assert_eq!(parse_judge_choice("7 then 3", &candidates), Some(2));
+
+        let prompt = judge_prompt("question", "Pick the most accurate.", &candidates);
+        assert!(prompt.contains("<response number=\"3\">"));
+        assert!(!prompt.contains("<response number=\"2\">"));
+    }
+
+    #[test]
+    fn test_execute_options() {
+        let options = ExecuteOptions::best_of(0, CandidateSelector::ConfidenceProxy)
+            .with_model(crate::llm::models::Bedrock::NovaMicro);
+        let config = options.best_of.unwrap();
+        assert_eq!(config.n, 1);
+        assert_eq!(config.models.len(), 1);
+        assert_eq!(config.selector.name(), "confidence_proxy");
+        assert!(ExecuteOptions::new().best_of.is_none());
+
+        let options = ExecuteOptions::new()
+            .with_tool_choice(ToolChoice::tool("plan"))
+            .with_prefill("{");
+        assert_eq!(options.tool_choice, Some(ToolChoice::tool("plan")));
+        assert_eq!(options.prefill.as_deref(), Some("{"));
+    }
+}
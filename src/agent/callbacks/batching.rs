@@ -5,10 +5,10 @@
 //! during streaming.
 
 use super::*;
+use crate::tools::elicitation::{ElicitationRequest, ElicitationResponse};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, Notify};
-use tokio::time::sleep;
 
 /// Configuration for event batching
 #[derive(Debug, Clone)]
@@ -78,7 +78,7 @@ pub struct BatchingCallbackHandler {
     config: BatchConfig,
     batch: Arc<Mutex<EventBatch>>,
     flush_notifier: Arc<Notify>,
-    _flush_task: tokio::task::JoinHandle<()>,
+    _flush_task: crate::runtime::JoinHandle<()>,
 }
 
 impl BatchingCallbackHandler {
@@ -115,18 +115,11 @@ impl BatchingCallbackHandler {
         flush_notifier: Arc<Notify>,
         handler: Arc<dyn CallbackHandler>,
         max_delay: Duration,
-    ) -> tokio::task::JoinHandle<()> {
-        tokio::spawn(async move {
+    ) -> crate::runtime::JoinHandle<()> {
+        crate::runtime::spawn(async move {
             loop {
-                // Wait for either a notification or timeout
-                tokio::select! {
-                    _ = flush_notifier.notified() => {
-                        // Immediate flush requested
-                    }
-                    _ = sleep(max_delay) => {
-                        // Timeout-based flush
-                    }
-                }
+                // Wait for an immediate flush request, or flush on timeout
+                let _ = crate::runtime::timeout(max_delay, flush_notifier.notified()).await;
 
                 // Flush the batch
                 let events_to_flush = {
@@ -154,9 +147,9 @@ impl BatchingCallbackHandler {
     fn should_batch_event(&self, event: &CallbackEvent) -> bool {
         match event {
             CallbackEvent::ContentDelta { .. } => self.config.batch_content_deltas,
-            CallbackEvent::ToolStart { .. } | CallbackEvent::ToolComplete { .. } => {
-                self.config.batch_tool_events
-            }
+            CallbackEvent::ToolStart { .. }
+            | CallbackEvent::ToolProgress { .. }
+            | CallbackEvent::ToolComplete { .. } => self.config.batch_tool_events,
             // Don't batch critical events like errors or completion
             CallbackEvent::Error { .. }
             | CallbackEvent::EventLoopComplete { .. }
@@ -211,6 +204,10 @@ impl CallbackHandler for BatchingCallbackHandler {
             self.inner_handler.handle_event(event).await
         }
     }
+
+    async fn on_elicitation(&self, request: &ElicitationRequest) -> ElicitationResponse {
+        self.inner_handler.on_elicitation(request).await
+    }
 }
 
 impl Drop for BatchingCallbackHandler {
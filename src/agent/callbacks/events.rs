@@ -3,9 +3,12 @@
 //! This module defines all the events that can be emitted during agent execution,
 //! providing comprehensive coverage of the agentic workflow.
 
+use crate::agent::anomaly::{Anomaly, AnomalyAction};
 use crate::agent::event_loop::{EventLoopConfig, EventLoopResult};
 use crate::error::StoodError;
 use crate::llm::traits::ProviderType;
+use crate::mcp::health::MCPHealthEvent;
+use crate::tools::ToolErrorSource;
 use crate::types::{Messages, StopReason};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
@@ -62,8 +65,14 @@ pub enum CallbackEvent {
         tool_use_id: String,
         output: Option<Value>,
         error: Option<String>,
+        /// Typed error behind `error`, if the tool kept it (see [`ToolErrorSource`])
+        error_source: Option<ToolErrorSource>,
         duration: Duration,
     },
+    /// A running tool reported its progress (see [`ProgressReporter`](crate::tools::ProgressReporter))
+    ToolProgress {
+        event: ToolProgressEvent,
+    },
 
     // Parallel Execution Events
     ParallelStart {
@@ -105,6 +114,22 @@ pub enum CallbackEvent {
         reasoning: String,
         duration: Duration,
     },
+
+    // MCP Connection Events (keepalive failures and reconnection)
+    McpHealth {
+        event: MCPHealthEvent,
+    },
+
+    // Progress Events (live token and cost ticker, see `callbacks::progress`)
+    Progress {
+        event: ProgressEvent,
+    },
+
+    // Behavior Monitor Events (see `agent::anomaly`)
+    BehaviorAnomaly {
+        anomaly: Anomaly,
+        action: AnomalyAction,
+    },
 }
 
 /// Tool-specific events for easier handling
@@ -122,10 +147,49 @@ pub enum ToolEvent {
     Failed {
         name: String,
         error: String,
+        /// Typed error behind `error`, if the tool kept it
+        source: Option<ToolErrorSource>,
         duration: Duration,
     },
 }
 
+/// Progress a running tool reported
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolProgressEvent {
+    pub tool_name: String,
+    pub tool_use_id: String,
+    /// How far along the tool is, from 0 to 100
+    pub percent: f32,
+    /// What the tool is currently doing
+    pub message: String,
+}
+
+/// Tokens and cost of an execution so far
+#[derive(Debug, Clone)]
+pub struct ProgressEvent {
+    /// Tokens used since the execution started, including an estimate for a
+    /// response that is still streaming
+    pub tokens_so_far: TokenUsage,
+    /// Cost in USD of `tokens_so_far`, if pricing is configured
+    pub estimated_cost: Option<f64>,
+    /// Time since the execution started
+    pub elapsed: Duration,
+    pub current_phase: ProgressPhase,
+}
+
+/// What the execution is doing when progress is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressPhase {
+    /// A model response is streaming
+    Streaming,
+    /// A model call has completed
+    ModelCall,
+    /// Tools have finished executing
+    ToolExecution,
+    /// A cycle has completed and the next one has not started
+    BetweenCycles,
+}
+
 /// Token usage information (matches Python callback patterns)
 #[derive(Debug, Clone)]
 pub struct TokenUsage {
@@ -6,12 +6,13 @@
 
 use super::config::PrintingConfig;
 use super::error::CallbackError;
-use super::events::{CallbackEvent, ToolEvent};
+use super::events::{CallbackEvent, ToolEvent, ToolProgressEvent};
 use super::traits::CallbackHandler;
 use crate::agent::result::AgentResult;
 #[allow(unused_imports)] // Used in future callback features
 use crate::agent::result::ToolCallSummary;
 use crate::error::StoodError;
+use crate::tools::elicitation::{ElicitationRequest, ElicitationResponse};
 use async_trait::async_trait;
 use std::io::{self, Write};
 use std::sync::Arc;
@@ -75,6 +76,7 @@ impl CallbackHandler for PrintingCallbackHandler {
                 name,
                 error,
                 duration,
+                ..
             } => {
                 println!("❌ Tool {} failed after {:?}: {}", name, duration, error);
             }
@@ -82,6 +84,19 @@ impl CallbackHandler for PrintingCallbackHandler {
         Ok(())
     }
 
+    async fn on_tool_progress(&self, event: &ToolProgressEvent) -> Result<(), CallbackError> {
+        if self.config.show_tools {
+            println!(
+                "⏳ {} {} {:>3.0}% {}",
+                event.tool_name,
+                progress_bar(event.percent, 20),
+                event.percent,
+                event.message
+            );
+        }
+        Ok(())
+    }
+
     async fn on_complete(&self, result: &AgentResult) -> Result<(), CallbackError> {
         if self.config.show_performance {
             println!("\\n📊 Execution Summary:");
@@ -166,6 +181,7 @@ impl CallbackHandler for PrintingCallbackHandler {
                         tool_name,
                         output,
                         error,
+                        error_source,
                         duration,
                         ..
                     } => {
@@ -173,6 +189,7 @@ impl CallbackHandler for PrintingCallbackHandler {
                             self.on_tool(ToolEvent::Failed {
                                 name: tool_name,
                                 error: err,
+                                source: error_source,
                                 duration,
                             })
                             .await?;
@@ -185,6 +202,9 @@ impl CallbackHandler for PrintingCallbackHandler {
                             .await?;
                         }
                     }
+                    CallbackEvent::ToolProgress { event } => {
+                        self.on_tool_progress(&event).await?;
+                    }
                     CallbackEvent::EventLoopComplete { result, .. } => {
                         // Convert EventLoopResult to AgentResult for callback
                         let agent_result = AgentResult::from(result, std::time::Duration::ZERO);
@@ -238,6 +258,12 @@ impl CallbackHandler for PrintingCallbackHandler {
     }
 }
 
+/// Text progress bar `width` characters wide, e.g. `[#####-----]` for 50%
+fn progress_bar(percent: f32, width: usize) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * width as f32).round() as usize;
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+}
+
 /// Composite handler (equivalent to Python's CompositeCallbackHandler)
 ///
 /// This handler allows combining multiple callback handlers so that
@@ -302,6 +328,17 @@ impl CallbackHandler for CompositeCallbackHandler {
         Ok(())
     }
 
+    async fn on_elicitation(&self, request: &ElicitationRequest) -> ElicitationResponse {
+        // The first handler that answers wins
+        for handler in &self.handlers {
+            match handler.on_elicitation(request).await {
+                ElicitationResponse::Decline => continue,
+                response => return response,
+            }
+        }
+        ElicitationResponse::Decline
+    }
+
     async fn handle_event(&self, event: CallbackEvent) -> Result<(), CallbackError> {
         for handler in &self.handlers {
             handler.handle_event(event.clone()).await?;
@@ -351,6 +388,7 @@ impl CallbackHandler for PerformanceCallbackHandler {
                 name,
                 duration,
                 error,
+                ..
             } => match self.log_level {
                 tracing::Level::ERROR => {
                     tracing::error!(tool = %name, duration = ?duration, error = %error, "Tool execution failed")
@@ -405,6 +443,7 @@ mod tests {
 
     /// Helper to create a mock AgentResult for testing
     fn create_mock_agent_result() -> AgentResult {
+        let default = AgentResult::default();
         AgentResult {
             response: "Test response".to_string(),
             execution: ExecutionDetails {
@@ -421,21 +460,21 @@ mod tests {
                     model_time: Duration::from_millis(800),
                     tool_time: Duration::from_millis(200),
                     was_streamed: true,
+                    ..default.execution.performance
                 },
+                ..default.execution
             },
             tools_called: vec!["calculator".to_string()],
             tools_successful: vec!["calculator".to_string()],
-            tools_failed: vec![],
             tool_call_summary: ToolCallSummary {
                 total_attempts: 1,
                 successful: 1,
-                failed: 0,
-                failed_calls: vec![],
+                ..default.tool_call_summary
             },
             duration: Duration::from_millis(1000),
             used_tools: true,
             success: true,
-            error: None,
+            ..default
         }
     }
 
@@ -498,6 +537,7 @@ mod tests {
             .on_tool(ToolEvent::Failed {
                 name: "calculator".to_string(),
                 error: "Division by zero".to_string(),
+                source: None,
                 duration: Duration::from_millis(50)
             })
             .await
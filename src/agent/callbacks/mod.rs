@@ -8,6 +8,8 @@ pub mod config;
 pub mod error;
 pub mod events;
 pub mod handlers;
+pub mod notifications;
+pub mod progress;
 pub mod traits;
 
 #[cfg(feature = "benchmarks")]
@@ -18,9 +20,18 @@ pub mod batching;
 pub use batching::{BatchConfig, BatchingCallbackHandler, EventBatch};
 pub use config::{CallbackHandlerConfig, PrintingConfig};
 pub use error::CallbackError;
-pub use events::{CallbackEvent, TokenUsage, ToolEvent};
+pub use events::{
+    CallbackEvent, ProgressEvent, ProgressPhase, TokenUsage, ToolEvent, ToolProgressEvent,
+};
 pub use handlers::{
     CompositeCallbackHandler, NullCallbackHandler, PerformanceCallbackHandler,
     PrintingCallbackHandler,
 };
+#[cfg(feature = "sns")]
+pub use notifications::SnsSink;
+pub use notifications::{
+    Notification, NotificationCallbackHandler, NotificationSink, NotificationStatus,
+    PayloadTemplate, SlackSink, WebhookSink,
+};
+pub use progress::ProgressConfig;
 pub use traits::{CallbackHandler, SyncCallbackHandler};
@@ -0,0 +1,635 @@
+//! Notifications sent when an execution completes or fails.
+//!
+//! A [`NotificationSink`] delivers a [`Notification`] — the response, metrics
+//! and error classification of a finished execution — to an external system.
+//! Three sinks are provided:
+//!
+//! - [`WebhookSink`] POSTs the notification as JSON to any URL
+//! - [`SlackSink`] posts a message to a Slack-compatible incoming webhook
+//! - `SnsSink` publishes to an Amazon SNS topic (requires the `sns` feature)
+//!
+//! Payloads can be customised with a [`PayloadTemplate`], in which
+//! `{{field}}` placeholders are replaced with notification fields. Webhooks
+//! are sent through the installed [proxy](crate::proxy) and can be
+//! restricted with a [`NetworkPolicy`].
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! use stood::agent::callbacks::{PayloadTemplate, SlackSink, WebhookSink};
+//! use stood::tools::network::NetworkPolicy;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let agent = Agent::builder()
+//!     .with_notification_sink(
+//!         WebhookSink::new("https://example.com/hooks/agent")
+//!             .with_network_policy(NetworkPolicy::new().with_require_https(true)),
+//!     )
+//!     .with_notification_sink(
+//!         SlackSink::new("https://hooks.slack.com/services/T000/B000/XXXX")
+//!             .with_template(PayloadTemplate::text(
+//!                 "Nightly report {{status}}: {{error_kind}} {{error}}",
+//!             ))
+//!             .failures_only(),
+//!     )
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::error::CallbackError;
+use super::events::CallbackEvent;
+use super::traits::CallbackHandler;
+use crate::agent::result::AgentResult;
+use crate::error_recovery::history::classify;
+#[cfg(feature = "sns")]
+use crate::llm::http::LazyAwsClient;
+#[cfg(feature = "sns")]
+use crate::telemetry::AwsCredentialSource;
+use crate::tools::network::NetworkPolicy;
+use crate::{Result, StoodError};
+
+/// Default time allowed for a sink to deliver a notification
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([a-z_]+)\s*\}\}").expect("valid placeholder regex"));
+
+/// Outcome of the execution a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationStatus {
+    Completed,
+    Failed,
+}
+
+/// Summary of a finished execution
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub status: NotificationStatus,
+    /// Final response text
+    pub response: String,
+    /// Error message, if the execution failed
+    pub error: Option<String>,
+    /// Kind of error, e.g. `ThrottlingError` or `ModelError`
+    pub error_kind: Option<String>,
+    /// Whether the error is worth retrying
+    pub retryable: Option<bool>,
+    pub termination_reason: String,
+    pub cycles: u32,
+    pub model_calls: u32,
+    /// Names of the tools called, in order
+    pub tool_calls: Vec<String>,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub total_tokens: u32,
+    pub duration_ms: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Notification {
+    /// Summarize `result`, classifying `error` if the execution failed with one
+    pub fn from_result(result: &AgentResult, error: Option<&StoodError>) -> Self {
+        let (input_tokens, output_tokens, total_tokens) =
+            result.execution.tokens.as_ref().map_or((0, 0, 0), |t| {
+                (t.input_tokens, t.output_tokens, t.total_tokens)
+            });
+        Self {
+            status: if result.success {
+                NotificationStatus::Completed
+            } else {
+                NotificationStatus::Failed
+            },
+            response: result.response.clone(),
+            error: result
+                .error
+                .clone()
+                .or_else(|| error.map(ToString::to_string)),
+            error_kind: error.map(classify),
+            retryable: error.map(StoodError::is_retryable),
+            termination_reason: result.termination_reason.to_string(),
+            cycles: result.execution.cycles,
+            model_calls: result.execution.model_calls,
+            tool_calls: result.tools_called.clone(),
+            input_tokens,
+            output_tokens,
+            total_tokens,
+            duration_ms: result.duration.as_millis() as u64,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Whether the execution failed
+    pub fn is_failure(&self) -> bool {
+        self.status == NotificationStatus::Failed
+    }
+}
+
+/// Payload with `{{field}}` placeholders for notification fields
+///
+/// Any top-level [`Notification`] field can be used, e.g. `{{status}}`,
+/// `{{response}}`, `{{error_kind}}` or `{{total_tokens}}`. Missing values
+/// render as empty strings, lists as comma-separated values, and unknown
+/// placeholders are left as they are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadTemplate {
+    template: String,
+    escape_json: bool,
+}
+
+impl PayloadTemplate {
+    /// Plain text template
+    pub fn text(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            escape_json: false,
+        }
+    }
+
+    /// JSON template; values are escaped so they can be placed inside JSON strings
+    pub fn json(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            escape_json: true,
+        }
+    }
+
+    /// Whether the rendered payload is JSON
+    pub fn is_json(&self) -> bool {
+        self.escape_json
+    }
+
+    /// Render the template for `notification`
+    pub fn render(&self, notification: &Notification) -> String {
+        let fields = serde_json::to_value(notification).unwrap_or_default();
+        PLACEHOLDER
+            .replace_all(&self.template, |caps: &regex::Captures| {
+                let Some(value) = fields.get(&caps[1]) else {
+                    return caps[0].to_string();
+                };
+                let text = field_text(value);
+                if self.escape_json {
+                    let quoted = Value::String(text).to_string();
+                    quoted[1..quoted.len() - 1].to_string()
+                } else {
+                    text
+                }
+            })
+            .into_owned()
+    }
+}
+
+fn field_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(field_text).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// Destination for execution notifications
+#[async_trait]
+pub trait NotificationSink: Send + Sync + std::fmt::Debug {
+    /// Deliver `notification`
+    async fn notify(&self, notification: &Notification) -> Result<()>;
+
+    /// Whether to deliver `notification`; by default every notification is sent
+    fn accepts(&self, notification: &Notification) -> bool {
+        let _ = notification;
+        true
+    }
+}
+
+/// POSTs notifications to a URL, as JSON unless a template is set
+///
+/// Requests go through the installed [proxy](crate::proxy) and are checked
+/// against the sink's [`NetworkPolicy`], which allows every host by default.
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: String,
+    template: Option<PayloadTemplate>,
+    headers: Vec<(String, String)>,
+    failures_only: bool,
+    timeout: Duration,
+    network_policy: NetworkPolicy,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            template: None,
+            headers: Vec::new(),
+            failures_only: false,
+            timeout: DEFAULT_TIMEOUT,
+            network_policy: NetworkPolicy::new(),
+        }
+    }
+
+    /// Send the rendered template instead of the notification JSON
+    pub fn with_template(mut self, template: PayloadTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Add a header to every request, e.g. for authentication
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Time allowed for the webhook to respond (default 10 seconds)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Only deliver to the webhook if `policy` allows its URL
+    pub fn with_network_policy(mut self, policy: NetworkPolicy) -> Self {
+        self.network_policy = policy;
+        self
+    }
+
+    /// Only notify about failed executions
+    pub fn failures_only(mut self) -> Self {
+        self.failures_only = true;
+        self
+    }
+
+    /// A POST request to the webhook through a client enforcing the policy
+    async fn post(&self, target: &str) -> Result<reqwest::RequestBuilder> {
+        let client = self
+            .network_policy
+            .client_for(&self.url)
+            .await
+            .map_err(|e| StoodError::network_error(format!("{} blocked: {}", target, e)))?;
+        Ok(client.post(&self.url).timeout(self.timeout))
+    }
+}
+
+#[async_trait]
+impl NotificationSink for WebhookSink {
+    async fn notify(&self, notification: &Notification) -> Result<()> {
+        let (body, content_type) = match &self.template {
+            Some(template) if !template.is_json() => (template.render(notification), "text/plain"),
+            Some(template) => (template.render(notification), "application/json"),
+            None => (
+                serde_json::to_string(notification).map_err(|e| {
+                    StoodError::serialization_error(format!(
+                        "Failed to serialize notification: {}",
+                        e
+                    ))
+                })?,
+                "application/json",
+            ),
+        };
+        let mut request = self
+            .post("Webhook")
+            .await?
+            .header("Content-Type", content_type)
+            .body(body);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        send(request, "Webhook").await
+    }
+
+    fn accepts(&self, notification: &Notification) -> bool {
+        !self.failures_only || notification.is_failure()
+    }
+}
+
+/// Posts notifications to a Slack-compatible incoming webhook
+///
+/// The message is sent as `{"text": ...}`, which Slack, Mattermost, Rocket.Chat
+/// and Microsoft Teams incoming webhooks all accept.
+#[derive(Debug, Clone)]
+pub struct SlackSink {
+    webhook: WebhookSink,
+    template: PayloadTemplate,
+}
+
+impl SlackSink {
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            webhook: WebhookSink::new(webhook_url),
+            template: PayloadTemplate::text(
+                "Agent execution {{status}} ({{termination_reason}}) in {{duration_ms}} ms, \
+                 {{total_tokens}} tokens\n{{error_kind}} {{error}}\n{{response}}",
+            ),
+        }
+    }
+
+    /// Message text template
+    pub fn with_template(mut self, template: PayloadTemplate) -> Self {
+        self.template = PayloadTemplate::text(template.template);
+        self
+    }
+
+    /// Time allowed for the webhook to respond (default 10 seconds)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.webhook = self.webhook.with_timeout(timeout);
+        self
+    }
+
+    /// Only deliver to the webhook if `policy` allows its URL
+    pub fn with_network_policy(mut self, policy: NetworkPolicy) -> Self {
+        self.webhook = self.webhook.with_network_policy(policy);
+        self
+    }
+
+    /// Only notify about failed executions
+    pub fn failures_only(mut self) -> Self {
+        self.webhook = self.webhook.failures_only();
+        self
+    }
+}
+
+#[async_trait]
+impl NotificationSink for SlackSink {
+    async fn notify(&self, notification: &Notification) -> Result<()> {
+        let text = self.template.render(notification);
+        let request = self
+            .webhook
+            .post("Slack webhook")
+            .await?
+            .json(&json!({ "text": text.trim() }));
+        send(request, "Slack webhook").await
+    }
+
+    fn accepts(&self, notification: &Notification) -> bool {
+        self.webhook.accepts(notification)
+    }
+}
+
+/// Publishes notifications to an Amazon SNS topic (requires the `sns`
+/// feature)
+///
+/// The message is the notification JSON unless a template is set. Uses the
+/// default AWS credential chain unless other credentials are set.
+#[cfg(feature = "sns")]
+#[derive(Debug, Clone)]
+pub struct SnsSink {
+    topic_arn: String,
+    client: LazyAwsClient<aws_sdk_sns::Client>,
+    template: Option<PayloadTemplate>,
+    subject: PayloadTemplate,
+    failures_only: bool,
+}
+
+#[cfg(feature = "sns")]
+impl SnsSink {
+    /// Publish to `topic_arn`, in the topic's region
+    pub fn new(topic_arn: impl Into<String>) -> Result<Self> {
+        let topic_arn = topic_arn.into();
+        // arn:aws:sns:<region>:<account>:<topic>
+        let region = match topic_arn.split(':').collect::<Vec<_>>()[..] {
+            ["arn", _, "sns", region, _, _] if !region.is_empty() => region.to_string(),
+            _ => {
+                return Err(StoodError::configuration_error(format!(
+                    "Invalid SNS topic ARN '{}'",
+                    topic_arn
+                )))
+            }
+        };
+        Ok(Self {
+            topic_arn,
+            client: LazyAwsClient::new(region),
+            template: None,
+            subject: PayloadTemplate::text("Agent execution {{status}}"),
+            failures_only: false,
+        })
+    }
+
+    /// Publish with an existing SNS client
+    pub fn from_client(client: aws_sdk_sns::Client, topic_arn: impl Into<String>) -> Self {
+        Self {
+            topic_arn: topic_arn.into(),
+            client: LazyAwsClient::from_client(client),
+            template: None,
+            subject: PayloadTemplate::text("Agent execution {{status}}"),
+            failures_only: false,
+        }
+    }
+
+    /// Where to get AWS credentials from (default: the default credential chain)
+    pub fn with_credentials(mut self, source: AwsCredentialSource) -> Self {
+        self.client = self.client.with_credentials(source);
+        self
+    }
+
+    /// Message template
+    pub fn with_template(mut self, template: PayloadTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Subject template, used by email subscriptions
+    pub fn with_subject(mut self, subject: PayloadTemplate) -> Self {
+        self.subject = subject;
+        self
+    }
+
+    /// Only notify about failed executions
+    pub fn failures_only(mut self) -> Self {
+        self.failures_only = true;
+        self
+    }
+}
+
+#[cfg(feature = "sns")]
+#[async_trait]
+impl NotificationSink for SnsSink {
+    async fn notify(&self, notification: &Notification) -> Result<()> {
+        let message = match &self.template {
+            Some(template) => template.render(notification),
+            None => serde_json::to_string(notification).map_err(|e| {
+                StoodError::serialization_error(format!("Failed to serialize notification: {}", e))
+            })?,
+        };
+        // SNS subjects are limited to 100 characters
+        let subject: String = self
+            .subject
+            .render(notification)
+            .chars()
+            .take(100)
+            .collect();
+
+        let client = self
+            .client
+            .get(aws_sdk_sns::Client::new)
+            .await
+            .map_err(|e| {
+                StoodError::configuration_error(format!("SNS client unavailable: {}", e))
+            })?;
+        let publish = client
+            .publish()
+            .topic_arn(&self.topic_arn)
+            .subject(subject)
+            .message(message)
+            .send();
+        match crate::runtime::timeout(DEFAULT_TIMEOUT, publish).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(StoodError::network_error(format!(
+                "SNS Publish failed: {}",
+                aws_sdk_sns::error::DisplayErrorContext(e)
+            ))),
+            Err(_) => Err(StoodError::timeout_error(DEFAULT_TIMEOUT.as_millis() as u64)),
+        }
+    }
+
+    fn accepts(&self, notification: &Notification) -> bool {
+        !self.failures_only || notification.is_failure()
+    }
+}
+
+async fn send(request: reqwest::RequestBuilder, target: &str) -> Result<()> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| StoodError::network_error(format!("{} request failed: {}", target, e)))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(StoodError::network_error(format!(
+        "{} returned HTTP {}: {}",
+        target,
+        status.as_u16(),
+        body.chars().take(200).collect::<String>()
+    )))
+}
+
+/// Callback handler that sends a notification to its sinks when an
+/// execution completes or fails
+///
+/// Delivery failures are logged and never fail the execution. Added to an
+/// agent by [`AgentBuilder::with_notification_sink`](crate::agent::AgentBuilder::with_notification_sink).
+#[derive(Debug, Default)]
+pub struct NotificationCallbackHandler {
+    sinks: Vec<Arc<dyn NotificationSink>>,
+    /// Error reported during the current execution, classified on completion
+    last_error: Mutex<Option<StoodError>>,
+}
+
+impl NotificationCallbackHandler {
+    pub fn new(sinks: Vec<Arc<dyn NotificationSink>>) -> Self {
+        Self {
+            sinks,
+            last_error: Mutex::new(None),
+        }
+    }
+
+    /// Add a sink
+    pub fn with_sink(mut self, sink: impl NotificationSink + 'static) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Send `notification` to every sink that accepts it
+    pub async fn dispatch(&self, notification: &Notification) {
+        let deliveries = self
+            .sinks
+            .iter()
+            .filter(|sink| sink.accepts(notification))
+            .map(|sink| async move {
+                if let Err(e) = sink.notify(notification).await {
+                    tracing::warn!("Failed to deliver notification to {:?}: {}", sink, e);
+                }
+            });
+        futures::future::join_all(deliveries).await;
+    }
+}
+
+#[async_trait]
+impl CallbackHandler for NotificationCallbackHandler {
+    async fn handle_event(&self, event: CallbackEvent) -> std::result::Result<(), CallbackError> {
+        match event {
+            CallbackEvent::Error { error, .. } => {
+                *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error);
+            }
+            CallbackEvent::EventLoopComplete {
+                result,
+                total_duration,
+            } => {
+                let error = self
+                    .last_error
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .take();
+                let result = AgentResult::from(result, total_duration);
+                let notification = Notification::from_result(&result, error.as_ref());
+                self.dispatch(&notification).await;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification() -> Notification {
+        let result = AgentResult {
+            success: false,
+            response: "Partial \"draft\"".to_string(),
+            tools_called: vec!["search".to_string(), "fetch".to_string()],
+            ..Default::default()
+        };
+        Notification::from_result(&result, Some(&StoodError::throttling_error("slow down")))
+    }
+
+    #[test]
+    fn test_templates_render_notification_fields() {
+        let notification = notification();
+        assert_eq!(notification.status, NotificationStatus::Failed);
+        assert_eq!(notification.error_kind.as_deref(), Some("ThrottlingError"));
+        assert_eq!(notification.retryable, Some(true));
+
+        let text =
+            PayloadTemplate::text("{{status}}: {{error_kind}} after {{tool_calls}} {{other}}");
+        assert_eq!(
+            text.render(&notification),
+            "failed: ThrottlingError after search, fetch {{other}}"
+        );
+        let json =
+            PayloadTemplate::json(r#"{"summary": "{{response}}", "tokens": {{total_tokens}}}"#);
+        let payload: Value = serde_json::from_str(&json.render(&notification)).unwrap();
+        assert_eq!(payload["summary"], "Partial \"draft\"");
+        assert_eq!(payload["tokens"], 0);
+    }
+
+    #[cfg(feature = "sns")]
+    #[test]
+    fn test_sns_sink_requires_topic_arn() {
+        assert!(SnsSink::new("arn:aws:sns:eu-west-1:123456789012:alerts").is_ok());
+        assert!(SnsSink::new("https://sns.amazonaws.com/alerts").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_enforces_network_policy() {
+        let sink = WebhookSink::new("http://127.0.0.1:9/hooks/agent")
+            .with_network_policy(NetworkPolicy::new().with_deny_private_ranges(true));
+        assert!(!sink.clone().failures_only().accepts(&Notification {
+            status: NotificationStatus::Completed,
+            ..notification()
+        }));
+
+        let error = sink.notify(&notification()).await.unwrap_err();
+        assert!(error.to_string().contains("Webhook blocked"), "{}", error);
+    }
+}
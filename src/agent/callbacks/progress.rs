@@ -0,0 +1,178 @@
+//! Periodic progress events for live token and cost tickers.
+//!
+//! With [`AgentBuilder::with_progress_events`], the event loop emits
+//! [`CallbackEvent::Progress`](super::CallbackEvent::Progress) at most once
+//! per [`ProgressConfig::interval`] while a response streams, and after every
+//! model call, tool batch and cycle. Each [`ProgressEvent`] carries the
+//! tokens used so far in the execution and, with [`ProgressConfig::with_pricing`],
+//! their estimated cost, so frontends can show a ticker without counting
+//! deltas themselves.
+//!
+//! Tokens of a response that is still streaming are estimated from its
+//! length until the provider reports the real usage at the end of the call.
+//!
+//! [`AgentBuilder::with_progress_events`]: crate::agent::AgentBuilder::with_progress_events
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::events::{ProgressEvent, ProgressPhase, TokenUsage};
+use crate::agent::quota::TokenPricing;
+
+/// Characters per token used to estimate streamed output
+const CHARS_PER_TOKEN: usize = 4;
+
+/// How often progress events are emitted and how cost is computed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressConfig {
+    /// Minimum time between progress events while streaming
+    pub interval: Duration,
+    /// Token prices for [`ProgressEvent::estimated_cost`]
+    pub pricing: Option<TokenPricing>,
+}
+
+impl Default for ProgressConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(500),
+            pricing: None,
+        }
+    }
+}
+
+impl ProgressConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_pricing(mut self, pricing: TokenPricing) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+}
+
+/// Progress of one execution, shared by the streaming and cycle code paths
+#[derive(Debug)]
+pub(crate) struct ProgressTicker {
+    config: ProgressConfig,
+    state: Mutex<TickerState>,
+}
+
+#[derive(Debug)]
+struct TickerState {
+    started: Instant,
+    last_emitted: Option<Instant>,
+    input_tokens: u32,
+    output_tokens: u32,
+    streamed_chars: usize,
+}
+
+impl ProgressTicker {
+    pub(crate) fn new(config: ProgressConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(TickerState {
+                started: Instant::now(),
+                last_emitted: None,
+                input_tokens: 0,
+                output_tokens: 0,
+                streamed_chars: 0,
+            }),
+        }
+    }
+
+    /// Start counting a new execution
+    pub(crate) fn reset(&self) {
+        let mut state = self.lock();
+        *state = TickerState {
+            started: Instant::now(),
+            last_emitted: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            streamed_chars: 0,
+        };
+    }
+
+    /// Count text streamed by the current model call
+    pub(crate) fn record_stream(&self, chars: usize) {
+        self.lock().streamed_chars += chars;
+    }
+
+    /// Replace the estimate of the current model call with its reported usage
+    pub(crate) fn record_usage(&self, input_tokens: u32, output_tokens: u32) {
+        let mut state = self.lock();
+        state.input_tokens += input_tokens;
+        state.output_tokens += output_tokens;
+        state.streamed_chars = 0;
+    }
+
+    /// The current progress, if `force` is set or the interval has elapsed
+    pub(crate) fn tick(&self, phase: ProgressPhase, force: bool) -> Option<ProgressEvent> {
+        let mut state = self.lock();
+        let now = Instant::now();
+        let due = state
+            .last_emitted
+            .is_none_or(|last| now.duration_since(last) >= self.config.interval);
+        if !force && !due {
+            return None;
+        }
+        state.last_emitted = Some(now);
+
+        let estimated_output = state.streamed_chars.div_ceil(CHARS_PER_TOKEN) as u32;
+        let input_tokens = state.input_tokens;
+        let output_tokens = state.output_tokens + estimated_output;
+        Some(ProgressEvent {
+            tokens_so_far: TokenUsage {
+                input_tokens,
+                output_tokens,
+                total_tokens: input_tokens + output_tokens,
+            },
+            estimated_cost: self
+                .config
+                .pricing
+                .map(|pricing| pricing.cost(input_tokens, output_tokens)),
+            elapsed: now.duration_since(state.started),
+            current_phase: phase,
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TickerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticker_estimates_streamed_tokens_until_usage_is_reported() {
+        let ticker = ProgressTicker::new(
+            ProgressConfig::new()
+                .with_interval(Duration::from_secs(60))
+                .with_pricing(TokenPricing::new(1.0, 10.0)),
+        );
+
+        ticker.record_stream(10);
+        let first = ticker.tick(ProgressPhase::Streaming, false).unwrap();
+        assert_eq!(first.tokens_so_far.output_tokens, 3);
+        assert_eq!(first.current_phase, ProgressPhase::Streaming);
+
+        // Within the interval only forced ticks are emitted
+        ticker.record_stream(100);
+        assert!(ticker.tick(ProgressPhase::Streaming, false).is_none());
+
+        ticker.record_usage(1_000, 20);
+        let after_call = ticker.tick(ProgressPhase::BetweenCycles, true).unwrap();
+        assert_eq!(after_call.tokens_so_far.input_tokens, 1_000);
+        assert_eq!(after_call.tokens_so_far.output_tokens, 20);
+        assert_eq!(after_call.tokens_so_far.total_tokens, 1_020);
+        let cost = after_call.estimated_cost.unwrap();
+        assert!((cost - 0.0012).abs() < 1e-9);
+    }
+}
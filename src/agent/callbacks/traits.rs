@@ -45,9 +45,10 @@
 //! ```
 
 use super::error::CallbackError;
-use super::events::{CallbackEvent, ToolEvent};
+use super::events::{CallbackEvent, ProgressEvent, ToolEvent, ToolProgressEvent};
 use crate::agent::result::AgentResult;
 use crate::error::StoodError;
+use crate::tools::elicitation::{ElicitationRequest, ElicitationResponse};
 use async_trait::async_trait;
 use std::time::Duration;
 
@@ -80,6 +81,15 @@ pub trait CallbackHandler: Send + Sync {
         Ok(()) // Default no-op
     }
 
+    /// Handle progress reported by a running tool
+    ///
+    /// This method is called each time a tool reports progress through its
+    /// [`ProgressReporter`](crate::tools::ProgressReporter).
+    async fn on_tool_progress(&self, event: &ToolProgressEvent) -> Result<(), CallbackError> {
+        let _ = event;
+        Ok(()) // Default no-op
+    }
+
     /// Handle execution completion (matches Python's completion pattern)
     ///
     /// This method is called when the entire agent execution completes,
@@ -150,6 +160,26 @@ pub trait CallbackHandler: Send + Sync {
         Ok(()) // Default no-op
     }
 
+    /// Handle progress events
+    ///
+    /// This method is called periodically with the tokens and estimated cost
+    /// of the execution so far, when progress events are enabled.
+    async fn on_progress(&self, event: &ProgressEvent) -> Result<(), CallbackError> {
+        let _ = event;
+        Ok(()) // Default no-op
+    }
+
+    /// Answer a tool's or an MCP server's request for user input
+    ///
+    /// This method is called when a tool returns
+    /// [`ToolResult::needs_input`](crate::tools::ToolResult::needs_input) or an
+    /// MCP server sends `elicitation/create`. The tool call waits for the answer.
+    /// The default declines, so the model gets the question back as an error.
+    async fn on_elicitation(&self, request: &ElicitationRequest) -> ElicitationResponse {
+        let _ = request;
+        ElicitationResponse::Decline
+    }
+
     /// Full event handler for advanced usage (matches Python's flexibility)
     ///
     /// This method receives all events and can be used for comprehensive
@@ -173,6 +203,7 @@ pub trait CallbackHandler: Send + Sync {
                 tool_name,
                 output,
                 error,
+                error_source,
                 duration,
                 ..
             } => {
@@ -180,6 +211,7 @@ pub trait CallbackHandler: Send + Sync {
                     self.on_tool(ToolEvent::Failed {
                         name: tool_name,
                         error: err,
+                        source: error_source,
                         duration,
                     })
                     .await
@@ -192,6 +224,7 @@ pub trait CallbackHandler: Send + Sync {
                     .await
                 }
             }
+            CallbackEvent::ToolProgress { event } => self.on_tool_progress(&event).await,
             CallbackEvent::EventLoopComplete { result, .. } => {
                 // Convert EventLoopResult to AgentResult for callback
                 let agent_result = AgentResult::from(result, Duration::ZERO);
@@ -228,6 +261,7 @@ pub trait CallbackHandler: Send + Sync {
                 self.on_evaluation(&strategy, decision, &reasoning, duration)
                     .await
             }
+            CallbackEvent::Progress { event } => self.on_progress(&event).await,
             _ => Ok(()), // Ignore other events by default
         }
     }
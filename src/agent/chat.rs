@@ -0,0 +1,196 @@
+//! Turn-by-turn chat with roles and metadata.
+//!
+//! [`Agent::chat_turn`] takes a [`ChatMessage`] rather than a prompt string, so
+//! chat applications control the structure of the conversation: user turns run
+//! the agent, assistant turns are recorded as if the model had said them, and
+//! system turns replace the system prompt. The sender name and metadata are
+//! kept on the message in the conversation history.
+//!
+//! [`Agent::chat_turn_with_prefill`] starts the assistant's reply with given
+//! text, which the model continues:
+//!
+//! ```no_run
+//! use stood::agent::{Agent, ChatMessage};
+//!
+//! # async fn example(mut agent: Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! agent
+//!     .chat_turn(ChatMessage::assistant("Welcome back! How can I help?"))
+//!     .await?;
+//!
+//! let turn = ChatMessage::user("List three colors as a JSON array")
+//!     .with_name("alice")
+//!     .with_metadata("channel", "web".into());
+//! let result = agent.chat_turn_with_prefill(turn, "[").await?;
+//! assert!(result.response.starts_with('['));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Agent, AgentResult, TurnOptions};
+use crate::types::{Message, MessageRole};
+use crate::{Result, StoodError};
+
+/// Metadata key under which [`ChatMessage::name`] is stored on the message
+pub const CHAT_NAME_METADATA_KEY: &str = "name";
+
+/// One turn of a chat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// Who the turn is from
+    pub role: MessageRole,
+    /// Text of the turn
+    pub content: String,
+    /// Name of the participant, e.g. to tell users in a group chat apart
+    pub name: Option<String>,
+    /// Application data kept with the message in the conversation history
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+}
+
+impl ChatMessage {
+    /// Create a turn with the given role
+    pub fn new(role: MessageRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            name: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Create a user turn
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(MessageRole::User, content)
+    }
+
+    /// Create an assistant turn
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(MessageRole::Assistant, content)
+    }
+
+    /// Create a system turn
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(MessageRole::System, content)
+    }
+
+    /// Set the participant name
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Add a metadata entry
+    pub fn with_metadata(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Metadata of the conversation message, including the name
+    fn message_metadata(&self) -> HashMap<String, Value> {
+        let mut metadata = self.metadata.clone();
+        if let Some(name) = &self.name {
+            metadata.insert(
+                CHAT_NAME_METADATA_KEY.to_string(),
+                Value::from(name.as_str()),
+            );
+        }
+        metadata
+    }
+}
+
+impl From<ChatMessage> for Message {
+    fn from(turn: ChatMessage) -> Self {
+        let metadata = turn.message_metadata();
+        let mut message = Message::new(
+            turn.role,
+            vec![crate::types::ContentBlock::text(turn.content)],
+        );
+        message.metadata = metadata;
+        message
+    }
+}
+
+impl Agent {
+    /// Add a turn to the conversation
+    ///
+    /// - A user turn is executed like [`execute`](Self::execute) and returns
+    ///   the result.
+    /// - An assistant turn is added to the history without calling the model,
+    ///   e.g. to restore a conversation or inject a canned reply.
+    /// - A system turn replaces the system prompt for later turns.
+    ///
+    /// Only user turns return a result.
+    pub async fn chat_turn(&mut self, message: ChatMessage) -> Result<Option<AgentResult>> {
+        match message.role {
+            MessageRole::User => {
+                let turn = TurnOptions {
+                    metadata: message.message_metadata(),
+                    ..TurnOptions::default()
+                };
+                self.execute_turn(message.content, turn).await.map(Some)
+            }
+            MessageRole::Assistant => {
+                self.conversation.add_message(message.into());
+                Ok(None)
+            }
+            MessageRole::System => {
+                self.config.system_prompt = Some(message.content.clone());
+                self.conversation.set_system_prompt(Some(message.content));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Execute a user turn with the assistant's reply starting with `prefill`
+    ///
+    /// The model continues from the prefill, which steers the format of the
+    /// reply (e.g. `{` for a JSON object). The prefill is included in
+    /// [`AgentResult::response`] and in the assistant message in the history.
+    /// Some models reject a prefill that ends with whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoodError::ValidationError`] if `message` is not a user turn.
+    pub async fn chat_turn_with_prefill(
+        &mut self,
+        message: ChatMessage,
+        prefill: impl Into<String>,
+    ) -> Result<AgentResult> {
+        if message.role != MessageRole::User {
+            return Err(StoodError::validation_error(
+                "Assistant prefill requires a user turn",
+            ));
+        }
+        let turn = TurnOptions {
+            prefill: Some(prefill.into()),
+            metadata: message.message_metadata(),
+            ..TurnOptions::default()
+        };
+        self.execute_turn(message.content, turn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_message_into_conversation_message() {
+        let message: Message = ChatMessage::user("hi")
+            .with_name("alice")
+            .with_metadata("channel", Value::from("web"))
+            .into();
+        assert_eq!(message.role, MessageRole::User);
+        assert_eq!(message.text().as_deref(), Some("hi"));
+        assert_eq!(message.metadata[CHAT_NAME_METADATA_KEY], "alice");
+        assert_eq!(message.metadata["channel"], "web");
+
+        let plain: Message = ChatMessage::assistant("hello").into();
+        assert!(plain.metadata.is_empty());
+    }
+}
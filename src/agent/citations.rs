@@ -0,0 +1,206 @@
+//! Citations of tool-provided sources in agent responses.
+//!
+//! With citations enabled ([`AgentBuilder::with_citations`]), tool results that
+//! contain documents with a `source_id` are sent to the model together with
+//! instructions to cite them inline, and the citations in the final response
+//! are returned in [`AgentResult::citations`]. RAG applications can then link
+//! each claim to the document supporting it.
+//!
+//! A tool returns citable documents by including objects with a `source_id`
+//! anywhere in its JSON output, optionally with a `title` and the document
+//! `content` (or `text`):
+//!
+//! ```json
+//! {"results": [{"source_id": "kb-12", "title": "Refund policy", "content": "..."}]}
+//! ```
+//!
+//! The model cites them as `[cite:kb-12]` or, quoting the source,
+//! `[cite:kb-12 "refunds within 30 days"]`:
+//!
+//! ```no_run
+//! # use stood::agent::Agent;
+//! # async fn example(mut agent: Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let result = agent.execute("What is the refund policy?").await?;
+//! for citation in &result.citations {
+//!     let claim = &result.response[citation.span.clone()];
+//!     println!("{} ({}): {:?}", claim, citation.source_id, citation.quote);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`AgentBuilder::with_citations`]: crate::agent::AgentBuilder::with_citations
+//! [`AgentResult::citations`]: crate::agent::AgentResult::citations
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Instructions sent with tool results that contain citable documents
+pub const CITATION_INSTRUCTIONS: &str = "These results contain documents with a source_id. \
+When your answer uses information from a document, cite it right after the claim as \
+[cite:SOURCE_ID], or as [cite:SOURCE_ID \"exact quote\"] to quote the supporting text. \
+Only cite source_ids that appear in tool results.";
+
+/// Depth up to which tool output is searched for documents
+const MAX_DOCUMENT_DEPTH: usize = 4;
+
+static CITATION_MARKER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\[cite:\s*([^\s\]"]+)(?:\s+"([^"]*)")?\s*\]"#).expect("valid citation regex")
+});
+
+/// A document returned by a tool that the model can cite
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceDocument {
+    /// Identifier the model cites the document by
+    pub source_id: String,
+    /// Document title, if the tool provided one
+    pub title: Option<String>,
+    /// Document text, if the tool provided it
+    pub content: Option<String>,
+}
+
+/// A citation of a source document in the response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// Source the claim is attributed to
+    pub source_id: String,
+    /// Byte range of the cited claim in the response, i.e. the text from the
+    /// start of its sentence up to the citation marker
+    pub span: Range<usize>,
+    /// Text the model quoted from the source, if any
+    pub quote: Option<String>,
+}
+
+/// Find the citable documents in a tool's output
+///
+/// Any object with a string `source_id` is a document; arrays and nested
+/// objects are searched a few levels deep.
+pub fn extract_documents(output: &Value) -> Vec<SourceDocument> {
+    let mut documents = Vec::new();
+    collect_documents(output, 0, &mut documents);
+    documents
+}
+
+fn collect_documents(value: &Value, depth: usize, documents: &mut Vec<SourceDocument>) {
+    if depth > MAX_DOCUMENT_DEPTH {
+        return;
+    }
+    match value {
+        Value::Object(object) => {
+            if let Some(source_id) = object.get("source_id").and_then(Value::as_str) {
+                let text = |key: &str| object.get(key).and_then(Value::as_str).map(String::from);
+                documents.push(SourceDocument {
+                    source_id: source_id.to_string(),
+                    title: text("title"),
+                    content: text("content").or_else(|| text("text")),
+                });
+            } else {
+                for child in object.values() {
+                    collect_documents(child, depth + 1, documents);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_documents(item, depth + 1, documents);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Extract the citations of `sources` from a response
+///
+/// Markers citing a source_id that is not among `sources` are ignored, since
+/// the model cannot have seen such a document.
+pub fn parse_citations(response: &str, sources: &[SourceDocument]) -> Vec<Citation> {
+    let known: HashSet<&str> = sources.iter().map(|s| s.source_id.as_str()).collect();
+    // End of the previous marker and the claim it cited
+    let mut previous: Option<(usize, Range<usize>)> = None;
+
+    CITATION_MARKER
+        .captures_iter(response)
+        .filter_map(|captures| {
+            let marker = captures.get(0)?;
+            let source_id = captures.get(1)?.as_str();
+            let span = match &previous {
+                // Several markers after one claim all cite that claim
+                Some((end, span)) if response[*end..marker.start()].trim().is_empty() => {
+                    span.clone()
+                }
+                _ => {
+                    let after_previous = previous.as_ref().map_or(0, |(end, _)| *end);
+                    let start = sentence_start(&response[..marker.start()]).max(after_previous);
+                    trim_range(response, start..marker.start())
+                }
+            };
+            previous = Some((marker.end(), span.clone()));
+            known.contains(source_id).then(|| Citation {
+                source_id: source_id.to_string(),
+                span,
+                quote: captures.get(2).map(|q| q.as_str().to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Byte offset where the sentence ending at the end of `text` starts
+fn sentence_start(text: &str) -> usize {
+    text.trim_end()
+        .char_indices()
+        .rev()
+        .find(|(_, c)| matches!(c, '.' | '!' | '?' | '\n'))
+        .map(|(i, c)| i + c.len_utf8())
+        .unwrap_or(0)
+}
+
+fn trim_range(text: &str, range: Range<usize>) -> Range<usize> {
+    let slice = &text[range.clone()];
+    let start = range.start + (slice.len() - slice.trim_start().len());
+    let end = range.end - (slice.len() - slice.trim_end().len());
+    start..end.max(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_documents_and_parse_citations() {
+        let output = json!({
+            "query": "refunds",
+            "results": [
+                {"source_id": "kb-1", "title": "Refunds", "content": "Refunds within 30 days."},
+                {"source_id": "kb-2", "text": "Shipping is free."}
+            ]
+        });
+        let sources = extract_documents(&output);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[1].content.as_deref(), Some("Shipping is free."));
+
+        let response = "Refunds are possible for a month [cite:kb-1 \"Refunds within 30 days.\"]. \
+                        Shipping costs nothing [cite:kb-2][cite:kb-1]. Returns are easy [cite:kb-9].";
+        let citations = parse_citations(response, &sources);
+        assert_eq!(citations.len(), 3);
+        assert_eq!(
+            &response[citations[0].span.clone()],
+            "Refunds are possible for a month"
+        );
+        assert_eq!(
+            citations[0].quote.as_deref(),
+            Some("Refunds within 30 days.")
+        );
+        assert_eq!(
+            &response[citations[1].span.clone()],
+            "Shipping costs nothing"
+        );
+        assert_eq!(citations[2].source_id, "kb-1");
+        assert_eq!(citations[2].span, citations[1].span);
+    }
+}
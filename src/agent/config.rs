@@ -73,6 +73,9 @@ pub struct ExecutionConfig {
 
     /// Log level for controlling debug output from the agent
     pub log_level: LogLevel,
+
+    /// Number of executions kept in the agent's metrics history (0 disables it)
+    pub metrics_history_size: usize,
 }
 
 impl Default for ExecutionConfig {
@@ -83,6 +86,7 @@ impl Default for ExecutionConfig {
             streaming: true,
             timeout: Some(Duration::from_secs(300)), // 5 minutes
             log_level: LogLevel::default(),
+            metrics_history_size: crate::agent::metrics_history::DEFAULT_METRICS_HISTORY_SIZE,
         }
     }
 }
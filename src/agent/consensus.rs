@@ -0,0 +1,489 @@
+//! M-of-N consensus across agents and models.
+//!
+//! A [`ConsensusRunner`] executes the same prompt on N agents (or one agent
+//! with N models) in parallel, groups the answers that agree, and reports the
+//! consensus when at least M of them agree. When they do not, the result is
+//! flagged as a disagreement instead of trusting any single answer, which
+//! suits high-stakes extraction where one model's output is not enough.
+//!
+//! Answers are compared with an [`AnswerComparison`]:
+//!
+//! - [`AnswerComparison::Exact`] - equal after normalizing whitespace and case;
+//!   JSON answers are compared as values, ignoring key order and formatting
+//! - [`AnswerComparison::Embedding`] - cosine similarity of embeddings above
+//!   a threshold, for free-text answers
+//! - [`AnswerComparison::Judge`] - a judge agent groups the answers that mean
+//!   the same thing
+//!
+//! ```no_run
+//! use stood::agent::{Agent, AnswerComparison, ConsensusRunner};
+//! use stood::llm::models::Bedrock;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let agent = Agent::builder()
+//!     .system_prompt("Extract the invoice total as JSON: {\"total\": number}")
+//!     .build()
+//!     .await?;
+//!
+//! let runner = ConsensusRunner::new(2, AnswerComparison::Exact)
+//!     .with_model(&agent, Bedrock::ClaudeHaiku45)
+//!     .with_model(&agent, Bedrock::ClaudeSonnet45)
+//!     .with_model(&agent, Bedrock::NovaPro);
+//!
+//! let result = runner.run("Invoice #42 ... Total due: $1,250.00").await?;
+//! match result.response() {
+//!     Some(answer) => println!("{} of 3 agree: {}", result.agreement(), answer),
+//!     None => println!("No consensus: {:?}", result.groups),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Every execution runs on a copy of its agent, so the runner can be reused
+//! and the agents' conversations are not changed.
+
+use std::sync::Arc;
+
+use crate::agent::best_of::Candidate;
+use crate::agent::result::AgentResult;
+use crate::agent::router::ModelRoute;
+use crate::agent::Agent;
+use crate::llm::embeddings::{cosine_similarity, Embedder};
+use crate::llm::traits::LlmModel;
+use crate::{Result, StoodError};
+
+/// How answers are compared for agreement
+#[derive(Debug, Clone)]
+pub enum AnswerComparison {
+    /// Equal after normalization; JSON answers are compared as values
+    Exact,
+
+    /// Cosine similarity of the answers' embeddings is at least `threshold`
+    Embedding {
+        embedder: Arc<dyn Embedder>,
+        /// Minimum similarity, e.g. 0.9
+        threshold: f32,
+    },
+
+    /// A judge agent groups the answers that agree
+    Judge {
+        /// The judge agent instance
+        judge_agent: Box<Agent>,
+        /// What makes two answers the same, e.g. "the same total amount"
+        criteria: String,
+    },
+}
+
+impl AnswerComparison {
+    /// Compare by embedding similarity
+    pub fn embedding(embedder: Arc<dyn Embedder>, threshold: f32) -> Self {
+        Self::Embedding {
+            embedder,
+            threshold,
+        }
+    }
+
+    /// Compare with a judge agent
+    pub fn judge(judge_agent: Agent, criteria: impl Into<String>) -> Self {
+        Self::Judge {
+            judge_agent: Box::new(judge_agent),
+            criteria: criteria.into(),
+        }
+    }
+
+    /// Get the name of the comparison for logging
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Exact => "exact",
+            Self::Embedding { .. } => "embedding",
+            Self::Judge { .. } => "judge",
+        }
+    }
+}
+
+/// One agent taking part in the consensus
+#[derive(Debug, Clone)]
+struct Member {
+    agent: Agent,
+    /// Model replacing the agent's own
+    model: Option<ModelRoute>,
+}
+
+/// Runs a prompt on several agents and checks that enough of them agree
+#[derive(Debug, Clone)]
+pub struct ConsensusRunner {
+    members: Vec<Member>,
+    required: usize,
+    comparison: AnswerComparison,
+}
+
+impl ConsensusRunner {
+    /// Require `required` agreeing answers (at least 1), compared with `comparison`
+    pub fn new(required: usize, comparison: AnswerComparison) -> Self {
+        Self {
+            members: Vec::new(),
+            required: required.max(1),
+            comparison,
+        }
+    }
+
+    /// Add an agent
+    pub fn with_agent(mut self, agent: Agent) -> Self {
+        self.members.push(Member { agent, model: None });
+        self
+    }
+
+    /// Add a copy of `agent` that uses `model`
+    pub fn with_model<M: LlmModel>(mut self, agent: &Agent, model: M) -> Self {
+        self.members.push(Member {
+            agent: agent.clone(),
+            model: Some(ModelRoute::from_model(&model)),
+        });
+        self
+    }
+
+    /// Number of agents the prompt runs on
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Execute `prompt` on every agent in parallel and group the answers
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoodError::ConfigurationError`] if fewer agents than the
+    /// required agreement were added, the first execution error if every
+    /// execution failed, and embedding errors of
+    /// [`AnswerComparison::Embedding`]. A failed judge falls back to exact
+    /// comparison.
+    pub async fn run(&self, prompt: impl Into<String>) -> Result<ConsensusResult> {
+        let prompt = prompt.into();
+        if self.members.len() < self.required {
+            return Err(StoodError::configuration_error(format!(
+                "Consensus requires {} agreeing answers but only {} agents were added",
+                self.required,
+                self.members.len()
+            )));
+        }
+
+        let mut runners = Vec::with_capacity(self.members.len());
+        for member in &self.members {
+            let mut agent = member.agent.clone();
+            if let Some(route) = &member.model {
+                agent.set_model_route(route).await?;
+            }
+            runners.push(agent);
+        }
+        let outcomes = futures::future::join_all(
+            runners
+                .iter_mut()
+                .map(|runner| runner.execute(prompt.clone())),
+        )
+        .await;
+
+        let mut first_error = None;
+        let candidates: Vec<Candidate> = outcomes
+            .into_iter()
+            .zip(&runners)
+            .enumerate()
+            .map(|(index, (outcome, runner))| Candidate {
+                index,
+                model_id: runner.config.model_id.clone(),
+                result: outcome.map_err(|e| {
+                    let message = e.to_string();
+                    first_error.get_or_insert(e);
+                    message
+                }),
+                score: None,
+            })
+            .collect();
+        if candidates.iter().all(|c| c.result.is_err()) {
+            return Err(first_error.expect("all executions failed with an error"));
+        }
+
+        let answers: Vec<(usize, &str)> = candidates
+            .iter()
+            .filter_map(|c| c.response().map(|response| (c.index, response)))
+            .collect();
+        let mut groups = self.group(&prompt, &answers).await?;
+        // Largest group first; ties keep the order of the agents
+        groups.sort_by_key(|group| std::cmp::Reverse(group.len()));
+
+        let consensus = groups
+            .first()
+            .filter(|group| group.len() >= self.required)
+            .map(|group| group[0]);
+        match consensus {
+            Some(index) => tracing::debug!(
+                "Consensus of {}/{} answers ({}), answer of agent {}",
+                groups[0].len(),
+                candidates.len(),
+                self.comparison.name(),
+                index
+            ),
+            None => tracing::info!(
+                "No consensus: largest agreement is {} of {} answers, {} required",
+                groups.first().map_or(0, Vec::len),
+                candidates.len(),
+                self.required
+            ),
+        }
+
+        Ok(ConsensusResult {
+            consensus,
+            groups,
+            required: self.required,
+            comparison: self.comparison.name().to_string(),
+            candidates,
+        })
+    }
+
+    /// Group the indices of `answers` that agree
+    async fn group(&self, prompt: &str, answers: &[(usize, &str)]) -> Result<Vec<Vec<usize>>> {
+        match &self.comparison {
+            AnswerComparison::Exact => Ok(group_exact(answers)),
+            AnswerComparison::Embedding {
+                embedder,
+                threshold,
+            } => {
+                let texts: Vec<String> = answers.iter().map(|(_, text)| text.to_string()).collect();
+                let vectors = embedder
+                    .embed(&texts)
+                    .await
+                    .map_err(|e| StoodError::model_error(format!("Embedding failed: {}", e)))?;
+                Ok(group_by_similarity(answers, &vectors, *threshold))
+            }
+            AnswerComparison::Judge {
+                judge_agent,
+                criteria,
+            } => {
+                let mut judge = (**judge_agent).clone();
+                match judge.execute(judge_prompt(prompt, criteria, answers)).await {
+                    Ok(verdict) => Ok(parse_judge_groups(&verdict.response, answers)),
+                    Err(e) => {
+                        tracing::warn!("Judge agent failed, comparing answers exactly: {}", e);
+                        Ok(group_exact(answers))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Result of a consensus run
+#[derive(Debug, Clone)]
+pub struct ConsensusResult {
+    /// Index of the candidate whose answer represents the consensus, if at
+    /// least `required` answers agree
+    pub consensus: Option<usize>,
+    /// Indices of agreeing candidates, largest group first; failed
+    /// executions are in no group
+    pub groups: Vec<Vec<usize>>,
+    /// Number of agreeing answers required
+    pub required: usize,
+    /// Name of the comparison used
+    pub comparison: String,
+    /// All executions in the order the agents were added
+    pub candidates: Vec<Candidate>,
+}
+
+impl ConsensusResult {
+    /// Whether enough answers agree
+    pub fn is_consensus(&self) -> bool {
+        self.consensus.is_some()
+    }
+
+    /// Result of the consensus answer
+    pub fn result(&self) -> Option<&AgentResult> {
+        self.candidates[self.consensus?].result.as_ref().ok()
+    }
+
+    /// Text of the consensus answer
+    pub fn response(&self) -> Option<&str> {
+        self.result().map(|result| result.response.as_str())
+    }
+
+    /// Size of the largest group of agreeing answers
+    pub fn agreement(&self) -> usize {
+        self.groups.first().map_or(0, Vec::len)
+    }
+}
+
+/// Canonical form of an answer for exact comparison
+fn normalize(answer: &str) -> String {
+    let mut text = answer.trim();
+    // Unwrap a fenced block such as ```json ... ```
+    if let Some(fenced) = text.strip_prefix("```") {
+        let body = fenced.split_once('\n').map_or("", |(_, body)| body);
+        text = body.trim_end().trim_end_matches("```").trim();
+    }
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
+        return canonical_json(&value);
+    }
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', '!'])
+        .to_lowercase()
+}
+
+/// JSON with object keys sorted, so key order does not matter
+fn canonical_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            let fields: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    format!(
+                        "{}:{}",
+                        serde_json::Value::String(key.clone()),
+                        canonical_json(value)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        // 1250 and 1250.0 are the same amount
+        serde_json::Value::Number(number) => number
+            .as_f64()
+            .map_or_else(|| number.to_string(), |n| n.to_string()),
+        other => other.to_string(),
+    }
+}
+
+fn group_exact(answers: &[(usize, &str)]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for (index, answer) in answers {
+        let key = normalize(answer);
+        match groups.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, members)) => members.push(*index),
+            None => groups.push((key, vec![*index])),
+        }
+    }
+    groups.into_iter().map(|(_, members)| members).collect()
+}
+
+/// Add each answer to the first group whose first answer is similar enough
+fn group_by_similarity(
+    answers: &[(usize, &str)],
+    vectors: &[Vec<f32>],
+    threshold: f32,
+) -> Vec<Vec<usize>> {
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for (position, (index, _)) in answers.iter().enumerate() {
+        let vector = &vectors[position];
+        match groups
+            .iter_mut()
+            .find(|(first, _)| cosine_similarity(&vectors[*first], vector) >= threshold)
+        {
+            Some((_, members)) => members.push(*index),
+            None => groups.push((position, vec![*index])),
+        }
+    }
+    groups.into_iter().map(|(_, members)| members).collect()
+}
+
+/// Build the prompt asking a judge to group agreeing answers
+fn judge_prompt(prompt: &str, criteria: &str, answers: &[(usize, &str)]) -> String {
+    let mut text = format!(
+        "Several answers were given to the same request. Group the answers that \
+         agree with each other. {}\n\nRequest:\n{}\n\n",
+        criteria, prompt
+    );
+    for (index, answer) in answers {
+        text.push_str(&format!(
+            "<answer number=\"{}\">\n{}\n</answer>\n\n",
+            index + 1,
+            answer
+        ));
+    }
+    text.push_str(
+        "Reply with only the groups, one per line, as answer numbers separated \
+         by commas. Put an answer that agrees with no other on its own line.",
+    );
+    text
+}
+
+/// Parse the judge's groups; answers the judge left out are their own group
+fn parse_judge_groups(response: &str, answers: &[(usize, &str)]) -> Vec<Vec<usize>> {
+    let mut assigned = std::collections::HashSet::new();
+    let mut groups = Vec::new();
+    for line in response.lines() {
+        let group: Vec<usize> = line
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|s| s.parse::<usize>().ok())
+            .filter_map(|n| n.checked_sub(1))
+            .filter(|index| answers.iter().any(|(i, _)| i == index))
+            .filter(|index| assigned.insert(*index))
+            .collect();
+        if !group.is_empty() {
+            groups.push(group);
+        }
+    }
+    for (index, _) in answers {
+        if assigned.insert(*index) {
+            groups.push(vec![*index]);
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_grouping_normalizes_answers() {
+        assert_eq!(normalize("  The answer is   42. "), "the answer is 42");
+        assert_eq!(
+            normalize("```json\n{\"b\": [1, {\"d\": 2, \"c\": 3}], \"a\": \"x\"}\n```"),
+            normalize("{\"a\":\"x\",\"b\":[1,{\"c\":3,\"d\":2}]}")
+        );
+
+        let answers = [
+            (0, "{\"total\": 1250.0, \"currency\": \"USD\"}"),
+            (1, "{\"total\": 1250}"),
+            (2, "{\"total\": 1250, \"currency\": \"EUR\"}"),
+            (3, "{\n  \"currency\": \"USD\",\n  \"total\": 1250.0\n}"),
+        ];
+        assert_eq!(group_exact(&answers), vec![vec![0, 3], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn test_similarity_grouping() {
+        let answers = [(0, "a"), (1, "b"), (2, "c")];
+        let vectors = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.95, 0.1]];
+        assert_eq!(
+            group_by_similarity(&answers, &vectors, 0.9),
+            vec![vec![0, 2], vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_judge_group_parsing() {
+        let answers = [(0, "a"), (1, "b"), (2, "c"), (4, "e")];
+        assert_eq!(
+            parse_judge_groups("Groups:\n1, 3\n2, 4\n5", &answers),
+            vec![vec![0, 2], vec![1], vec![4]]
+        );
+        // Unknown numbers are ignored and unmentioned answers stand alone
+        assert_eq!(
+            parse_judge_groups("1, 2, 9", &answers),
+            vec![vec![0, 1], vec![2], vec![4]]
+        );
+
+        let prompt = judge_prompt("question", "Same total.", &answers);
+        assert!(prompt.contains("<answer number=\"5\">"));
+        assert!(!prompt.contains("<answer number=\"4\">"));
+    }
+}
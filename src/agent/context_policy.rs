@@ -0,0 +1,534 @@
+//! Pluggable policies for trimming conversation history.
+//!
+//! When the conversation of an agent grows past its message or token limits,
+//! its [`ConversationManager`](super::ConversationManager) asks a
+//! [`ContextPolicy`] which messages to drop. Select one with
+//! [`AgentBuilder::with_context_policy`](super::AgentBuilder::with_context_policy):
+//!
+//! | Policy | Keeps |
+//! |---|---|
+//! | [`KeepLastN`] (default) | The most recent messages |
+//! | [`ImportanceWeighted`] | Important messages (user turns, or messages with an `importance` metadata value) over routine tool traffic |
+//! | [`SummarizeThenDrop`] | The most recent messages, plus a summary of the dropped ones |
+//! | [`PinSystemAndFirstTurn`] | System messages and the first exchange (usually the task), then the most recent messages |
+//!
+//! Policies never separate a tool call from its result, so the trimmed
+//! conversation is always valid for the model provider. They never drop
+//! [pinned](Message::pinned) messages either, even if the conversation then
+//! stays over its limits. Messages whose [time to live](Message::with_ttl)
+//! has passed are removed before any policy runs.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use stood::agent::context_policy::PinSystemAndFirstTurn;
+//! use stood::agent::Agent;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let agent = Agent::builder()
+//!     .with_context_policy(Arc::new(PinSystemAndFirstTurn))
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::context_manager::{
+    render_transcript, truncate_text, TruncationStrategy, COMPACTION_SUMMARY_PREFIX,
+};
+use crate::types::{ContentBlock, Message, MessageRole};
+
+pub use crate::types::IMPORTANCE_METADATA_KEY;
+
+/// Limits a conversation must be trimmed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextLimits {
+    /// Maximum number of messages
+    pub max_messages: usize,
+    /// Maximum estimated tokens, including `reserved_tokens`
+    pub max_tokens: usize,
+    /// Tokens used outside the messages (e.g. by the system prompt)
+    pub reserved_tokens: usize,
+}
+
+impl ContextLimits {
+    /// Estimated tokens of `messages` plus the reserved tokens
+    pub fn tokens(&self, messages: &[Message]) -> usize {
+        self.reserved_tokens + messages.iter().map(estimate_message_tokens).sum::<usize>()
+    }
+
+    /// Whether `messages` are within both limits
+    pub fn fits(&self, messages: &[Message]) -> bool {
+        messages.len() <= self.max_messages && self.tokens(messages) <= self.max_tokens
+    }
+}
+
+/// Rough token estimate of a message (about 4 characters per token plus
+/// formatting overhead)
+pub fn estimate_message_tokens(message: &Message) -> usize {
+    message.text().map_or(0, |text| text.len().div_ceil(4) + 10)
+}
+
+/// Decides which messages to drop when a conversation exceeds its limits
+pub trait ContextPolicy: Send + Sync + fmt::Debug {
+    /// Trim `messages` so they fit `limits`, if possible
+    ///
+    /// Called after every message added to the conversation, only when the
+    /// limits are exceeded.
+    fn apply(&self, messages: &mut Vec<Message>, limits: &ContextLimits);
+
+    /// Name of the policy for logging
+    fn name(&self) -> &str;
+}
+
+/// Ranges of messages that must be kept or dropped together: an assistant
+/// message calling tools and the message with the tool results
+pub fn message_groups(messages: &[Message]) -> Vec<Range<usize>> {
+    let has = |message: &Message, tool_result: bool| {
+        message.content.iter().any(|block| match block {
+            ContentBlock::ToolUse { .. } => !tool_result,
+            ContentBlock::ToolResult { .. } => tool_result,
+            _ => false,
+        })
+    };
+    let mut groups = Vec::new();
+    let mut start = 0;
+    while start < messages.len() {
+        let mut end = start + 1;
+        if has(&messages[start], false) && end < messages.len() && has(&messages[end], true) {
+            end += 1;
+        }
+        groups.push(start..end);
+        start = end;
+    }
+    groups
+}
+
+/// Remove expired messages, with their tool call or result, at `now`
+///
+/// Groups holding a pinned message are kept. Returns the number of messages
+/// removed.
+pub fn remove_expired(messages: &mut Vec<Message>, now: chrono::DateTime<chrono::Utc>) -> usize {
+    let expired: Vec<Range<usize>> = message_groups(messages)
+        .into_iter()
+        .filter(|group| {
+            let group = &messages[group.clone()];
+            group.iter().any(|m| m.is_expired_at(now)) && !group.iter().any(Message::is_pinned)
+        })
+        .collect();
+    let before = messages.len();
+    remove_groups(messages, &expired);
+    before - messages.len()
+}
+
+/// Remove the messages of `groups` (ranges into `messages`)
+fn remove_groups(messages: &mut Vec<Message>, groups: &[Range<usize>]) {
+    let mut index = 0;
+    messages.retain(|_| {
+        let keep = !groups.iter().any(|group| group.contains(&index));
+        index += 1;
+        keep
+    });
+}
+
+/// Drop groups in `order` until `messages` fit, skipping the last group and
+/// groups holding a pinned message
+fn drop_in_order(
+    messages: &mut Vec<Message>,
+    limits: &ContextLimits,
+    groups: &[Range<usize>],
+    order: impl IntoIterator<Item = usize>,
+) {
+    let mut count = messages.len();
+    let mut tokens = limits.tokens(messages);
+    let mut dropped = Vec::new();
+    for index in order {
+        if count <= limits.max_messages && tokens <= limits.max_tokens {
+            break;
+        }
+        if index + 1 >= groups.len()
+            || messages[groups[index].clone()]
+                .iter()
+                .any(Message::is_pinned)
+        {
+            continue;
+        }
+        let group = groups[index].clone();
+        count -= group.len();
+        tokens -= messages[group.clone()]
+            .iter()
+            .map(estimate_message_tokens)
+            .sum::<usize>();
+        dropped.push(group);
+    }
+    remove_groups(messages, &dropped);
+}
+
+/// Drop the oldest messages until the conversation fits (default policy)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeepLastN;
+
+impl ContextPolicy for KeepLastN {
+    fn apply(&self, messages: &mut Vec<Message>, limits: &ContextLimits) {
+        let groups = message_groups(messages);
+        drop_in_order(messages, limits, &groups, 0..groups.len());
+    }
+
+    fn name(&self) -> &str {
+        "keep_last_n"
+    }
+}
+
+/// Drop the least important messages first, oldest first among equals
+///
+/// A message's importance is its `importance` metadata value
+/// ([`IMPORTANCE_METADATA_KEY`]) if set; otherwise user messages score 1.0,
+/// assistant messages 0.6 and tool calls with their results 0.3. The most
+/// recent groups are always kept.
+#[derive(Debug, Clone, Copy)]
+pub struct ImportanceWeighted {
+    keep_recent: usize,
+}
+
+impl Default for ImportanceWeighted {
+    fn default() -> Self {
+        Self { keep_recent: 4 }
+    }
+}
+
+impl ImportanceWeighted {
+    /// Policy keeping the 4 most recent messages (or tool call pairs)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Always keep the `count` most recent messages (or tool call pairs)
+    pub fn with_keep_recent(mut self, count: usize) -> Self {
+        self.keep_recent = count;
+        self
+    }
+
+    fn importance(messages: &[Message]) -> f64 {
+        let explicit = messages
+            .iter()
+            .filter_map(Message::importance)
+            .reduce(f64::max);
+        if let Some(importance) = explicit {
+            return importance;
+        }
+        if messages.len() > 1 {
+            0.3
+        } else if messages[0].role == MessageRole::Assistant {
+            0.6
+        } else {
+            1.0
+        }
+    }
+}
+
+impl ContextPolicy for ImportanceWeighted {
+    fn apply(&self, messages: &mut Vec<Message>, limits: &ContextLimits) {
+        let groups = message_groups(messages);
+        let droppable = groups.len().saturating_sub(self.keep_recent.max(1));
+        let mut order: Vec<(usize, f64)> = (0..droppable)
+            .map(|index| (index, Self::importance(&messages[groups[index].clone()])))
+            .collect();
+        // Stable sort keeps older groups first among equal importance
+        order.sort_by(|a, b| a.1.total_cmp(&b.1));
+        drop_in_order(
+            messages,
+            limits,
+            &groups,
+            order.into_iter().map(|(index, _)| index),
+        );
+    }
+
+    fn name(&self) -> &str {
+        "importance_weighted"
+    }
+}
+
+/// Summarizes dropped messages into text
+pub type Summarizer = Arc<dyn Fn(&[Message]) -> String + Send + Sync>;
+
+/// Replace the oldest messages with a summary of them
+///
+/// The summary is prepended to the first kept user message, like
+/// [compaction](crate::context_manager::CompactionConfig), and is itself
+/// summarized again when later trimming drops it. Pinned messages among the
+/// oldest ones are kept as they are instead. The default summarizer
+/// keeps the end of a plain-text transcript of the dropped messages; use
+/// [`with_summarizer`](Self::with_summarizer) for a smarter one.
+#[derive(Clone)]
+pub struct SummarizeThenDrop {
+    summarizer: Summarizer,
+    max_summary_bytes: usize,
+}
+
+impl fmt::Debug for SummarizeThenDrop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SummarizeThenDrop")
+            .field("max_summary_bytes", &self.max_summary_bytes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for SummarizeThenDrop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SummarizeThenDrop {
+    /// Policy with the default transcript summarizer and 2000-byte summaries
+    pub fn new() -> Self {
+        Self {
+            summarizer: Arc::new(|messages: &[Message]| render_transcript(messages)),
+            max_summary_bytes: 2_000,
+        }
+    }
+
+    /// Summarize dropped messages with `summarizer`
+    pub fn with_summarizer(
+        mut self,
+        summarizer: impl Fn(&[Message]) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.summarizer = Arc::new(summarizer);
+        self
+    }
+
+    /// Truncate summaries to `max_bytes`, keeping their end
+    pub fn with_max_summary_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_summary_bytes = max_bytes;
+        self
+    }
+}
+
+/// Whether a message starts a new user turn (a user message without tool results)
+fn starts_turn(message: &Message) -> bool {
+    message.role == MessageRole::User
+        && !message
+            .content
+            .iter()
+            .any(|block| matches!(block, ContentBlock::ToolResult { .. }))
+}
+
+impl ContextPolicy for SummarizeThenDrop {
+    fn apply(&self, messages: &mut Vec<Message>, limits: &ContextLimits) {
+        // Room for the summary itself
+        let summary_tokens = self.max_summary_bytes.div_ceil(4);
+        let budget = ContextLimits {
+            max_messages: limits.max_messages,
+            max_tokens: limits.max_tokens.saturating_sub(summary_tokens),
+            reserved_tokens: limits.reserved_tokens,
+        };
+        let mut tokens = budget.tokens(messages);
+        let mut split = None;
+        for index in 1..messages.len() {
+            tokens -= estimate_message_tokens(&messages[index - 1]);
+            if starts_turn(&messages[index]) {
+                split = Some(index);
+                if messages.len() - index <= budget.max_messages && tokens <= budget.max_tokens {
+                    break;
+                }
+            }
+        }
+        let Some(split) = split else {
+            // No turn boundary to summarize at
+            return KeepLastN.apply(messages, limits);
+        };
+
+        let (pinned, dropped): (Vec<_>, Vec<_>) = message_groups(&messages[..split])
+            .into_iter()
+            .map(|group| messages[group].to_vec())
+            .partition(|group| group.iter().any(Message::is_pinned));
+        let dropped: Vec<Message> = dropped.into_iter().flatten().collect();
+        messages.drain(..split);
+        if !dropped.is_empty() {
+            let summary = (self.summarizer)(&dropped);
+            let summary = truncate_text(&summary, self.max_summary_bytes, TruncationStrategy::Tail);
+            messages[0].content.insert(
+                0,
+                ContentBlock::Text {
+                    text: format!("{}\n{}", COMPACTION_SUMMARY_PREFIX, summary.trim()),
+                },
+            );
+        }
+        messages.splice(0..0, pinned.into_iter().flatten());
+        if !limits.fits(messages) {
+            KeepLastN.apply(messages, limits);
+        }
+    }
+
+    fn name(&self) -> &str {
+        "summarize_then_drop"
+    }
+}
+
+/// Keep system messages and the first exchange, dropping the oldest of the rest
+///
+/// The first exchange is the first user message, which usually states the
+/// task, and the reply to it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PinSystemAndFirstTurn;
+
+impl ContextPolicy for PinSystemAndFirstTurn {
+    fn apply(&self, messages: &mut Vec<Message>, limits: &ContextLimits) {
+        let groups = message_groups(messages);
+        let first_turn = groups
+            .iter()
+            .position(|group| messages[group.start].role == MessageRole::User);
+        let order: Vec<usize> = (0..groups.len())
+            .filter(|&index| {
+                let pinned_turn =
+                    first_turn.is_some_and(|first| index == first || index == first + 1);
+                let system = messages[groups[index].clone()]
+                    .iter()
+                    .any(|m| m.role == MessageRole::System);
+                !pinned_turn && !system
+            })
+            .collect();
+        drop_in_order(messages, limits, &groups, order);
+    }
+
+    fn name(&self) -> &str {
+        "pin_system_and_first_turn"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool_pair(id: &str) -> [Message; 2] {
+        [
+            Message::new(
+                MessageRole::Assistant,
+                vec![ContentBlock::ToolUse {
+                    id: id.to_string(),
+                    name: "lookup".to_string(),
+                    input: json!({}),
+                }],
+            ),
+            Message::new(
+                MessageRole::User,
+                vec![ContentBlock::ToolResult {
+                    tool_use_id: id.to_string(),
+                    content: crate::types::ToolResultContent::text("ok"),
+                    is_error: false,
+                }],
+            ),
+        ]
+    }
+
+    fn texts(messages: &[Message]) -> Vec<String> {
+        messages
+            .iter()
+            .map(|m| m.text().unwrap_or_else(|| "<tool>".to_string()))
+            .collect()
+    }
+
+    fn limits(max_messages: usize) -> ContextLimits {
+        ContextLimits {
+            max_messages,
+            max_tokens: 100_000,
+            reserved_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn test_policies_keep_tool_pairs_and_pinned_messages() {
+        let mut conversation = vec![Message::user("task"), Message::assistant("plan")];
+        conversation.extend(tool_pair("t1"));
+        conversation.push(Message::user("follow-up"));
+        conversation.push(Message::assistant("answer"));
+
+        let mut messages = conversation.clone();
+        KeepLastN.apply(&mut messages, &limits(3));
+        // The tool result is never kept without its call
+        assert_eq!(texts(&messages), vec!["follow-up", "answer"]);
+
+        let mut messages = conversation.clone();
+        PinSystemAndFirstTurn.apply(&mut messages, &limits(4));
+        assert_eq!(
+            texts(&messages),
+            vec!["task", "plan", "follow-up", "answer"]
+        );
+
+        let mut messages = conversation.clone();
+        messages[1]
+            .metadata
+            .insert(IMPORTANCE_METADATA_KEY.to_string(), json!(5.0));
+        ImportanceWeighted::new()
+            .with_keep_recent(1)
+            .apply(&mut messages, &limits(3));
+        assert_eq!(texts(&messages), vec!["plan", "follow-up", "answer"]);
+    }
+
+    #[test]
+    fn test_summarize_then_drop() {
+        let mut messages = vec![
+            Message::user("first question"),
+            Message::assistant("first answer"),
+            Message::user("second question"),
+            Message::assistant("second answer"),
+        ];
+        SummarizeThenDrop::new()
+            .with_summarizer(|dropped| format!("{} messages about questions", dropped.len()))
+            .apply(&mut messages, &limits(2));
+
+        assert_eq!(messages.len(), 2);
+        let first = messages[0].text().unwrap();
+        assert!(first.starts_with(COMPACTION_SUMMARY_PREFIX));
+        assert!(first.contains("2 messages about questions"));
+        assert!(first.ends_with("second question"));
+    }
+
+    #[test]
+    fn test_pinned_messages_are_never_trimmed() {
+        let conversation = vec![
+            Message::user("first question").pinned(),
+            Message::assistant("first answer"),
+            Message::user("second question"),
+            Message::assistant("second answer"),
+        ];
+
+        let mut messages = conversation.clone();
+        KeepLastN.apply(&mut messages, &limits(3));
+        assert_eq!(
+            texts(&messages),
+            vec!["first question", "second question", "second answer"]
+        );
+
+        let mut messages = conversation.clone();
+        SummarizeThenDrop::new()
+            .with_summarizer(|dropped| texts(dropped).join(", "))
+            .apply(&mut messages, &limits(3));
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].text().unwrap(), "first question");
+        let summarized = messages[1].text().unwrap();
+        assert!(summarized.contains("first answer"));
+        assert!(!summarized.contains("first question"));
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let mut messages = vec![Message::user("task")];
+        messages.extend(tool_pair("t1"));
+        messages[2] = messages[2].clone().with_ttl(std::time::Duration::ZERO);
+        messages.push(
+            Message::assistant("pinned note")
+                .with_ttl(std::time::Duration::ZERO)
+                .pinned(),
+        );
+        messages.push(Message::assistant("fresh").with_ttl(std::time::Duration::from_secs(60)));
+
+        // The expired tool result takes its call with it
+        assert_eq!(remove_expired(&mut messages, chrono::Utc::now()), 2);
+        assert_eq!(texts(&messages), vec!["task", "pinned note", "fresh"]);
+    }
+}
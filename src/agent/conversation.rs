@@ -80,6 +80,31 @@
 //! ));
 //! ```
 //!
+//! # Checkpoints
+//!
+//! Name a point in the conversation and return to it later, e.g. to undo the
+//! last exchange:
+//! ```rust
+//! use stood::agent::conversation::ConversationManager;
+//!
+//! let mut manager = ConversationManager::new();
+//! manager.add_user_message("Refactor the parser");
+//! manager.add_assistant_message("Here is the refactored parser...");
+//!
+//! manager.checkpoint("before_tests");
+//! manager.add_user_message("Now add tests");
+//! manager.add_assistant_message("Here are the tests...");
+//!
+//! // Undo the last exchange
+//! manager.rollback_to("before_tests")?;
+//! assert_eq!(manager.message_count(), 2);
+//! # Ok::<(), stood::StoodError>(())
+//! ```
+//!
+//! Checkpoints live in memory; [`save_checkpoints`](ConversationManager::save_checkpoints)
+//! and [`load_checkpoints`](ConversationManager::load_checkpoints) keep them
+//! in a [`KvStore`](crate::storage::KvStore) across restarts.
+//!
 //! # Token Management
 //!
 //! Monitor and optimize token usage:
@@ -163,10 +188,14 @@
 //! - Bedrock formatting: O(n) with zero-copy where possible
 //! - Memory usage: Scales linearly with conversation length up to limits
 
+use super::context_policy::{remove_expired, ContextLimits, ContextPolicy, KeepLastN};
+use super::ConversationSnapshot;
 use crate::llm::traits::LlmModel;
+use crate::storage::{KvStore, KvStoreExt};
 use crate::types::{Message, MessageRole, Messages};
-use crate::Result;
+use crate::{Result, StoodError};
 use serde_json::{json, Value};
+use std::sync::Arc;
 
 /// Manages conversation history and context window for an agent
 #[derive(Debug, Clone)]
@@ -179,6 +208,10 @@ pub struct ConversationManager {
     max_tokens: usize,
     /// System prompt to include with requests
     system_prompt: Option<String>,
+    /// Policy deciding which messages to drop when limits are exceeded
+    policy: Arc<dyn ContextPolicy>,
+    /// Named checkpoints, in the order they were created
+    checkpoints: Vec<(String, ConversationSnapshot)>,
 }
 
 impl ConversationManager {
@@ -189,6 +222,8 @@ impl ConversationManager {
             max_messages: 100,   // Default limit
             max_tokens: 100_000, // Default token limit
             system_prompt: None,
+            policy: Arc::new(KeepLastN),
+            checkpoints: Vec::new(),
         }
     }
 
@@ -199,9 +234,27 @@ impl ConversationManager {
             max_messages,
             max_tokens,
             system_prompt: None,
+            policy: Arc::new(KeepLastN),
+            checkpoints: Vec::new(),
         }
     }
 
+    /// Use `policy` to trim the conversation when limits are exceeded
+    pub fn with_policy(mut self, policy: Arc<dyn ContextPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Replace the policy used to trim the conversation
+    pub fn set_policy(&mut self, policy: Arc<dyn ContextPolicy>) {
+        self.policy = policy;
+    }
+
+    /// The policy used to trim the conversation
+    pub fn policy(&self) -> &Arc<dyn ContextPolicy> {
+        &self.policy
+    }
+
     /// Set the system prompt
     pub fn set_system_prompt(&mut self, prompt: Option<String>) {
         self.system_prompt = prompt;
@@ -235,6 +288,79 @@ impl ConversationManager {
         &self.messages
     }
 
+    /// Get mutable access to the messages in the conversation
+    pub fn messages_mut(&mut self) -> &mut Messages {
+        &mut self.messages
+    }
+
+    /// Snapshot the messages and system prompt, e.g. to diff against a later state
+    ///
+    /// See [`ConversationDiff`](super::ConversationDiff).
+    pub fn snapshot(&self) -> super::ConversationSnapshot {
+        super::ConversationSnapshot::new(self.messages.messages.clone(), self.system_prompt.clone())
+    }
+
+    /// Save the messages and system prompt as checkpoint `name`
+    ///
+    /// An existing checkpoint with the same name is replaced.
+    pub fn checkpoint(&mut self, name: impl Into<String>) {
+        let name = name.into();
+        self.checkpoints.retain(|(existing, _)| *existing != name);
+        let snapshot = self.snapshot();
+        self.checkpoints.push((name, snapshot));
+    }
+
+    /// Restore the messages and system prompt saved as checkpoint `name`
+    ///
+    /// Checkpoints created after `name` are removed; `name` itself is kept, so
+    /// the conversation can be rolled back to it again.
+    pub fn rollback_to(&mut self, name: &str) -> Result<()> {
+        let position = self
+            .checkpoints
+            .iter()
+            .position(|(existing, _)| existing == name)
+            .ok_or_else(|| {
+                StoodError::conversation_error(format!("No checkpoint named '{}'", name))
+            })?;
+        self.checkpoints.truncate(position + 1);
+        let snapshot = &self.checkpoints[position].1;
+        self.messages.messages = snapshot.messages.clone();
+        self.system_prompt = snapshot.system_prompt.clone();
+        Ok(())
+    }
+
+    /// Names of the checkpoints, oldest first
+    pub fn checkpoints(&self) -> Vec<&str> {
+        self.checkpoints
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Remove checkpoint `name`, returning whether it existed
+    pub fn remove_checkpoint(&mut self, name: &str) -> bool {
+        let before = self.checkpoints.len();
+        self.checkpoints.retain(|(existing, _)| existing != name);
+        self.checkpoints.len() != before
+    }
+
+    /// Save the checkpoints to `store` under `key`
+    pub async fn save_checkpoints(&self, store: &dyn KvStore, key: &str) -> Result<()> {
+        store.put_json(key, &self.checkpoints).await
+    }
+
+    /// Replace the checkpoints with those saved in `store` under `key`,
+    /// returning whether any were saved there
+    pub async fn load_checkpoints(&mut self, store: &dyn KvStore, key: &str) -> Result<bool> {
+        match store.get_json(key).await? {
+            Some(checkpoints) => {
+                self.checkpoints = checkpoints;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     /// Get messages with system prompt included (creates a new Messages struct)
     pub fn messages_with_system_prompt(&self) -> Messages {
         let mut messages = self.messages.clone();
@@ -336,19 +462,34 @@ impl ConversationManager {
         Ok(request)
     }
 
-    /// Manage context window by removing old messages if limits are exceeded
+    /// Manage context window by removing expired messages, then letting the
+    /// policy trim messages if limits are exceeded
     fn manage_context_window(&mut self) {
-        // First, check message count limit
-        if self.messages.len() > self.max_messages {
-            let messages_to_remove = self.messages.len() - self.max_messages;
-            self.messages.messages.drain(0..messages_to_remove);
+        let expired = remove_expired(&mut self.messages.messages, chrono::Utc::now());
+        if expired > 0 {
+            tracing::debug!("Removed {} expired messages from conversation", expired);
         }
 
-        // Then check token limit (rough estimation)
-        while self.estimate_token_count() > self.max_tokens && self.messages.len() > 1 {
-            // Remove the oldest message (but try to keep pairs if possible)
-            self.messages.messages.remove(0);
+        let limits = ContextLimits {
+            max_messages: self.max_messages,
+            max_tokens: self.max_tokens,
+            reserved_tokens: self
+                .system_prompt
+                .as_deref()
+                .map_or(0, estimate_text_tokens),
+        };
+        if limits.fits(&self.messages.messages) {
+            return;
         }
+
+        let before = self.messages.len();
+        self.policy.apply(&mut self.messages.messages, &limits);
+        tracing::debug!(
+            "Context policy '{}' trimmed conversation from {} to {} messages",
+            self.policy.name(),
+            before,
+            self.messages.len()
+        );
     }
 
     /// Get conversation summary for debugging
@@ -440,6 +581,58 @@ mod tests {
         assert!(manager.is_empty());
     }
 
+    #[test]
+    fn test_rollback_to_checkpoint() {
+        let mut manager = ConversationManager::new();
+        manager.add_user_message("Hello");
+        manager.add_assistant_message("Hi");
+        manager.checkpoint("greeting");
+
+        manager.set_system_prompt(Some("Be brief".to_string()));
+        manager.add_user_message("Refactor this");
+        manager.checkpoint("refactor");
+        manager.add_assistant_message("Done");
+
+        manager.rollback_to("greeting").unwrap();
+        assert_eq!(manager.message_count(), 2);
+        assert!(manager.system_prompt().is_none());
+        assert_eq!(manager.checkpoints(), vec!["greeting"]);
+        assert!(manager.rollback_to("refactor").is_err());
+
+        manager.add_user_message("Something else");
+        manager.rollback_to("greeting").unwrap();
+        assert_eq!(
+            manager.last_message().unwrap().text(),
+            Some("Hi".to_string())
+        );
+        assert!(manager.remove_checkpoint("greeting"));
+        assert!(manager.checkpoints().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints_survive_in_store() {
+        let store = crate::storage::InMemoryKvStore::new();
+        let mut manager = ConversationManager::new();
+        manager.add_user_message("Hello");
+        manager.checkpoint("first");
+        manager.add_assistant_message("Hi");
+        manager.checkpoint("second");
+        manager
+            .save_checkpoints(&store, "checkpoints")
+            .await
+            .unwrap();
+
+        let mut restored = ConversationManager::new();
+        assert!(!restored.load_checkpoints(&store, "missing").await.unwrap());
+        assert!(restored
+            .load_checkpoints(&store, "checkpoints")
+            .await
+            .unwrap());
+        assert_eq!(restored.checkpoints(), vec!["first", "second"]);
+        restored.rollback_to("first").unwrap();
+        assert_eq!(restored.message_count(), 1);
+    }
+
     #[test]
     fn test_token_estimation() {
         let mut manager = ConversationManager::new();
@@ -0,0 +1,331 @@
+//! Diffs between two states of a conversation.
+//!
+//! Context management changes the history behind the application's back:
+//! the conversation window drops old messages, compaction replaces them with a
+//! summary, and executions replay the history without tool blocks. A
+//! [`ConversationDiff`] between two [`ConversationSnapshot`]s shows exactly
+//! which messages were added, removed, summarized or truncated, so these
+//! changes can be audited.
+//!
+//! Every [`Agent::execute`](crate::agent::Agent::execute) call records the diff
+//! of its conversation in
+//! [`ExecutionDetails::conversation_diff`](crate::agent::ExecutionDetails::conversation_diff).
+//! Snapshots can also be taken at any other point:
+//!
+//! ```no_run
+//! # use stood::agent::{Agent, ConversationDiff};
+//! # async fn example(mut agent: Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let before = agent.conversation().snapshot();
+//! agent.execute("Summarize the report").await?;
+//! agent.execute("Now list the open questions").await?;
+//!
+//! let diff = ConversationDiff::between(&before, &agent.conversation().snapshot());
+//! println!("{}", diff.summary());
+//! for removed in &diff.removed {
+//!     println!("{:?}: {:?}", removed.reason, removed.message.text());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::context_manager::COMPACTION_SUMMARY_PREFIX;
+use crate::types::{ContentBlock, Message};
+
+/// The messages and system prompt of a conversation at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSnapshot {
+    /// Messages in chronological order
+    pub messages: Vec<Message>,
+    /// System prompt at the time of the snapshot
+    pub system_prompt: Option<String>,
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+}
+
+impl ConversationSnapshot {
+    /// Snapshot the given messages and system prompt
+    pub fn new(messages: Vec<Message>, system_prompt: Option<String>) -> Self {
+        Self {
+            messages,
+            system_prompt,
+            taken_at: Utc::now(),
+        }
+    }
+}
+
+/// Why a message is no longer in the conversation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovalReason {
+    /// Replaced by a compaction summary
+    Summarized,
+    /// Removed without a summary, e.g. by the conversation window
+    Dropped,
+}
+
+/// A message that was removed between the two snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovedMessage {
+    /// The message as it was in the earlier snapshot
+    pub message: Message,
+    /// Why it was removed
+    pub reason: RemovalReason,
+}
+
+/// How a message present in both snapshots changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MessageChange {
+    /// A compaction summary of earlier messages was prepended
+    Summarized {
+        /// The summary, without the summary marker
+        summary: String,
+    },
+    /// Text or tool output was shortened
+    Truncated {
+        /// Characters of content before the change
+        chars_before: usize,
+        /// Characters of content after the change
+        chars_after: usize,
+    },
+    /// Content blocks were otherwise changed, e.g. tool blocks removed
+    Modified,
+}
+
+/// A message present in both snapshots with different content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedMessage {
+    /// The message in the earlier snapshot
+    pub before: Message,
+    /// The message in the later snapshot
+    pub after: Message,
+    /// What changed
+    pub change: MessageChange,
+}
+
+/// Differences between two conversation snapshots
+///
+/// Messages are matched by their id, so a message is only reported as added
+/// or removed if it is absent from the other snapshot.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationDiff {
+    /// Messages only in the later snapshot, in order
+    pub added: Vec<Message>,
+    /// Messages only in the earlier snapshot, in order
+    pub removed: Vec<RemovedMessage>,
+    /// Messages in both snapshots whose content changed, in order
+    pub changed: Vec<ChangedMessage>,
+    /// Messages in both snapshots with identical content
+    pub unchanged: usize,
+    /// Whether the system prompt differs between the snapshots
+    pub system_prompt_changed: bool,
+}
+
+impl ConversationDiff {
+    /// Compute the changes from `before` to `after`
+    pub fn between(before: &ConversationSnapshot, after: &ConversationSnapshot) -> Self {
+        let after_by_id: HashMap<Uuid, &Message> =
+            after.messages.iter().map(|m| (m.id, m)).collect();
+        let before_ids: HashSet<Uuid> = before.messages.iter().map(|m| m.id).collect();
+
+        let mut diff = ConversationDiff {
+            system_prompt_changed: before.system_prompt != after.system_prompt,
+            ..Default::default()
+        };
+
+        for message in &before.messages {
+            match after_by_id.get(&message.id) {
+                Some(later) if later.content == message.content => diff.unchanged += 1,
+                Some(later) => diff.changed.push(ChangedMessage {
+                    before: message.clone(),
+                    after: (*later).clone(),
+                    change: classify_change(message, later),
+                }),
+                None => diff.removed.push(RemovedMessage {
+                    message: message.clone(),
+                    reason: RemovalReason::Dropped,
+                }),
+            }
+        }
+
+        // Removed messages were summarized if a new summary appeared alongside them
+        let summary_added = diff
+            .changed
+            .iter()
+            .any(|c| matches!(c.change, MessageChange::Summarized { .. }))
+            || after
+                .messages
+                .iter()
+                .any(|m| !before_ids.contains(&m.id) && compaction_summary(m).is_some());
+        if summary_added {
+            for removed in &mut diff.removed {
+                removed.reason = RemovalReason::Summarized;
+            }
+        }
+
+        diff.added = after
+            .messages
+            .iter()
+            .filter(|m| !before_ids.contains(&m.id))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Whether the snapshots are identical
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && !self.system_prompt_changed
+    }
+
+    /// Messages removed for the given reason
+    pub fn removed_by(&self, reason: RemovalReason) -> impl Iterator<Item = &Message> {
+        self.removed
+            .iter()
+            .filter(move |r| r.reason == reason)
+            .map(|r| &r.message)
+    }
+
+    /// One-line description of the changes
+    pub fn summary(&self) -> String {
+        let count = |reason| self.removed_by(reason).count();
+        let truncated = self
+            .changed
+            .iter()
+            .filter(|c| matches!(c.change, MessageChange::Truncated { .. }))
+            .count();
+        let mut summary = format!(
+            "{} added, {} summarized, {} dropped, {} truncated, {} otherwise modified, {} unchanged",
+            self.added.len(),
+            count(RemovalReason::Summarized),
+            count(RemovalReason::Dropped),
+            truncated,
+            self.changed.len() - truncated,
+            self.unchanged
+        );
+        if self.system_prompt_changed {
+            summary.push_str(", system prompt changed");
+        }
+        summary
+    }
+}
+
+/// The compaction summary at the start of `message`, if any
+fn compaction_summary(message: &Message) -> Option<&str> {
+    match message.content.first() {
+        Some(ContentBlock::Text { text }) => {
+            text.strip_prefix(COMPACTION_SUMMARY_PREFIX).map(str::trim)
+        }
+        _ => None,
+    }
+}
+
+fn classify_change(before: &Message, after: &Message) -> MessageChange {
+    if compaction_summary(before).is_none() {
+        if let Some(summary) = compaction_summary(after) {
+            return MessageChange::Summarized {
+                summary: summary.to_string(),
+            };
+        }
+    }
+
+    let chars_before = content_chars(before);
+    let chars_after = content_chars(after);
+    let same_shape = before.content.len() == after.content.len()
+        && before
+            .content
+            .iter()
+            .zip(&after.content)
+            .all(|(a, b)| std::mem::discriminant(a) == std::mem::discriminant(b));
+    if same_shape && chars_after < chars_before {
+        MessageChange::Truncated {
+            chars_before,
+            chars_after,
+        }
+    } else {
+        MessageChange::Modified
+    }
+}
+
+/// Characters of text, tool input and tool output in a message
+fn content_chars(message: &Message) -> usize {
+    message
+        .content
+        .iter()
+        .map(|block| match block {
+            ContentBlock::Text { text } => text.chars().count(),
+            ContentBlock::ToolUse { input, .. } => input.to_string().chars().count(),
+            ContentBlock::ToolResult { content, .. } => content.to_display_string().chars().count(),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_manager::ContextManager;
+    use crate::types::Messages;
+
+    #[test]
+    fn test_diff_reports_compaction_and_new_messages() {
+        let mut messages = Messages::new();
+        for i in 0..3 {
+            messages.add_user_message(&format!("question {}", i));
+            messages.add_assistant_message(&format!("answer {}", i));
+        }
+        let before = ConversationSnapshot::new(messages.messages.clone(), None);
+
+        ContextManager::default().apply_compaction(&mut messages, 4, "Questions 0 and 1 answered.");
+        messages.add_user_message("question 3");
+        let after = ConversationSnapshot::new(messages.messages.clone(), Some("Be brief".into()));
+
+        let diff = ConversationDiff::between(&before, &after);
+        assert_eq!(diff.removed_by(RemovalReason::Summarized).count(), 4);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(
+            diff.changed[0].change,
+            MessageChange::Summarized {
+                summary: "Questions 0 and 1 answered.".to_string()
+            }
+        );
+        assert!(diff.system_prompt_changed);
+        assert_eq!(
+            diff.summary(),
+            "1 added, 4 summarized, 0 dropped, 0 truncated, 1 otherwise modified, 1 unchanged, \
+             system prompt changed"
+        );
+    }
+
+    #[test]
+    fn test_diff_reports_truncation_and_drops() {
+        let first = Message::user("a long question about the report");
+        let second = Message::assistant("an answer");
+        let before = ConversationSnapshot::new(vec![first.clone(), second.clone()], None);
+
+        let mut shortened = second.clone();
+        shortened.content = vec![ContentBlock::text("an")];
+        let after = ConversationSnapshot::new(vec![shortened], None);
+
+        let diff = ConversationDiff::between(&before, &after);
+        assert_eq!(diff.removed[0].reason, RemovalReason::Dropped);
+        assert_eq!(diff.removed[0].message.id, first.id);
+        assert_eq!(
+            diff.changed[0].change,
+            MessageChange::Truncated {
+                chars_before: 9,
+                chars_after: 2
+            }
+        );
+        assert!(ConversationDiff::between(&before, &before).is_empty());
+    }
+}
@@ -0,0 +1,353 @@
+//! Deadlines for agent executions.
+//!
+//! [`max_duration`](crate::agent::EventLoopConfig::max_duration) and the
+//! [phase timeouts](crate::agent::phase_timeouts) bound each execution the same
+//! way. A [`Deadline`] set with [`ExecuteOptions::with_deadline`] bounds one
+//! request, such as an HTTP handler with ten seconds left to answer, and is
+//! propagated to everything the execution waits on:
+//!
+//! - model calls and the other [phases](crate::agent::ExecutionPhase) time out
+//!   when the deadline passes, even if their own timeout is longer
+//! - tool calls time out at the deadline if that is before the executor's
+//!   [`execution_timeout`](crate::tools::ExecutorConfig::execution_timeout)
+//! - retries at every layer are refused when their backoff would end after the
+//!   deadline, so a request with 10s left never waits out a 30s backoff; they
+//!   are counted in the execution's
+//!   [`RetrySummary::deadline_refusals`](crate::error_recovery::RetrySummary::deadline_refusals)
+//! - no cycle starts once the deadline has passed
+//!
+//! An execution stopped by its deadline fails with
+//! [`StoodError::DeadlineExceeded`], which carries the partial result: the
+//! responses, tool calls and token usage of the cycles that did run, and the
+//! messages they added in `execution.conversation_diff`. As with other errors,
+//! the agent's conversation is left as it was before the execution.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use stood::agent::{Agent, Deadline, ExecuteOptions};
+//! use stood::StoodError;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut agent = Agent::builder().build().await?;
+//! let options = ExecuteOptions::new().with_deadline(Deadline::after(Duration::from_secs(10)));
+//! match agent.execute_with_options("Summarize the open incidents", options).await {
+//!     Ok(result) => println!("{}", result.winner().response),
+//!     Err(StoodError::DeadlineExceeded { partial }) => {
+//!         println!("Out of time after {} cycles: {}", partial.execution.cycles, partial.response)
+//!     }
+//!     Err(e) => return Err(e.into()),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Code running within an execution can read its deadline with
+//! [`Deadline::current`]. Tasks spawned by a tool do not inherit it.
+//!
+//! [`ExecuteOptions::with_deadline`]: crate::agent::ExecuteOptions::with_deadline
+//! [`StoodError::DeadlineExceeded`]: crate::StoodError::DeadlineExceeded
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::error_recovery::RetryHistory;
+
+tokio::task_local! {
+    static CURRENT_DEADLINE: Deadline;
+}
+
+/// Point in time by which an execution must finish
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Deadline {
+    at: Instant,
+}
+
+impl Deadline {
+    /// A deadline at `instant`
+    pub fn at(instant: Instant) -> Self {
+        Self { at: instant }
+    }
+
+    /// A deadline `timeout` from now
+    pub fn after(timeout: Duration) -> Self {
+        Self::at(Instant::now() + timeout)
+    }
+
+    /// When the deadline passes
+    pub fn instant(&self) -> Instant {
+        self.at
+    }
+
+    /// Time left until the deadline, zero once it has passed
+    pub fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the deadline has passed
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.at
+    }
+
+    /// `timeout`, shortened to the time left if the deadline comes first
+    pub fn cap(&self, timeout: Duration) -> Duration {
+        timeout.min(self.remaining())
+    }
+
+    /// Whether waiting `delay` still leaves time before the deadline
+    pub fn allows_wait(&self, delay: Duration) -> bool {
+        self.remaining() > delay
+    }
+
+    /// Run `future` with this deadline as the [`current`](Self::current) one
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_DEADLINE.scope(self, future).await
+    }
+
+    /// The deadline of the enclosing [`scope`](Self::scope), if any
+    pub fn current() -> Option<Self> {
+        CURRENT_DEADLINE.try_with(|deadline| *deadline).ok()
+    }
+
+    /// Whether the current deadline, if any, leaves time for a retry after
+    /// waiting `delay`
+    ///
+    /// A refused retry is recorded in the current [`RetryHistory`], which tells
+    /// the event loop that the execution failed for lack of time.
+    pub(crate) fn allows_current_retry(delay: Duration) -> bool {
+        let allowed = Self::current().is_none_or(|deadline| deadline.allows_wait(delay));
+        if !allowed {
+            RetryHistory::record_current_deadline_refusal();
+        }
+        allowed
+    }
+
+    /// `timeout` capped by the current deadline, if any
+    pub(crate) fn cap_current(timeout: Duration) -> Duration {
+        Self::current().map_or(timeout, |deadline| deadline.cap(timeout))
+    }
+
+    /// Run `future` under `deadline` when there is one
+    pub(crate) async fn scope_opt<F: Future>(deadline: Option<Self>, future: F) -> F::Output {
+        match deadline {
+            Some(deadline) => deadline.scope(future).await,
+            None => future.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::config::ExecutionConfig;
+    use crate::agent::{Agent, AgentConfig, ExecuteOptions};
+    use crate::llm::providers::retry::{retry_llm_operation, RetryConfig};
+    use crate::llm::traits::{
+        ChatConfig, ChatResponse, HealthStatus, LlmError, LlmProvider, ProviderCapabilities,
+        ProviderType, StreamEvent, Tool, ToolCall,
+    };
+    use crate::types::Messages;
+    use crate::StoodError;
+    use async_trait::async_trait;
+    use futures::Stream;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Provider whose first call asks for the calculator and whose later calls
+    /// hang, or that is rate limited with a 30s backoff if `throttled`
+    #[derive(Debug)]
+    struct SlowProvider {
+        throttled: bool,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LlmProvider for SlowProvider {
+        async fn chat(
+            &self,
+            _model_id: &str,
+            _messages: &Messages,
+            _config: &ChatConfig,
+        ) -> Result<ChatResponse, LlmError> {
+            if self.throttled {
+                let config = RetryConfig {
+                    max_attempts: 3,
+                    initial_delay: Duration::from_secs(30),
+                    max_delay: Duration::from_secs(60),
+                    backoff_multiplier: 2.0,
+                    jitter: false,
+                };
+                return retry_llm_operation(
+                    || {
+                        Box::pin(async {
+                            Err(LlmError::RateLimitError {
+                                provider: ProviderType::LmStudio,
+                                retry_after: None,
+                            })
+                        })
+                    },
+                    &config,
+                )
+                .await;
+            }
+
+            let mut tool_calls = Vec::new();
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                tool_calls.push(ToolCall {
+                    id: "call-1".to_string(),
+                    name: "calculator".to_string(),
+                    input: serde_json::json!({ "expression": "2+2" }),
+                });
+            } else {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            Ok(ChatResponse {
+                content: String::new(),
+                tool_calls,
+                thinking: None,
+                usage: None,
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn chat_with_tools(
+            &self,
+            model_id: &str,
+            messages: &Messages,
+            _tools: &[Tool],
+            config: &ChatConfig,
+        ) -> Result<ChatResponse, LlmError> {
+            self.chat(model_id, messages, config).await
+        }
+
+        async fn chat_streaming(
+            &self,
+            _model_id: &str,
+            _messages: &Messages,
+            _config: &ChatConfig,
+        ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+            Err(LlmError::UnsupportedFeature {
+                feature: "streaming".to_string(),
+                provider: ProviderType::LmStudio,
+            })
+        }
+
+        async fn chat_streaming_with_tools(
+            &self,
+            model_id: &str,
+            messages: &Messages,
+            _tools: &[Tool],
+            config: &ChatConfig,
+        ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+            self.chat_streaming(model_id, messages, config).await
+        }
+
+        async fn health_check(&self) -> Result<HealthStatus, LlmError> {
+            unimplemented!()
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                supports_streaming: false,
+                supports_tools: true,
+                supports_thinking: false,
+                supports_vision: false,
+                supports_prompt_caching: false,
+                supports_tool_caching: false,
+                max_tokens: None,
+                available_models: Vec::new(),
+            }
+        }
+
+        fn provider_type(&self) -> ProviderType {
+            ProviderType::LmStudio
+        }
+
+        fn supported_models(&self) -> Vec<&'static str> {
+            Vec::new()
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    async fn agent(throttled: bool) -> Agent {
+        Agent::build_internal(
+            Arc::new(SlowProvider {
+                throttled,
+                calls: AtomicUsize::new(0),
+            }),
+            Box::new(crate::llm::models::Bedrock::ClaudeHaiku45),
+            AgentConfig::default(),
+            vec![Box::new(crate::tools::builtin::CalculatorTool::new())],
+            vec![],
+            ExecutionConfig {
+                streaming: false,
+                ..ExecutionConfig::default()
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn execute_within(agent: &mut Agent, timeout: Duration) -> crate::agent::AgentResult {
+        let options = ExecuteOptions::new().with_deadline(Deadline::after(timeout));
+        match agent.execute_with_options("What is 2+2?", options).await {
+            Err(StoodError::DeadlineExceeded { partial }) => *partial,
+            other => panic!("expected DeadlineExceeded, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deadline_caps_and_scopes() {
+        let deadline = Deadline::after(Duration::from_secs(10));
+        assert!(!deadline.is_expired());
+        assert!(deadline.cap(Duration::from_secs(30)) <= Duration::from_secs(10));
+        assert_eq!(deadline.cap(Duration::from_secs(1)), Duration::from_secs(1));
+        assert!(deadline.allows_wait(Duration::from_secs(1)));
+        assert!(!deadline.allows_wait(Duration::from_secs(30)));
+
+        assert!(Deadline::current().is_none());
+        assert!(Deadline::allows_current_retry(Duration::from_secs(30)));
+        deadline
+            .scope(async {
+                assert_eq!(Deadline::current(), Some(deadline));
+                assert!(!Deadline::allows_current_retry(Duration::from_secs(30)));
+                assert!(Deadline::cap_current(Duration::from_secs(30)) <= Duration::from_secs(10));
+            })
+            .await;
+
+        let passed = Deadline::at(Instant::now());
+        assert!(passed.is_expired());
+        assert_eq!(passed.remaining(), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_stops_slow_model_call_with_partial_result() {
+        let mut agent = agent(false).await;
+        let started = Instant::now();
+        let partial = execute_within(&mut agent, Duration::from_millis(500)).await;
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!partial.success);
+        assert_eq!(partial.tools_called, vec!["calculator".to_string()]);
+        assert_eq!(partial.tools_successful, vec!["calculator".to_string()]);
+        assert!(partial.execution.conversation_diff.is_some());
+        assert_eq!(agent.conversation().message_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_refuses_backoff_past_it() {
+        let mut agent = agent(true).await;
+        let started = Instant::now();
+        let partial = execute_within(&mut agent, Duration::from_secs(10)).await;
+
+        // Fails at once instead of waiting out the 30s backoff
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(partial.execution.retries.deadline_refusals, 1);
+        assert!(partial.error.is_some());
+    }
+}
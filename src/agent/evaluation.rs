@@ -1,5 +1,14 @@
 use crate::agent::Agent;
+use crate::context_manager::{truncate_text, TruncationStrategy};
+use crate::types::{ContentBlock, Message, MessageRole, ToolResultContent};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Marker of evaluation prompts, which are left out of evaluator transcripts
+const EVALUATION_ARTIFACT_MARKER: &str = "[INTERNAL EVALUATION";
+
+/// Maximum bytes of a tool input or result in a [`TranscriptView::ToolsDigest`]
+const DIGEST_ENTRY_BYTES: usize = 200;
 
 /// Configuration for a single perspective in multi-perspective evaluation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,6 +94,176 @@ impl EvaluationStrategy {
     }
 }
 
+/// How much of the conversation evaluators see
+///
+/// Evaluation calls run in an isolated context, but by default they still get
+/// the whole conversation, so their token cost grows with every cycle. Smaller
+/// views keep it roughly constant. Every view starts with the original
+/// request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptView {
+    /// All user and assistant text and tool results
+    #[default]
+    Full,
+    /// The original request and the last `n` turns, each starting at a user
+    /// prompt
+    LastTurns(usize),
+    /// The original request and counts of the turns and tool calls since
+    SummaryOnly,
+    /// The original request and one line per tool call with its outcome
+    ToolsDigest,
+}
+
+impl TranscriptView {
+    /// Render `messages` for an evaluation prompt
+    pub fn render(&self, messages: &[Message]) -> String {
+        let messages: Vec<&Message> = messages
+            .iter()
+            .filter(|message| {
+                !message
+                    .text()
+                    .is_some_and(|text| text.contains(EVALUATION_ARTIFACT_MARKER))
+            })
+            .collect();
+        let request = messages
+            .iter()
+            .find(|message| is_prompt(message))
+            .and_then(|message| message.text());
+
+        match self {
+            Self::Full => render_full(&messages),
+            Self::LastTurns(n) => {
+                let starts: Vec<usize> = (0..messages.len())
+                    .filter(|&i| is_prompt(messages[i]))
+                    .collect();
+                let first_kept = starts.len().saturating_sub((*n).max(1));
+                let start = starts.get(first_kept).copied().unwrap_or(0);
+                let recent = render_full(&messages[start..]);
+                match request {
+                    Some(request) if first_kept > 1 => format!(
+                        "User: {}\n[{} earlier turns omitted]\n{}",
+                        request,
+                        first_kept - 1,
+                        recent
+                    ),
+                    Some(request) if first_kept == 1 => format!("User: {}\n{}", request, recent),
+                    _ => recent,
+                }
+            }
+            Self::SummaryOnly => {
+                let turns = messages.iter().filter(|m| is_prompt(m)).count();
+                let tool_calls = tool_calls(&messages).count();
+                format!(
+                    "User: {}\n[{} turns and {} tool calls so far]",
+                    request.unwrap_or_default(),
+                    turns,
+                    tool_calls
+                )
+            }
+            Self::ToolsDigest => {
+                let results: HashMap<&str, (&ToolResultContent, bool)> = messages
+                    .iter()
+                    .flat_map(|message| &message.content)
+                    .filter_map(|block| match block {
+                        ContentBlock::ToolResult {
+                            tool_use_id,
+                            content,
+                            is_error,
+                        } => Some((tool_use_id.as_str(), (content, *is_error))),
+                        _ => None,
+                    })
+                    .collect();
+                let mut lines = vec![format!("User: {}", request.unwrap_or_default())];
+                for (id, name, input) in tool_calls(&messages) {
+                    let outcome = match results.get(id) {
+                        Some((content, true)) => {
+                            format!("error: {}", digest(&result_text(content)))
+                        }
+                        Some((content, false)) => format!("ok: {}", digest(&result_text(content))),
+                        None => "no result".to_string(),
+                    };
+                    lines.push(format!(
+                        "Tool {}({}) -> {}",
+                        name,
+                        digest(&input.to_string()),
+                        outcome
+                    ));
+                }
+                lines.join("\n")
+            }
+        }
+    }
+}
+
+/// Whether `message` is a user prompt rather than tool results
+fn is_prompt(message: &Message) -> bool {
+    message.role == MessageRole::User
+        && message
+            .content
+            .iter()
+            .any(|block| matches!(block, ContentBlock::Text { .. }))
+}
+
+fn tool_calls<'a>(
+    messages: &'a [&'a Message],
+) -> impl Iterator<Item = (&'a str, &'a str, &'a serde_json::Value)> {
+    messages
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { id, name, input } => Some((id.as_str(), name.as_str(), input)),
+            _ => None,
+        })
+}
+
+fn render_full(messages: &[&Message]) -> String {
+    let mut parts = Vec::new();
+    for message in messages {
+        if message.role == MessageRole::System {
+            continue;
+        }
+        if let Some(text) = message.text() {
+            if !text.trim().is_empty() {
+                let role = match message.role {
+                    MessageRole::User => "User",
+                    _ => "Assistant",
+                };
+                parts.push(format!("{}: {}", role, text));
+            }
+        }
+        for block in &message.content {
+            if let ContentBlock::ToolResult { content, .. } = block {
+                if let Some(text) = content.as_text() {
+                    parts.push(format!("Tool result: {}", text));
+                } else if let Some(data) = content.as_json() {
+                    parts.push(format!("Tool result: {}", data));
+                }
+            }
+        }
+    }
+    parts.join("\n")
+}
+
+fn result_text(content: &ToolResultContent) -> String {
+    match content {
+        ToolResultContent::Text { text } => text.clone(),
+        ToolResultContent::Json { data } => data.to_string(),
+        ToolResultContent::Binary { mime_type, .. } => format!("<{}>", mime_type),
+        ToolResultContent::Multiple { blocks } => {
+            blocks.iter().map(result_text).collect::<Vec<_>>().join(" ")
+        }
+    }
+}
+
+fn digest(text: &str) -> String {
+    truncate_text(
+        &text.replace('\n', " "),
+        DIGEST_ENTRY_BYTES,
+        TruncationStrategy::Head,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,6 +293,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_transcript_views() {
+        let messages = vec![
+            Message::user("Find flights to Lisbon"),
+            Message::new(
+                MessageRole::Assistant,
+                vec![ContentBlock::tool_use(
+                    "t1",
+                    "search_flights",
+                    serde_json::json!({"to": "LIS"}),
+                )],
+            ),
+            Message::new(
+                MessageRole::User,
+                vec![ContentBlock::tool_result_success(
+                    "t1",
+                    ToolResultContent::text("3 flights found"),
+                )],
+            ),
+            Message::assistant("There are 3 flights."),
+            Message::user("[INTERNAL EVALUATION] Are you done?"),
+            Message::user("Book the cheapest"),
+            Message::assistant("Booked."),
+        ];
+
+        let full = TranscriptView::Full.render(&messages);
+        assert!(full.contains("Tool result: 3 flights found"));
+        assert!(!full.contains("INTERNAL EVALUATION"));
+
+        let last = TranscriptView::LastTurns(1).render(&messages);
+        assert_eq!(
+            last,
+            "User: Find flights to Lisbon\nUser: Book the cheapest\nAssistant: Booked."
+        );
+
+        assert_eq!(
+            TranscriptView::SummaryOnly.render(&messages),
+            "User: Find flights to Lisbon\n[2 turns and 1 tool calls so far]"
+        );
+        assert_eq!(
+            TranscriptView::ToolsDigest.render(&messages),
+            "User: Find flights to Lisbon\n\
+             Tool search_flights({\"to\":\"LIS\"}) -> ok: 3 flights found"
+        );
+    }
+
     #[test]
     fn test_perspective_config() {
         let perspective = PerspectiveConfig {
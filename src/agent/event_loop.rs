@@ -11,20 +11,43 @@
 //! and comprehensive telemetry integration.
 
 use crate::llm::traits::LlmProvider;
-use crate::types::{ContentBlock, MessageRole};
+use crate::types::tools::ToolChoice;
 use chrono::Utc;
 use serde_json::Value;
 use std::time::{Duration, Instant};
 use tracing::debug;
 use uuid::Uuid;
 
+use crate::agent::anomaly::{Anomaly, BehaviorMonitor};
+use crate::agent::callbacks::events::{ProgressPhase, RawResponseData, ResponseType, SSEEvent};
+use crate::agent::callbacks::progress::{ProgressConfig, ProgressTicker};
 use crate::agent::callbacks::{CallbackEvent, CallbackHandler};
-use crate::agent::evaluation::EvaluationStrategy;
+use crate::agent::citations::{
+    extract_documents, parse_citations, Citation, SourceDocument, CITATION_INSTRUCTIONS,
+};
+use crate::agent::deadline::Deadline;
+use crate::agent::evaluation::{EvaluationStrategy, TranscriptView};
+use crate::agent::execution_trace::{ExecutionTrace, RecoveryAction, TraceEvent};
+use crate::agent::hooks::{CycleHook, CycleHookContext};
+use crate::agent::parameter_schedule::ParameterSchedule;
+use crate::agent::phase_timeouts::{ExecutionPhase, PhaseTimeouts};
+use crate::agent::state_machine::{AgenticStateMachine, StepOutcome};
+use crate::agent::stop::{StopCondition, StopContext, TerminationReason};
+use crate::agent::token_attribution::{RequestEstimate, TokenAttribution};
 use crate::agent::Agent;
-use crate::error_recovery::RetryConfig;
+use crate::context_manager::{CompactionConfig, CompactionResult};
+use crate::error_recovery::{
+    RetryBudget, RetryBudgetUsage, RetryConfig, RetryHistory, RetrySummary,
+};
 use crate::streaming::{StreamCallback, StreamConfig, StreamEvent};
-use crate::telemetry::{CycleMetrics, EventLoopMetrics, PerformanceTracer, ToolExecutionMetric};
-use crate::tools::{ExecutorConfig, ToolExecutor, ToolRegistry};
+use crate::telemetry::{
+    CycleMetrics, EventLoopMetrics, ModelCallMetric, PerformanceTracer, ToolExecutionMetric,
+};
+use crate::tools::interim::finished_update;
+use crate::tools::{
+    CallbackElicitor, ExecutorConfig, InterimResultConfig, PartialOutput, ProgressReporter,
+    ToolCancelReason, ToolExecutor, ToolRegistry,
+};
 use crate::Result;
 use std::sync::Arc;
 
@@ -57,12 +80,61 @@ pub struct EventLoopConfig {
     pub stream_config: StreamConfig,
     /// Error recovery configuration
     pub retry_config: RetryConfig,
+    /// Limit on retries across all retry layers, renewed for each execution
+    pub retry_budget: Option<RetryBudget>,
     /// Evaluation strategy for determining continuation
     pub evaluation_strategy: EvaluationStrategy,
     /// Maximum number of tool iterations per cycle
     pub max_tool_iterations: u32,
+    /// Maximum input + output tokens for the entire event loop
+    pub max_total_tokens: Option<u32>,
+    /// Custom conditions checked after each cycle that would otherwise continue
+    pub stop_conditions: Vec<Arc<dyn StopCondition>>,
     /// Cancellation token for early termination
     pub cancellation_token: Option<tokio_util::sync::CancellationToken>,
+    /// Hooks run around model calls and tool batches with access to the conversation
+    pub cycle_hooks: Vec<Arc<dyn CycleHook>>,
+    /// Conversation compaction, triggered automatically or by the `compact_context` tool
+    pub compaction: Option<CompactionConfig>,
+    /// Tool choice for the first model call of each execution (the model
+    /// decides when unset)
+    ///
+    /// `ToolChoice::Tool` forces a specific tool, e.g. to always start with a
+    /// planning tool. Later model calls always let the model decide.
+    pub first_call_tool_choice: Option<ToolChoice>,
+    /// Start tool calls as soon as their streamed input is complete, while the
+    /// model is still streaming the rest of its turn
+    ///
+    /// Only applies to streaming responses and is skipped when cycle hooks are
+    /// configured, since they must see the tool batch before it runs. A tool
+    /// started early still runs if the model's response later fails.
+    pub early_tool_start: bool,
+    /// Ask the model to cite documents returned by tools and extract the
+    /// citations from the response
+    pub citations: bool,
+    /// Record every model request, tool execution, evaluation verdict and
+    /// recovery action in an [`ExecutionTrace`]
+    pub execution_trace: bool,
+    /// Keep the raw provider payload of every model call (response JSON or
+    /// stream chunks) for debugging and custom parsing
+    pub capture_raw_responses: bool,
+    /// Emit periodic [`CallbackEvent::Progress`] events with tokens and cost so far
+    pub progress: Option<ProgressConfig>,
+    /// Temperature and other inference parameters to use for specific cycles
+    pub parameter_schedule: Option<ParameterSchedule>,
+    /// Answer tools still running after a deadline with their partial output
+    /// instead of waiting for them before the next model call
+    pub interim_tool_results: Option<InterimResultConfig>,
+    /// How much of the conversation evaluation calls see
+    pub evaluation_transcript: TranscriptView,
+    /// System prompt for evaluation calls, separate from the agent's (which
+    /// evaluators use when unset)
+    pub evaluator_system_prompt: Option<String>,
+    /// Timeouts for the reasoning, tool batch, evaluation and synthesis
+    /// phases of each cycle, on top of `max_duration`
+    pub phase_timeouts: PhaseTimeouts,
+    /// Watches model responses for runaway loops and other anomalous behavior
+    pub behavior_monitor: Option<BehaviorMonitor>,
 }
 
 impl Default for EventLoopConfig {
@@ -75,9 +147,26 @@ impl Default for EventLoopConfig {
             enable_telemetry: true,
             stream_config: StreamConfig::default(),
             retry_config: RetryConfig::default(),
+            retry_budget: None,
             evaluation_strategy: EvaluationStrategy::default(),
             max_tool_iterations: 7, // Default conservative limit
+            max_total_tokens: None,
             cancellation_token: None,
+            cycle_hooks: Vec::new(),
+            stop_conditions: Vec::new(),
+            compaction: None,
+            first_call_tool_choice: None,
+            early_tool_start: false,
+            citations: false,
+            execution_trace: false,
+            capture_raw_responses: false,
+            progress: None,
+            parameter_schedule: None,
+            interim_tool_results: None,
+            evaluation_transcript: TranscriptView::default(),
+            evaluator_system_prompt: None,
+            phase_timeouts: PhaseTimeouts::default(),
+            behavior_monitor: None,
         }
     }
 }
@@ -101,6 +190,20 @@ pub struct EventLoopResult {
     pub was_streamed: bool,
     /// Stream events collected during execution
     pub stream_events: Vec<StreamEvent>,
+    /// Why the loop stopped
+    pub termination_reason: TerminationReason,
+    /// Token usage broken down by request part, cycle and tool
+    pub token_attribution: TokenAttribution,
+    /// Citations of tool-provided documents in the response
+    pub citations: Vec<Citation>,
+    /// Recorded decisions of the execution, if tracing is enabled
+    pub execution_trace: Option<ExecutionTrace>,
+    /// Retries consumed from the retry budget, if one applies
+    pub retry_budget: Option<RetryBudgetUsage>,
+    /// Failed attempts and circuit breaker trips across retry layers
+    pub retries: RetrySummary,
+    /// Raw provider payloads of the model calls, if capture is enabled
+    pub raw_responses: Vec<RawResponseData>,
 }
 
 /// Isolated evaluation context to prevent conversation pollution
@@ -113,11 +216,16 @@ struct EvaluationContext {
 
 impl EvaluationContext {
     /// Create a new evaluation context using the same provider as the main agent
-    fn new(agent: &Agent) -> Self {
+    ///
+    /// The evaluator uses `system_prompt` if set, and the agent's system prompt
+    /// otherwise.
+    fn new(agent: &Agent, system_prompt: Option<&str>) -> Self {
         Self {
             provider: agent.provider().clone(),
             model_id: agent.config().model_id.clone(),
-            system_prompt: agent.conversation().system_prompt().map(|s| s.to_string()),
+            system_prompt: system_prompt
+                .or_else(|| agent.conversation().system_prompt())
+                .map(|s| s.to_string()),
         }
     }
 
@@ -160,6 +268,7 @@ pub struct EventLoop {
 
     // Streaming completion tracking
     stream_completion_time: Option<std::time::Instant>,
+    first_token_time: Option<std::time::Instant>,
     stream_was_active: bool,
 
     // Isolated evaluation context
@@ -169,6 +278,97 @@ pub struct EventLoop {
     // When cancellation occurs mid-execution, we need to add synthetic
     // tool_results for any pending tool_uses to keep conversation valid
     pending_tool_uses: Vec<crate::tools::ToolUse>,
+
+    // Number of model calls made during the current execution, passed to cycle hooks
+    model_call_count: u32,
+
+    // Tokens recorded in metrics before the current execution started
+    tokens_at_start: u32,
+    // Set when a limit is hit inside a cycle's tool loop
+    cycle_termination: Option<TerminationReason>,
+
+    // Cycle currently executing (1-based) and token attribution for this execution
+    current_cycle: u32,
+    token_attribution: TokenAttribution,
+
+    // Calls per tool in this execution, for its tool call limits
+    tool_call_counts: std::collections::HashMap<String, u32>,
+
+    // Tool calls started while the model response was still streaming, by tool use id
+    early_tool_executions: std::collections::HashMap<String, EarlyToolExecution>,
+
+    // Tool calls still running after the interim result deadline, by tool use id,
+    // and the updates on them for the next tool result message
+    background_tools: std::collections::HashMap<String, BackgroundToolExecution>,
+    background_updates: Vec<String>,
+
+    // Citable documents returned by tools during this execution
+    citation_sources: Vec<SourceDocument>,
+
+    // Decisions recorded during this execution, if tracing is enabled
+    execution_trace: Option<ExecutionTrace>,
+
+    // Raw provider payloads of this execution's model calls, if captured
+    raw_responses: Vec<RawResponseData>,
+
+    // Tokens and cost of this execution for progress events, if enabled
+    progress: Option<ProgressTicker>,
+
+    // Text the assistant's first response of the next execution starts with
+    assistant_prefill: Option<String>,
+    // Overrides the configured tool choice of the first model call
+    tool_choice_override: Option<ToolChoice>,
+    // Time by which the next execution must finish
+    deadline: Option<Deadline>,
+}
+
+/// Handle to a tool call running as a spawned task, returning its result and duration
+type SpawnedToolHandle = crate::runtime::JoinHandle<(
+    std::result::Result<crate::tools::ToolResult, crate::tools::ToolError>,
+    Duration,
+)>;
+
+/// A tool call started before the model finished streaming its response
+struct EarlyToolExecution {
+    input: Value,
+    handle: SpawnedToolHandle,
+}
+
+/// A tool call that got an interim result and keeps running
+struct BackgroundToolExecution {
+    tool_use: crate::tools::ToolUse,
+    partial_output: PartialOutput,
+    started: Instant,
+    handle: SpawnedToolHandle,
+}
+
+/// State of an execution between the steps of an [`AgenticStateMachine`]
+pub(crate) struct ExecutionRun {
+    prompt: String,
+    started: Instant,
+    span: Option<crate::telemetry::StoodSpan>,
+    guard: crate::telemetry::OperationGuard,
+    cycles_executed: u32,
+    // Responses of all cycles, joined into the final response
+    responses: Vec<String>,
+    error: Option<String>,
+    // Set once a cycle has stopped the execution
+    termination_reason: Option<TerminationReason>,
+    // Conversation length before the prompt was added
+    first_message: usize,
+    pub(crate) retry_history: RetryHistory,
+    pub(crate) retry_budget: Option<RetryBudget>,
+    pub(crate) deadline: Option<Deadline>,
+}
+
+impl ExecutionRun {
+    pub(crate) fn cycles_executed(&self) -> u32 {
+        self.cycles_executed
+    }
+
+    pub(crate) fn termination_reason(&self) -> Option<&TerminationReason> {
+        self.termination_reason.as_ref()
+    }
 }
 
 /// Span tracking information for telemetry
@@ -226,7 +426,10 @@ impl EventLoop {
         config: EventLoopConfig,
         callback_handler: Option<Arc<dyn CallbackHandler>>,
     ) -> Result<Self> {
-        let tool_executor = ToolExecutor::new(config.tool_config.clone());
+        let mut tool_executor = ToolExecutor::new(config.tool_config.clone());
+        if let Some(token) = &config.cancellation_token {
+            tool_executor = tool_executor.with_cancellation_token(token.clone());
+        }
 
         let tracer = if config.enable_telemetry {
             // Use agent's telemetry config if available, otherwise fall back to env
@@ -252,10 +455,14 @@ impl EventLoop {
 
         // Create evaluation context if evaluation is enabled
         let evaluation_context = if config.evaluation_strategy.requires_evaluation() {
-            Some(EvaluationContext::new(&agent))
+            Some(EvaluationContext::new(
+                &agent,
+                config.evaluator_system_prompt.as_deref(),
+            ))
         } else {
             None
         };
+        let progress = config.progress.clone().map(ProgressTicker::new);
 
         Ok(Self {
             agent,
@@ -274,6 +481,7 @@ impl EventLoop {
 
             // Initialize streaming completion tracking
             stream_completion_time: None,
+            first_token_time: None,
             stream_was_active: false,
 
             // Initialize evaluation context
@@ -281,57 +489,94 @@ impl EventLoop {
 
             // Initialize pending tool uses tracking
             pending_tool_uses: Vec::new(),
+
+            model_call_count: 0,
+            tokens_at_start: 0,
+            cycle_termination: None,
+            current_cycle: 0,
+            token_attribution: TokenAttribution::default(),
+            tool_call_counts: std::collections::HashMap::new(),
+            early_tool_executions: std::collections::HashMap::new(),
+            background_tools: std::collections::HashMap::new(),
+            background_updates: Vec::new(),
+            citation_sources: Vec::new(),
+            execution_trace: None,
+            raw_responses: Vec::new(),
+            progress,
+            assistant_prefill: None,
+            tool_choice_override: None,
+            deadline: None,
         })
     }
 
+    /// Start the assistant's first response of the next execution with `prefill`
+    ///
+    /// The model continues from the prefill instead of starting a new message,
+    /// e.g. `{` to get a JSON object. The prefill is part of the response text
+    /// and of the assistant message added to the conversation. Some models
+    /// reject a prefill that ends with whitespace.
+    pub fn set_assistant_prefill(&mut self, prefill: Option<String>) {
+        self.assistant_prefill = prefill;
+    }
+
+    /// Use `tool_choice` for the first model call of each execution instead of
+    /// [`EventLoopConfig::first_call_tool_choice`]
+    pub fn set_first_call_tool_choice(&mut self, tool_choice: Option<ToolChoice>) {
+        self.tool_choice_override = tool_choice;
+    }
+
+    /// Stop each execution once `deadline` passes
+    ///
+    /// The deadline also caps phase and tool timeouts and refuses retries
+    /// whose backoff would outlast it; see [`crate::agent::deadline`].
+    pub fn set_deadline(&mut self, deadline: Option<Deadline>) {
+        self.deadline = deadline;
+    }
+
+    /// The deadline set with [`set_deadline`](Self::set_deadline)
+    pub fn deadline(&self) -> Option<Deadline> {
+        self.deadline
+    }
+
     /// Get a reference to the agent
     pub fn agent(&self) -> &Agent {
         &self.agent
     }
 
-    /// Create a clean conversation summary for evaluation (no tool blocks, no evaluation artifacts)
-    fn create_evaluation_summary(&self) -> String {
-        let mut summary_parts = Vec::new();
-
-        for message in &self.agent.conversation().messages().messages {
-            match message.role {
-                MessageRole::User => {
-                    if let Some(text) = message.text() {
-                        // Skip evaluation artifacts
-                        if !text.contains("[INTERNAL EVALUATION") {
-                            summary_parts.push(format!("User: {}", text));
-                        }
-                    }
-                }
-                MessageRole::Assistant => {
-                    if let Some(text) = message.text() {
-                        if !text.trim().is_empty() {
-                            summary_parts.push(format!("Assistant: {}", text));
-                        }
-                    }
-                    // Extract tool results only (skip tool use blocks)
-                    for block in &message.content {
-                        if let ContentBlock::ToolResult { content, .. } = block {
-                            if let crate::types::ToolResultContent::Text { text } = content {
-                                summary_parts.push(format!("Tool result: {}", text));
-                            } else if let crate::types::ToolResultContent::Json { data } = content {
-                                summary_parts.push(format!("Tool result: {}", data));
-                            }
-                        }
-                    }
-                }
-                MessageRole::System => {} // Skip system messages
-            }
-        }
+    /// Get the event loop configuration
+    pub fn config(&self) -> &EventLoopConfig {
+        &self.config
+    }
 
-        summary_parts.join("\n")
+    /// Create a clean conversation transcript for evaluation (no tool calls, no
+    /// evaluation artifacts), limited to the configured view
+    fn create_evaluation_summary(&self) -> String {
+        self.config
+            .evaluation_transcript
+            .render(&self.agent.conversation().messages().messages)
     }
 
     /// Execute the agentic loop for a given prompt
+    ///
+    /// Drives an [`AgenticStateMachine`] until the execution finishes; use one
+    /// directly to run the cycles yourself.
     pub async fn execute(&mut self, prompt: impl Into<String>) -> Result<EventLoopResult> {
+        let mut machine = AgenticStateMachine::new(self);
+        machine.start(prompt).await?;
+        loop {
+            if let StepOutcome::Finished(result) = machine.step().await? {
+                return Ok(*result);
+            }
+        }
+    }
+
+    /// Start executing `prompt`: add it to the conversation and notify callbacks
+    ///
+    /// Runs within the retry scopes of the execution, which it keeps for the
+    /// following steps.
+    pub(crate) async fn start_run(&mut self, prompt: String) -> ExecutionRun {
         crate::perf_checkpoint!("stood.event_loop.execute.start");
         let _execute_guard = crate::perf_guard!("stood.event_loop.execute");
-        let prompt = prompt.into();
 
         // Create telemetry span for the event loop
         let event_loop_span: Option<crate::telemetry::StoodSpan> = self.tracer.as_ref().map(|t| {
@@ -352,14 +597,30 @@ impl EventLoop {
         });
         let loop_start = Instant::now();
         let loop_id = Uuid::new_v4();
+        self.model_call_count = 0;
+        self.tokens_at_start = self.metrics.total_tokens.total_tokens;
+        self.cycle_termination = None;
+        self.token_attribution = TokenAttribution::default();
+        self.citation_sources.clear();
+        self.tool_call_counts.clear();
+        self.execution_trace = self.config.execution_trace.then(ExecutionTrace::new);
+        self.raw_responses.clear();
+        if let Some(progress) = &self.progress {
+            progress.reset();
+        }
+        // A tripped monitor stops the execution before the first model call
+        let tripped = self.config.behavior_monitor.as_ref().and_then(|monitor| {
+            monitor.start_execution();
+            monitor.tripped()
+        });
 
         debug!("🚀 EventLoop::execute() started with prompt: '{}'", prompt);
 
-        let _event_loop_guard = self
+        let event_loop_guard = self
             .performance_tracer
             .start_operation("event_loop_execution");
-        _event_loop_guard.add_context("loop_id", &loop_id.to_string());
-        _event_loop_guard.add_context("prompt_length", &prompt.len().to_string());
+        event_loop_guard.add_context("loop_id", &loop_id.to_string());
+        event_loop_guard.add_context("prompt_length", &prompt.len().to_string());
 
         tracing::info!("Starting agentic loop {} for prompt: {}", loop_id, prompt);
 
@@ -385,177 +646,266 @@ impl EventLoop {
             self.config.max_duration.as_secs(),
         );
 
-        // Store the original prompt for tool analysis
-        let original_prompt = prompt.clone();
-
         // Add initial user message to conversation
         debug!("💬 Adding user message to EventLoop conversation");
+        let first_message = self.agent.conversation().message_count();
         self.agent.add_user_message(&prompt);
         debug!(
             "💬 EventLoop conversation now has {} messages",
             self.agent.conversation().message_count()
         );
 
-        let mut model_interaction_count = 0;
-        // final_response will be set from all_responses.join() below
-        let mut all_responses = Vec::new(); // Collect all responses from all cycles
-        let mut loop_error = None;
+        ExecutionRun {
+            prompt,
+            started: loop_start,
+            span: event_loop_span,
+            guard: event_loop_guard,
+            cycles_executed: 0,
+            responses: Vec::new(),
+            error: None,
+            termination_reason: tripped
+                .map(|anomaly| TerminationReason::BehaviorAnomaly { anomaly }),
+            first_message,
+            retry_history: RetryHistory::current().unwrap_or_default(),
+            retry_budget: RetryBudget::current(),
+            deadline: Deadline::current(),
+        }
+    }
 
-        // Execute model interaction cycles until completion, limits, or cancellation
-        while model_interaction_count < self.config.max_cycles
-            && loop_start.elapsed() < self.config.max_duration
+    /// Whether `run` can execute another cycle, i.e. has not stopped and is
+    /// within its limits
+    pub(crate) fn can_continue(&self, run: &ExecutionRun) -> bool {
+        run.termination_reason.is_none()
+            && run.cycles_executed < self.config.max_cycles
+            && run.started.elapsed() < self.config.max_duration
+            && !run.deadline.is_some_and(|deadline| deadline.is_expired())
             && !self.is_cancelled()
-        {
-            let cycle_id = Uuid::new_v4();
+    }
 
-            // 📊 COMPREHENSIVE MODEL INTERACTION CYCLE LOGGING
-            tracing::info!(
-                "🔄 MODEL INTERACTION CYCLE {} of {} - Duration: {}ms",
-                model_interaction_count + 1,
-                self.config.max_cycles,
-                loop_start.elapsed().as_millis()
-            );
-            debug!(
-                "🔄 Model Interaction Cycle {} ID: {}, Conversation messages: {}",
-                model_interaction_count + 1,
-                cycle_id,
-                self.agent.conversation().message_count()
-            );
+    /// Execute the next model interaction cycle of `run`, returning its
+    /// response, or the error that failed it and stopped the execution
+    pub(crate) async fn run_cycle(&mut self, run: &mut ExecutionRun) -> Result<String> {
+        let model_interaction_count = run.cycles_executed;
+        let cycle_id = Uuid::new_v4();
+        let deadline_refusals = run.retry_history.deadline_refusals();
 
-            // Emit CycleStart callback (model interaction)
-            if let Some(ref callback) = self.callback_handler {
-                let event = CallbackEvent::CycleStart {
-                    cycle_id,
-                    cycle_number: model_interaction_count + 1,
-                };
-                if let Err(e) = callback.handle_event(event).await {
-                    tracing::warn!("Callback error during CycleStart: {}", e);
-                }
-            }
+        // 📊 COMPREHENSIVE MODEL INTERACTION CYCLE LOGGING
+        tracing::info!(
+            "🔄 MODEL INTERACTION CYCLE {} of {} - Duration: {}ms",
+            model_interaction_count + 1,
+            self.config.max_cycles,
+            run.started.elapsed().as_millis()
+        );
+        debug!(
+            "🔄 Model Interaction Cycle {} ID: {}, Conversation messages: {}",
+            model_interaction_count + 1,
+            cycle_id,
+            self.agent.conversation().message_count()
+        );
 
-            _event_loop_guard.checkpoint(&format!(
-                "starting_model_interaction_{}",
-                model_interaction_count + 1
-            ));
-            self.performance_tracer.record_cycle(
-                &format!("model_interaction_start_{}", model_interaction_count + 1),
-                Duration::from_millis(0),
-            );
+        // Emit CycleStart callback (model interaction)
+        if let Some(ref callback) = self.callback_handler {
+            let event = CallbackEvent::CycleStart {
+                cycle_id,
+                cycle_number: model_interaction_count + 1,
+            };
+            if let Err(e) = callback.handle_event(event).await {
+                tracing::warn!("Callback error during CycleStart: {}", e);
+            }
+        }
 
-            debug!(
-                "🔄 Starting model interaction {} with execute_cycle_with_prompt",
-                model_interaction_count + 1
-            );
+        run.guard.checkpoint(&format!(
+            "starting_model_interaction_{}",
+            model_interaction_count + 1
+        ));
+        self.performance_tracer.record_cycle(
+            &format!("model_interaction_start_{}", model_interaction_count + 1),
+            Duration::from_millis(0),
+        );
 
-            // Pass the parent span context to the method for proper parent-child relationship
-            let parent_context = event_loop_span.as_ref().map(|span| span.context());
-            // Pass invoke_agent span IDs for log event linking (AgentCore Evaluations)
-            let invoke_agent_span_ids = event_loop_span
-                .as_ref()
-                .map(|span| (span.trace_id().to_string(), span.span_id().to_string()));
-
-            crate::perf_checkpoint!("stood.event_loop.cycle.start");
-            let cycle_result = crate::perf_timed!("stood.event_loop.cycle", {
-                self.execute_cycle_with_prompt_with_context(
-                    cycle_id,
-                    &original_prompt,
-                    model_interaction_count == 0,
-                    parent_context,
-                    invoke_agent_span_ids,
-                )
-                .await
-            });
-            crate::perf_checkpoint!("stood.event_loop.cycle.end");
+        debug!(
+            "🔄 Starting model interaction {} with execute_cycle_with_prompt",
+            model_interaction_count + 1
+        );
 
-            match cycle_result {
-                Ok(cycle_result) => {
-                    model_interaction_count += 1;
+        // Pass the parent span context to the method for proper parent-child relationship
+        let parent_context = run.span.as_ref().map(|span| span.context());
+        // Pass invoke_agent span IDs for log event linking (AgentCore Evaluations)
+        let invoke_agent_span_ids = run
+            .span
+            .as_ref()
+            .map(|span| (span.trace_id().to_string(), span.span_id().to_string()));
 
-                    // Collect the response from this cycle
-                    all_responses.push(cycle_result.response.clone());
+        self.current_cycle = model_interaction_count + 1;
+        crate::perf_checkpoint!("stood.event_loop.cycle.start");
+        let cycle_result = crate::perf_timed!("stood.event_loop.cycle", {
+            self.execute_cycle_with_prompt_with_context(
+                cycle_id,
+                &run.prompt,
+                model_interaction_count == 0,
+                parent_context,
+                invoke_agent_span_ids,
+            )
+            .await
+        });
+        crate::perf_checkpoint!("stood.event_loop.cycle.end");
+
+        match cycle_result {
+            Ok(cycle_result) => {
+                run.cycles_executed += 1;
+                let model_interaction_count = run.cycles_executed;
+
+                // Collect the response from this cycle
+                run.responses.push(cycle_result.response.clone());
+
+                // 📊 COMPREHENSIVE MODEL INTERACTION CYCLE COMPLETION LOGGING
+                tracing::info!("✅ MODEL INTERACTION CYCLE {} COMPLETED - Response length: {}, Should continue: {}, Tool iterations: {}",
+                    model_interaction_count,
+                    cycle_result.response.len(),
+                    cycle_result.should_continue,
+                    cycle_result.tool_iterations_used.unwrap_or(0)
+                );
+                debug!(
+                    "✅ Model Interaction Cycle {} response preview: '{}'",
+                    model_interaction_count,
+                    cycle_result.response.chars().take(100).collect::<String>()
+                );
 
-                    // 📊 COMPREHENSIVE MODEL INTERACTION CYCLE COMPLETION LOGGING
-                    tracing::info!("✅ MODEL INTERACTION CYCLE {} COMPLETED - Response length: {}, Should continue: {}, Tool iterations: {}",
-                        model_interaction_count,
-                        cycle_result.response.len(),
-                        cycle_result.should_continue,
-                        cycle_result.tool_iterations_used.unwrap_or(0)
-                    );
-                    debug!(
-                        "✅ Model Interaction Cycle {} response preview: '{}'",
-                        model_interaction_count,
-                        cycle_result.response.chars().take(100).collect::<String>()
-                    );
+                self.performance_tracer.record_cycle(
+                    &format!("model_interaction_completed_{}", model_interaction_count),
+                    Duration::from_millis(0),
+                );
+                self.emit_progress(ProgressPhase::BetweenCycles, true).await;
 
-                    self.performance_tracer.record_cycle(
-                        &format!("model_interaction_completed_{}", model_interaction_count),
-                        Duration::from_millis(0),
-                    );
+                if let Some(reason) = self.cycle_termination.take() {
+                    tracing::warn!("Event loop stopping: {}", reason);
+                    run.termination_reason = Some(reason);
+                    return Ok(cycle_result.response);
+                }
 
-                    if cycle_result.should_continue {
-                        tracing::info!("🔄 Model Interaction Cycle {} completed, CONTINUING to next model interaction", model_interaction_count);
-                        _event_loop_guard.checkpoint(&format!(
-                            "model_interaction_{}_continue",
-                            model_interaction_count
-                        ));
-                        continue;
-                    } else {
-                        tracing::info!(
-                            "🏁 Model Interaction Cycle {} completed task, STOPPING event loop",
-                            model_interaction_count
-                        );
-                        _event_loop_guard.checkpoint(&format!(
-                            "model_interaction_{}_final",
-                            model_interaction_count
-                        ));
-                        break;
+                if cycle_result.should_continue {
+                    if let Some(reason) = self
+                        .check_stop_conditions(
+                            model_interaction_count,
+                            run.started.elapsed(),
+                            &cycle_result.response,
+                        )
+                        .await
+                    {
+                        tracing::info!("🛑 Event loop stopping: {}", reason);
+                        run.termination_reason = Some(reason);
+                        return Ok(cycle_result.response);
                     }
-                }
-                Err(e) => {
-                    loop_error = Some(format!(
-                        "Model Interaction Cycle {} failed: {}",
-                        cycle_id, e
+                    tracing::info!("🔄 Model Interaction Cycle {} completed, CONTINUING to next model interaction", model_interaction_count);
+                    run.guard.checkpoint(&format!(
+                        "model_interaction_{}_continue",
+                        model_interaction_count
+                    ));
+                } else {
+                    tracing::info!(
+                        "🏁 Model Interaction Cycle {} completed task, STOPPING event loop",
+                        model_interaction_count
+                    );
+                    run.guard.checkpoint(&format!(
+                        "model_interaction_{}_final",
+                        model_interaction_count
                     ));
-                    tracing::error!("Event loop failed: {}", e);
+                    run.termination_reason = Some(TerminationReason::Completed);
+                }
+                Ok(cycle_result.response)
+            }
+            Err(e) => {
+                run.error = Some(format!(
+                    "Model Interaction Cycle {} failed: {}",
+                    cycle_id, e
+                ));
+                // A phase timed out at the deadline or a retry was refused for it
+                let out_of_time = run.deadline.is_some_and(|deadline| deadline.is_expired())
+                    || run.retry_history.deadline_refusals() > deadline_refusals;
+                run.termination_reason = Some(if out_of_time {
+                    TerminationReason::DeadlineExceeded
+                } else {
+                    TerminationReason::Error
+                });
+                tracing::error!("Event loop failed: {}", e);
 
-                    // Emit Error callback
-                    if let Some(ref callback) = self.callback_handler {
-                        let event = CallbackEvent::Error {
-                            error: e.clone(),
-                            context: format!(
-                                "Model Interaction Cycle {} execution failed",
-                                cycle_id
-                            ),
-                        };
-                        if let Err(callback_err) = callback.handle_event(event).await {
-                            tracing::warn!("Callback error during Error event: {}", callback_err);
-                        }
+                // Emit Error callback
+                if let Some(ref callback) = self.callback_handler {
+                    let event = CallbackEvent::Error {
+                        error: e.clone(),
+                        context: format!("Model Interaction Cycle {} execution failed", cycle_id),
+                    };
+                    if let Err(callback_err) = callback.handle_event(event).await {
+                        tracing::warn!("Callback error during Error event: {}", callback_err);
                     }
-
-                    break;
                 }
+
+                Err(e)
             }
         }
+    }
 
+    /// Finish `run`: settle cancelled tools, notify callbacks and build the result
+    pub(crate) async fn finish_run(&mut self, run: ExecutionRun) -> EventLoopResult {
+        let ExecutionRun {
+            started: loop_start,
+            span: event_loop_span,
+            guard: _event_loop_guard,
+            cycles_executed: model_interaction_count,
+            responses: all_responses,
+            error: mut loop_error,
+            termination_reason,
+            first_message,
+            deadline,
+            ..
+        } = run;
         let total_duration = loop_start.elapsed();
         let mut success = loop_error.is_none();
+        let mut termination_reason = termination_reason.unwrap_or_else(|| {
+            if self.is_cancelled() {
+                TerminationReason::Cancelled
+            } else if deadline.is_some_and(|deadline| deadline.is_expired()) {
+                TerminationReason::DeadlineExceeded
+            } else if model_interaction_count >= self.config.max_cycles {
+                TerminationReason::MaxCycles {
+                    limit: self.config.max_cycles,
+                }
+            } else {
+                TerminationReason::MaxDuration {
+                    limit: self.config.max_duration,
+                }
+            }
+        });
+
+        if let TerminationReason::BehaviorAnomaly { anomaly } = &termination_reason {
+            loop_error
+                .get_or_insert_with(|| format!("Stopped by the behavior monitor: {}", anomaly));
+            success = false;
+        }
+        if termination_reason == TerminationReason::DeadlineExceeded {
+            loop_error.get_or_insert_with(|| "Deadline exceeded".to_string());
+            success = false;
+        }
 
         // Combine all responses from all cycles into the final response
         let mut final_response = all_responses.join("\n\n"); // Join with double newlines for readability
 
+        self.trace(TraceEvent::Stop {
+            reason: termination_reason.to_string(),
+            budget_exhausted: termination_reason.is_budget_exhausted(),
+        });
+
         // Emit EventLoopComplete callback
         if let Some(ref callback) = self.callback_handler {
             let event = CallbackEvent::EventLoopComplete {
-                result: EventLoopResult {
-                    response: final_response.clone(),
-                    cycles_executed: model_interaction_count,
+                result: self.loop_result(
+                    final_response.clone(),
+                    model_interaction_count,
                     total_duration,
-                    metrics: self.metrics.clone(),
                     success,
-                    error: loop_error.clone(),
-                    was_streamed: self.config.enable_streaming,
-                    stream_events: self.stream_events.clone(),
-                },
+                    loop_error.clone(),
+                    termination_reason.clone(),
+                ),
                 total_duration,
             };
             if let Err(e) = callback.handle_event(event).await {
@@ -595,6 +945,7 @@ impl EventLoop {
 
         if self.is_cancelled() {
             tracing::info!("Event loop cancelled by cancellation token");
+            self.abort_early_tool_executions();
 
             // Add synthetic tool results for any pending tool uses to keep conversation valid
             // This ensures tool_use blocks always have matching tool_result blocks
@@ -619,6 +970,7 @@ impl EventLoop {
                             )
                         })),
                         error: Some("Execution cancelled by user request".to_string()),
+                        error_source: None,
                         duration: std::time::Duration::ZERO,
                     })
                     .collect();
@@ -642,8 +994,11 @@ impl EventLoop {
             final_response = "Execution cancelled by user request".to_string();
             // Set loop error to indicate cancellation
             loop_error = Some("Execution cancelled by cancellation token".to_string());
+            termination_reason = TerminationReason::Cancelled;
         }
 
+        self.cancel_background_tools().await;
+
         debug!("🏁 EventLoop::execute() completing with final_response: '{}', model_interactions: {}, success: {}",
                  final_response, model_interaction_count, success);
         debug!(
@@ -653,6 +1008,18 @@ impl EventLoop {
 
         // Complete the event loop span
         if let Some(mut span) = event_loop_span {
+            // Message-level GenAI events for the messages this execution added
+            if let Some(ref tracer) = self.tracer {
+                if tracer.can_export_log_events() {
+                    let messages = &self.agent.conversation().messages().messages;
+                    tracer.queue_genai_message_logs(
+                        span.trace_id(),
+                        span.span_id(),
+                        self.agent.conversation().system_prompt(),
+                        messages.get(first_message..).unwrap_or_default(),
+                    );
+                }
+            }
             span.set_attribute(
                 "event_loop.model_interactions",
                 model_interaction_count as i64,
@@ -685,16 +1052,52 @@ impl EventLoop {
             }
         }
 
-        Ok(EventLoopResult {
-            response: final_response,
-            cycles_executed: model_interaction_count,
+        let result = self.loop_result(
+            final_response,
+            model_interaction_count,
+            total_duration,
+            success,
+            loop_error,
+            termination_reason,
+        );
+        // The next execution starts its own attribution, trace and raw responses
+        self.token_attribution = TokenAttribution::default();
+        self.execution_trace = None;
+        self.raw_responses.clear();
+        result
+    }
+
+    /// Result of an execution ending with `response`, with the metrics and
+    /// records the event loop has collected
+    fn loop_result(
+        &self,
+        response: String,
+        cycles_executed: u32,
+        total_duration: Duration,
+        success: bool,
+        error: Option<String>,
+        termination_reason: TerminationReason,
+    ) -> EventLoopResult {
+        let citations = self.citations(&response);
+        EventLoopResult {
+            response,
+            cycles_executed,
             total_duration,
             metrics: self.metrics.clone(),
             success,
-            error: loop_error,
+            error,
             was_streamed: self.config.enable_streaming,
             stream_events: self.stream_events.clone(),
-        })
+            termination_reason,
+            token_attribution: self.token_attribution.clone(),
+            citations,
+            execution_trace: self.execution_trace.clone(),
+            retry_budget: RetryBudget::current().map(|budget| budget.usage()),
+            retries: RetryHistory::current()
+                .map(|history| history.summary())
+                .unwrap_or_default(),
+            raw_responses: self.raw_responses.clone(),
+        }
     }
 
     /// Execute a single model interaction with the 5-phase pattern using original prompt
@@ -703,7 +1106,7 @@ impl EventLoop {
         cycle_id: Uuid,
         original_prompt: &str,
         is_first_cycle: bool,
-        _parent_context: Option<opentelemetry::Context>,
+        _parent_context: Option<crate::telemetry::Context>,
         invoke_agent_span_ids: Option<(String, String)>, // (trace_id, span_id) for log events
     ) -> Result<CycleResult> {
         // Create telemetry span for the model interaction cycle
@@ -717,6 +1120,7 @@ impl EventLoop {
         );
         let cycle_start = Instant::now();
         let mut cycle_metrics = CycleMetrics::new(cycle_id);
+        let model_calls_before = self.metrics.model_calls.len();
 
         let _cycle_guard = self.performance_tracer.start_operation("cycle_execution");
         _cycle_guard.add_context("cycle_id", &cycle_id.to_string());
@@ -749,7 +1153,10 @@ impl EventLoop {
         // Phase 1: Get tool configuration for model
         _cycle_guard.checkpoint("get_tool_config");
         let tool_config_start = Instant::now();
-        let tool_config = self.tool_registry.get_tool_config().await;
+        let tool_config = self
+            .tool_registry
+            .get_tool_config_with_context(&self.tool_description_defaults())
+            .await;
         debug!("🔧 Tool config has {} tools", tool_config.tools.len());
         let tool_config_duration = tool_config_start.elapsed();
 
@@ -775,7 +1182,13 @@ impl EventLoop {
             "🌐 About to make LLM provider call. Streaming: {}",
             self.config.enable_streaming
         );
-        let llm_response = self.execute_chat_with_tools(&tool_config).await?;
+        let phase_timeouts = self.config.phase_timeouts;
+        let llm_response = phase_timeouts
+            .run(
+                ExecutionPhase::Reasoning,
+                self.execute_chat_with_tools(&tool_config),
+            )
+            .await?;
 
         let model_duration = model_start.elapsed();
         _cycle_guard.checkpoint("model_invocation_complete");
@@ -806,6 +1219,11 @@ impl EventLoop {
             }
         }
 
+        if let (Some(progress), Some(usage)) = (&self.progress, &llm_response.usage) {
+            progress.record_usage(usage.input_tokens, usage.output_tokens);
+        }
+        self.emit_progress(ProgressPhase::ModelCall, true).await;
+
         // Emit ModelComplete callback
         if let Some(ref callback) = self.callback_handler {
             let event = CallbackEvent::ModelComplete {
@@ -819,7 +1237,11 @@ impl EventLoop {
                         total_tokens: t.total_tokens,
                     }
                 }),
-                raw_response_data: None, // TODO: Implement in Phase 2
+                raw_response_data: if self.config.capture_raw_responses {
+                    self.raw_responses.last().cloned()
+                } else {
+                    None
+                },
             };
             if let Err(e) = callback.handle_event(event).await {
                 tracing::warn!("Callback error during ModelComplete: {}", e);
@@ -1013,6 +1435,16 @@ impl EventLoop {
                     "📋 TRACE: ==================== END ITERATION STATE ===================="
                 );
             }
+            if let Some(anomaly) = self.inspect_behavior(&current_response).await {
+                current_response.content = format!(
+                    "I've stopped because unusual behavior was detected: {}",
+                    anomaly
+                );
+                current_response.tool_calls.clear();
+                self.cycle_termination = Some(TerminationReason::BehaviorAnomaly { anomaly });
+                break;
+            }
+
             // Check if response has tool calls to determine next action
             if !current_response.tool_calls.is_empty() {
                 tool_iteration_count += 1;
@@ -1035,9 +1467,33 @@ impl EventLoop {
                     // Create a final response with max iterations message
                     current_response.content = "I've reached the maximum number of tool executions. Please try rephrasing your request.".to_string();
                     current_response.tool_calls.clear();
+                    self.cycle_termination = Some(TerminationReason::MaxToolIterations {
+                        limit: max_tool_iterations,
+                    });
                     break;
                 }
 
+                if let Some(limit) = self.config.max_total_tokens {
+                    let used = self.tokens_used(&cycle_metrics);
+                    if used >= limit {
+                        tracing::warn!(
+                            "🚫 Token budget of {} exhausted ({} used), not executing {} requested tools",
+                            limit,
+                            used,
+                            current_response.tool_calls.len()
+                        );
+                        current_response.content = format!(
+                            "I've reached the token budget for this task before finishing. {}",
+                            current_response.content
+                        )
+                        .trim_end()
+                        .to_string();
+                        current_response.tool_calls.clear();
+                        self.cycle_termination = Some(TerminationReason::MaxTokens { limit, used });
+                        break;
+                    }
+                }
+
                 tracing::info!(
                     "🤖 LLM requested {} tools in iteration {}: {:?}",
                     current_response.tool_calls.len(),
@@ -1139,24 +1595,65 @@ impl EventLoop {
                 // If cancellation occurs during execution, we'll add synthetic results
                 self.pending_tool_uses = tool_uses.clone();
 
+                let hook_context = self.cycle_hook_context();
+                for hook in self.config.cycle_hooks.clone() {
+                    debug!("Running before_tool_batch hook '{}'", hook.name());
+                    hook.before_tool_batch(
+                        self.agent.conversation_mut().messages_mut(),
+                        &tool_uses,
+                        &hook_context,
+                    )
+                    .await?;
+                }
+
                 // Execute tools using existing infrastructure - pass context for proper span hierarchy
                 let cycle_context = cycle_span.as_ref().map(|span| span.context());
-                match self
-                    .tool_execution_phase(tool_uses, &mut cycle_metrics, cycle_context)
+                let phase_timeouts = self.config.phase_timeouts;
+                match phase_timeouts
+                    .run(
+                        ExecutionPhase::ToolBatch,
+                        self.tool_execution_phase(tool_uses, &mut cycle_metrics, cycle_context),
+                    )
                     .await
                 {
                     Ok(tool_results) => {
                         // Clear pending tool uses - execution completed successfully
                         self.pending_tool_uses.clear();
+                        self.emit_progress(ProgressPhase::ToolExecution, true).await;
 
                         tracing::info!(
                             "✅ Tool execution completed with {} results",
                             tool_results.len()
                         );
 
+                        for result in &tool_results {
+                            self.trace(TraceEvent::ToolExecution {
+                                tool_use_id: result.tool_use_id.clone(),
+                                tool_name: result.tool_name.clone(),
+                                input: result.input.clone(),
+                                success: result.success,
+                                output: result.output.clone(),
+                                error: result.error.clone(),
+                                duration_ms: result.duration.as_millis() as u64,
+                            });
+                        }
+
+                        if self.config.citations {
+                            for result in &tool_results {
+                                if let Some(output) = &result.output {
+                                    self.citation_sources.extend(extract_documents(output));
+                                }
+                            }
+                        }
+
                         // Add tool results to conversation for next LLM iteration
-                        let tool_result_message =
+                        let mut tool_result_message =
                             self.create_tool_result_message(tool_results.clone());
+                        tool_result_message.content.extend(
+                            self.background_updates
+                                .drain(..)
+                                .map(crate::types::ContentBlock::text),
+                        );
                         self.agent
                             .conversation_mut()
                             .add_message(tool_result_message);
@@ -1273,8 +1770,22 @@ impl EventLoop {
                         }
 
                         // Make another LLM call to get the final response based on tool results
-                        match self.execute_chat_with_tools(&tool_config).await {
+                        let phase_timeouts = self.config.phase_timeouts;
+                        match phase_timeouts
+                            .run(
+                                ExecutionPhase::Synthesis,
+                                self.execute_chat_with_tools(&tool_config),
+                            )
+                            .await
+                        {
                             Ok(follow_up_response) => {
+                                if let Some(usage) = &follow_up_response.usage {
+                                    cycle_metrics.tokens_used.input_tokens += usage.input_tokens;
+                                    cycle_metrics.tokens_used.output_tokens += usage.output_tokens;
+                                    cycle_metrics.tokens_used.total_tokens =
+                                        cycle_metrics.tokens_used.input_tokens
+                                            + cycle_metrics.tokens_used.output_tokens;
+                                }
                                 current_response = follow_up_response;
                                 tracing::debug!("✅ Received follow-up response from LLM");
                                 tracing::debug!(
@@ -1293,12 +1804,19 @@ impl EventLoop {
                                     tracing::debug!("✅ Follow-up response preview: '{}'", preview);
                                 }
                             }
+                            Err(e @ StoodError::TimeoutError { phase: Some(_), .. }) => {
+                                return Err(e);
+                            }
                             Err(e) => {
                                 tracing::error!("❌ Follow-up LLM call failed: {}", e);
                                 tracing::error!("❌ Follow-up LLM call error details: {:?}", e);
 
                                 // Include more detailed error information for debugging
                                 let error_details = format!("Follow-up LLM call failed: {}", e);
+                                self.trace(TraceEvent::ContextRecovery {
+                                    action: RecoveryAction::FollowUpFailure,
+                                    detail: error_details.clone(),
+                                });
                                 tracing::warn!("🔧 Nova debugging - conversation state before failed follow-up: {} messages", self.agent.conversation().messages().len());
 
                                 current_response.tool_calls.clear();
@@ -1388,11 +1906,21 @@ impl EventLoop {
                             }
 
                             current_response.content = recovered_response;
+                            self.trace(TraceEvent::ContextRecovery {
+                                action: RecoveryAction::ResponseRecovery,
+                                detail: if needs_recovery {
+                                    "Empty response after tool execution, rebuilt from tool outputs"
+                                } else {
+                                    "MCP verification markers missing, replaced with tool outputs"
+                                }
+                                .to_string(),
+                            });
                             tracing::info!("✅ RECOVERY SUCCESS - Generated response from tool outputs ({} chars)", current_response.content.len());
                         }
                     }
                     Err(e) => {
                         tracing::error!("❌ Tool execution failed: {}", e);
+                        self.abort_early_tool_executions();
 
                         // Add synthetic tool results for pending tool uses to keep conversation valid
                         // This ensures tool_use blocks always have matching tool_result blocks
@@ -1417,6 +1945,7 @@ impl EventLoop {
                                         )
                                     })),
                                     error: Some(format!("Tool execution failed: {}", e)),
+                                    error_source: None,
                                     duration: std::time::Duration::ZERO,
                                 })
                                 .collect();
@@ -1430,6 +1959,10 @@ impl EventLoop {
                         }
 
                         // Graceful fallback - provide error context but continue
+                        self.trace(TraceEvent::ContextRecovery {
+                            action: RecoveryAction::ToolFailureFallback,
+                            detail: e.to_string(),
+                        });
                         current_response.tool_calls.clear();
                         current_response.content = format!("I encountered an issue executing the requested tools: {}. Let me provide what I can based on my knowledge.", e);
                         tracing::debug!(
@@ -1450,8 +1983,12 @@ impl EventLoop {
         self.wait_for_stream_completion().await;
 
         // Evaluate whether to continue based on the configured strategy BEFORE adding response to conversation
-        let evaluation_result = self
-            .evaluate_continuation(&current_response, &cycle_metrics)
+        let phase_timeouts = self.config.phase_timeouts;
+        let evaluation_result = phase_timeouts
+            .run(
+                ExecutionPhase::Evaluation,
+                self.evaluate_continuation(&current_response, &cycle_metrics),
+            )
             .await?;
 
         tracing::info!(
@@ -1582,6 +2119,7 @@ impl EventLoop {
             );
             // Record token usage for this model interaction
             span.record_tokens(tokens_used.input_tokens, tokens_used.output_tokens);
+            span.record_latency(&self.metrics.model_calls[model_calls_before..]);
             span.set_success();
 
             // Queue chat completion log event for AgentCore Evaluations
@@ -1645,16 +2183,86 @@ impl EventLoop {
         Ok(tool_uses)
     }
 
+    /// Record an event of the current cycle if tracing is enabled
+    fn trace(&mut self, event: TraceEvent) {
+        if let Some(trace) = &mut self.execution_trace {
+            trace.record(self.current_cycle, event);
+        }
+    }
+
+    /// Raw provider payload of the model call that produced `response`
+    ///
+    /// Only the Bedrock provider exposes its raw payloads; other providers
+    /// return None.
+    fn raw_response_data(
+        &self,
+        response: &crate::llm::traits::ChatResponse,
+    ) -> Option<RawResponseData> {
+        let provider = self
+            .agent
+            .provider()
+            .as_any()
+            .downcast_ref::<crate::llm::providers::BedrockProvider>()?;
+
+        let mut raw_metadata = response.metadata.clone();
+        raw_metadata.insert("cycle".to_string(), self.current_cycle.into());
+        raw_metadata.insert(
+            "model_id".to_string(),
+            self.agent.config().model_id.clone().into(),
+        );
+
+        if let Some(json) = provider.get_last_response_json() {
+            return Some(RawResponseData {
+                response_type: ResponseType::NonStreaming,
+                non_streaming_json: Some(json),
+                streaming_events: None,
+                raw_metadata,
+            });
+        }
+        let events = provider
+            .get_last_stream_chunks()
+            .into_iter()
+            .map(|(timestamp, raw_json)| stream_chunk_event(timestamp, raw_json))
+            .collect();
+        Some(RawResponseData {
+            response_type: ResponseType::Streaming,
+            non_streaming_json: None,
+            streaming_events: Some(events),
+            raw_metadata,
+        })
+    }
+
+    /// Citations of the documents returned by tools in `response`
+    fn citations(&self, response: &str) -> Vec<Citation> {
+        if self.config.citations {
+            parse_citations(response, &self.citation_sources)
+        } else {
+            Vec::new()
+        }
+    }
+
     /// Create a message containing tool results for the conversation
     fn create_tool_result_message(&self, tool_results: Vec<ToolResult>) -> crate::types::Message {
         let content_blocks: Vec<crate::types::ContentBlock> = tool_results
             .into_iter()
             .map(|result| {
+                let cites_documents = self.config.citations
+                    && result
+                        .output
+                        .as_ref()
+                        .is_some_and(|output| !extract_documents(output).is_empty());
+                let mut content = crate::types::ToolResultContent::json(result.output.unwrap_or_else(|| {
+                    serde_json::json!({"error": result.error.unwrap_or_else(|| "Unknown error".to_string())})
+                }));
+                if cites_documents {
+                    content = crate::types::ToolResultContent::multiple(vec![
+                        content,
+                        crate::types::ToolResultContent::text(CITATION_INSTRUCTIONS),
+                    ]);
+                }
                 crate::types::ContentBlock::ToolResult {
                     tool_use_id: result.tool_use_id,
-                    content: crate::types::ToolResultContent::json(result.output.unwrap_or_else(|| {
-                        serde_json::json!({"error": result.error.unwrap_or_else(|| "Unknown error".to_string())})
-                    })),
+                    content,
                     is_error: !result.success,
                 }
             })
@@ -1668,7 +2276,7 @@ impl EventLoop {
         &mut self,
         tool_uses: Vec<crate::tools::ToolUse>,
         cycle_metrics: &mut CycleMetrics,
-        cycle_context: Option<opentelemetry::Context>,
+        cycle_context: Option<crate::telemetry::Context>,
     ) -> Result<Vec<ToolResult>> {
         let _tool_phase_guard = self
             .performance_tracer
@@ -1683,8 +2291,22 @@ impl EventLoop {
         let mut results = Vec::new();
         cycle_metrics.tool_calls += tool_uses.len() as u32;
 
-        // NEW: Use ToolExecutor directly for parallel execution instead of legacy ToolRegistry
-        if tool_uses.len() > 1 {
+        // Calls over their tool's limit are answered without executing
+        let call_order: Vec<String> = tool_uses.iter().map(|t| t.tool_use_id.clone()).collect();
+        let mut refused = Vec::new();
+        let tool_uses = self.enforce_call_limits(tool_uses, &mut refused);
+
+        // Tools started while the response was streaming only need their results
+        let tool_uses = self
+            .collect_early_tool_results(tool_uses, &mut results)
+            .await;
+        let started_early = results.len();
+
+        if let Some(interim) = self.config.interim_tool_results.clone() {
+            self.execute_tools_with_deadline(tool_uses, &interim, &mut results)
+                .await;
+        } else if tool_uses.len() > 1 {
+            // NEW: Use ToolExecutor directly for parallel execution instead of legacy ToolRegistry
             // Multiple tools - use parallel execution via ToolExecutor
             tracing::debug!(
                 "🚀 Executing {} tools in parallel using ToolExecutor (max_parallel_tools={})",
@@ -1753,6 +2375,7 @@ impl EventLoop {
                             success: false,
                             output: None,
                             error: Some(format!("Tool '{}' not found", tool_use.name)),
+                            error_source: None,
                             duration: Duration::from_millis(1),
                         };
                         results.push(error_result);
@@ -1792,9 +2415,10 @@ impl EventLoop {
             }
 
             // Execute tools in parallel using ToolExecutor
+            let agent_context = self.tool_context();
             let parallel_results = self
                 .tool_executor
-                .execute_tools_parallel(tool_executions, None)
+                .execute_tools_parallel(tool_executions, Some(&agent_context))
                 .await;
 
             // Convert results and emit callbacks
@@ -1821,6 +2445,7 @@ impl EventLoop {
                         success: true,
                         output: Some(tool_result.content.clone()),
                         error: tool_result.error,
+                        error_source: tool_result.source,
                         duration,
                     }
                 } else {
@@ -1836,6 +2461,7 @@ impl EventLoop {
                         success: false,
                         output: None,
                         error: Some(tool_result.content.to_string()),
+                        error_source: tool_result.source,
                         duration,
                     }
                 };
@@ -1913,6 +2539,7 @@ impl EventLoop {
                         tool_use_id: tool_use.tool_use_id.clone(),
                         duration,
                         error: result.error.clone(),
+                        error_source: result.error_source.clone(),
                         output: result.output.clone(),
                     };
                     if let Err(e) = callback.handle_event(event).await {
@@ -2035,10 +2662,31 @@ impl EventLoop {
 
                 _tool_guard.checkpoint("start_tool_execution");
                 let tool_execution_start = Instant::now();
-                let tool_result = self
-                    .tool_registry
-                    .execute_tool(&tool_use.name, Some(tool_use.input.clone()), None)
-                    .await;
+                let agent_context = self
+                    .tool_context()
+                    .for_tool_call(&tool_use.name, &tool_use.tool_use_id);
+                let execution = self.tool_registry.execute_tool(
+                    &tool_use.name,
+                    Some(tool_use.input.clone()),
+                    Some(&agent_context),
+                );
+                let outcome = match &self.config.cancellation_token {
+                    Some(token) => tokio::select! {
+                        result = execution => Some(result),
+                        _ = token.cancelled() => None,
+                    },
+                    None => Some(execution.await),
+                };
+                let tool_result = match outcome {
+                    Some(result) => result,
+                    None => {
+                        // The execution was dropped mid-flight, let the tool clean up
+                        if let Some(tool) = self.tool_registry.get_tool(&tool_use.name).await {
+                            tool.on_cancel(ToolCancelReason::Cancelled).await;
+                        }
+                        Err(crate::tools::ToolError::execution_failed("cancelled"))
+                    }
+                };
                 let tool_execution_duration = tool_execution_start.elapsed();
 
                 if tool_execution_duration > Duration::from_millis(500) {
@@ -2071,6 +2719,7 @@ impl EventLoop {
                                 success: true,
                                 output: Some(tool_result.content),
                                 error: None,
+                                error_source: None,
                                 duration: execution_start.elapsed(),
                             }
                         } else {
@@ -2091,6 +2740,7 @@ impl EventLoop {
                                 success: false,
                                 output: None,
                                 error: tool_result.error,
+                                error_source: tool_result.source,
                                 duration: execution_start.elapsed(),
                             }
                         }
@@ -2108,6 +2758,7 @@ impl EventLoop {
                             success: false,
                             output: None,
                             error: Some(tool_error.to_string()),
+                            error_source: tool_error.error_source().cloned(),
                             duration: execution_start.elapsed(),
                         }
                     }
@@ -2178,6 +2829,7 @@ impl EventLoop {
                         tool_use_id: result.tool_use_id.clone(),
                         output: result.output.clone(),
                         error: result.error.clone(),
+                        error_source: result.error_source.clone(),
                         duration: result.duration,
                     };
                     if let Err(e) = callback.handle_event(event).await {
@@ -2197,6 +2849,7 @@ impl EventLoop {
                     start_time: Utc::now(),
                     input_size_bytes: Some(tool_use.input.to_string().len()),
                     output_size_bytes: result.output.as_ref().map(|o| o.to_string().len()),
+                    input: (!result.success).then(|| tool_use.input.clone()),
                 };
 
                 self.metrics.add_tool_execution(tool_metric);
@@ -2204,25 +2857,950 @@ impl EventLoop {
             }
         }
 
-        Ok(results)
-    }
+        let reliability = self.tool_registry.reliability();
+        for result in &results {
+            if result.success {
+                reliability.record_success(&result.tool_name);
+            } else {
+                reliability.record_failure(&result.tool_name, result.error.as_deref());
+            }
+        }
 
-    /// Unified method for executing chat with tools (streaming or non-streaming)
-    async fn execute_chat_with_tools(
-        &mut self,
+        if started_early > 0 || !refused.is_empty() {
+            // Keep results in the order the model requested the calls
+            results.append(&mut refused);
+            results.sort_by_key(|r| call_order.iter().position(|id| *id == r.tool_use_id));
+        }
+
+        if let Some(budget) = self.tool_executor.config().output_budget.clone() {
+            self.apply_output_budget(&budget, &mut results).await;
+        }
+
+        self.apply_compaction_requests(&mut results).await;
+
+        Ok(results)
+    }
+
+    /// Start a streamed tool call before the model finishes its response
+    ///
+    /// Does nothing unless early tool start is enabled, the call's input is a
+    /// complete object and the tool exists without a call limit.
+    async fn start_tool_early(&mut self, tool_call: &crate::llm::traits::ToolCall) {
+        if !self.config.early_tool_start
+            || !self.config.cycle_hooks.is_empty()
+            || !tool_call.input.is_object()
+            || self.early_tool_executions.contains_key(&tool_call.id)
+            || self
+                .agent
+                .config()
+                .tool_call_limits
+                .limit_for(&tool_call.name)
+                .is_some()
+            || !self.tool_registry.has_tool(&tool_call.name).await
+        {
+            return;
+        }
+
+        debug!(
+            "Starting tool '{}' ({}) while the response is still streaming",
+            tool_call.name, tool_call.id
+        );
+        if let Some(ref callback) = self.callback_handler {
+            let event = CallbackEvent::ToolStart {
+                tool_name: tool_call.name.clone(),
+                tool_use_id: tool_call.id.clone(),
+                input: tool_call.input.clone(),
+            };
+            if let Err(e) = callback.handle_event(event).await {
+                tracing::warn!("Callback error during early ToolStart: {}", e);
+            }
+        }
+
+        let registry = self.tool_registry.clone();
+        let agent_context = self
+            .tool_context()
+            .for_tool_call(&tool_call.name, &tool_call.id);
+        let name = tool_call.name.clone();
+        let input = tool_call.input.clone();
+        let deadline = Deadline::current();
+        // Same limit as the executor applies to calls it runs itself
+        let limit = Deadline::cap_current(self.tool_executor.config().execution_timeout);
+        let handle = crate::runtime::spawn(Deadline::scope_opt(deadline, async move {
+            let started = Instant::now();
+            let execution = registry.execute_tool(&name, Some(input), Some(&agent_context));
+            let result = match crate::runtime::timeout(limit, execution).await {
+                Ok(result) => result,
+                Err(_) => {
+                    let reason = ToolCancelReason::Timeout(limit);
+                    if let Some(tool) = registry.get_tool(&name).await {
+                        tool.on_cancel(reason).await;
+                    }
+                    Err(crate::tools::ToolError::execution_failed(
+                        reason.to_string(),
+                    ))
+                }
+            };
+            (result, started.elapsed())
+        }));
+        self.early_tool_executions.insert(
+            tool_call.id.clone(),
+            EarlyToolExecution {
+                input: tool_call.input.clone(),
+                handle,
+            },
+        );
+    }
+
+    /// Abort tool calls started early whose results were never collected
+    fn abort_early_tool_executions(&mut self) {
+        for (_, execution) in self.early_tool_executions.drain() {
+            execution.handle.abort();
+        }
+    }
+
+    /// Wait for the tool calls in `tool_uses` that were started early and add
+    /// their results, returning the calls that still have to be executed
+    async fn collect_early_tool_results(
+        &mut self,
+        tool_uses: Vec<crate::tools::ToolUse>,
+        results: &mut Vec<ToolResult>,
+    ) -> Vec<crate::tools::ToolUse> {
+        if self.early_tool_executions.is_empty() {
+            return tool_uses;
+        }
+
+        let mut remaining = Vec::new();
+        for tool_use in tool_uses {
+            let Some(EarlyToolExecution { input, mut handle }) =
+                self.early_tool_executions.remove(&tool_use.tool_use_id)
+            else {
+                remaining.push(tool_use);
+                continue;
+            };
+            if input != tool_use.input {
+                // The final input differs from the one the tool was started with
+                handle.abort();
+                remaining.push(tool_use);
+                continue;
+            }
+
+            let outcome = match &self.config.cancellation_token {
+                Some(token) => tokio::select! {
+                    joined = &mut handle => Some(joined),
+                    _ = token.cancelled() => None,
+                },
+                None => Some((&mut handle).await),
+            };
+            let (tool_result, duration) = match outcome {
+                Some(Ok(finished)) => finished,
+                Some(Err(e)) => (Err(crate::tools::ToolError::from_source(e)), Duration::ZERO),
+                None => {
+                    handle.abort();
+                    if let Some(tool) = self.tool_registry.get_tool(&tool_use.name).await {
+                        tool.on_cancel(ToolCancelReason::Cancelled).await;
+                    }
+                    (
+                        Err(crate::tools::ToolError::execution_failed("cancelled")),
+                        Duration::ZERO,
+                    )
+                }
+            };
+
+            let result = self
+                .finish_spawned_tool(&tool_use, tool_result, duration)
+                .await;
+            debug!(
+                "Collected result of early started tool '{}' (success: {})",
+                result.tool_name, result.success
+            );
+            results.push(result);
+        }
+
+        // Calls the model did not end up making
+        self.abort_early_tool_executions();
+        remaining
+    }
+
+    /// Record the result of a tool call that ran as a spawned task
+    async fn finish_spawned_tool(
+        &mut self,
+        tool_use: &crate::tools::ToolUse,
+        tool_result: std::result::Result<crate::tools::ToolResult, crate::tools::ToolError>,
+        duration: Duration,
+    ) -> ToolResult {
+        let (success, output, error, error_source) = match tool_result {
+            Ok(tool_result) if tool_result.success => (true, Some(tool_result.content), None, None),
+            Ok(tool_result) => (false, None, tool_result.error, tool_result.source),
+            Err(e) => (false, None, Some(e.to_string()), e.error_source().cloned()),
+        };
+        let result = ToolResult {
+            tool_use_id: tool_use.tool_use_id.clone(),
+            tool_name: tool_use.name.clone(),
+            input: tool_use.input.clone(),
+            success,
+            output,
+            error,
+            error_source,
+            duration,
+        };
+
+        self.performance_logger.log_tool_performance(
+            &result.tool_name,
+            result.duration,
+            result.success,
+        );
+        if let Some(ref callback) = self.callback_handler {
+            let event = CallbackEvent::ToolComplete {
+                tool_name: result.tool_name.clone(),
+                tool_use_id: result.tool_use_id.clone(),
+                output: result.output.clone(),
+                error: result.error.clone(),
+                error_source: result.error_source.clone(),
+                duration: result.duration,
+            };
+            if let Err(e) = callback.handle_event(event).await {
+                tracing::warn!("Callback error during ToolComplete: {}", e);
+            }
+        }
+        self.metrics.add_tool_execution(ToolExecutionMetric {
+            tool_name: result.tool_name.clone(),
+            tool_use_id: Some(result.tool_use_id.clone()),
+            duration: result.duration,
+            success: result.success,
+            error: result.error.clone(),
+            trace_id: None,
+            span_id: None,
+            start_time: Utc::now(),
+            input_size_bytes: Some(tool_use.input.to_string().len()),
+            output_size_bytes: result.output.as_ref().map(|o| o.to_string().len()),
+            input: (!result.success).then(|| tool_use.input.clone()),
+        });
+        result
+    }
+
+    /// Run a tool batch until the interim result deadline
+    ///
+    /// Tools still running at the deadline get an interim result with their
+    /// partial output and keep running in the background. Updates on tools
+    /// already running in the background are queued for the next tool result
+    /// message.
+    async fn execute_tools_with_deadline(
+        &mut self,
+        tool_uses: Vec<crate::tools::ToolUse>,
+        interim: &InterimResultConfig,
+        results: &mut Vec<ToolResult>,
+    ) {
+        let deadline = Instant::now() + interim.deadline;
+        let mut running = Vec::with_capacity(tool_uses.len());
+        for tool_use in tool_uses {
+            if let Some(ref callback) = self.callback_handler {
+                let event = CallbackEvent::ToolStart {
+                    tool_name: tool_use.name.clone(),
+                    tool_use_id: tool_use.tool_use_id.clone(),
+                    input: tool_use.input.clone(),
+                };
+                if let Err(e) = callback.handle_event(event).await {
+                    tracing::warn!("Callback error during ToolStart: {}", e);
+                }
+            }
+
+            let partial_output = PartialOutput::new();
+            let agent_context = self
+                .tool_context()
+                .for_tool_call(&tool_use.name, &tool_use.tool_use_id)
+                .with_partial_output(partial_output.clone());
+            let registry = self.tool_registry.clone();
+            let name = tool_use.name.clone();
+            let input = tool_use.input.clone();
+            let deadline = Deadline::current();
+            let handle = crate::runtime::spawn(Deadline::scope_opt(deadline, async move {
+                let started = Instant::now();
+                let result = registry
+                    .execute_tool(&name, Some(input), Some(&agent_context))
+                    .await;
+                (result, started.elapsed())
+            }));
+            running.push(BackgroundToolExecution {
+                tool_use,
+                partial_output,
+                started: Instant::now(),
+                handle,
+            });
+        }
+
+        let mut still_running = Vec::new();
+        for mut execution in running {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let wait = crate::runtime::timeout(remaining, &mut execution.handle);
+            let outcome = match &self.config.cancellation_token {
+                Some(token) => tokio::select! {
+                    waited = wait => Some(waited),
+                    _ = token.cancelled() => None,
+                },
+                None => Some(wait.await),
+            };
+            let (tool_result, duration) = match outcome {
+                Some(Ok(Ok(finished))) => finished,
+                Some(Ok(Err(e))) => (
+                    Err(crate::tools::ToolError::from_source(e)),
+                    execution.started.elapsed(),
+                ),
+                Some(Err(_)) => {
+                    let elapsed = execution.started.elapsed();
+                    debug!(
+                        "Tool '{}' is still running after {:?}, continuing without its result",
+                        execution.tool_use.name, elapsed
+                    );
+                    results.push(ToolResult {
+                        tool_use_id: execution.tool_use.tool_use_id.clone(),
+                        tool_name: execution.tool_use.name.clone(),
+                        input: execution.tool_use.input.clone(),
+                        success: true,
+                        output: Some(interim.interim_output(
+                            &execution.tool_use.name,
+                            elapsed,
+                            &execution.partial_output,
+                        )),
+                        error: None,
+                        error_source: None,
+                        duration: elapsed,
+                    });
+                    still_running.push(execution);
+                    continue;
+                }
+                None => {
+                    execution.handle.abort();
+                    if let Some(tool) = self.tool_registry.get_tool(&execution.tool_use.name).await
+                    {
+                        tool.on_cancel(ToolCancelReason::Cancelled).await;
+                    }
+                    (
+                        Err(crate::tools::ToolError::execution_failed("cancelled")),
+                        execution.started.elapsed(),
+                    )
+                }
+            };
+            let result = self
+                .finish_spawned_tool(&execution.tool_use, tool_result, duration)
+                .await;
+            results.push(result);
+        }
+
+        let updates = self.poll_background_tools(interim).await;
+        self.background_updates.extend(updates);
+        for execution in still_running {
+            self.background_tools
+                .insert(execution.tool_use.tool_use_id.clone(), execution);
+        }
+    }
+
+    /// Collect the results of finished background tools and the partial
+    /// output of those still running, oldest first
+    async fn poll_background_tools(&mut self, interim: &InterimResultConfig) -> Vec<String> {
+        use futures::FutureExt;
+
+        let mut ids: Vec<(Instant, String)> = self
+            .background_tools
+            .iter()
+            .map(|(id, execution)| (execution.started, id.clone()))
+            .collect();
+        ids.sort();
+
+        let mut updates = Vec::new();
+        for (_, id) in ids {
+            let Some(execution) = self.background_tools.get_mut(&id) else {
+                continue;
+            };
+            let Some(joined) = (&mut execution.handle).now_or_never() else {
+                updates.push(interim.running_update(
+                    &execution.tool_use.name,
+                    &id,
+                    execution.started.elapsed(),
+                    &execution.partial_output,
+                ));
+                continue;
+            };
+            let Some(execution) = self.background_tools.remove(&id) else {
+                continue;
+            };
+            let (tool_result, duration) = joined.unwrap_or_else(|e| {
+                (
+                    Err(crate::tools::ToolError::from_source(e)),
+                    execution.started.elapsed(),
+                )
+            });
+            let result = self
+                .finish_spawned_tool(&execution.tool_use, tool_result, duration)
+                .await;
+            let error = result.error.clone().unwrap_or_default();
+            let output = result
+                .output
+                .clone()
+                .unwrap_or_else(|| serde_json::json!({ "error": error }));
+            updates.push(finished_update(
+                &result.tool_name,
+                &result.tool_use_id,
+                &output,
+                result.success,
+            ));
+        }
+        updates
+    }
+
+    /// Cancel background tools that have not finished when the execution ends
+    async fn cancel_background_tools(&mut self) {
+        self.background_updates.clear();
+        let executions: Vec<BackgroundToolExecution> =
+            self.background_tools.drain().map(|(_, e)| e).collect();
+        for execution in executions {
+            tracing::info!(
+                "Cancelling background tool '{}' ({}), the execution ended before it finished",
+                execution.tool_use.name,
+                execution.tool_use.tool_use_id
+            );
+            execution.handle.abort();
+            if let Some(tool) = self.tool_registry.get_tool(&execution.tool_use.name).await {
+                tool.on_cancel(ToolCancelReason::Cancelled).await;
+            }
+        }
+    }
+
+    /// Perform compactions requested through the `compact_context` tool and
+    /// report the outcome in place of the tool's placeholder output
+    async fn apply_compaction_requests(&mut self, results: &mut [ToolResult]) {
+        for result in results.iter_mut() {
+            if !result.success
+                || result.tool_name != crate::tools::builtin::COMPACT_CONTEXT_TOOL_NAME
+            {
+                continue;
+            }
+
+            let keep_recent = result
+                .input
+                .get("keep_recent_messages")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize);
+            let focus = result
+                .input
+                .get("focus")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+
+            match self
+                .compact_conversation(keep_recent, focus.as_deref())
+                .await
+            {
+                Ok(Some(compaction)) => {
+                    result.output = Some(serde_json::json!({
+                        "status": "compacted",
+                        "messages_compacted": compaction.messages_compacted,
+                        "tokens_before": compaction.tokens_before,
+                        "tokens_after": compaction.tokens_after,
+                        "tokens_freed": compaction.tokens_freed(),
+                    }));
+                }
+                Ok(None) => {
+                    result.output = Some(serde_json::json!({
+                        "status": "nothing_to_compact",
+                        "tokens_freed": 0,
+                    }));
+                }
+                Err(e) => {
+                    result.success = false;
+                    result.output = None;
+                    result.error = Some(format!("Context compaction failed: {}", e));
+                }
+            }
+        }
+    }
+
+    /// Replace older turns of the conversation with a model-written summary.
+    ///
+    /// Returns `Ok(None)` if compaction is not configured or there are not
+    /// enough older messages to compact.
+    async fn compact_conversation(
+        &mut self,
+        keep_recent: Option<usize>,
+        focus: Option<&str>,
+    ) -> Result<Option<CompactionResult>> {
+        let Some(config) = self.config.compaction.clone() else {
+            return Ok(None);
+        };
+        let manager = crate::context_manager::ContextManager::with_config(config.context.clone());
+        let keep_recent = keep_recent.unwrap_or(config.keep_recent_messages);
+
+        let messages = self.agent.conversation().messages();
+        let Some(split) = manager.compaction_split(messages, keep_recent) else {
+            debug!("Not enough older messages to compact");
+            return Ok(None);
+        };
+        let transcript = crate::context_manager::render_transcript(&messages.messages[..split]);
+
+        let mut request = crate::types::Messages::new();
+        request.add_user_message(&config.summary_prompt(&transcript, focus));
+        let response = self
+            .agent
+            .provider()
+            .chat(&self.agent.config().model_id, &request, &Default::default())
+            .await
+            .map_err(|e| StoodError::model_error(format!("Summarization failed: {}", e)))?;
+        let summary = response.content.trim();
+        if summary.is_empty() {
+            return Err(StoodError::model_error(
+                "Summarizer returned an empty summary",
+            ));
+        }
+
+        let compaction =
+            manager.apply_compaction(self.agent.conversation_mut().messages_mut(), split, summary);
+        tracing::info!(
+            "Compacted {} messages, freeing ~{} tokens",
+            compaction.messages_compacted,
+            compaction.tokens_freed()
+        );
+        self.trace(TraceEvent::ContextRecovery {
+            action: RecoveryAction::Compaction,
+            detail: format!(
+                "Compacted {} messages ({} -> {} tokens)",
+                compaction.messages_compacted, compaction.tokens_before, compaction.tokens_after
+            ),
+        });
+        Ok(Some(compaction))
+    }
+
+    /// Whether the conversation has passed the automatic compaction threshold,
+    /// counted by the provider if configured
+    async fn should_auto_compact(&self) -> bool {
+        let Some(config) = &self.config.compaction else {
+            return false;
+        };
+        let messages = self.agent.conversation().messages_with_system_prompt();
+        if !config.use_provider_token_count || config.trigger_percentage.is_none() {
+            return config.should_compact(&messages);
+        }
+        let tools = self.llm_tools().await;
+        let count = crate::context_manager::ContextManager::with_config(config.context.clone())
+            .count_tokens(
+                self.agent.provider().as_ref(),
+                &self.agent.config().model_id,
+                &messages,
+                &tools,
+            )
+            .await;
+        debug!(
+            "Context before model call: {} tokens ({:?})",
+            count.tokens, count.source
+        );
+        config.should_compact_tokens(count.tokens)
+    }
+
+    /// Shrink tool outputs that exceed the configured budget before they are
+    /// added to the conversation
+    async fn apply_output_budget(
+        &self,
+        budget: &crate::context_manager::ToolOutputBudget,
+        results: &mut [ToolResult],
+    ) {
+        let rendered: Vec<Option<String>> = results
+            .iter()
+            .map(|r| {
+                r.output.as_ref().map(|output| match output {
+                    serde_json::Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                })
+            })
+            .collect();
+        let sizes: Vec<(&str, usize)> = results
+            .iter()
+            .zip(&rendered)
+            .map(|(r, text)| (r.tool_name.as_str(), text.as_ref().map_or(0, |t| t.len())))
+            .collect();
+        let allowances = budget.allocate(&sizes);
+
+        for ((result, text), allowance) in results.iter_mut().zip(rendered).zip(allowances) {
+            let Some(text) = text else { continue };
+            if text.len() <= allowance {
+                continue;
+            }
+
+            tracing::debug!(
+                "Tool '{}' output of {} bytes exceeds budget of {} bytes, shrinking with {:?}",
+                result.tool_name,
+                text.len(),
+                allowance,
+                budget.strategy
+            );
+
+            let mut shrunk = None;
+            if budget.strategy == crate::context_manager::TruncationStrategy::Summarize {
+                shrunk = self
+                    .summarize_tool_output(&result.tool_name, &text, allowance)
+                    .await
+                    .filter(|summary| summary.len() <= allowance);
+            }
+            let shrunk = shrunk.unwrap_or_else(|| budget.truncate(&text, allowance));
+            result.output = Some(serde_json::Value::String(shrunk));
+        }
+    }
+
+    /// Ask the model for a summary of an oversized tool output
+    async fn summarize_tool_output(
+        &self,
+        tool_name: &str,
+        output: &str,
+        max_bytes: usize,
+    ) -> Option<String> {
+        // Never send more to the summarizer than the budget allows for a full cycle
+        let input = crate::context_manager::truncate_text(
+            output,
+            self.tool_executor
+                .config()
+                .output_budget
+                .as_ref()
+                .map_or(max_bytes, |b| b.max_bytes_per_cycle.max(max_bytes)),
+            crate::context_manager::TruncationStrategy::HeadAndTail,
+        );
+        let prompt = format!(
+            "Summarize the following output of the '{}' tool in at most {} characters. \
+             Preserve identifiers, numbers, errors and any details needed to continue the task. \
+             Respond with the summary only.\n\n{}",
+            tool_name,
+            max_bytes.saturating_sub(64),
+            input
+        );
+
+        let mut messages = crate::types::Messages::new();
+        messages.add_user_message(&prompt);
+        match self
+            .agent
+            .provider()
+            .chat(
+                &self.agent.config().model_id,
+                &messages,
+                &Default::default(),
+            )
+            .await
+        {
+            Ok(response) => Some(format!("[summarized by model] {}", response.content.trim())),
+            Err(e) => {
+                tracing::warn!("Failed to summarize output of tool '{}': {}", tool_name, e);
+                None
+            }
+        }
+    }
+
+    /// Unified method for executing chat with tools (streaming or non-streaming)
+    async fn execute_chat_with_tools(
+        &mut self,
         tool_config: &crate::types::tools::ToolConfig,
     ) -> Result<crate::llm::traits::ChatResponse> {
-        if self.config.enable_streaming {
+        if self.should_auto_compact().await {
+            if let Err(e) = self.compact_conversation(None, None).await {
+                tracing::warn!("Automatic context compaction failed: {}", e);
+            }
+        }
+
+        self.model_call_count += 1;
+        let hook_context = self.cycle_hook_context();
+        let hooks = self.config.cycle_hooks.clone();
+
+        for hook in &hooks {
+            debug!("Running before_model_call hook '{}'", hook.name());
+            hook.before_model_call(self.agent.conversation_mut().messages_mut(), &hook_context)
+                .await?;
+        }
+
+        // Force the configured tool choice on the first model call only
+        let forced_choice = match self.model_call_count {
+            1 => self
+                .tool_choice_override
+                .clone()
+                .or_else(|| self.config.first_call_tool_choice.clone()),
+            _ => None,
+        };
+        let forced_config;
+        let tool_config = match forced_choice {
+            Some(tool_choice) if !tool_config.tools.is_empty() => {
+                debug!("Using tool choice {:?} for the first model call", tool_choice);
+                forced_config = crate::types::tools::ToolConfig {
+                    tools: tool_config.tools.clone(),
+                    tool_choice,
+                };
+                &forced_config
+            }
+            _ => tool_config,
+        };
+
+        // Continue from the prefill on the first model call of the execution
+        let prefill = self.assistant_prefill.take();
+        if let Some(prefill) = &prefill {
+            self.agent
+                .conversation_mut()
+                .messages_mut()
+                .push(crate::types::Message::assistant(prefill));
+            if let Some(ref callback) = self.callback_handler {
+                let event = CallbackEvent::ContentDelta {
+                    delta: prefill.clone(),
+                    complete: false,
+                    reasoning: false,
+                };
+                if let Err(e) = callback.handle_event(event).await {
+                    tracing::warn!("Callback error during prefill ContentDelta: {}", e);
+                }
+            }
+        }
+
+        let conversation = self.agent.conversation();
+        let request_estimate = RequestEstimate::new(
+            conversation.system_prompt(),
+            conversation.messages(),
+            &tool_config.tools,
+        );
+        let traced_messages = self.execution_trace.is_some().then(|| {
+            serde_json::to_value(conversation.messages_with_system_prompt()).unwrap_or_default()
+        });
+        let model_start = Instant::now();
+
+        let response = if self.config.enable_streaming {
             self.execute_streaming_chat_internal(tool_config).await
         } else {
             self.execute_non_streaming_chat_internal(tool_config).await
+        };
+        let response = match prefill {
+            Some(prefill) => {
+                self.agent
+                    .conversation_mut()
+                    .remove_last_if_role(crate::types::MessageRole::Assistant);
+                response.map(|mut response| {
+                    response.content.insert_str(0, &prefill);
+                    response
+                })
+            }
+            None => response,
+        }?;
+        self.record_model_call(&response, model_start);
+
+        if self.config.capture_raw_responses {
+            if let Some(raw) = self.raw_response_data(&response) {
+                self.raw_responses.push(raw);
+            }
+        }
+
+        if let Some(messages) = traced_messages {
+            let raw_request = self
+                .agent
+                .provider()
+                .as_any()
+                .downcast_ref::<crate::llm::providers::BedrockProvider>()
+                .and_then(|provider| provider.get_last_request_json())
+                .and_then(|json| serde_json::from_str(&json).ok());
+            self.trace(TraceEvent::ModelRequest {
+                model_id: self.agent.config().model_id.clone(),
+                messages,
+                tools: tool_config
+                    .tools
+                    .iter()
+                    .map(|t| t.tool_spec.name.clone())
+                    .collect(),
+                raw_request,
+            });
+            self.trace(TraceEvent::ModelResponse {
+                content: response.content.clone(),
+                tool_calls: response.tool_calls.clone(),
+                usage: response.usage.clone(),
+                duration_ms: model_start.elapsed().as_millis() as u64,
+                metadata: response.metadata.clone(),
+            });
+        }
+
+        self.token_attribution.record(
+            self.current_cycle,
+            &request_estimate,
+            response.usage.as_ref(),
+        );
+        self.token_attribution
+            .record_tool_calls(response.tool_calls.iter().map(|call| call.name.as_str()));
+
+        for hook in &hooks {
+            debug!("Running after_model_call hook '{}'", hook.name());
+            hook.after_model_call(
+                self.agent.conversation_mut().messages_mut(),
+                &response,
+                &hook_context,
+            )
+            .await?;
+        }
+
+        Ok(response)
+    }
+
+    /// Record the latency breakdown of a model call started at `model_start`
+    fn record_model_call(
+        &mut self,
+        response: &crate::llm::traits::ChatResponse,
+        model_start: Instant,
+    ) {
+        let streamed = self.config.enable_streaming;
+        let duration = model_start.elapsed();
+        let provider_latency = response
+            .metadata
+            .get(crate::llm::traits::PROVIDER_LATENCY_METADATA_KEY)
+            .and_then(|latency| latency.as_u64())
+            .map(Duration::from_millis);
+        self.metrics.add_model_call(ModelCallMetric {
+            duration,
+            time_to_first_token: self
+                .first_token_time
+                .filter(|_| streamed)
+                .map(|first_token| first_token.saturating_duration_since(model_start)),
+            provider_latency,
+            output_tokens: response
+                .usage
+                .as_ref()
+                .map_or(0, |usage| usage.output_tokens),
+            streamed,
+            start_time: Utc::now() - duration,
+        });
+    }
+
+    /// Build the context passed to cycle hooks
+    fn cycle_hook_context(&self) -> CycleHookContext {
+        CycleHookContext {
+            agent_id: self.agent.agent_id().to_string(),
+            agent_name: self.agent.agent_name().map(str::to_string),
+            model_call_number: self.model_call_count,
+        }
+    }
+
+    /// Context for tool calls, forwarding their progress reports and input
+    /// requests to the callback handler
+    fn tool_context(&self) -> crate::agent::AgentContext {
+        let context = self.agent.create_context("agent");
+        let Some(callback) = self.callback_handler.clone() else {
+            return context;
+        };
+        let elicitor = Arc::new(CallbackElicitor::new(callback.clone()));
+        let (reporter, mut reports) = ProgressReporter::channel();
+        // Ends once the tool calls holding the reporter have finished
+        crate::runtime::spawn(async move {
+            while let Some(event) = reports.recv().await {
+                let event = CallbackEvent::ToolProgress { event };
+                if let Err(e) = callback.handle_event(event).await {
+                    tracing::warn!("Callback error during ToolProgress: {}", e);
+                }
+            }
+        });
+        context.with_progress(reporter).with_elicitor(elicitor)
+    }
+
+    /// Registered tools as sent to the model, with their call limits in the descriptions
+    async fn llm_tools(&self) -> Vec<crate::llm::traits::Tool> {
+        let mut tools = self
+            .tool_registry
+            .to_llm_tools_with_context(&self.tool_description_defaults())
+            .await;
+        let limits = &self.agent.config().tool_call_limits;
+        if !limits.is_empty() {
+            for tool in &mut tools {
+                tool.description = limits.describe(&tool.name, &tool.description);
+            }
+        }
+        tools
+    }
+
+    /// Refuse the calls in `tool_uses` that exceed their tool's call limit,
+    /// counting the others, and return the calls that may run
+    fn enforce_call_limits(
+        &mut self,
+        tool_uses: Vec<crate::tools::ToolUse>,
+        refused: &mut Vec<ToolResult>,
+    ) -> Vec<crate::tools::ToolUse> {
+        let limits = &self.agent.config().tool_call_limits;
+        if limits.is_empty() {
+            return tool_uses;
+        }
+
+        let mut allowed = Vec::new();
+        for tool_use in tool_uses {
+            let count = self
+                .tool_call_counts
+                .entry(tool_use.name.clone())
+                .or_insert(0);
+            match limits.check(&tool_use.name, *count) {
+                Ok(()) => {
+                    *count += 1;
+                    allowed.push(tool_use);
+                }
+                Err(reached) => {
+                    tracing::warn!(
+                        "Refusing call to '{}': limit of {} calls per execution reached",
+                        tool_use.name,
+                        reached.max_calls
+                    );
+                    refused.push(ToolResult {
+                        tool_use_id: tool_use.tool_use_id,
+                        tool_name: tool_use.name,
+                        input: tool_use.input,
+                        success: false,
+                        output: None,
+                        error: Some(reached.to_string()),
+                        error_source: None,
+                        duration: Duration::ZERO,
+                    });
+                }
+            }
+        }
+        allowed
+    }
+
+    /// Built-in variables for templated tool descriptions (see [`crate::tools::templating`])
+    fn tool_description_defaults(&self) -> crate::tools::DescriptionContext {
+        let mut context = crate::tools::DescriptionContext::new()
+            .with("date", Utc::now().format("%Y-%m-%d"))
+            .with("agent_id", self.agent.agent_id())
+            .with("model", self.agent.model().model_id());
+        if let Some(name) = self.agent.agent_name() {
+            context.set("agent_name", name);
+        }
+        if let Some(workspace) = &self.agent.workspace {
+            context.set("workspace", workspace.path().display());
         }
+        context
+    }
+
+    /// Configuration of a model call in the current cycle
+    ///
+    /// Uses the agent's configured settings (max_tokens, temperature, etc.),
+    /// overridden by the parameter schedule for the current cycle.
+    fn chat_config(
+        &self,
+        tool_config: &crate::types::tools::ToolConfig,
+    ) -> crate::llm::traits::ChatConfig {
+        let agent_config = self.agent.config();
+        let mut chat_config = crate::llm::traits::ChatConfig {
+            model_id: agent_config.model_id.clone(),
+            provider: agent_config.provider,
+            temperature: agent_config.temperature,
+            max_tokens: agent_config.max_tokens,
+            enable_thinking: false,
+            cache_strategy: agent_config.cache_strategy.clone(),
+            tool_choice: tool_config.tool_choice.clone(),
+            additional_params: std::collections::HashMap::new(),
+        };
+        if let Some(schedule) = &self.config.parameter_schedule {
+            schedule.apply(
+                self.current_cycle.max(1),
+                self.config.max_cycles,
+                &mut chat_config,
+            );
+        }
+        chat_config
     }
 
     /// Internal method for non-streaming chat execution
     async fn execute_non_streaming_chat_internal(
         &mut self,
-        _tool_config: &crate::types::tools::ToolConfig,
+        tool_config: &crate::types::tools::ToolConfig,
     ) -> Result<crate::llm::traits::ChatResponse> {
         let chat_start = Instant::now();
 
@@ -2233,23 +3811,13 @@ impl EventLoop {
         debug!("🌐 Using non-streaming path, making LLM provider API call");
 
         // Convert tool registry to LLM tool format
-        let llm_tools = self.tool_registry.to_llm_tools().await;
+        let llm_tools = self.llm_tools().await;
         debug!(
             "🔧 Converted {} tools from registry for LLM provider",
             llm_tools.len()
         );
 
-        // Use agent's configured settings (max_tokens, temperature, etc.)
-        let agent_config = self.agent.config();
-        let chat_config = crate::llm::traits::ChatConfig {
-            model_id: agent_config.model_id.clone(),
-            provider: agent_config.provider,
-            temperature: agent_config.temperature,
-            max_tokens: agent_config.max_tokens,
-            enable_thinking: false,
-            cache_strategy: agent_config.cache_strategy.clone(),
-            additional_params: std::collections::HashMap::new(),
-        };
+        let chat_config = self.chat_config(tool_config);
 
         let messages_with_prompt = self.agent.conversation().messages_with_system_prompt();
         let response = match self
@@ -2291,13 +3859,15 @@ impl EventLoop {
     /// Internal method for streaming chat execution
     async fn execute_streaming_chat_internal(
         &mut self,
-        _tool_config: &crate::types::tools::ToolConfig,
+        tool_config: &crate::types::tools::ToolConfig,
     ) -> Result<crate::llm::traits::ChatResponse> {
         tracing::info!("🔧🌊 Starting real LLM provider streaming execution with tools");
 
         // Mark that streaming is active
         self.stream_was_active = true;
         self.stream_completion_time = None;
+        self.first_token_time = None;
+        self.abort_early_tool_executions();
 
         // TRACE MODE: Print full conversation state before making the request
         if tracing::level_enabled!(tracing::Level::TRACE) {
@@ -2372,7 +3942,7 @@ impl EventLoop {
         }
 
         // Convert tool registry to LLM tool format
-        let llm_tools = self.tool_registry.to_llm_tools().await;
+        let llm_tools = self.llm_tools().await;
         debug!(
             "🔧 Converted {} tools from registry for LLM provider streaming",
             llm_tools.len()
@@ -2400,17 +3970,7 @@ impl EventLoop {
             }
         }
 
-        // Use agent's configured settings (max_tokens, temperature, etc.)
-        let agent_config = self.agent.config();
-        let chat_config = crate::llm::traits::ChatConfig {
-            model_id: agent_config.model_id.clone(),
-            provider: agent_config.provider,
-            temperature: agent_config.temperature,
-            max_tokens: agent_config.max_tokens,
-            enable_thinking: false,
-            cache_strategy: agent_config.cache_strategy.clone(),
-            additional_params: std::collections::HashMap::new(),
-        };
+        let chat_config = self.chat_config(tool_config);
 
         // Get the streaming receiver from LLM provider using streaming with tools
         let messages_with_prompt = self.agent.conversation().messages_with_system_prompt();
@@ -2458,6 +4018,9 @@ impl EventLoop {
         let mut active_content_blocks: std::collections::HashMap<usize, String> =
             std::collections::HashMap::new();
         let mut stream_usage: Option<crate::llm::traits::Usage> = None;
+        let mut provider_latency_ms: Option<u64> = None;
+        // Detects tool inputs that are complete so the tools can start early
+        let mut streamed_inputs = crate::streaming::StreamingToolInputs::new();
 
         tracing::info!(
             "🎯 Processing real-time streaming events with universal content block pattern"
@@ -2468,6 +4031,18 @@ impl EventLoop {
         let mut received_event_count = 0;
         while let Some(stream_event) = stream_receiver.next().await {
             received_event_count += 1;
+            if self.first_token_time.is_none()
+                && matches!(
+                    stream_event,
+                    crate::llm::traits::StreamEvent::ContentBlockDelta { .. }
+                        | crate::llm::traits::StreamEvent::ContentDelta { .. }
+                        | crate::llm::traits::StreamEvent::ToolCallStart { .. }
+                        | crate::llm::traits::StreamEvent::ToolCallDelta { .. }
+                        | crate::llm::traits::StreamEvent::ThinkingDelta { .. }
+                )
+            {
+                self.first_token_time = Some(Instant::now());
+            }
             tracing::debug!(
                 "🎯 Event loop received stream event #{}: {:?}",
                 received_event_count,
@@ -2547,6 +4122,7 @@ impl EventLoop {
                                     );
                                 }
                             }
+                            self.record_streamed(text.len()).await;
                         }
                         crate::llm::traits::ContentBlockDelta::ToolUse {
                             tool_call_id,
@@ -2564,6 +4140,7 @@ impl EventLoop {
                                 .entry(tool_call_id.clone())
                                 .or_default()
                                 .push_str(input_delta);
+                            self.record_streamed(input_delta.len()).await;
 
                             // Create or update tool call info
                             if !current_tool_calls.contains_key(tool_call_id) {
@@ -2613,6 +4190,16 @@ impl EventLoop {
                                     .get(tool_call_id)
                                     .unwrap_or(&String::new())
                             );
+
+                            if let Some(input) = streamed_inputs.push(tool_call_id, input_delta) {
+                                if let Some(tool_call) = current_tool_calls.get(tool_call_id) {
+                                    let tool_call = crate::llm::traits::ToolCall {
+                                        input,
+                                        ..tool_call.clone()
+                                    };
+                                    self.start_tool_early(&tool_call).await;
+                                }
+                            }
                         }
                         crate::llm::traits::ContentBlockDelta::Thinking { reasoning_delta } => {
                             tracing::debug!(
@@ -2632,6 +4219,7 @@ impl EventLoop {
                                     tracing::warn!("Callback error during thinking delta: {}", e);
                                 }
                             }
+                            self.record_streamed(reasoning_delta.len()).await;
                         }
                     }
                 }
@@ -2754,12 +4342,14 @@ impl EventLoop {
 
                     break;
                 }
-                crate::llm::traits::StreamEvent::Metadata { usage } => {
+                crate::llm::traits::StreamEvent::Metadata { usage, latency_ms } => {
                     tracing::debug!("📊 Stream metadata received: {:?}", usage);
                     stream_usage = usage.clone();
+                    provider_latency_ms = latency_ms.or(provider_latency_ms);
                 }
                 crate::llm::traits::StreamEvent::Error { error } => {
                     tracing::error!("❌ Stream error: {}", error);
+                    self.abort_early_tool_executions();
                     return Err(crate::StoodError::model_error(format!(
                         "Stream error: {}",
                         error
@@ -2784,6 +4374,7 @@ impl EventLoop {
                             tracing::warn!("Callback error during legacy ContentDelta: {}", e);
                         }
                     }
+                    self.record_streamed(delta.len()).await;
                 }
                 crate::llm::traits::StreamEvent::ToolCallStart { tool_call } => {
                     tracing::debug!(
@@ -2793,6 +4384,8 @@ impl EventLoop {
                     );
                     current_tool_calls.insert(tool_call.id.clone(), tool_call.clone());
                     current_tool_inputs.insert(tool_call.id.clone(), String::new());
+                    streamed_inputs.reset(&tool_call.id);
+                    self.start_tool_early(tool_call).await;
                 }
                 crate::llm::traits::StreamEvent::ToolCallDelta {
                     tool_call_id,
@@ -2807,6 +4400,7 @@ impl EventLoop {
                         .entry(tool_call_id.clone())
                         .or_default()
                         .push_str(delta);
+                    let completed_input = streamed_inputs.push(tool_call_id, delta);
 
                     // For Nova, the delta might contain the complete JSON input
                     // Try to parse it and update the tool call if successful
@@ -2826,6 +4420,11 @@ impl EventLoop {
                             }
                         }
                     }
+                    if completed_input.is_some() {
+                        if let Some(tool_call) = current_tool_calls.get(tool_call_id).cloned() {
+                            self.start_tool_early(&tool_call).await;
+                        }
+                    }
                 }
                 crate::llm::traits::StreamEvent::Done { usage } => {
                     tracing::debug!(
@@ -2902,9 +4501,15 @@ impl EventLoop {
         );
 
         // Return the final response
-        let response = final_response.ok_or_else(|| {
+        let mut response = final_response.ok_or_else(|| {
             crate::StoodError::model_error("No response received from streaming".to_string())
         })?;
+        if let Some(latency_ms) = provider_latency_ms {
+            response.metadata.insert(
+                crate::llm::traits::PROVIDER_LATENCY_METADATA_KEY.to_string(),
+                serde_json::json!(latency_ms),
+            );
+        }
 
         tracing::debug!(
             "🔧 Streaming method returning response with {} tool calls",
@@ -2970,6 +4575,28 @@ impl EventLoop {
         tracing::debug!("Stream cancellation completed");
     }
 
+    /// Count streamed output and emit a progress event if one is due
+    async fn record_streamed(&self, chars: usize) {
+        if let Some(progress) = &self.progress {
+            progress.record_stream(chars);
+            self.emit_progress(ProgressPhase::Streaming, false).await;
+        }
+    }
+
+    /// Emit a progress event if progress events are enabled and `force` is set
+    /// or the progress interval has elapsed
+    async fn emit_progress(&self, phase: ProgressPhase, force: bool) {
+        let (Some(progress), Some(callback)) = (&self.progress, &self.callback_handler) else {
+            return;
+        };
+        if let Some(event) = progress.tick(phase, force) {
+            let event = CallbackEvent::Progress { event };
+            if let Err(e) = callback.handle_event(event).await {
+                tracing::warn!("Callback error during Progress: {}", e);
+            }
+        }
+    }
+
     /// Check if the event loop has been cancelled
     ///
     /// Returns true if a cancellation token was provided and has been cancelled.
@@ -3058,7 +4685,7 @@ impl EventLoop {
                     "⏳ Waiting {}ms for stream completion buffer",
                     remaining.as_millis()
                 );
-                tokio::time::sleep(remaining).await;
+                crate::runtime::sleep(remaining).await;
             }
         }
     }
@@ -3069,12 +4696,14 @@ impl EventLoop {
         cycle_metrics: &CycleMetrics,
     ) -> Result<EvaluationResult> {
         let strategy = self.config.evaluation_strategy.clone();
+        let strategy_name = strategy.name();
 
         // Create evaluation span as child of current cycle span
         let evaluation_span = if let Some(ref tracer) = self.tracer {
             let mut span = tracer.start_agent_span("evaluation");
             span.set_attribute("evaluation.strategy", strategy.name());
             span.set_attribute("evaluation.cycle_id", cycle_metrics.cycle_id.to_string());
+            span.set_attribute("evaluation.cycle_number", self.metrics.cycles.len() as i64);
             span.set_attribute(
                 "evaluation.response_length",
                 current_response.content.len() as i64,
@@ -3126,6 +4755,22 @@ impl EventLoop {
             match &result {
                 Ok(eval_result) => {
                     span.set_attribute("evaluation.decision", eval_result.decision);
+                    span.set_attribute("evaluation.basis", eval_result.basis);
+                    span.set_attribute(
+                        "evaluation.reasoning",
+                        evaluation_preview(&eval_result.reasoning),
+                    );
+                    span.add_event(
+                        if eval_result.decision {
+                            "evaluation.continue"
+                        } else {
+                            "evaluation.stop"
+                        },
+                        vec![
+                            crate::telemetry::KeyValue::new("evaluation.strategy", strategy_name),
+                            crate::telemetry::KeyValue::new("evaluation.basis", eval_result.basis),
+                        ],
+                    );
                     span.set_attribute(
                         "evaluation.duration_ms",
                         evaluation_duration.as_millis() as i64,
@@ -3153,6 +4798,27 @@ impl EventLoop {
             }
         }
 
+        if let Ok(eval_result) = &result {
+            tracing::info!(
+                target: "stood::evaluation",
+                strategy = strategy_name,
+                decision = if eval_result.decision { "continue" } else { "stop" },
+                basis = eval_result.basis,
+                cycle = self.metrics.cycles.len(),
+                duration_ms = evaluation_duration.as_millis() as u64,
+                has_feedback = eval_result.response.is_some(),
+                "Evaluation decided to {}",
+                if eval_result.decision { "continue" } else { "stop" }
+            );
+            self.trace(TraceEvent::Evaluation {
+                strategy: strategy_name.to_string(),
+                should_continue: eval_result.decision,
+                reasoning: eval_result.reasoning.clone(),
+                feedback: eval_result.response.clone(),
+                duration_ms: evaluation_duration.as_millis() as u64,
+            });
+        }
+
         tracing::info!(
             "✅ Evaluation completed in {:?} with decision: {:?}",
             evaluation_duration,
@@ -3194,6 +4860,7 @@ impl EventLoop {
             reasoning:
                 "Model-driven: stopping after one cycle, model decides continuation naturally"
                     .to_string(),
+            basis: "model_driven",
         };
 
         // Trigger evaluation complete callback
@@ -3218,7 +4885,7 @@ impl EventLoop {
         &mut self,
         current_response: &crate::llm::traits::ChatResponse,
         evaluation_prompt: &str,
-        _max_iterations: u32,
+        max_iterations: u32,
     ) -> Result<EvaluationResult> {
         use std::time::Instant;
         let start_time = Instant::now();
@@ -3253,6 +4920,11 @@ impl EventLoop {
             span.set_attribute("evaluation.type", "task_evaluation");
             span.set_attribute("evaluation.model_id", self.agent.config().model_id.clone());
             span.set_attribute("evaluation.prompt_length", evaluation_question.len() as i64);
+            span.set_attribute("evaluation.max_iterations", max_iterations as i64);
+            span.set_attribute(
+                "evaluation.prompt",
+                evaluation_preview(&evaluation_question),
+            );
             Some(span)
         } else {
             None
@@ -3267,17 +4939,23 @@ impl EventLoop {
             });
         };
 
+        let evaluation_result =
+            EvaluationResult::parse_evaluation_response(&evaluation_response_content);
+
         // Complete model span with response details
         if let Some(mut span) = evaluation_model_span {
             span.set_attribute(
                 "evaluation.response_length",
                 evaluation_response_content.len() as i64,
             );
+            span.set_attribute(
+                "evaluation.response",
+                evaluation_preview(&evaluation_response_content),
+            );
+            span.set_attribute("evaluation.decision", evaluation_result.decision);
+            span.set_attribute("evaluation.basis", evaluation_result.basis);
             span.set_attribute("evaluation.llm_status", "success");
         }
-
-        let evaluation_result =
-            EvaluationResult::parse_evaluation_response(&evaluation_response_content);
         let duration = start_time.elapsed();
 
         tracing::info!(
@@ -3331,49 +5009,53 @@ impl EventLoop {
         // Get the original conversation history for context
         let original_conversation = self.agent.conversation().messages().clone();
 
-        // Build context from original conversation
-        let mut context_parts = Vec::new();
-        for message in &original_conversation.messages {
-            match message.role {
-                crate::types::MessageRole::User => {
-                    let content = message
-                        .content
-                        .iter()
-                        .filter_map(|block| match block {
-                            crate::types::ContentBlock::Text { text } => Some(text.as_str()),
-                            _ => None,
-                        })
-                        .collect::<Vec<_>>()
-                        .join(" ");
-                    context_parts.push(format!("User: {}", content));
-                }
-                crate::types::MessageRole::Assistant => {
-                    let content = message
-                        .content
-                        .iter()
-                        .filter_map(|block| match block {
-                            crate::types::ContentBlock::Text { text } => Some(text.as_str()),
-                            _ => None,
-                        })
-                        .collect::<Vec<_>>()
-                        .join(" ");
-                    context_parts.push(format!("Assistant: {}", content));
-                }
-                crate::types::MessageRole::System => {
-                    let content = message
-                        .content
-                        .iter()
-                        .filter_map(|block| match block {
-                            crate::types::ContentBlock::Text { text } => Some(text.as_str()),
-                            _ => None,
-                        })
-                        .collect::<Vec<_>>()
-                        .join(" ");
-                    context_parts.push(format!("System: {}", content));
+        // Build context from original conversation, unless a smaller view is configured
+        let conversation_context = if self.config.evaluation_transcript != TranscriptView::Full {
+            self.create_evaluation_summary()
+        } else {
+            let mut context_parts = Vec::new();
+            for message in &original_conversation.messages {
+                match message.role {
+                    crate::types::MessageRole::User => {
+                        let content = message
+                            .content
+                            .iter()
+                            .filter_map(|block| match block {
+                                crate::types::ContentBlock::Text { text } => Some(text.as_str()),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        context_parts.push(format!("User: {}", content));
+                    }
+                    crate::types::MessageRole::Assistant => {
+                        let content = message
+                            .content
+                            .iter()
+                            .filter_map(|block| match block {
+                                crate::types::ContentBlock::Text { text } => Some(text.as_str()),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        context_parts.push(format!("Assistant: {}", content));
+                    }
+                    crate::types::MessageRole::System => {
+                        let content = message
+                            .content
+                            .iter()
+                            .filter_map(|block| match block {
+                                crate::types::ContentBlock::Text { text } => Some(text.as_str()),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        context_parts.push(format!("System: {}", content));
+                    }
                 }
             }
-        }
-        let conversation_context = context_parts.join("\n");
+            context_parts.join("\n")
+        };
 
         let agent_question = format!(
             "[INTERNAL EVALUATION - This is a private conversation for decision-making]\n\n{}\n\nConversation history:\n{}\n\nAgent's current response: \"{}\"\n\nBased on the full context, evaluate whether the agent should continue working. Respond with JSON in this exact format:\n{{\n  \"decision\": \"CONTINUE\" or \"STOP\",\n  \"response\": \"Additional content to add if continuing (empty if stopping)\"\n}}\n\nIf you decide the agent should CONTINUE, provide additional content in the 'response' field that will be added to the conversation to continue the task. If you decide to STOP, leave the 'response' field empty.",
@@ -3382,10 +5064,49 @@ impl EventLoop {
             current_response.content
         );
 
+        let evaluator_span = if let Some(ref tracer) = self.tracer {
+            let mut span = tracer.start_agent_span("evaluator_agent");
+            span.set_attribute("evaluation.type", "agent_based");
+            span.set_attribute(
+                "evaluation.model_id",
+                evaluator_agent.config().model_id.clone(),
+            );
+            span.set_attribute("evaluation.prompt", evaluation_preview(&agent_question));
+            Some(span)
+        } else {
+            None
+        };
+
         // Execute the evaluator agent (use Box::pin to avoid recursion issue)
-        let agent_result = Box::pin(evaluator_agent.execute(agent_question)).await?;
+        let agent_result = match Box::pin(evaluator_agent.execute(agent_question)).await {
+            Ok(agent_result) => agent_result,
+            Err(e) => {
+                if let Some(mut span) = evaluator_span {
+                    span.set_error(&e.to_string());
+                }
+                return Err(e);
+            }
+        };
 
         let evaluation_result = EvaluationResult::parse_evaluation_response(&agent_result.response);
+
+        if let Some(mut span) = evaluator_span {
+            span.set_attribute(
+                "evaluation.response",
+                evaluation_preview(&agent_result.response),
+            );
+            span.set_attribute(
+                "evaluation.evaluator_cycles",
+                agent_result.execution.cycles as i64,
+            );
+            span.set_attribute(
+                "evaluation.evaluator_tools",
+                agent_result.tools_called.join(", "),
+            );
+            span.set_attribute("evaluation.decision", evaluation_result.decision);
+            span.set_attribute("evaluation.basis", evaluation_result.basis);
+            span.set_success();
+        }
         let duration = start_time.elapsed();
 
         tracing::info!(
@@ -3461,6 +5182,19 @@ impl EventLoop {
                 current_response.content
             );
 
+            let perspective_span = if let Some(ref tracer) = self.tracer {
+                let mut span = tracer.start_model_span("evaluation_perspective");
+                span.set_attribute("evaluation.perspective", perspective.name.clone());
+                span.set_attribute("evaluation.perspective_weight", perspective.weight as f64);
+                span.set_attribute(
+                    "evaluation.prompt",
+                    evaluation_preview(&perspective_question),
+                );
+                Some(span)
+            } else {
+                None
+            };
+
             // Use isolated evaluation context to avoid polluting main conversation
             let perspective_response_content = if let Some(ref eval_ctx) = self.evaluation_context {
                 eval_ctx.evaluate_with_prompt(&perspective_question).await?
@@ -3478,6 +5212,17 @@ impl EventLoop {
             weighted_score += perspective_score * perspective.weight;
             total_weight += perspective.weight;
 
+            if let Some(mut span) = perspective_span {
+                span.set_attribute(
+                    "evaluation.response",
+                    evaluation_preview(&perspective_response_content),
+                );
+                span.set_attribute("evaluation.perspective_vote", perspective_continue);
+                span.set_attribute("evaluation.perspective_score", perspective_score as f64);
+                span.set_attribute("evaluation.running_weighted_score", weighted_score as f64);
+                span.set_success();
+            }
+
             perspective_details.push(format!(
                 "{}: {} (weight: {:.2})",
                 perspective.name,
@@ -3522,10 +5267,11 @@ impl EventLoop {
             decision: should_continue,
             response: None, // Multi-perspective doesn't generate additional content
             reasoning: format!(
-                "Weighted score: {:.2}/1.0 - {}",
+                "Weighted score: {:.2}/1.0 (continue above 0.50) - {}",
                 final_score,
                 perspective_details.join(", ")
             ),
+            basis: "weighted_vote",
         };
 
         // Trigger evaluation complete callback
@@ -3762,6 +5508,8 @@ struct ToolResult {
     success: bool,
     output: Option<Value>,
     error: Option<String>,
+    /// Typed error behind `error`, passed on to callbacks
+    error_source: Option<crate::tools::ToolErrorSource>,
     duration: Duration,
 }
 
@@ -3773,6 +5521,74 @@ struct CycleResult {
     tool_iterations_used: Option<u32>,
 }
 
+impl EventLoop {
+    /// Tokens consumed by the current execution, including the running cycle
+    fn tokens_used(&self, cycle_metrics: &CycleMetrics) -> u32 {
+        self.metrics
+            .total_tokens
+            .total_tokens
+            .saturating_sub(self.tokens_at_start)
+            + cycle_metrics.tokens_used.total_tokens
+    }
+
+    /// Check a model response with the behavior monitor, reporting anomalies
+    /// to the callback handler and returning the one that stops the execution
+    async fn inspect_behavior(
+        &self,
+        response: &crate::llm::traits::ChatResponse,
+    ) -> Option<Anomaly> {
+        let monitor = self.config.behavior_monitor.as_ref()?;
+        let verdict = monitor.inspect(self.current_cycle, response).await;
+        if let Some(ref callback) = self.callback_handler {
+            for (anomaly, action) in verdict.anomalies {
+                let event = CallbackEvent::BehaviorAnomaly { anomaly, action };
+                if let Err(e) = callback.handle_event(event).await {
+                    tracing::warn!("Callback error during BehaviorAnomaly: {}", e);
+                }
+            }
+        }
+        verdict.stop
+    }
+
+    /// Check the token budget and custom stop conditions after a cycle
+    async fn check_stop_conditions(
+        &self,
+        cycles_completed: u32,
+        elapsed: Duration,
+        last_response: &str,
+    ) -> Option<TerminationReason> {
+        let used = self
+            .metrics
+            .total_tokens
+            .total_tokens
+            .saturating_sub(self.tokens_at_start);
+        if let Some(limit) = self.config.max_total_tokens {
+            if used >= limit {
+                return Some(TerminationReason::MaxTokens { limit, used });
+            }
+        }
+
+        if self.config.stop_conditions.is_empty() {
+            return None;
+        }
+        let ctx = StopContext {
+            cycles_completed,
+            elapsed,
+            total_tokens: used,
+            last_response: last_response.to_string(),
+        };
+        for condition in &self.config.stop_conditions {
+            if let Some(reason) = condition.should_stop(&ctx).await {
+                return Some(TerminationReason::Condition {
+                    name: condition.name().to_string(),
+                    reason,
+                });
+            }
+        }
+        None
+    }
+}
+
 /// Result of an evaluation with structured decision and response
 #[derive(Debug, Clone)]
 struct EvaluationResult {
@@ -3782,6 +5598,21 @@ struct EvaluationResult {
     response: Option<String>,
     /// Raw evaluation reasoning/response for logging
     reasoning: String,
+    /// How the decision was reached, e.g. `json` or `keyword_fallback`
+    basis: &'static str,
+}
+
+/// Longest evaluator prompt or response recorded on evaluation spans
+const EVALUATION_TRACE_CHARS: usize = 2000;
+
+/// `text` cut to [`EVALUATION_TRACE_CHARS`] for span attributes
+fn evaluation_preview(text: &str) -> String {
+    if text.chars().count() <= EVALUATION_TRACE_CHARS {
+        return text.to_string();
+    }
+    let mut preview: String = text.chars().take(EVALUATION_TRACE_CHARS).collect();
+    preview.push('…');
+    preview
 }
 
 impl EvaluationResult {
@@ -3795,14 +5626,14 @@ impl EvaluationResult {
         // Strategy 1: Try direct JSON parsing
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(response) {
             tracing::debug!("✅ Direct JSON parsing successful");
-            return Self::parse_json_object(&json, response);
+            return Self::parse_json_object(&json, response, "json");
         }
 
         // Strategy 2: Try regex-based JSON extraction for mixed content
         if let Some(extracted_json) = Self::extract_json_from_mixed_content(response) {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&extracted_json) {
                 tracing::debug!("✅ Regex-extracted JSON parsing successful");
-                return Self::parse_json_object(&json, response);
+                return Self::parse_json_object(&json, response, "extracted_json");
             }
         }
 
@@ -3812,7 +5643,11 @@ impl EvaluationResult {
     }
 
     /// Parse a valid JSON object into EvaluationResult
-    fn parse_json_object(json: &serde_json::Value, original_response: &str) -> Self {
+    fn parse_json_object(
+        json: &serde_json::Value,
+        original_response: &str,
+        basis: &'static str,
+    ) -> Self {
         // Handle decision field - support both string and boolean types
         let decision = json
             .get("decision")
@@ -3859,6 +5694,7 @@ impl EvaluationResult {
             decision,
             response: response_content,
             reasoning: original_response.to_string(),
+            basis,
         }
     }
 
@@ -3944,6 +5780,7 @@ impl EvaluationResult {
             decision,
             response: response_content,
             reasoning: response.to_string(),
+            basis: "keyword_fallback",
         }
     }
 
@@ -3978,11 +5815,74 @@ impl EvaluationResult {
     }
 }
 
+/// Wrap a raw stream chunk, reading its event type from the chunk itself
+fn stream_chunk_event(timestamp: chrono::DateTime<chrono::Utc>, raw_json: String) -> SSEEvent {
+    let event_type = serde_json::from_str::<serde_json::Value>(&raw_json)
+        .ok()
+        .and_then(|chunk| match chunk.get("type") {
+            Some(kind) => kind.as_str().map(str::to_string),
+            // Nova chunks are keyed by their event type instead
+            None => chunk.as_object()?.keys().next().cloned(),
+        });
+    SSEEvent {
+        timestamp,
+        raw_json,
+        event_type,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agent::config::ExecutionConfig;
+    use crate::agent::test_support::{self, ScriptedProvider};
     use crate::agent::Agent;
-    use crate::tools::ToolRegistry;
+    use crate::tools::{Tool, ToolError, ToolRegistry};
+
+    #[test]
+    fn test_stream_chunk_event_type() {
+        let now = chrono::Utc::now();
+        let claude = stream_chunk_event(
+            now,
+            r#"{"type":"content_block_delta","delta":{"text":"Hi"}}"#.to_string(),
+        );
+        assert_eq!(claude.event_type.as_deref(), Some("content_block_delta"));
+
+        let nova = stream_chunk_event(now, r#"{"messageStop":{}}"#.to_string());
+        assert_eq!(nova.event_type.as_deref(), Some("messageStop"));
+
+        let invalid = stream_chunk_event(now, "not json".to_string());
+        assert_eq!(invalid.event_type, None);
+        assert_eq!(invalid.raw_json, "not json");
+    }
+
+    #[test]
+    fn test_evaluation_basis() {
+        let json = EvaluationResult::parse_evaluation_response(
+            r#"{"decision": "CONTINUE", "response": "Add tests"}"#,
+        );
+        assert!(json.decision);
+        assert_eq!(json.basis, "json");
+
+        let extracted = EvaluationResult::parse_evaluation_response(
+            "Verdict:\n```json\n{\"decision\": \"STOP\", \"response\": \"\"}\n```",
+        );
+        assert!(!extracted.decision);
+        assert_eq!(extracted.basis, "extracted_json");
+
+        // A stray keyword is enough to continue, which the basis makes visible
+        let fallback =
+            EvaluationResult::parse_evaluation_response("Looks done, nothing is missing");
+        assert!(fallback.decision);
+        assert_eq!(fallback.basis, "keyword_fallback");
+
+        let long = "x".repeat(EVALUATION_TRACE_CHARS + 10);
+        assert_eq!(
+            evaluation_preview(&long).chars().count(),
+            EVALUATION_TRACE_CHARS + 1
+        );
+        assert_eq!(evaluation_preview("short"), "short");
+    }
 
     #[tokio::test]
     async fn test_event_loop_creation() {
@@ -4077,6 +5977,156 @@ mod tests {
         assert!(matches!(last_event, StreamEvent::MessageStop(_)));
     }
 
+    /// Tool that never finishes, recording why it was cancelled
+    #[derive(Debug, Default)]
+    struct HangingTool {
+        cancellations: Arc<std::sync::Mutex<Vec<ToolCancelReason>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Tool for HangingTool {
+        fn name(&self) -> &str {
+            "hang"
+        }
+
+        fn description(&self) -> &str {
+            "Never returns"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(
+            &self,
+            _parameters: Option<serde_json::Value>,
+            _agent_context: Option<&crate::agent::AgentContext>,
+        ) -> std::result::Result<crate::tools::ToolResult, ToolError> {
+            futures::future::pending().await
+        }
+
+        async fn on_cancel(&self, reason: ToolCancelReason) {
+            self.cancellations.lock().unwrap().push(reason);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_early_started_tool_times_out() {
+        let tool = HangingTool::default();
+        let cancellations = tool.cancellations.clone();
+        let provider = Arc::new(ScriptedProvider::new([test_support::tool_call(
+            "call-1",
+            "hang",
+            serde_json::json!({}),
+        )]));
+        let timeout = Duration::from_millis(100);
+        let config = ExecutionConfig {
+            streaming: true,
+            event_loop: EventLoopConfig {
+                early_tool_start: true,
+                tool_config: ExecutorConfig {
+                    execution_timeout: timeout,
+                    ..ExecutorConfig::default()
+                },
+                ..EventLoopConfig::default()
+            },
+            ..ExecutionConfig::default()
+        };
+        let mut agent = test_support::agent(provider.clone(), vec![Box::new(tool)], config).await;
+
+        let result = tokio::time::timeout(Duration::from_secs(5), agent.execute("Hang"))
+            .await
+            .expect("early started tool was not timed out")
+            .unwrap();
+
+        assert_eq!(result.tools_failed, vec!["hang".to_string()]);
+        assert_eq!(
+            *cancellations.lock().unwrap(),
+            vec![ToolCancelReason::Timeout(timeout)]
+        );
+        // The model is told about the timeout
+        let requests = provider.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(format!("{:?}", requests[1].messages.last()).contains("timed out"));
+    }
+
+    /// Hook that records where it ran and tags each model call's conversation
+    #[derive(Debug, Default)]
+    struct RecordingHook {
+        calls: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CycleHook for RecordingHook {
+        async fn before_model_call(
+            &self,
+            messages: &mut crate::types::Messages,
+            ctx: &CycleHookContext,
+        ) -> Result<()> {
+            let tag = format!("[model call {}]", ctx.model_call_number);
+            crate::agent::hooks::append_context(messages, tag.as_str());
+            self.calls.lock().unwrap().push(tag);
+            Ok(())
+        }
+
+        async fn before_tool_batch(
+            &self,
+            _messages: &mut crate::types::Messages,
+            tool_uses: &[crate::tools::ToolUse],
+            _ctx: &CycleHookContext,
+        ) -> Result<()> {
+            let names: Vec<&str> = tool_uses.iter().map(|t| t.name.as_str()).collect();
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("[tools {}]", names.join(", ")));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cycle_hooks_run_between_cycles() {
+        for streaming in [false, true] {
+            let hook = Arc::new(RecordingHook::default());
+            let provider = Arc::new(ScriptedProvider::new([
+                test_support::tool_call(
+                    "call-1",
+                    "calculator",
+                    serde_json::json!({ "expression": "2 + 2" }),
+                ),
+                test_support::text("4"),
+            ]));
+            let config = ExecutionConfig {
+                streaming,
+                event_loop: EventLoopConfig {
+                    cycle_hooks: vec![hook.clone()],
+                    ..EventLoopConfig::default()
+                },
+                ..ExecutionConfig::default()
+            };
+            let tools: Vec<Box<dyn Tool>> =
+                vec![Box::new(crate::tools::builtin::CalculatorTool::new())];
+            let mut agent = test_support::agent(provider.clone(), tools, config).await;
+
+            let result = agent.execute("What is 2 + 2?").await.unwrap();
+
+            assert_eq!(result.response, "4", "streaming: {}", streaming);
+            assert_eq!(
+                *hook.calls.lock().unwrap(),
+                ["[model call 1]", "[tools calculator]", "[model call 2]"],
+                "streaming: {}",
+                streaming
+            );
+            // What the hook added before each call is what the model received
+            let requests = provider.requests();
+            assert_eq!(requests.len(), 2);
+            let last_message = |i: usize| format!("{:?}", requests[i].messages.last());
+            assert!(last_message(0).contains("[model call 1]"));
+            assert!(last_message(1).contains("[model call 2]"));
+            assert!(last_message(1).contains("call-1"), "{}", last_message(1));
+        }
+    }
+
     // Test helper that implements StreamCallback
     #[allow(dead_code)]
     struct TestStreamCallback {
@@ -0,0 +1,395 @@
+//! Step-by-step record of the decisions made by the event loop.
+//!
+//! With tracing enabled ([`AgentBuilder::with_execution_trace`]), every model
+//! request and response, tool execution, evaluation verdict, context recovery
+//! action and the final stop decision is recorded in an [`ExecutionTrace`],
+//! returned in [`AgentResult::execution_trace`]. The trace answers questions
+//! such as why an agent kept looping or stopped early, after the fact.
+//!
+//! Traces serialize to JSON for storage and diffing, and render to a
+//! self-contained HTML report for reading in a browser:
+//!
+//! ```no_run
+//! # use stood::agent::Agent;
+//! # async fn example(mut agent: Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let result = agent.execute("Plan a trip to Lisbon").await?;
+//! if let Some(trace) = &result.execution_trace {
+//!     std::fs::write("trace.json", trace.to_json())?;
+//!     std::fs::write("trace.html", trace.render_html())?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Traces hold full copies of the conversation for every model request, so
+//! they grow quickly and should only be enabled while debugging.
+//!
+//! [`AgentBuilder::with_execution_trace`]: crate::agent::AgentBuilder::with_execution_trace
+//! [`AgentResult::execution_trace`]: crate::agent::AgentResult::execution_trace
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::llm::traits::{ToolCall, Usage};
+
+/// Recorded decisions of one agent execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    /// When the execution started
+    pub started_at: DateTime<Utc>,
+    /// Recorded events in the order they happened
+    pub entries: Vec<TraceEntry>,
+    #[serde(skip)]
+    start: Option<Instant>,
+}
+
+/// One recorded event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// Time since the execution started
+    pub elapsed_ms: u64,
+    /// Event loop cycle the event belongs to (1-based)
+    pub cycle: u32,
+    /// What happened
+    #[serde(flatten)]
+    pub event: TraceEvent,
+}
+
+/// A decision or action of the event loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// A request sent to the model
+    ModelRequest {
+        /// Model the request was sent to
+        model_id: String,
+        /// Conversation sent, including the system prompt
+        messages: Value,
+        /// Names of the tools offered to the model
+        tools: Vec<String>,
+        /// Request body as sent by the provider, if it captures it
+        raw_request: Option<Value>,
+    },
+    /// The model's response
+    ModelResponse {
+        /// Response text
+        content: String,
+        /// Tools the model asked to call
+        tool_calls: Vec<ToolCall>,
+        /// Token usage reported by the provider
+        usage: Option<Usage>,
+        /// How long the model call took
+        duration_ms: u64,
+        /// Provider-specific response metadata
+        metadata: HashMap<String, Value>,
+    },
+    /// A tool call and its result
+    ToolExecution {
+        /// Id of the call
+        tool_use_id: String,
+        /// Tool name
+        tool_name: String,
+        /// Tool input
+        input: Value,
+        /// Whether the call succeeded
+        success: bool,
+        /// Tool output
+        output: Option<Value>,
+        /// Error message, if the call failed
+        error: Option<String>,
+        /// How long the call took
+        duration_ms: u64,
+    },
+    /// Verdict of the evaluation strategy on whether to continue
+    Evaluation {
+        /// Name of the evaluation strategy
+        strategy: String,
+        /// Whether the strategy decided to run another cycle
+        #[serde(rename = "continue")]
+        should_continue: bool,
+        /// The strategy's reasoning
+        reasoning: String,
+        /// Message sent to the model to continue, if any
+        feedback: Option<String>,
+        /// How long the evaluation took
+        duration_ms: u64,
+    },
+    /// The event loop repaired the conversation or the response
+    ContextRecovery {
+        /// What was done
+        action: RecoveryAction,
+        /// Details such as the error that triggered the recovery
+        detail: String,
+    },
+    /// The event loop stopped
+    Stop {
+        /// Why it stopped
+        reason: String,
+        /// Whether a limit (cycles, duration, tokens, tool iterations) was hit
+        budget_exhausted: bool,
+    },
+}
+
+/// Kinds of context recovery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// Older messages were replaced by a summary
+    Compaction,
+    /// An empty or incomplete response was rebuilt from the tool outputs
+    ResponseRecovery,
+    /// Tool execution failed and the model was told so
+    ToolFailureFallback,
+    /// The model call after tool execution failed and was replaced by an
+    /// error response
+    FollowUpFailure,
+}
+
+impl ExecutionTrace {
+    /// Start an empty trace
+    pub fn new() -> Self {
+        Self {
+            started_at: Utc::now(),
+            entries: Vec::new(),
+            start: Some(Instant::now()),
+        }
+    }
+
+    /// Record an event of `cycle`
+    pub fn record(&mut self, cycle: u32, event: TraceEvent) {
+        let elapsed = self.start.map_or(Duration::ZERO, |start| start.elapsed());
+        self.entries.push(TraceEntry {
+            elapsed_ms: elapsed.as_millis() as u64,
+            cycle,
+            event,
+        });
+    }
+
+    /// Why the execution stopped, if the trace recorded it
+    pub fn stop_reason(&self) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find_map(|entry| match &entry.event {
+                TraceEvent::Stop { reason, .. } => Some(reason.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Serialize the trace to pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("ExecutionTrace serialization cannot fail")
+    }
+
+    /// Load a trace saved with [`to_json`](Self::to_json)
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Render the trace as a self-contained HTML report
+    ///
+    /// Each event is a collapsible row with a one-line summary; expanding it
+    /// shows the full recorded data.
+    pub fn render_html(&self) -> String {
+        let mut html = String::from(HTML_HEAD);
+        let _ = write!(
+            html,
+            "<h1>Execution trace</h1>\n<p>Started {} &middot; {} events &middot; stopped: <b>{}</b></p>\n",
+            self.started_at.to_rfc3339(),
+            self.entries.len(),
+            escape_html(self.stop_reason().unwrap_or("unknown"))
+        );
+
+        let mut cycle = None;
+        for entry in &self.entries {
+            if cycle != Some(entry.cycle) {
+                let _ = writeln!(html, "<h2>Cycle {}</h2>", entry.cycle);
+                cycle = Some(entry.cycle);
+            }
+            let details = serde_json::to_string_pretty(&entry.event).unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<details class=\"{kind}\"><summary><span class=\"time\">+{ms} ms</span> \
+                 <span class=\"kind\">{kind}</span> {summary}</summary><pre>{details}</pre></details>",
+                kind = entry.event.kind(),
+                ms = entry.elapsed_ms,
+                summary = escape_html(&entry.event.summary()),
+                details = escape_html(&details)
+            );
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+impl Default for ExecutionTrace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceEvent {
+    /// Serialized name of the event kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TraceEvent::ModelRequest { .. } => "model_request",
+            TraceEvent::ModelResponse { .. } => "model_response",
+            TraceEvent::ToolExecution { .. } => "tool_execution",
+            TraceEvent::Evaluation { .. } => "evaluation",
+            TraceEvent::ContextRecovery { .. } => "context_recovery",
+            TraceEvent::Stop { .. } => "stop",
+        }
+    }
+
+    /// One-line description of the event
+    pub fn summary(&self) -> String {
+        match self {
+            TraceEvent::ModelRequest {
+                model_id,
+                messages,
+                tools,
+                ..
+            } => format!(
+                "{} with {} messages and {} tools",
+                model_id,
+                messages["messages"].as_array().map_or(0, Vec::len),
+                tools.len()
+            ),
+            TraceEvent::ModelResponse {
+                content,
+                tool_calls,
+                duration_ms,
+                ..
+            } => {
+                let mut summary = format!("{} chars in {} ms", content.len(), duration_ms);
+                if !tool_calls.is_empty() {
+                    let names: Vec<_> = tool_calls.iter().map(|c| c.name.as_str()).collect();
+                    let _ = write!(summary, ", calls {}", names.join(", "));
+                }
+                summary
+            }
+            TraceEvent::ToolExecution {
+                tool_name,
+                success,
+                duration_ms,
+                ..
+            } => format!(
+                "{} {} in {} ms",
+                tool_name,
+                if *success { "succeeded" } else { "failed" },
+                duration_ms
+            ),
+            TraceEvent::Evaluation {
+                strategy,
+                should_continue,
+                ..
+            } => format!(
+                "{} decided to {}",
+                strategy,
+                if *should_continue { "continue" } else { "stop" }
+            ),
+            TraceEvent::ContextRecovery { action, detail } => {
+                format!("{:?}: {}", action, detail)
+            }
+            TraceEvent::Stop { reason, .. } => reason.clone(),
+        }
+    }
+}
+
+const HTML_HEAD: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Execution trace</title>
+<style>
+body { font-family: sans-serif; margin: 2em; }
+details { border-left: 4px solid #ccc; margin: 4px 0; padding: 2px 8px; }
+summary { cursor: pointer; }
+pre { background: #f6f6f6; padding: 8px; overflow-x: auto; }
+.time { color: #888; font-family: monospace; }
+.kind { font-weight: bold; }
+.model_request, .model_response { border-color: #4a7bd0; }
+.tool_execution { border-color: #3a9a5b; }
+.evaluation { border-color: #c98a1b; }
+.context_recovery { border-color: #b04ac0; }
+.stop { border-color: #c0392b; }
+</style>
+</head>
+<body>
+"#;
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_trace() -> ExecutionTrace {
+        let mut trace = ExecutionTrace::new();
+        trace.record(
+            1,
+            TraceEvent::ToolExecution {
+                tool_use_id: "t1".to_string(),
+                tool_name: "search".to_string(),
+                input: json!({"query": "<b>rust</b>"}),
+                success: true,
+                output: Some(json!("found")),
+                error: None,
+                duration_ms: 3,
+            },
+        );
+        trace.record(
+            1,
+            TraceEvent::Evaluation {
+                strategy: "task_evaluation".to_string(),
+                should_continue: true,
+                reasoning: "Missing the summary".to_string(),
+                feedback: Some("Add a summary".to_string()),
+                duration_ms: 12,
+            },
+        );
+        trace.record(
+            2,
+            TraceEvent::Stop {
+                reason: "reached maximum of 2 cycles".to_string(),
+                budget_exhausted: true,
+            },
+        );
+        trace
+    }
+
+    #[test]
+    fn test_trace_json_round_trip() {
+        let trace = sample_trace();
+        let value: Value = serde_json::from_str(&trace.to_json()).unwrap();
+        assert_eq!(value["entries"][1]["kind"], "evaluation");
+        assert_eq!(value["entries"][1]["continue"], true);
+        assert_eq!(value["entries"][1]["cycle"], 1);
+
+        let loaded = ExecutionTrace::from_json(&trace.to_json()).unwrap();
+        assert_eq!(loaded.entries.len(), 3);
+        assert_eq!(loaded.stop_reason(), Some("reached maximum of 2 cycles"));
+    }
+
+    #[test]
+    fn test_render_html_report() {
+        let html = sample_trace().render_html();
+        assert!(html.contains("stopped: <b>reached maximum of 2 cycles</b>"));
+        assert!(html.contains("<h2>Cycle 2</h2>"));
+        assert!(html.contains("task_evaluation decided to continue"));
+        assert!(html.contains("&lt;b&gt;rust&lt;/b&gt;"));
+        assert!(!html.contains("<b>rust</b>"));
+    }
+}
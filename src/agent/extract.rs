@@ -0,0 +1,428 @@
+//! Typed information extraction.
+//!
+//! [`Agent::extract`] turns text (or a prompt) into a value of any type that
+//! implements [`serde::Deserialize`] and [`JsonSchema`]. The JSON schema of the
+//! type is included in the request, the reply is parsed into the type, and when
+//! parsing fails the error is sent back to the model so it can correct itself,
+//! up to [`ExtractionOptions::max_retries`] times.
+//!
+//! Extraction is a single model request (plus retries) outside the agent loop:
+//! tools are not offered and the conversation history is not changed.
+//!
+//! Replies that are nearly valid JSON — a trailing comma, or a streamed reply
+//! cut off inside a string — are fixed with
+//! [`repair_json`](crate::streaming::json_repair::repair_json) before they are
+//! validated, instead of costing a retry. See [`ExtractionOptions::with_json_repair`].
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! use stood::schemars::JsonSchema;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, JsonSchema)]
+//! #[schemars(crate = "stood::schemars")]
+//! struct Invoice {
+//!     /// Invoice number as printed on the document
+//!     number: String,
+//!     total: f64,
+//!     due_date: Option<String>,
+//! }
+//!
+//! # async fn example(agent: Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let result = agent
+//!     .extract::<Invoice>("Invoice INV-042, total due $1,250.00 by March 3rd")
+//!     .await?;
+//! println!("{:?} (confidence {:.2})", result.value, result.confidence());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::Agent;
+use crate::llm::traits::{ChatConfig, ContentBlockDelta, StreamEvent};
+use crate::streaming::json_repair::parse_repaired;
+use crate::types::Messages;
+use crate::{Result, StoodError};
+
+pub use schemars::JsonSchema;
+
+const EXTRACTION_SYSTEM_PROMPT: &str = "You extract structured information. Reply with a \
+single JSON object and nothing else, in the form {\"data\": <value matching the schema>, \
+\"confidence\": <number between 0 and 1>}. The confidence is how sure you are that the data is \
+complete and correct. Use null for optional fields the input does not provide; never invent \
+values.";
+
+/// Options for [`Agent::extract_with`]
+#[derive(Debug, Clone)]
+pub struct ExtractionOptions {
+    /// Retries after a reply that cannot be parsed (default: 2)
+    pub max_retries: u32,
+    /// Additional instructions, e.g. how to normalize dates
+    pub instructions: Option<String>,
+    /// Sampling temperature (default: 0.0)
+    pub temperature: Option<f32>,
+    /// Maximum tokens of each reply (default: the agent's setting)
+    pub max_tokens: Option<u32>,
+    /// Stream each reply instead of waiting for the complete response (default: false)
+    pub streaming: bool,
+    /// Repair nearly valid JSON before validating it (default: true)
+    pub repair_json: bool,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            instructions: None,
+            temperature: Some(0.0),
+            max_tokens: None,
+            streaming: false,
+            repair_json: true,
+        }
+    }
+}
+
+impl ExtractionOptions {
+    /// Set the number of retries after unparseable replies
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Add instructions to the extraction request
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Stream replies from the provider
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Repair trailing commas, unterminated strings and unclosed brackets in
+    /// replies before validating them, instead of retrying
+    pub fn with_json_repair(mut self, repair: bool) -> Self {
+        self.repair_json = repair;
+        self
+    }
+}
+
+/// A value extracted by [`Agent::extract`], with metadata about how reliable it is
+#[derive(Debug, Clone)]
+pub struct ExtractionResult<T> {
+    /// The extracted value
+    pub value: T,
+    /// Model requests made, including retries
+    pub attempts: u32,
+    /// Confidence reported by the model, between 0.0 and 1.0
+    pub model_confidence: Option<f64>,
+    /// Top-level schema fields that were null or absent in the reply, sorted
+    pub missing_fields: Vec<String>,
+    /// Top-level fields defined by the schema
+    pub total_fields: usize,
+    /// Why earlier attempts were rejected
+    pub retry_errors: Vec<String>,
+    /// The reply the value was parsed from
+    pub raw_response: String,
+    /// Whether the reply had to be repaired to be valid JSON; the last value
+    /// of a truncated reply may be incomplete
+    pub repaired: bool,
+    /// Time spent on all attempts
+    pub duration: Duration,
+}
+
+impl<T> ExtractionResult<T> {
+    /// Overall confidence in the extraction, between 0.0 and 1.0
+    ///
+    /// The model's reported confidence (1.0 if it reported none), scaled by the
+    /// fraction of schema fields that were filled in and reduced by 10% for
+    /// each retry that was needed.
+    pub fn confidence(&self) -> f64 {
+        let completeness = if self.total_fields == 0 {
+            1.0
+        } else {
+            1.0 - self.missing_fields.len() as f64 / self.total_fields as f64
+        };
+        let retries = self.attempts.saturating_sub(1) as i32;
+        self.model_confidence.unwrap_or(1.0) * completeness * 0.9f64.powi(retries)
+    }
+}
+
+impl Agent {
+    /// Extract a value of type `T` from `input`
+    ///
+    /// `input` is either the text to extract from or a prompt describing what to
+    /// produce. Retries up to twice with the parse error as feedback. See
+    /// [`extract_with`](Self::extract_with) to change the options.
+    pub async fn extract<T>(&self, input: impl Into<String>) -> Result<ExtractionResult<T>>
+    where
+        T: DeserializeOwned + JsonSchema,
+    {
+        self.extract_with(input, ExtractionOptions::default()).await
+    }
+
+    /// Extract a value of type `T` from `input` with custom options
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoodError::ModelError`] if the model cannot be called or no
+    /// reply could be parsed into `T` within the allowed retries.
+    pub async fn extract_with<T>(
+        &self,
+        input: impl Into<String>,
+        options: ExtractionOptions,
+    ) -> Result<ExtractionResult<T>>
+    where
+        T: DeserializeOwned + JsonSchema,
+    {
+        let started = Instant::now();
+        let schema = schemars::schema_for!(T).to_value();
+        let model_id = self.model().model_id().to_string();
+        let config = ChatConfig {
+            model_id: model_id.clone(),
+            provider: self.model().provider(),
+            temperature: options.temperature,
+            max_tokens: options.max_tokens.or(self.config.max_tokens),
+            ..ChatConfig::default()
+        };
+
+        let mut system_prompt = EXTRACTION_SYSTEM_PROMPT.to_string();
+        if let Some(instructions) = &options.instructions {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(instructions);
+        }
+        let mut messages = Messages::new();
+        messages.add_system_message(&system_prompt);
+        messages.add_user_message(&extraction_prompt(&schema, &input.into()));
+
+        let mut retry_errors = Vec::new();
+        for attempt in 1..=options.max_retries + 1 {
+            let reply = self
+                .extraction_reply(&model_id, &messages, &config, options.streaming)
+                .await?;
+
+            match parse_extraction::<T>(&reply, &schema, options.repair_json) {
+                Ok(parsed) => {
+                    return Ok(ExtractionResult {
+                        value: parsed.value,
+                        attempts: attempt,
+                        model_confidence: parsed.confidence,
+                        missing_fields: parsed.missing_fields,
+                        total_fields: parsed.total_fields,
+                        retry_errors,
+                        raw_response: reply,
+                        repaired: parsed.repaired,
+                        duration: started.elapsed(),
+                    });
+                }
+                Err(error) => {
+                    tracing::debug!(attempt, error = %error, "Extraction reply rejected");
+                    messages.add_assistant_message(&reply);
+                    messages.add_user_message(&format!(
+                        "Your reply could not be used: {}\nReply again with only the corrected \
+                         JSON object.",
+                        error
+                    ));
+                    retry_errors.push(error);
+                }
+            }
+        }
+
+        Err(StoodError::model_error(format!(
+            "Extraction failed after {} attempts: {}",
+            retry_errors.len(),
+            retry_errors
+                .last()
+                .map(String::as_str)
+                .unwrap_or("no reply")
+        )))
+    }
+
+    /// Text of one reply, streamed or not
+    async fn extraction_reply(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        config: &ChatConfig,
+        streaming: bool,
+    ) -> Result<String> {
+        let call_failed = |e: &dyn std::fmt::Display| {
+            StoodError::model_error(format!("Extraction call failed: {}", e))
+        };
+        if !streaming {
+            let response = self
+                .provider()
+                .chat(model_id, messages, config)
+                .await
+                .map_err(|e| call_failed(&e))?;
+            return Ok(response.content);
+        }
+
+        let mut stream = self
+            .provider()
+            .chat_streaming(model_id, messages, config)
+            .await
+            .map_err(|e| call_failed(&e))?;
+        let mut reply = String::new();
+        while let Some(event) = stream.next().await {
+            match event {
+                StreamEvent::ContentBlockDelta {
+                    delta: ContentBlockDelta::Text { text },
+                    ..
+                } => reply.push_str(&text),
+                StreamEvent::ContentDelta { delta, .. } => reply.push_str(&delta),
+                StreamEvent::Error { error } => return Err(call_failed(&error)),
+                _ => {}
+            }
+        }
+        Ok(reply)
+    }
+}
+
+fn extraction_prompt(schema: &Value, input: &str) -> String {
+    format!(
+        "Extract data matching this JSON schema:\n{}\n\nInput:\n{}",
+        serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string()),
+        input
+    )
+}
+
+struct ParsedExtraction<T> {
+    value: T,
+    confidence: Option<f64>,
+    missing_fields: Vec<String>,
+    total_fields: usize,
+    repaired: bool,
+}
+
+fn parse_extraction<T: DeserializeOwned>(
+    reply: &str,
+    schema: &Value,
+    repair: bool,
+) -> std::result::Result<ParsedExtraction<T>, String> {
+    let start = reply
+        .find(['{', '['])
+        .ok_or("the reply does not contain a JSON object")?;
+    let (mut envelope, repaired) = match json_candidate(reply).map(serde_json::from_str::<Value>) {
+        Some(Ok(envelope)) => (envelope, false),
+        // A truncated reply may not end with a closing bracket at all
+        _ if repair => {
+            let tail = reply[start..].trim_end().trim_end_matches("```");
+            parse_repaired(tail).ok_or("the reply is not valid JSON and could not be repaired")?
+        }
+        Some(Err(e)) => return Err(format!("the reply is not valid JSON: {}", e)),
+        None => return Err("the reply does not contain a JSON object".to_string()),
+    };
+
+    // Accept the bare value when the model omitted the envelope
+    let (data, confidence) = match envelope.get_mut("data") {
+        Some(data) => (
+            data.take(),
+            envelope
+                .get("confidence")
+                .and_then(Value::as_f64)
+                .map(|c| c.clamp(0.0, 1.0)),
+        ),
+        None => (envelope, None),
+    };
+
+    let fields: Vec<&String> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| properties.keys().collect())
+        .unwrap_or_default();
+    // Sorted, as the order of schema properties depends on serde_json features
+    let mut missing_fields: Vec<String> = fields
+        .iter()
+        .filter(|field| data.get(field.as_str()).is_none_or(Value::is_null))
+        .map(|field| field.to_string())
+        .collect();
+    missing_fields.sort();
+
+    let value = serde_json::from_value(data)
+        .map_err(|e| format!("the data does not match the schema: {}", e))?;
+    Ok(ParsedExtraction {
+        value,
+        confidence,
+        missing_fields,
+        total_fields: fields.len(),
+        repaired,
+    })
+}
+
+/// The JSON part of a reply, without code fences or surrounding prose
+fn json_candidate(reply: &str) -> Option<&str> {
+    let start = reply.find(['{', '['])?;
+    let end = reply.rfind(['}', ']'])?;
+    (start < end).then(|| &reply[start..=end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+    struct Contact {
+        name: String,
+        email: Option<String>,
+        age: Option<u32>,
+    }
+
+    #[test]
+    fn test_parse_extraction_envelope_and_errors() {
+        let schema = schemars::schema_for!(Contact).to_value();
+        assert!(extraction_prompt(&schema, "Ada").contains("\"email\""));
+
+        let reply = "Here you go:\n```json\n{\"data\": {\"name\": \"Ada\", \"email\": null}, \
+                     \"confidence\": 0.8}\n```";
+        let parsed = parse_extraction::<Contact>(reply, &schema, true).unwrap();
+        assert_eq!(parsed.value.name, "Ada");
+        assert_eq!(parsed.confidence, Some(0.8));
+        assert_eq!(parsed.missing_fields, vec!["age", "email"]);
+
+        let result = ExtractionResult {
+            value: parsed.value,
+            attempts: 2,
+            model_confidence: parsed.confidence,
+            missing_fields: parsed.missing_fields,
+            total_fields: parsed.total_fields,
+            retry_errors: vec![],
+            raw_response: reply.to_string(),
+            repaired: parsed.repaired,
+            duration: Duration::ZERO,
+        };
+        assert!((result.confidence() - 0.8 / 3.0 * 0.9).abs() < 1e-9);
+
+        let bare = parse_extraction::<Contact>("{\"name\": \"Bob\", \"age\": 40}", &schema, true);
+        assert_eq!(bare.unwrap().value.age, Some(40));
+        assert!(parse_extraction::<Contact>("no json here", &schema, true).is_err());
+        let mismatch = parse_extraction::<Contact>("{\"data\": {\"age\": 3}}", &schema, true);
+        assert!(mismatch
+            .err()
+            .unwrap()
+            .contains("does not match the schema"));
+    }
+
+    #[test]
+    fn test_parse_extraction_repairs_truncated_reply() {
+        let schema = schemars::schema_for!(Contact).to_value();
+        let truncated = "```json\n{\"data\": {\"name\": \"Ada Lovelace\", \"email\": \"ada@";
+
+        let parsed = parse_extraction::<Contact>(truncated, &schema, true).unwrap();
+        assert!(parsed.repaired);
+        assert_eq!(parsed.value.name, "Ada Lovelace");
+        assert_eq!(parsed.value.email.as_deref(), Some("ada@"));
+        assert!(parse_extraction::<Contact>(truncated, &schema, false).is_err());
+
+        let clean = parse_extraction::<Contact>("{\"name\": \"Bob\"}", &schema, true).unwrap();
+        assert!(!clean.repaired);
+    }
+}
@@ -0,0 +1,157 @@
+//! Cycle hooks for modifying the conversation during agentic execution.
+//!
+//! Tool middleware ([`crate::tools::middleware`]) intercepts individual tool
+//! calls but cannot touch the conversation. A [`CycleHook`] runs at fixed points
+//! in every cycle of the event loop and receives mutable access to the
+//! conversation [`Messages`], so applications can inject dynamic context such as
+//! the current time, a user profile or retrieved documents.
+//!
+//! ```text
+//! before_model_call → model → after_model_call → before_tool_batch → tools → (repeat)
+//! ```
+//!
+//! # Keeping the conversation valid
+//!
+//! Changes made by hooks persist in the conversation. Providers require each
+//! assistant `tool_use` block to be answered by a `tool_result` in the very next
+//! user message, so hooks should add content to the last user message rather
+//! than inserting new messages between a tool call and its result. The
+//! [`append_context`] helper does this.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use stood::agent::{Agent, CycleHook, CycleHookContext};
+//! use stood::agent::hooks::append_context;
+//! use stood::types::Messages;
+//! use async_trait::async_trait;
+//! use std::sync::Arc;
+//!
+//! #[derive(Debug)]
+//! struct CurrentTimeHook;
+//!
+//! #[async_trait]
+//! impl CycleHook for CurrentTimeHook {
+//!     async fn before_model_call(
+//!         &self,
+//!         messages: &mut Messages,
+//!         ctx: &CycleHookContext,
+//!     ) -> stood::Result<()> {
+//!         if ctx.model_call_number == 1 {
+//!             append_context(messages, format!("Current time: {}", chrono::Utc::now()));
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let agent = Agent::builder()
+//!     .with_cycle_hook(Arc::new(CurrentTimeHook))
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+
+use crate::llm::traits::ChatResponse;
+use crate::tools::ToolUse;
+use crate::types::{ContentBlock, Message, MessageRole, Messages};
+
+/// Information about the current point in the event loop, passed to hooks
+#[derive(Debug, Clone)]
+pub struct CycleHookContext {
+    /// Identifier of the executing agent
+    pub agent_id: String,
+    /// Name of the executing agent (if set)
+    pub agent_name: Option<String>,
+    /// 1-based number of the model call within this execution
+    pub model_call_number: u32,
+}
+
+/// Hook invoked at fixed points of each event loop cycle.
+///
+/// All methods default to doing nothing. Returning an error aborts the
+/// execution with that error.
+#[async_trait]
+pub trait CycleHook: Send + Sync + std::fmt::Debug {
+    /// Called before every model call with the conversation that will be sent
+    async fn before_model_call(
+        &self,
+        _messages: &mut Messages,
+        _ctx: &CycleHookContext,
+    ) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Called after every successful model call, before the response is added
+    /// to the conversation
+    async fn after_model_call(
+        &self,
+        _messages: &mut Messages,
+        _response: &ChatResponse,
+        _ctx: &CycleHookContext,
+    ) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Called before a batch of tool calls requested by the model is executed.
+    ///
+    /// The last message in `messages` is the assistant message containing the
+    /// tool calls; do not add messages after it.
+    async fn before_tool_batch(
+        &self,
+        _messages: &mut Messages,
+        _tool_uses: &[ToolUse],
+        _ctx: &CycleHookContext,
+    ) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Name of this hook for logging/debugging
+    fn name(&self) -> &str {
+        "unnamed_cycle_hook"
+    }
+}
+
+/// Add text to the last user message, or append a new user message if the
+/// conversation does not end with one.
+///
+/// Adding to the existing message keeps `tool_use`/`tool_result` pairs adjacent
+/// and avoids consecutive user messages.
+pub fn append_context(messages: &mut Messages, text: impl Into<String>) {
+    let text = text.into();
+    match messages.messages.last_mut() {
+        Some(last) if last.role == MessageRole::User => {
+            last.content.push(ContentBlock::Text { text });
+        }
+        _ => messages.push(Message::user(text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_context_extends_last_user_message() {
+        let mut messages = Messages::new();
+        messages.add_user_message("What time is it?");
+        append_context(&mut messages, "Current time: noon");
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages.messages[0].content.len(), 2);
+    }
+
+    #[test]
+    fn test_append_context_adds_user_message_after_assistant() {
+        let mut messages = Messages::new();
+        messages.add_user_message("Hi");
+        messages.push(Message::assistant("Hello"));
+        append_context(&mut messages, "Profile: admin");
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages.messages[2].role, MessageRole::User);
+    }
+}
@@ -935,9 +935,9 @@ async fn test_event_loop_handles_tool_failures() {
             _parameters: Option<Value>,
             _agent_context: Option<&crate::agent::AgentContext>,
         ) -> Result<ToolResult, ToolError> {
-            Err(ToolError::ExecutionFailed {
-                message: "This tool always fails for testing purposes".to_string(),
-            })
+            Err(ToolError::execution_failed(
+                "This tool always fails for testing purposes",
+            ))
         }
     }
 
@@ -1157,6 +1157,14 @@ async fn test_callback_integration_end_to_end() {
                 }
                 CallbackEvent::EvaluationStart { .. } => "EvaluationStart".to_string(),
                 CallbackEvent::EvaluationComplete { .. } => "EvaluationComplete".to_string(),
+                CallbackEvent::McpHealth { .. } => "McpHealth".to_string(),
+                CallbackEvent::Progress { .. } => "Progress".to_string(),
+                CallbackEvent::BehaviorAnomaly { anomaly, .. } => {
+                    format!("BehaviorAnomaly({})", anomaly.kind)
+                }
+                CallbackEvent::ToolProgress { event } => {
+                    format!("ToolProgress({}, {}%)", event.tool_name, event.percent)
+                }
             };
 
             self.events.lock().unwrap().push(event_description);
@@ -0,0 +1,483 @@
+//! Response language control.
+//!
+//! [`ResponseLanguage`] tells the model which language to answer in, either a
+//! fixed [`LanguageTag`] or the language detected in the user's prompt. The
+//! response is checked with [`detect_language`]; if it comes back in another
+//! language, the turn is run once more with a stricter instruction. Turns that
+//! called tools are not run again, so their tools are never called twice; the
+//! retry's model calls and tokens are added to the turn's result.
+//!
+//! ```no_run
+//! use stood::agent::{Agent, ExecuteOptions, LanguageTag, ResponseLanguage};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // Answer every user in the language they write in
+//! let mut agent = Agent::builder()
+//!     .with_response_language(ResponseLanguage::MatchUser)
+//!     .build()
+//!     .await?;
+//!
+//! // Except for this call, which must be answered in Brazilian Portuguese
+//! let result = agent
+//!     .execute_with_options(
+//!         "Summarize the attached contract",
+//!         ExecuteOptions::new().response_language(LanguageTag::new("pt-BR")),
+//!     )
+//!     .await?;
+//! if let Some(check) = &result.winner().execution.language {
+//!     println!("Detected {:?}, retried: {}", check.detected, check.retried);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Detection is a lightweight heuristic based on scripts and common words.
+//! It recognises Arabic, Chinese, Dutch, English, French, German, Greek,
+//! Hebrew, Hindi, Italian, Japanese, Korean, Portuguese, Russian, Spanish,
+//! Thai and Ukrainian, and returns `None` for short or ambiguous text, in
+//! which case the response is accepted as it is.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Fewest words of Latin-script text the detector will classify
+const MIN_LATIN_WORDS: usize = 4;
+
+/// BCP 47 language tag, e.g. `en`, `es` or `pt-BR`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LanguageTag(String);
+
+impl LanguageTag {
+    /// Normalize `tag` to the usual casing (`pt_br` becomes `pt-BR`)
+    pub fn new(tag: impl AsRef<str>) -> Self {
+        let normalized = tag
+            .as_ref()
+            .trim()
+            .split(['-', '_'])
+            .enumerate()
+            .map(|(i, part)| match (i, part.len()) {
+                (0, _) => part.to_lowercase(),
+                (_, 2) => part.to_uppercase(),
+                (_, 4) => {
+                    let mut chars = part.chars();
+                    chars.next().map_or_else(String::new, |first| {
+                        first
+                            .to_uppercase()
+                            .chain(chars.flat_map(char::to_lowercase))
+                            .collect()
+                    })
+                }
+                _ => part.to_lowercase(),
+            })
+            .collect::<Vec<_>>()
+            .join("-");
+        Self(normalized)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The language subtag, e.g. `pt` for `pt-BR`
+    pub fn primary(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+
+    /// English name of the language, if it is a common one
+    pub fn name(&self) -> Option<&'static str> {
+        LANGUAGE_NAMES
+            .iter()
+            .find(|(code, _)| *code == self.primary())
+            .map(|(_, name)| *name)
+    }
+
+    /// Whether both tags name the same language, ignoring region and script
+    pub fn same_language(&self, other: &LanguageTag) -> bool {
+        self.primary() == other.primary()
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for LanguageTag {
+    fn from(tag: &str) -> Self {
+        Self::new(tag)
+    }
+}
+
+/// Language the agent should respond in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseLanguage {
+    /// Always respond in this language
+    Fixed(LanguageTag),
+    /// Respond in the language of the user's prompt, when it can be detected
+    MatchUser,
+}
+
+impl ResponseLanguage {
+    /// The language to respond to `prompt` in, if known
+    pub fn resolve(&self, prompt: &str) -> Option<LanguageTag> {
+        match self {
+            ResponseLanguage::Fixed(tag) => Some(tag.clone()),
+            ResponseLanguage::MatchUser => detect_language(prompt),
+        }
+    }
+}
+
+/// Outcome of checking the response language of an execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageCheck {
+    /// Language the response was requested in
+    pub target: LanguageTag,
+    /// Language detected in the final response, if it could be detected
+    pub detected: Option<LanguageTag>,
+    /// Whether the turn was run again because the first response was in
+    /// another language
+    pub retried: bool,
+}
+
+impl LanguageCheck {
+    /// Check `response` against `target`
+    pub fn new(target: LanguageTag, response: &str) -> Self {
+        Self {
+            detected: detect_language(response),
+            target,
+            retried: false,
+        }
+    }
+
+    /// Whether the response is in the target language, or its language is unknown
+    pub fn is_match(&self) -> bool {
+        self.detected
+            .as_ref()
+            .is_none_or(|detected| detected.same_language(&self.target))
+    }
+}
+
+/// System prompt instruction to respond in `language`
+///
+/// `strict` is used when retrying after a response in the wrong language.
+pub(crate) fn instruction(language: &LanguageTag, strict: bool) -> String {
+    let name = match language.name() {
+        Some(name) => format!("{} ({})", name, language),
+        None => format!("the language with tag {}", language),
+    };
+    if strict {
+        format!(
+            "Your entire response MUST be written in {}. A previous response was in \
+             another language and was rejected. Translate any quoted material or tool \
+             output you use into {}.",
+            name, name
+        )
+    } else {
+        format!(
+            "Respond in {}, regardless of the language of tool output or other content.",
+            name
+        )
+    }
+}
+
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("zh", "Chinese"),
+];
+
+/// Frequent short words of Latin-script languages
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "of", "to", "in", "that", "it", "for", "with", "this",
+            "you", "was", "be", "on", "not", "have", "what", "can",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "de", "que", "y", "en", "es", "un", "una", "por", "con",
+            "para", "del", "se", "no", "lo", "como", "está",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "de", "des", "et", "est", "un", "une", "du", "que", "pour", "dans",
+            "pas", "avec", "sur", "vous", "nous", "ce", "je",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "zu", "mit", "den", "von",
+            "auf", "sie", "ich", "es", "für", "auch", "wie", "dem",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "la", "di", "che", "e", "è", "un", "una", "per", "non", "con", "gli", "sono",
+            "del", "della", "questo", "le", "si", "lo", "anche",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "de", "que", "e", "é", "um", "uma", "para", "com", "não", "do",
+            "da", "em", "no", "na", "se", "você",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "van", "is", "dat", "niet", "op", "te", "met", "voor",
+            "zijn", "ik", "je", "die", "ook", "maar", "wat", "er",
+        ],
+    ),
+];
+
+/// Detect the language of `text`, or `None` if it is too short or ambiguous
+pub fn detect_language(text: &str) -> Option<LanguageTag> {
+    if let Some(tag) = detect_script(text) {
+        return Some(LanguageTag::new(tag));
+    }
+
+    let lowercase = text.to_lowercase();
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.len() < MIN_LATIN_WORDS {
+        return None;
+    }
+
+    let mut scores: Vec<(&str, usize)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let hits = words.iter().filter(|w| stopwords.contains(w)).count();
+            (*code, hits)
+        })
+        .collect();
+    // Letters specific to one language break ties between close relatives
+    for (code, letters) in [("es", "ñ¿¡"), ("pt", "ãõ"), ("de", "ßäöü"), ("fr", "êœçë")]
+    {
+        if lowercase.chars().any(|c| letters.contains(c)) {
+            if let Some(score) = scores.iter_mut().find(|(c, _)| *c == code) {
+                score.1 += 2;
+            }
+        }
+    }
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+
+    let (best, best_hits) = scores[0];
+    let runner_up = scores[1].1;
+    // Require a clear winner backed by a meaningful share of the words
+    (best_hits >= 2 && best_hits > runner_up && best_hits * 10 >= words.len())
+        .then(|| LanguageTag::new(best))
+}
+
+/// Language of text written mostly in a non-Latin script
+fn detect_script(text: &str) -> Option<&'static str> {
+    let mut letters = 0usize;
+    let mut counts: Vec<(&'static str, usize)> = Vec::new();
+    let mut kana = false;
+    let mut ukrainian = false;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        let script = match c {
+            '\u{3040}'..='\u{30FF}' => {
+                kana = true;
+                "ja"
+            }
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => "zh",
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => "ko",
+            '\u{0400}'..='\u{04FF}' => {
+                ukrainian |= matches!(c, 'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ');
+                "ru"
+            }
+            '\u{0600}'..='\u{06FF}' => "ar",
+            '\u{0590}'..='\u{05FF}' => "he",
+            '\u{0370}'..='\u{03FF}' => "el",
+            '\u{0900}'..='\u{097F}' => "hi",
+            '\u{0E00}'..='\u{0E7F}' => "th",
+            _ => continue,
+        };
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some(count) => count.1 += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+
+    // Japanese mixes kanji with kana; kanji alone is taken as Chinese
+    if kana {
+        let cjk: usize = counts
+            .iter()
+            .filter(|(s, _)| matches!(*s, "ja" | "zh"))
+            .map(|(_, n)| n)
+            .sum();
+        counts.retain(|(s, _)| !matches!(*s, "ja" | "zh"));
+        counts.push(("ja", cjk));
+    }
+    let (script, count) = counts.into_iter().max_by_key(|(_, n)| *n)?;
+    if count * 2 < letters {
+        return None;
+    }
+    Some(if script == "ru" && ukrainian {
+        "uk"
+    } else {
+        script
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::config::ExecutionConfig;
+    use crate::agent::test_support::{self, ScriptedProvider};
+    use crate::agent::{Agent, QuotaLimits, QuotaManager, SessionQuota};
+    use crate::llm::traits::{ChatResponse, Usage};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_detects_languages() {
+        let detect = |text: &str| detect_language(text).map(|tag| tag.to_string());
+        assert_eq!(
+            detect("What is the weather going to be like in the city this weekend?").as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            detect("¿Cuál es el tiempo que hace en la ciudad este fin de semana?").as_deref(),
+            Some("es")
+        );
+        assert_eq!(
+            detect("Quel temps fera-t-il dans la ville pour le week-end ?").as_deref(),
+            Some("fr")
+        );
+        assert_eq!(
+            detect("Wie wird das Wetter in der Stadt am Wochenende?").as_deref(),
+            Some("de")
+        );
+        assert_eq!(detect("今週末の天気はどうですか").as_deref(), Some("ja"));
+        assert_eq!(
+            detect("Какая погода будет в выходные?").as_deref(),
+            Some("ru")
+        );
+        assert_eq!(detect("OK").as_deref(), None);
+        assert_eq!(
+            detect("fn main() { println!(\"{}\", 42); }").as_deref(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_language_tags_and_checks() {
+        let tag = LanguageTag::new("pt_br");
+        assert_eq!(tag.as_str(), "pt-BR");
+        assert_eq!(tag.name(), Some("Portuguese"));
+        assert_eq!(LanguageTag::new("ZH-hant-tw").as_str(), "zh-Hant-TW");
+
+        let check =
+            LanguageCheck::new(tag.clone(), "Este é um resumo do contrato que você enviou.");
+        assert!(check.is_match());
+        let check = LanguageCheck::new(tag, "This is a summary of the contract that you sent.");
+        assert!(!check.is_match());
+        assert!(instruction(&LanguageTag::new("es"), false).contains("Spanish (es)"));
+    }
+
+    const FRENCH: &str =
+        "Le contrat est signé et la livraison est prévue pour la semaine prochaine.";
+    const ENGLISH: &str = "The contract is signed and the delivery is planned for next week.";
+
+    fn answer(text: &str, input_tokens: u32, output_tokens: u32) -> ChatResponse {
+        ChatResponse {
+            usage: Some(Usage::new(input_tokens, output_tokens)),
+            ..test_support::text(text)
+        }
+    }
+
+    async fn english_agent(provider: Arc<ScriptedProvider>, quotas: &QuotaManager) -> Agent {
+        let mut agent = test_support::agent(
+            provider,
+            vec![Box::new(crate::tools::builtin::CalculatorTool::new())],
+            ExecutionConfig {
+                streaming: false,
+                ..ExecutionConfig::default()
+            },
+        )
+        .await;
+        agent.config.response_language = Some(ResponseLanguage::Fixed(LanguageTag::new("en")));
+        agent.config.quota = Some(SessionQuota {
+            manager: quotas.clone(),
+            session_id: "user".to_string(),
+        });
+        agent
+    }
+
+    #[tokio::test]
+    async fn test_retry_counts_the_turn_once() {
+        let provider = Arc::new(ScriptedProvider::new([
+            answer(FRENCH, 10, 5),
+            answer(ENGLISH, 20, 8),
+        ]));
+        let quotas = QuotaManager::new(QuotaLimits::new());
+        let mut agent = english_agent(provider.clone(), &quotas).await;
+
+        let result = agent.execute("Summarize the contract").await.unwrap();
+
+        assert_eq!(result.response, ENGLISH);
+        assert!(result.execution.language.as_ref().unwrap().retried);
+        assert_eq!(result.execution.model_calls, 2);
+        let tokens = result.execution.tokens.as_ref().unwrap();
+        assert_eq!((tokens.input_tokens, tokens.output_tokens), (30, 13));
+        let usage = quotas.usage("user");
+        assert_eq!(usage.executions_last_hour, 1);
+        assert_eq!(usage.tokens_last_day, 43);
+        assert_eq!(agent.metrics_history().len(), 1);
+        assert_eq!(agent.conversation().message_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_turn_with_tool_calls_is_not_retried() {
+        let provider = Arc::new(ScriptedProvider::new([
+            test_support::tool_call(
+                "call-1",
+                "calculator",
+                serde_json::json!({ "expression": "2+2" }),
+            ),
+            answer(FRENCH, 10, 5),
+        ]));
+        let quotas = QuotaManager::new(QuotaLimits::new());
+        let mut agent = english_agent(provider.clone(), &quotas).await;
+
+        let result = agent.execute("Summarize the contract").await.unwrap();
+
+        let check = result.execution.language.as_ref().unwrap();
+        assert!(!check.retried);
+        assert!(!check.is_match());
+        assert_eq!(result.tools_called, vec!["calculator".to_string()]);
+        assert_eq!(provider.requests().len(), 2);
+        assert_eq!(quotas.usage("user").executions_last_hour, 1);
+    }
+}
@@ -0,0 +1,390 @@
+//! Typed mailboxes with priorities and deadlines for multi-agent message passing.
+//!
+//! An [`AgentMailbox`] is a queue shared by its clones: any clone can send,
+//! and receivers get the highest priority message first, oldest first within
+//! a priority. Messages whose deadline passed before they were received are
+//! dropped and counted in [`AgentMailbox::expired`].
+//!
+//! A supervisor gives each worker a mailbox for instructions and shares one
+//! mailbox for results, so it collects results as workers finish instead of
+//! awaiting them in order. Registering a worker's cancellation token with
+//! [`AgentMailbox::preempt_on`] lets urgent instructions interrupt a running
+//! execution:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use stood::agent::{Agent, AgentMailbox, Envelope, Priority};
+//!
+//! # async fn example(mut worker: Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let instructions: AgentMailbox<String> = AgentMailbox::new();
+//! let results: AgentMailbox<String> = AgentMailbox::new();
+//!
+//! let (inbox, outbox) = (instructions.clone(), results.clone());
+//! tokio::spawn(async move {
+//!     while let Some(instruction) = inbox.recv().await {
+//!         let token = worker.reset_cancellation_token();
+//!         inbox.preempt_on(Priority::Urgent, token);
+//!         if let Ok(result) = worker.execute(&instruction.payload).await {
+//!             let _ = outbox.send(Envelope::new(result.response).from("worker"));
+//!         }
+//!     }
+//! });
+//!
+//! instructions.send(Envelope::new("Summarize the Q3 report".to_string()))?;
+//! instructions.send(
+//!     Envelope::new("Stop and check the outage ticket first".to_string())
+//!         .with_priority(Priority::Urgent)
+//!         .with_deadline(Duration::from_secs(30)),
+//! )?;
+//!
+//! while let Some(result) = results.recv().await {
+//!     println!("{}: {}", result.from.unwrap_or_default(), result.payload);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+use crate::{Result, StoodError};
+
+/// Priority of a message; higher priorities are received first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    /// Preempts workers registered with [`AgentMailbox::preempt_on`]
+    Urgent,
+}
+
+/// A message with its priority, deadline and sender
+#[derive(Debug, Clone)]
+pub struct Envelope<T> {
+    /// The message
+    pub payload: T,
+    /// Name of the sending agent, if given
+    pub from: Option<String>,
+    pub priority: Priority,
+    /// When the message stops being worth delivering
+    pub deadline: Option<Instant>,
+    /// Sequence number assigned when the message is sent
+    pub id: u64,
+}
+
+impl<T> Envelope<T> {
+    /// A message with [`Priority::Normal`] and no deadline
+    pub fn new(payload: T) -> Self {
+        Self {
+            payload,
+            from: None,
+            priority: Priority::Normal,
+            deadline: None,
+            id: 0,
+        }
+    }
+
+    /// Name the sending agent
+    pub fn from(mut self, sender: impl Into<String>) -> Self {
+        self.from = Some(sender.into());
+        self
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Drop the message if it is not received within `timeout`
+    pub fn with_deadline(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// Whether the deadline has passed
+    pub fn is_expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Heap entry ordered by priority, then by sequence number (oldest first)
+struct Queued<T>(Envelope<T>);
+
+impl<T> Ord for Queued<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .priority
+            .cmp(&other.0.priority)
+            .then_with(|| other.0.id.cmp(&self.0.id))
+    }
+}
+
+impl<T> PartialOrd for Queued<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> PartialEq for Queued<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Queued<T> {}
+
+struct Shared<T> {
+    queue: Mutex<BinaryHeap<Queued<T>>>,
+    preempt: Mutex<Vec<(Priority, CancellationToken)>>,
+    notify: Notify,
+    next_id: AtomicU64,
+    expired: AtomicU64,
+    closed: AtomicBool,
+}
+
+/// Priority queue of typed messages between agents
+///
+/// Clones share the same queue.
+pub struct AgentMailbox<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for AgentMailbox<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for AgentMailbox<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentMailbox")
+            .field("len", &self.len())
+            .field("expired", &self.expired())
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl<T> Default for AgentMailbox<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> AgentMailbox<T> {
+    /// An empty, open mailbox
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                queue: Mutex::new(BinaryHeap::new()),
+                preempt: Mutex::new(Vec::new()),
+                notify: Notify::new(),
+                next_id: AtomicU64::new(1),
+                expired: AtomicU64::new(0),
+                closed: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Queue a message, returning its id
+    ///
+    /// Cancels the tokens registered with [`preempt_on`](Self::preempt_on)
+    /// for the message's priority or lower. Fails once the mailbox is closed.
+    pub fn send(&self, mut envelope: Envelope<T>) -> Result<u64> {
+        if self.is_closed() {
+            return Err(StoodError::invalid_input("Mailbox is closed"));
+        }
+        let id = self.shared.next_id.fetch_add(1, AtomicOrdering::Relaxed);
+        envelope.id = id;
+        let priority = envelope.priority;
+        self.shared
+            .queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Queued(envelope));
+        self.shared.notify.notify_one();
+
+        self.shared
+            .preempt
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|(threshold, token)| {
+                if priority >= *threshold {
+                    token.cancel();
+                    false
+                } else {
+                    !token.is_cancelled()
+                }
+            });
+        Ok(id)
+    }
+
+    /// Receive the most urgent message without waiting
+    pub fn try_recv(&self) -> Option<Envelope<T>> {
+        let mut queue = self.shared.queue.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(Queued(envelope)) = queue.pop() {
+            if envelope.is_expired() {
+                self.shared.expired.fetch_add(1, AtomicOrdering::Relaxed);
+                continue;
+            }
+            return Some(envelope);
+        }
+        None
+    }
+
+    /// Wait for the most urgent message
+    ///
+    /// Returns `None` once the mailbox is closed and empty.
+    pub async fn recv(&self) -> Option<Envelope<T>> {
+        loop {
+            let notified = self.shared.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(envelope) = self.try_recv() {
+                return Some(envelope);
+            }
+            if self.is_closed() {
+                return None;
+            }
+            notified.await;
+        }
+    }
+
+    /// Wait at most `timeout` for a message
+    pub async fn recv_timeout(&self, timeout: Duration) -> Option<Envelope<T>> {
+        crate::runtime::timeout(timeout, self.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Priority of the most urgent queued message
+    ///
+    /// Lets a worker check for urgent instructions between steps.
+    pub fn peek_priority(&self) -> Option<Priority> {
+        self.shared
+            .queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|queued| !queued.0.is_expired())
+            .map(|queued| queued.0.priority)
+            .max()
+    }
+
+    /// Cancel `token` when a message of `priority` or higher is sent
+    ///
+    /// Pass an agent's [cancellation token](crate::agent::Agent::cancellation_token)
+    /// to interrupt its running execution. Each registration fires once;
+    /// register the agent's new token after
+    /// [resetting it](crate::agent::Agent::reset_cancellation_token).
+    pub fn preempt_on(&self, priority: Priority, token: CancellationToken) {
+        self.shared
+            .preempt
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((priority, token));
+    }
+
+    /// Stop accepting messages; receivers drain what is queued, then get `None`
+    pub fn close(&self) {
+        self.shared.closed.store(true, AtomicOrdering::SeqCst);
+        self.shared.notify.notify_waiters();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(AtomicOrdering::SeqCst)
+    }
+
+    /// Number of queued messages, including expired ones not yet dropped
+    pub fn len(&self) -> usize {
+        self.shared
+            .queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of messages dropped because their deadline passed
+    pub fn expired(&self) -> u64 {
+        self.shared.expired.load(AtomicOrdering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_priority_order_and_deadlines() {
+        let mailbox = AgentMailbox::new();
+        mailbox.send(Envelope::new("first")).unwrap();
+        mailbox
+            .send(Envelope::new("stale").with_deadline(Duration::ZERO))
+            .unwrap();
+        mailbox.send(Envelope::new("second")).unwrap();
+        mailbox
+            .send(Envelope::new("urgent").with_priority(Priority::Urgent))
+            .unwrap();
+        mailbox
+            .send(Envelope::new("later").with_priority(Priority::Low))
+            .unwrap();
+
+        assert_eq!(mailbox.peek_priority(), Some(Priority::Urgent));
+        let received: Vec<_> = std::iter::from_fn(|| mailbox.try_recv())
+            .map(|envelope| envelope.payload)
+            .collect();
+        assert_eq!(received, ["urgent", "first", "second", "later"]);
+        assert_eq!(mailbox.expired(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_urgent_message_preempts_and_close_ends_recv() {
+        let mailbox = AgentMailbox::new();
+        let token = CancellationToken::new();
+        mailbox.preempt_on(Priority::Urgent, token.clone());
+
+        mailbox
+            .send(Envelope::new(1).with_priority(Priority::High))
+            .unwrap();
+        assert!(!token.is_cancelled());
+
+        let receiver = mailbox.clone();
+        let waiting = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(envelope) = receiver.recv().await {
+                received.push(envelope.payload);
+            }
+            received
+        });
+        mailbox
+            .send(
+                Envelope::new(2)
+                    .with_priority(Priority::Urgent)
+                    .from("supervisor"),
+            )
+            .unwrap();
+        assert!(token.is_cancelled());
+
+        mailbox.close();
+        assert!(mailbox.send(Envelope::new(3)).is_err());
+        let received = waiting.await.unwrap();
+        assert_eq!(received.len(), 2);
+    }
+}
@@ -0,0 +1,319 @@
+//! Execution metrics history for an agent.
+//!
+//! Each [`AgentResult`] carries metrics for a single call, which are lost once the
+//! result is dropped. [`AgentMetricsHistory`] keeps the most recent executions in a
+//! fixed-size ring buffer and computes rolling aggregates (latency percentiles,
+//! token totals, tool failure rates) for dashboards and adaptive behavior.
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut agent = Agent::builder().with_metrics_history_size(50).build().await?;
+//! agent.execute("Hello").await?;
+//!
+//! let stats = agent.metrics_history().aggregates();
+//! println!("p95 latency: {:?}, tokens: {}", stats.p95_latency, stats.total_tokens);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::agent::result::AgentResult;
+
+/// Default number of executions kept by [`AgentMetricsHistory`]
+pub const DEFAULT_METRICS_HISTORY_SIZE: usize = 100;
+
+/// Metrics for a single agent execution
+#[derive(Debug, Clone)]
+pub struct ExecutionRecord {
+    /// When the execution finished
+    pub timestamp: DateTime<Utc>,
+    /// Total execution time
+    pub duration: Duration,
+    /// Whether execution completed successfully
+    pub success: bool,
+    /// Number of reasoning cycles executed
+    pub cycles: u32,
+    /// Number of model calls made
+    pub model_calls: u32,
+    /// Input tokens consumed
+    pub input_tokens: u32,
+    /// Output tokens generated
+    pub output_tokens: u32,
+    /// Names of tools called (one entry per call)
+    pub tools_called: Vec<String>,
+    /// Names of tools that failed (one entry per failed call)
+    pub tools_failed: Vec<String>,
+}
+
+impl ExecutionRecord {
+    /// Create a record from a completed execution
+    pub fn from_result(result: &AgentResult) -> Self {
+        let tokens = result.execution.tokens.as_ref();
+        Self {
+            timestamp: Utc::now(),
+            duration: result.duration,
+            success: result.success,
+            cycles: result.execution.cycles,
+            model_calls: result.execution.model_calls,
+            input_tokens: tokens.map(|t| t.input_tokens).unwrap_or(0),
+            output_tokens: tokens.map(|t| t.output_tokens).unwrap_or(0),
+            tools_called: result.tools_called.clone(),
+            tools_failed: result.tools_failed.clone(),
+        }
+    }
+
+    /// Create a record for an execution that returned an error
+    pub fn failed(duration: Duration) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            duration,
+            success: false,
+            cycles: 0,
+            model_calls: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            tools_called: Vec::new(),
+            tools_failed: Vec::new(),
+        }
+    }
+}
+
+/// Call and failure counts for one tool
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolFailureStats {
+    pub calls: u32,
+    pub failures: u32,
+}
+
+impl ToolFailureStats {
+    /// Fraction of calls that failed (0.0 when the tool was never called)
+    pub fn failure_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.calls as f64
+        }
+    }
+}
+
+/// Rolling aggregates over the executions in an [`AgentMetricsHistory`]
+#[derive(Debug, Clone, Default)]
+pub struct MetricsAggregates {
+    /// Number of executions included
+    pub executions: usize,
+    /// Fraction of executions that succeeded
+    pub success_rate: f64,
+    /// Mean execution time
+    pub avg_latency: Duration,
+    /// Median execution time
+    pub p50_latency: Duration,
+    /// 95th percentile execution time
+    pub p95_latency: Duration,
+    /// Longest execution time
+    pub max_latency: Duration,
+    /// Total input tokens consumed
+    pub total_input_tokens: u64,
+    /// Total output tokens generated
+    pub total_output_tokens: u64,
+    /// Total tokens used
+    pub total_tokens: u64,
+    /// Mean tokens per execution
+    pub avg_tokens_per_execution: f64,
+    /// Total tool calls
+    pub tool_calls: u32,
+    /// Total failed tool calls
+    pub tool_failures: u32,
+    /// Fraction of tool calls that failed
+    pub tool_failure_rate: f64,
+    /// Call and failure counts by tool name
+    pub per_tool: HashMap<String, ToolFailureStats>,
+}
+
+/// Ring buffer of recent execution metrics with rolling aggregates
+#[derive(Debug, Clone)]
+pub struct AgentMetricsHistory {
+    capacity: usize,
+    records: VecDeque<ExecutionRecord>,
+}
+
+impl Default for AgentMetricsHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_METRICS_HISTORY_SIZE)
+    }
+}
+
+impl AgentMetricsHistory {
+    /// Create a history keeping at most `capacity` executions (0 disables recording)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: VecDeque::with_capacity(capacity.min(DEFAULT_METRICS_HISTORY_SIZE)),
+        }
+    }
+
+    /// Add a record, evicting the oldest one if the history is full
+    pub fn record(&mut self, record: ExecutionRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Records from oldest to newest
+    pub fn records(&self) -> impl Iterator<Item = &ExecutionRecord> {
+        self.records.iter()
+    }
+
+    /// The most recent record
+    pub fn latest(&self) -> Option<&ExecutionRecord> {
+        self.records.back()
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Aggregates over all recorded executions
+    pub fn aggregates(&self) -> MetricsAggregates {
+        self.aggregates_over(self.records.len())
+    }
+
+    /// Aggregates over the `n` most recent executions
+    pub fn aggregates_over(&self, n: usize) -> MetricsAggregates {
+        let skip = self.records.len().saturating_sub(n);
+        let window: Vec<&ExecutionRecord> = self.records.iter().skip(skip).collect();
+        if window.is_empty() {
+            return MetricsAggregates::default();
+        }
+
+        let executions = window.len();
+        let mut latencies: Vec<Duration> = window.iter().map(|r| r.duration).collect();
+        latencies.sort();
+        let total_latency: Duration = latencies.iter().sum();
+
+        let mut per_tool: HashMap<String, ToolFailureStats> = HashMap::new();
+        for record in &window {
+            for name in &record.tools_called {
+                per_tool.entry(name.clone()).or_default().calls += 1;
+            }
+            for name in &record.tools_failed {
+                per_tool.entry(name.clone()).or_default().failures += 1;
+            }
+        }
+        let tool_calls: u32 = per_tool.values().map(|s| s.calls).sum();
+        let tool_failures: u32 = per_tool.values().map(|s| s.failures).sum();
+
+        let total_input_tokens: u64 = window.iter().map(|r| r.input_tokens as u64).sum();
+        let total_output_tokens: u64 = window.iter().map(|r| r.output_tokens as u64).sum();
+        let total_tokens = total_input_tokens + total_output_tokens;
+
+        MetricsAggregates {
+            executions,
+            success_rate: window.iter().filter(|r| r.success).count() as f64 / executions as f64,
+            avg_latency: total_latency / executions as u32,
+            p50_latency: percentile(&latencies, 0.50),
+            p95_latency: percentile(&latencies, 0.95),
+            max_latency: latencies[executions - 1],
+            total_input_tokens,
+            total_output_tokens,
+            total_tokens,
+            avg_tokens_per_execution: total_tokens as f64 / executions as f64,
+            tool_calls,
+            tool_failures,
+            tool_failure_rate: if tool_calls == 0 {
+                0.0
+            } else {
+                tool_failures as f64 / tool_calls as f64
+            },
+            per_tool,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(millis: u64, tokens: u32, called: &[&str], failed: &[&str]) -> ExecutionRecord {
+        ExecutionRecord {
+            duration: Duration::from_millis(millis),
+            input_tokens: tokens,
+            output_tokens: tokens,
+            tools_called: called.iter().map(|s| s.to_string()).collect(),
+            tools_failed: failed.iter().map(|s| s.to_string()).collect(),
+            success: true,
+            cycles: 1,
+            model_calls: 1,
+            ..ExecutionRecord::failed(Duration::ZERO)
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_evicts_oldest() {
+        let mut history = AgentMetricsHistory::new(3);
+        for millis in 1..=5 {
+            history.record(record(millis, 0, &[], &[]));
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            history.records().next().unwrap().duration,
+            Duration::from_millis(3)
+        );
+        assert_eq!(history.latest().unwrap().duration, Duration::from_millis(5));
+
+        let mut disabled = AgentMetricsHistory::new(0);
+        disabled.record(record(1, 0, &[], &[]));
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let mut history = AgentMetricsHistory::new(100);
+        for millis in 1..=20 {
+            history.record(record(millis * 10, 5, &["search"], &[]));
+        }
+        history.record(record(1_000, 5, &["search", "fetch"], &["fetch"]));
+        history.record(ExecutionRecord::failed(Duration::from_millis(50)));
+
+        let stats = history.aggregates();
+        assert_eq!(stats.executions, 22);
+        assert_eq!(stats.max_latency, Duration::from_millis(1_000));
+        assert_eq!(stats.p95_latency, Duration::from_millis(200));
+        assert_eq!(stats.total_tokens, 210);
+        assert_eq!(stats.tool_calls, 22);
+        assert_eq!(stats.tool_failures, 1);
+        assert_eq!(stats.per_tool["fetch"].failure_rate(), 1.0);
+        assert!((stats.success_rate - 21.0 / 22.0).abs() < f64::EPSILON);
+
+        let recent = history.aggregates_over(2);
+        assert_eq!(recent.executions, 2);
+        assert_eq!(recent.total_tokens, 10);
+    }
+}
@@ -130,9 +130,21 @@
 //! - [`EventLoop`] - Orchestrates agentic execution workflows
 
 // BedrockClient now in llm::providers::bedrock
-use crate::tools::{Tool, ToolMiddleware, ToolRegistry};
-use crate::types::Message;
+use crate::audio::{Synthesizer, Transcriber};
+use crate::context_manager::CompactionConfig;
+use crate::error_recovery::RetryBudget;
+use crate::secrets::SecretsProvider;
+use crate::shutdown::InFlightExecutions;
+use crate::tools::plan::PlanPromptHook;
+use crate::tools::{
+    AuditLog, DescriptionContext, Elicitor, FlakyToolPolicy, ManifestVerifier, PartialOutput, Plan,
+    PlanState, PlanTool, ProgressReporter, Tool, ToolCallLimits, ToolFixtures, ToolGuardrails,
+    ToolMiddleware, ToolRegistry, Workspace,
+};
+use crate::types::tools::ToolChoice;
+use crate::types::{ContentBlock, Message};
 use crate::{Result, StoodError};
+use persona::PersonaBaseline;
 use std::sync::Arc;
 use std::time::Duration;
 #[allow(unused_imports)] // Used in future features
@@ -141,26 +153,108 @@ use uuid::Uuid;
 // LLM provider system imports
 use crate::llm::providers::retry::RetryConfig;
 use crate::llm::registry::PROVIDER_REGISTRY;
+use crate::llm::tool_schema::{translate_tool_schema, SchemaDialect};
 use crate::llm::traits::{CacheStrategy, LlmModel, LlmProvider, ProviderType};
 
 use crate::telemetry::{StoodTracer, TelemetryConfig};
 
+pub mod anomaly;
+pub mod artifacts;
+pub mod best_of;
 pub mod callbacks;
+pub mod chat;
+pub mod citations;
 pub mod config;
+pub mod consensus;
+pub mod context_policy;
 pub mod conversation;
+pub mod conversation_diff;
+pub mod deadline;
 pub mod evaluation;
 pub mod event_loop;
+pub mod execution_trace;
+pub mod extract;
+pub mod hooks;
+pub mod language;
+pub mod mailbox;
+pub mod metrics_history;
+pub mod parameter_schedule;
+pub mod persona;
+pub mod phase_timeouts;
+pub mod pool;
+pub mod preflight;
+#[cfg(feature = "hot-reload")]
+pub mod prompt_reload;
+pub mod quota;
+pub mod response_processor;
 pub mod result;
-
+pub mod router;
+pub mod state_machine;
+pub mod stop;
+pub mod system_prompt;
+pub mod token_attribution;
+pub mod triage;
+pub mod variants;
+pub mod voice;
+
+pub use anomaly::{
+    Anomaly, AnomalyAction, AnomalyApprover, AnomalyKind, BehaviorMonitor, BehaviorMonitorConfig,
+};
+pub use artifacts::{Artifact, ArtifactCollector, ArtifactContent};
+pub use best_of::{
+    BestOfConfig, BestOfResult, Candidate, CandidateScorer, CandidateSelector, ExecuteOptions,
+};
 pub use callbacks::{
     CallbackHandler, CallbackHandlerConfig, CompositeCallbackHandler, NullCallbackHandler,
-    PerformanceCallbackHandler, PrintingCallbackHandler, PrintingConfig,
+    PerformanceCallbackHandler, PrintingCallbackHandler, PrintingConfig, ProgressConfig,
 };
+pub use chat::{ChatMessage, CHAT_NAME_METADATA_KEY};
+pub use citations::{Citation, SourceDocument};
 pub use config::{ExecutionConfig, LogLevel};
+pub use consensus::{AnswerComparison, ConsensusResult, ConsensusRunner};
+pub use context_policy::{
+    ContextLimits, ContextPolicy, ImportanceWeighted, KeepLastN, PinSystemAndFirstTurn,
+    SummarizeThenDrop,
+};
 pub use conversation::ConversationManager;
-pub use evaluation::{EvaluationStrategy, PerspectiveConfig};
+pub use conversation_diff::{
+    ChangedMessage, ConversationDiff, ConversationSnapshot, MessageChange, RemovalReason,
+    RemovedMessage,
+};
+pub use deadline::Deadline;
+pub use evaluation::{EvaluationStrategy, PerspectiveConfig, TranscriptView};
 pub use event_loop::{EventLoop, EventLoopConfig, EventLoopResult};
+pub use execution_trace::{ExecutionTrace, RecoveryAction, TraceEntry, TraceEvent};
+pub use extract::{ExtractionOptions, ExtractionResult};
+pub use hooks::{CycleHook, CycleHookContext};
+pub use language::{LanguageCheck, LanguageTag, ResponseLanguage};
+pub use mailbox::{AgentMailbox, Envelope, Priority};
+pub use metrics_history::{
+    AgentMetricsHistory, ExecutionRecord, MetricsAggregates, ToolFailureStats,
+};
+pub use parameter_schedule::{CycleSelector, InferenceParameters, ParameterSchedule};
+pub use persona::Persona;
+pub use phase_timeouts::{ExecutionPhase, PhaseTimeouts};
+pub use pool::{AgentPool, AgentPoolConfig, AgentPoolStats, PooledAgent};
+pub use preflight::{PreflightCheck, PreflightOptions, PreflightReport};
+pub use quota::{QuotaLimits, QuotaManager, QuotaViolation, SessionQuota, TokenPricing};
+pub use response_processor::{ProcessingStep, ResponseProcessor};
 pub use result::{AgentResult, ExecutionDetails, PerformanceMetrics, TokenUsage};
+pub use router::{
+    Classification, ComplexityClassifier, HeuristicClassifier, LlmClassifier, ModelRoute,
+    ModelRouter, RoutingContext, RoutingDecision, TaskComplexity,
+};
+pub use state_machine::{AgenticState, AgenticStateMachine, StepOutcome};
+pub use stop::{StopCondition, StopContext, TerminationReason};
+pub use system_prompt::{
+    FragmentSource, PromptContext, PromptContextProvider, PromptFragment, SystemPromptBuilder,
+};
+pub use token_attribution::{TokenAttribution, TokenBreakdown, ToolTokenUsage};
+pub use triage::{TriageRecovery, TriageReport};
+pub use variants::{Variant, VariantsConfig, VariantsResult};
+
+#[cfg(feature = "hot-reload")]
+pub use prompt_reload::WatchedPrompt;
 
 #[cfg(test)]
 mod integration_tests;
@@ -171,6 +265,9 @@ mod tools_integration_tests;
 #[cfg(test)]
 mod llm_integration_test;
 
+#[cfg(test)]
+mod test_support;
+
 /// Configuration for agent behavior and model parameters.
 ///
 /// This struct controls how your agent interacts with multiple LLM providers,
@@ -195,8 +292,7 @@ mod llm_integration_test;
 ///     ),
 ///     agent_id: None,
 ///     agent_name: None,
-///     telemetry_config: None,
-///     retry_config: None,
+///     ..AgentConfig::default()
 /// };
 /// ```
 ///
@@ -220,6 +316,52 @@ pub struct AgentConfig {
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
     pub system_prompt: Option<String>,
+    /// Composed system prompt evaluated at execute-time; takes precedence over `system_prompt`
+    pub system_prompt_builder: Option<SystemPromptBuilder>,
+    /// Source of the system prompt evaluated at execute-time (e.g. a reloaded prompt file);
+    /// replaces `system_prompt` when it returns text
+    pub system_prompt_provider: Option<Arc<dyn PromptContextProvider>>,
+    /// Chooses the model for each execution based on prompt complexity
+    pub model_router: Option<ModelRouter>,
+    /// Where tools resolve secrets and environment variables (see [`crate::secrets`])
+    pub secrets: Option<Arc<dyn SecretsProvider>>,
+    /// Processors applied to the final response, in order
+    pub response_processors: Vec<Arc<dyn ResponseProcessor>>,
+    /// Session quota checked before each execution (see [`quota`])
+    pub quota: Option<SessionQuota>,
+    /// In-flight execution tracking for graceful shutdown (see [`crate::shutdown`])
+    pub shutdown: Option<InFlightExecutions>,
+    /// Tamper-evident log of every tool execution (see [`crate::tools::audit`])
+    pub audit_log: Option<AuditLog>,
+    /// Constraints on tool parameter values (see [`crate::tools::guardrails`])
+    pub tool_guardrails: ToolGuardrails,
+    /// Caps on calls to individual tools per execution (see [`crate::tools::call_limits`])
+    pub tool_call_limits: ToolCallLimits,
+    /// Verifies the signed manifest of each tool before it is registered
+    /// (see [`crate::tools::manifest`])
+    pub tool_manifest_verifier: Option<ManifestVerifier>,
+    /// Variables of templated tool descriptions (see [`crate::tools::templating`])
+    pub tool_description_variables: DescriptionContext,
+    /// Tool versions offered instead of the latest, by tool name (see [`crate::tools::versioning`])
+    pub tool_versions: std::collections::HashMap<String, String>,
+    /// Task plan shared with the [`PlanTool`](crate::tools::PlanTool) across executions
+    pub plan: Option<PlanState>,
+    /// Give each execution a [`Workspace`] shared by its tools
+    pub workspace: bool,
+    /// Policy trimming the conversation history (see [`context_policy`]); `None` keeps the last messages
+    pub context_policy: Option<Arc<dyn ContextPolicy>>,
+    /// Append a note listing flaky tools to the system prompt (see [`crate::tools::reliability`])
+    pub flaky_tool_policy: Option<FlakyToolPolicy>,
+    /// Language to respond in (see [`language`]); per-call options take precedence
+    pub response_language: Option<ResponseLanguage>,
+    /// Speech-to-text for [`Agent::execute_from_audio`] (see [`crate::audio`])
+    pub transcriber: Option<Arc<dyn Transcriber>>,
+    /// Text-to-speech for [`Agent::respond_as_audio`] (see [`crate::audio`])
+    pub synthesizer: Option<Arc<dyn Synthesizer>>,
+    /// Personas available to [`Agent::switch_persona`], by name (see [`persona`])
+    pub personas: std::collections::HashMap<String, Persona>,
+    /// Name of the active persona
+    pub active_persona: Option<String>,
     pub agent_id: Option<String>,
     pub agent_name: Option<String>,
     /// Prompt caching strategy for reducing latency and costs
@@ -235,7 +377,21 @@ pub struct AgentContext {
     pub agent_id: String,
     pub agent_name: Option<String>,
     pub agent_type: String,
-    pub span_context: Option<opentelemetry::Context>,
+    pub span_context: Option<crate::telemetry::Context>,
+    /// Secrets available to tools; `None` restricts them to the default environment allowlist
+    pub secrets: Option<Arc<dyn SecretsProvider>>,
+    /// Task plan maintained by the [`PlanTool`](crate::tools::PlanTool), if enabled
+    pub plan: Option<PlanState>,
+    /// Scratch directory and key-value store of the current execution, if enabled
+    pub workspace: Option<Workspace>,
+    /// Outputs tools add for [`AgentResult::artifacts`]
+    pub artifacts: ArtifactCollector,
+    /// Output the current tool call has produced so far, if interim results are enabled
+    pub partial_output: Option<PartialOutput>,
+    /// Progress reporter of the current tool call, if a callback handler is set
+    pub progress: Option<ProgressReporter>,
+    /// Asks the user for input tools need, if a callback handler is set
+    pub elicitor: Option<Arc<dyn Elicitor>>,
 }
 
 impl AgentContext {
@@ -246,6 +402,13 @@ impl AgentContext {
             agent_name: agent.agent_name.clone(),
             agent_type: agent_type.into(),
             span_context: None, // Will be set by telemetry system
+            secrets: agent.config.secrets.clone(),
+            plan: agent.config.plan.clone(),
+            workspace: agent.workspace.clone(),
+            artifacts: agent.artifacts.clone(),
+            partial_output: None,
+            progress: None,
+            elicitor: None,
         }
     }
 
@@ -260,11 +423,66 @@ impl AgentContext {
             agent_name,
             agent_type: agent_type.into(),
             span_context: None,
+            secrets: None,
+            plan: None,
+            workspace: None,
+            artifacts: ArtifactCollector::new(),
+            partial_output: None,
+            progress: None,
+            elicitor: None,
+        }
+    }
+
+    /// Collect the partial output of the tool call into `partial_output`
+    pub fn with_partial_output(mut self, partial_output: PartialOutput) -> Self {
+        self.partial_output = Some(partial_output);
+        self
+    }
+
+    /// Report output produced so far by a slow tool
+    ///
+    /// Shown to the model if the tool is still running when the
+    /// [`InterimResultConfig::deadline`](crate::tools::InterimResultConfig::deadline)
+    /// passes; does nothing otherwise.
+    pub fn report_partial_output(&self, output: impl AsRef<str>) {
+        if let Some(partial_output) = &self.partial_output {
+            partial_output.push(output.as_ref());
+        }
+    }
+
+    /// Report the progress of the current tool call to `progress`
+    pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Ask `elicitor` for the input tools request with [`ToolResult::needs_input`](crate::tools::ToolResult::needs_input)
+    pub fn with_elicitor(mut self, elicitor: Arc<dyn Elicitor>) -> Self {
+        self.elicitor = Some(elicitor);
+        self
+    }
+
+    /// Attribute progress reports to the call `tool_use_id` of `tool_name`
+    pub fn for_tool_call(mut self, tool_name: &str, tool_use_id: &str) -> Self {
+        if let Some(progress) = &self.progress {
+            self.progress = Some(progress.for_tool(tool_name, tool_use_id));
+        }
+        self
+    }
+
+    /// Report that the current tool call is `percent` (0 to 100) done
+    ///
+    /// Delivered to the callback handler as
+    /// [`CallbackEvent::ToolProgress`](callbacks::CallbackEvent::ToolProgress);
+    /// does nothing if the agent has none.
+    pub fn report_progress(&self, percent: f32, message: impl Into<String>) {
+        if let Some(progress) = &self.progress {
+            progress.report(percent, message);
         }
     }
 
     /// Set the span context for telemetry tracking
-    pub fn with_span_context(mut self, span_context: opentelemetry::Context) -> Self {
+    pub fn with_span_context(mut self, span_context: crate::telemetry::Context) -> Self {
         self.span_context = Some(span_context);
         self
     }
@@ -315,6 +533,28 @@ impl Default for AgentConfig {
             temperature: Some(0.7),
             max_tokens: Some(4096),
             system_prompt: None,
+            system_prompt_builder: None,
+            system_prompt_provider: None,
+            model_router: None,
+            secrets: None,
+            response_processors: Vec::new(),
+            quota: None,
+            shutdown: None,
+            audit_log: None,
+            tool_guardrails: ToolGuardrails::default(),
+            tool_call_limits: ToolCallLimits::default(),
+            tool_manifest_verifier: None,
+            tool_description_variables: DescriptionContext::default(),
+            tool_versions: std::collections::HashMap::new(),
+            plan: None,
+            workspace: false,
+            context_policy: None,
+            flaky_tool_policy: None,
+            response_language: None,
+            transcriber: None,
+            synthesizer: None,
+            personas: std::collections::HashMap::new(),
+            active_persona: None,
             agent_id: None,
             agent_name: None,
             cache_strategy: CacheStrategy::default(),
@@ -412,6 +652,13 @@ pub struct Agent {
     conversation: ConversationManager,
     tool_registry: ToolRegistry,
     execution_config: ExecutionConfig, // Pre-configured execution settings
+    metrics_history: AgentMetricsHistory,
+    /// Workspace of the running execution (only set on the event loop's copy)
+    workspace: Option<Workspace>,
+    /// Artifacts added by tools during the running execution
+    artifacts: ArtifactCollector,
+    /// Settings to restore when the active persona is reset
+    persona_baseline: Option<PersonaBaseline>,
 
     tracer: Option<StoodTracer>,
 }
@@ -445,6 +692,10 @@ impl Clone for Agent {
             conversation: self.conversation.clone(),
             tool_registry: self.tool_registry.clone(),
             execution_config: self.execution_config.clone(),
+            metrics_history: self.metrics_history.clone(),
+            workspace: self.workspace.clone(),
+            artifacts: self.artifacts.clone(),
+            persona_baseline: self.persona_baseline.clone(),
             tracer: self.tracer.clone(),
         }
     }
@@ -516,6 +767,23 @@ fn create_model_from_config(provider: &ProviderType, model_id: &str) -> Box<dyn
     result
 }
 
+/// Per-turn settings passed from the public execution methods to the event loop
+#[derive(Debug, Clone, Default)]
+struct TurnOptions {
+    /// Text the assistant's response starts with
+    prefill: Option<String>,
+    /// Tool choice for the first model call, overriding the configured one
+    tool_choice: Option<ToolChoice>,
+    /// Metadata attached to the user message added to the conversation
+    metadata: std::collections::HashMap<String, serde_json::Value>,
+    /// Simulate tool calls with these fixtures (dry run)
+    tool_fixtures: Option<ToolFixtures>,
+    /// Response language, overriding the configured one
+    response_language: Option<ResponseLanguage>,
+    /// Time by which the execution must finish
+    deadline: Option<Deadline>,
+}
+
 impl Agent {
     pub fn builder() -> AgentBuilder {
         AgentBuilder::new()
@@ -541,11 +809,17 @@ impl Agent {
 
         // Set system prompt from config if provided
         conversation.set_system_prompt(config.system_prompt.clone());
+        if let Some(policy) = &config.context_policy {
+            conversation.set_policy(policy.clone());
+        }
 
         // Initialize tool registry and register tools
         let tool_registry = crate::perf_timed!("stood.build_internal.tool_registry_new", {
             ToolRegistry::new()
         });
+        if let Some(verifier) = &config.tool_manifest_verifier {
+            tool_registry.set_manifest_verifier(verifier.clone()).await;
+        }
         #[cfg(feature = "perf-timing")]
         let tool_count = tools.len();
         crate::perf_timed!("stood.build_internal.register_tools", {
@@ -570,6 +844,28 @@ impl Agent {
         #[cfg(feature = "perf-timing")]
         crate::perf_checkpoint!("stood.build_internal.middleware_registered", &format!("count={}", middleware_count));
 
+        if let Some(audit_log) = &config.audit_log {
+            tool_registry.set_audit_log(audit_log.clone()).await;
+        }
+        if !config.tool_guardrails.is_empty() {
+            tool_registry
+                .add_guardrails(config.tool_guardrails.clone())
+                .await;
+        }
+        if !config.tool_description_variables.is_empty() {
+            tool_registry
+                .add_description_variables(&config.tool_description_variables)
+                .await;
+        }
+        for (name, version) in &config.tool_versions {
+            tool_registry
+                .pin_version(name, version)
+                .await
+                .map_err(|e| {
+                    StoodError::configuration_error(format!("Failed to pin tool version: {}", e))
+                })?;
+        }
+
         // Initialize smart telemetry with auto-detection
         crate::perf_checkpoint!("stood.build_internal.telemetry_init.start");
         let tracer = crate::perf_timed!("stood.build_internal.telemetry_init", {
@@ -616,7 +912,11 @@ impl Agent {
             config,
             conversation,
             tool_registry,
+            metrics_history: AgentMetricsHistory::new(execution_config.metrics_history_size),
             execution_config,
+            workspace: None,
+            artifacts: ArtifactCollector::new(),
+            persona_baseline: None,
 
             tracer,
         })
@@ -697,6 +997,16 @@ impl Agent {
         self.model.as_ref()
     }
 
+    /// Current task plan, if the plan tool is enabled
+    pub fn plan(&self) -> Option<Plan> {
+        self.config.plan.as_ref().map(PlanState::snapshot)
+    }
+
+    /// Metrics of recent executions with rolling aggregates
+    pub fn metrics_history(&self) -> &AgentMetricsHistory {
+        &self.metrics_history
+    }
+
     pub fn conversation(&self) -> &ConversationManager {
         &self.conversation
     }
@@ -817,9 +1127,155 @@ impl Agent {
     /// - `ConversationError` - Context management issues
     /// - `InvalidInput` - Empty prompts or invalid parameters
     pub async fn execute<S: Into<String>>(&mut self, prompt: S) -> Result<AgentResult> {
-        let prompt = prompt.into();
+        self.execute_turn(prompt.into(), TurnOptions::default())
+            .await
+    }
+
+    /// Run one user turn through the event loop
+    async fn execute_turn(&mut self, prompt: String, turn: TurnOptions) -> Result<AgentResult> {
         let start_time = std::time::Instant::now();
 
+        // Refuse the execution before calling the model if the session is over quota
+        if let Some(quota) = &self.config.quota {
+            quota.manager.start_execution(&quota.session_id)?;
+        }
+
+        // Refuse new executions once shutdown has started, and let it wait for this one
+        let _in_flight = match &self.config.shutdown {
+            Some(executions) => Some(executions.begin(
+                self.agent_id.clone(),
+                self.cancellation_token(),
+                self.tracer.clone(),
+            )?),
+            None => None,
+        };
+
+        // The turn counts once, however many attempts the language check made
+        let outcome = self.execute_turn_in_language(prompt, turn).await;
+        let result = match &outcome {
+            Ok(result) => Some(result),
+            Err(StoodError::DeadlineExceeded { partial }) => Some(partial.as_ref()),
+            Err(_) => None,
+        };
+        match result {
+            Some(result) => {
+                if let (Some(quota), Some(tokens)) = (&self.config.quota, &result.execution.tokens)
+                {
+                    quota.manager.record_usage(
+                        &quota.session_id,
+                        tokens.input_tokens,
+                        tokens.output_tokens,
+                    );
+                }
+                self.metrics_history
+                    .record(ExecutionRecord::from_result(result));
+            }
+            None => self
+                .metrics_history
+                .record(ExecutionRecord::failed(start_time.elapsed())),
+        }
+        outcome
+    }
+
+    /// Run the turn in the response language, retrying once if the response
+    /// came back in another one
+    async fn execute_turn_in_language(
+        &mut self,
+        prompt: String,
+        mut turn: TurnOptions,
+    ) -> Result<AgentResult> {
+        let language = turn
+            .response_language
+            .take()
+            .or_else(|| self.config.response_language.clone())
+            .and_then(|language| language.resolve(&prompt));
+        let Some(language) = language else {
+            return self.execute_turn_once(prompt, turn, None).await;
+        };
+
+        let conversation_before = self.conversation.snapshot();
+        let instruction = language::instruction(&language, false);
+        let mut first = self
+            .execute_turn_once(prompt.clone(), turn.clone(), Some(instruction))
+            .await?;
+        let check = LanguageCheck::new(language.clone(), &first.response);
+
+        // Run the turn once more, from the same conversation, if the response
+        // came back in another language. Turns that called tools are kept as
+        // they are, since a second run would call the tools again.
+        if !first.success
+            || check.is_match()
+            || !first.tools_called.is_empty()
+            || turn.deadline.is_some_and(|d| d.is_expired())
+        {
+            first.execution.language = Some(check);
+            return Ok(first);
+        }
+        tracing::info!(
+            "Response language {:?} does not match {}, retrying",
+            check.detected,
+            language
+        );
+        let conversation_after = self.conversation.snapshot();
+        self.conversation.messages_mut().messages = conversation_before.messages;
+        let instruction = language::instruction(&language, true);
+        let mut result = match self
+            .execute_turn_once(prompt, turn, Some(instruction))
+            .await
+        {
+            Ok(result) => result,
+            Err(StoodError::DeadlineExceeded { mut partial }) => {
+                partial.add_attempt(&first);
+                return Err(StoodError::DeadlineExceeded { partial });
+            }
+            Err(e) => {
+                tracing::warn!("Language retry failed, keeping the first response: {}", e);
+                self.conversation.messages_mut().messages = conversation_after.messages;
+                first.execution.language = Some(LanguageCheck {
+                    retried: true,
+                    ..check
+                });
+                return Ok(first);
+            }
+        };
+        result.add_attempt(&first);
+        result.execution.language = Some(LanguageCheck {
+            retried: true,
+            ..LanguageCheck::new(language, &result.response)
+        });
+        Ok(result)
+    }
+
+    async fn execute_turn_once(
+        &mut self,
+        prompt: String,
+        turn: TurnOptions,
+        language_instruction: Option<String>,
+    ) -> Result<AgentResult> {
+        let start_time = std::time::Instant::now();
+
+        // Offer only the tools of the active persona
+        let tool_registry = match self.persona_tools() {
+            Some(tools) => self.tool_registry.restricted_to(tools).await,
+            None => self.tool_registry.clone(),
+        };
+
+        // A forced tool that does not exist would be rejected by the model provider
+        let tool_choice = turn.tool_choice.or_else(|| {
+            self.execution_config
+                .event_loop
+                .first_call_tool_choice
+                .clone()
+        });
+        if let Some(ToolChoice::Tool { name }) = &tool_choice {
+            if !tool_registry.has_tool(name).await {
+                return Err(StoodError::configuration_error(format!(
+                    "Tool choice requires tool '{}', which is not registered",
+                    name
+                )));
+            }
+        }
+
         // Use pre-configured ExecutionConfig from Agent construction
         let config = &self.execution_config;
 
@@ -832,7 +1288,10 @@ impl Agent {
             &self.config.model_id
         );
 
-        let model = create_model_from_config(&self.config.provider, &self.config.model_id);
+        // Pick the model for this execution if a router is configured
+        let (provider, agent_config, routing) = self.route_model(&prompt).await?;
+
+        let model = create_model_from_config(&agent_config.provider, &agent_config.model_id);
 
         tracing::debug!(
             target: "stood::agent",
@@ -841,9 +1300,9 @@ impl Agent {
         );
 
         let agent_copy = Agent::build_internal(
-            Arc::clone(&self.provider),
+            provider,
             model,
-            self.config.clone(),
+            agent_config,
             vec![], // Tools are already in the main agent's registry
             vec![], // Middleware is already in the main agent's registry
             config.clone(),
@@ -852,24 +1311,46 @@ impl Agent {
         )
         .await?;
 
-        // Copy current conversation state to the EventLoop agent
+        // Copy current conversation state to the EventLoop agent, keeping message
+        // ids so the conversation diff can match messages across executions
+        let conversation_before = self.conversation.snapshot();
         let mut event_loop_agent = agent_copy;
+        if self.config.workspace {
+            let workspace = Workspace::new().map_err(|e| {
+                StoodError::internal_error(format!("Failed to create workspace: {}", e))
+            })?;
+            event_loop_agent.workspace = Some(workspace);
+        }
         for message in self.conversation.messages().messages.iter() {
             match message.role {
-                crate::types::MessageRole::User => {
+                crate::types::MessageRole::User | crate::types::MessageRole::Assistant => {
                     if let Some(text) = message.text() {
-                        event_loop_agent.add_user_message(text);
-                    }
-                }
-                crate::types::MessageRole::Assistant => {
-                    if let Some(text) = message.text() {
-                        event_loop_agent.add_assistant_message(text);
+                        event_loop_agent.conversation_mut().add_message(Message {
+                            content: vec![ContentBlock::text(text)],
+                            ..message.clone()
+                        });
                     }
                 }
                 _ => {} // Skip system messages as they're handled separately
             }
         }
 
+        // Refresh the system prompt from its source (e.g. a reloaded prompt file)
+        if let Some(provider) = &self.config.system_prompt_provider {
+            let prompt_context = PromptContext {
+                agent_id: self.agent_id.clone(),
+                agent_name: self.agent_name.clone(),
+                user_prompt: prompt.clone(),
+                message_count: self.conversation.message_count(),
+            };
+            if let Some(system_prompt) = provider.provide(&prompt_context).await? {
+                if self.config.system_prompt.as_deref() != Some(system_prompt.as_str()) {
+                    self.config.system_prompt = Some(system_prompt.clone());
+                    self.conversation.set_system_prompt(Some(system_prompt));
+                }
+            }
+        }
+
         // Copy system prompt
         if let Some(system_prompt) = self.conversation.system_prompt() {
             event_loop_agent
@@ -877,6 +1358,44 @@ impl Agent {
                 .set_system_prompt(Some(system_prompt.to_string()));
         }
 
+        // Compose the system prompt from its fragments for this execution
+        if let Some(builder) = &self.config.system_prompt_builder {
+            let prompt_context = PromptContext {
+                agent_id: self.agent_id.clone(),
+                agent_name: self.agent_name.clone(),
+                user_prompt: prompt.clone(),
+                message_count: self.conversation.message_count(),
+            };
+            let system_prompt = builder.build(&prompt_context).await?;
+            event_loop_agent
+                .conversation_mut()
+                .set_system_prompt(system_prompt);
+        }
+
+        // Warn the model about tools that kept failing in earlier executions
+        if let Some(policy) = &self.config.flaky_tool_policy {
+            if let Some(note) = self.tool_registry.reliability().flaky_tools_note(policy) {
+                let system_prompt = match event_loop_agent.conversation().system_prompt() {
+                    Some(existing) => format!("{}\n\n{}", existing, note),
+                    None => note,
+                };
+                event_loop_agent
+                    .conversation_mut()
+                    .set_system_prompt(Some(system_prompt));
+            }
+        }
+
+        // Ask for the response language
+        if let Some(instruction) = language_instruction {
+            let system_prompt = match event_loop_agent.conversation().system_prompt() {
+                Some(existing) => format!("{}\n\n{}", existing, instruction),
+                None => instruction,
+            };
+            event_loop_agent
+                .conversation_mut()
+                .set_system_prompt(Some(system_prompt));
+        }
+
         // Create callback handler from configuration
         let callback_handler = match &config.callback_handler {
             CallbackHandlerConfig::None => None,
@@ -900,24 +1419,350 @@ impl Agent {
             event_loop_config.enable_telemetry = self.config.telemetry_config.is_some();
         }
 
+        let tool_registry = match turn.tool_fixtures {
+            Some(fixtures) => tool_registry.dry_run(fixtures),
+            None => tool_registry,
+        };
         let mut event_loop = event_loop::EventLoop::new_with_callbacks(
             event_loop_agent,
-            self.tool_registry.clone(),
+            tool_registry,
             event_loop_config,
             callback_handler,
         )?;
+        event_loop.set_assistant_prefill(turn.prefill);
+        event_loop.set_first_call_tool_choice(tool_choice);
+        event_loop.set_deadline(turn.deadline);
 
         let event_loop_result = event_loop.execute(prompt).await?;
 
         // Convert to unified result type
-        let agent_result = AgentResult::from(event_loop_result, start_time.elapsed());
-
+        let mut agent_result = AgentResult::from(event_loop_result, start_time.elapsed());
+        agent_result.execution.routing = routing;
+        agent_result.plan = self.config.plan.as_ref().map(PlanState::snapshot);
+        agent_result.artifacts = event_loop.agent().artifacts.take();
+        if agent_result.success && !self.config.response_processors.is_empty() {
+            let original = std::mem::take(&mut agent_result.response);
+            let (processed, steps) = response_processor::apply_processors(
+                &self.config.response_processors,
+                original.clone(),
+            )
+            .await;
+            if processed != original {
+                agent_result.execution.original_response = Some(original);
+            }
+            agent_result.response = processed;
+            agent_result.execution.post_processing = steps;
+        }
         // Sync conversation state from EventLoop result
         self.sync_conversation_from_eventloop(event_loop.agent());
+        if !turn.metadata.is_empty() {
+            let previous: std::collections::HashSet<_> =
+                conversation_before.messages.iter().map(|m| m.id).collect();
+            if let Some(message) = self
+                .conversation
+                .messages_mut()
+                .messages
+                .iter_mut()
+                .find(|m| m.role == crate::types::MessageRole::User && !previous.contains(&m.id))
+            {
+                message.metadata.extend(turn.metadata);
+            }
+        }
+        agent_result.execution.conversation_diff = Some(ConversationDiff::between(
+            &conversation_before,
+            &self.conversation.snapshot(),
+        ));
+
+        if agent_result.termination_reason == TerminationReason::DeadlineExceeded {
+            // Leave the conversation as it was, as for any failed execution
+            self.conversation.messages_mut().messages = conversation_before.messages;
+            return Err(StoodError::deadline_exceeded(agent_result));
+        }
 
         Ok(agent_result)
     }
 
+    /// Execute a prompt with per-call options
+    ///
+    /// With [`ExecuteOptions::best_of`], the prompt is executed by `n` independent
+    /// copies of this agent in parallel and the winner is chosen by the configured
+    /// [`CandidateSelector`]. The winner's conversation replaces this agent's
+    /// conversation. Without options this behaves like [`execute`](Self::execute)
+    /// with a single candidate.
+    pub async fn execute_with_options<S: Into<String>>(
+        &mut self,
+        prompt: S,
+        options: ExecuteOptions,
+    ) -> Result<BestOfResult> {
+        let prompt = prompt.into();
+        let config = options.best_of.unwrap_or(BestOfConfig {
+            n: 1,
+            selector: CandidateSelector::ConfidenceProxy,
+            models: Vec::new(),
+        });
+
+        let mut runners = Vec::with_capacity(config.n);
+        for index in 0..config.n {
+            let mut runner = self.clone();
+            if !config.models.is_empty() {
+                runner
+                    .set_model_route(&config.models[index % config.models.len()])
+                    .await?;
+            }
+            runners.push(runner);
+        }
+
+        let turn = TurnOptions {
+            prefill: options.prefill,
+            tool_choice: options.tool_choice,
+            tool_fixtures: options.tool_fixtures,
+            response_language: options.response_language,
+            deadline: options.deadline,
+            ..TurnOptions::default()
+        };
+        let outcomes = futures::future::join_all(
+            runners
+                .iter_mut()
+                .map(|runner| runner.execute_turn(prompt.clone(), turn.clone())),
+        )
+        .await;
+
+        let mut first_error = None;
+        let mut candidates: Vec<Candidate> = outcomes
+            .into_iter()
+            .zip(&runners)
+            .enumerate()
+            .map(|(index, (outcome, runner))| Candidate {
+                index,
+                model_id: runner.config.model_id.clone(),
+                result: outcome.map_err(|e| {
+                    let message = e.to_string();
+                    first_error.get_or_insert(e);
+                    message
+                }),
+                score: None,
+            })
+            .collect();
+
+        if candidates.iter().all(|c| c.result.is_err()) {
+            return Err(first_error.expect("all candidates failed with an error"));
+        }
+
+        let winner = match &config.selector {
+            CandidateSelector::ConfidenceProxy => {
+                let scores = best_of::confidence_scores(&candidates);
+                for (candidate, score) in candidates.iter_mut().zip(scores) {
+                    candidate.score = score;
+                }
+                best_of::best_scored(&candidates)
+            }
+            CandidateSelector::Custom(scorer) => {
+                let scores: Vec<Option<f64>> = candidates
+                    .iter()
+                    .map(|c| c.result.is_ok().then(|| scorer(c, &candidates)))
+                    .collect();
+                for (candidate, score) in candidates.iter_mut().zip(scores) {
+                    candidate.score = score;
+                }
+                best_of::best_scored(&candidates)
+            }
+            CandidateSelector::Judge {
+                judge_agent,
+                criteria,
+            } => {
+                let mut judge = (**judge_agent).clone();
+                let judge_prompt = best_of::judge_prompt(&prompt, criteria, &candidates);
+                match judge.execute(judge_prompt).await {
+                    Ok(verdict) => best_of::parse_judge_choice(&verdict.response, &candidates),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Judge agent failed, using first successful candidate: {}",
+                            e
+                        );
+                        None
+                    }
+                }
+            }
+        }
+        .or_else(|| candidates.iter().position(|c| c.result.is_ok()))
+        .expect("at least one candidate succeeded");
+
+        tracing::debug!(
+            "Best-of-{} selected candidate {} using {}",
+            config.n,
+            winner,
+            config.selector.name()
+        );
+
+        // Adopt the winning execution's conversation and metrics
+        self.conversation = runners[winner].conversation.clone();
+        if let Ok(result) = &candidates[winner].result {
+            self.metrics_history
+                .record(ExecutionRecord::from_result(result));
+        }
+
+        Ok(BestOfResult {
+            winner,
+            candidates,
+            selector: config.selector.name().to_string(),
+        })
+    }
+
+    /// Generate `n` alternative responses to `prompt`
+    ///
+    /// Runs `n` executions in parallel at temperatures spread over the
+    /// default range and returns the distinct responses. The agent's
+    /// conversation is unchanged until one is adopted with
+    /// [`choose_variant`](Self::choose_variant). See [`variants`].
+    pub async fn execute_variants<S: Into<String>>(
+        &mut self,
+        prompt: S,
+        n: usize,
+    ) -> Result<VariantsResult> {
+        self.execute_variants_with(prompt, VariantsConfig::new(n))
+            .await
+    }
+
+    /// Generate alternative responses to `prompt` with explicit settings
+    ///
+    /// Fails only if every execution fails.
+    pub async fn execute_variants_with<S: Into<String>>(
+        &mut self,
+        prompt: S,
+        config: VariantsConfig,
+    ) -> Result<VariantsResult> {
+        let prompt = prompt.into();
+        let temperatures = config.temperatures();
+        let mut runners: Vec<Agent> = temperatures
+            .iter()
+            .map(|&temperature| {
+                let mut runner = self.clone();
+                runner.config.temperature = Some(temperature);
+                runner
+            })
+            .collect();
+
+        let outcomes = futures::future::join_all(
+            runners
+                .iter_mut()
+                .map(|runner| runner.execute_turn(prompt.clone(), TurnOptions::default())),
+        )
+        .await;
+
+        let mut first_error = None;
+        let mut failures = Vec::new();
+        let mut runs = Vec::with_capacity(outcomes.len());
+        for (index, (outcome, runner)) in outcomes.into_iter().zip(runners).enumerate() {
+            match outcome {
+                Ok(result) => runs.push(Variant {
+                    index,
+                    temperature: temperatures[index],
+                    result,
+                    diversity: 0.0,
+                    conversation: runner.conversation,
+                }),
+                Err(e) => {
+                    failures.push(e.to_string());
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if runs.is_empty() {
+            return Err(first_error.expect("every variant failed with an error"));
+        }
+
+        let (variants, duplicates) = variants::dedup_and_score(runs, config.duplicate_threshold);
+        tracing::debug!(
+            "Generated {} variants ({} duplicates, {} failures)",
+            variants.len(),
+            duplicates,
+            failures.len()
+        );
+        Ok(VariantsResult {
+            variants,
+            duplicates,
+            failures,
+        })
+    }
+
+    /// Continue the conversation from a variant the user picked
+    ///
+    /// Replaces this agent's conversation with the variant's and records its
+    /// execution in the metrics history.
+    pub fn choose_variant(&mut self, variant: &Variant) {
+        self.conversation = variant.conversation.clone();
+        self.metrics_history
+            .record(ExecutionRecord::from_result(&variant.result));
+    }
+
+    /// Switch this agent to a different model
+    async fn set_model_route(&mut self, route: &ModelRoute) -> Result<()> {
+        if route.provider != self.config.provider {
+            self.provider = PROVIDER_REGISTRY
+                .get_provider(route.provider)
+                .await
+                .map_err(|e| StoodError::ConfigurationError {
+                    message: format!("Failed to get provider for {}: {}", route.model_id, e),
+                })?;
+        }
+        self.config.provider = route.provider;
+        self.config.model_id = route.model_id.clone();
+        // An explicit model choice overrides routing
+        self.config.model_router = None;
+        self.model = create_model_from_config(&self.config.provider, &self.config.model_id);
+        Ok(())
+    }
+
+    /// Apply the model router (if any) to choose the provider and config for an execution
+    ///
+    /// Classification failures are logged and fall back to the agent's configured model.
+    async fn route_model(
+        &self,
+        prompt: &str,
+    ) -> Result<(Arc<dyn LlmProvider>, AgentConfig, Option<RoutingDecision>)> {
+        let Some(router) = &self.config.model_router else {
+            return Ok((Arc::clone(&self.provider), self.config.clone(), None));
+        };
+
+        let context = RoutingContext {
+            tool_count: self.tool_registry.tool_names().await.len(),
+            message_count: self.conversation.message_count(),
+        };
+        let default_route = ModelRoute {
+            provider: self.config.provider,
+            model_id: self.config.model_id.clone(),
+        };
+        let decision = match router.decide(prompt, &context, &default_route).await {
+            Ok(decision) => decision,
+            Err(e) => {
+                tracing::warn!("Model routing failed, using default model: {}", e);
+                return Ok((Arc::clone(&self.provider), self.config.clone(), None));
+            }
+        };
+        tracing::debug!(
+            "Routed {:?} prompt to {} ({})",
+            decision.complexity,
+            decision.model_id,
+            decision.reason
+        );
+
+        let provider = if decision.provider == self.config.provider {
+            Arc::clone(&self.provider)
+        } else {
+            PROVIDER_REGISTRY
+                .get_provider(decision.provider)
+                .await
+                .map_err(|e| StoodError::ConfigurationError {
+                    message: format!("Failed to get provider for routed model: {}", e),
+                })?
+        };
+
+        let mut config = self.config.clone();
+        config.provider = decision.provider;
+        config.model_id = decision.model_id.clone();
+        Ok((provider, config, Some(decision)))
+    }
+
     /// Check if the agent has access to tools for agentic execution
     pub fn supports_agentic_execution(&self) -> bool {
         // For now, all agents support agentic execution
@@ -930,7 +1775,8 @@ impl Agent {
         AgentPerformanceSummary {
             total_messages: self.conversation.message_count(),
             conversation_length: self.conversation.messages().messages.len(),
-            has_system_prompt: self.conversation.system_prompt().is_some(),
+            has_system_prompt: self.conversation.system_prompt().is_some()
+                || self.config.system_prompt_builder.is_some(),
             provider: self.config.provider,
             model_id: self.config.model_id.clone(),
         }
@@ -1025,6 +1871,27 @@ impl Agent {
 /// - Max tokens must be greater than 0
 /// - Model must be supported by the selected provider
 /// - Provider must be properly configured
+///
+/// `temperature()` and `max_tokens()` panic on invalid values. For user-supplied
+/// configuration, call [`validating`](Self::validating) first so setters record
+/// problems instead, and build with [`try_build`](Self::try_build), which returns a
+/// single [`StoodError::ConfigurationError`] listing everything that is wrong:
+///
+/// ```no_run
+/// # use stood::agent::Agent;
+/// # async fn example(user_temperature: f32) -> Result<(), Box<dyn std::error::Error>> {
+/// let agent = Agent::builder()
+///     .validating()
+///     .temperature(user_temperature)
+///     .max_tokens(0)
+///     .try_build()
+///     .await;
+/// if let Err(e) = agent {
+///     eprintln!("{}", e); // lists both the temperature and max_tokens problems
+/// }
+/// # Ok(())
+/// # }
+/// ```
 pub struct AgentBuilder {
     config: AgentConfig,
     tools: Vec<Box<dyn Tool>>,
@@ -1034,6 +1901,15 @@ pub struct AgentBuilder {
     agent_name: Option<String>,
     aws_credentials: Option<AwsCredentials>,
     middlewares: Vec<Arc<dyn ToolMiddleware>>,
+    mcp_health: Vec<Arc<crate::mcp::MCPHealth>>,
+    notification_sinks: Vec<Arc<dyn callbacks::NotificationSink>>,
+    /// File the system prompt was read from, and whether to interpolate env vars
+    system_prompt_file: Option<(std::path::PathBuf, bool)>,
+    #[cfg(feature = "hot-reload")]
+    prompt_hot_reload: bool,
+    /// Record invalid setter values instead of panicking
+    validating: bool,
+    validation_errors: Vec<String>,
 }
 
 /// AWS credentials for programmatic authentication
@@ -1056,9 +1932,195 @@ impl AgentBuilder {
             agent_name: None,
             aws_credentials: None,
             middlewares: Vec::new(),
+            mcp_health: Vec::new(),
+            notification_sinks: Vec::new(),
+            system_prompt_file: None,
+            #[cfg(feature = "hot-reload")]
+            prompt_hot_reload: false,
+            validating: false,
+            validation_errors: Vec::new(),
         }
     }
 
+    /// Create a builder from a TOML or YAML agent file
+    ///
+    /// The file describes the model, prompts, tool groups, retry, telemetry,
+    /// evaluation strategy and limits, with `${VAR}` / `${VAR:-default}`
+    /// environment variable interpolation. See [`crate::config::agent_file`]
+    /// for the format. The returned builder can be customized further, e.g. to
+    /// add application tools.
+    ///
+    /// Invalid values are reported when building, as with [`validating`](Self::validating).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use stood::agent::AgentBuilder;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut agent = AgentBuilder::from_config_file("agents/support.toml")?
+    ///     .try_build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_config_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = crate::config::AgentFileConfig::from_file(path).map_err(|e| {
+            StoodError::configuration_error(format!(
+                "Failed to load agent file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::from_agent_config(file)
+    }
+
+    /// Create a builder from the `STOOD_*` environment variables
+    ///
+    /// Sets the provider, model, inference parameters, execution limits and
+    /// telemetry as described in [`crate::config::environment`]. Fails with
+    /// every missing or invalid variable if any are found; unknown variables
+    /// are logged as warnings.
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_config(crate::config::from_env())
+    }
+
+    /// Create a builder from configuration read with [`crate::config::from_env`]
+    pub fn from_env_config(config: crate::config::EnvConfig) -> Result<Self> {
+        config.report.log();
+        config
+            .report
+            .into_result()
+            .map_err(|e| StoodError::configuration_error(e.to_string()))?;
+
+        let model = create_model_from_config(&config.agent.provider, &config.agent.model_id);
+        if model.provider() != config.agent.provider || model.model_id() != config.agent.model_id {
+            return Err(StoodError::configuration_error(format!(
+                "Unknown model '{}' for provider '{}'",
+                config.agent.model_id,
+                config.agent.provider.as_str()
+            )));
+        }
+
+        let mut builder = Self::new();
+        builder.agent_id = config.agent.agent_id.clone();
+        builder.agent_name = config.agent.agent_name.clone();
+        builder.config = config.agent;
+        builder.config.telemetry_config = Some(config.telemetry);
+        builder.execution_config = config.execution;
+        builder.model = Some(model);
+        Ok(builder)
+    }
+
+    /// Create a builder from an already loaded agent definition
+    ///
+    /// Like [`from_config_file`](Self::from_config_file), for definitions
+    /// embedded in other documents such as [workflows](crate::workflow).
+    pub fn from_agent_config(file: crate::config::AgentFileConfig) -> Result<Self> {
+        Self::new().validating().apply_config_file(file)
+    }
+
+    fn apply_config_file(mut self, file: crate::config::AgentFileConfig) -> Result<Self> {
+        use crate::config::agent_file::EvaluationSection;
+
+        let provider = file
+            .provider()
+            .map_err(|e| StoodError::configuration_error(e.to_string()))?;
+        let model = create_model_from_config(&provider, &file.model.id);
+        if model.provider() != provider || model.model_id() != file.model.id {
+            return Err(StoodError::configuration_error(format!(
+                "Unknown model '{}' for provider '{}'",
+                file.model.id,
+                provider.as_str()
+            )));
+        }
+        self.config.provider = provider;
+        self.config.model_id = file.model.id.clone();
+        self.model = Some(model);
+
+        if let Some(temperature) = file.model.temperature {
+            self = self.temperature(temperature);
+        }
+        if let Some(max_tokens) = file.model.max_tokens {
+            self = self.max_tokens(max_tokens);
+        }
+        if let Some(name) = file.name {
+            self = self.name(name);
+        }
+        if let Some(id) = file.id {
+            self = self.with_id(id);
+        }
+        if let Some(system) = file.prompt.system {
+            self = self.system_prompt(system);
+        }
+        if let Some(path) = file.prompt.system_file {
+            self.system_prompt_file = Some((path, true));
+        }
+
+        for group in &file.tools.groups {
+            self.tools.extend(group.tools());
+        }
+        if let Some(max_parallel) = file.tools.max_parallel {
+            self = self.max_parallel_tools(max_parallel);
+        }
+        if let Some(max_iterations) = file.tools.max_iterations {
+            self.execution_config.event_loop.max_tool_iterations = max_iterations;
+        }
+        if let Some(secs) = file.tools.timeout_secs {
+            self.execution_config
+                .event_loop
+                .tool_config
+                .execution_timeout = Duration::from_secs(secs);
+        }
+
+        if let Some(retry) = &file.retry {
+            self = self.with_retry_config(retry.retry_config());
+        }
+        if let Some(telemetry) = &file.telemetry {
+            self = self.with_telemetry(telemetry.telemetry_config());
+        }
+        match file.evaluation {
+            Some(EvaluationSection::None) | None => {}
+            Some(EvaluationSection::Task { prompt }) => {
+                self = self.with_task_evaluation(prompt);
+            }
+            Some(EvaluationSection::MultiPerspective { perspectives }) => {
+                self = self.with_multi_perspective_evaluation(perspectives);
+            }
+        }
+
+        if let Some(max_cycles) = file.limits.max_cycles {
+            self = self.with_max_cycles(max_cycles);
+        }
+        if let Some(secs) = file.limits.max_duration_secs {
+            self = self.with_max_duration(Duration::from_secs(secs));
+        }
+        if let Some(max_total_tokens) = file.limits.max_total_tokens {
+            self = self.with_max_total_tokens(max_total_tokens);
+        }
+        if let Some(streaming) = file.streaming {
+            self = self.with_streaming(streaming);
+        }
+        Ok(self)
+    }
+
+    /// Record invalid values passed to setters instead of panicking
+    ///
+    /// The recorded problems are returned by [`validate`](Self::validate),
+    /// [`try_build`](Self::try_build) and [`build`](Self::build).
+    pub fn validating(mut self) -> Self {
+        self.validating = true;
+        self
+    }
+
+    /// Panic with `message`, or record it when the builder is validating
+    fn invalid(&mut self, message: String) {
+        if !self.validating {
+            panic!("{}", message);
+        }
+        self.validation_errors.push(message);
+    }
+
     /// Set model using LLM provider system
     ///
     /// # Examples
@@ -1102,7 +2164,11 @@ impl AgentBuilder {
 
     pub fn temperature(mut self, temperature: f32) -> Self {
         if !(0.0..=1.0).contains(&temperature) {
-            panic!("Temperature must be between 0.0 and 1.0");
+            self.invalid(format!(
+                "Temperature must be between 0.0 and 1.0 (got {})",
+                temperature
+            ));
+            return self;
         }
         self.config.temperature = Some(temperature);
         self
@@ -1110,7 +2176,8 @@ impl AgentBuilder {
 
     pub fn max_tokens(mut self, max_tokens: u32) -> Self {
         if max_tokens == 0 {
-            panic!("Max tokens must be greater than 0");
+            self.invalid("Max tokens must be greater than 0".to_string());
+            return self;
         }
         self.config.max_tokens = Some(max_tokens);
         self
@@ -1121,6 +2188,290 @@ impl AgentBuilder {
         self
     }
 
+    /// Read the system prompt from a file
+    ///
+    /// With the `hot-reload` feature and `with_prompt_hot_reload`, the file is
+    /// re-read before each execution after it changes.
+    pub fn system_prompt_file<P: AsRef<std::path::Path>>(mut self, path: P) -> Self {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(prompt) => {
+                self.config.system_prompt = Some(prompt);
+                self.system_prompt_file = Some((path.to_path_buf(), false));
+            }
+            Err(e) => self.invalid(format!(
+                "Failed to read system prompt file {}: {}",
+                path.display(),
+                e
+            )),
+        }
+        self
+    }
+
+    /// Reload the system prompt file between executions when it changes
+    ///
+    /// Applies to the file set with [`system_prompt_file`](Self::system_prompt_file)
+    /// or an agent file's `prompt.system_file`. Executions already in progress keep
+    /// the prompt they started with. See [`WatchedPrompt`] to reload prompt template
+    /// fragments.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use stood::agent::Agent;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let agent = Agent::builder()
+    ///     .system_prompt_file("prompts/support.md")
+    ///     .with_prompt_hot_reload()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "hot-reload")]
+    pub fn with_prompt_hot_reload(mut self) -> Self {
+        self.prompt_hot_reload = true;
+        self
+    }
+
+    /// Route each execution to a model based on prompt complexity
+    ///
+    /// The model set with [`model`](Self::model) is used for complexity levels the
+    /// router has no route for. See [`ModelRouter`] for details.
+    pub fn with_model_router(mut self, router: ModelRouter) -> Self {
+        self.config.model_router = Some(router);
+        self
+    }
+
+    /// Set where tools resolve secrets and environment variables
+    ///
+    /// Wrap the provider in [`ScopedSecrets`](crate::secrets::ScopedSecrets) to
+    /// restrict which names tools can read. Without this, tools such as `env_var`
+    /// can only read the variables in
+    /// [`DEFAULT_ENV_ALLOWLIST`](crate::secrets::DEFAULT_ENV_ALLOWLIST).
+    pub fn with_secrets(mut self, secrets: Arc<dyn SecretsProvider>) -> Self {
+        self.config.secrets = Some(secrets);
+        self
+    }
+
+    /// Respond in a fixed language, or in the language of each prompt
+    ///
+    /// The instruction is added to the system prompt, and a response in another
+    /// language is retried once unless the turn called tools. See [`language`].
+    pub fn with_response_language(mut self, language: ResponseLanguage) -> Self {
+        self.config.response_language = Some(language);
+        self
+    }
+
+    /// Register a persona that [`Agent::switch_persona`] can activate
+    ///
+    /// A persona registered under the same name replaces the earlier one.
+    pub fn with_persona(mut self, persona: Persona) -> Self {
+        self.config.personas.insert(persona.name.clone(), persona);
+        self
+    }
+
+    /// Transcribe prompts given to [`Agent::execute_from_audio`]
+    pub fn with_transcriber(mut self, transcriber: Arc<dyn Transcriber>) -> Self {
+        self.config.transcriber = Some(transcriber);
+        self
+    }
+
+    /// Speak responses returned by [`Agent::respond_as_audio`]
+    pub fn with_synthesizer(mut self, synthesizer: Arc<dyn Synthesizer>) -> Self {
+        self.config.synthesizer = Some(synthesizer);
+        self
+    }
+
+    /// Add a processor that transforms the final response before it is returned
+    ///
+    /// Processors run in the order they are added. See
+    /// [`response_processor`] for the built-in processors.
+    pub fn with_response_processor(mut self, processor: Arc<dyn ResponseProcessor>) -> Self {
+        self.config.response_processors.push(processor);
+        self
+    }
+
+    /// Constrain tool parameter values beyond their JSON schemas
+    ///
+    /// Calls that violate a guardrail are not executed; the model receives an
+    /// error result explaining the violation. See [`crate::tools::guardrails`]
+    /// for the constraint syntax.
+    ///
+    /// ```no_run
+    /// # use stood::agent::Agent;
+    /// # use stood::tools::ToolGuardrails;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let builder = Agent::builder().with_tool_guardrails(ToolGuardrails::parse(
+    ///     "*.path under /workspace\nhttp_request.url host in docs.rs",
+    /// )?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tool_guardrails(mut self, guardrails: ToolGuardrails) -> Self {
+        self.config.tool_guardrails.extend(guardrails);
+        self
+    }
+
+    /// Cap how often the model may call individual tools in one execution
+    ///
+    /// Each limit is stated in the tool's description. Calls over the limit
+    /// are not executed; the model receives an error result telling it to stop
+    /// calling the tool and what to do instead. See [`crate::tools::call_limits`].
+    ///
+    /// ```no_run
+    /// # use stood::agent::Agent;
+    /// # use stood::tools::ToolCallLimits;
+    /// let builder = Agent::builder().with_tool_call_limits(
+    ///     ToolCallLimits::new()
+    ///         .with_limit("http_request", 10)
+    ///         .with_limit("file_write", 3)
+    ///         .with_alternative("http_request", "Answer from the pages already fetched."),
+    /// );
+    /// ```
+    pub fn with_tool_call_limits(mut self, limits: ToolCallLimits) -> Self {
+        self.config.tool_call_limits.extend(limits);
+        self
+    }
+
+    /// Only register tools whose signed manifest `verifier` accepts
+    ///
+    /// Building the agent fails if any tool is refused. See
+    /// [`crate::tools::manifest`].
+    pub fn with_tool_manifest_verifier(mut self, verifier: ManifestVerifier) -> Self {
+        self.config.tool_manifest_verifier = Some(verifier);
+        self
+    }
+
+    /// Give each execution a [`Workspace`] shared by its tools
+    ///
+    /// Tools reach it through [`AgentContext::workspace`] to exchange large
+    /// intermediate data by reference instead of through the conversation. The
+    /// workspace is deleted when the execution ends. See
+    /// [`crate::tools::workspace`].
+    pub fn with_workspace(mut self) -> Self {
+        self.config.workspace = true;
+        self
+    }
+
+    /// Choose how the conversation history is trimmed once it exceeds its limits
+    ///
+    /// Defaults to [`KeepLastN`]. See [`context_policy`] for the available
+    /// policies.
+    pub fn with_context_policy(mut self, policy: Arc<dyn ContextPolicy>) -> Self {
+        self.config.context_policy = Some(policy);
+        self
+    }
+
+    /// Give the model a `plan` tool to write and update a step-by-step task plan
+    ///
+    /// The plan is kept across cycles and executions, and returned in
+    /// [`AgentResult::plan`]. With `render_in_system_prompt`, the current plan
+    /// is appended to the system prompt before every model call. See
+    /// [`crate::tools::plan`].
+    pub fn with_plan_tool(mut self, render_in_system_prompt: bool) -> Self {
+        let state = PlanState::new();
+        self.tools
+            .push(Box::new(PlanTool::with_state(state.clone())));
+        if render_in_system_prompt {
+            self.execution_config
+                .event_loop
+                .cycle_hooks
+                .push(Arc::new(PlanPromptHook::new(state.clone())));
+        }
+        self.config.plan = Some(state);
+        self
+    }
+
+    /// Record every tool execution in a tamper-evident audit log
+    ///
+    /// See [`crate::tools::audit`] for the record format and the available sinks.
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.config.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Set a variable of templated tool descriptions
+    ///
+    /// Tool descriptions can refer to it as `{{name}}`; it is resolved each
+    /// time the tool schemas are sent to the model, together with built-in
+    /// variables such as `{{date}}` and `{{workspace}}`. See
+    /// [`crate::tools::templating`].
+    pub fn with_tool_description_variable(
+        mut self,
+        name: impl Into<String>,
+        value: impl ToString,
+    ) -> Self {
+        self.config.tool_description_variables.set(name, value);
+        self
+    }
+
+    /// Offer `version` of the tool `name` to the model instead of the latest
+    ///
+    /// The version must be registered with the agent's tools, e.g. by adding
+    /// several versions with [`tool`](Self::tool); building fails otherwise.
+    /// See [`crate::tools::versioning`].
+    pub fn with_tool_version(
+        mut self,
+        name: impl Into<String>,
+        version: impl Into<String>,
+    ) -> Self {
+        self.config
+            .tool_versions
+            .insert(name.into(), version.into());
+        self
+    }
+
+    /// Enforce the quota of `session_id` in `manager` on every execution
+    ///
+    /// Executions of a session over its limits fail with
+    /// [`StoodError::QuotaExceeded`] before the model is called; the tokens of
+    /// each execution are recorded against the session. Share one
+    /// [`QuotaManager`] between the agents of all sessions.
+    pub fn with_quota(mut self, manager: QuotaManager, session_id: impl Into<String>) -> Self {
+        self.config.quota = Some(SessionQuota {
+            manager,
+            session_id: session_id.into(),
+        });
+        self
+    }
+
+    /// Register every execution with a shutdown tracker
+    ///
+    /// Once shutdown starts, new executions fail with
+    /// [`StoodError::ServiceUnavailable`]; running ones get
+    /// [`ShutdownConfig::request_timeout`](crate::shutdown::ShutdownConfig::request_timeout)
+    /// to finish before they are cancelled and return their partial results.
+    /// Enables cancellation if it is not already enabled. Pass
+    /// [`ShutdownManager::executions`](crate::shutdown::ShutdownManager::executions).
+    pub fn with_shutdown(mut self, executions: InFlightExecutions) -> Self {
+        self.config.shutdown = Some(executions);
+        if self
+            .execution_config
+            .event_loop
+            .cancellation_token
+            .is_none()
+        {
+            self = self.with_cancellation();
+        }
+        self
+    }
+
+    /// Set how many executions are kept in [`Agent::metrics_history`] (0 disables it)
+    pub fn with_metrics_history_size(mut self, size: usize) -> Self {
+        self.execution_config.metrics_history_size = size;
+        self
+    }
+
+    /// Compose the system prompt from fragments evaluated on every execution
+    ///
+    /// Takes precedence over [`system_prompt`](Self::system_prompt). See
+    /// [`SystemPromptBuilder`] for ordering and token budget options.
+    pub fn with_system_prompt_builder(mut self, builder: SystemPromptBuilder) -> Self {
+        self.config.system_prompt_builder = Some(builder);
+        self
+    }
+
     pub fn name<S: Into<String>>(mut self, name: S) -> Self {
         self.agent_name = Some(name.into());
         self
@@ -1260,6 +2611,32 @@ impl AgentBuilder {
         self
     }
 
+    /// Tell the model which tools kept failing in earlier executions
+    ///
+    /// Before each execution, tools that are flaky under `policy` are listed in a
+    /// short note appended to the system prompt, with their failure counts and
+    /// last errors, so the model avoids retrying broken tools. The history is
+    /// kept by the registry's [`ToolReliabilityTracker`](crate::tools::ToolReliabilityTracker).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use stood::agent::Agent;
+    /// use stood::tools::FlakyToolPolicy;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let agent = Agent::builder()
+    ///     .with_builtin_tools()
+    ///     .with_flaky_tool_notes(FlakyToolPolicy::default())
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_flaky_tool_notes(mut self, policy: FlakyToolPolicy) -> Self {
+        self.config.flaky_tool_policy = Some(policy);
+        self
+    }
+
     /// Add all built-in tools to the agent
     pub fn with_builtin_tools(mut self) -> Self {
         // We'll implement this by creating builtin tools
@@ -1268,7 +2645,11 @@ impl AgentBuilder {
             Box::new(crate::tools::builtin::FileReadTool::new()) as Box<dyn Tool>,
             Box::new(crate::tools::builtin::FileWriteTool::new()) as Box<dyn Tool>,
             Box::new(crate::tools::builtin::FileListTool::new()) as Box<dyn Tool>,
+            Box::new(crate::tools::builtin::FileEditTool::new()) as Box<dyn Tool>,
+            Box::new(crate::tools::builtin::GrepTool::new()) as Box<dyn Tool>,
+            Box::new(crate::tools::builtin::GlobTool::new()) as Box<dyn Tool>,
             Box::new(crate::tools::builtin::HttpRequestTool::new()) as Box<dyn Tool>,
+            Box::new(crate::tools::builtin::WebPageTool::new()) as Box<dyn Tool>,
             Box::new(crate::tools::builtin::CurrentTimeTool::new()) as Box<dyn Tool>,
             Box::new(crate::tools::builtin::EnvVarTool::new()) as Box<dyn Tool>,
         ];
@@ -1326,6 +2707,201 @@ impl AgentBuilder {
         self
     }
 
+    /// Add a hook that runs around every model call and tool batch
+    ///
+    /// Unlike tool middleware, cycle hooks receive mutable access to the conversation,
+    /// so they can inject dynamic context such as the current time or retrieved
+    /// documents. Hooks run in the order they were added. See [`hooks`] for details.
+    pub fn with_cycle_hook(mut self, hook: Arc<dyn CycleHook>) -> Self {
+        self.execution_config.event_loop.cycle_hooks.push(hook);
+        self
+    }
+
+    /// Set the maximum number of model interaction cycles per execution (default: 10)
+    pub fn with_max_cycles(mut self, max_cycles: u32) -> Self {
+        self.execution_config.event_loop.max_cycles = max_cycles;
+        self
+    }
+
+    /// Set the maximum wall-clock duration of an execution (default: 5 minutes)
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.execution_config.event_loop.max_duration = max_duration;
+        self
+    }
+
+    /// Stop an execution once it has consumed `max_tokens` input + output tokens
+    ///
+    /// The budget is checked before each tool round and after each cycle, so the
+    /// model call in flight when the budget runs out still completes.
+    pub fn with_max_total_tokens(mut self, max_tokens: u32) -> Self {
+        self.execution_config.event_loop.max_total_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Add a custom condition checked after each cycle that would otherwise continue
+    ///
+    /// When a condition returns a reason, execution stops and the result's
+    /// [`AgentResult::termination_reason`] is [`TerminationReason::Condition`].
+    /// See [`stop`] for details.
+    pub fn with_stop_condition(mut self, condition: Arc<dyn StopCondition>) -> Self {
+        self.execution_config
+            .event_loop
+            .stop_conditions
+            .push(condition);
+        self
+    }
+
+    /// Watch model responses with `monitor` and stop on anomalous behavior
+    ///
+    /// Several agents can share one monitor, so a tripped circuit breaker
+    /// halts all of them. See [`anomaly`] for what is detected.
+    pub fn with_behavior_monitor(mut self, monitor: BehaviorMonitor) -> Self {
+        self.execution_config.event_loop.behavior_monitor = Some(monitor);
+        self
+    }
+
+    /// Enable conversation compaction
+    ///
+    /// Older turns are replaced with a model-written summary when context usage
+    /// passes the configured threshold. If `config.expose_tool` is set, the model
+    /// also gets the `compact_context` tool so it can compact on its own.
+    ///
+    /// ```no_run
+    /// use stood::agent::Agent;
+    /// use stood::context_manager::CompactionConfig;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let agent = Agent::builder()
+    ///     .with_context_compaction(
+    ///         CompactionConfig::default()
+    ///             .with_trigger_percentage(Some(60.0))
+    ///             .with_keep_recent_messages(8),
+    ///     )
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_context_compaction(mut self, config: CompactionConfig) -> Self {
+        if config.expose_tool {
+            self.tools
+                .push(Box::new(crate::tools::builtin::CompactContextTool::new()) as Box<dyn Tool>);
+        }
+        self.execution_config.event_loop.compaction = Some(config);
+        self
+    }
+
+    /// Force how the model chooses tools on the first model call of each execution
+    ///
+    /// Use `ToolChoice::tool(name)` to always start with a specific tool (e.g. a
+    /// planning tool) or `ToolChoice::Any` to require some tool call. Later
+    /// model calls let the model decide. Can be overridden per call with
+    /// [`ExecuteOptions::with_tool_choice`].
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.execution_config.event_loop.first_call_tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Start tools as soon as their input has been streamed, before the model
+    /// finishes the rest of its turn
+    ///
+    /// Reduces latency when the model calls several tools or writes text after
+    /// a tool call. Only applies with streaming enabled and without cycle hooks.
+    /// Tools started early run even if the response later fails, so only
+    /// enable this when the tools are safe to run speculatively.
+    pub fn with_early_tool_start(mut self) -> Self {
+        self.execution_config.event_loop.early_tool_start = true;
+        self
+    }
+
+    /// Have the model cite documents returned by tools
+    ///
+    /// Tool results containing objects with a `source_id` are sent with
+    /// instructions to cite them inline, and the citations are returned in
+    /// [`AgentResult::citations`]. See the [`citations`] module for the format.
+    pub fn with_citations(mut self) -> Self {
+        self.execution_config.event_loop.citations = true;
+        self
+    }
+
+    /// Record the event loop's decisions in an execution trace
+    ///
+    /// The trace is returned in [`AgentResult::execution_trace`] and can be
+    /// saved as JSON or rendered to an HTML report for debugging why an agent
+    /// looped or stopped early. See the [`execution_trace`] module.
+    pub fn with_execution_trace(mut self) -> Self {
+        self.execution_config.event_loop.execution_trace = true;
+        self
+    }
+
+    /// Emit periodic [`CallbackEvent::Progress`](callbacks::CallbackEvent::Progress)
+    /// events with the tokens and estimated cost of the execution so far
+    ///
+    /// Events are emitted at most once per [`ProgressConfig::interval`] while
+    /// a response streams, and after every model call, tool batch and cycle.
+    /// Requires a callback handler.
+    pub fn with_progress_events(mut self, config: ProgressConfig) -> Self {
+        self.execution_config.event_loop.progress = Some(config);
+        self
+    }
+
+    /// Give slow tools interim results instead of blocking the next model call
+    ///
+    /// Tools still running when [`InterimResultConfig::deadline`] passes are
+    /// answered with their partial output so far and keep running; their final
+    /// result is added to the next tool result message.
+    ///
+    /// [`InterimResultConfig::deadline`]: crate::tools::InterimResultConfig::deadline
+    pub fn with_interim_tool_results(mut self, config: crate::tools::InterimResultConfig) -> Self {
+        self.execution_config.event_loop.interim_tool_results = Some(config);
+        self
+    }
+
+    /// Vary temperature and other inference parameters across cycles
+    ///
+    /// Cycles without a matching step in the schedule use the agent's
+    /// configured parameters.
+    pub fn with_parameter_schedule(mut self, schedule: ParameterSchedule) -> Self {
+        self.execution_config.event_loop.parameter_schedule = Some(schedule);
+        self
+    }
+
+    /// Keep the raw provider payload of every model call
+    ///
+    /// The response JSON, or the raw chunks of a streamed response, are
+    /// returned in [`ExecutionDetails::raw_responses`] and in the
+    /// `ModelComplete` callback event, for debugging and for reading
+    /// provider-specific fields stood does not model. Currently only the
+    /// Bedrock provider exposes its raw payloads.
+    pub fn with_raw_responses(mut self) -> Self {
+        self.execution_config.event_loop.capture_raw_responses = true;
+        self
+    }
+
+    /// Bound the retries of each execution across all retry layers
+    ///
+    /// Provider retries and error-recovery retries draw from one fresh copy of
+    /// `budget` per execution, so their limits no longer multiply. The
+    /// consumed budget is reported in [`AgentResult::retry_budget`].
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use stood::agent::Agent;
+    /// use stood::error_recovery::RetryBudget;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let agent = Agent::builder()
+    ///     .with_retry_budget(RetryBudget::new(4).with_max_retry_time(Duration::from_secs(60)))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.execution_config.event_loop.retry_budget = Some(budget);
+        self
+    }
+
     /// Add a think tool with custom prompt for structured problem-solving
     ///
     /// The think tool provides structured thinking guidance based on Anthropic's research.
@@ -1416,13 +2992,19 @@ impl AgentBuilder {
         })?;
 
         // Create tool adapters
+        let health = mcp_client.health();
         let mcp_client_arc = Arc::new(RwLock::new(mcp_client));
 
         for tool in tools {
-            let mcp_tool = MCPAgentTool::new(tool, mcp_client_arc.clone(), namespace.clone());
+            let mcp_tool = MCPAgentTool::new(tool, mcp_client_arc.clone(), namespace.clone())
+                .with_health(health.clone());
             self.tools.push(Box::new(mcp_tool));
         }
 
+        // Keep the connection alive and reconnect if the server goes away
+        crate::mcp::MCPClient::start_health_monitor(mcp_client_arc).await;
+        self.mcp_health.push(health);
+
         Ok(self)
     }
 
@@ -1488,12 +3070,18 @@ impl AgentBuilder {
             })?;
 
             // Create tool adapters
+            let health = mcp_client.health();
             let mcp_client_arc = Arc::new(RwLock::new(mcp_client));
 
             for tool in tools {
-                let mcp_tool = MCPAgentTool::new(tool, mcp_client_arc.clone(), namespace.clone());
+                let mcp_tool = MCPAgentTool::new(tool, mcp_client_arc.clone(), namespace.clone())
+                    .with_health(health.clone());
                 self.tools.push(Box::new(mcp_tool));
             }
+
+            // Keep the connection alive and reconnect if the server goes away
+            crate::mcp::MCPClient::start_health_monitor(mcp_client_arc).await;
+            self.mcp_health.push(health);
         }
 
         Ok(self)
@@ -1644,6 +3232,18 @@ impl AgentBuilder {
         self
     }
 
+    /// Send a notification to `sink` when an execution completes or fails
+    ///
+    /// Sinks are notified after the other callback handlers, whichever
+    /// callback configuration is set.
+    pub fn with_notification_sink<S: callbacks::NotificationSink + 'static>(
+        mut self,
+        sink: S,
+    ) -> Self {
+        self.notification_sinks.push(Arc::new(sink));
+        self
+    }
+
     /// Enable streaming by default
     pub fn with_streaming(mut self, enabled: bool) -> Self {
         self.execution_config.streaming = enabled;
@@ -1707,12 +3307,67 @@ impl AgentBuilder {
             tool_config.execution_strategy = crate::tools::executor::ExecutionStrategy::Parallel;
         }
 
+        // Keep any output budget and concurrency controller configured before this call
+        let previous = &mut self.execution_config.event_loop.tool_config;
+        tool_config.output_budget = previous.output_budget.take();
+        tool_config.adaptive_concurrency = previous.adaptive_concurrency.take();
+
         // Update the EventLoopConfig with the new tool configuration
         self.execution_config.event_loop.tool_config = tool_config;
 
         self
     }
 
+    /// Limit how much tool output can enter the conversation
+    ///
+    /// Results larger than the per-tool limit, or cycles whose combined results
+    /// exceed the per-cycle limit, are shrunk using the budget's truncation
+    /// strategy before being added to the conversation.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use stood::agent::Agent;
+    /// use stood::context_manager::{ToolOutputBudget, TruncationStrategy};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let agent = Agent::builder()
+    ///     .with_tool_output_budget(
+    ///         ToolOutputBudget::new(20_000, 60_000)
+    ///             .with_strategy(TruncationStrategy::Tail)
+    ///             .with_tool_limit("file_read", 40_000),
+    ///     )
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tool_output_budget(
+        mut self,
+        budget: crate::context_manager::ToolOutputBudget,
+    ) -> Self {
+        self.execution_config.event_loop.tool_config.output_budget = Some(budget);
+        self
+    }
+
+    /// Cap parallel tool executions with an adaptive concurrency controller
+    ///
+    /// Pass the controller installed on the model provider with
+    /// [`ProviderRegistry::set_concurrency_controller`] so that tool
+    /// parallelism shrinks along with model requests when the provider
+    /// throttles, instead of fighting it with a static `max_parallel_tools`.
+    ///
+    /// [`ProviderRegistry::set_concurrency_controller`]: crate::llm::registry::ProviderRegistry::set_concurrency_controller
+    pub fn with_adaptive_concurrency(
+        mut self,
+        controller: Arc<crate::llm::concurrency::AdaptiveConcurrency>,
+    ) -> Self {
+        self.execution_config
+            .event_loop
+            .tool_config
+            .adaptive_concurrency = Some(controller);
+        self
+    }
+
     /// Use CPU count for parallel execution (matches reference-python max_parallel_tools=None)
     ///
     /// This sets max_parallel_tools to the number of CPU cores, providing
@@ -1832,6 +3487,44 @@ impl AgentBuilder {
         self
     }
 
+    /// Limit how much of the conversation evaluation calls see
+    ///
+    /// By default evaluators get the full conversation, so evaluation costs
+    /// grow with every cycle.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use stood::agent::{Agent, TranscriptView};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let agent = Agent::builder()
+    ///     .with_task_evaluation("Have I fully answered the question?")
+    ///     .with_evaluation_transcript(TranscriptView::LastTurns(2))
+    ///     .build().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_evaluation_transcript(mut self, view: TranscriptView) -> Self {
+        self.execution_config.event_loop.evaluation_transcript = view;
+        self
+    }
+
+    /// Time box the reasoning, tool batch, evaluation and synthesis phases of
+    /// each cycle independently
+    ///
+    /// See [`phase_timeouts`] for what each phase covers.
+    pub fn with_phase_timeouts(mut self, timeouts: PhaseTimeouts) -> Self {
+        self.execution_config.event_loop.phase_timeouts = timeouts;
+        self
+    }
+
+    /// Use a separate system prompt for task and multi-perspective evaluation
+    /// calls instead of the agent's own
+    pub fn with_evaluator_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.execution_config.event_loop.evaluator_system_prompt = Some(prompt.into());
+        self
+    }
+
     /// Set a high limit for tool iterations to enable more autonomous behavior
     ///
     /// This increases the maximum number of tool execution rounds per cycle,
@@ -1980,6 +3673,133 @@ impl AgentBuilder {
         self
     }
 
+    /// Check the configuration without building the agent
+    ///
+    /// Reports every problem at once: invalid values recorded by a
+    /// [`validating`](Self::validating) builder, an empty model id, zero limits,
+    /// duplicate tool names and conflicting options.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoodError::ConfigurationError`] listing all problems found.
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = self.validation_errors.clone();
+        let config = &self.config;
+        let event_loop = &self.execution_config.event_loop;
+
+        if config.model_id.trim().is_empty() {
+            errors.push("Model id must not be empty".to_string());
+        }
+        if let Some(temperature) = config.temperature {
+            if !(0.0..=1.0).contains(&temperature) {
+                errors.push(format!(
+                    "Temperature must be between 0.0 and 1.0 (got {})",
+                    temperature
+                ));
+            }
+        }
+        if config.max_tokens == Some(0) {
+            errors.push("Max tokens must be greater than 0".to_string());
+        }
+        if config.system_prompt.is_some() && config.system_prompt_builder.is_some() {
+            errors.push(
+                "Both system_prompt and with_system_prompt_builder are set; the builder would \
+                 silently replace the static prompt"
+                    .to_string(),
+            );
+        }
+        #[cfg(feature = "hot-reload")]
+        if self.prompt_hot_reload && self.system_prompt_file.is_none() {
+            errors.push(
+                "with_prompt_hot_reload requires a system prompt file (system_prompt_file or \
+                 prompt.system_file)"
+                    .to_string(),
+            );
+        }
+        if self.aws_credentials.is_some() && config.provider != ProviderType::Bedrock {
+            errors.push(format!(
+                "AWS credentials were configured but the model uses the {:?} provider",
+                config.provider
+            ));
+        }
+        if event_loop.max_cycles == 0 {
+            errors.push("max_cycles must be greater than 0".to_string());
+        }
+        if event_loop.max_duration.is_zero() {
+            errors.push("max_duration must be greater than 0".to_string());
+        }
+        if event_loop.max_total_tokens == Some(0) {
+            errors.push("max_total_tokens must be greater than 0".to_string());
+        }
+        if event_loop.max_tool_iterations == 0 {
+            errors.push("max_tool_iterations must be greater than 0".to_string());
+        }
+        if let Err(e) = event_loop.tool_config.validate() {
+            errors.push(e.to_string());
+        }
+
+        let mut tool_names = std::collections::HashSet::new();
+        for tool in &self.tools {
+            if !tool_names.insert(tool.name()) {
+                errors.push(format!(
+                    "Tool '{}' is registered more than once",
+                    tool.name()
+                ));
+            }
+        }
+        errors.extend(Self::tool_schema_errors(&self.tools, &config.model_id));
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Self::configuration_errors(&errors))
+        }
+    }
+
+    /// Validate the configuration, then build the agent
+    ///
+    /// Unlike [`build`](Self::build), all configuration problems are reported
+    /// together before any provider is contacted. Combine with
+    /// [`validating`](Self::validating) for configuration that comes from users or
+    /// config files.
+    pub async fn try_build(self) -> Result<Agent> {
+        self.validate()?;
+        self.build().await
+    }
+
+    /// Tools whose input schema the model cannot accept, even after
+    /// translation to the model family's schema dialect
+    fn tool_schema_errors(tools: &[Box<dyn Tool>], model_id: &str) -> Vec<String> {
+        let dialect = SchemaDialect::for_model(model_id);
+        tools
+            .iter()
+            .filter_map(|tool| {
+                let translation = translate_tool_schema(dialect, &tool.parameters_schema());
+                let issues: Vec<String> =
+                    translation.issues.iter().map(|i| i.to_string()).collect();
+                (!issues.is_empty()).then(|| {
+                    format!(
+                        "Tool '{}' has a schema {} does not support: {}",
+                        tool.name(),
+                        model_id,
+                        issues.join("; ")
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn configuration_errors(errors: &[String]) -> StoodError {
+        StoodError::ConfigurationError {
+            message: format!(
+                "Invalid agent configuration ({} problem{}):\n  - {}",
+                errors.len(),
+                if errors.len() == 1 { "" } else { "s" },
+                errors.join("\n  - ")
+            ),
+        }
+    }
+
     /// Build the configured agent instance with smart defaults.
     ///
     /// Automatically creates a BedrockClient if none was provided, enabling
@@ -2037,9 +3857,46 @@ impl AgentBuilder {
     /// - Tool registration fails
     /// - Bedrock client creation fails
     pub async fn build(mut self) -> Result<Agent> {
+        if !self.validation_errors.is_empty() {
+            return Err(Self::configuration_errors(&self.validation_errors));
+        }
         crate::perf_checkpoint!("stood.agent_builder.build.start");
         let _build_guard = crate::perf_guard!("stood.agent_builder.build");
 
+        #[cfg(feature = "hot-reload")]
+        if self.prompt_hot_reload {
+            let (path, interpolate_env) = self.system_prompt_file.as_ref().ok_or_else(|| {
+                StoodError::configuration_error(
+                    "with_prompt_hot_reload requires a system prompt file",
+                )
+            })?;
+            let watched = if *interpolate_env {
+                WatchedPrompt::with_env_interpolation(path)?
+            } else {
+                WatchedPrompt::new(path)?
+            };
+            self.config.system_prompt_provider = Some(watched);
+        }
+
+        if !self.notification_sinks.is_empty() {
+            let notifier = CallbackHandlerConfig::Custom(Arc::new(
+                callbacks::NotificationCallbackHandler::new(std::mem::take(
+                    &mut self.notification_sinks,
+                )),
+            ));
+            self.execution_config.callback_handler = match std::mem::replace(
+                &mut self.execution_config.callback_handler,
+                CallbackHandlerConfig::None,
+            ) {
+                CallbackHandlerConfig::None => notifier,
+                CallbackHandlerConfig::Composite(mut configs) => {
+                    configs.push(notifier);
+                    CallbackHandlerConfig::Composite(configs)
+                }
+                other => CallbackHandlerConfig::Composite(vec![other, notifier]),
+            };
+        }
+
         // Use provided model or create default
         let model = if let Some(m) = self.model {
             // DEBUG: Log that model was found
@@ -2078,6 +3935,13 @@ impl AgentBuilder {
             self.config.max_tokens = Some(model_max);
         }
 
+        // Reject tool schemas the model cannot accept now rather than on the
+        // first request
+        let schema_errors = Self::tool_schema_errors(&self.tools, &self.config.model_id);
+        if !schema_errors.is_empty() {
+            return Err(Self::configuration_errors(&schema_errors));
+        }
+
         // CRITICAL FIX: Auto-configure provider registry with timeout and error handling
         let provider_type = model.provider();
 
@@ -2126,7 +3990,7 @@ impl AgentBuilder {
 
         // Check if provider is configured, with timeout
         let is_configured = crate::perf_timed!("stood.agent_builder.is_configured_check", {
-            tokio::time::timeout(
+            crate::runtime::timeout(
                 std::time::Duration::from_secs(5),
                 crate::llm::registry::PROVIDER_REGISTRY.is_configured(provider_type),
             )
@@ -2137,7 +4001,7 @@ impl AgentBuilder {
         if !is_configured {
             // Auto-configure with timeout
             crate::perf_timed!("stood.agent_builder.auto_configure", {
-                tokio::time::timeout(
+                crate::runtime::timeout(
                     std::time::Duration::from_secs(10),
                     crate::llm::registry::ProviderRegistry::configure(),
                 )
@@ -2153,7 +4017,7 @@ impl AgentBuilder {
 
         // Get provider from registry with timeout (THIS IS THE MAIN BOTTLENECK)
         let provider = crate::perf_timed!("stood.agent_builder.get_provider", {
-            tokio::time::timeout(
+            crate::runtime::timeout(
                 std::time::Duration::from_secs(30),
                 PROVIDER_REGISTRY.get_provider(provider_type),
             )
@@ -2166,6 +4030,30 @@ impl AgentBuilder {
             })
         })?;
 
+        // Report MCP connection health changes to the callback handler
+        if !self.mcp_health.is_empty()
+            && !matches!(
+                self.execution_config.callback_handler,
+                CallbackHandlerConfig::None
+            )
+        {
+            let handler = Agent::create_callback_handler(&self.execution_config.callback_handler)?;
+            for health in &self.mcp_health {
+                let handler = handler.clone();
+                health.add_listener(move |event| {
+                    let handler = handler.clone();
+                    let event = callbacks::CallbackEvent::McpHealth {
+                        event: event.clone(),
+                    };
+                    crate::runtime::spawn(async move {
+                        if let Err(e) = handler.handle_event(event).await {
+                            tracing::warn!("Callback handler failed on MCP health event: {}", e);
+                        }
+                    });
+                });
+            }
+        }
+
         // Build internal agent
         let agent = crate::perf_timed!("stood.agent_builder.build_internal", {
             Agent::build_internal(
@@ -2262,6 +4150,79 @@ mod tests {
         Agent::builder().max_tokens(0);
     }
 
+    #[tokio::test]
+    async fn test_agent_builder_try_build_collects_errors() {
+        let builder = Agent::builder()
+            .validating()
+            .temperature(1.5)
+            .max_tokens(0)
+            .system_prompt("static")
+            .with_system_prompt_builder(SystemPromptBuilder::new().base("composed"))
+            .with_max_cycles(0)
+            .tool(Box::new(crate::tools::builtin::CalculatorTool::new()))
+            .tool(Box::new(crate::tools::builtin::CalculatorTool::new()));
+
+        let message = builder.validate().unwrap_err().to_string();
+        assert!(message.contains("5 problems"), "{}", message);
+        assert!(message.contains("Temperature must be between 0.0 and 1.0 (got 1.5)"));
+        assert!(message.contains("Max tokens must be greater than 0"));
+        assert!(message.contains("system_prompt"));
+        assert!(message.contains("max_cycles"));
+        assert!(message.contains("registered more than once"));
+
+        let err = builder.try_build().await.unwrap_err();
+        assert!(matches!(err, StoodError::ConfigurationError { .. }));
+        assert!(Agent::builder().validating().validate().is_ok());
+    }
+
+    #[test]
+    fn test_agent_builder_rejects_unsupported_tool_schema() {
+        use serde_json::json;
+
+        #[derive(Debug)]
+        struct UnionTool;
+
+        #[async_trait::async_trait]
+        impl Tool for UnionTool {
+            fn name(&self) -> &str {
+                "union_tool"
+            }
+            fn description(&self) -> &str {
+                "Takes a string or a number"
+            }
+            fn parameters_schema(&self) -> serde_json::Value {
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "value": { "anyOf": [{ "type": "string" }, { "type": "number" }] }
+                    }
+                })
+            }
+            async fn execute(
+                &self,
+                _parameters: Option<serde_json::Value>,
+                _agent_context: Option<&crate::agent::AgentContext>,
+            ) -> std::result::Result<crate::tools::ToolResult, crate::tools::ToolError>
+            {
+                Ok(crate::tools::ToolResult::success(json!(null)))
+            }
+        }
+
+        let claude = Agent::builder()
+            .model(crate::llm::models::Bedrock::ClaudeHaiku45)
+            .tool(Box::new(UnionTool));
+        assert!(claude.validate().is_ok());
+
+        let nova = Agent::builder()
+            .model(crate::llm::models::Bedrock::NovaLite)
+            .tool(Box::new(UnionTool))
+            .with_builtin_tools();
+        let message = nova.validate().unwrap_err().to_string();
+        assert!(message.contains("1 problem"), "{}", message);
+        assert!(message.contains("Tool 'union_tool' has a schema"));
+        assert!(message.contains("$.properties.value.anyOf"));
+    }
+
     #[tokio::test]
     async fn test_agent_history_management() {
         let mut agent = Agent::builder().build().await.unwrap();
@@ -2522,6 +4483,32 @@ mod tests {
         assert_eq!(agent.agent_name(), Some("BuilderAgent"));
     }
 
+    #[tokio::test]
+    async fn test_agent_builder_from_env_config() {
+        let config = crate::config::EnvConfig::from_vars([
+            ("STOOD_AGENT_ID", "env-agent"),
+            ("STOOD_MAX_CYCLES", "3"),
+            ("STOOD_TEMPERATURE", "0.1"),
+        ]);
+        let builder = AgentBuilder::from_env_config(config).unwrap();
+        let agent = test_support::agent_with_config(
+            Arc::new(test_support::ScriptedProvider::default()),
+            builder.config,
+            vec![],
+            builder.execution_config,
+        )
+        .await;
+        assert_eq!(agent.agent_id(), "env-agent");
+        assert_eq!(agent.config().temperature, Some(0.1));
+        assert_eq!(agent.execution_config.event_loop.max_cycles, 3);
+
+        let invalid = crate::config::EnvConfig::from_vars([("STOOD_MAX_TOKENS", "0")]);
+        let err = AgentBuilder::from_env_config(invalid).err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("STOOD_MAX_TOKENS: invalid value '0'"));
+    }
+
     #[tokio::test]
     async fn test_agent_builder_with_custom_tools() {
         use crate::tools::Tool;
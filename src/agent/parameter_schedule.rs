@@ -0,0 +1,213 @@
+//! Per-cycle scheduling of inference parameters.
+//!
+//! A [`ParameterSchedule`] varies temperature, max tokens and provider-specific
+//! parameters across the model interaction cycles of an execution, e.g. a high
+//! temperature for the first, exploratory cycle and a low one for the last,
+//! synthesis cycle. Cycles without a matching step use the agent's configured
+//! parameters.
+//!
+//! ```no_run
+//! use stood::agent::parameter_schedule::{InferenceParameters, ParameterSchedule};
+//! use stood::agent::Agent;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let schedule = ParameterSchedule::new()
+//!     .first_cycle(InferenceParameters::new().with_temperature(0.9))
+//!     .from_cycle(2, InferenceParameters::new().with_temperature(0.5))
+//!     .last_cycle(InferenceParameters::new().with_temperature(0.1));
+//!
+//! let mut agent = Agent::builder()
+//!     .with_max_cycles(4)
+//!     .with_parameter_schedule(schedule)
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::llm::traits::ChatConfig;
+
+/// Which cycles a schedule step applies to
+///
+/// Cycles are numbered from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CycleSelector {
+    /// The first cycle of each execution
+    First,
+    /// The last cycle allowed by `max_cycles`
+    Last,
+    /// A single cycle
+    Cycle(u32),
+    /// A cycle and every cycle after it
+    From(u32),
+    /// An inclusive range of cycles
+    Between(u32, u32),
+}
+
+impl CycleSelector {
+    /// Whether `cycle` of an execution limited to `max_cycles` is selected
+    pub fn matches(&self, cycle: u32, max_cycles: u32) -> bool {
+        match *self {
+            Self::First => cycle == 1,
+            Self::Last => cycle == max_cycles,
+            Self::Cycle(n) => cycle == n,
+            Self::From(n) => cycle >= n,
+            Self::Between(start, end) => (start..=end).contains(&cycle),
+        }
+    }
+}
+
+/// Inference parameters that override the agent's configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InferenceParameters {
+    /// Sampling temperature
+    pub temperature: Option<f32>,
+    /// Maximum tokens in the model's response
+    pub max_tokens: Option<u32>,
+    /// Provider-specific parameters, e.g. `top_p`
+    #[serde(default)]
+    pub additional_params: HashMap<String, Value>,
+}
+
+impl InferenceParameters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    pub fn with_param(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.additional_params.insert(key.into(), value);
+        self
+    }
+
+    /// Overlay `other` on these parameters; values set in `other` win
+    fn merge(&mut self, other: &InferenceParameters) {
+        if other.temperature.is_some() {
+            self.temperature = other.temperature;
+        }
+        if other.max_tokens.is_some() {
+            self.max_tokens = other.max_tokens;
+        }
+        self.additional_params.extend(
+            other
+                .additional_params
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone())),
+        );
+    }
+}
+
+/// Inference parameters that vary across the cycles of an execution
+///
+/// Steps are applied in the order they were added, so when several steps
+/// select a cycle, values set by later steps win.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParameterSchedule {
+    steps: Vec<(CycleSelector, InferenceParameters)>,
+}
+
+impl ParameterSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `parameters` for the cycles selected by `cycles`
+    pub fn at(mut self, cycles: CycleSelector, parameters: InferenceParameters) -> Self {
+        self.steps.push((cycles, parameters));
+        self
+    }
+
+    /// Use `parameters` for the first cycle
+    pub fn first_cycle(self, parameters: InferenceParameters) -> Self {
+        self.at(CycleSelector::First, parameters)
+    }
+
+    /// Use `parameters` for the last cycle allowed by `max_cycles`
+    pub fn last_cycle(self, parameters: InferenceParameters) -> Self {
+        self.at(CycleSelector::Last, parameters)
+    }
+
+    /// Use `parameters` from `cycle` onwards
+    pub fn from_cycle(self, cycle: u32, parameters: InferenceParameters) -> Self {
+        self.at(CycleSelector::From(cycle), parameters)
+    }
+
+    /// Whether the schedule has no steps
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The overrides for `cycle` of an execution limited to `max_cycles`
+    pub fn parameters_for(&self, cycle: u32, max_cycles: u32) -> InferenceParameters {
+        let mut parameters = InferenceParameters::default();
+        for (cycles, step) in &self.steps {
+            if cycles.matches(cycle, max_cycles) {
+                parameters.merge(step);
+            }
+        }
+        parameters
+    }
+
+    /// Apply the overrides for `cycle` to a model call's configuration
+    pub fn apply(&self, cycle: u32, max_cycles: u32, config: &mut ChatConfig) {
+        let parameters = self.parameters_for(cycle, max_cycles);
+        if parameters.temperature.is_some() {
+            config.temperature = parameters.temperature;
+        }
+        if parameters.max_tokens.is_some() {
+            config.max_tokens = parameters.max_tokens;
+        }
+        config
+            .additional_params
+            .extend(parameters.additional_params);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_later_steps_override_earlier_ones() {
+        let schedule = ParameterSchedule::new()
+            .first_cycle(
+                InferenceParameters::new()
+                    .with_temperature(0.9)
+                    .with_param("top_p", json!(0.95)),
+            )
+            .from_cycle(2, InferenceParameters::new().with_temperature(0.5))
+            .last_cycle(
+                InferenceParameters::new()
+                    .with_temperature(0.1)
+                    .with_max_tokens(2_000),
+            );
+
+        let first = schedule.parameters_for(1, 4);
+        assert_eq!(first.temperature, Some(0.9));
+        assert_eq!(first.additional_params["top_p"], json!(0.95));
+        assert_eq!(schedule.parameters_for(3, 4).temperature, Some(0.5));
+        let last = schedule.parameters_for(4, 4);
+        assert_eq!(last.temperature, Some(0.1));
+        assert_eq!(last.max_tokens, Some(2_000));
+
+        // A single-cycle execution is both first and last
+        assert_eq!(schedule.parameters_for(1, 1).temperature, Some(0.1));
+        assert_eq!(schedule.parameters_for(1, 1).max_tokens, Some(2_000));
+    }
+}
@@ -0,0 +1,305 @@
+//! Personas an agent can switch between at runtime.
+//!
+//! A [`Persona`] bundles a system prompt, a tool set, a model and sampling
+//! parameters under a name. Personas are registered with
+//! [`AgentBuilder::with_persona`](crate::agent::AgentBuilder::with_persona) and
+//! activated with [`Agent::switch_persona`] between executions. The
+//! conversation history is kept, so one agent can draft, review and summarize
+//! the same work instead of handing transcripts between several agents.
+//!
+//! ```no_run
+//! use stood::agent::{Agent, Persona};
+//! use stood::llm::models::Bedrock;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut agent = Agent::builder()
+//!     .with_builtin_tools()
+//!     .with_persona(
+//!         Persona::new("reviewer")
+//!             .with_system_prompt("You review code for bugs. Be terse.")
+//!             .with_tools(["file_read", "grep"])
+//!             .with_model(Bedrock::ClaudeSonnet45)
+//!             .with_temperature(0.1),
+//!     )
+//!     .build()
+//!     .await?;
+//!
+//! agent.execute("Write a function that parses ISO dates").await?;
+//! agent.switch_persona("reviewer").await?;
+//! agent.execute("Review the function you just wrote").await?;
+//! agent.reset_persona().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Settings a persona leaves unset are those the agent was built with. A
+//! [`SystemPromptBuilder`](crate::agent::SystemPromptBuilder) or prompt
+//! provider configured on the agent still takes precedence over the persona's
+//! system prompt.
+
+use super::router::{ModelRoute, ModelRouter};
+use super::Agent;
+use crate::llm::traits::LlmModel;
+use crate::{Result, StoodError};
+
+/// A named configuration of an agent
+#[derive(Debug, Clone, PartialEq)]
+pub struct Persona {
+    pub name: String,
+    /// System prompt of the persona; `None` keeps the agent's
+    pub system_prompt: Option<String>,
+    /// Names of the tools the persona may use; `None` allows all registered tools
+    pub tools: Option<Vec<String>>,
+    /// Model of the persona; `None` keeps the agent's
+    pub model: Option<ModelRoute>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+impl Persona {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            system_prompt: None,
+            tools: None,
+            model: None,
+            temperature: None,
+            max_tokens: None,
+        }
+    }
+
+    pub fn with_system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    /// Only offer the tools named in `tools` to the model
+    pub fn with_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tools = Some(tools.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Offer no tools to the model
+    pub fn without_tools(self) -> Self {
+        self.with_tools(Vec::<String>::new())
+    }
+
+    pub fn with_model<M: LlmModel>(mut self, model: M) -> Self {
+        self.model = Some(ModelRoute::from_model(&model));
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+}
+
+/// Settings of the agent before its first persona switch
+#[derive(Debug, Clone)]
+pub(super) struct PersonaBaseline {
+    system_prompt: Option<String>,
+    model: ModelRoute,
+    model_router: Option<ModelRouter>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+}
+
+impl Agent {
+    /// Activate the registered persona `name` for the following executions
+    ///
+    /// Replaces the settings of any previously active persona; the
+    /// conversation history is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoodError::ConfigurationError`] if no persona `name` is
+    /// registered, or if its tool set names a tool that is not registered.
+    pub async fn switch_persona(&mut self, name: &str) -> Result<()> {
+        let persona = self.config.personas.get(name).cloned().ok_or_else(|| {
+            let mut known: Vec<_> = self.config.personas.keys().cloned().collect();
+            known.sort();
+            StoodError::configuration_error(format!(
+                "Unknown persona '{}'. Registered personas: {}",
+                name,
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            ))
+        })?;
+        for tool in persona.tools.iter().flatten() {
+            if !self.tool_registry.has_tool(tool).await {
+                return Err(StoodError::configuration_error(format!(
+                    "Persona '{}' uses tool '{}', which is not registered",
+                    name, tool
+                )));
+            }
+        }
+
+        let baseline = self.persona_baseline();
+        self.apply_persona_settings(
+            persona.system_prompt.clone().or(baseline.system_prompt),
+            persona.model.clone().unwrap_or(baseline.model),
+            if persona.model.is_some() {
+                None
+            } else {
+                baseline.model_router
+            },
+        )
+        .await?;
+        self.config.temperature = persona.temperature.or(baseline.temperature);
+        self.config.max_tokens = persona.max_tokens.or(baseline.max_tokens);
+        self.config.active_persona = Some(persona.name.clone());
+        tracing::debug!("Switched agent {} to persona '{}'", self.agent_id, name);
+        Ok(())
+    }
+
+    /// Deactivate the active persona, restoring the settings the agent was built with
+    pub async fn reset_persona(&mut self) -> Result<()> {
+        let Some(baseline) = self.persona_baseline.clone() else {
+            return Ok(());
+        };
+        self.apply_persona_settings(
+            baseline.system_prompt,
+            baseline.model,
+            baseline.model_router,
+        )
+        .await?;
+        self.config.temperature = baseline.temperature;
+        self.config.max_tokens = baseline.max_tokens;
+        self.config.active_persona = None;
+        Ok(())
+    }
+
+    /// The active persona, if any
+    pub fn active_persona(&self) -> Option<&Persona> {
+        self.config
+            .active_persona
+            .as_ref()
+            .and_then(|name| self.config.personas.get(name))
+    }
+
+    /// Names of the tools offered to the model, if the active persona restricts them
+    pub(super) fn persona_tools(&self) -> Option<&[String]> {
+        self.active_persona()?.tools.as_deref()
+    }
+
+    fn persona_baseline(&mut self) -> PersonaBaseline {
+        let config = &self.config;
+        self.persona_baseline
+            .get_or_insert_with(|| PersonaBaseline {
+                system_prompt: config.system_prompt.clone(),
+                model: ModelRoute {
+                    provider: config.provider,
+                    model_id: config.model_id.clone(),
+                },
+                model_router: config.model_router.clone(),
+                temperature: config.temperature,
+                max_tokens: config.max_tokens,
+            })
+            .clone()
+    }
+
+    async fn apply_persona_settings(
+        &mut self,
+        system_prompt: Option<String>,
+        model: ModelRoute,
+        model_router: Option<ModelRouter>,
+    ) -> Result<()> {
+        if model.provider != self.config.provider || model.model_id != self.config.model_id {
+            self.set_model_route(&model).await?;
+        }
+        self.config.model_router = model_router;
+        self.conversation.set_system_prompt(system_prompt.clone());
+        self.config.system_prompt = system_prompt;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::config::ExecutionConfig;
+    use crate::agent::test_support::{self, ScriptedProvider};
+    use crate::agent::AgentConfig;
+    use crate::llm::models::Bedrock;
+    use crate::tools::builtin::CalculatorTool;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_switch_and_reset_persona() {
+        let mut config = AgentConfig {
+            model_id: Bedrock::ClaudeHaiku45.model_id().to_string(),
+            system_prompt: Some("You are a helpful assistant.".to_string()),
+            temperature: Some(0.7),
+            ..AgentConfig::default()
+        };
+        for persona in [
+            Persona::new("reviewer")
+                .with_system_prompt("You review code.")
+                .with_tools(["calculator"])
+                .with_model(Bedrock::ClaudeSonnet45)
+                .with_temperature(0.1),
+            Persona::new("broken").with_tools(["missing_tool"]),
+        ] {
+            config.personas.insert(persona.name.clone(), persona);
+        }
+        let mut agent = test_support::agent_with_config(
+            Arc::new(ScriptedProvider::default()),
+            config,
+            vec![Box::new(CalculatorTool::new())],
+            ExecutionConfig::default(),
+        )
+        .await;
+        agent
+            .conversation_mut()
+            .add_user_message("Write a function");
+
+        agent.switch_persona("reviewer").await.unwrap();
+        assert_eq!(agent.active_persona().unwrap().name, "reviewer");
+        assert_eq!(
+            agent.conversation().system_prompt(),
+            Some("You review code.")
+        );
+        assert_eq!(agent.config().model_id, Bedrock::ClaudeSonnet45.model_id());
+        assert_eq!(agent.config().temperature, Some(0.1));
+        assert_eq!(
+            agent.persona_tools(),
+            Some(["calculator".to_string()].as_slice())
+        );
+        assert_eq!(agent.conversation().message_count(), 1);
+        assert!(agent
+            .tool_registry
+            .restricted_to(&[])
+            .await
+            .tool_names()
+            .await
+            .is_empty());
+        assert!(agent.tool_registry.has_tool("calculator").await);
+
+        assert!(agent.switch_persona("unknown").await.is_err());
+        assert!(agent.switch_persona("broken").await.is_err());
+        assert_eq!(agent.active_persona().unwrap().name, "reviewer");
+
+        agent.reset_persona().await.unwrap();
+        assert!(agent.active_persona().is_none());
+        assert_eq!(
+            agent.conversation().system_prompt(),
+            Some("You are a helpful assistant.")
+        );
+        assert_eq!(agent.config().model_id, Bedrock::ClaudeHaiku45.model_id());
+        assert_eq!(agent.config().temperature, Some(0.7));
+        assert_eq!(agent.conversation().message_count(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Per-phase timeouts for agentic execution.
+//!
+//! Besides the overall [`max_duration`](crate::agent::EventLoopConfig::max_duration),
+//! each phase of a cycle can get its own time box through
+//! [`EventLoopConfig::phase_timeouts`](crate::agent::EventLoopConfig::phase_timeouts):
+//!
+//! | Phase | Covers |
+//! |---|---|
+//! | [`Reasoning`](ExecutionPhase::Reasoning) | The model call starting each cycle, including its retries |
+//! | [`ToolBatch`](ExecutionPhase::ToolBatch) | Executing one round of tool calls |
+//! | [`Evaluation`](ExecutionPhase::Evaluation) | Deciding whether to continue, including evaluation model calls |
+//! | [`Synthesis`](ExecutionPhase::Synthesis) | The model call answering tool results |
+//!
+//! Each limit applies to every occurrence of its phase on its own, and is
+//! shortened to the time left when the execution has a
+//! [`Deadline`](crate::agent::Deadline). A phase that runs out of time fails
+//! with [`StoodError::TimeoutError`] naming the phase, except a tool batch: its
+//! calls are answered with that error so the model can continue without them.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use stood::agent::phase_timeouts::PhaseTimeouts;
+//! use stood::agent::Agent;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let agent = Agent::builder()
+//!     .with_phase_timeouts(
+//!         PhaseTimeouts::new()
+//!             .with_reasoning(Duration::from_secs(30))
+//!             .with_tool_batch(Duration::from_secs(120))
+//!             .with_evaluation(Duration::from_secs(10)),
+//!     )
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::agent::deadline::Deadline;
+use crate::{Result, StoodError};
+
+/// A phase of an agentic cycle with its own timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExecutionPhase {
+    /// The model call starting a cycle
+    Reasoning,
+    /// One round of tool calls
+    ToolBatch,
+    /// The continuation evaluation after a cycle
+    Evaluation,
+    /// The model call answering tool results
+    Synthesis,
+}
+
+impl fmt::Display for ExecutionPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Reasoning => "reasoning",
+            Self::ToolBatch => "tool batch",
+            Self::Evaluation => "evaluation",
+            Self::Synthesis => "synthesis",
+        })
+    }
+}
+
+/// Timeouts for each [`ExecutionPhase`], unlimited when unset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimeouts {
+    pub reasoning: Option<Duration>,
+    pub tool_batch: Option<Duration>,
+    pub evaluation: Option<Duration>,
+    pub synthesis: Option<Duration>,
+}
+
+impl PhaseTimeouts {
+    /// No phase timeouts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_reasoning(mut self, timeout: Duration) -> Self {
+        self.reasoning = Some(timeout);
+        self
+    }
+
+    pub fn with_tool_batch(mut self, timeout: Duration) -> Self {
+        self.tool_batch = Some(timeout);
+        self
+    }
+
+    pub fn with_evaluation(mut self, timeout: Duration) -> Self {
+        self.evaluation = Some(timeout);
+        self
+    }
+
+    pub fn with_synthesis(mut self, timeout: Duration) -> Self {
+        self.synthesis = Some(timeout);
+        self
+    }
+
+    /// Timeout of `phase`, if any
+    pub fn get(&self, phase: ExecutionPhase) -> Option<Duration> {
+        match phase {
+            ExecutionPhase::Reasoning => self.reasoning,
+            ExecutionPhase::ToolBatch => self.tool_batch,
+            ExecutionPhase::Evaluation => self.evaluation,
+            ExecutionPhase::Synthesis => self.synthesis,
+        }
+    }
+
+    /// Run `future` as `phase`, failing if it exceeds the phase's timeout or
+    /// the [current deadline](Deadline::current)
+    pub async fn run<T>(
+        &self,
+        phase: ExecutionPhase,
+        future: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let timeout = match (self.get(phase), Deadline::current()) {
+            (None, None) => return future.await,
+            (Some(timeout), None) => timeout,
+            (timeout, Some(deadline)) => {
+                if deadline.is_expired() {
+                    return Err(StoodError::phase_timeout(phase, Duration::ZERO));
+                }
+                deadline.cap(timeout.unwrap_or(Duration::MAX))
+            }
+        };
+        crate::runtime::timeout(timeout, future)
+            .await
+            .map_err(|_| StoodError::phase_timeout(phase, timeout))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_phase_timeouts_are_independent() {
+        let timeouts = PhaseTimeouts::new().with_tool_batch(Duration::from_millis(10));
+        let slow = || async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok("done")
+        };
+
+        let err = timeouts
+            .run(ExecutionPhase::ToolBatch, slow())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StoodError::TimeoutError {
+                timeout_ms: 10,
+                phase: Some(ExecutionPhase::ToolBatch),
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Timeout error: tool batch phase timed out after 10ms"
+        );
+
+        // Phases without a timeout are unlimited
+        assert_eq!(
+            timeouts
+                .run(ExecutionPhase::Reasoning, slow())
+                .await
+                .unwrap(),
+            "done"
+        );
+
+        // unless the execution has a deadline
+        let err = Deadline::after(Duration::from_millis(10))
+            .scope(timeouts.run(ExecutionPhase::Reasoning, slow()))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StoodError::TimeoutError {
+                phase: Some(ExecutionPhase::Reasoning),
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,607 @@
+//! Pool of pre-built agents for multi-tenant services.
+//!
+//! Building an [`Agent`] configures its provider, loads its tools and
+//! connects MCP servers, which is too slow to repeat on every HTTP request.
+//! An [`AgentPool`] keeps built agents around, keyed by tenant and
+//! configuration profile, and hands them out with [`AgentPool::checkout`].
+//! The returned [`PooledAgent`] derefs to the agent and goes back to the pool
+//! when dropped.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use stood::agent::pool::{AgentPool, AgentPoolConfig};
+//! use stood::agent::Agent;
+//! use stood::llm::models::Bedrock;
+//!
+//! # async fn example(tenant_id: &str) -> Result<(), stood::StoodError> {
+//! let pool = AgentPool::new(
+//!     AgentPoolConfig::new()
+//!         .with_max_checked_out(64)
+//!         .with_max_checked_out_per_tenant(4)
+//!         .with_idle_timeout(Duration::from_secs(600)),
+//! )
+//! .with_profile("support", || async {
+//!     Agent::builder()
+//!         .model(Bedrock::ClaudeHaiku45)
+//!         .system_prompt("You are a support assistant.")
+//!         .build()
+//!         .await
+//! });
+//! pool.start_reaper(Duration::from_secs(60));
+//!
+//! let mut agent = pool.checkout(tenant_id, "support").await?;
+//! let result = agent.execute("Where is my order?").await?;
+//! println!("{}", result.response);
+//! // Dropping `agent` clears its history and returns it to the pool
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Tenants are isolated: an agent is only ever handed out again to the
+//! tenant that used it, and its conversation is cleared on checkin unless
+//! [`AgentPoolConfig::with_reset_on_checkin`] is turned off.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::agent::Agent;
+use crate::{Result, StoodError};
+
+/// Builds a fresh agent for a profile
+pub type AgentFactory = Arc<dyn Fn() -> BoxFuture<'static, Result<Agent>> + Send + Sync>;
+
+/// Limits of an [`AgentPool`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentPoolConfig {
+    /// Agents checked out at the same time, across all tenants
+    pub max_checked_out: usize,
+    /// Agents checked out at the same time by one tenant
+    pub max_checked_out_per_tenant: Option<usize>,
+    /// Idle agents kept for each tenant and profile
+    pub max_idle_per_key: usize,
+    /// Idle agents unused for this long are dropped
+    pub idle_timeout: Duration,
+    /// How long [`AgentPool::checkout`] waits for capacity; waits forever when unset
+    pub checkout_timeout: Option<Duration>,
+    /// Clear the conversation history of agents returned to the pool
+    pub reset_on_checkin: bool,
+}
+
+impl Default for AgentPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_checked_out: 32,
+            max_checked_out_per_tenant: None,
+            max_idle_per_key: 4,
+            idle_timeout: Duration::from_secs(5 * 60),
+            checkout_timeout: Some(Duration::from_secs(30)),
+            reset_on_checkin: true,
+        }
+    }
+}
+
+impl AgentPoolConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_checked_out(mut self, max: usize) -> Self {
+        self.max_checked_out = max.max(1);
+        self
+    }
+
+    pub fn with_max_checked_out_per_tenant(mut self, max: usize) -> Self {
+        self.max_checked_out_per_tenant = Some(max.max(1));
+        self
+    }
+
+    pub fn with_max_idle_per_key(mut self, max: usize) -> Self {
+        self.max_idle_per_key = max;
+        self
+    }
+
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    pub fn with_checkout_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.checkout_timeout = timeout;
+        self
+    }
+
+    pub fn with_reset_on_checkin(mut self, reset: bool) -> Self {
+        self.reset_on_checkin = reset;
+        self
+    }
+}
+
+/// Tenant and profile an agent belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PoolKey {
+    pub tenant: String,
+    pub profile: String,
+}
+
+/// Counters of an [`AgentPool`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AgentPoolStats {
+    /// Agents built by profile factories
+    pub created: u64,
+    /// Checkouts served by an idle agent
+    pub reused: u64,
+    /// Idle agents dropped for being unused or over the idle limit
+    pub evicted: u64,
+    /// Agents dropped with [`PooledAgent::discard`]
+    pub discarded: u64,
+    /// Agents currently idle in the pool
+    pub idle: usize,
+    /// Agents currently checked out
+    pub checked_out: usize,
+}
+
+/// Bounded set of pre-built agents keyed by tenant and profile
+///
+/// Clones share the same agents.
+#[derive(Clone)]
+pub struct AgentPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    config: AgentPoolConfig,
+    profiles: RwLock<HashMap<String, AgentFactory>>,
+    idle: Mutex<IdleAgents<Agent>>,
+    capacity: Arc<Semaphore>,
+    tenant_capacity: Mutex<HashMap<String, Arc<Semaphore>>>,
+    created: AtomicU64,
+    reused: AtomicU64,
+    evicted: AtomicU64,
+    discarded: AtomicU64,
+}
+
+impl AgentPool {
+    pub fn new(config: AgentPoolConfig) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                capacity: Arc::new(Semaphore::new(config.max_checked_out.max(1))),
+                config,
+                profiles: RwLock::new(HashMap::new()),
+                idle: Mutex::new(IdleAgents::default()),
+                tenant_capacity: Mutex::new(HashMap::new()),
+                created: AtomicU64::new(0),
+                reused: AtomicU64::new(0),
+                evicted: AtomicU64::new(0),
+                discarded: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Register the factory that builds agents for `profile`
+    pub fn with_profile<F, Fut>(self, profile: impl Into<String>, factory: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Agent>> + Send + 'static,
+    {
+        self.add_profile(profile, factory);
+        self
+    }
+
+    /// Register or replace the factory for `profile`
+    ///
+    /// Idle agents built by a replaced factory are dropped.
+    pub fn add_profile<F, Fut>(&self, profile: impl Into<String>, factory: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Agent>> + Send + 'static,
+    {
+        let profile = profile.into();
+        let factory: AgentFactory = Arc::new(move || Box::pin(factory()));
+        let replaced = self
+            .inner
+            .profiles
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(profile.clone(), factory)
+            .is_some();
+        if replaced {
+            let dropped = self
+                .inner
+                .lock_idle()
+                .remove_where(|key| key.profile == profile);
+            self.inner.count_evicted(dropped.len());
+        }
+    }
+
+    /// Registered profile names
+    pub fn profiles(&self) -> Vec<String> {
+        let mut profiles: Vec<String> = self
+            .inner
+            .profiles
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        profiles.sort();
+        profiles
+    }
+
+    /// Check out an agent of `profile` for `tenant`
+    ///
+    /// Reuses an idle agent of the same tenant and profile, or builds one.
+    /// Waits while the pool or the tenant is at its checkout limit, failing
+    /// with [`StoodError::TimeoutError`] after
+    /// [`checkout_timeout`](AgentPoolConfig::checkout_timeout).
+    pub async fn checkout(&self, tenant: &str, profile: &str) -> Result<PooledAgent> {
+        let factory = self.inner.factory(profile)?;
+        let permits = match self.inner.config.checkout_timeout {
+            Some(timeout) => crate::runtime::timeout(timeout, self.inner.acquire(tenant))
+                .await
+                .map_err(|_| StoodError::timeout_error(timeout.as_millis() as u64))?,
+            None => self.inner.acquire(tenant).await,
+        };
+
+        let key = PoolKey {
+            tenant: tenant.to_string(),
+            profile: profile.to_string(),
+        };
+        self.evict_idle();
+        let idle = self.inner.lock_idle().take(&key);
+        let agent = match idle {
+            Some(agent) => {
+                self.inner.reused.fetch_add(1, Ordering::Relaxed);
+                agent
+            }
+            None => {
+                let agent = factory().await?;
+                self.inner.created.fetch_add(1, Ordering::Relaxed);
+                tracing::debug!(tenant, profile, "Built pooled agent");
+                agent
+            }
+        };
+
+        Ok(PooledAgent {
+            agent: Some(agent),
+            key,
+            pool: Arc::downgrade(&self.inner),
+            _permits: permits,
+        })
+    }
+
+    /// Build agents of `profile` for `tenant` ahead of their first checkout
+    ///
+    /// Never fills the pool beyond [`max_idle_per_key`](AgentPoolConfig::max_idle_per_key).
+    pub async fn warm(&self, tenant: &str, profile: &str, count: usize) -> Result<()> {
+        let factory = self.inner.factory(profile)?;
+        let key = PoolKey {
+            tenant: tenant.to_string(),
+            profile: profile.to_string(),
+        };
+        let missing = self
+            .inner
+            .config
+            .max_idle_per_key
+            .saturating_sub(self.inner.lock_idle().len_for(&key));
+        for _ in 0..count.min(missing) {
+            let agent = factory().await?;
+            self.inner.created.fetch_add(1, Ordering::Relaxed);
+            self.inner.checkin(key.clone(), agent);
+        }
+        Ok(())
+    }
+
+    /// Drop agents idle for longer than [`idle_timeout`](AgentPoolConfig::idle_timeout)
+    ///
+    /// Returns the number of agents dropped.
+    pub fn evict_idle(&self) -> usize {
+        let expired = self
+            .inner
+            .lock_idle()
+            .remove_expired(Instant::now(), self.inner.config.idle_timeout);
+        self.inner.count_evicted(expired.len())
+    }
+
+    /// Drop the idle agents of `tenant`, e.g. when it is offboarded
+    pub fn remove_tenant(&self, tenant: &str) -> usize {
+        let removed = self
+            .inner
+            .lock_idle()
+            .remove_where(|key| key.tenant == tenant);
+        self.inner
+            .tenant_capacity
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(tenant);
+        self.inner.count_evicted(removed.len())
+    }
+
+    /// Drop all idle agents
+    pub fn clear(&self) -> usize {
+        let removed = self.inner.lock_idle().remove_where(|_| true);
+        self.inner.count_evicted(removed.len())
+    }
+
+    /// Call [`evict_idle`](Self::evict_idle) every `interval` until the pool is dropped
+    pub fn start_reaper(&self, interval: Duration) -> crate::runtime::JoinHandle<()> {
+        let pool = Arc::downgrade(&self.inner);
+        crate::runtime::spawn(async move {
+            loop {
+                crate::runtime::sleep(interval).await;
+                let Some(inner) = pool.upgrade() else { break };
+                let evicted = AgentPool { inner }.evict_idle();
+                if evicted > 0 {
+                    tracing::debug!(evicted, "Evicted idle pooled agents");
+                }
+            }
+        })
+    }
+
+    pub fn stats(&self) -> AgentPoolStats {
+        let inner = &self.inner;
+        AgentPoolStats {
+            created: inner.created.load(Ordering::Relaxed),
+            reused: inner.reused.load(Ordering::Relaxed),
+            evicted: inner.evicted.load(Ordering::Relaxed),
+            discarded: inner.discarded.load(Ordering::Relaxed),
+            idle: inner.lock_idle().len(),
+            checked_out: inner.config.max_checked_out.max(1) - inner.capacity.available_permits(),
+        }
+    }
+
+    pub fn config(&self) -> &AgentPoolConfig {
+        &self.inner.config
+    }
+}
+
+impl fmt::Debug for AgentPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AgentPool")
+            .field("config", &self.inner.config)
+            .field("profiles", &self.profiles())
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl PoolInner {
+    fn factory(&self, profile: &str) -> Result<AgentFactory> {
+        self.profiles
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(profile)
+            .cloned()
+            .ok_or_else(|| {
+                StoodError::configuration_error(format!("Agent pool has no profile '{}'", profile))
+            })
+    }
+
+    async fn acquire(&self, tenant: &str) -> Vec<OwnedSemaphorePermit> {
+        let mut permits = Vec::with_capacity(2);
+        if let Some(max) = self.config.max_checked_out_per_tenant {
+            let tenant_capacity = self
+                .tenant_capacity
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(tenant.to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(max)))
+                .clone();
+            permits.push(
+                tenant_capacity
+                    .acquire_owned()
+                    .await
+                    .expect("Semaphore should not be closed"),
+            );
+        }
+        permits.push(
+            self.capacity
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("Semaphore should not be closed"),
+        );
+        permits
+    }
+
+    fn checkin(&self, key: PoolKey, mut agent: Agent) {
+        if self.config.reset_on_checkin {
+            agent.clear_history();
+        }
+        let rejected =
+            self.lock_idle()
+                .put(key, agent, Instant::now(), self.config.max_idle_per_key);
+        if rejected.is_some() {
+            self.count_evicted(1);
+        }
+    }
+
+    fn count_evicted(&self, count: usize) -> usize {
+        self.evicted.fetch_add(count as u64, Ordering::Relaxed);
+        count
+    }
+
+    fn lock_idle(&self) -> std::sync::MutexGuard<'_, IdleAgents<Agent>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Agent checked out of an [`AgentPool`]
+///
+/// Derefs to [`Agent`]. Dropping it returns the agent to the pool; use
+/// [`discard`](Self::discard) for agents left in a bad state.
+pub struct PooledAgent {
+    agent: Option<Agent>,
+    key: PoolKey,
+    pool: Weak<PoolInner>,
+    _permits: Vec<OwnedSemaphorePermit>,
+}
+
+impl PooledAgent {
+    /// Tenant and profile of this agent
+    pub fn key(&self) -> &PoolKey {
+        &self.key
+    }
+
+    /// Drop the agent instead of returning it to the pool
+    pub fn discard(mut self) {
+        self.agent = None;
+        if let Some(pool) = self.pool.upgrade() {
+            pool.discarded.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Take the agent out of the pool for good
+    pub fn detach(mut self) -> Agent {
+        self.agent.take().expect("agent is present until dropped")
+    }
+}
+
+impl Deref for PooledAgent {
+    type Target = Agent;
+
+    fn deref(&self) -> &Agent {
+        self.agent.as_ref().expect("agent is present until dropped")
+    }
+}
+
+impl DerefMut for PooledAgent {
+    fn deref_mut(&mut self) -> &mut Agent {
+        self.agent.as_mut().expect("agent is present until dropped")
+    }
+}
+
+impl Drop for PooledAgent {
+    fn drop(&mut self) {
+        if let (Some(agent), Some(pool)) = (self.agent.take(), self.pool.upgrade()) {
+            pool.checkin(self.key.clone(), agent);
+        }
+    }
+}
+
+impl fmt::Debug for PooledAgent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledAgent")
+            .field("key", &self.key)
+            .field("agent_id", &self.agent.as_ref().map(|a| a.agent_id()))
+            .finish()
+    }
+}
+
+/// Idle items per key, most recently returned last
+struct IdleAgents<T> {
+    entries: HashMap<PoolKey, Vec<(T, Instant)>>,
+}
+
+impl<T> Default for IdleAgents<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<T> IdleAgents<T> {
+    /// The most recently returned item, so rarely used ones expire
+    fn take(&mut self, key: &PoolKey) -> Option<T> {
+        let items = self.entries.get_mut(key)?;
+        let item = items.pop().map(|(item, _)| item);
+        if items.is_empty() {
+            self.entries.remove(key);
+        }
+        item
+    }
+
+    /// Store `item`, handing it back if `key` already has `max` items
+    fn put(&mut self, key: PoolKey, item: T, now: Instant, max: usize) -> Option<T> {
+        let items = self.entries.entry(key).or_default();
+        if items.len() >= max {
+            return Some(item);
+        }
+        items.push((item, now));
+        None
+    }
+
+    fn remove_expired(&mut self, now: Instant, idle_timeout: Duration) -> Vec<T> {
+        let mut expired = Vec::new();
+        for items in self.entries.values_mut() {
+            let (old, fresh): (Vec<_>, Vec<_>) = std::mem::take(items)
+                .into_iter()
+                .partition(|(_, since)| now.duration_since(*since) >= idle_timeout);
+            *items = fresh;
+            expired.extend(old.into_iter().map(|(item, _)| item));
+        }
+        self.entries.retain(|_, items| !items.is_empty());
+        expired
+    }
+
+    fn remove_where(&mut self, mut matches: impl FnMut(&PoolKey) -> bool) -> Vec<T> {
+        let keys: Vec<PoolKey> = self
+            .entries
+            .keys()
+            .filter(|k| matches(k))
+            .cloned()
+            .collect();
+        keys.iter()
+            .filter_map(|key| self.entries.remove(key))
+            .flatten()
+            .map(|(item, _)| item)
+            .collect()
+    }
+
+    fn len_for(&self, key: &PoolKey) -> usize {
+        self.entries.get(key).map_or(0, Vec::len)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(tenant: &str, profile: &str) -> PoolKey {
+        PoolKey {
+            tenant: tenant.to_string(),
+            profile: profile.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_idle_agents_isolate_tenants_and_expire() {
+        let start = Instant::now();
+        let mut idle = IdleAgents::default();
+        assert_eq!(idle.put(key("acme", "support"), 1, start, 2), None);
+        assert_eq!(
+            idle.put(key("acme", "support"), 2, start + Duration::from_secs(5), 2),
+            None
+        );
+        assert_eq!(idle.put(key("acme", "support"), 3, start, 2), Some(3));
+        assert_eq!(idle.put(key("globex", "support"), 4, start, 2), None);
+
+        assert_eq!(idle.take(&key("initech", "support")), None);
+        assert_eq!(idle.remove_where(|k| k.tenant == "globex"), vec![4]);
+
+        let expired = idle.remove_expired(start + Duration::from_secs(10), Duration::from_secs(10));
+        assert_eq!(expired, vec![1]);
+        assert_eq!(idle.take(&key("acme", "support")), Some(2));
+        assert_eq!(idle.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_checkout_of_unknown_profile_fails() {
+        let pool = AgentPool::new(AgentPoolConfig::new());
+        let error = pool.checkout("acme", "missing").await.unwrap_err();
+        assert!(matches!(error, StoodError::ConfigurationError { .. }));
+        assert_eq!(pool.stats(), AgentPoolStats::default());
+    }
+}
@@ -0,0 +1,438 @@
+//! Preflight validation of an agent's configuration.
+//!
+//! Misconfigured credentials, models the account has no access to, or malformed
+//! tool schemas otherwise only surface mid-run as model errors.
+//! [`Agent::preflight`](crate::agent::Agent::preflight) checks all of these up front
+//! and returns a [`PreflightReport`] describing each check.
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let agent = Agent::builder().build().await?;
+//! let report = agent.preflight().await;
+//! if !report.is_ok() {
+//!     eprintln!("{}", report);
+//!     std::process::exit(1);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::agent::Agent;
+use crate::health::HealthStatus;
+use crate::llm::traits::{ChatConfig, LlmError};
+use crate::tools::ToolSource;
+use crate::types::Messages;
+
+/// Options controlling which preflight checks run
+#[derive(Debug, Clone)]
+pub struct PreflightOptions {
+    /// Send a minimal request (one output token) to confirm the account can
+    /// invoke the configured model
+    pub probe_model: bool,
+    /// Validate tool names and parameter schemas
+    pub check_tools: bool,
+    /// Check that MCP-backed tools are connected
+    pub check_mcp: bool,
+    /// Timeout for each network check
+    pub timeout: Duration,
+}
+
+impl Default for PreflightOptions {
+    fn default() -> Self {
+        Self {
+            probe_model: true,
+            check_tools: true,
+            check_mcp: true,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl PreflightOptions {
+    /// Skip the model invocation probe (no request is sent to the model)
+    pub fn without_model_probe(mut self) -> Self {
+        self.probe_model = false;
+        self
+    }
+}
+
+/// Result of a single preflight check
+#[derive(Debug, Clone)]
+pub struct PreflightCheck {
+    /// Check name (`provider`, `model_access`, `tool_schemas`, `mcp`)
+    pub name: String,
+    /// `Healthy` if the check passed, `Degraded` for warnings, `Unhealthy` on failure
+    pub status: HealthStatus,
+    /// What was checked and, on failure, what to fix
+    pub message: String,
+    /// Time taken by the check
+    pub duration: Duration,
+}
+
+/// Structured report returned by [`Agent::preflight`](crate::agent::Agent::preflight)
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    /// Checks in the order they ran
+    pub checks: Vec<PreflightCheck>,
+    /// Total time taken
+    pub duration: Duration,
+}
+
+impl PreflightReport {
+    /// Whether no check failed (warnings are allowed)
+    pub fn is_ok(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == HealthStatus::Unhealthy)
+    }
+
+    /// Checks that passed with warnings
+    pub fn warnings(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == HealthStatus::Degraded)
+    }
+
+    /// Look up a check by name
+    pub fn check(&self, name: &str) -> Option<&PreflightCheck> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        name: &str,
+        status: HealthStatus,
+        message: impl Into<String>,
+        duration: Duration,
+    ) {
+        self.checks.push(PreflightCheck {
+            name: name.to_string(),
+            status,
+            message: message.into(),
+            duration,
+        });
+    }
+}
+
+impl std::fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Preflight {} ({:?})",
+            if self.is_ok() { "passed" } else { "FAILED" },
+            self.duration
+        )?;
+        for check in &self.checks {
+            let marker = match check.status {
+                HealthStatus::Healthy => "ok",
+                HealthStatus::Degraded => "warn",
+                HealthStatus::Unhealthy => "FAIL",
+            };
+            writeln!(f, "  [{:>4}] {}: {}", marker, check.name, check.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Agent {
+    /// Validate credentials, model access, tool schemas and MCP connections with
+    /// the default [`PreflightOptions`].
+    ///
+    /// Checks never fail fast: every check runs and the report lists all problems.
+    pub async fn preflight(&self) -> PreflightReport {
+        self.preflight_with(PreflightOptions::default()).await
+    }
+
+    /// Validate the agent's configuration with custom options.
+    ///
+    /// Model access is verified by sending a request limited to one output token,
+    /// because listing foundation models requires the Bedrock control-plane API,
+    /// which this crate does not depend on. Disable the probe with
+    /// [`PreflightOptions::without_model_probe`] to avoid the (tiny) request cost.
+    pub async fn preflight_with(&self, options: PreflightOptions) -> PreflightReport {
+        let started = Instant::now();
+        let mut report = PreflightReport::default();
+
+        let start = Instant::now();
+        let (status, message) =
+            match crate::runtime::timeout(options.timeout, self.provider().health_check()).await {
+                Ok(Ok(health)) if health.healthy => (
+                    HealthStatus::Healthy,
+                    format!("{} provider configured", health.provider),
+                ),
+                Ok(Ok(health)) => (
+                    HealthStatus::Unhealthy,
+                    health
+                        .error
+                        .unwrap_or_else(|| format!("{} provider is unhealthy", health.provider)),
+                ),
+                Ok(Err(e)) => (HealthStatus::Unhealthy, e.to_string()),
+                Err(_) => (
+                    HealthStatus::Unhealthy,
+                    format!("provider check timed out after {:?}", options.timeout),
+                ),
+            };
+        report.push("provider", status, message, start.elapsed());
+
+        if options.probe_model {
+            let start = Instant::now();
+            let (status, message) = self.probe_model(options.timeout).await;
+            report.push("model_access", status, message, start.elapsed());
+        }
+
+        if options.check_tools {
+            let start = Instant::now();
+            let (status, message) = self.check_tool_schemas().await;
+            report.push("tool_schemas", status, message, start.elapsed());
+        }
+
+        if options.check_mcp {
+            let start = Instant::now();
+            if let Some((status, message)) = self.check_mcp_tools().await {
+                report.push("mcp", status, message, start.elapsed());
+            }
+        }
+
+        report.duration = started.elapsed();
+        report
+    }
+
+    async fn probe_model(&self, timeout: Duration) -> (HealthStatus, String) {
+        let model_id = self.model().model_id();
+        let mut messages = Messages::new();
+        messages.add_user_message("Reply with OK.");
+        let config = ChatConfig {
+            model_id: model_id.to_string(),
+            provider: self.model().provider(),
+            max_tokens: Some(1),
+            ..Default::default()
+        };
+
+        match crate::runtime::timeout(timeout, self.provider().chat(model_id, &messages, &config))
+            .await
+        {
+            Ok(Ok(_)) => (
+                HealthStatus::Healthy,
+                format!("model '{}' is accessible", model_id),
+            ),
+            Ok(Err(LlmError::ModelNotFound { .. })) => (
+                HealthStatus::Unhealthy,
+                format!(
+                    "model '{}' was not found; check the model id and that it is offered in this region",
+                    model_id
+                ),
+            ),
+            Ok(Err(LlmError::AuthenticationError { .. })) => (
+                HealthStatus::Unhealthy,
+                "credentials were rejected; check your AWS profile or access keys".to_string(),
+            ),
+            Ok(Err(LlmError::RateLimitError { .. })) => (
+                HealthStatus::Degraded,
+                format!("model '{}' is accessible but currently throttled", model_id),
+            ),
+            Ok(Err(e)) => {
+                let error = e.to_string();
+                let lower = error.to_lowercase();
+                let message = if lower.contains("access") && lower.contains("denied")
+                    || lower.contains("don't have access")
+                {
+                    format!(
+                        "access to model '{}' is denied; enable it under Bedrock model access: {}",
+                        model_id, error
+                    )
+                } else {
+                    format!("model '{}' could not be invoked: {}", model_id, error)
+                };
+                (HealthStatus::Unhealthy, message)
+            }
+            Err(_) => (
+                HealthStatus::Unhealthy,
+                format!("model '{}' did not respond within {:?}", model_id, timeout),
+            ),
+        }
+    }
+
+    async fn check_tool_schemas(&self) -> (HealthStatus, String) {
+        let names = self.tool_registry().tool_names().await;
+        let mut problems = Vec::new();
+        for name in &names {
+            let Some(tool) = self.tool_registry().get_tool(name).await else {
+                continue;
+            };
+            for problem in
+                validate_tool_definition(tool.name(), tool.description(), &tool.parameters_schema())
+            {
+                problems.push(format!("{}: {}", name, problem));
+            }
+        }
+
+        if problems.is_empty() {
+            (
+                HealthStatus::Healthy,
+                format!("{} tool definitions are valid", names.len()),
+            )
+        } else {
+            (HealthStatus::Unhealthy, problems.join("; "))
+        }
+    }
+
+    async fn check_mcp_tools(&self) -> Option<(HealthStatus, String)> {
+        let mut total = 0;
+        let mut unavailable = Vec::new();
+        for name in self.tool_registry().tool_names().await {
+            let Some(tool) = self.tool_registry().get_tool(&name).await else {
+                continue;
+            };
+            if tool.source() == ToolSource::MCP {
+                total += 1;
+                if !tool.is_available() {
+                    unavailable.push(name);
+                }
+            }
+        }
+
+        match (total, unavailable.len()) {
+            (0, _) => None,
+            (_, 0) => Some((
+                HealthStatus::Healthy,
+                format!("{} MCP tools connected", total),
+            )),
+            (total, down) if down == total => Some((
+                HealthStatus::Unhealthy,
+                format!("all {} MCP tools are disconnected", total),
+            )),
+            (total, down) => Some((
+                HealthStatus::Degraded,
+                format!(
+                    "{} of {} MCP tools are disconnected: {}",
+                    down,
+                    total,
+                    unavailable.join(", ")
+                ),
+            )),
+        }
+    }
+}
+
+/// Check a tool definition against the constraints providers enforce.
+///
+/// Returns a list of problems; an empty list means the definition is valid.
+pub fn validate_tool_definition(name: &str, description: &str, schema: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    // Bedrock's Converse API requires names matching [a-zA-Z0-9_-]{1,64}
+    if name.is_empty() || name.len() > 64 {
+        problems.push(format!("name must be 1-64 characters (got {})", name.len()));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+    {
+        problems.push(format!("name contains invalid character '{}'", c));
+    }
+    if description.trim().is_empty() {
+        problems.push("description is empty".to_string());
+    }
+
+    let Some(object) = schema.as_object() else {
+        problems.push("parameter schema is not a JSON object".to_string());
+        return problems;
+    };
+    if object.get("type").and_then(Value::as_str) != Some("object") {
+        problems.push("parameter schema must have \"type\": \"object\"".to_string());
+    }
+    let properties = match object.get("properties") {
+        None => None,
+        Some(Value::Object(properties)) => Some(properties),
+        Some(_) => {
+            problems.push("\"properties\" must be an object".to_string());
+            None
+        }
+    };
+    match object.get("required") {
+        None => {}
+        Some(Value::Array(required)) => {
+            for entry in required {
+                match entry.as_str() {
+                    Some(field) if properties.is_some_and(|p| p.contains_key(field)) => {}
+                    Some(field) => problems.push(format!(
+                        "required parameter '{}' is not defined in properties",
+                        field
+                    )),
+                    None => problems.push("\"required\" entries must be strings".to_string()),
+                }
+            }
+        }
+        Some(_) => problems.push("\"required\" must be an array".to_string()),
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_tool_definition() {
+        let valid = json!({
+            "type": "object",
+            "properties": {"path": {"type": "string"}},
+            "required": ["path"]
+        });
+        assert!(validate_tool_definition("file_read", "Read a file", &valid).is_empty());
+
+        let invalid = json!({
+            "type": "array",
+            "properties": {"path": {"type": "string"}},
+            "required": ["path", "mode"]
+        });
+        let problems = validate_tool_definition("read file", "", &invalid);
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems.iter().any(|p| p.contains("invalid character ' '")));
+        assert!(problems.iter().any(|p| p.contains("'mode'")));
+
+        assert_eq!(
+            validate_tool_definition("t", "d", &json!("string")),
+            vec!["parameter schema is not a JSON object".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_report_status() {
+        let mut report = PreflightReport::default();
+        report.push("provider", HealthStatus::Healthy, "ok", Duration::ZERO);
+        report.push(
+            "mcp",
+            HealthStatus::Degraded,
+            "1 server down",
+            Duration::ZERO,
+        );
+        assert!(report.is_ok());
+        assert_eq!(report.warnings().count(), 1);
+
+        report.push(
+            "model_access",
+            HealthStatus::Unhealthy,
+            "access denied",
+            Duration::ZERO,
+        );
+        assert!(!report.is_ok());
+        assert_eq!(report.failures().next().unwrap().name, "model_access");
+        assert!(report
+            .to_string()
+            .contains("[FAIL] model_access: access denied"));
+    }
+}
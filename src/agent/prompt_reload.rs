@@ -0,0 +1,203 @@
+//! Prompt files that are reloaded when they change on disk.
+//!
+//! A [`WatchedPrompt`] watches a file and re-reads it before the next execution
+//! after it changes, so prompts can be iterated on in a long-running service
+//! without restarting it. Executions already in progress keep the prompt they
+//! started with.
+//!
+//! Use [`AgentBuilder::with_prompt_hot_reload`](crate::agent::AgentBuilder::with_prompt_hot_reload)
+//! to reload the system prompt set with
+//! [`system_prompt_file`](crate::agent::AgentBuilder::system_prompt_file) or an
+//! agent file's `prompt.system_file`. `WatchedPrompt` is also a
+//! [`PromptContextProvider`], so individual template fragments of a
+//! [`SystemPromptBuilder`](crate::agent::SystemPromptBuilder) can be reloaded:
+//!
+//! ```no_run
+//! use stood::agent::{Agent, PromptFragment, SystemPromptBuilder, WatchedPrompt};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let prompt = SystemPromptBuilder::new()
+//!     .base("You are a support assistant.")
+//!     .fragment(PromptFragment::dynamic("policies", WatchedPrompt::new("prompts/policies.md")?));
+//!
+//! let agent = Agent::builder()
+//!     .with_system_prompt_builder(prompt)
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requires the `hot-reload` feature.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::system_prompt::{PromptContext, PromptContextProvider};
+use crate::StoodError;
+
+/// A prompt file reloaded after it changes
+pub struct WatchedPrompt {
+    path: PathBuf,
+    interpolate_env: bool,
+    content: Mutex<String>,
+    changed: Arc<AtomicBool>,
+    _watcher: RecommendedWatcher,
+}
+
+impl std::fmt::Debug for WatchedPrompt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchedPrompt")
+            .field("path", &self.path)
+            .field("interpolate_env", &self.interpolate_env)
+            .finish()
+    }
+}
+
+impl WatchedPrompt {
+    /// Read `path` and watch it for changes
+    pub fn new(path: impl AsRef<Path>) -> crate::Result<Arc<Self>> {
+        Self::create(path.as_ref(), false)
+    }
+
+    /// Like [`new`](Self::new), replacing `${VAR}` and `${VAR:-default}` with
+    /// environment variables each time the file is read
+    pub fn with_env_interpolation(path: impl AsRef<Path>) -> crate::Result<Arc<Self>> {
+        Self::create(path.as_ref(), true)
+    }
+
+    fn create(path: &Path, interpolate_env: bool) -> crate::Result<Arc<Self>> {
+        let content = read_prompt(path, interpolate_env)?;
+
+        // Watch the directory rather than the file: editors often save by
+        // replacing the file, which would end a watch on the file itself
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let changed = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&changed);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if let Ok(event) = event {
+                    if event.kind.is_access() {
+                        return;
+                    }
+                    if event
+                        .paths
+                        .iter()
+                        .any(|changed| changed.file_name() == file_name.as_deref())
+                    {
+                        flag.store(true, Ordering::SeqCst);
+                    }
+                }
+            })
+            .map_err(|e| watch_error(path, e))?;
+        watcher
+            .watch(&directory, RecursiveMode::NonRecursive)
+            .map_err(|e| watch_error(path, e))?;
+
+        Ok(Arc::new(Self {
+            path: path.to_path_buf(),
+            interpolate_env,
+            content: Mutex::new(content),
+            changed,
+            _watcher: watcher,
+        }))
+    }
+
+    /// Path of the watched file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Current prompt, re-read from disk if the file changed
+    ///
+    /// If the file can no longer be read (e.g. while it is being rewritten),
+    /// the last successfully read prompt is returned.
+    pub fn current(&self) -> String {
+        let mut content = self.content.lock().unwrap();
+        if self.changed.swap(false, Ordering::SeqCst) {
+            match read_prompt(&self.path, self.interpolate_env) {
+                Ok(reloaded) => {
+                    if *content != reloaded {
+                        tracing::info!(
+                            path = %self.path.display(),
+                            "Reloaded prompt file"
+                        );
+                        *content = reloaded;
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    path = %self.path.display(),
+                    error = %e,
+                    "Failed to reload prompt file, keeping previous version"
+                ),
+            }
+        }
+        content.clone()
+    }
+}
+
+#[async_trait]
+impl PromptContextProvider for WatchedPrompt {
+    async fn provide(&self, _ctx: &PromptContext) -> crate::Result<Option<String>> {
+        Ok(Some(self.current()))
+    }
+}
+
+fn read_prompt(path: &Path, interpolate_env: bool) -> crate::Result<String> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        StoodError::configuration_error(format!(
+            "Failed to read prompt file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    if interpolate_env {
+        crate::config::agent_file::interpolate_env(&content)
+            .map_err(|e| StoodError::configuration_error(e.to_string()))
+    } else {
+        Ok(content)
+    }
+}
+
+fn watch_error(path: &Path, error: notify::Error) -> StoodError {
+    StoodError::configuration_error(format!(
+        "Failed to watch prompt file {}: {}",
+        path.display(),
+        error
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_watched_prompt_reloads_after_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("system.md");
+        std::fs::write(&path, "version one").unwrap();
+
+        let prompt = WatchedPrompt::new(&path).unwrap();
+        assert_eq!(prompt.current(), "version one");
+
+        std::fs::write(&path, "version two").unwrap();
+        let mut current = prompt.current();
+        for _ in 0..50 {
+            if current == "version two" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            current = prompt.current();
+        }
+        assert_eq!(current, "version two");
+    }
+}
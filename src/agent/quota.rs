@@ -0,0 +1,560 @@
+//! Per-session rate and quota enforcement for multi-tenant services.
+//!
+//! A [`QuotaManager`] tracks the usage of each session (or user, or tenant:
+//! any string id) over rolling windows and enforces [`QuotaLimits`] on
+//! executions per hour, tokens per day and cost per day. An agent configured
+//! with [`AgentBuilder::with_quota`] checks its session's quota before every
+//! execution and fails with [`StoodError::QuotaExceeded`] before the model is
+//! called, then records the tokens the execution used.
+//!
+//! One manager is shared by all the agents of a service:
+//!
+//! ```no_run
+//! use stood::agent::quota::{QuotaLimits, QuotaManager, TokenPricing};
+//! use stood::agent::Agent;
+//! use stood::StoodError;
+//!
+//! # async fn example(user_id: &str) -> Result<(), StoodError> {
+//! let quotas = QuotaManager::new(
+//!     QuotaLimits::new()
+//!         .with_executions_per_hour(60)
+//!         .with_tokens_per_day(500_000),
+//! )
+//! .with_pricing(TokenPricing::new(0.80, 4.00));
+//!
+//! let mut agent = Agent::builder()
+//!     .with_quota(quotas.clone(), user_id)
+//!     .build()
+//!     .await?;
+//!
+//! match agent.execute("Summarize my inbox").await {
+//!     Err(StoodError::QuotaExceeded { violation: Some(v), .. }) => {
+//!         println!("Try again in {:?}", v.retry_after);
+//!     }
+//!     other => println!("{:?}", other.map(|r| r.response)),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Usage is kept in memory, so limits apply per process. Sessions without
+//! usage in the last 24 hours are forgotten, apart from limits given with
+//! [`QuotaManager::set_limits`].
+//!
+//! [`AgentBuilder::with_quota`]: crate::agent::AgentBuilder::with_quota
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::StoodError;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Limits applied to each session; unset limits are not enforced
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuotaLimits {
+    /// Executions started in the last hour
+    pub executions_per_hour: Option<u32>,
+    /// Input and output tokens used in the last 24 hours
+    pub tokens_per_day: Option<u64>,
+    /// Cost in USD of the last 24 hours (requires [`TokenPricing`])
+    pub cost_per_day: Option<f64>,
+}
+
+impl QuotaLimits {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the executions started per rolling hour
+    pub fn with_executions_per_hour(mut self, executions: u32) -> Self {
+        self.executions_per_hour = Some(executions);
+        self
+    }
+
+    /// Limit the tokens used per rolling 24 hours
+    pub fn with_tokens_per_day(mut self, tokens: u64) -> Self {
+        self.tokens_per_day = Some(tokens);
+        self
+    }
+
+    /// Limit the cost in USD per rolling 24 hours
+    pub fn with_cost_per_day(mut self, cost: f64) -> Self {
+        self.cost_per_day = Some(cost);
+        self
+    }
+}
+
+/// Price of tokens, used to compute the cost of an execution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPricing {
+    /// USD per million input tokens
+    pub input_per_million: f64,
+    /// USD per million output tokens
+    pub output_per_million: f64,
+}
+
+impl TokenPricing {
+    /// Pricing from USD per million input and output tokens
+    pub fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    /// Cost in USD of the given token counts
+    pub fn cost(&self, input_tokens: u32, output_tokens: u32) -> f64 {
+        (input_tokens as f64 * self.input_per_million
+            + output_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// The limit a session exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaKind {
+    /// [`QuotaLimits::executions_per_hour`]
+    ExecutionsPerHour,
+    /// [`QuotaLimits::tokens_per_day`]
+    TokensPerDay,
+    /// [`QuotaLimits::cost_per_day`]
+    CostPerDay,
+}
+
+impl fmt::Display for QuotaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            QuotaKind::ExecutionsPerHour => "executions per hour",
+            QuotaKind::TokensPerDay => "tokens per day",
+            QuotaKind::CostPerDay => "cost per day",
+        })
+    }
+}
+
+/// Details of a [`StoodError::QuotaExceeded`] raised by a [`QuotaManager`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaViolation {
+    /// Session that exceeded its quota
+    pub session_id: String,
+    /// Which limit was exceeded
+    pub kind: QuotaKind,
+    /// The limit
+    pub limit: f64,
+    /// Usage in the current window
+    pub used: f64,
+    /// When enough of the window expires for the session to be under the limit
+    pub retry_after: Duration,
+}
+
+/// Usage of a session in the current windows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuotaUsage {
+    /// Executions started in the last hour
+    pub executions_last_hour: u32,
+    /// Tokens used in the last 24 hours
+    pub tokens_last_day: u64,
+    /// Cost in USD of the last 24 hours
+    pub cost_last_day: f64,
+}
+
+#[derive(Debug, Default)]
+struct SessionState {
+    limits: Option<QuotaLimits>,
+    executions: VecDeque<Instant>,
+    // (time, tokens, cost) of each recorded execution
+    consumption: VecDeque<(Instant, u64, f64)>,
+}
+
+impl SessionState {
+    fn expire(&mut self, now: Instant) {
+        while self
+            .executions
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= HOUR)
+        {
+            self.executions.pop_front();
+        }
+        while self
+            .consumption
+            .front()
+            .is_some_and(|(t, _, _)| now.duration_since(*t) >= DAY)
+        {
+            self.consumption.pop_front();
+        }
+    }
+
+    fn usage(&self) -> QuotaUsage {
+        QuotaUsage {
+            executions_last_hour: self.executions.len() as u32,
+            tokens_last_day: self.consumption.iter().map(|(_, tokens, _)| tokens).sum(),
+            cost_last_day: self.consumption.iter().map(|(_, _, cost)| cost).sum(),
+        }
+    }
+
+    /// Whether the session only holds its limits, if any
+    fn is_idle(&self) -> bool {
+        self.executions.is_empty() && self.consumption.is_empty()
+    }
+
+    /// How long until the consumption window drops below `limit`
+    fn consumption_retry_after(
+        &self,
+        now: Instant,
+        used: f64,
+        limit: f64,
+        amount: impl Fn(u64, f64) -> f64,
+    ) -> Duration {
+        let mut remaining = used;
+        for (t, tokens, cost) in &self.consumption {
+            remaining -= amount(*tokens, *cost);
+            if remaining < limit {
+                return DAY.saturating_sub(now.duration_since(*t));
+            }
+        }
+        DAY
+    }
+}
+
+#[derive(Debug)]
+struct Sessions {
+    states: HashMap<String, SessionState>,
+    last_sweep: Instant,
+}
+
+impl Sessions {
+    /// Drop the sessions that are idle and have no limits of their own,
+    /// at most once an hour
+    fn sweep(&mut self, now: Instant) {
+        if now.duration_since(self.last_sweep) < HOUR {
+            return;
+        }
+        self.last_sweep = now;
+        self.states.retain(|_, state| {
+            state.expire(now);
+            state.limits.is_some() || !state.is_idle()
+        });
+    }
+}
+
+/// Tracks usage per session and enforces [`QuotaLimits`]
+///
+/// Clones share the same usage.
+#[derive(Debug, Clone)]
+pub struct QuotaManager {
+    default_limits: QuotaLimits,
+    pricing: Option<TokenPricing>,
+    sessions: Arc<Mutex<Sessions>>,
+}
+
+impl QuotaManager {
+    /// Enforce `default_limits` on every session without its own limits
+    pub fn new(default_limits: QuotaLimits) -> Self {
+        Self {
+            default_limits,
+            pricing: None,
+            sessions: Arc::new(Mutex::new(Sessions {
+                states: HashMap::new(),
+                last_sweep: Instant::now(),
+            })),
+        }
+    }
+
+    /// Compute the cost of recorded tokens with `pricing`
+    ///
+    /// Without pricing, executions cost nothing and cost limits never trigger.
+    pub fn with_pricing(mut self, pricing: TokenPricing) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Give `session_id` its own limits instead of the defaults
+    pub fn set_limits(&self, session_id: &str, limits: QuotaLimits) {
+        self.lock()
+            .states
+            .entry(session_id.to_string())
+            .or_default()
+            .limits = Some(limits);
+    }
+
+    /// Limits that apply to `session_id`
+    pub fn limits(&self, session_id: &str) -> QuotaLimits {
+        self.lock()
+            .states
+            .get(session_id)
+            .and_then(|state| state.limits.clone())
+            .unwrap_or_else(|| self.default_limits.clone())
+    }
+
+    /// Fail if `session_id` is over any of its limits
+    pub fn check(&self, session_id: &str) -> Result<(), StoodError> {
+        let now = Instant::now();
+        let limits = self.limits(session_id);
+        let mut sessions = self.lock();
+        let state = sessions.states.entry(session_id.to_string()).or_default();
+        state.expire(now);
+        Self::check_state(session_id, &limits, state, now)
+    }
+
+    /// Check the quota of `session_id` and count one execution against it
+    pub fn start_execution(&self, session_id: &str) -> Result<(), StoodError> {
+        let now = Instant::now();
+        let limits = self.limits(session_id);
+        let mut sessions = self.lock();
+        let state = sessions.states.entry(session_id.to_string()).or_default();
+        state.expire(now);
+        Self::check_state(session_id, &limits, state, now)?;
+        state.executions.push_back(now);
+        Ok(())
+    }
+
+    /// Record the tokens an execution of `session_id` used
+    pub fn record_usage(&self, session_id: &str, input_tokens: u32, output_tokens: u32) {
+        let cost = self
+            .pricing
+            .map_or(0.0, |pricing| pricing.cost(input_tokens, output_tokens));
+        let tokens = input_tokens as u64 + output_tokens as u64;
+        self.lock()
+            .states
+            .entry(session_id.to_string())
+            .or_default()
+            .consumption
+            .push_back((Instant::now(), tokens, cost));
+    }
+
+    /// Usage of `session_id` in the current windows
+    pub fn usage(&self, session_id: &str) -> QuotaUsage {
+        let mut sessions = self.lock();
+        match sessions.states.get_mut(session_id) {
+            Some(state) => {
+                state.expire(Instant::now());
+                state.usage()
+            }
+            None => QuotaUsage::default(),
+        }
+    }
+
+    /// Forget the usage of `session_id`, keeping its limits
+    pub fn reset(&self, session_id: &str) {
+        if let Some(state) = self.lock().states.get_mut(session_id) {
+            state.executions.clear();
+            state.consumption.clear();
+        }
+    }
+
+    fn check_state(
+        session_id: &str,
+        limits: &QuotaLimits,
+        state: &SessionState,
+        now: Instant,
+    ) -> Result<(), StoodError> {
+        let usage = state.usage();
+        let violation = |kind, limit: f64, used: f64, retry_after| {
+            let violation = QuotaViolation {
+                session_id: session_id.to_string(),
+                kind,
+                limit,
+                used,
+                retry_after,
+            };
+            Err(StoodError::QuotaExceeded {
+                message: format!(
+                    "session '{}' reached its limit of {} {} (retry in {}s)",
+                    session_id,
+                    limit,
+                    kind,
+                    retry_after.as_secs()
+                ),
+                violation: Some(violation),
+            })
+        };
+
+        if let Some(limit) = limits.executions_per_hour {
+            if usage.executions_last_hour >= limit {
+                // The window drops below the limit when the oldest counted execution expires
+                let index = (usage.executions_last_hour - limit) as usize;
+                let retry_after = state
+                    .executions
+                    .get(index)
+                    .map_or(HOUR, |t| HOUR.saturating_sub(now.duration_since(*t)));
+                return violation(
+                    QuotaKind::ExecutionsPerHour,
+                    limit as f64,
+                    usage.executions_last_hour as f64,
+                    retry_after,
+                );
+            }
+        }
+        if let Some(limit) = limits.tokens_per_day {
+            if usage.tokens_last_day >= limit {
+                let (used, limit) = (usage.tokens_last_day as f64, limit as f64);
+                let retry_after =
+                    state.consumption_retry_after(now, used, limit, |tokens, _| tokens as f64);
+                return violation(QuotaKind::TokensPerDay, limit, used, retry_after);
+            }
+        }
+        if let Some(limit) = limits.cost_per_day {
+            if usage.cost_last_day >= limit {
+                let used = usage.cost_last_day;
+                let retry_after = state.consumption_retry_after(now, used, limit, |_, cost| cost);
+                return violation(QuotaKind::CostPerDay, limit, used, retry_after);
+            }
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Sessions> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.sweep(Instant::now());
+        sessions
+    }
+}
+
+/// A [`QuotaManager`] and the session an agent's executions count against
+#[derive(Debug, Clone)]
+pub struct SessionQuota {
+    /// Shared usage and limits
+    pub manager: QuotaManager,
+    /// Session of the agent
+    pub session_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::config::ExecutionConfig;
+    use crate::agent::test_support::{self, ScriptedProvider};
+    use crate::llm::traits::{ChatResponse, Usage};
+    use crate::tools::{Tool, ToolError, ToolResult};
+    use tokio_util::sync::CancellationToken;
+
+    #[test]
+    fn test_execution_and_token_limits_per_session() {
+        let quotas = QuotaManager::new(
+            QuotaLimits::new()
+                .with_executions_per_hour(2)
+                .with_tokens_per_day(1_000),
+        );
+        quotas.start_execution("alice").unwrap();
+        quotas.start_execution("alice").unwrap();
+        let err = quotas.start_execution("alice").unwrap_err();
+        let StoodError::QuotaExceeded {
+            violation: Some(violation),
+            ..
+        } = err
+        else {
+            panic!("unexpected error: {:?}", err);
+        };
+        assert_eq!(violation.kind, QuotaKind::ExecutionsPerHour);
+        assert_eq!(violation.used, 2.0);
+        assert!(violation.retry_after > Duration::from_secs(59 * 60));
+        assert_eq!(quotas.usage("alice").executions_last_hour, 2);
+
+        // Other sessions have their own usage
+        quotas.start_execution("bob").unwrap();
+        quotas.record_usage("bob", 700, 300);
+        let err = quotas.check("bob").unwrap_err();
+        assert!(err.to_string().contains("1000 tokens per day"), "{}", err);
+
+        quotas.reset("bob");
+        assert!(quotas.check("bob").is_ok());
+    }
+
+    #[test]
+    fn test_cost_limit_uses_pricing_and_session_limits() {
+        let quotas = QuotaManager::new(QuotaLimits::new().with_cost_per_day(1.0))
+            .with_pricing(TokenPricing::new(3.0, 15.0));
+        quotas.set_limits("trial", QuotaLimits::new().with_cost_per_day(0.01));
+
+        quotas.record_usage("trial", 1_000, 1_000);
+        assert!((quotas.usage("trial").cost_last_day - 0.018).abs() < 1e-9);
+        let err = quotas.check("trial").unwrap_err();
+        assert!(matches!(
+            err,
+            StoodError::QuotaExceeded {
+                violation: Some(QuotaViolation {
+                    kind: QuotaKind::CostPerDay,
+                    ..
+                }),
+                ..
+            }
+        ));
+
+        quotas.record_usage("paid", 1_000, 1_000);
+        assert!(quotas.check("paid").is_ok());
+        assert_eq!(quotas.limits("paid").cost_per_day, Some(1.0));
+    }
+
+    #[test]
+    fn test_idle_sessions_are_forgotten() {
+        let quotas = QuotaManager::new(QuotaLimits::new());
+        quotas.start_execution("alice").unwrap();
+        quotas.record_usage("alice", 10, 10);
+        quotas.set_limits("trial", QuotaLimits::new().with_tokens_per_day(100));
+        quotas.record_usage("trial", 10, 10);
+
+        let mut sessions = quotas.lock();
+        sessions.sweep(Instant::now() + HOUR);
+        assert_eq!(sessions.states.len(), 2);
+        sessions.sweep(Instant::now() + DAY + HOUR);
+        assert!(!sessions.states.contains_key("alice"));
+        assert!(sessions.states["trial"].is_idle());
+        drop(sessions);
+        assert_eq!(quotas.limits("trial").tokens_per_day, Some(100));
+    }
+
+    /// Tool that cancels the execution it runs in
+    #[derive(Debug)]
+    struct CancelTool(CancellationToken);
+
+    #[async_trait::async_trait]
+    impl Tool for CancelTool {
+        fn name(&self) -> &str {
+            "cancel"
+        }
+
+        fn description(&self) -> &str {
+            "Cancels the execution"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(
+            &self,
+            _parameters: Option<serde_json::Value>,
+            _agent_context: Option<&crate::agent::AgentContext>,
+        ) -> Result<ToolResult, ToolError> {
+            self.0.cancel();
+            Ok(ToolResult::success(serde_json::json!("cancelled")))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_execution_records_its_tokens() {
+        let token = CancellationToken::new();
+        let provider = Arc::new(ScriptedProvider::new([ChatResponse {
+            usage: Some(Usage::new(100, 20)),
+            ..test_support::tool_call("call-1", "cancel", serde_json::json!({}))
+        }]));
+        let mut config = ExecutionConfig::default();
+        config.event_loop.cancellation_token = Some(token.clone());
+        let mut agent =
+            test_support::agent(provider, vec![Box::new(CancelTool(token))], config).await;
+        let quotas = QuotaManager::new(QuotaLimits::new());
+        agent.config.quota = Some(SessionQuota {
+            manager: quotas.clone(),
+            session_id: "alice".to_string(),
+        });
+
+        let result = agent.execute("Cancel").await.unwrap();
+
+        assert!(!result.success);
+        assert_eq!(quotas.usage("alice").tokens_last_day, 120);
+    }
+}
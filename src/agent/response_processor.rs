@@ -0,0 +1,567 @@
+//! Post-processing of the final response.
+//!
+//! A chain of [`ResponseProcessor`]s configured on the builder transforms the
+//! model's final response before it is returned in
+//! [`AgentResult::response`](crate::agent::AgentResult::response). Processors run
+//! in the order they were added, each receiving the previous one's output.
+//!
+//! Built-in processors cover common needs: [`StripMarkdown`], [`MaxLength`],
+//! [`ConvertFormat`] and [`AgentRewrite`] (a model pass such as a grammar check).
+//! Closures can be used through [`FnResponseProcessor`].
+//!
+//! Every step is recorded in
+//! [`ExecutionDetails::post_processing`](crate::agent::ExecutionDetails::post_processing),
+//! together with the original response when it was changed. A failing processor
+//! leaves the text unchanged and records its error; the remaining processors still
+//! run. The conversation history keeps the unprocessed response.
+//!
+//! ```no_run
+//! use stood::agent::response_processor::{FnResponseProcessor, MaxLength, StripMarkdown};
+//! use stood::agent::Agent;
+//! use std::sync::Arc;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut agent = Agent::builder()
+//!     .with_response_processor(Arc::new(StripMarkdown))
+//!     .with_response_processor(Arc::new(MaxLength::new(160)))
+//!     .with_response_processor(Arc::new(FnResponseProcessor::new("sign", |text| {
+//!         format!("{}\n-- Support Bot", text)
+//!     })))
+//!     .build()
+//!     .await?;
+//!
+//! let result = agent.execute("Summarize my order status").await?;
+//! for step in &result.execution.post_processing {
+//!     println!("{}: changed={}", step.processor, step.changed);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::agent::Agent;
+
+/// Transforms the final response before it is returned
+#[async_trait]
+pub trait ResponseProcessor: Send + Sync + std::fmt::Debug {
+    /// Return the transformed response
+    async fn process(&self, response: &str) -> crate::Result<String>;
+
+    /// Name recorded in [`ProcessingStep::processor`]
+    fn name(&self) -> &str;
+}
+
+/// Record of one processor applied to the response
+#[derive(Debug, Clone)]
+pub struct ProcessingStep {
+    /// Name of the processor
+    pub processor: String,
+    /// Whether the processor changed the text
+    pub changed: bool,
+    /// Length of the text before the processor ran, in characters
+    pub chars_before: usize,
+    /// Length of the text after the processor ran, in characters
+    pub chars_after: usize,
+    /// Time spent in the processor
+    pub duration: Duration,
+    /// Error returned by the processor, in which case the text was left unchanged
+    pub error: Option<String>,
+}
+
+/// Run `processors` over `response` in order, returning the final text and a
+/// record of each step
+pub async fn apply_processors(
+    processors: &[Arc<dyn ResponseProcessor>],
+    response: String,
+) -> (String, Vec<ProcessingStep>) {
+    let mut text = response;
+    let mut steps = Vec::with_capacity(processors.len());
+
+    for processor in processors {
+        let started = Instant::now();
+        let chars_before = text.chars().count();
+        let (changed, error) = match processor.process(&text).await {
+            Ok(processed) => {
+                let changed = processed != text;
+                text = processed;
+                (changed, None)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Response processor '{}' failed, keeping previous text: {}",
+                    processor.name(),
+                    e
+                );
+                (false, Some(e.to_string()))
+            }
+        };
+        steps.push(ProcessingStep {
+            processor: processor.name().to_string(),
+            changed,
+            chars_before,
+            chars_after: text.chars().count(),
+            duration: started.elapsed(),
+            error,
+        });
+    }
+
+    (text, steps)
+}
+
+/// Removes Markdown syntax, leaving plain text
+///
+/// Headings, emphasis, inline code, links, images, block quotes and code fences
+/// are unwrapped; list items become `- ` bullets.
+#[derive(Debug, Clone, Default)]
+pub struct StripMarkdown;
+
+impl StripMarkdown {
+    fn strip(text: &str) -> String {
+        let mut lines = Vec::new();
+        for line in text.lines() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                continue;
+            }
+            if !trimmed.is_empty() && trimmed.chars().all(|c| matches!(c, '-' | '*' | '_' | ' ')) {
+                // Horizontal rule
+                continue;
+            }
+            let line = match heading_level(trimmed) {
+                Some(level) => trimmed[level..].trim_start(),
+                None => trimmed,
+            };
+            let line = line.trim_start_matches("> ").trim_start_matches('>');
+            let line = match line.strip_prefix("* ").or_else(|| line.strip_prefix("+ ")) {
+                Some(item) => format!("- {}", Self::strip_inline(item)),
+                None => Self::strip_inline(line),
+            };
+            lines.push(line);
+        }
+        lines.join("\n").trim().to_string()
+    }
+
+    fn strip_inline(line: &str) -> String {
+        let mut out = String::with_capacity(line.len());
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '*' | '`' => i += 1,
+                '_' if (i + 1 < chars.len() && chars[i + 1] == '_')
+                    || (i > 0 && chars[i - 1] == '_') =>
+                {
+                    i += 1
+                }
+                '!' if chars.get(i + 1) == Some(&'[') => i += 1,
+                '[' => {
+                    // [label](url) -> label
+                    let close = chars[i..].iter().position(|&c| c == ']').map(|p| p + i);
+                    match close {
+                        Some(close) if chars.get(close + 1) == Some(&'(') => {
+                            let end = chars[close..].iter().position(|&c| c == ')');
+                            out.extend(&chars[i + 1..close]);
+                            i = end.map(|e| close + e + 1).unwrap_or(close + 1);
+                        }
+                        _ => {
+                            out.push('[');
+                            i += 1;
+                        }
+                    }
+                }
+                c => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        }
+        out
+    }
+}
+
+#[async_trait]
+impl ResponseProcessor for StripMarkdown {
+    async fn process(&self, response: &str) -> crate::Result<String> {
+        Ok(Self::strip(response))
+    }
+
+    fn name(&self) -> &str {
+        "strip_markdown"
+    }
+}
+
+/// Limits the response to a maximum number of characters
+///
+/// Text is cut at the last word boundary that fits and the suffix (default `…`)
+/// is appended.
+#[derive(Debug, Clone)]
+pub struct MaxLength {
+    max_chars: usize,
+    suffix: String,
+}
+
+impl MaxLength {
+    pub fn new(max_chars: usize) -> Self {
+        Self {
+            max_chars,
+            suffix: "…".to_string(),
+        }
+    }
+
+    /// Text appended when the response is shortened
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+}
+
+#[async_trait]
+impl ResponseProcessor for MaxLength {
+    async fn process(&self, response: &str) -> crate::Result<String> {
+        if response.chars().count() <= self.max_chars {
+            return Ok(response.to_string());
+        }
+        let keep = self.max_chars.saturating_sub(self.suffix.chars().count());
+        let cut = response
+            .char_indices()
+            .nth(keep)
+            .map(|(i, _)| i)
+            .unwrap_or(response.len());
+        let head = &response[..cut];
+        // Prefer a word boundary unless that would discard most of the text
+        let head = match head.rfind(char::is_whitespace) {
+            Some(space) if space > cut / 2 => &head[..space],
+            _ => head,
+        };
+        Ok(format!("{}{}", head.trim_end(), self.suffix))
+    }
+
+    fn name(&self) -> &str {
+        "max_length"
+    }
+}
+
+/// Target format for [`ConvertFormat`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetFormat {
+    /// Markdown rendered as simple HTML
+    Html,
+    /// A JSON object with the response under `field`
+    Json { field: String },
+}
+
+/// Converts the (Markdown) response into another format
+#[derive(Debug, Clone)]
+pub struct ConvertFormat {
+    target: TargetFormat,
+}
+
+impl ConvertFormat {
+    pub fn new(target: TargetFormat) -> Self {
+        Self { target }
+    }
+
+    /// Render Markdown headings, lists, paragraphs, code blocks and emphasis as HTML
+    pub fn html() -> Self {
+        Self::new(TargetFormat::Html)
+    }
+
+    /// Wrap the response as `{"<field>": "<response>"}`
+    pub fn json(field: impl Into<String>) -> Self {
+        Self::new(TargetFormat::Json {
+            field: field.into(),
+        })
+    }
+
+    fn to_html(text: &str) -> String {
+        let mut html = Vec::new();
+        let mut paragraph: Vec<String> = Vec::new();
+        let mut in_list = false;
+        let mut in_code = false;
+
+        let flush = |paragraph: &mut Vec<String>, html: &mut Vec<String>| {
+            if !paragraph.is_empty() {
+                html.push(format!("<p>{}</p>", paragraph.join(" ")));
+                paragraph.clear();
+            }
+        };
+
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") {
+                flush(&mut paragraph, &mut html);
+                html.push(
+                    if in_code {
+                        "</code></pre>"
+                    } else {
+                        "<pre><code>"
+                    }
+                    .to_string(),
+                );
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                html.push(escape_html(line));
+                continue;
+            }
+
+            let item = trimmed
+                .strip_prefix("- ")
+                .or_else(|| trimmed.strip_prefix("* "));
+            if item.is_none() && in_list {
+                html.push("</ul>".to_string());
+                in_list = false;
+            }
+
+            if let Some(item) = item {
+                flush(&mut paragraph, &mut html);
+                if !in_list {
+                    html.push("<ul>".to_string());
+                    in_list = true;
+                }
+                html.push(format!("<li>{}</li>", inline_html(item)));
+            } else if trimmed.is_empty() {
+                flush(&mut paragraph, &mut html);
+            } else if let Some(level) = heading_level(trimmed) {
+                flush(&mut paragraph, &mut html);
+                let content = trimmed[level..].trim();
+                html.push(format!("<h{0}>{1}</h{0}>", level, inline_html(content)));
+            } else {
+                paragraph.push(inline_html(trimmed));
+            }
+        }
+        flush(&mut paragraph, &mut html);
+        if in_list {
+            html.push("</ul>".to_string());
+        }
+        if in_code {
+            html.push("</code></pre>".to_string());
+        }
+        html.join("\n")
+    }
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    ((1..=6).contains(&level) && line[level..].starts_with(' ')).then_some(level)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escape text and render `**bold**`, `*italic*` and `` `code` `` spans
+fn inline_html(text: &str) -> String {
+    let escaped = escape_html(text);
+    let mut out = String::with_capacity(escaped.len());
+    let mut rest = escaped.as_str();
+    while !rest.is_empty() {
+        let (marker, tag) = if rest.starts_with("**") {
+            ("**", "strong")
+        } else if rest.starts_with('*') {
+            ("*", "em")
+        } else if rest.starts_with('`') {
+            ("`", "code")
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        match rest[marker.len()..].find(marker) {
+            Some(end) if end > 0 => {
+                let inner = &rest[marker.len()..marker.len() + end];
+                out.push_str(&format!("<{0}>{1}</{0}>", tag, inner));
+                rest = &rest[2 * marker.len() + end..];
+            }
+            _ => {
+                out.push_str(marker);
+                rest = &rest[marker.len()..];
+            }
+        }
+    }
+    out
+}
+
+#[async_trait]
+impl ResponseProcessor for ConvertFormat {
+    async fn process(&self, response: &str) -> crate::Result<String> {
+        match &self.target {
+            TargetFormat::Html => Ok(Self::to_html(response)),
+            TargetFormat::Json { field } => {
+                let mut object = serde_json::Map::new();
+                object.insert(
+                    field.clone(),
+                    serde_json::Value::String(response.to_string()),
+                );
+                Ok(serde_json::Value::Object(object).to_string())
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self.target {
+            TargetFormat::Html => "convert_html",
+            TargetFormat::Json { .. } => "convert_json",
+        }
+    }
+}
+
+/// Rewrites the response with a separate agent, e.g. for a grammar check
+///
+/// The agent receives the instruction followed by the response and its reply
+/// replaces the response. A fresh copy of the agent is used for every call, so its
+/// conversation never accumulates.
+#[derive(Debug, Clone)]
+pub struct AgentRewrite {
+    name: String,
+    agent: Agent,
+    instruction: String,
+}
+
+impl AgentRewrite {
+    pub fn new(name: impl Into<String>, agent: Agent, instruction: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            agent,
+            instruction: instruction.into(),
+        }
+    }
+
+    /// Fix spelling and grammar without changing meaning or formatting
+    pub fn grammar_check(agent: Agent) -> Self {
+        Self::new(
+            "grammar_check",
+            agent,
+            "Correct spelling, grammar and punctuation in the text below. Keep its meaning, \
+             tone and formatting. Reply with the corrected text only.",
+        )
+    }
+}
+
+#[async_trait]
+impl ResponseProcessor for AgentRewrite {
+    async fn process(&self, response: &str) -> crate::Result<String> {
+        let mut agent = self.agent.clone();
+        agent.clear_history();
+        let result = agent
+            .execute(format!("{}\n\n---\n{}", self.instruction, response))
+            .await?;
+        Ok(result.response.trim().to_string())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// [`ResponseProcessor`] backed by a synchronous closure
+pub struct FnResponseProcessor<F> {
+    name: String,
+    transform: F,
+}
+
+impl<F> FnResponseProcessor<F>
+where
+    F: Fn(&str) -> String + Send + Sync,
+{
+    pub fn new(name: impl Into<String>, transform: F) -> Self {
+        Self {
+            name: name.into(),
+            transform,
+        }
+    }
+}
+
+impl<F> std::fmt::Debug for FnResponseProcessor<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnResponseProcessor")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<F> ResponseProcessor for FnResponseProcessor<F>
+where
+    F: Fn(&str) -> String + Send + Sync,
+{
+    async fn process(&self, response: &str) -> crate::Result<String> {
+        Ok((self.transform)(response))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Failing;
+
+    #[async_trait]
+    impl ResponseProcessor for Failing {
+        async fn process(&self, _response: &str) -> crate::Result<String> {
+            Err(crate::StoodError::InvalidInput {
+                message: "boom".to_string(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "failing"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_processor_chain_records_steps() {
+        let processors: Vec<Arc<dyn ResponseProcessor>> = vec![
+            Arc::new(StripMarkdown),
+            Arc::new(Failing),
+            Arc::new(MaxLength::new(20)),
+            Arc::new(FnResponseProcessor::new("noop", |t| t.to_string())),
+        ];
+        let (text, steps) = apply_processors(
+            &processors,
+            "## Status\n\nYour **order** has shipped and will arrive [soon](https://x.io)."
+                .to_string(),
+        )
+        .await;
+
+        assert_eq!(text, "Status\n\nYour order…");
+        assert_eq!(steps.len(), 4);
+        assert!(steps[0].changed);
+        assert_eq!(steps[1].error.as_deref(), Some("Invalid input: boom"));
+        assert!(!steps[1].changed);
+        assert!(steps[2].changed);
+        assert!(steps[2].chars_after <= 20);
+        assert!(!steps[3].changed);
+    }
+
+    #[tokio::test]
+    async fn test_convert_format() {
+        let html = ConvertFormat::html()
+            .process("# Title\n\nSome *text* & more\n\n- one\n- `two`")
+            .await
+            .unwrap();
+        assert_eq!(
+            html,
+            "<h1>Title</h1>\n<p>Some <em>text</em> &amp; more</p>\n<ul>\n<li>one</li>\n<li><code>two</code></li>\n</ul>"
+        );
+
+        let json = ConvertFormat::json("answer")
+            .process("hi \"there\"")
+            .await
+            .unwrap();
+        assert_eq!(json, r#"{"answer":"hi \"there\""}"#);
+    }
+}
@@ -4,8 +4,20 @@
 //! from an agent execution, including the response text, execution metrics,
 //! tool usage, and performance data.
 
+use crate::agent::artifacts::Artifact;
+use crate::agent::callbacks::events::RawResponseData;
+use crate::agent::citations::Citation;
+use crate::agent::conversation_diff::ConversationDiff;
 use crate::agent::event_loop::EventLoopResult;
-use crate::telemetry::EventLoopMetrics;
+use crate::agent::execution_trace::ExecutionTrace;
+use crate::agent::language::LanguageCheck;
+use crate::agent::response_processor::ProcessingStep;
+use crate::agent::router::RoutingDecision;
+use crate::agent::stop::TerminationReason;
+use crate::agent::token_attribution::TokenAttribution;
+use crate::error_recovery::{RetryBudgetUsage, RetrySummary};
+use crate::telemetry::{EventLoopMetrics, ModelCallMetric};
+use crate::tools::Plan;
 use std::time::Duration;
 
 /// Unified result type that contains all information from execution
@@ -72,6 +84,27 @@ pub struct AgentResult {
 
     /// Error message if execution failed
     pub error: Option<String>,
+
+    /// Why execution stopped (task completed, budget exhausted, cancelled, ...)
+    pub termination_reason: TerminationReason,
+
+    /// Citations of tool-provided documents in the response (empty unless
+    /// citations are enabled)
+    pub citations: Vec<Citation>,
+
+    /// Recorded decisions of the event loop (`None` unless execution tracing
+    /// is enabled)
+    pub execution_trace: Option<ExecutionTrace>,
+
+    /// Retries consumed from the retry budget (`None` unless a budget is
+    /// configured)
+    pub retry_budget: Option<RetryBudgetUsage>,
+
+    /// Task plan after the execution (`None` unless the plan tool is enabled)
+    pub plan: Option<Plan>,
+
+    /// Files, charts and other outputs added by tools
+    pub artifacts: Vec<Artifact>,
 }
 
 /// Detailed execution metrics and information
@@ -92,8 +125,34 @@ pub struct ExecutionDetails {
     /// Token usage information (if available)
     pub tokens: Option<TokenUsage>,
 
+    /// Token usage broken down by request part, cycle and tool (agentic execution only)
+    pub token_attribution: Option<TokenAttribution>,
+
     /// Performance metrics
     pub performance: PerformanceMetrics,
+
+    /// Model routing decision (if a model router is configured)
+    pub routing: Option<RoutingDecision>,
+
+    /// Response processors applied to the final response, in order
+    pub post_processing: Vec<ProcessingStep>,
+
+    /// The model's response before post-processing, if a processor changed it
+    pub original_response: Option<String>,
+
+    /// How the conversation changed during execution, including messages removed
+    /// or summarized by context management
+    pub conversation_diff: Option<ConversationDiff>,
+
+    /// Raw provider payloads of the model calls, in order (empty unless raw
+    /// response capture is enabled)
+    pub raw_responses: Vec<RawResponseData>,
+
+    /// Failed attempts, backoff and circuit breaker trips across retry layers
+    pub retries: RetrySummary,
+
+    /// Response language check, if a response language was requested
+    pub language: Option<LanguageCheck>,
 }
 
 /// Token usage information from model calls
@@ -123,6 +182,18 @@ pub struct PerformanceMetrics {
 
     /// Whether streaming was used
     pub was_streamed: bool,
+
+    /// Average time to the first streamed token of a model call
+    pub time_to_first_token: Option<Duration>,
+
+    /// Output tokens per second of model generation
+    pub tokens_per_second: Option<f64>,
+
+    /// Total latency reported by the provider, when it reports one
+    pub provider_latency: Option<Duration>,
+
+    /// Latency breakdown of each model call
+    pub model_calls: Vec<ModelCallMetric>,
 }
 
 /// Detailed breakdown of tool call attempts and results
@@ -155,6 +226,9 @@ pub struct FailedToolCall {
 
     /// Duration of the failed attempt
     pub duration: Duration,
+
+    /// Input the model passed to the tool
+    pub input: Option<serde_json::Value>,
 }
 
 // Python-like string conversion - returns just the response text
@@ -188,7 +262,15 @@ impl AgentResult {
                     })
                 }
             },
+            token_attribution: Some(event_result.token_attribution),
             performance: PerformanceMetrics::from(&event_result.metrics, event_result.was_streamed),
+            routing: None,
+            post_processing: Vec::new(),
+            original_response: None,
+            conversation_diff: None,
+            raw_responses: event_result.raw_responses,
+            retries: event_result.retries,
+            language: None,
         };
 
         let successful_tools = event_result.metrics.tools_successful();
@@ -213,71 +295,61 @@ impl AgentResult {
             duration: total_duration,
             success: event_result.success,
             error: event_result.error,
+            termination_reason: event_result.termination_reason,
+            citations: event_result.citations,
+            execution_trace: event_result.execution_trace,
+            retry_budget: event_result.retry_budget,
+            plan: None,
+            artifacts: Vec::new(),
         }
     }
 
     /// Create a simple success result (for non-agentic execution)
     pub fn simple_success(response: String, duration: Duration) -> Self {
+        let default = Self::default();
         Self {
             response,
             execution: ExecutionDetails {
                 cycles: 1,
                 model_calls: 1,
-                tool_executions: 0,
-                tokens: None,
                 performance: PerformanceMetrics {
                     avg_cycle_time: duration,
                     model_time: duration,
-                    tool_time: Duration::ZERO,
-                    was_streamed: false,
+                    ..default.execution.performance
                 },
-            },
-            used_tools: false,
-            tools_called: Vec::new(),
-            tools_successful: Vec::new(),
-            tools_failed: Vec::new(),
-            tool_call_summary: ToolCallSummary {
-                total_attempts: 0,
-                successful: 0,
-                failed: 0,
-                failed_calls: Vec::new(),
+                ..default.execution
             },
             duration,
             success: true,
-            error: None,
+            ..default
         }
     }
 
     /// Create an error result
     pub fn error(error_message: String, duration: Duration) -> Self {
         Self {
-            response: String::new(),
-            execution: ExecutionDetails {
-                cycles: 0,
-                model_calls: 0,
-                tool_executions: 0,
-                tokens: None,
-                performance: PerformanceMetrics {
-                    avg_cycle_time: Duration::ZERO,
-                    model_time: Duration::ZERO,
-                    tool_time: Duration::ZERO,
-                    was_streamed: false,
-                },
-            },
-            used_tools: false,
-            tools_called: Vec::new(),
-            tools_successful: Vec::new(),
-            tools_failed: Vec::new(),
-            tool_call_summary: ToolCallSummary {
-                total_attempts: 0,
-                successful: 0,
-                failed: 0,
-                failed_calls: Vec::new(),
-            },
             duration,
-            success: false,
             error: Some(error_message),
+            termination_reason: TerminationReason::Error,
+            ..Self::default()
+        }
+    }
+
+    /// Add the model calls, tokens and time of an earlier, discarded attempt
+    /// at the same turn
+    pub(crate) fn add_attempt(&mut self, earlier: &AgentResult) {
+        self.execution.model_calls += earlier.execution.model_calls;
+        if let Some(earlier) = &earlier.execution.tokens {
+            let tokens = self.execution.tokens.get_or_insert(TokenUsage {
+                input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+            });
+            tokens.input_tokens += earlier.input_tokens;
+            tokens.output_tokens += earlier.output_tokens;
+            tokens.total_tokens += earlier.total_tokens;
         }
+        self.duration += earlier.duration;
     }
 }
 
@@ -296,6 +368,10 @@ impl PerformanceMetrics {
             model_time: metrics.total_model_time(),
             tool_time: metrics.total_tool_time(),
             was_streamed,
+            time_to_first_token: metrics.average_time_to_first_token(),
+            tokens_per_second: metrics.tokens_per_second(),
+            provider_latency: metrics.total_provider_latency(),
+            model_calls: metrics.model_calls.clone(),
         }
     }
 }
@@ -309,12 +385,24 @@ impl Default for AgentResult {
                 model_calls: 0,
                 tool_executions: 0,
                 tokens: None,
+                token_attribution: None,
                 performance: PerformanceMetrics {
                     avg_cycle_time: Duration::ZERO,
                     model_time: Duration::ZERO,
                     tool_time: Duration::ZERO,
                     was_streamed: false,
+                    time_to_first_token: None,
+                    tokens_per_second: None,
+                    provider_latency: None,
+                    model_calls: Vec::new(),
                 },
+                routing: None,
+                post_processing: Vec::new(),
+                original_response: None,
+                conversation_diff: None,
+                raw_responses: Vec::new(),
+                retries: Default::default(),
+                language: None,
             },
             used_tools: false,
             tools_called: Vec::new(),
@@ -329,6 +417,12 @@ impl Default for AgentResult {
             duration: Duration::ZERO,
             success: false,
             error: None,
+            termination_reason: TerminationReason::Completed,
+            citations: Vec::new(),
+            execution_trace: None,
+            retry_budget: None,
+            plan: None,
+            artifacts: Vec::new(),
         }
     }
 }
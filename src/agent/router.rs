@@ -0,0 +1,452 @@
+//! Adaptive model routing based on task complexity.
+//!
+//! A [`ModelRouter`] classifies each prompt before execution and picks the model
+//! for that execution: simple queries go to a cheap, fast model while complex
+//! multi-step tasks go to a stronger one. Classification is done by a
+//! [`ComplexityClassifier`], either the built-in [`HeuristicClassifier`] (no model
+//! call) or an [`LlmClassifier`] that asks a small model such as Haiku or Nova Micro.
+//!
+//! The decision for each execution is reported in
+//! [`ExecutionDetails::routing`](crate::agent::ExecutionDetails::routing).
+//!
+//! ```no_run
+//! use stood::agent::{Agent, ModelRouter};
+//! use stood::llm::models::Bedrock;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let router = ModelRouter::heuristic()
+//!     .simple(Bedrock::ClaudeHaiku45)
+//!     .complex(Bedrock::ClaudeSonnet45);
+//!
+//! let mut agent = Agent::builder()
+//!     .model(Bedrock::ClaudeHaiku45)
+//!     .with_model_router(router)
+//!     .build()
+//!     .await?;
+//!
+//! let result = agent.execute("What is 2 + 2?").await?;
+//! println!("Routed to: {:?}", result.execution.routing.map(|r| r.model_id));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::llm::registry::PROVIDER_REGISTRY;
+use crate::llm::traits::{ChatConfig, LlmModel, ProviderType};
+use crate::types::{Message, Messages};
+use crate::StoodError;
+
+/// Estimated complexity of a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskComplexity {
+    /// Short factual or conversational queries
+    Simple,
+    /// Tasks that need some reasoning or a single tool call
+    Moderate,
+    /// Multi-step tasks, analysis, or tasks likely to use several tools
+    Complex,
+}
+
+impl TaskComplexity {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.to_lowercase();
+        if text.contains("complex") {
+            Some(Self::Complex)
+        } else if text.contains("moderate") {
+            Some(Self::Moderate)
+        } else if text.contains("simple") {
+            Some(Self::Simple)
+        } else {
+            None
+        }
+    }
+}
+
+/// Information about the agent available to classifiers
+#[derive(Debug, Clone)]
+pub struct RoutingContext {
+    /// Number of tools registered on the agent
+    pub tool_count: usize,
+    /// Number of messages already in the conversation
+    pub message_count: usize,
+}
+
+/// Result of classifying a prompt
+#[derive(Debug, Clone)]
+pub struct Classification {
+    pub complexity: TaskComplexity,
+    /// Short explanation of the classification
+    pub reason: String,
+}
+
+/// Classifies prompts by complexity
+#[async_trait]
+pub trait ComplexityClassifier: Send + Sync + std::fmt::Debug {
+    async fn classify(&self, prompt: &str, ctx: &RoutingContext) -> crate::Result<Classification>;
+
+    /// Name of this classifier, reported in routing decisions
+    fn name(&self) -> &str;
+}
+
+/// Rule-based classifier using prompt length and wording
+#[derive(Debug, Clone)]
+pub struct HeuristicClassifier {
+    /// Prompts up to this many characters without complex keywords are simple
+    pub simple_max_chars: usize,
+    /// Prompts of at least this many characters are complex
+    pub complex_min_chars: usize,
+    /// Words and phrases that indicate multi-step work
+    pub complex_keywords: Vec<String>,
+}
+
+impl Default for HeuristicClassifier {
+    fn default() -> Self {
+        Self {
+            simple_max_chars: 200,
+            complex_min_chars: 1_500,
+            complex_keywords: [
+                "analyze",
+                "analyse",
+                "compare",
+                "design",
+                "implement",
+                "refactor",
+                "investigate",
+                "plan",
+                "step by step",
+                "research",
+                "debug",
+                "and then",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        }
+    }
+}
+
+impl HeuristicClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ComplexityClassifier for HeuristicClassifier {
+    async fn classify(&self, prompt: &str, ctx: &RoutingContext) -> crate::Result<Classification> {
+        let lower = prompt.to_lowercase();
+        let keywords: Vec<&str> = self
+            .complex_keywords
+            .iter()
+            .filter(|k| lower.contains(k.as_str()))
+            .map(|k| k.as_str())
+            .collect();
+        // Numbered or bulleted lists usually describe several steps
+        let list_items = prompt
+            .lines()
+            .filter(|line| {
+                let line = line.trim_start();
+                line.starts_with("- ")
+                    || line.starts_with("* ")
+                    || line.split_once(". ").is_some_and(|(n, _)| {
+                        !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+                    })
+            })
+            .count();
+
+        let (complexity, reason) = if prompt.len() >= self.complex_min_chars {
+            (
+                TaskComplexity::Complex,
+                format!("long prompt ({} chars)", prompt.len()),
+            )
+        } else if keywords.len() >= 2 || list_items >= 3 {
+            (
+                TaskComplexity::Complex,
+                format!(
+                    "multi-step wording (keywords: {:?}, list items: {})",
+                    keywords, list_items
+                ),
+            )
+        } else if keywords.is_empty() && prompt.len() <= self.simple_max_chars {
+            (
+                TaskComplexity::Simple,
+                format!("short prompt ({} chars)", prompt.len()),
+            )
+        } else if !keywords.is_empty() && ctx.tool_count > 0 {
+            (
+                TaskComplexity::Complex,
+                format!(
+                    "keywords {:?} with {} tools available",
+                    keywords, ctx.tool_count
+                ),
+            )
+        } else {
+            (TaskComplexity::Moderate, "no strong signals".to_string())
+        };
+
+        Ok(Classification { complexity, reason })
+    }
+
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+}
+
+const CLASSIFIER_PROMPT: &str =
+    "Classify the complexity of the following task for an AI assistant. \
+Answer with exactly one word: simple (a short factual or conversational answer), \
+moderate (some reasoning or a single lookup), or complex (multi-step work, analysis, \
+or several tool calls).\n\nTask:\n";
+
+/// Classifier that asks a small, cheap model to rate the prompt
+#[derive(Debug, Clone)]
+pub struct LlmClassifier {
+    provider: ProviderType,
+    model_id: String,
+}
+
+impl LlmClassifier {
+    /// Use the given model for classification (e.g. `Bedrock::ClaudeHaiku45` or `Bedrock::NovaMicro`)
+    pub fn new<M: LlmModel>(model: M) -> Self {
+        Self {
+            provider: model.provider(),
+            model_id: model.model_id().to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ComplexityClassifier for LlmClassifier {
+    async fn classify(&self, prompt: &str, _ctx: &RoutingContext) -> crate::Result<Classification> {
+        let provider = PROVIDER_REGISTRY
+            .get_provider(self.provider)
+            .await
+            .map_err(|e| {
+                StoodError::configuration_error(format!("Failed to get provider: {}", e))
+            })?;
+
+        let mut messages = Messages::new();
+        messages.push(Message::user(format!("{}{}", CLASSIFIER_PROMPT, prompt)));
+        let config = ChatConfig {
+            model_id: self.model_id.clone(),
+            provider: self.provider,
+            temperature: Some(0.0),
+            max_tokens: Some(10),
+            ..ChatConfig::default()
+        };
+
+        let response = provider
+            .chat(&self.model_id, &messages, &config)
+            .await
+            .map_err(|e| StoodError::model_error(format!("Classifier call failed: {}", e)))?;
+
+        let complexity = TaskComplexity::parse(&response.content).ok_or_else(|| {
+            StoodError::model_error(format!(
+                "Unexpected classifier response: {}",
+                response.content
+            ))
+        })?;
+        Ok(Classification {
+            complexity,
+            reason: format!("classified by {}", self.model_id),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "llm"
+    }
+}
+
+/// Model selected for a complexity level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelRoute {
+    pub provider: ProviderType,
+    pub model_id: String,
+}
+
+impl ModelRoute {
+    pub fn from_model<M: LlmModel>(model: &M) -> Self {
+        Self {
+            provider: model.provider(),
+            model_id: model.model_id().to_string(),
+        }
+    }
+}
+
+/// Routing decision for one execution, reported in the agent result
+#[derive(Debug, Clone)]
+pub struct RoutingDecision {
+    pub complexity: TaskComplexity,
+    pub provider: ProviderType,
+    pub model_id: String,
+    /// Name of the classifier that made the decision
+    pub classifier: String,
+    /// Why the prompt was classified this way
+    pub reason: String,
+    /// Whether the agent's default model was used because no route matched
+    pub used_default: bool,
+}
+
+/// Routes each execution to a model based on the prompt's complexity
+#[derive(Debug, Clone)]
+pub struct ModelRouter {
+    classifier: Arc<dyn ComplexityClassifier>,
+    routes: HashMap<TaskComplexity, ModelRoute>,
+}
+
+impl ModelRouter {
+    /// Create a router using a custom classifier
+    pub fn new(classifier: Arc<dyn ComplexityClassifier>) -> Self {
+        Self {
+            classifier,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Create a router using [`HeuristicClassifier`] defaults
+    pub fn heuristic() -> Self {
+        Self::new(Arc::new(HeuristicClassifier::default()))
+    }
+
+    /// Create a router that classifies prompts with the given model
+    pub fn with_classifier_model<M: LlmModel>(model: M) -> Self {
+        Self::new(Arc::new(LlmClassifier::new(model)))
+    }
+
+    /// Model for simple prompts
+    pub fn simple<M: LlmModel>(self, model: M) -> Self {
+        self.route(TaskComplexity::Simple, model)
+    }
+
+    /// Model for moderate prompts
+    pub fn moderate<M: LlmModel>(self, model: M) -> Self {
+        self.route(TaskComplexity::Moderate, model)
+    }
+
+    /// Model for complex prompts
+    pub fn complex<M: LlmModel>(self, model: M) -> Self {
+        self.route(TaskComplexity::Complex, model)
+    }
+
+    /// Set the model for a complexity level
+    pub fn route<M: LlmModel>(mut self, complexity: TaskComplexity, model: M) -> Self {
+        self.routes
+            .insert(complexity, ModelRoute::from_model(&model));
+        self
+    }
+
+    /// Classify a prompt and choose a model.
+    ///
+    /// Complexity levels without a route use `default`. Moderate prompts fall
+    /// back to the complex route before the default when no moderate route is set.
+    pub async fn decide(
+        &self,
+        prompt: &str,
+        ctx: &RoutingContext,
+        default: &ModelRoute,
+    ) -> crate::Result<RoutingDecision> {
+        let classification = self.classifier.classify(prompt, ctx).await?;
+        let route = self.routes.get(&classification.complexity).or_else(|| {
+            (classification.complexity == TaskComplexity::Moderate)
+                .then(|| self.routes.get(&TaskComplexity::Complex))
+                .flatten()
+        });
+        let used_default = route.is_none();
+        let route = route.unwrap_or(default);
+
+        Ok(RoutingDecision {
+            complexity: classification.complexity,
+            provider: route.provider,
+            model_id: route.model_id.clone(),
+            classifier: self.classifier.name().to_string(),
+            reason: classification.reason,
+            used_default,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::models::Bedrock;
+
+    fn ctx(tool_count: usize) -> RoutingContext {
+        RoutingContext {
+            tool_count,
+            message_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_classification() {
+        let classifier = HeuristicClassifier::default();
+
+        let simple = classifier
+            .classify("What is 2 + 2?", &ctx(0))
+            .await
+            .unwrap();
+        assert_eq!(simple.complexity, TaskComplexity::Simple);
+
+        let complex = classifier
+            .classify("Analyze the logs and then compare error rates", &ctx(0))
+            .await
+            .unwrap();
+        assert_eq!(complex.complexity, TaskComplexity::Complex);
+
+        let steps = "Please do this:\n1. Fetch the data\n2. Clean it\n3. Plot it";
+        let listed = classifier.classify(steps, &ctx(0)).await.unwrap();
+        assert_eq!(listed.complexity, TaskComplexity::Complex);
+
+        let moderate = classifier
+            .classify(&"Tell me about the history of Rome. ".repeat(10), &ctx(0))
+            .await
+            .unwrap();
+        assert_eq!(moderate.complexity, TaskComplexity::Moderate);
+    }
+
+    #[tokio::test]
+    async fn test_router_decisions() {
+        let router = ModelRouter::heuristic()
+            .simple(Bedrock::NovaMicro)
+            .complex(Bedrock::ClaudeSonnet45);
+        let default = ModelRoute::from_model(&Bedrock::ClaudeHaiku45);
+
+        let decision = router.decide("Hi there", &ctx(0), &default).await.unwrap();
+        assert_eq!(decision.model_id, Bedrock::NovaMicro.model_id());
+        assert!(!decision.used_default);
+        assert_eq!(decision.classifier, "heuristic");
+
+        // Moderate falls back to the complex route
+        let decision = router
+            .decide(&"Tell me about Rome. ".repeat(20), &ctx(0), &default)
+            .await
+            .unwrap();
+        assert_eq!(decision.complexity, TaskComplexity::Moderate);
+        assert_eq!(decision.model_id, Bedrock::ClaudeSonnet45.model_id());
+
+        let simple_only = ModelRouter::heuristic().simple(Bedrock::NovaMicro);
+        let decision = simple_only
+            .decide("Research and compare vendors", &ctx(0), &default)
+            .await
+            .unwrap();
+        assert!(decision.used_default);
+        assert_eq!(decision.model_id, default.model_id);
+    }
+
+    #[test]
+    fn test_parse_classifier_response() {
+        assert_eq!(
+            TaskComplexity::parse("Simple."),
+            Some(TaskComplexity::Simple)
+        );
+        assert_eq!(
+            TaskComplexity::parse("COMPLEX"),
+            Some(TaskComplexity::Complex)
+        );
+        assert_eq!(TaskComplexity::parse("unsure"), None);
+    }
+}
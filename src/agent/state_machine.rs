@@ -0,0 +1,243 @@
+//! Step-by-step execution of the agentic loop.
+//!
+//! [`EventLoop::execute`] runs model interaction cycles until the task is done
+//! or a limit is reached. An [`AgenticStateMachine`] runs the same execution
+//! one [`step`](AgenticStateMachine::step) at a time, so applications can
+//! drive it themselves: interleave cycles with their own scheduler, persist
+//! between them, or run one cycle per request in a serverless handler.
+//!
+//! ```no_run
+//! use stood::agent::event_loop::{EventLoop, EventLoopConfig};
+//! use stood::agent::state_machine::{AgenticStateMachine, StepOutcome};
+//! use stood::agent::Agent;
+//! use stood::tools::ToolRegistry;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let agent = Agent::builder().build().await?;
+//! let event_loop = EventLoop::new(agent, ToolRegistry::new(), EventLoopConfig::default())?;
+//!
+//! let mut machine = AgenticStateMachine::new(event_loop);
+//! machine.start("Plan a three-day trip to Lisbon").await?;
+//! let result = loop {
+//!     match machine.step().await? {
+//!         StepOutcome::Cycle { cycle_number, response } => {
+//!             println!("Cycle {}: {}", cycle_number, response);
+//!         }
+//!         StepOutcome::CycleFailed { error, .. } => eprintln!("Cycle failed: {}", error),
+//!         StepOutcome::Finished(result) => break result,
+//!     }
+//! };
+//! println!("{} ({})", result.response, result.termination_reason);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The execution lives in the machine between steps. Dropping a step before
+//! it completes abandons the execution and leaves the machine idle.
+
+use std::borrow::BorrowMut;
+use std::future::Future;
+
+use super::deadline::Deadline;
+use super::event_loop::{EventLoop, EventLoopResult, ExecutionRun};
+use super::stop::TerminationReason;
+use crate::error_recovery::{RetryBudget, RetryHistory};
+use crate::{Result, StoodError};
+
+/// Where an [`AgenticStateMachine`] is in its execution
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgenticState {
+    /// No execution in progress; [`start`](AgenticStateMachine::start) one
+    Idle,
+    /// Executing; the next step runs another cycle, or finishes the execution
+    /// if a limit has been reached meanwhile
+    Running { cycles_executed: u32 },
+    /// A cycle stopped the execution; the next step finishes it
+    Stopping { reason: TerminationReason },
+}
+
+/// What a step of an [`AgenticStateMachine`] did
+#[derive(Debug)]
+pub enum StepOutcome {
+    /// Model interaction cycle `cycle_number` completed with `response`
+    Cycle { cycle_number: u32, response: String },
+    /// Cycle `cycle_number` failed with `error`, stopping the execution
+    CycleFailed {
+        cycle_number: u32,
+        error: StoodError,
+    },
+    /// The execution finished; the machine is idle again
+    Finished(Box<EventLoopResult>),
+}
+
+/// Drives an [`EventLoop`] one cycle at a time
+///
+/// Owns the event loop by default; [`EventLoop::execute`] drives one over
+/// `&mut EventLoop`.
+pub struct AgenticStateMachine<E: BorrowMut<EventLoop> = EventLoop> {
+    event_loop: E,
+    run: Option<ExecutionRun>,
+}
+
+impl<E: BorrowMut<EventLoop>> AgenticStateMachine<E> {
+    /// An idle machine driving `event_loop`
+    pub fn new(event_loop: E) -> Self {
+        Self {
+            event_loop,
+            run: None,
+        }
+    }
+
+    pub fn event_loop(&self) -> &EventLoop {
+        self.event_loop.borrow()
+    }
+
+    /// The event loop, abandoning any execution in progress
+    pub fn into_event_loop(self) -> E {
+        self.event_loop
+    }
+
+    pub fn state(&self) -> AgenticState {
+        match &self.run {
+            None => AgenticState::Idle,
+            Some(run) => match run.termination_reason() {
+                Some(reason) => AgenticState::Stopping {
+                    reason: reason.clone(),
+                },
+                None => AgenticState::Running {
+                    cycles_executed: run.cycles_executed(),
+                },
+            },
+        }
+    }
+
+    /// Start executing `prompt`
+    ///
+    /// Adds the prompt to the conversation; the first [`step`](Self::step)
+    /// runs the first cycle. Fails if an execution is already in progress.
+    pub async fn start(&mut self, prompt: impl Into<String>) -> Result<()> {
+        if self.run.is_some() {
+            return Err(StoodError::invalid_input(
+                "An execution is already in progress; step it to completion first",
+            ));
+        }
+        let event_loop = self.event_loop.borrow_mut();
+        let history = RetryHistory::new();
+        let budget = event_loop
+            .config()
+            .retry_budget
+            .as_ref()
+            .map(RetryBudget::fresh);
+        let deadline = event_loop.deadline();
+        let run = scoped(
+            history,
+            budget,
+            deadline,
+            event_loop.start_run(prompt.into()),
+        )
+        .await;
+        self.run = Some(run);
+        Ok(())
+    }
+
+    /// Run the next cycle, or finish the execution once it has stopped or
+    /// reached a limit
+    ///
+    /// Fails if no execution is in progress.
+    pub async fn step(&mut self) -> Result<StepOutcome> {
+        let Some(mut run) = self.run.take() else {
+            return Err(StoodError::invalid_input(
+                "No execution in progress; start one first",
+            ));
+        };
+        let event_loop = self.event_loop.borrow_mut();
+        let history = run.retry_history.clone();
+        let budget = run.retry_budget.clone();
+        let deadline = run.deadline;
+
+        if !event_loop.can_continue(&run) {
+            let result = scoped(history, budget, deadline, event_loop.finish_run(run)).await;
+            return Ok(StepOutcome::Finished(Box::new(result)));
+        }
+
+        let cycle_number = run.cycles_executed() + 1;
+        let outcome = scoped(history, budget, deadline, event_loop.run_cycle(&mut run)).await;
+        self.run = Some(run);
+        Ok(match outcome {
+            Ok(response) => StepOutcome::Cycle {
+                cycle_number,
+                response,
+            },
+            Err(error) => StepOutcome::CycleFailed {
+                cycle_number,
+                error,
+            },
+        })
+    }
+}
+
+/// Run `future` with the retry history, budget and deadline of an execution
+async fn scoped<F: Future>(
+    history: RetryHistory,
+    budget: Option<RetryBudget>,
+    deadline: Option<Deadline>,
+    future: F,
+) -> F::Output {
+    // Boxed so that the scope wrappers do not each hold the large cycle future
+    let future = history.scope(Deadline::scope_opt(deadline, Box::pin(future)));
+    match budget {
+        Some(budget) => budget.scope(future).await,
+        None => future.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::config::ExecutionConfig;
+    use crate::agent::event_loop::EventLoopConfig;
+    use crate::agent::test_support::{self, ScriptedProvider};
+    use crate::tools::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_state_transitions_without_cycles() {
+        let agent = test_support::agent(
+            Arc::new(ScriptedProvider::default()),
+            vec![],
+            ExecutionConfig::default(),
+        )
+        .await;
+        let config = EventLoopConfig {
+            max_cycles: 0,
+            ..Default::default()
+        };
+        let event_loop = EventLoop::new(agent, ToolRegistry::new(), config).unwrap();
+        let mut machine = AgenticStateMachine::new(event_loop);
+
+        assert_eq!(machine.state(), AgenticState::Idle);
+        assert!(machine.step().await.is_err());
+
+        machine.start("Hello").await.unwrap();
+        assert_eq!(
+            machine.state(),
+            AgenticState::Running { cycles_executed: 0 }
+        );
+        assert!(machine.start("Again").await.is_err());
+        assert_eq!(
+            machine.event_loop().agent().conversation().message_count(),
+            1
+        );
+
+        // No cycle fits the limit, so the first step finishes the execution
+        let StepOutcome::Finished(result) = machine.step().await.unwrap() else {
+            panic!("expected the execution to finish");
+        };
+        assert_eq!(result.cycles_executed, 0);
+        assert_eq!(
+            result.termination_reason,
+            TerminationReason::MaxCycles { limit: 0 }
+        );
+        assert_eq!(machine.state(), AgenticState::Idle);
+    }
+}
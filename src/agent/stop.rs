@@ -0,0 +1,216 @@
+//! Stop conditions for agentic execution.
+//!
+//! Besides finishing the task, the event loop stops when a budget from
+//! [`EventLoopConfig`](crate::agent::EventLoopConfig) is exhausted: the number of
+//! cycles, wall-clock duration, total tokens or tool iterations. Applications can
+//! add their own [`StopCondition`]s, which are checked after every cycle that
+//! would otherwise continue.
+//!
+//! The reason execution ended is recorded as a [`TerminationReason`] on
+//! [`AgentResult::termination_reason`](crate::agent::AgentResult::termination_reason),
+//! so callers can tell a completed task from an exhausted budget.
+//!
+//! ```no_run
+//! use stood::agent::stop::{FnStopCondition, TerminationReason};
+//! use stood::agent::Agent;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut agent = Agent::builder()
+//!     .with_max_cycles(20)
+//!     .with_max_duration(Duration::from_secs(120))
+//!     .with_max_total_tokens(50_000)
+//!     .with_stop_condition(Arc::new(FnStopCondition::new("answer_found", |ctx| {
+//!         let found = ctx.last_response.contains("FINAL ANSWER");
+//!         async move { found.then(|| "final answer produced".to_string()) }
+//!     })))
+//!     .build()
+//!     .await?;
+//!
+//! let result = agent.execute("Research the question").await?;
+//! if result.termination_reason.is_budget_exhausted() {
+//!     println!("Stopped early: {}", result.termination_reason);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::agent::anomaly::Anomaly;
+
+/// Why an agent execution ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerminationReason {
+    /// The model finished the task
+    Completed,
+    /// `max_cycles` model interaction cycles were executed
+    MaxCycles { limit: u32 },
+    /// `max_duration` elapsed
+    MaxDuration { limit: Duration },
+    /// `max_total_tokens` were consumed
+    MaxTokens { limit: u32, used: u32 },
+    /// The model requested tools more than `max_tool_iterations` times in one cycle
+    MaxToolIterations { limit: u32 },
+    /// A custom [`StopCondition`] stopped execution
+    Condition { name: String, reason: String },
+    /// The [behavior monitor](crate::agent::anomaly) detected an anomaly
+    /// and stopped execution
+    BehaviorAnomaly { anomaly: Anomaly },
+    /// The [`Deadline`](crate::agent::Deadline) of the execution passed
+    DeadlineExceeded,
+    /// The cancellation token was triggered
+    Cancelled,
+    /// Execution failed with an error
+    Error,
+}
+
+impl TerminationReason {
+    /// Whether execution stopped because a limit was reached rather than because
+    /// the task completed, failed or was cancelled
+    pub fn is_budget_exhausted(&self) -> bool {
+        matches!(
+            self,
+            Self::MaxCycles { .. }
+                | Self::MaxDuration { .. }
+                | Self::MaxTokens { .. }
+                | Self::MaxToolIterations { .. }
+                | Self::DeadlineExceeded
+        )
+    }
+}
+
+impl std::fmt::Display for TerminationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Completed => write!(f, "completed"),
+            Self::MaxCycles { limit } => write!(f, "reached maximum of {} cycles", limit),
+            Self::MaxDuration { limit } => write!(f, "reached maximum duration of {:?}", limit),
+            Self::MaxTokens { limit, used } => {
+                write!(f, "used {} tokens (limit {})", used, limit)
+            }
+            Self::MaxToolIterations { limit } => {
+                write!(f, "reached maximum of {} tool iterations", limit)
+            }
+            Self::Condition { name, reason } => write!(f, "stopped by '{}': {}", name, reason),
+            Self::BehaviorAnomaly { anomaly } => {
+                write!(f, "stopped by the behavior monitor: {}", anomaly)
+            }
+            Self::DeadlineExceeded => write!(f, "deadline exceeded"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::Error => write!(f, "failed"),
+        }
+    }
+}
+
+/// State of the execution passed to stop conditions
+#[derive(Debug, Clone)]
+pub struct StopContext {
+    /// Model interaction cycles completed so far
+    pub cycles_completed: u32,
+    /// Time since execution started
+    pub elapsed: Duration,
+    /// Tokens consumed so far (input + output)
+    pub total_tokens: u32,
+    /// Response of the cycle that just completed
+    pub last_response: String,
+}
+
+/// Custom condition checked after each cycle that would otherwise continue
+#[async_trait]
+pub trait StopCondition: Send + Sync + std::fmt::Debug {
+    /// Return `Some(reason)` to stop execution
+    async fn should_stop(&self, ctx: &StopContext) -> Option<String>;
+
+    /// Name recorded in [`TerminationReason::Condition`]
+    fn name(&self) -> &str {
+        "custom"
+    }
+}
+
+/// [`StopCondition`] backed by an async closure
+pub struct FnStopCondition<F> {
+    name: String,
+    predicate: F,
+}
+
+impl<F, Fut> FnStopCondition<F>
+where
+    F: Fn(&StopContext) -> Fut + Send + Sync,
+    Fut: Future<Output = Option<String>> + Send,
+{
+    pub fn new(name: impl Into<String>, predicate: F) -> Self {
+        Self {
+            name: name.into(),
+            predicate,
+        }
+    }
+}
+
+impl<F> std::fmt::Debug for FnStopCondition<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnStopCondition")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<F, Fut> StopCondition for FnStopCondition<F>
+where
+    F: Fn(&StopContext) -> Fut + Send + Sync,
+    Fut: Future<Output = Option<String>> + Send,
+{
+    async fn should_stop(&self, ctx: &StopContext) -> Option<String> {
+        (self.predicate)(ctx).await
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fn_stop_condition() {
+        let condition = FnStopCondition::new("token_guard", |ctx: &StopContext| {
+            let over = ctx.total_tokens > 100;
+            async move { over.then(|| "too many tokens".to_string()) }
+        });
+        let mut ctx = StopContext {
+            cycles_completed: 1,
+            elapsed: Duration::from_secs(1),
+            total_tokens: 50,
+            last_response: String::new(),
+        };
+        assert_eq!(condition.should_stop(&ctx).await, None);
+        ctx.total_tokens = 150;
+        assert_eq!(
+            condition.should_stop(&ctx).await.as_deref(),
+            Some("too many tokens")
+        );
+        assert_eq!(condition.name(), "token_guard");
+    }
+
+    #[test]
+    fn test_budget_exhausted() {
+        assert!(TerminationReason::MaxTokens {
+            limit: 10,
+            used: 12
+        }
+        .is_budget_exhausted());
+        assert!(!TerminationReason::Completed.is_budget_exhausted());
+        assert!(!TerminationReason::Condition {
+            name: "x".to_string(),
+            reason: "y".to_string()
+        }
+        .is_budget_exhausted());
+    }
+}
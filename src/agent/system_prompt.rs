@@ -0,0 +1,333 @@
+//! System prompt composition from multiple sources.
+//!
+//! A [`SystemPromptBuilder`] assembles the system prompt from ordered fragments
+//! instead of a single static string. Fragments are either static text (base
+//! persona, tool usage guidelines) or [`PromptContextProvider`]s evaluated each
+//! time the agent executes (current date, user profile, retrieved documents).
+//!
+//! Each fragment may have its own token limit, and the builder may have an overall
+//! token budget. When the budget is exceeded, optional fragments are dropped in
+//! order of ascending priority until the prompt fits.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use stood::agent::{Agent, PromptContext, PromptContextProvider, PromptFragment, SystemPromptBuilder};
+//! use async_trait::async_trait;
+//! use std::sync::Arc;
+//!
+//! #[derive(Debug)]
+//! struct DateProvider;
+//!
+//! #[async_trait]
+//! impl PromptContextProvider for DateProvider {
+//!     async fn provide(&self, _ctx: &PromptContext) -> stood::Result<Option<String>> {
+//!         Ok(Some(format!("Today is {}.", chrono::Utc::now().date_naive())))
+//!     }
+//! }
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let prompt = SystemPromptBuilder::new()
+//!     .base("You are a helpful travel assistant.")
+//!     .guidelines("Use the search tool before answering questions about prices.")
+//!     .fragment(PromptFragment::dynamic("date", Arc::new(DateProvider)).with_priority(50))
+//!     .with_token_budget(2_000);
+//!
+//! let agent = Agent::builder()
+//!     .with_system_prompt_builder(prompt)
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::context_manager::{truncate_text, TruncationStrategy};
+
+/// Characters per token used for fragment budgets
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Information available to dynamic prompt fragments at execute-time
+#[derive(Debug, Clone)]
+pub struct PromptContext {
+    /// Identifier of the executing agent
+    pub agent_id: String,
+    /// Name of the executing agent (if set)
+    pub agent_name: Option<String>,
+    /// The user prompt being executed
+    pub user_prompt: String,
+    /// Number of messages already in the conversation
+    pub message_count: usize,
+}
+
+/// Source of dynamic system prompt content, evaluated on every execution
+#[async_trait]
+pub trait PromptContextProvider: Send + Sync + std::fmt::Debug {
+    /// Produce the fragment text, or `None` to omit the fragment this time
+    async fn provide(&self, ctx: &PromptContext) -> crate::Result<Option<String>>;
+}
+
+/// Content of a prompt fragment
+#[derive(Debug, Clone)]
+pub enum FragmentSource {
+    /// Fixed text
+    Static(String),
+    /// Text produced at execute-time
+    Dynamic(Arc<dyn PromptContextProvider>),
+}
+
+/// One section of a composed system prompt
+#[derive(Debug, Clone)]
+pub struct PromptFragment {
+    /// Name used in logs and to replace fragments
+    pub name: String,
+    /// Content of the fragment
+    pub source: FragmentSource,
+    /// Position in the prompt; lower values come first
+    pub order: i32,
+    /// Importance when the token budget is exceeded; lower values are dropped first
+    pub priority: i32,
+    /// Required fragments are never dropped to meet the budget
+    pub required: bool,
+    /// Maximum tokens for this fragment; longer text is truncated
+    pub max_tokens: Option<usize>,
+}
+
+impl PromptFragment {
+    /// Create a fragment with fixed text
+    pub fn text(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::with_source(name, FragmentSource::Static(text.into()))
+    }
+
+    /// Create a fragment evaluated at execute-time
+    pub fn dynamic(name: impl Into<String>, provider: Arc<dyn PromptContextProvider>) -> Self {
+        Self::with_source(name, FragmentSource::Dynamic(provider))
+    }
+
+    fn with_source(name: impl Into<String>, source: FragmentSource) -> Self {
+        Self {
+            name: name.into(),
+            source,
+            order: 100,
+            priority: 100,
+            required: false,
+            max_tokens: None,
+        }
+    }
+
+    pub fn with_order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+}
+
+/// Composes a system prompt from ordered fragments with optional token budgets
+#[derive(Debug, Clone, Default)]
+pub struct SystemPromptBuilder {
+    fragments: Vec<PromptFragment>,
+    token_budget: Option<usize>,
+    separator: Option<String>,
+}
+
+impl SystemPromptBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the base persona. Placed first and never dropped.
+    pub fn base(self, text: impl Into<String>) -> Self {
+        self.fragment(PromptFragment::text("base", text).with_order(0).required())
+    }
+
+    /// Set tool usage guidelines, placed after the base persona
+    pub fn guidelines(self, text: impl Into<String>) -> Self {
+        self.fragment(
+            PromptFragment::text("guidelines", text)
+                .with_order(10)
+                .with_priority(200),
+        )
+    }
+
+    /// Add a fragment, replacing any existing fragment with the same name
+    pub fn fragment(mut self, fragment: PromptFragment) -> Self {
+        self.fragments.retain(|f| f.name != fragment.name);
+        self.fragments.push(fragment);
+        self
+    }
+
+    /// Limit the composed prompt to approximately this many tokens
+    pub fn with_token_budget(mut self, max_tokens: usize) -> Self {
+        self.token_budget = Some(max_tokens);
+        self
+    }
+
+    /// Text placed between fragments (default: a blank line)
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = Some(separator.into());
+        self
+    }
+
+    /// Names of the configured fragments in prompt order
+    pub fn fragment_names(&self) -> Vec<&str> {
+        self.ordered().iter().map(|f| f.name.as_str()).collect()
+    }
+
+    fn ordered(&self) -> Vec<&PromptFragment> {
+        let mut fragments: Vec<&PromptFragment> = self.fragments.iter().collect();
+        fragments.sort_by_key(|f| f.order);
+        fragments
+    }
+
+    /// Evaluate all fragments and compose the system prompt.
+    ///
+    /// Returns `None` if no fragment produced any text.
+    pub async fn build(&self, ctx: &PromptContext) -> crate::Result<Option<String>> {
+        let mut resolved: Vec<(&PromptFragment, String)> = Vec::new();
+        for fragment in self.ordered() {
+            let text = match &fragment.source {
+                FragmentSource::Static(text) => Some(text.clone()),
+                FragmentSource::Dynamic(provider) => provider.provide(ctx).await?,
+            };
+            let Some(text) = text.filter(|t| !t.trim().is_empty()) else {
+                continue;
+            };
+            let text = match fragment.max_tokens {
+                Some(max_tokens) => truncate_text(
+                    &text,
+                    max_tokens * CHARS_PER_TOKEN,
+                    TruncationStrategy::Head,
+                ),
+                None => text,
+            };
+            resolved.push((fragment, text));
+        }
+
+        if let Some(budget) = self.token_budget {
+            let max_chars = budget * CHARS_PER_TOKEN;
+            let total = |r: &Vec<(&PromptFragment, String)>| -> usize {
+                r.iter().map(|(_, t)| t.len()).sum()
+            };
+            while total(&resolved) > max_chars {
+                let lowest = resolved
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (f, _))| !f.required)
+                    .min_by_key(|(_, (f, _))| f.priority)
+                    .map(|(i, _)| i);
+                match lowest {
+                    Some(index) => {
+                        let (fragment, _) = resolved.remove(index);
+                        tracing::debug!(
+                            "Dropped system prompt fragment '{}' to fit token budget",
+                            fragment.name
+                        );
+                    }
+                    None => {
+                        tracing::warn!(
+                            "Required system prompt fragments exceed token budget of {}",
+                            budget
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+
+        if resolved.is_empty() {
+            return Ok(None);
+        }
+        let separator = self.separator.as_deref().unwrap_or("\n\n");
+        Ok(Some(
+            resolved
+                .into_iter()
+                .map(|(_, text)| text)
+                .collect::<Vec<_>>()
+                .join(separator),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct EchoProvider;
+
+    #[async_trait]
+    impl PromptContextProvider for EchoProvider {
+        async fn provide(&self, ctx: &PromptContext) -> crate::Result<Option<String>> {
+            Ok(Some(format!("User asked: {}", ctx.user_prompt)))
+        }
+    }
+
+    fn context() -> PromptContext {
+        PromptContext {
+            agent_id: "agent-1".to_string(),
+            agent_name: None,
+            user_prompt: "hello".to_string(),
+            message_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fragments_are_ordered_and_evaluated() {
+        let builder = SystemPromptBuilder::new()
+            .fragment(PromptFragment::dynamic("echo", Arc::new(EchoProvider)))
+            .guidelines("Use tools.")
+            .base("You are helpful.");
+
+        assert_eq!(builder.fragment_names(), vec!["base", "guidelines", "echo"]);
+        let prompt = builder.build(&context()).await.unwrap().unwrap();
+        assert_eq!(
+            prompt,
+            "You are helpful.\n\nUse tools.\n\nUser asked: hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_budget_drops_low_priority_fragments() {
+        let builder = SystemPromptBuilder::new()
+            .base("Base persona.")
+            .fragment(PromptFragment::text("docs", "x".repeat(400)).with_priority(10))
+            .fragment(PromptFragment::text("profile", "Profile info.").with_priority(50))
+            .with_token_budget(20);
+
+        let prompt = builder.build(&context()).await.unwrap().unwrap();
+        assert!(prompt.contains("Base persona."));
+        assert!(prompt.contains("Profile info."));
+        assert!(!prompt.contains("xxx"));
+    }
+
+    #[tokio::test]
+    async fn test_fragment_token_limit_truncates() {
+        let builder = SystemPromptBuilder::new()
+            .fragment(PromptFragment::text("long", "y".repeat(1_000)).with_max_tokens(50));
+
+        let prompt = builder.build(&context()).await.unwrap().unwrap();
+        assert!(prompt.len() <= 200);
+        assert!(SystemPromptBuilder::new()
+            .build(&context())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,191 @@
+//! Scripted model provider for agent tests that must not reach a real model
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::Stream;
+
+use super::config::ExecutionConfig;
+use super::{Agent, AgentConfig};
+use crate::llm::traits::{
+    ChatConfig, ChatResponse, HealthStatus, LlmError, LlmProvider, ProviderCapabilities,
+    ProviderType, StreamEvent, Tool as ToolSpec, ToolCall,
+};
+use crate::tools::Tool;
+use crate::types::Messages;
+
+/// Provider that replays queued responses and records the conversation of
+/// every call
+///
+/// Once the queue is empty it answers "done" without tool calls. Streaming
+/// calls replay the same responses as legacy stream events, with each tool
+/// call's input complete in its `ToolCallStart`.
+#[derive(Debug, Default)]
+pub(crate) struct ScriptedProvider {
+    responses: Mutex<VecDeque<ChatResponse>>,
+    requests: Mutex<Vec<Messages>>,
+}
+
+impl ScriptedProvider {
+    pub(crate) fn new(responses: impl IntoIterator<Item = ChatResponse>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into_iter().collect()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The conversation sent with each call so far
+    pub(crate) fn requests(&self) -> Vec<Messages> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn next(&self, messages: &Messages) -> ChatResponse {
+        self.requests.lock().unwrap().push(messages.clone());
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| text("done"))
+    }
+}
+
+/// A final answer
+pub(crate) fn text(content: &str) -> ChatResponse {
+    ChatResponse {
+        content: content.to_string(),
+        tool_calls: Vec::new(),
+        thinking: None,
+        usage: None,
+        metadata: HashMap::new(),
+    }
+}
+
+/// A response asking for one tool call
+pub(crate) fn tool_call(id: &str, name: &str, input: serde_json::Value) -> ChatResponse {
+    ChatResponse {
+        tool_calls: vec![ToolCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            input,
+        }],
+        ..text("")
+    }
+}
+
+/// Agent on `provider` with the given tools
+pub(crate) async fn agent(
+    provider: Arc<ScriptedProvider>,
+    tools: Vec<Box<dyn Tool>>,
+    execution_config: ExecutionConfig,
+) -> Agent {
+    agent_with_config(provider, AgentConfig::default(), tools, execution_config).await
+}
+
+/// Agent on `provider` with `config`, e.g. to set its system prompt or personas
+///
+/// `config` keeps its provider type and model id, but every call goes to
+/// `provider`.
+pub(crate) async fn agent_with_config(
+    provider: Arc<ScriptedProvider>,
+    config: AgentConfig,
+    tools: Vec<Box<dyn Tool>>,
+    execution_config: ExecutionConfig,
+) -> Agent {
+    Agent::build_internal(
+        provider,
+        Box::new(crate::llm::models::Bedrock::ClaudeHaiku45),
+        config,
+        tools,
+        vec![],
+        execution_config,
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+#[async_trait]
+impl LlmProvider for ScriptedProvider {
+    async fn chat(
+        &self,
+        _model_id: &str,
+        messages: &Messages,
+        _config: &ChatConfig,
+    ) -> Result<ChatResponse, LlmError> {
+        Ok(self.next(messages))
+    }
+
+    async fn chat_with_tools(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        _tools: &[ToolSpec],
+        config: &ChatConfig,
+    ) -> Result<ChatResponse, LlmError> {
+        self.chat(model_id, messages, config).await
+    }
+
+    async fn chat_streaming(
+        &self,
+        _model_id: &str,
+        messages: &Messages,
+        _config: &ChatConfig,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        let response = self.next(messages);
+        let mut events = Vec::new();
+        if !response.content.is_empty() {
+            events.push(StreamEvent::ContentDelta {
+                delta: response.content,
+                index: 0,
+            });
+        }
+        for tool_call in response.tool_calls {
+            events.push(StreamEvent::ToolCallStart { tool_call });
+        }
+        events.push(StreamEvent::Done {
+            usage: response.usage,
+        });
+        Ok(Box::new(futures::stream::iter(events)))
+    }
+
+    async fn chat_streaming_with_tools(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        _tools: &[ToolSpec],
+        config: &ChatConfig,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        self.chat_streaming(model_id, messages, config).await
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, LlmError> {
+        unimplemented!()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_streaming: true,
+            supports_tools: true,
+            supports_thinking: false,
+            supports_vision: false,
+            supports_prompt_caching: false,
+            supports_tool_caching: false,
+            max_tokens: None,
+            available_models: Vec::new(),
+        }
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::LmStudio
+    }
+
+    fn supported_models(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -0,0 +1,328 @@
+//! Attribution of token usage to the parts of each model request.
+//!
+//! Providers only report how many input and output tokens a model call used.
+//! [`TokenAttribution`] breaks that number down into the system prompt, tool
+//! schemas, tool results and user/assistant turns, per cycle and per tool, so you
+//! can see which tools bloat the context.
+//!
+//! The split is estimated from the size of each part of the request and scaled so
+//! that the parts add up to the input tokens the provider reported (including
+//! prompt cache reads and writes). Every model call resends the whole
+//! conversation, so a large tool result is counted again on each later call,
+//! which is exactly what it costs.
+//!
+//! ```no_run
+//! # use stood::agent::Agent;
+//! # async fn example(mut agent: Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let result = agent.execute("Analyze the repository").await?;
+//! if let Some(attribution) = &result.execution.token_attribution {
+//!     println!("Tool schemas: {} tokens", attribution.total.tool_schemas);
+//!     for (tool, usage) in attribution.tools_by_cost().iter().take(3) {
+//!         println!("{}: {} tokens over {} calls", tool, usage.total(), usage.calls);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::llm::traits::Usage;
+use crate::types::{ContentBlock, Messages, ToolResultContent};
+
+/// Characters per token used to estimate the size of each request part
+const CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Tokens attributed to each kind of content
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenBreakdown {
+    /// System prompt
+    pub system_prompt: u32,
+    /// Tool names, descriptions and input schemas sent with the request
+    pub tool_schemas: u32,
+    /// Tool results in the conversation
+    pub tool_results: u32,
+    /// User messages (excluding tool results)
+    pub user_turns: u32,
+    /// Assistant messages, including tool call requests
+    pub assistant_turns: u32,
+    /// Tokens generated by the model
+    pub output: u32,
+}
+
+impl TokenBreakdown {
+    /// Input tokens across all parts of the request
+    pub fn input(&self) -> u32 {
+        self.system_prompt
+            + self.tool_schemas
+            + self.tool_results
+            + self.user_turns
+            + self.assistant_turns
+    }
+
+    /// Input and output tokens
+    pub fn total(&self) -> u32 {
+        self.input() + self.output
+    }
+
+    fn add(&mut self, other: &TokenBreakdown) {
+        self.system_prompt += other.system_prompt;
+        self.tool_schemas += other.tool_schemas;
+        self.tool_results += other.tool_results;
+        self.user_turns += other.user_turns;
+        self.assistant_turns += other.assistant_turns;
+        self.output += other.output;
+    }
+}
+
+/// Tokens attributed to one tool
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolTokenUsage {
+    /// Tokens spent sending the tool's schema
+    pub schema: u32,
+    /// Tokens spent sending the tool's results
+    pub results: u32,
+    /// Number of times the tool was called
+    pub calls: u32,
+}
+
+impl ToolTokenUsage {
+    /// Schema and result tokens
+    pub fn total(&self) -> u32 {
+        self.schema + self.results
+    }
+}
+
+/// Token breakdown for one cycle
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CycleTokenAttribution {
+    /// Cycle number, starting at 1
+    pub cycle: u32,
+    /// Model calls made during the cycle
+    pub model_calls: u32,
+    /// Tokens attributed during the cycle
+    pub breakdown: TokenBreakdown,
+}
+
+/// Token usage of an execution broken down by content, cycle and tool
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenAttribution {
+    /// Totals across all model calls
+    pub total: TokenBreakdown,
+    /// Breakdown for each cycle
+    pub per_cycle: Vec<CycleTokenAttribution>,
+    /// Breakdown for each tool, by tool name
+    pub per_tool: BTreeMap<String, ToolTokenUsage>,
+}
+
+impl TokenAttribution {
+    /// Tools ordered by attributed tokens, most expensive first
+    pub fn tools_by_cost(&self) -> Vec<(&str, &ToolTokenUsage)> {
+        let mut tools: Vec<_> = self
+            .per_tool
+            .iter()
+            .map(|(name, usage)| (name.as_str(), usage))
+            .collect();
+        tools.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.total()));
+        tools
+    }
+
+    /// Attribute one model call made during `cycle`
+    pub(crate) fn record(&mut self, cycle: u32, request: &RequestEstimate, usage: Option<&Usage>) {
+        let scale = match usage {
+            Some(usage) if request.total() > 0.0 => {
+                let reported = usage.input_tokens
+                    + usage.cache_read_tokens.unwrap_or(0)
+                    + usage.cache_write_tokens.unwrap_or(0);
+                reported as f64 / request.total()
+            }
+            _ => 1.0,
+        };
+        let scaled = |estimate: f64| (estimate * scale).round() as u32;
+
+        let breakdown = TokenBreakdown {
+            system_prompt: scaled(request.system_prompt),
+            tool_schemas: scaled(request.tool_schemas.values().sum()),
+            tool_results: scaled(request.tool_results.values().sum()),
+            user_turns: scaled(request.user_turns),
+            assistant_turns: scaled(request.assistant_turns),
+            output: usage.map(|u| u.output_tokens).unwrap_or(0),
+        };
+        self.total.add(&breakdown);
+
+        match self.per_cycle.last_mut() {
+            Some(last) if last.cycle == cycle => {
+                last.model_calls += 1;
+                last.breakdown.add(&breakdown);
+            }
+            _ => self.per_cycle.push(CycleTokenAttribution {
+                cycle,
+                model_calls: 1,
+                breakdown,
+            }),
+        }
+
+        for (tool, estimate) in &request.tool_schemas {
+            self.per_tool.entry(tool.clone()).or_default().schema += scaled(*estimate);
+        }
+        for (tool, estimate) in &request.tool_results {
+            self.per_tool.entry(tool.clone()).or_default().results += scaled(*estimate);
+        }
+    }
+
+    /// Count calls to tools requested by a model response
+    pub(crate) fn record_tool_calls<'a>(&mut self, tools: impl IntoIterator<Item = &'a str>) {
+        for tool in tools {
+            self.per_tool.entry(tool.to_string()).or_default().calls += 1;
+        }
+    }
+}
+
+/// Estimated size in tokens of each part of a model request
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestEstimate {
+    system_prompt: f64,
+    tool_schemas: HashMap<String, f64>,
+    tool_results: HashMap<String, f64>,
+    user_turns: f64,
+    assistant_turns: f64,
+}
+
+impl RequestEstimate {
+    /// Estimate a request sending `system_prompt` and `messages` with `tools`
+    pub(crate) fn new(
+        system_prompt: Option<&str>,
+        messages: &Messages,
+        tools: &[crate::types::tools::Tool],
+    ) -> Self {
+        let mut estimate = Self {
+            system_prompt: system_prompt.map_or(0.0, estimate_tokens),
+            ..Self::default()
+        };
+
+        for tool in tools {
+            let spec = &tool.tool_spec;
+            let size = estimate_tokens(&spec.name)
+                + estimate_tokens(&spec.description)
+                + estimate_tokens(&spec.input_schema.to_string());
+            estimate.tool_schemas.insert(spec.name.clone(), size);
+        }
+
+        // Results only carry the tool_use_id, so map ids back to tool names
+        let mut tool_names: HashMap<&str, &str> = HashMap::new();
+        for message in &messages.messages {
+            for block in &message.content {
+                if let ContentBlock::ToolUse { id, name, .. } = block {
+                    tool_names.insert(id, name);
+                }
+            }
+        }
+
+        for message in &messages.messages {
+            let is_user = message.role == crate::types::MessageRole::User;
+            for block in &message.content {
+                let size = match block {
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        ..
+                    } => {
+                        let name = tool_names.get(tool_use_id.as_str()).copied();
+                        *estimate
+                            .tool_results
+                            .entry(name.unwrap_or("unknown").to_string())
+                            .or_default() += result_tokens(content);
+                        continue;
+                    }
+                    ContentBlock::Text { text } => estimate_tokens(text),
+                    ContentBlock::ToolUse { name, input, .. } => {
+                        estimate_tokens(name) + estimate_tokens(&input.to_string())
+                    }
+                    ContentBlock::Thinking { content, .. } => estimate_tokens(content),
+                    ContentBlock::ReasoningContent { reasoning } => {
+                        estimate_tokens(reasoning.text())
+                    }
+                };
+                if is_user {
+                    estimate.user_turns += size;
+                } else {
+                    estimate.assistant_turns += size;
+                }
+            }
+        }
+        estimate
+    }
+
+    fn total(&self) -> f64 {
+        self.system_prompt
+            + self.tool_schemas.values().sum::<f64>()
+            + self.tool_results.values().sum::<f64>()
+            + self.user_turns
+            + self.assistant_turns
+    }
+}
+
+fn estimate_tokens(text: &str) -> f64 {
+    text.len() as f64 / CHARS_PER_TOKEN
+}
+
+fn result_tokens(content: &ToolResultContent) -> f64 {
+    match content {
+        ToolResultContent::Text { text } => estimate_tokens(text),
+        ToolResultContent::Json { data } => estimate_tokens(&data.to_string()),
+        ToolResultContent::Binary { data, .. } => estimate_tokens(data),
+        ToolResultContent::Multiple { blocks } => blocks.iter().map(result_tokens).sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tools::{Tool, ToolSpec};
+    use crate::types::Message;
+
+    #[test]
+    fn test_attribution_scales_to_reported_usage() {
+        let system_prompt = "s".repeat(400);
+        let mut messages = Messages::new();
+        messages.push(Message::user("u".repeat(400)));
+        messages.push(Message::new(
+            crate::types::MessageRole::Assistant,
+            vec![ContentBlock::tool_use(
+                "t1",
+                "search",
+                serde_json::json!({}),
+            )],
+        ));
+        messages.push(Message::new(
+            crate::types::MessageRole::User,
+            vec![ContentBlock::tool_result_success(
+                "t1",
+                ToolResultContent::text("r".repeat(1200)),
+            )],
+        ));
+        let tools = vec![Tool::new(ToolSpec {
+            name: "search".to_string(),
+            description: "d".repeat(100),
+            input_schema: serde_json::json!({}),
+            metadata: Default::default(),
+        })];
+
+        let request = RequestEstimate::new(Some(&system_prompt), &messages, &tools);
+        let raw = request.total();
+        let mut attribution = TokenAttribution::default();
+        attribution.record(1, &request, Some(&Usage::new((raw * 2.0) as u32, 50)));
+        attribution.record(1, &request, None);
+        attribution.record_tool_calls(["search"]);
+
+        // Tool results are the largest part of the request
+        let search = &attribution.per_tool["search"];
+        assert_eq!(search.calls, 1);
+        assert!(search.results > attribution.total.user_turns);
+        assert_eq!(attribution.total.system_prompt, 200 + 100);
+        assert_eq!(attribution.total.output, 50);
+        assert_eq!(attribution.per_cycle.len(), 1);
+        assert_eq!(attribution.per_cycle[0].model_calls, 2);
+        assert_eq!(attribution.tools_by_cost()[0].0, "search");
+    }
+}
@@ -0,0 +1,363 @@
+//! Triage reports for failed executions.
+//!
+//! When an execution fails, the cause is usually spread over several parts of
+//! the [`AgentResult`]: the error, the retry history, the failed tool calls and
+//! the context recovery recorded in the execution trace.
+//! [`AgentResult::triage_report`] collects them into a [`TriageReport`] that
+//! renders as plain text and suggests remediations, such as raising
+//! `max_tokens` or fixing a tool whose schema the model keeps getting wrong.
+//!
+//! ```no_run
+//! # use stood::agent::Agent;
+//! # async fn example(mut agent: Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let result = agent.execute("Reconcile the invoices").await?;
+//! if let Some(report) = result.triage_report() {
+//!     eprintln!("{}", report);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Context recovery actions are only listed when execution tracing is enabled
+//! ([`AgentBuilder::with_execution_trace`](crate::agent::AgentBuilder::with_execution_trace)).
+
+use std::fmt;
+
+use crate::agent::execution_trace::{RecoveryAction, TraceEvent};
+use crate::agent::result::{AgentResult, FailedToolCall};
+use crate::agent::stop::TerminationReason;
+use crate::error_recovery::{RetryLayer, RetrySummary};
+
+/// Summary of why an execution failed, with suggested remediations
+#[derive(Debug, Clone)]
+pub struct TriageReport {
+    /// Why execution stopped
+    pub termination_reason: TerminationReason,
+    /// Error the execution failed with
+    pub error: Option<String>,
+    /// Last error returned by the model provider
+    pub last_model_error: Option<String>,
+    /// Failed attempts across retry layers
+    pub retries: RetrySummary,
+    /// Failed tool calls, with the input the model passed
+    pub failed_tools: Vec<FailedToolCall>,
+    /// Repairs the event loop made to the conversation or the response
+    pub recovery_actions: Vec<TriageRecovery>,
+    /// Summary of the messages summarized, dropped or truncated by context management
+    pub context_changes: Option<String>,
+    /// Suggested fixes, most specific first
+    pub remediations: Vec<String>,
+}
+
+/// A context recovery action taken during the execution
+#[derive(Debug, Clone)]
+pub struct TriageRecovery {
+    /// Event loop cycle of the action (1-based)
+    pub cycle: u32,
+    /// What was done
+    pub action: RecoveryAction,
+    /// Details such as the error that triggered the recovery
+    pub detail: String,
+}
+
+impl AgentResult {
+    /// Compile a [`TriageReport`] if the execution failed or stopped before
+    /// completing the task
+    pub fn triage_report(&self) -> Option<TriageReport> {
+        if self.success && matches!(self.termination_reason, TerminationReason::Completed) {
+            return None;
+        }
+
+        let retries = self.execution.retries.clone();
+        let last_model_error = retries
+            .attempts
+            .iter()
+            .rev()
+            .find(|attempt| attempt.layer == RetryLayer::Provider)
+            .map(|attempt| attempt.error.clone());
+        let recovery_actions = self
+            .execution_trace
+            .iter()
+            .flat_map(|trace| &trace.entries)
+            .filter_map(|entry| match &entry.event {
+                TraceEvent::ContextRecovery { action, detail } => Some(TriageRecovery {
+                    cycle: entry.cycle,
+                    action: *action,
+                    detail: detail.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
+        let context_changes = self
+            .execution
+            .conversation_diff
+            .as_ref()
+            .filter(|diff| !diff.removed.is_empty())
+            .map(|diff| diff.summary());
+
+        let mut report = TriageReport {
+            termination_reason: self.termination_reason.clone(),
+            error: self.error.clone(),
+            last_model_error,
+            retries,
+            failed_tools: self.tool_call_summary.failed_calls.clone(),
+            recovery_actions,
+            context_changes,
+            remediations: Vec::new(),
+        };
+        report.remediations = suggest_remediations(&report);
+        Some(report)
+    }
+}
+
+/// Remediations for the failures in `report`, without duplicates
+fn suggest_remediations(report: &TriageReport) -> Vec<String> {
+    let mut remediations = Vec::new();
+    let mut suggest = |remediation: String| {
+        if !remediations.contains(&remediation) {
+            remediations.push(remediation);
+        }
+    };
+
+    let model_errors: Vec<String> = report
+        .error
+        .iter()
+        .chain(&report.last_model_error)
+        .chain(report.retries.attempts.iter().map(|attempt| &attempt.error))
+        .map(|error| error.to_lowercase())
+        .collect();
+    let any_error = |needles: &[&str]| {
+        model_errors
+            .iter()
+            .any(|error| needles.iter().any(|needle| error.contains(needle)))
+    };
+
+    if any_error(&["max_tokens", "max tokens", "maximum tokens"]) {
+        suggest("Increase max_tokens so the model's response is not cut off".to_string());
+    }
+    if any_error(&[
+        "context window",
+        "context length",
+        "too long",
+        "too many tokens",
+    ]) {
+        suggest(
+            "Enable context compaction (with_context_compaction) or shorten the conversation"
+                .to_string(),
+        );
+    }
+    if any_error(&["throttl", "rate limit", "too many requests"])
+        || report
+            .retries
+            .attempts
+            .iter()
+            .any(|attempt| attempt.classification.contains("Throttl"))
+    {
+        suggest("Lower the request rate or request a higher provider quota".to_string());
+    }
+    if any_error(&[
+        "access denied",
+        "accessdenied",
+        "credential",
+        "unauthorized",
+    ]) {
+        suggest("Check the provider credentials and access to the model".to_string());
+    }
+    if report.retries.circuit_breaker_trips > 0 {
+        suggest(
+            "A circuit breaker opened after repeated failures; check the provider's status"
+                .to_string(),
+        );
+    }
+
+    for call in &report.failed_tools {
+        let error = call.error_message.to_lowercase();
+        let name = &call.tool_name;
+        if error.contains("not found") || error.contains("not available") {
+            suggest(format!(
+                "Tool {} is not available; register it or stop referring to it in the prompt",
+                name
+            ));
+        } else if ["invalid param", "schema", "missing", "required", "expected"]
+            .iter()
+            .any(|needle| error.contains(needle))
+        {
+            suggest(format!(
+                "Tool {} schema mismatch; compare the model's input with its parameters schema \
+                 and description",
+                name
+            ));
+        } else if error.contains("timeout") || error.contains("timed out") {
+            suggest(format!(
+                "Tool {} timed out; increase its timeout or make it faster",
+                name
+            ));
+        }
+    }
+
+    match &report.termination_reason {
+        TerminationReason::MaxCycles { limit } => suggest(format!(
+            "Raise the cycle limit of {} (with_max_cycles) or split the task",
+            limit
+        )),
+        TerminationReason::MaxDuration { limit } => suggest(format!(
+            "Raise the duration limit of {:?} (with_max_duration)",
+            limit
+        )),
+        TerminationReason::MaxTokens { limit, .. } => suggest(format!(
+            "Raise the token budget of {} (with_max_total_tokens)",
+            limit
+        )),
+        TerminationReason::MaxToolIterations { limit } => suggest(format!(
+            "Raise the tool iteration limit of {}, or check whether the model is stuck \
+             calling the same tool",
+            limit
+        )),
+        TerminationReason::DeadlineExceeded => suggest(
+            "Allow more time before the deadline (ExecuteOptions::with_deadline) or split \
+             the task so each request does less"
+                .to_string(),
+        ),
+        TerminationReason::BehaviorAnomaly { anomaly } => suggest(format!(
+            "Check the conversation for a runaway loop or injected instructions before \
+             resetting the behavior monitor ({})",
+            anomaly.kind
+        )),
+        _ => {}
+    }
+
+    remediations
+}
+
+impl fmt::Display for TriageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Execution stopped: {}", self.termination_reason)?;
+        if let Some(error) = &self.error {
+            writeln!(f, "Error: {}", error)?;
+        }
+        if let Some(error) = &self.last_model_error {
+            writeln!(f, "Last model error: {}", error)?;
+        }
+
+        if !self.retries.is_empty() {
+            writeln!(
+                f,
+                "\nRetries: {} retries, {:?} backing off, {} circuit breaker trips",
+                self.retries.retries(),
+                self.retries.total_backoff,
+                self.retries.circuit_breaker_trips
+            )?;
+            for attempt in &self.retries.attempts {
+                writeln!(
+                    f,
+                    "  - {:?} attempt {}: {}: {}",
+                    attempt.layer, attempt.attempt, attempt.classification, attempt.error
+                )?;
+            }
+        }
+
+        if !self.failed_tools.is_empty() {
+            writeln!(f, "\nFailed tools:")?;
+            for call in &self.failed_tools {
+                writeln!(
+                    f,
+                    "  - {} ({}) after {:?}: {}",
+                    call.tool_name, call.tool_use_id, call.duration, call.error_message
+                )?;
+                if let Some(input) = &call.input {
+                    writeln!(f, "    input: {}", input)?;
+                }
+            }
+        }
+
+        if !self.recovery_actions.is_empty() || self.context_changes.is_some() {
+            writeln!(f, "\nContext recovery:")?;
+            for recovery in &self.recovery_actions {
+                writeln!(
+                    f,
+                    "  - cycle {}: {:?}: {}",
+                    recovery.cycle, recovery.action, recovery.detail
+                )?;
+            }
+            if let Some(changes) = &self.context_changes {
+                writeln!(f, "  - conversation: {}", changes)?;
+            }
+        }
+
+        if !self.remediations.is_empty() {
+            writeln!(f, "\nSuggested remediations:")?;
+            for remediation in &self.remediations {
+                writeln!(f, "  - {}", remediation)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::execution_trace::ExecutionTrace;
+    use crate::error_recovery::RetryAttempt;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn test_successful_execution_has_no_report() {
+        let result = AgentResult::simple_success("done".to_string(), Duration::ZERO);
+        assert!(result.triage_report().is_none());
+    }
+
+    #[test]
+    fn test_report_collects_failures_and_remediations() {
+        let mut result = AgentResult::error(
+            "Model error: response stopped at max_tokens".to_string(),
+            Duration::from_secs(3),
+        );
+        result.execution.retries.attempts.push(RetryAttempt {
+            layer: RetryLayer::Provider,
+            attempt: 1,
+            classification: "ThrottlingError".to_string(),
+            error: "Too many requests".to_string(),
+            backoff: Some(Duration::from_secs(1)),
+            elapsed: Duration::from_millis(200),
+        });
+        result.tool_call_summary.failed_calls.push(FailedToolCall {
+            tool_name: "search".to_string(),
+            tool_use_id: "tool_1".to_string(),
+            error_message: "Invalid parameters: missing field `query`".to_string(),
+            duration: Duration::from_millis(5),
+            input: Some(json!({"q": "invoices"})),
+        });
+        let mut trace = ExecutionTrace::new();
+        trace.record(
+            2,
+            TraceEvent::ContextRecovery {
+                action: RecoveryAction::ToolFailureFallback,
+                detail: "search failed".to_string(),
+            },
+        );
+        result.execution_trace = Some(trace);
+
+        let report = result.triage_report().unwrap();
+        assert_eq!(
+            report.last_model_error.as_deref(),
+            Some("Too many requests")
+        );
+        assert_eq!(report.recovery_actions.len(), 1);
+        assert_eq!(
+            report.remediations,
+            [
+                "Increase max_tokens so the model's response is not cut off",
+                "Lower the request rate or request a higher provider quota",
+                "Tool search schema mismatch; compare the model's input with its parameters \
+                 schema and description",
+            ]
+        );
+
+        let text = report.to_string();
+        assert!(text.contains("Execution stopped: "));
+        assert!(text.contains("input: {\"q\":\"invoices\"}"));
+        assert!(text.contains("cycle 2: ToolFailureFallback: search failed"));
+    }
+}
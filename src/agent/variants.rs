@@ -0,0 +1,246 @@
+//! Several alternative responses to one prompt, for "regenerate" pickers.
+//!
+//! [`Agent::execute_variants`](crate::agent::Agent::execute_variants) runs the
+//! prompt as N parallel executions spread over a range of temperatures,
+//! drops near-duplicate responses and scores how much each remaining variant
+//! differs from the others. Unlike best-of-N sampling nothing is selected and
+//! the agent's conversation is left alone until the user picks a variant with
+//! [`Agent::choose_variant`](crate::agent::Agent::choose_variant).
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut agent = Agent::builder().build().await?;
+//! let variants = agent.execute_variants("Suggest a name for a coffee shop", 3).await?;
+//!
+//! for variant in &variants.variants {
+//!     println!("[{:.2}] {}", variant.diversity, variant.response());
+//! }
+//! // The user picked the second one
+//! agent.choose_variant(&variants.variants[1]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Providers don't accept sampling seeds, so variety comes from temperature
+//! alone. Diversity and duplicates are judged by word overlap, the same
+//! measure the best-of-N confidence proxy uses.
+
+use crate::agent::best_of::{jaccard, word_set};
+use crate::agent::conversation::ConversationManager;
+use crate::agent::result::AgentResult;
+
+/// Settings for [`Agent::execute_variants_with`](crate::agent::Agent::execute_variants_with)
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantsConfig {
+    /// Number of executions to run
+    pub n: usize,
+    /// Temperature of the first execution
+    pub min_temperature: f32,
+    /// Temperature of the last execution
+    pub max_temperature: f32,
+    /// Word overlap (0-1) at or above which a response is a duplicate of an
+    /// earlier one
+    pub duplicate_threshold: f64,
+}
+
+impl Default for VariantsConfig {
+    fn default() -> Self {
+        Self {
+            n: 3,
+            min_temperature: 0.4,
+            max_temperature: 1.0,
+            duplicate_threshold: 0.9,
+        }
+    }
+}
+
+impl VariantsConfig {
+    /// Run `n` executions with the default temperature range
+    pub fn new(n: usize) -> Self {
+        Self {
+            n: n.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Spread temperatures evenly from `min` to `max`, both within 0.0-1.0
+    pub fn with_temperature_range(mut self, min: f32, max: f32) -> Self {
+        self.min_temperature = min.clamp(0.0, 1.0);
+        self.max_temperature = max.clamp(self.min_temperature, 1.0);
+        self
+    }
+
+    /// Treat responses overlapping at least this much as duplicates
+    ///
+    /// `1.0` only drops responses with exactly the same words.
+    pub fn with_duplicate_threshold(mut self, threshold: f64) -> Self {
+        self.duplicate_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Temperature of each execution
+    pub(crate) fn temperatures(&self) -> Vec<f32> {
+        if self.n <= 1 {
+            return vec![(self.min_temperature + self.max_temperature) / 2.0];
+        }
+        let step = (self.max_temperature - self.min_temperature) / (self.n - 1) as f32;
+        (0..self.n)
+            .map(|i| self.min_temperature + step * i as f32)
+            .collect()
+    }
+}
+
+/// One distinct response
+#[derive(Debug, Clone)]
+pub struct Variant {
+    /// Position of the execution that produced this variant
+    pub index: usize,
+    /// Temperature the execution ran with
+    pub temperature: f32,
+    /// Execution result
+    pub result: AgentResult,
+    /// How much this response differs from the closest other variant, from 0
+    /// (same words) to 1 (no words in common); 0 when it is the only variant
+    pub diversity: f64,
+    /// Conversation after the execution, adopted by `choose_variant`
+    pub(crate) conversation: ConversationManager,
+}
+
+impl Variant {
+    /// Response text
+    pub fn response(&self) -> &str {
+        &self.result.response
+    }
+}
+
+/// Result of [`Agent::execute_variants`](crate::agent::Agent::execute_variants)
+#[derive(Debug, Clone)]
+pub struct VariantsResult {
+    /// Distinct responses in execution order
+    pub variants: Vec<Variant>,
+    /// Number of responses dropped as duplicates of an earlier one
+    pub duplicates: usize,
+    /// Error messages of executions that failed
+    pub failures: Vec<String>,
+}
+
+impl VariantsResult {
+    /// Response text of every variant
+    pub fn responses(&self) -> Vec<&str> {
+        self.variants.iter().map(Variant::response).collect()
+    }
+
+    /// Average difference between pairs of variants, from 0 to 1
+    pub fn diversity(&self) -> f64 {
+        let words: Vec<_> = self
+            .variants
+            .iter()
+            .map(|v| word_set(v.response()))
+            .collect();
+        let mut total = 0.0;
+        let mut pairs = 0;
+        for (i, a) in words.iter().enumerate() {
+            for b in &words[i + 1..] {
+                total += 1.0 - jaccard(a, b);
+                pairs += 1;
+            }
+        }
+        if pairs == 0 {
+            0.0
+        } else {
+            total / pairs as f64
+        }
+    }
+}
+
+/// Drop duplicates of earlier variants and score the rest
+///
+/// Returns the kept variants and the number dropped.
+pub(crate) fn dedup_and_score(runs: Vec<Variant>, threshold: f64) -> (Vec<Variant>, usize) {
+    let mut kept: Vec<Variant> = Vec::with_capacity(runs.len());
+    let mut words = Vec::with_capacity(runs.len());
+    let mut duplicates = 0;
+    for variant in runs {
+        let variant_words = word_set(variant.response());
+        if words
+            .iter()
+            .any(|earlier| jaccard(&variant_words, earlier) >= threshold)
+        {
+            duplicates += 1;
+            continue;
+        }
+        words.push(variant_words);
+        kept.push(variant);
+    }
+
+    for (i, variant) in kept.iter_mut().enumerate() {
+        variant.diversity = words
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, other)| 1.0 - jaccard(&words[i], other))
+            .min_by(f64::total_cmp)
+            .unwrap_or(0.0);
+    }
+    (kept, duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(index: usize, response: &str) -> Variant {
+        Variant {
+            index,
+            temperature: 0.5,
+            result: AgentResult {
+                response: response.to_string(),
+                ..AgentResult::default()
+            },
+            diversity: 0.0,
+            conversation: ConversationManager::new(),
+        }
+    }
+
+    #[test]
+    fn test_temperatures() {
+        let config = VariantsConfig::new(3).with_temperature_range(0.2, 0.8);
+        let temperatures = config.temperatures();
+        assert_eq!(temperatures.len(), 3);
+        assert!((temperatures[0] - 0.2).abs() < 1e-6);
+        assert!((temperatures[1] - 0.5).abs() < 1e-6);
+        assert!((temperatures[2] - 0.8).abs() < 1e-6);
+        let single = VariantsConfig::new(0).temperatures();
+        assert_eq!(single.len(), 1);
+        assert!((single[0] - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dedup_and_score() {
+        let runs = vec![
+            variant(0, "Bean There, a cozy corner cafe"),
+            variant(1, "bean there: a cozy corner cafe!"),
+            variant(2, "The Daily Grind"),
+            variant(3, "Brew Haven, a cozy cafe"),
+        ];
+        let (kept, duplicates) = dedup_and_score(runs, 0.9);
+        assert_eq!(duplicates, 1);
+        assert_eq!(
+            kept.iter().map(|v| v.index).collect::<Vec<_>>(),
+            vec![0, 2, 3]
+        );
+        // "The Daily Grind" shares no words with the others
+        assert_eq!(kept[1].diversity, 1.0);
+        assert!(kept[0].diversity < 1.0);
+
+        let result = VariantsResult {
+            variants: kept,
+            duplicates,
+            failures: Vec::new(),
+        };
+        assert_eq!(result.responses().len(), 3);
+        assert!(result.diversity() > 0.5 && result.diversity() < 1.0);
+    }
+}
@@ -0,0 +1,89 @@
+//! Spoken prompts and responses.
+//!
+//! [`Agent::execute_from_audio`] transcribes a recording with the agent's
+//! [`Transcriber`](crate::audio::Transcriber) and executes the text like
+//! [`Agent::execute`]. [`Agent::respond_as_audio`] speaks the last response
+//! with the agent's [`Synthesizer`](crate::audio::Synthesizer), after removing
+//! Markdown syntax that would otherwise be read aloud.
+//!
+//! ```no_run
+//! # async fn example(mut agent: stood::agent::Agent, recording: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+//! let result = agent.execute_from_audio(recording).await?;
+//! println!("Heard a question, answered: {}", result.response);
+//!
+//! let speech = agent.respond_as_audio().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! See [`crate::audio`] for the speech services.
+
+use super::response_processor::{ResponseProcessor, StripMarkdown};
+use super::{Agent, AgentResult};
+use crate::audio::Audio;
+use crate::{Result, StoodError};
+
+impl Agent {
+    /// Transcribe `audio` and execute the text as the prompt
+    ///
+    /// The transcript is the user message added to the conversation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoodError::ConfigurationError`] if the agent has no
+    /// transcriber, [`StoodError::InvalidInput`] if the recording is empty or
+    /// contains no speech, and the errors of [`execute`](Self::execute).
+    pub async fn execute_from_audio(&mut self, audio: impl Into<Audio>) -> Result<AgentResult> {
+        let transcriber = self.config.transcriber.clone().ok_or_else(|| {
+            StoodError::configuration_error(
+                "execute_from_audio requires a transcriber (AgentBuilder::with_transcriber)",
+            )
+        })?;
+        let audio = audio.into();
+        if audio.is_empty() {
+            return Err(StoodError::invalid_input("The audio is empty"));
+        }
+
+        let transcript = transcriber.transcribe(&audio).await?;
+        let transcript = transcript.trim();
+        if transcript.is_empty() {
+            return Err(StoodError::invalid_input(
+                "No speech was recognized in the audio",
+            ));
+        }
+        tracing::debug!(
+            "🎙️ Transcribed {} bytes of audio with {}: {} chars",
+            audio.data.len(),
+            transcriber.name(),
+            transcript.len()
+        );
+        self.execute(transcript).await
+    }
+
+    /// Speak the last response in the conversation
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoodError::ConfigurationError`] if the agent has no
+    /// synthesizer and [`StoodError::ConversationError`] if there is no
+    /// response with text to speak.
+    pub async fn respond_as_audio(&self) -> Result<Audio> {
+        let synthesizer = self.config.synthesizer.as_ref().ok_or_else(|| {
+            StoodError::configuration_error(
+                "respond_as_audio requires a synthesizer (AgentBuilder::with_synthesizer)",
+            )
+        })?;
+        let response = self
+            .conversation
+            .last_assistant_message()
+            .and_then(|message| message.text())
+            .unwrap_or_default();
+        let text = StripMarkdown.process(&response).await?;
+        if text.trim().is_empty() {
+            return Err(StoodError::conversation_error(
+                "There is no response to speak",
+            ));
+        }
+        Ok(synthesizer.synthesize(&text).await?)
+    }
+}
@@ -0,0 +1,457 @@
+//! Amazon Transcribe and Amazon Polly (requires the `aws-audio` feature).
+//!
+//! - **[`TranscribeTranscriber`]** - Uploads the recording to an S3 staging
+//!   location, runs a batch transcription job writing its transcript next to
+//!   it and deletes both
+//! - **[`PollySynthesizer`]** - Synthesizes speech with a Polly voice, splitting
+//!   long text at sentence boundaries
+//!
+//! Both are built on the AWS SDK clients and use the standard AWS credential
+//! chain and the installed [proxy](crate::proxy), like the Bedrock provider.
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use aws_sdk_polly::types::{Engine, OutputFormat, TextType, VoiceId};
+use aws_sdk_transcribe::config::http::HttpResponse;
+use aws_sdk_transcribe::error::{DisplayErrorContext, SdkError};
+use aws_sdk_transcribe::types::{LanguageCode, Media, MediaFormat, TranscriptionJobStatus};
+
+use super::{Audio, AudioError, AudioFormat, Synthesizer, Transcriber};
+use crate::llm::http::LazyAwsClient;
+use crate::tools::s3::S3Connection;
+
+/// Characters Polly accepts per request
+const POLLY_MAX_CHARACTERS: usize = 3000;
+
+/// Convert a failed AWS request: errors returned by the service are service
+/// errors, everything else is a network error
+fn sdk_error<E>(service: &str, error: SdkError<E, HttpResponse>) -> AudioError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    match error {
+        SdkError::ServiceError(e) => AudioError::Service {
+            service: service.to_string(),
+            message: format!(
+                "HTTP {}: {}",
+                e.raw().status().as_u16(),
+                DisplayErrorContext(e.err())
+            ),
+        },
+        e => AudioError::Network(DisplayErrorContext(&e).to_string()),
+    }
+}
+
+/// Transcribes recordings with Amazon Transcribe batch jobs.
+///
+/// Transcribe reads media from and writes transcripts to S3, so each
+/// recording is uploaded to the staging bucket (which `staging` must allow
+/// reads and writes to), transcribed, and deleted together with its
+/// transcript and job. Accepts MP3, WAV, FLAC and Ogg audio.
+#[derive(Debug, Clone)]
+pub struct TranscribeTranscriber {
+    client: LazyAwsClient<aws_sdk_transcribe::Client>,
+    staging: S3Connection,
+    bucket: String,
+    prefix: String,
+    language_code: Option<String>,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl TranscribeTranscriber {
+    /// Transcribe in `region`, staging recordings in `bucket`
+    pub fn new(region: impl AsRef<str>, staging: S3Connection, bucket: impl Into<String>) -> Self {
+        Self::with_client(LazyAwsClient::new(region.as_ref()), staging, bucket)
+    }
+
+    /// Use an existing Transcribe client, staging recordings in `bucket`
+    pub fn from_client(
+        client: aws_sdk_transcribe::Client,
+        staging: S3Connection,
+        bucket: impl Into<String>,
+    ) -> Self {
+        Self::with_client(LazyAwsClient::from_client(client), staging, bucket)
+    }
+
+    fn with_client(
+        client: LazyAwsClient<aws_sdk_transcribe::Client>,
+        staging: S3Connection,
+        bucket: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            staging,
+            bucket: bucket.into(),
+            prefix: "stood-audio/".to_string(),
+            language_code: None,
+            poll_interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    /// Key prefix of staged recordings (default: `stood-audio/`)
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Language of the recordings, e.g. `en-US`; identified automatically if unset
+    pub fn with_language(mut self, language_code: impl Into<String>) -> Self {
+        self.language_code = Some(language_code.into());
+        self
+    }
+
+    /// Maximum time to wait for a job (default: 5 minutes)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    async fn client(&self) -> Result<&aws_sdk_transcribe::Client, AudioError> {
+        self.client
+            .get(aws_sdk_transcribe::Client::new)
+            .await
+            .map_err(|e| AudioError::Configuration(e.to_string()))
+    }
+
+    /// Start the job, writing its transcript to `transcript_key`, and wait
+    /// for it to complete
+    async fn run_job(
+        &self,
+        job_name: &str,
+        uri: &str,
+        format: AudioFormat,
+        transcript_key: &str,
+    ) -> Result<(), AudioError> {
+        let client = self.client().await?;
+        let mut request = client
+            .start_transcription_job()
+            .transcription_job_name(job_name)
+            .media(Media::builder().media_file_uri(uri).build())
+            .media_format(MediaFormat::from(format.extension()))
+            .output_bucket_name(&self.bucket)
+            .output_key(transcript_key);
+        request = match &self.language_code {
+            Some(language_code) => {
+                request.language_code(LanguageCode::from(language_code.as_str()))
+            }
+            None => request.identify_language(true),
+        };
+        request
+            .send()
+            .await
+            .map_err(|e| sdk_error("transcribe", e))?;
+
+        let started = Instant::now();
+        loop {
+            let response = client
+                .get_transcription_job()
+                .transcription_job_name(job_name)
+                .send()
+                .await
+                .map_err(|e| sdk_error("transcribe", e))?;
+            let job = response
+                .transcription_job()
+                .ok_or_else(|| self.service_error("The job was not found"))?;
+            match job.transcription_job_status() {
+                Some(TranscriptionJobStatus::Completed) => return Ok(()),
+                Some(TranscriptionJobStatus::Failed) => {
+                    return Err(self
+                        .service_error(job.failure_reason().unwrap_or("Transcription job failed")))
+                }
+                _ => {}
+            }
+            if started.elapsed() >= self.timeout {
+                return Err(AudioError::Timeout {
+                    service: "transcribe".to_string(),
+                    timeout_ms: self.timeout.as_millis() as u64,
+                });
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn fetch_transcript(&self, transcript_key: &str) -> Result<String, AudioError> {
+        let data = self
+            .staging
+            .download(&self.bucket, transcript_key)
+            .await
+            .map_err(|message| AudioError::Service {
+                service: "s3".to_string(),
+                message,
+            })?;
+        let transcript: serde_json::Value = serde_json::from_slice(&data)
+            .map_err(|e| self.service_error(format!("Invalid transcript: {}", e)))?;
+        parse_transcript(&transcript).ok_or_else(|| self.service_error("Invalid transcript"))
+    }
+
+    fn service_error(&self, message: impl Into<String>) -> AudioError {
+        AudioError::Service {
+            service: "transcribe".to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Text of a Transcribe transcript document
+fn parse_transcript(transcript: &serde_json::Value) -> Option<String> {
+    let transcripts = transcript["results"]["transcripts"].as_array()?;
+    Some(
+        transcripts
+            .iter()
+            .filter_map(|t| t["transcript"].as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+#[async_trait]
+impl Transcriber for TranscribeTranscriber {
+    async fn transcribe(&self, audio: &Audio) -> Result<String, AudioError> {
+        let format = match audio.format {
+            Some(
+                format @ (AudioFormat::Mp3
+                | AudioFormat::Wav
+                | AudioFormat::Flac
+                | AudioFormat::Ogg),
+            ) => format,
+            Some(other) => {
+                return Err(AudioError::UnsupportedFormat(format!(
+                    "Amazon Transcribe batch jobs do not accept {}",
+                    other.mime_type()
+                )))
+            }
+            None => {
+                return Err(AudioError::UnsupportedFormat(
+                    "the audio format could not be detected".to_string(),
+                ))
+            }
+        };
+
+        let job_name = format!("stood-{}", uuid::Uuid::new_v4().simple());
+        let key = format!("{}{}.{}", self.prefix, job_name, format.extension());
+        self.staging
+            .upload(&self.bucket, &key, audio.data.clone(), format.mime_type())
+            .await
+            .map_err(|message| AudioError::Service {
+                service: "s3".to_string(),
+                message,
+            })?;
+
+        let uri = format!("s3://{}/{}", self.bucket, key);
+        let transcript_key = format!("{}{}.json", self.prefix, job_name);
+        let result = match self.run_job(&job_name, &uri, format, &transcript_key).await {
+            Ok(()) => self.fetch_transcript(&transcript_key).await,
+            Err(e) => Err(e),
+        };
+
+        // Clean up regardless of the outcome; failures only leave stale objects
+        for key in [&key, &transcript_key] {
+            if let Err(e) = self.staging.delete(&self.bucket, key).await {
+                tracing::warn!("Failed to delete s3://{}/{}: {}", self.bucket, key, e);
+            }
+        }
+        if let Ok(client) = self.client().await {
+            if let Err(e) = client
+                .delete_transcription_job()
+                .transcription_job_name(&job_name)
+                .send()
+                .await
+            {
+                tracing::debug!(
+                    "Failed to delete transcription job {}: {}",
+                    job_name,
+                    DisplayErrorContext(e)
+                );
+            }
+        }
+        result
+    }
+
+    fn name(&self) -> &str {
+        "transcribe"
+    }
+}
+
+/// Speaks text with an Amazon Polly voice
+#[derive(Debug, Clone)]
+pub struct PollySynthesizer {
+    client: LazyAwsClient<aws_sdk_polly::Client>,
+    voice_id: String,
+    engine: String,
+    output_format: AudioFormat,
+    language_code: Option<String>,
+}
+
+impl PollySynthesizer {
+    /// Synthesize in `region` with the `Joanna` neural voice, as MP3
+    pub fn new(region: impl AsRef<str>) -> Self {
+        Self::with_client(LazyAwsClient::new(region.as_ref()))
+    }
+
+    /// Use an existing Polly client, with the `Joanna` neural voice, as MP3
+    pub fn from_client(client: aws_sdk_polly::Client) -> Self {
+        Self::with_client(LazyAwsClient::from_client(client))
+    }
+
+    fn with_client(client: LazyAwsClient<aws_sdk_polly::Client>) -> Self {
+        Self {
+            client,
+            voice_id: "Joanna".to_string(),
+            engine: "neural".to_string(),
+            output_format: AudioFormat::Mp3,
+            language_code: None,
+        }
+    }
+
+    /// Polly voice, e.g. `Matthew` or `Lea`
+    pub fn with_voice(mut self, voice_id: impl Into<String>) -> Self {
+        self.voice_id = voice_id.into();
+        self
+    }
+
+    /// `standard`, `neural`, `long-form` or `generative` (default: `neural`)
+    pub fn with_engine(mut self, engine: impl Into<String>) -> Self {
+        self.engine = engine.into();
+        self
+    }
+
+    /// MP3, Ogg or PCM at 8000 or 16000 Hz (default: MP3)
+    pub fn with_output_format(mut self, format: AudioFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
+    /// Language of bilingual voices, e.g. `en-IN`
+    pub fn with_language(mut self, language_code: impl Into<String>) -> Self {
+        self.language_code = Some(language_code.into());
+        self
+    }
+
+    /// Polly output format and sample rate producing `output_format`
+    fn polly_format(&self) -> Result<(OutputFormat, Option<String>), AudioError> {
+        match self.output_format {
+            AudioFormat::Mp3 => Ok((OutputFormat::Mp3, None)),
+            AudioFormat::Ogg => Ok((OutputFormat::OggVorbis, None)),
+            AudioFormat::Pcm { sample_rate } => {
+                Ok((OutputFormat::Pcm, Some(sample_rate.to_string())))
+            }
+            other => Err(AudioError::UnsupportedFormat(format!(
+                "Amazon Polly cannot produce {}",
+                other.mime_type()
+            ))),
+        }
+    }
+}
+
+/// Split `text` into chunks of at most `max_chars` characters, preferring
+/// sentence and then word boundaries
+fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+    while rest.chars().count() > max_chars {
+        let limit = rest
+            .char_indices()
+            .nth(max_chars)
+            .map_or(rest.len(), |(index, _)| index);
+        let window = &rest[..limit];
+        let split = window
+            .rfind(['.', '!', '?', '\n'])
+            .map(|index| index + 1)
+            .or_else(|| window.rfind(char::is_whitespace))
+            .filter(|&index| index > 0)
+            .unwrap_or(limit);
+        chunks.push(rest[..split].trim().to_string());
+        rest = rest[split..].trim_start();
+    }
+    if !rest.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+#[async_trait]
+impl Synthesizer for PollySynthesizer {
+    async fn synthesize(&self, text: &str) -> Result<Audio, AudioError> {
+        let (output_format, sample_rate) = self.polly_format()?;
+        let client = self
+            .client
+            .get(aws_sdk_polly::Client::new)
+            .await
+            .map_err(|e| AudioError::Configuration(e.to_string()))?;
+        let mut data = Vec::new();
+        for chunk in split_text(text, POLLY_MAX_CHARACTERS) {
+            let mut request = client
+                .synthesize_speech()
+                .engine(Engine::from(self.engine.as_str()))
+                .text(chunk)
+                .text_type(TextType::Text)
+                .voice_id(VoiceId::from(self.voice_id.as_str()))
+                .output_format(output_format.clone())
+                .set_sample_rate(sample_rate.clone());
+            if let Some(language_code) = &self.language_code {
+                request = request.language_code(aws_sdk_polly::types::LanguageCode::from(
+                    language_code.as_str(),
+                ));
+            }
+            let output = request.send().await.map_err(|e| sdk_error("polly", e))?;
+            let audio = output
+                .audio_stream
+                .collect()
+                .await
+                .map_err(|e| AudioError::Network(e.to_string()))?;
+            data.extend_from_slice(&audio.into_bytes());
+        }
+        Ok(Audio::new(data, self.output_format))
+    }
+
+    fn name(&self) -> &str {
+        "polly"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_text() {
+        let text = "First sentence. Second one is longer! Third";
+        assert_eq!(
+            split_text(text, 20),
+            vec!["First sentence.", "Second one is", "longer! Third"]
+        );
+        assert_eq!(split_text("  short  ", 20), vec!["short"]);
+        assert!(split_text("", 20).is_empty());
+        assert_eq!(split_text("abcdef", 4), vec!["abcd", "ef"]);
+    }
+
+    #[test]
+    fn test_polly_format_and_transcript() {
+        let polly = PollySynthesizer::new("us-east-1")
+            .with_output_format(AudioFormat::Pcm { sample_rate: 16000 });
+        assert_eq!(
+            polly.polly_format().unwrap(),
+            (OutputFormat::Pcm, Some("16000".to_string()))
+        );
+        assert!(polly
+            .with_output_format(AudioFormat::Wav)
+            .polly_format()
+            .is_err());
+
+        let transcript = serde_json::json!({
+            "results": { "transcripts": [{ "transcript": "What's the weather in Paris?" }] }
+        });
+        assert_eq!(
+            parse_transcript(&transcript).as_deref(),
+            Some("What's the weather in Paris?")
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Speech-to-text and text-to-speech for voice agents.
+//!
+//! A [`Transcriber`] turns recorded speech into text and a [`Synthesizer`]
+//! turns text into speech. Configured on an agent with
+//! [`AgentBuilder::with_transcriber`](crate::agent::AgentBuilder::with_transcriber)
+//! and [`AgentBuilder::with_synthesizer`](crate::agent::AgentBuilder::with_synthesizer),
+//! they let [`Agent::execute_from_audio`](crate::agent::Agent::execute_from_audio)
+//! run a spoken prompt through the regular event loop and
+//! [`Agent::respond_as_audio`](crate::agent::Agent::respond_as_audio) speak
+//! the last response.
+//!
+//! Implementations backed by Amazon Transcribe and Amazon Polly are available
+//! in [`aws`] with the `aws-audio` feature.
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use stood::agent::Agent;
+//! use stood::audio::aws::{PollySynthesizer, TranscribeTranscriber};
+//! use stood::tools::s3::{S3Config, S3Connection};
+//!
+//! # async fn example(recording: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+//! // Amazon Transcribe reads recordings from S3
+//! let staging = S3Connection::connect(S3Config::new().allow("voice-staging").with_writes(true)).await?;
+//!
+//! let mut agent = Agent::builder()
+//!     .with_transcriber(Arc::new(TranscribeTranscriber::new("us-east-1", staging, "voice-staging")))
+//!     .with_synthesizer(Arc::new(PollySynthesizer::new("us-east-1").with_voice("Matthew")))
+//!     .build()
+//!     .await?;
+//!
+//! agent.execute_from_audio(recording).await?;
+//! let reply = agent.respond_as_audio().await?;
+//! std::fs::write("reply.mp3", &reply.data)?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "aws-audio")]
+pub mod aws;
+
+use async_trait::async_trait;
+
+/// Encoding of an [`Audio`] clip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Mp3,
+    Wav,
+    Flac,
+    Ogg,
+    /// Raw signed 16-bit little-endian mono samples
+    Pcm {
+        sample_rate: u32,
+    },
+}
+
+impl AudioFormat {
+    /// Detect the format of encoded audio from its header
+    ///
+    /// Raw PCM has no header and is never detected.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match data {
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some(Self::Wav),
+            [b'f', b'L', b'a', b'C', ..] => Some(Self::Flac),
+            [b'O', b'g', b'g', b'S', ..] => Some(Self::Ogg),
+            [b'I', b'D', b'3', ..] => Some(Self::Mp3),
+            // MPEG audio frame sync
+            [0xFF, second, ..] if second & 0xE0 == 0xE0 => Some(Self::Mp3),
+            _ => None,
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Mp3 => "audio/mpeg",
+            Self::Wav => "audio/wav",
+            Self::Flac => "audio/flac",
+            Self::Ogg => "audio/ogg",
+            Self::Pcm { .. } => "audio/L16",
+        }
+    }
+
+    /// Usual file extension, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+            Self::Ogg => "ogg",
+            Self::Pcm { .. } => "pcm",
+        }
+    }
+}
+
+/// An audio clip
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Audio {
+    pub data: Vec<u8>,
+    /// Encoding of `data`; `None` if it could not be detected
+    pub format: Option<AudioFormat>,
+}
+
+impl Audio {
+    /// Audio in a known format
+    pub fn new(data: Vec<u8>, format: AudioFormat) -> Self {
+        Self {
+            data,
+            format: Some(format),
+        }
+    }
+
+    /// Audio whose format is detected from its header
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        let format = AudioFormat::detect(&data);
+        Self { data, format }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for Audio {
+    fn from(data: Vec<u8>) -> Self {
+        Self::from_bytes(data)
+    }
+}
+
+impl From<&[u8]> for Audio {
+    fn from(data: &[u8]) -> Self {
+        Self::from_bytes(data.to_vec())
+    }
+}
+
+/// Errors of speech services
+#[derive(Debug, thiserror::Error)]
+pub enum AudioError {
+    /// The audio is in a format the service does not accept
+    #[error("Unsupported audio format: {0}")]
+    UnsupportedFormat(String),
+
+    /// The client for the service could not be configured
+    #[error("Configuration error: {0}")]
+    Configuration(String),
+
+    /// The service could not be reached
+    #[error("Network error: {0}")]
+    Network(String),
+
+    /// The service rejected the request or failed to process it
+    #[error("{service} error: {message}")]
+    Service { service: String, message: String },
+
+    /// The service did not finish in time
+    #[error("{service} did not finish within {timeout_ms}ms")]
+    Timeout { service: String, timeout_ms: u64 },
+}
+
+/// Converts speech to text
+#[async_trait]
+pub trait Transcriber: Send + Sync + std::fmt::Debug {
+    /// Transcribe `audio`, returning the recognized text
+    async fn transcribe(&self, audio: &Audio) -> Result<String, AudioError>;
+
+    /// Name of the implementation, for logs
+    fn name(&self) -> &str;
+}
+
+/// Converts text to speech
+#[async_trait]
+pub trait Synthesizer: Send + Sync + std::fmt::Debug {
+    /// Speak `text`
+    async fn synthesize(&self, text: &str) -> Result<Audio, AudioError>;
+
+    /// Name of the implementation, for logs
+    fn name(&self) -> &str;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        let mut wav = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        wav.extend_from_slice(&[0; 8]);
+        assert_eq!(AudioFormat::detect(&wav), Some(AudioFormat::Wav));
+        assert_eq!(AudioFormat::detect(b"ID3\x04"), Some(AudioFormat::Mp3));
+        assert_eq!(
+            AudioFormat::detect(&[0xFF, 0xFB, 0x90]),
+            Some(AudioFormat::Mp3)
+        );
+        assert_eq!(AudioFormat::detect(b"OggS\0"), Some(AudioFormat::Ogg));
+        assert_eq!(AudioFormat::detect(b"fLaC"), Some(AudioFormat::Flac));
+        assert_eq!(AudioFormat::detect(&[0, 1, 2, 3]), None);
+
+        let audio = Audio::from(b"fLaC\0\0".as_slice());
+        assert_eq!(audio.format.map(|f| f.extension()), Some("flac"));
+    }
+}
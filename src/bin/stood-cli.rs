@@ -0,0 +1,79 @@
+//! Developer tooling for projects built on stood
+//!
+//! Usage:
+//! cargo run --bin stood-cli -- new-tool weather --params "location:String,units:Option<String>"
+//! cargo run --bin stood-cli -- new-tool weather --params "location:String" --output src/tools/weather.rs
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use stood::tools::scaffold::ToolScaffold;
+
+#[derive(Parser)]
+#[command(name = "stood-cli", about = "Developer tooling for stood agents")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a #[tool] function skeleton with a schema test and registration snippet
+    NewTool {
+        /// Tool name, a snake_case identifier
+        name: String,
+        /// Comma-separated parameters, e.g. "location:String,units:Option<String>"
+        #[arg(long, default_value = "")]
+        params: String,
+        /// Tool description shown to the model
+        #[arg(long)]
+        description: Option<String>,
+        /// File to write instead of printing to stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    match cli.command {
+        Command::NewTool {
+            name,
+            params,
+            description,
+            output,
+            force,
+        } => {
+            let mut scaffold = ToolScaffold::parse(&name, &params)?;
+            if let Some(description) = description {
+                scaffold = scaffold.with_description(description);
+            }
+            let source = scaffold.render();
+
+            match output {
+                Some(path) => {
+                    if path.exists() && !force {
+                        return Err(format!(
+                            "{} already exists, use --force to overwrite it",
+                            path.display()
+                        )
+                        .into());
+                    }
+                    std::fs::write(&path, source)?;
+                    eprintln!("Wrote tool '{}' to {}", name, path.display());
+                }
+                None => print!("{}", source),
+            }
+        }
+    }
+    Ok(())
+}
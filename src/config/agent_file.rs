@@ -0,0 +1,490 @@
+//! Agent definitions loaded from TOML or YAML files
+//!
+//! An agent file describes the model, prompts, tool groups, retry behavior,
+//! telemetry and evaluation strategy of an agent, so they can be tuned without
+//! recompiling. Load one with
+//! [`AgentBuilder::from_config_file`](crate::agent::AgentBuilder::from_config_file).
+//!
+//! Values may reference environment variables as `${VAR}` or
+//! `${VAR:-default}`; `$$` produces a literal `$`. Referencing an unset
+//! variable without a default is an error.
+//!
+//! ```toml
+//! name = "support-agent"
+//! streaming = true
+//!
+//! [model]
+//! provider = "bedrock"
+//! id = "${SUPPORT_MODEL:-us.anthropic.claude-haiku-4-5-20251001-v1:0}"
+//! temperature = 0.3
+//! max_tokens = 4096
+//!
+//! [prompt]
+//! system_file = "prompts/support.md"   # relative to this file
+//!
+//! [tools]
+//! groups = ["files", "utility"]
+//! max_parallel = 4
+//!
+//! [retry]
+//! preset = "conservative"
+//!
+//! [telemetry]
+//! enabled = true
+//! region = "${AWS_REGION:-us-east-1}"
+//! service_name = "support-agent"
+//!
+//! [evaluation]
+//! strategy = "task"
+//! prompt = "Has the customer's question been fully answered?"
+//!
+//! [limits]
+//! max_cycles = 20
+//! max_duration_secs = 300
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use super::ConfigError;
+use crate::agent::evaluation::PerspectiveConfig;
+use crate::llm::providers::retry::RetryConfig;
+use crate::llm::traits::ProviderType;
+use crate::tools::Tool;
+
+/// Agent definition loaded from a configuration file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentFileConfig {
+    /// Agent name
+    pub name: Option<String>,
+    /// Agent id
+    pub id: Option<String>,
+    /// Model and sampling parameters
+    pub model: ModelSection,
+    /// System prompt
+    #[serde(default)]
+    pub prompt: PromptSection,
+    /// Tool groups and execution settings
+    #[serde(default)]
+    pub tools: ToolsSection,
+    /// Retry behavior for model requests
+    pub retry: Option<RetrySection>,
+    /// Telemetry export
+    pub telemetry: Option<TelemetrySection>,
+    /// Evaluation strategy used to decide whether to continue
+    pub evaluation: Option<EvaluationSection>,
+    /// Execution limits
+    #[serde(default)]
+    pub limits: LimitsSection,
+    /// Stream model responses
+    pub streaming: Option<bool>,
+}
+
+/// Model selection and sampling parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelSection {
+    /// Provider name, as returned by [`ProviderType::as_str`] (e.g. `bedrock`, `lm_studio`)
+    #[serde(default = "default_provider")]
+    pub provider: String,
+    /// Model id (e.g. `us.anthropic.claude-haiku-4-5-20251001-v1:0`)
+    pub id: String,
+    /// Sampling temperature
+    pub temperature: Option<f32>,
+    /// Maximum tokens to generate
+    pub max_tokens: Option<u32>,
+}
+
+/// System prompt, given inline or read from a file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptSection {
+    /// Inline system prompt
+    pub system: Option<String>,
+    /// File containing the system prompt, relative to the agent file
+    ///
+    /// Resolved against the agent file's directory when loaded.
+    pub system_file: Option<PathBuf>,
+}
+
+/// Tool groups and tool execution settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolsSection {
+    /// Built-in tool groups to register
+    #[serde(default)]
+    pub groups: Vec<ToolGroup>,
+    /// Maximum tools executed concurrently
+    pub max_parallel: Option<usize>,
+    /// Maximum tool iterations per execution
+    pub max_iterations: Option<u32>,
+    /// Timeout for a single tool call, in seconds
+    pub timeout_secs: Option<u64>,
+}
+
+/// Group of built-in tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolGroup {
+    /// Every built-in tool
+    Builtin,
+    /// Reading, writing, editing and searching files
+    Files,
+    /// HTTP requests and web pages
+    Web,
+    /// Calculator, current time and environment variables
+    Utility,
+}
+
+impl ToolGroup {
+    /// Create the tools in this group
+    pub fn tools(&self) -> Vec<Box<dyn Tool>> {
+        use crate::tools::builtin::*;
+
+        match self {
+            ToolGroup::Builtin => [ToolGroup::Utility, ToolGroup::Files, ToolGroup::Web]
+                .iter()
+                .flat_map(|group| group.tools())
+                .collect(),
+            ToolGroup::Files => vec![
+                Box::new(FileReadTool::new()),
+                Box::new(FileWriteTool::new()),
+                Box::new(FileListTool::new()),
+                Box::new(FileEditTool::new()),
+                Box::new(GrepTool::new()),
+                Box::new(GlobTool::new()),
+            ],
+            ToolGroup::Web => vec![
+                Box::new(HttpRequestTool::new()),
+                Box::new(WebPageTool::new()),
+            ],
+            ToolGroup::Utility => vec![
+                Box::new(CalculatorTool::new()),
+                Box::new(CurrentTimeTool::new()),
+                Box::new(EnvVarTool::new()),
+            ],
+        }
+    }
+}
+
+/// Retry behavior, from a preset or explicit values
+///
+/// Explicit values override the preset, which defaults to `default`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetrySection {
+    /// Preset to start from
+    pub preset: Option<RetryPreset>,
+    /// Maximum retry attempts
+    pub max_attempts: Option<u32>,
+    /// Delay before the first retry, in milliseconds
+    pub initial_delay_ms: Option<u64>,
+    /// Maximum delay between retries, in milliseconds
+    pub max_delay_ms: Option<u64>,
+    /// Multiplier applied to the delay after each retry
+    pub backoff_multiplier: Option<f64>,
+    /// Add random jitter to retry delays
+    pub jitter: Option<bool>,
+}
+
+/// Named retry configurations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryPreset {
+    /// [`RetryConfig::lm_studio_default`]
+    Default,
+    /// [`RetryConfig::lm_studio_conservative`]
+    Conservative,
+    /// [`RetryConfig::lm_studio_aggressive`]
+    Aggressive,
+    /// [`RetryConfig::disabled`]
+    Disabled,
+}
+
+impl RetrySection {
+    /// Build the retry configuration
+    pub fn retry_config(&self) -> RetryConfig {
+        let mut config = match self.preset.unwrap_or(RetryPreset::Default) {
+            RetryPreset::Default => RetryConfig::lm_studio_default(),
+            RetryPreset::Conservative => RetryConfig::lm_studio_conservative(),
+            RetryPreset::Aggressive => RetryConfig::lm_studio_aggressive(),
+            RetryPreset::Disabled => RetryConfig::disabled(),
+        };
+        if let Some(max_attempts) = self.max_attempts {
+            config.max_attempts = max_attempts;
+        }
+        if let Some(ms) = self.initial_delay_ms {
+            config.initial_delay = Duration::from_millis(ms);
+        }
+        if let Some(ms) = self.max_delay_ms {
+            config.max_delay = Duration::from_millis(ms);
+        }
+        if let Some(multiplier) = self.backoff_multiplier {
+            config.backoff_multiplier = multiplier;
+        }
+        if let Some(jitter) = self.jitter {
+            config.jitter = jitter;
+        }
+        config
+    }
+}
+
+/// Telemetry export to CloudWatch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetrySection {
+    /// Export telemetry
+    #[serde(default)]
+    pub enabled: bool,
+    /// AWS region to export to
+    pub region: Option<String>,
+    /// Service name reported with traces
+    pub service_name: Option<String>,
+    /// Record prompts and responses in spans
+    pub content_capture: Option<bool>,
+    /// Anonymize recorded prompts and responses before export
+    #[serde(default)]
+    pub anonymize_content: bool,
+}
+
+impl TelemetrySection {
+    /// Build the telemetry configuration
+    pub fn telemetry_config(&self) -> crate::telemetry::TelemetryConfig {
+        use crate::telemetry::{ContentAnonymizer, TelemetryConfig};
+
+        let mut config = if !self.enabled {
+            TelemetryConfig::disabled()
+        } else if let Some(region) = &self.region {
+            TelemetryConfig::cloudwatch(region.clone())
+        } else {
+            TelemetryConfig::default().with_enabled(true)
+        };
+        if let Some(service_name) = &self.service_name {
+            config = config.with_service_name(service_name.clone());
+        }
+        if let Some(capture) = self.content_capture {
+            config = config.with_content_capture(capture);
+        }
+        if self.anonymize_content {
+            config = config.with_content_anonymization(ContentAnonymizer::new());
+        }
+        config
+    }
+}
+
+/// Evaluation strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case", deny_unknown_fields)]
+pub enum EvaluationSection {
+    /// Let the model decide when to stop
+    None,
+    /// Ask the agent whether the task is complete
+    Task {
+        /// Evaluation prompt
+        prompt: String,
+    },
+    /// Combine several weighted evaluation perspectives
+    MultiPerspective {
+        /// Perspectives to evaluate
+        perspectives: Vec<PerspectiveConfig>,
+    },
+}
+
+/// Execution limits
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LimitsSection {
+    /// Maximum event loop cycles
+    pub max_cycles: Option<u32>,
+    /// Maximum execution time, in seconds
+    pub max_duration_secs: Option<u64>,
+    /// Maximum tokens across all model calls
+    pub max_total_tokens: Option<u32>,
+}
+
+impl AgentFileConfig {
+    /// Load an agent file, choosing the format from the extension
+    ///
+    /// Supports `.toml`, `.yaml` and `.yml`. A `prompt.system_file` is read
+    /// relative to the agent file and stored in `prompt.system`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let content = interpolate_env(&fs::read_to_string(path)?)?;
+
+        let mut config: Self = match path.extension().and_then(|s| s.to_str()) {
+            Some("toml") => {
+                toml::from_str(&content).map_err(|e| ConfigError::FileParse(e.to_string()))?
+            }
+            Some("yaml") | Some("yml") => {
+                serde_yaml::from_str(&content).map_err(|e| ConfigError::FileParse(e.to_string()))?
+            }
+            _ => {
+                return Err(ConfigError::FileParse(
+                    "Unsupported agent file format. Use .toml, .yaml or .yml".to_string(),
+                ))
+            }
+        };
+
+        config.load_system_file(path.parent().unwrap_or_else(|| Path::new(".")))?;
+        Ok(config)
+    }
+
+    /// Read `prompt.system_file`, relative to `base`, into `prompt.system`
+    pub(crate) fn load_system_file(&mut self, base: &Path) -> Result<(), ConfigError> {
+        let Some(system_file) = self.prompt.system_file.take() else {
+            return Ok(());
+        };
+        if self.prompt.system.is_some() {
+            return Err(ConfigError::Validation(
+                "Set either prompt.system or prompt.system_file, not both".to_string(),
+            ));
+        }
+        let prompt_path = base.join(&system_file);
+        let prompt = fs::read_to_string(&prompt_path).map_err(|e| {
+            ConfigError::FileParse(format!(
+                "Failed to read system prompt {}: {}",
+                prompt_path.display(),
+                e
+            ))
+        })?;
+        self.prompt.system = Some(interpolate_env(&prompt)?);
+        self.prompt.system_file = Some(prompt_path);
+        Ok(())
+    }
+
+    /// Provider named in the model section
+    pub fn provider(&self) -> Result<ProviderType, ConfigError> {
+        super::environment::parse_provider(&self.model.provider)
+    }
+}
+
+/// Replace `${VAR}` and `${VAR:-default}` with environment variable values
+pub fn interpolate_env(content: &str) -> Result<String, ConfigError> {
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if let Some(after) = rest.strip_prefix("$$") {
+            output.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let end = after.find('}').ok_or_else(|| {
+                ConfigError::EnvVarParse("Unterminated '${' in agent file".to_string())
+            })?;
+            let expression = &after[..end];
+            let (name, default) = match expression.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (expression, None),
+            };
+            match (env::var(name), default) {
+                (Ok(value), _) => output.push_str(&value),
+                (Err(_), Some(default)) => output.push_str(default),
+                (Err(_), None) => {
+                    return Err(ConfigError::EnvVarParse(format!(
+                        "Environment variable {} is not set",
+                        name
+                    )))
+                }
+            }
+            rest = &after[end + 1..];
+        } else {
+            output.push('$');
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn default_provider() -> String {
+    ProviderType::Bedrock.as_str().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_file_toml_and_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        env::set_var("STOOD_AGENT_FILE_TEST_TEMP", "0.25");
+        fs::write(
+            dir.path().join("prompt.md"),
+            "You answer ${STOOD_AGENT_FILE_TEST_ROLE:-support} questions.",
+        )
+        .unwrap();
+
+        let toml_path = dir.path().join("agent.toml");
+        fs::write(
+            &toml_path,
+            r#"
+name = "support"
+
+[model]
+id = "us.anthropic.claude-haiku-4-5-20251001-v1:0"
+temperature = ${STOOD_AGENT_FILE_TEST_TEMP}
+
+[prompt]
+system_file = "prompt.md"
+
+[tools]
+groups = ["files", "utility"]
+
+[retry]
+preset = "aggressive"
+max_attempts = 7
+
+[evaluation]
+strategy = "task"
+prompt = "Costs $$5?"
+"#,
+        )
+        .unwrap();
+        let config = AgentFileConfig::from_file(&toml_path).unwrap();
+        assert_eq!(config.provider().unwrap(), ProviderType::Bedrock);
+        assert_eq!(config.model.temperature, Some(0.25));
+        assert_eq!(
+            config.prompt.system.as_deref(),
+            Some("You answer support questions.")
+        );
+        assert_eq!(
+            config.tools.groups,
+            vec![ToolGroup::Files, ToolGroup::Utility]
+        );
+        assert_eq!(config.retry.unwrap().retry_config().max_attempts, 7);
+        assert!(matches!(
+            config.evaluation,
+            Some(EvaluationSection::Task { ref prompt }) if prompt == "Costs $5?"
+        ));
+
+        let yaml_path = dir.path().join("agent.yaml");
+        fs::write(
+            &yaml_path,
+            "model:\n  provider: lm_studio\n  id: google/gemma-3-12b\nlimits:\n  max_cycles: 3\n",
+        )
+        .unwrap();
+        let config = AgentFileConfig::from_file(&yaml_path).unwrap();
+        assert_eq!(config.provider().unwrap(), ProviderType::LmStudio);
+        assert_eq!(config.limits.max_cycles, Some(3));
+    }
+
+    #[test]
+    fn test_interpolate_env_missing_variable() {
+        assert!(matches!(
+            interpolate_env("id = \"${STOOD_AGENT_FILE_TEST_UNSET}\""),
+            Err(ConfigError::EnvVarParse(_))
+        ));
+        assert_eq!(
+            interpolate_env("a${STOOD_AGENT_FILE_TEST_UNSET:-b}c $HOME").unwrap(),
+            "abc $HOME"
+        );
+    }
+}
@@ -0,0 +1,599 @@
+//! Agent configuration from `STOOD_*` environment variables.
+//!
+//! Container deployments configure agents through the environment.
+//! [`from_env`](super::from_env) reads every variable below into an
+//! [`AgentConfig`], [`ExecutionConfig`] and [`TelemetryConfig`] at once, and
+//! reports every missing or invalid value instead of stopping at the first:
+//!
+//! ```no_run
+//! let config = stood::config::from_env();
+//! if !config.report.is_valid() {
+//!     eprintln!("{}", config.report);
+//!     std::process::exit(1);
+//! }
+//! ```
+//!
+//! [`AgentBuilder::from_env`](crate::agent::AgentBuilder::from_env) builds an
+//! agent from the same variables, failing with the report.
+//!
+//! | Variable | Sets | Default |
+//! |---|---|---|
+//! | `STOOD_PROVIDER` | Provider (`bedrock`, `lm_studio`, `anthropic`, `openai`, `ollama`, `openrouter`, `candle`) | `bedrock` |
+//! | `STOOD_MODEL` | Model ID; required for providers other than Bedrock | Claude Haiku 4.5 |
+//! | `STOOD_TEMPERATURE` | Temperature, 0.0 to 1.0 | 0.7 |
+//! | `STOOD_MAX_TOKENS` | Maximum output tokens | 4096 |
+//! | `STOOD_SYSTEM_PROMPT` | System prompt | |
+//! | `STOOD_AGENT_ID`, `STOOD_AGENT_NAME` | Agent ID (also the telemetry log group) and name | |
+//! | `STOOD_STREAMING` | Stream responses | `true` |
+//! | `STOOD_TIMEOUT` | Execution timeout, in seconds | 300 |
+//! | `STOOD_LOG_LEVEL` | `off`, `info`, `debug` or `trace` | `off` |
+//! | `STOOD_MAX_CYCLES` | Maximum agentic cycles | 10 |
+//! | `STOOD_MAX_DURATION` | Maximum event loop duration, in seconds | 300 |
+//! | `STOOD_MAX_TOTAL_TOKENS` | Token budget of an execution | |
+//! | `STOOD_REASONING_TIMEOUT`, `STOOD_TOOL_BATCH_TIMEOUT`, `STOOD_EVALUATION_TIMEOUT`, `STOOD_SYNTHESIS_TIMEOUT` | [Phase timeouts](crate::agent::phase_timeouts), in seconds | |
+//! | `STOOD_CLOUDWATCH_ENABLED` | Export telemetry to CloudWatch (legacy: `OTEL_ENABLED`) | `false` |
+//! | `AWS_REGION` | CloudWatch region; required when exporting | |
+//! | `OTEL_SERVICE_NAME`, `OTEL_SERVICE_VERSION` | Service name and version in traces | `stood-agent`, crate version |
+//! | `STOOD_GENAI_CONTENT_CAPTURE`, `STOOD_GENAI_CONTENT_ANONYMIZE` | Capture message content, anonymized | `false` |
+//!
+//! Booleans accept `true`/`false`, `1`/`0` and `yes`/`no`; empty variables
+//! count as unset. Unrecognized `STOOD_*` variables, usually typos, are
+//! reported as warnings.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::ConfigError;
+use crate::agent::config::{ExecutionConfig, LogLevel};
+use crate::agent::AgentConfig;
+use crate::llm::traits::ProviderType;
+use crate::telemetry::{AwsCredentialSource, ContentAnonymizer, TelemetryConfig};
+
+/// Variables read by [`from_env`](super::from_env)
+const VARIABLES: &[&str] = &[
+    "STOOD_PROVIDER",
+    "STOOD_MODEL",
+    "STOOD_TEMPERATURE",
+    "STOOD_MAX_TOKENS",
+    "STOOD_SYSTEM_PROMPT",
+    "STOOD_AGENT_ID",
+    "STOOD_AGENT_NAME",
+    "STOOD_STREAMING",
+    "STOOD_TIMEOUT",
+    "STOOD_LOG_LEVEL",
+    "STOOD_MAX_CYCLES",
+    "STOOD_MAX_DURATION",
+    "STOOD_MAX_TOTAL_TOKENS",
+    "STOOD_REASONING_TIMEOUT",
+    "STOOD_TOOL_BATCH_TIMEOUT",
+    "STOOD_EVALUATION_TIMEOUT",
+    "STOOD_SYNTHESIS_TIMEOUT",
+    "STOOD_CLOUDWATCH_ENABLED",
+    "STOOD_GENAI_CONTENT_CAPTURE",
+    "STOOD_GENAI_CONTENT_ANONYMIZE",
+];
+
+/// Other `STOOD_*` variables read elsewhere in the library, not reported as unknown
+const OTHER_VARIABLES: &[&str] = &[
+    "STOOD_DEFAULT_MODEL",
+    "STOOD_BEDROCK_TIMEOUT",
+    "STOOD_OTEL_CONSOLE_EXPORT",
+    "STOOD_TOOLS_MAX_PARALLEL",
+    "STOOD_TOOLS_TIMEOUT",
+    "STOOD_SHUTDOWN_TIMEOUT",
+    "STOOD_REQUEST_TIMEOUT",
+    "STOOD_FORCE_SHUTDOWN",
+    "STOOD_CONTEXT_MANAGEMENT",
+    "STOOD_AUTO_RETRY",
+    "STOOD_TOOL_HOT_RELOAD",
+    "STOOD_HEALTH_CHECKS",
+    "STOOD_METRICS_COLLECTION",
+    "STOOD_GRACEFUL_SHUTDOWN",
+    "STOOD_LOG_DIR",
+    "STOOD_LOG_MAX_SIZE",
+    "STOOD_LOG_MAX_FILES",
+    "STOOD_FILE_LOG_LEVEL",
+    "STOOD_CONSOLE_LOG_LEVEL",
+    "STOOD_CONSOLE_LOGGING",
+    "STOOD_JSON_LOGS",
+    "STOOD_PERFORMANCE_TRACING",
+    "STOOD_CYCLE_DETECTION",
+];
+
+const PROVIDERS: [ProviderType; 7] = [
+    ProviderType::Bedrock,
+    ProviderType::LmStudio,
+    ProviderType::Anthropic,
+    ProviderType::OpenAI,
+    ProviderType::Ollama,
+    ProviderType::OpenRouter,
+    ProviderType::Candle,
+];
+
+/// Provider named `name`, as in `STOOD_PROVIDER` and agent files
+pub(crate) fn parse_provider(name: &str) -> Result<ProviderType, ConfigError> {
+    let lowercase = name.to_lowercase();
+    PROVIDERS
+        .into_iter()
+        .find(|provider| provider.as_str() == lowercase)
+        .ok_or_else(|| ConfigError::Validation(format!("Unknown provider '{}'", name)))
+}
+
+/// Configuration read from the environment, with the problems found
+#[derive(Debug, Clone)]
+pub struct EnvConfig {
+    pub agent: AgentConfig,
+    pub execution: ExecutionConfig,
+    pub telemetry: TelemetryConfig,
+    pub report: EnvReport,
+}
+
+impl EnvConfig {
+    /// Read the configuration from `vars` instead of the process environment
+    pub fn from_vars<I, K, V>(vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut reader = EnvReader::new(vars);
+        let agent = reader.agent();
+        let execution = reader.execution();
+        let telemetry = reader.telemetry();
+        let report = reader.finish();
+        Self {
+            agent,
+            execution,
+            telemetry,
+            report,
+        }
+    }
+}
+
+/// Startup validation report of an [`EnvConfig`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvReport {
+    /// Variables that were set and read, in order
+    pub used: Vec<String>,
+    /// Problems found, in order
+    pub issues: Vec<EnvIssue>,
+}
+
+/// A problem with one environment variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvIssue {
+    pub variable: String,
+    pub kind: EnvIssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvIssueKind {
+    /// The variable is required by another setting but not set
+    Missing { reason: String },
+    /// The value cannot be used; the default applies instead
+    Invalid { value: String, expected: String },
+    /// A `STOOD_*` variable the library does not read (a warning)
+    Unknown { suggestion: Option<String> },
+}
+
+impl EnvIssue {
+    /// Whether the issue makes the configuration unusable
+    pub fn is_error(&self) -> bool {
+        !matches!(self.kind, EnvIssueKind::Unknown { .. })
+    }
+}
+
+impl fmt::Display for EnvIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            EnvIssueKind::Missing { reason } => {
+                write!(f, "{}: missing ({})", self.variable, reason)
+            }
+            EnvIssueKind::Invalid { value, expected } => write!(
+                f,
+                "{}: invalid value '{}' (expected {})",
+                self.variable, value, expected
+            ),
+            EnvIssueKind::Unknown { suggestion: None } => {
+                write!(f, "{}: unknown variable", self.variable)
+            }
+            EnvIssueKind::Unknown {
+                suggestion: Some(suggestion),
+            } => write!(
+                f,
+                "{}: unknown variable (did you mean {}?)",
+                self.variable, suggestion
+            ),
+        }
+    }
+}
+
+impl EnvReport {
+    /// Whether no errors were found; warnings are allowed
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Missing and invalid variables
+    pub fn errors(&self) -> impl Iterator<Item = &EnvIssue> {
+        self.issues.iter().filter(|issue| issue.is_error())
+    }
+
+    /// Unknown variables
+    pub fn warnings(&self) -> impl Iterator<Item = &EnvIssue> {
+        self.issues.iter().filter(|issue| !issue.is_error())
+    }
+
+    /// Log each issue, errors as errors and unknown variables as warnings
+    pub fn log(&self) {
+        for issue in &self.issues {
+            if issue.is_error() {
+                tracing::error!("Environment configuration: {}", issue);
+            } else {
+                tracing::warn!("Environment configuration: {}", issue);
+            }
+        }
+    }
+
+    /// `Ok` if there are no errors, otherwise an error listing all of them
+    pub fn into_result(self) -> Result<(), ConfigError> {
+        if self.is_valid() {
+            return Ok(());
+        }
+        let errors: Vec<String> = self.errors().map(|issue| issue.to_string()).collect();
+        Err(ConfigError::Validation(format!(
+            "Invalid environment configuration:\n  - {}",
+            errors.join("\n  - ")
+        )))
+    }
+}
+
+impl fmt::Display for EnvReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self.errors().count();
+        let warnings = self.issues.len() - errors;
+        write!(
+            f,
+            "Environment configuration: {} variables read, {} errors, {} warnings",
+            self.used.len(),
+            errors,
+            warnings
+        )?;
+        for issue in &self.issues {
+            let label = if issue.is_error() { "error" } else { "warning" };
+            write!(f, "\n  {}: {}", label, issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads variables, recording what was used and what was wrong
+pub(crate) struct EnvReader {
+    vars: BTreeMap<String, String>,
+    report: EnvReport,
+}
+
+impl EnvReader {
+    pub(crate) fn new<I, K, V>(vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            vars: vars
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+            report: EnvReport::default(),
+        }
+    }
+
+    /// Reader of the process environment
+    pub(crate) fn from_process() -> Self {
+        Self::new(std::env::vars())
+    }
+
+    /// Value of `name`; empty values count as unset
+    fn get(&mut self, name: &str) -> Option<String> {
+        let value = self
+            .vars
+            .get(name)
+            .filter(|v| !v.trim().is_empty())?
+            .clone();
+        if !self.report.used.iter().any(|used| used == name) {
+            self.report.used.push(name.to_string());
+        }
+        Some(value)
+    }
+
+    fn is_set(&self, name: &str) -> bool {
+        self.vars.get(name).is_some_and(|v| !v.trim().is_empty())
+    }
+
+    fn parse_with<T>(
+        &mut self,
+        name: &str,
+        expected: &str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Option<T> {
+        let value = self.get(name)?;
+        let parsed = parse(value.trim());
+        if parsed.is_none() {
+            self.issue(
+                name,
+                EnvIssueKind::Invalid {
+                    value,
+                    expected: expected.to_string(),
+                },
+            );
+        }
+        parsed
+    }
+
+    fn parse<T: FromStr>(&mut self, name: &str, expected: &str) -> Option<T> {
+        self.parse_with(name, expected, |v| v.parse().ok())
+    }
+
+    fn bool(&mut self, name: &str) -> Option<bool> {
+        self.parse_with(name, "true or false", |v| match v.to_lowercase().as_str() {
+            "true" | "1" | "yes" => Some(true),
+            "false" | "0" | "no" => Some(false),
+            _ => None,
+        })
+    }
+
+    fn seconds(&mut self, name: &str) -> Option<Duration> {
+        self.parse_with(name, "a positive number of seconds", |v| {
+            v.parse::<u64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+        })
+    }
+
+    fn missing(&mut self, name: &str, reason: &str) {
+        self.issue(
+            name,
+            EnvIssueKind::Missing {
+                reason: reason.to_string(),
+            },
+        );
+    }
+
+    fn issue(&mut self, name: &str, kind: EnvIssueKind) {
+        self.report.issues.push(EnvIssue {
+            variable: name.to_string(),
+            kind,
+        });
+    }
+
+    pub(crate) fn agent(&mut self) -> AgentConfig {
+        let mut config = AgentConfig::default();
+        if let Some(provider) = self.parse_with(
+            "STOOD_PROVIDER",
+            "one of bedrock, lm_studio, anthropic, openai, ollama, openrouter, candle",
+            |v| parse_provider(v).ok(),
+        ) {
+            config.provider = provider;
+        }
+        match self.get("STOOD_MODEL") {
+            Some(model) => config.model_id = model,
+            None if config.provider != ProviderType::Bedrock => {
+                self.missing("STOOD_MODEL", "required when STOOD_PROVIDER is not bedrock")
+            }
+            None => {}
+        }
+        if let Some(temperature) =
+            self.parse_with("STOOD_TEMPERATURE", "a number between 0.0 and 1.0", |v| {
+                v.parse::<f32>().ok().filter(|t| (0.0..=1.0).contains(t))
+            })
+        {
+            config.temperature = Some(temperature);
+        }
+        if let Some(max_tokens) = self.parse_with("STOOD_MAX_TOKENS", "a positive integer", |v| {
+            v.parse::<u32>().ok().filter(|n| *n > 0)
+        }) {
+            config.max_tokens = Some(max_tokens);
+        }
+        config.system_prompt = self.get("STOOD_SYSTEM_PROMPT");
+        config.agent_id = self.get("STOOD_AGENT_ID");
+        config.agent_name = self.get("STOOD_AGENT_NAME");
+        config
+    }
+
+    pub(crate) fn execution(&mut self) -> ExecutionConfig {
+        let mut config = ExecutionConfig::default();
+        if let Some(streaming) = self.bool("STOOD_STREAMING") {
+            config.streaming = streaming;
+            config.event_loop.enable_streaming = streaming;
+        }
+        if let Some(timeout) = self.seconds("STOOD_TIMEOUT") {
+            config.timeout = Some(timeout);
+        }
+        if let Some(level) = self.parse_with(
+            "STOOD_LOG_LEVEL",
+            "one of off, info, debug, trace",
+            |v| match v.to_lowercase().as_str() {
+                "off" => Some(LogLevel::Off),
+                "info" => Some(LogLevel::Info),
+                "debug" => Some(LogLevel::Debug),
+                "trace" => Some(LogLevel::Trace),
+                _ => None,
+            },
+        ) {
+            config.log_level = level;
+        }
+
+        let event_loop = &mut config.event_loop;
+        if let Some(max_cycles) = self.parse("STOOD_MAX_CYCLES", "a non-negative integer") {
+            event_loop.max_cycles = max_cycles;
+        }
+        if let Some(max_duration) = self.seconds("STOOD_MAX_DURATION") {
+            event_loop.max_duration = max_duration;
+        }
+        if let Some(tokens) = self.parse_with("STOOD_MAX_TOTAL_TOKENS", "a positive integer", |v| {
+            v.parse::<u32>().ok().filter(|n| *n > 0)
+        }) {
+            event_loop.max_total_tokens = Some(tokens);
+        }
+        let timeouts = &mut event_loop.phase_timeouts;
+        timeouts.reasoning = self.seconds("STOOD_REASONING_TIMEOUT");
+        timeouts.tool_batch = self.seconds("STOOD_TOOL_BATCH_TIMEOUT");
+        timeouts.evaluation = self.seconds("STOOD_EVALUATION_TIMEOUT");
+        timeouts.synthesis = self.seconds("STOOD_SYNTHESIS_TIMEOUT");
+        config
+    }
+
+    pub(crate) fn telemetry(&mut self) -> TelemetryConfig {
+        let service_name = self
+            .get("OTEL_SERVICE_NAME")
+            .unwrap_or_else(|| "stood-agent".to_string());
+        let cloudwatch = self.bool("STOOD_CLOUDWATCH_ENABLED").unwrap_or(false);
+        let enabled = cloudwatch || self.bool("OTEL_ENABLED").unwrap_or(false);
+        if !enabled {
+            return TelemetryConfig::Disabled {
+                service_name,
+                log_level: crate::telemetry::LogLevel::INFO,
+            };
+        }
+
+        let region = match self.get("AWS_REGION") {
+            Some(region) => region,
+            None => {
+                self.missing(
+                    "AWS_REGION",
+                    "required when STOOD_CLOUDWATCH_ENABLED is set; defaulting to us-east-1",
+                );
+                "us-east-1".to_string()
+            }
+        };
+        TelemetryConfig::CloudWatch {
+            region,
+            credentials: AwsCredentialSource::Environment,
+            service_name,
+            service_version: self
+                .get("OTEL_SERVICE_VERSION")
+                .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string()),
+            agent_id: self.get("STOOD_AGENT_ID"),
+            content_capture: self.bool("STOOD_GENAI_CONTENT_CAPTURE").unwrap_or(false),
+            content_anonymization: self
+                .bool("STOOD_GENAI_CONTENT_ANONYMIZE")
+                .unwrap_or(false)
+                .then(|| Arc::new(ContentAnonymizer::new())),
+            log_level: crate::telemetry::LogLevel::INFO,
+            skip_log_group_check: false,
+        }
+    }
+
+    /// Report unrecognized `STOOD_*` variables and return the report
+    pub(crate) fn finish(mut self) -> EnvReport {
+        let unknown: Vec<String> = self
+            .vars
+            .keys()
+            .filter(|name| name.starts_with("STOOD_"))
+            .filter(|name| !VARIABLES.contains(&name.as_str()))
+            .filter(|name| !OTHER_VARIABLES.contains(&name.as_str()))
+            .filter(|name| self.is_set(name))
+            .cloned()
+            .collect();
+        for name in unknown {
+            let suggestion = VARIABLES
+                .iter()
+                .map(|known| (edit_distance(&name, known), known))
+                .filter(|(distance, _)| *distance <= 2)
+                .min_by_key(|(distance, _)| *distance)
+                .map(|(_, known)| known.to_string());
+            self.issue(&name, EnvIssueKind::Unknown { suggestion });
+        }
+        self.report
+    }
+}
+
+/// Levenshtein distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_vars_builds_configs() {
+        let config = EnvConfig::from_vars([
+            ("STOOD_PROVIDER", "lm_studio"),
+            ("STOOD_MODEL", "google/gemma-3-12b"),
+            ("STOOD_TEMPERATURE", "0.2"),
+            ("STOOD_MAX_CYCLES", "4"),
+            ("STOOD_TOOL_BATCH_TIMEOUT", "90"),
+            ("STOOD_LOG_LEVEL", "debug"),
+            ("STOOD_CLOUDWATCH_ENABLED", "true"),
+            ("AWS_REGION", "eu-west-1"),
+            ("STOOD_AGENT_ID", "support-agent"),
+            ("HOME", "/root"),
+        ]);
+
+        assert!(config.report.issues.is_empty(), "{}", config.report);
+        assert_eq!(config.agent.provider, ProviderType::LmStudio);
+        assert_eq!(config.agent.model_id, "google/gemma-3-12b");
+        assert_eq!(config.agent.temperature, Some(0.2));
+        assert_eq!(config.execution.event_loop.max_cycles, 4);
+        assert_eq!(
+            config.execution.event_loop.phase_timeouts.tool_batch,
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(config.execution.log_level, LogLevel::Debug);
+        assert!(config.telemetry.is_enabled());
+        assert_eq!(config.telemetry.agent_id(), Some("support-agent"));
+        assert_eq!(config.report.used.len(), 9);
+    }
+
+    #[test]
+    fn test_report_lists_every_problem() {
+        let config = EnvConfig::from_vars([
+            ("STOOD_PROVIDER", "openai"),
+            ("STOOD_TEMPERATURE", "1.5"),
+            ("STOOD_MAX_DURATION", "soon"),
+            ("STOOD_CLOUDWATCH_ENABLED", "yes"),
+            ("STOOD_MAX_CYCLE", "3"),
+            ("STOOD_LOG_DIR", "/var/log/stood"),
+        ]);
+        let report = config.report;
+
+        let errors: Vec<String> = report.errors().map(|issue| issue.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "STOOD_MODEL: missing (required when STOOD_PROVIDER is not bedrock)",
+                "STOOD_TEMPERATURE: invalid value '1.5' (expected a number between 0.0 and 1.0)",
+                "STOOD_MAX_DURATION: invalid value 'soon' (expected a positive number of seconds)",
+                "AWS_REGION: missing (required when STOOD_CLOUDWATCH_ENABLED is set; defaulting to us-east-1)",
+            ]
+        );
+        let warnings: Vec<String> = report.warnings().map(|issue| issue.to_string()).collect();
+        assert_eq!(
+            warnings,
+            vec!["STOOD_MAX_CYCLE: unknown variable (did you mean STOOD_MAX_CYCLES?)"]
+        );
+
+        // Invalid values fall back to the defaults
+        assert_eq!(config.agent.temperature, Some(0.7));
+        assert!(!report.is_valid());
+        let err = report.into_result().unwrap_err().to_string();
+        assert!(err.contains("Invalid environment configuration:\n  - STOOD_MODEL: missing"));
+    }
+}
@@ -13,6 +13,20 @@ use std::{
 };
 use thiserror::Error;
 
+pub mod agent_file;
+pub mod environment;
+
+pub use agent_file::AgentFileConfig;
+pub use environment::{EnvConfig, EnvIssue, EnvIssueKind, EnvReport};
+
+/// Read the agent, execution and telemetry configuration from the `STOOD_*`
+/// environment variables, with a report of missing and invalid values
+///
+/// See [`environment`] for the variables.
+pub fn from_env() -> EnvConfig {
+    EnvConfig::from_vars(std::env::vars())
+}
+
 /// Configuration errors
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -0,0 +1,298 @@
+//! Conversation compaction by summarizing older turns.
+//!
+//! Unlike [`ContextManager::manage_context`], which drops old messages, compaction
+//! replaces older turns with a model-written summary so the agent keeps the
+//! important facts from earlier in the conversation. The event loop performs the
+//! model call; this module decides where the conversation can be split, renders
+//! the part being compacted, and splices the summary back in.
+//!
+//! Compaction can be requested by the model through the `compact_context` tool
+//! ([`CompactContextTool`](crate::tools::builtin::CompactContextTool)) or triggered
+//! automatically when context usage passes [`CompactionConfig::trigger_percentage`].
+
+use super::{truncate_text, ContextConfig, ContextManager, TruncationStrategy};
+use crate::types::{ContentBlock, Message, MessageRole, Messages};
+
+/// Prefix of the text block that carries a compaction summary
+pub const COMPACTION_SUMMARY_PREFIX: &str = "[Summary of earlier conversation]";
+
+/// Configuration for conversation compaction
+#[derive(Debug, Clone)]
+pub struct CompactionConfig {
+    /// Context window settings used to estimate usage
+    pub context: ContextConfig,
+    /// Compact automatically before a model call once usage exceeds this
+    /// percentage of the context window (`None` disables automatic compaction)
+    pub trigger_percentage: Option<f32>,
+    /// Number of most recent messages that are never compacted
+    pub keep_recent_messages: usize,
+    /// Maximum characters of conversation sent to the summarizer
+    pub max_transcript_chars: usize,
+    /// Target length of the summary in characters
+    pub max_summary_chars: usize,
+    /// Whether to give the model the `compact_context` tool
+    pub expose_tool: bool,
+    /// Whether the automatic compaction check counts tokens with the
+    /// provider's count-tokens API (one extra request per model call) instead
+    /// of the local estimate
+    pub use_provider_token_count: bool,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            context: ContextConfig::default(),
+            trigger_percentage: Some(70.0),
+            keep_recent_messages: 6,
+            max_transcript_chars: 200_000,
+            max_summary_chars: 4_000,
+            expose_tool: true,
+            use_provider_token_count: false,
+        }
+    }
+}
+
+impl CompactionConfig {
+    pub fn with_trigger_percentage(mut self, percentage: Option<f32>) -> Self {
+        self.trigger_percentage = percentage;
+        self
+    }
+
+    pub fn with_keep_recent_messages(mut self, count: usize) -> Self {
+        self.keep_recent_messages = count;
+        self
+    }
+
+    pub fn with_context_config(mut self, context: ContextConfig) -> Self {
+        self.context = context;
+        self
+    }
+
+    pub fn with_tool(mut self, expose_tool: bool) -> Self {
+        self.expose_tool = expose_tool;
+        self
+    }
+
+    pub fn with_provider_token_count(mut self, enabled: bool) -> Self {
+        self.use_provider_token_count = enabled;
+        self
+    }
+
+    /// Whether usage has passed the automatic compaction threshold
+    pub fn should_compact(&self, messages: &Messages) -> bool {
+        let usage = ContextManager::with_config(self.context.clone()).analyze_usage(messages);
+        self.should_compact_tokens(usage.estimated_tokens)
+    }
+
+    /// Whether a request of `tokens` input tokens has passed the automatic
+    /// compaction threshold
+    pub fn should_compact_tokens(&self, tokens: usize) -> bool {
+        let Some(trigger) = self.trigger_percentage else {
+            return false;
+        };
+        (tokens as f32 / self.context.max_tokens as f32) * 100.0 >= trigger
+    }
+
+    /// Build the summarization prompt for a rendered transcript
+    pub fn summary_prompt(&self, transcript: &str, focus: Option<&str>) -> String {
+        let focus = focus
+            .map(|f| format!("\nPay particular attention to: {}\n", f))
+            .unwrap_or_default();
+        format!(
+            "Summarize the earlier part of this conversation between a user and an AI \
+             assistant in at most {} characters. The summary replaces these messages, so \
+             preserve the user's goals, decisions made, facts learned, file names, \
+             identifiers, numbers, errors and any unfinished work.{}\n\
+             Respond with the summary only.\n\n<conversation>\n{}\n</conversation>",
+            self.max_summary_chars,
+            focus,
+            truncate_text(
+                transcript,
+                self.max_transcript_chars,
+                TruncationStrategy::HeadAndTail
+            )
+        )
+    }
+}
+
+/// Result of a compaction
+#[derive(Debug, Clone)]
+pub struct CompactionResult {
+    /// Number of messages replaced by the summary
+    pub messages_compacted: usize,
+    /// Estimated tokens before compaction
+    pub tokens_before: usize,
+    /// Estimated tokens after compaction
+    pub tokens_after: usize,
+}
+
+impl CompactionResult {
+    /// Estimated tokens freed by compaction
+    pub fn tokens_freed(&self) -> usize {
+        self.tokens_before.saturating_sub(self.tokens_after)
+    }
+}
+
+impl ContextManager {
+    /// Find where the conversation can be split for compaction.
+    ///
+    /// Returns the index of the first message to keep. The split is always at a
+    /// user message that starts a new turn (no tool results), so tool calls stay
+    /// paired with their results, and at least `keep_recent` messages are kept.
+    /// Returns `None` if fewer than two messages could be compacted.
+    pub fn compaction_split(&self, messages: &Messages, keep_recent: usize) -> Option<usize> {
+        let latest_allowed = messages.messages.len().checked_sub(keep_recent)?;
+        (2..=latest_allowed)
+            .rev()
+            .filter(|&i| i < messages.messages.len())
+            .find(|&i| {
+                let message = &messages.messages[i];
+                message.role == MessageRole::User
+                    && !message
+                        .content
+                        .iter()
+                        .any(|block| matches!(block, ContentBlock::ToolResult { .. }))
+            })
+    }
+
+    /// Replace the messages before `split` with `summary`.
+    ///
+    /// The summary is prepended to the message at `split` rather than added as a
+    /// separate message, which keeps user and assistant messages alternating.
+    pub fn apply_compaction(
+        &self,
+        messages: &mut Messages,
+        split: usize,
+        summary: &str,
+    ) -> CompactionResult {
+        let tokens_before = self.analyze_usage(messages).estimated_tokens;
+
+        messages.messages[split].content.insert(
+            0,
+            ContentBlock::Text {
+                text: format!("{}\n{}", COMPACTION_SUMMARY_PREFIX, summary.trim()),
+            },
+        );
+        messages.messages.drain(..split);
+
+        CompactionResult {
+            messages_compacted: split,
+            tokens_before,
+            tokens_after: self.analyze_usage(messages).estimated_tokens,
+        }
+    }
+}
+
+/// Render messages as a plain-text transcript for summarization
+pub fn render_transcript(messages: &[Message]) -> String {
+    let mut lines = Vec::new();
+    for message in messages {
+        let speaker = match message.role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
+            MessageRole::System => "System",
+        };
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text } => lines.push(format!("{}: {}", speaker, text)),
+                ContentBlock::ToolUse { name, input, .. } => lines.push(format!(
+                    "{} called tool '{}' with {}",
+                    speaker,
+                    name,
+                    truncate_text(&input.to_string(), 1_000, TruncationStrategy::Head)
+                )),
+                ContentBlock::ToolResult {
+                    content, is_error, ..
+                } => lines.push(format!(
+                    "Tool {}: {}",
+                    if *is_error { "error" } else { "result" },
+                    truncate_text(
+                        &content.to_display_string(),
+                        4_000,
+                        TruncationStrategy::HeadAndTail
+                    )
+                )),
+                ContentBlock::Thinking { .. } | ContentBlock::ReasoningContent { .. } => {}
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ToolResultContent;
+
+    fn conversation() -> Messages {
+        let mut messages = Messages::new();
+        messages.add_user_message("Find the config file");
+        messages.push(Message::new(
+            MessageRole::Assistant,
+            vec![ContentBlock::ToolUse {
+                id: "t1".to_string(),
+                name: "glob".to_string(),
+                input: serde_json::json!({"pattern": "**/*.toml"}),
+            }],
+        ));
+        messages.push(Message::new(
+            MessageRole::User,
+            vec![ContentBlock::ToolResult {
+                tool_use_id: "t1".to_string(),
+                content: ToolResultContent::text("Cargo.toml"),
+                is_error: false,
+            }],
+        ));
+        messages.push(Message::assistant("It is Cargo.toml"));
+        messages.add_user_message("Now read it");
+        messages.push(Message::assistant("Here it is"));
+        messages
+    }
+
+    #[test]
+    fn test_split_avoids_tool_results() {
+        let manager = ContextManager::new();
+        let messages = conversation();
+
+        // Index 4 is the only user message that starts a turn
+        assert_eq!(manager.compaction_split(&messages, 2), Some(4));
+        // Keeping 3 messages would require splitting at the tool result
+        assert_eq!(manager.compaction_split(&messages, 3), None);
+    }
+
+    #[test]
+    fn test_apply_compaction() {
+        let manager = ContextManager::new();
+        let mut messages = conversation();
+        let transcript = render_transcript(&messages.messages[..4]);
+        assert!(transcript.contains("called tool 'glob'"));
+        assert!(transcript.contains("Tool result: Cargo.toml"));
+
+        let result = manager.apply_compaction(&mut messages, 4, "The config is Cargo.toml.");
+        assert_eq!(result.messages_compacted, 4);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages.messages[0].role, MessageRole::User);
+        let first = messages.messages[0].content[0].clone();
+        assert!(
+            matches!(first, ContentBlock::Text { text } if text.starts_with(COMPACTION_SUMMARY_PREFIX))
+        );
+    }
+
+    #[test]
+    fn test_should_compact() {
+        let config = CompactionConfig::default().with_context_config(ContextConfig {
+            max_tokens: 100,
+            ..Default::default()
+        });
+        let mut messages = Messages::new();
+        messages.add_user_message("short");
+        assert!(!config.should_compact(&messages));
+        messages.add_user_message(&"x".repeat(400));
+        assert!(config.should_compact(&messages));
+        assert!(config.should_compact_tokens(70));
+        assert!(!config.should_compact_tokens(69));
+        assert!(!config
+            .with_trigger_percentage(None)
+            .should_compact(&messages));
+    }
+}
@@ -8,6 +8,9 @@
 //! - Proactive overflow prevention
 //! - Priority-based message retention strategies
 //! - Integration with conversation management systems
+//! - Size budgets for tool results before they enter the conversation
+//! - Compaction of older turns into a model-written summary
+//! - Precise token counts from provider count-tokens APIs
 
 use std::collections::HashMap;
 use tracing::{debug, info};
@@ -17,6 +20,13 @@ use crate::{
     Result,
 };
 
+pub mod compaction;
+pub mod token_count;
+pub mod tool_budget;
+pub use compaction::*;
+pub use token_count::*;
+pub use tool_budget::*;
+
 /// Configuration for context management behavior
 #[derive(Debug, Clone)]
 pub struct ContextConfig {
@@ -445,6 +455,7 @@ mod tests {
                     "I don't have access to real-time weather data.",
                 ),
             ],
+            system_prompt: None,
         }
     }
 
@@ -522,7 +533,7 @@ mod tests {
     #[test]
     fn test_content_breakdown() {
         let manager = ContextManager::new();
-        let mut messages = Messages { messages: vec![] };
+        let mut messages = Messages::new();
 
         // Add message with tool use
         messages.messages.push(Message {
@@ -596,7 +607,7 @@ mod tests {
         let manager = ContextManager::with_config(config);
 
         // Create messages that exceed the limit
-        let mut messages = Messages { messages: vec![] };
+        let mut messages = Messages::new();
         for i in 0..10 {
             messages.messages.push(create_test_message(
                 MessageRole::User,
@@ -0,0 +1,104 @@
+//! Token counting with provider count-tokens APIs.
+//!
+//! Local estimates (about four characters per token) can be off by 20% or more
+//! for code, non-English text and tool schemas. Where the provider offers a
+//! count-tokens API (Bedrock CountTokens for Claude, Anthropic's
+//! `/v1/messages/count_tokens`), [`ContextManager::count_tokens`] uses it for a
+//! precise count and falls back to the local estimate otherwise.
+
+use tracing::debug;
+
+use super::ContextManager;
+use crate::llm::traits::{LlmProvider, Tool};
+use crate::types::Messages;
+
+/// Where a token count came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenCountSource {
+    /// The provider's count-tokens API
+    Provider,
+    /// The local character-based estimate
+    Estimate,
+}
+
+/// Input tokens of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenCount {
+    pub tokens: usize,
+    pub source: TokenCountSource,
+}
+
+impl TokenCount {
+    /// Whether the count came from the provider
+    pub fn is_exact(&self) -> bool {
+        self.source == TokenCountSource::Provider
+    }
+}
+
+impl ContextManager {
+    /// Count the input tokens of a request, using the provider's count-tokens
+    /// API when it is available and the local estimate otherwise
+    pub async fn count_tokens(
+        &self,
+        provider: &dyn LlmProvider,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+    ) -> TokenCount {
+        match provider.count_tokens(model_id, messages, tools).await {
+            Ok(Some(tokens)) => {
+                return TokenCount {
+                    tokens: tokens as usize,
+                    source: TokenCountSource::Provider,
+                }
+            }
+            Ok(None) => {}
+            Err(e) => debug!("Provider token count failed, using estimate: {}", e),
+        }
+        TokenCount {
+            tokens: self.estimate_request_tokens(messages, tools),
+            source: TokenCountSource::Estimate,
+        }
+    }
+
+    /// Local estimate of the input tokens of a request, including the system
+    /// prompt and tool definitions
+    pub fn estimate_request_tokens(&self, messages: &Messages, tools: &[Tool]) -> usize {
+        let extra_chars = messages.system_prompt.as_ref().map_or(0, String::len)
+            + tools
+                .iter()
+                .map(|tool| {
+                    tool.name.len() + tool.description.len() + tool.input_schema.to_string().len()
+                })
+                .sum::<usize>();
+        self.analyze_usage(messages).estimated_tokens
+            + (extra_chars as f32 / self.config.chars_per_token).ceil() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::providers::OllamaProvider;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_falls_back_to_estimate() {
+        let provider = OllamaProvider::new("http://localhost:11434".to_string())
+            .await
+            .unwrap();
+        let mut messages = Messages::new();
+        messages.add_user_message(&"a".repeat(400));
+        let tools = vec![Tool {
+            name: "search".to_string(),
+            description: "Search the web".to_string(),
+            input_schema: json!({"type": "object"}),
+        }];
+
+        let count = ContextManager::new()
+            .count_tokens(&provider, "llama3", &messages, &tools)
+            .await;
+        assert!(!count.is_exact());
+        assert_eq!(count.tokens, 100 + 10);
+    }
+}
@@ -0,0 +1,248 @@
+//! Size budgets for tool results entering the conversation.
+//!
+//! Large tool outputs (file dumps, HTTP bodies, query results) are the most common
+//! cause of context overflow. A [`ToolOutputBudget`] caps how many bytes a single
+//! tool result and a whole cycle's worth of tool results may contribute, shrinking
+//! oversized results before they are added to the conversation instead of waiting
+//! for the provider to reject the request.
+
+use std::collections::HashMap;
+
+use super::ContextConfig;
+
+/// How an oversized tool result is shrunk to fit its budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TruncationStrategy {
+    /// Keep the beginning of the output
+    Head,
+    /// Keep the end of the output (useful for logs and build output)
+    Tail,
+    /// Keep the beginning and end, dropping the middle
+    #[default]
+    HeadAndTail,
+    /// Ask the model to summarize the output, falling back to `HeadAndTail`
+    /// if summarization fails or the summary is still too large
+    Summarize,
+}
+
+/// Byte budgets for tool results, set on
+/// [`ExecutorConfig::output_budget`](crate::tools::ExecutorConfig::output_budget)
+#[derive(Debug, Clone)]
+pub struct ToolOutputBudget {
+    /// Maximum bytes for a single tool result
+    pub max_bytes_per_tool: usize,
+    /// Overrides of `max_bytes_per_tool` keyed by tool name
+    pub per_tool_limits: HashMap<String, usize>,
+    /// Maximum combined bytes for all tool results in one cycle
+    pub max_bytes_per_cycle: usize,
+    /// How oversized results are shrunk
+    pub strategy: TruncationStrategy,
+}
+
+impl Default for ToolOutputBudget {
+    fn default() -> Self {
+        Self {
+            max_bytes_per_tool: 50_000,
+            per_tool_limits: HashMap::new(),
+            max_bytes_per_cycle: 150_000,
+            strategy: TruncationStrategy::default(),
+        }
+    }
+}
+
+impl ToolOutputBudget {
+    /// Create a budget with the given per-tool and per-cycle limits
+    pub fn new(max_bytes_per_tool: usize, max_bytes_per_cycle: usize) -> Self {
+        Self {
+            max_bytes_per_tool,
+            max_bytes_per_cycle,
+            ..Default::default()
+        }
+    }
+
+    /// Derive a budget from a context window configuration.
+    ///
+    /// A cycle's tool results may use `fraction` of the safe context size, and a
+    /// single result may use half of that.
+    pub fn from_context_config(config: &ContextConfig, fraction: f32) -> Self {
+        let safe_tokens = config.max_tokens as f32 * config.buffer_percentage;
+        let per_cycle = (safe_tokens * fraction.clamp(0.0, 1.0) * config.chars_per_token) as usize;
+        Self::new((per_cycle / 2).max(1), per_cycle.max(1))
+    }
+
+    pub fn with_strategy(mut self, strategy: TruncationStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set a specific limit for one tool
+    pub fn with_tool_limit(mut self, tool_name: impl Into<String>, max_bytes: usize) -> Self {
+        self.per_tool_limits.insert(tool_name.into(), max_bytes);
+        self
+    }
+
+    /// Byte limit for a tool, before any per-cycle reduction
+    pub fn limit_for(&self, tool_name: &str) -> usize {
+        self.per_tool_limits
+            .get(tool_name)
+            .copied()
+            .unwrap_or(self.max_bytes_per_tool)
+    }
+
+    /// Compute the byte allowance for each result in a cycle.
+    ///
+    /// Each result first gets its per-tool limit. If the combined allowance still
+    /// exceeds the cycle budget, the budget is shared so that small results keep
+    /// their full size and the remainder is split evenly among the larger ones.
+    pub fn allocate(&self, results: &[(&str, usize)]) -> Vec<usize> {
+        let mut wanted: Vec<usize> = results
+            .iter()
+            .map(|(name, size)| (*size).min(self.limit_for(name)))
+            .collect();
+
+        if wanted.iter().sum::<usize>() <= self.max_bytes_per_cycle {
+            return wanted;
+        }
+
+        let mut remaining_budget = self.max_bytes_per_cycle;
+        let mut unsettled: Vec<usize> = (0..wanted.len()).collect();
+        // Settle results that fit within an even share, then redistribute
+        loop {
+            let share = remaining_budget / unsettled.len().max(1);
+            let (fits, too_big): (Vec<usize>, Vec<usize>) =
+                unsettled.iter().partition(|&&i| wanted[i] <= share);
+            if fits.is_empty() {
+                for i in too_big {
+                    wanted[i] = share;
+                }
+                return wanted;
+            }
+            for &i in &fits {
+                remaining_budget -= wanted[i];
+            }
+            unsettled = too_big;
+            if unsettled.is_empty() {
+                return wanted;
+            }
+        }
+    }
+
+    /// Shrink text to at most `max_bytes` using a non-model strategy.
+    ///
+    /// `Summarize` is treated as `HeadAndTail` here; model summarization is
+    /// performed by the event loop, which has access to the provider.
+    pub fn truncate(&self, text: &str, max_bytes: usize) -> String {
+        truncate_text(text, max_bytes, self.strategy)
+    }
+}
+
+/// Shrink text to at most `max_bytes`, inserting a marker describing what was removed
+pub fn truncate_text(text: &str, max_bytes: usize, strategy: TruncationStrategy) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+
+    // Size the marker for the worst case so the kept text can be computed up front
+    let marker_len = format!("\n[... {} bytes truncated ...]\n", text.len()).len();
+    let available = max_bytes.saturating_sub(marker_len);
+    let marker = format!("\n[... {} bytes truncated ...]\n", text.len() - available);
+
+    match strategy {
+        TruncationStrategy::Head => {
+            format!(
+                "{}{}",
+                &text[..floor_char_boundary(text, available)],
+                marker
+            )
+        }
+        TruncationStrategy::Tail => {
+            let start = ceil_char_boundary(text, text.len() - available);
+            format!("{}{}", marker, &text[start..])
+        }
+        TruncationStrategy::HeadAndTail | TruncationStrategy::Summarize => {
+            let head_end = floor_char_boundary(text, available / 2);
+            let tail_start = ceil_char_boundary(text, text.len() - (available - available / 2));
+            format!("{}{}{}", &text[..head_end], marker, &text[tail_start..])
+        }
+    }
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+    index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_strategies() {
+        let text = "a".repeat(100) + &"z".repeat(100);
+
+        let head = truncate_text(&text, 80, TruncationStrategy::Head);
+        assert!(head.len() <= 80);
+        assert!(head.starts_with("aaa") && head.contains("truncated"));
+
+        let tail = truncate_text(&text, 80, TruncationStrategy::Tail);
+        assert!(tail.len() <= 80);
+        assert!(tail.ends_with("zzz"));
+
+        let both = truncate_text(&text, 80, TruncationStrategy::HeadAndTail);
+        assert!(both.len() <= 80);
+        assert!(both.starts_with('a') && both.ends_with('z'));
+
+        assert_eq!(
+            truncate_text("short", 80, TruncationStrategy::Head),
+            "short"
+        );
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        let text = "é".repeat(100);
+        let truncated = truncate_text(&text, 60, TruncationStrategy::HeadAndTail);
+        assert!(truncated.len() <= 60);
+    }
+
+    #[test]
+    fn test_allocate_shares_cycle_budget() {
+        let budget = ToolOutputBudget::new(1_000, 1_200).with_tool_limit("small_tool", 100);
+
+        // Under budget: per-tool limits only
+        assert_eq!(budget.allocate(&[("a", 500), ("b", 600)]), vec![500, 600]);
+
+        // Per-tool override applies
+        assert_eq!(budget.allocate(&[("small_tool", 500)]), vec![100]);
+
+        // Small results keep their size, large ones split the remainder
+        assert_eq!(
+            budget.allocate(&[("a", 200), ("b", 5_000), ("c", 5_000)]),
+            vec![200, 500, 500]
+        );
+    }
+
+    #[test]
+    fn test_from_context_config() {
+        let config = ContextConfig {
+            max_tokens: 100_000,
+            buffer_percentage: 0.5,
+            chars_per_token: 4.0,
+            ..Default::default()
+        };
+        let budget = ToolOutputBudget::from_context_config(&config, 0.25);
+        assert_eq!(budget.max_bytes_per_cycle, 50_000);
+        assert_eq!(budget.max_bytes_per_tool, 25_000);
+    }
+}
@@ -130,6 +130,8 @@
 
 use thiserror::Error;
 
+use crate::error_recovery::{RetryHistory, RetryLayer};
+
 /// Main error type for the Stood library
 #[derive(Error, Debug, Clone)]
 pub enum StoodError {
@@ -174,9 +176,14 @@ pub enum StoodError {
     #[error("AWS resource not found: {message}")]
     ResourceNotFound { message: String },
 
-    /// AWS quota/limit exceeded
-    #[error("AWS quota exceeded: {message}")]
-    QuotaExceeded { message: String },
+    /// AWS quota/limit exceeded, or a session quota enforced by a
+    /// [`QuotaManager`](crate::agent::quota::QuotaManager)
+    #[error("Quota exceeded: {message}")]
+    QuotaExceeded {
+        message: String,
+        /// Set when a session quota was exceeded
+        violation: Option<crate::agent::quota::QuotaViolation>,
+    },
 
     /// Network-related errors
     #[error("Network error: {message}")]
@@ -187,8 +194,27 @@ pub enum StoodError {
     SerializationError { message: String },
 
     /// Timeout errors
-    #[error("Timeout error: operation timed out after {timeout_ms}ms")]
-    TimeoutError { timeout_ms: u64 },
+    #[error(
+        "Timeout error: {} timed out after {timeout_ms}ms",
+        .phase.map_or_else(|| "operation".to_string(), |phase| format!("{} phase", phase))
+    )]
+    TimeoutError {
+        timeout_ms: u64,
+        /// Set when an [`ExecutionPhase`](crate::agent::phase_timeouts::ExecutionPhase)
+        /// exceeded its timeout
+        phase: Option<crate::agent::phase_timeouts::ExecutionPhase>,
+    },
+
+    /// An execution did not finish before its [`Deadline`](crate::agent::Deadline)
+    #[error(
+        "Deadline exceeded after {} cycles and {}ms",
+        .partial.execution.cycles,
+        .partial.duration.as_millis()
+    )]
+    DeadlineExceeded {
+        /// What the execution produced before the deadline
+        partial: Box<crate::agent::AgentResult>,
+    },
 
     /// Internal library errors
     #[error("Internal error: {message}")]
@@ -270,6 +296,7 @@ impl StoodError {
     pub fn quota_exceeded(message: impl Into<String>) -> Self {
         Self::QuotaExceeded {
             message: message.into(),
+            violation: None,
         }
     }
 
@@ -289,7 +316,28 @@ impl StoodError {
 
     /// Create a TimeoutError
     pub fn timeout_error(timeout_ms: u64) -> Self {
-        Self::TimeoutError { timeout_ms }
+        Self::TimeoutError {
+            timeout_ms,
+            phase: None,
+        }
+    }
+
+    /// Create a TimeoutError for an execution phase that exceeded `timeout`
+    pub fn phase_timeout(
+        phase: crate::agent::phase_timeouts::ExecutionPhase,
+        timeout: std::time::Duration,
+    ) -> Self {
+        Self::TimeoutError {
+            timeout_ms: timeout.as_millis() as u64,
+            phase: Some(phase),
+        }
+    }
+
+    /// Create a DeadlineExceeded error carrying the partial result
+    pub fn deadline_exceeded(partial: crate::agent::AgentResult) -> Self {
+        Self::DeadlineExceeded {
+            partial: Box::new(partial),
+        }
     }
 
     /// Create an InternalError
@@ -852,6 +900,20 @@ impl From<serde_json::Error> for StoodError {
     }
 }
 
+/// Map speech service errors to StoodError
+impl From<crate::audio::AudioError> for StoodError {
+    fn from(error: crate::audio::AudioError) -> Self {
+        use crate::audio::AudioError;
+        match error {
+            AudioError::UnsupportedFormat(_) => StoodError::invalid_input(error.to_string()),
+            AudioError::Configuration(_) => StoodError::configuration_error(error.to_string()),
+            AudioError::Network(_) => StoodError::network_error(error.to_string()),
+            AudioError::Service { .. } => StoodError::model_error(error.to_string()),
+            AudioError::Timeout { timeout_ms, .. } => StoodError::timeout_error(timeout_ms),
+        }
+    }
+}
+
 /// Map ToolError to StoodError for unified error handling
 impl From<crate::tools::ToolError> for StoodError {
     fn from(error: crate::tools::ToolError) -> Self {
@@ -865,12 +927,18 @@ impl From<crate::tools::ToolError> for StoodError {
             crate::tools::ToolError::DuplicateTool { name } => {
                 StoodError::configuration_error(format!("Duplicate tool name: '{}'", name))
             }
-            crate::tools::ToolError::ExecutionFailed { message } => {
+            crate::tools::ToolError::ExecutionFailed { message, .. } => {
                 StoodError::tool_error(format!("Tool execution failed: {}", message))
             }
             crate::tools::ToolError::ToolNotAvailable { name } => {
                 StoodError::tool_error(format!("Tool '{}' is not available", name))
             }
+            crate::tools::ToolError::ManifestRejected { name, reason } => {
+                StoodError::configuration_error(format!(
+                    "Tool '{}' manifest rejected: {}",
+                    name, reason
+                ))
+            }
         }
     }
 }
@@ -996,12 +1064,24 @@ where
 
                 // Don't retry if error is not retryable or we've exceeded max retries
                 if !error.is_retryable() || attempt >= config.max_retries {
+                    RetryHistory::record_current(RetryLayer::Error, attempt + 1, &error, None);
                     return Err(error);
                 }
 
-                // Calculate delay and sleep
+                // Calculate delay and sleep, unless the retry budget is spent
                 let delay_ms = config.calculate_delay(attempt + 1);
-                tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+                let delay = tokio::time::Duration::from_millis(delay_ms);
+                let granted = crate::error_recovery::RetryBudget::acquire_current(delay);
+                RetryHistory::record_current(
+                    RetryLayer::Error,
+                    attempt + 1,
+                    &error,
+                    granted.then_some(delay),
+                );
+                if !granted {
+                    return Err(error);
+                }
+                tokio::time::sleep(delay).await;
 
                 attempt += 1;
             }
@@ -0,0 +1,224 @@
+//! Retry budget shared by every retry layer of an execution.
+//!
+//! Provider-level retries ([`crate::llm::providers::retry`]) and
+//! [`RetryExecutor`](super::RetryExecutor) each have their own attempt limits,
+//! so when both retry the same failure the worst-case latency is the product
+//! of the two. A [`RetryBudget`] caps the total number of retries and the time
+//! spent retrying across all layers: every retry first draws from the budget,
+//! and once it is spent, failures are returned instead of retried.
+//!
+//! An agent configured with [`AgentBuilder::with_retry_budget`] gives each
+//! execution a fresh budget and reports what was consumed in
+//! [`AgentResult::retry_budget`]. Outside an agent, run code under a budget
+//! with [`RetryBudget::scope`]:
+//!
+//! ```
+//! use std::time::Duration;
+//! use stood::error_recovery::RetryBudget;
+//!
+//! # async fn example() {
+//! let budget = RetryBudget::new(3).with_max_retry_time(Duration::from_secs(30));
+//! budget.clone().scope(async {
+//!     // Retries made here, at any layer, count against `budget`
+//! }).await;
+//! println!("{} retries used", budget.usage().retries);
+//! # }
+//! ```
+//!
+//! [`AgentBuilder::with_retry_budget`]: crate::agent::AgentBuilder::with_retry_budget
+//! [`AgentResult::retry_budget`]: crate::agent::AgentResult::retry_budget
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::deadline::Deadline;
+
+tokio::task_local! {
+    static CURRENT_BUDGET: RetryBudget;
+}
+
+/// Limit on retries shared across retry layers
+///
+/// Clones share the same counters.
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    max_retries: u32,
+    max_retry_time: Option<Duration>,
+    state: Arc<Mutex<BudgetState>>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    started: Instant,
+    usage: RetryBudgetUsage,
+}
+
+/// What a [`RetryBudget`] has consumed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryBudgetUsage {
+    /// Retries granted
+    pub retries: u32,
+    /// Total backoff delay of the granted retries
+    pub backoff: Duration,
+    /// Retries refused because the budget was spent
+    pub denied: u32,
+}
+
+impl RetryBudgetUsage {
+    /// Whether any retry was refused
+    pub fn is_exhausted(&self) -> bool {
+        self.denied > 0
+    }
+}
+
+impl RetryBudget {
+    /// Allow at most `max_retries` retries in total
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            max_retry_time: None,
+            state: Arc::new(Mutex::new(BudgetState {
+                started: Instant::now(),
+                usage: RetryBudgetUsage::default(),
+            })),
+        }
+    }
+
+    /// Refuse retries whose backoff would end more than `max_retry_time`
+    /// after the budget was created
+    pub fn with_max_retry_time(mut self, max_retry_time: Duration) -> Self {
+        self.max_retry_time = Some(max_retry_time);
+        self
+    }
+
+    /// A budget with the same limits and nothing consumed
+    pub fn fresh(&self) -> Self {
+        Self {
+            max_retries: self.max_retries,
+            max_retry_time: self.max_retry_time,
+            state: Arc::new(Mutex::new(BudgetState {
+                started: Instant::now(),
+                usage: RetryBudgetUsage::default(),
+            })),
+        }
+    }
+
+    /// Maximum number of retries
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Maximum time from the creation of the budget to the end of a retry's
+    /// backoff
+    pub fn max_retry_time(&self) -> Option<Duration> {
+        self.max_retry_time
+    }
+
+    /// Ask for one retry after waiting `delay`
+    ///
+    /// Returns `false`, and records the refusal, if the retry limit is
+    /// reached or the wait would end past the time limit.
+    pub fn try_acquire(&self, delay: Duration) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let out_of_time = self
+            .max_retry_time
+            .is_some_and(|limit| state.started.elapsed() + delay > limit);
+        if state.usage.retries >= self.max_retries || out_of_time {
+            state.usage.denied += 1;
+            return false;
+        }
+        state.usage.retries += 1;
+        state.usage.backoff += delay;
+        true
+    }
+
+    /// Retries left before the limit is reached
+    pub fn remaining_retries(&self) -> u32 {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.max_retries.saturating_sub(state.usage.retries)
+    }
+
+    /// What the budget has consumed so far
+    pub fn usage(&self) -> RetryBudgetUsage {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .usage
+            .clone()
+    }
+
+    /// Run `future` with this budget as the [`current`](Self::current) one
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_BUDGET.scope(self, future).await
+    }
+
+    /// The budget of the enclosing [`scope`](Self::scope), if any
+    pub fn current() -> Option<Self> {
+        CURRENT_BUDGET.try_with(Clone::clone).ok()
+    }
+
+    /// Ask the current budget for a retry; always granted outside a scope
+    ///
+    /// Refused without drawing on the budget when waiting `delay` would pass
+    /// the current [`Deadline`].
+    pub(crate) fn acquire_current(delay: Duration) -> bool {
+        Deadline::allows_current_retry(delay)
+            && Self::current().is_none_or(|budget| budget.try_acquire(delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_limits_retries_and_time() {
+        let budget = RetryBudget::new(2);
+        let shared = budget.clone();
+        assert!(budget.try_acquire(Duration::from_millis(10)));
+        assert!(shared.try_acquire(Duration::from_millis(20)));
+        assert!(!budget.try_acquire(Duration::ZERO));
+        assert_eq!(
+            shared.usage(),
+            RetryBudgetUsage {
+                retries: 2,
+                backoff: Duration::from_millis(30),
+                denied: 1,
+            }
+        );
+        assert_eq!(budget.fresh().remaining_retries(), 2);
+
+        let timed = RetryBudget::new(10).with_max_retry_time(Duration::from_secs(1));
+        assert!(timed.try_acquire(Duration::from_millis(500)));
+        assert!(!timed.try_acquire(Duration::from_secs(2)));
+        assert!(timed.usage().is_exhausted());
+    }
+
+    #[tokio::test]
+    async fn test_scope_sets_current_budget() {
+        assert!(RetryBudget::current().is_none());
+        assert!(RetryBudget::acquire_current(Duration::ZERO));
+
+        let budget = RetryBudget::new(1);
+        budget
+            .clone()
+            .scope(async {
+                assert!(RetryBudget::acquire_current(Duration::ZERO));
+                assert!(!RetryBudget::acquire_current(Duration::ZERO));
+            })
+            .await;
+        assert_eq!(budget.usage().retries, 1);
+
+        let budget = RetryBudget::new(5);
+        Deadline::after(Duration::from_secs(10))
+            .scope(budget.clone().scope(async {
+                assert!(!RetryBudget::acquire_current(Duration::from_secs(30)));
+                assert!(RetryBudget::acquire_current(Duration::from_secs(1)));
+            }))
+            .await;
+        assert_eq!(budget.usage().retries, 1);
+    }
+}
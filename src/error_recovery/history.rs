@@ -0,0 +1,263 @@
+//! History of the retries made during an execution.
+//!
+//! Provider-level retries ([`crate::llm::providers::retry`]),
+//! [`RetryExecutor`](super::RetryExecutor) and
+//! [`retry_with_backoff`](crate::error::retry_with_backoff) record every failed
+//! attempt in the [`RetryHistory`] of the enclosing scope, and
+//! [`CircuitBreaker`](super::CircuitBreaker)s record when they open. Agents
+//! give each execution a fresh history and report it in
+//! [`ExecutionDetails::retries`], which answers "why did this call take 90
+//! seconds?" without digging through logs:
+//!
+//! ```no_run
+//! # async fn example(agent: &mut stood::agent::Agent) -> Result<(), Box<dyn std::error::Error>> {
+//! let result = agent.execute("Summarize the report").await?;
+//! let retries = &result.execution.retries;
+//! println!(
+//!     "{} retries, {:?} backing off, {} circuit breaker trips",
+//!     retries.retries(),
+//!     retries.total_backoff,
+//!     retries.circuit_breaker_trips
+//! );
+//! for attempt in &retries.attempts {
+//!     println!("{:?} attempt {}: {}", attempt.layer, attempt.attempt, attempt.classification);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ExecutionDetails::retries`]: crate::agent::ExecutionDetails::retries
+
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+tokio::task_local! {
+    static CURRENT_HISTORY: RetryHistory;
+}
+
+/// Retry layer that made an attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryLayer {
+    /// Provider-level retries of LLM calls
+    Provider,
+    /// [`RetryExecutor`](super::RetryExecutor)
+    Executor,
+    /// [`retry_with_backoff`](crate::error::retry_with_backoff)
+    Error,
+}
+
+/// A failed attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryAttempt {
+    /// Layer that made the attempt
+    pub layer: RetryLayer,
+    /// Attempt number within the layer's retry loop, starting at 1
+    pub attempt: u32,
+    /// Kind of error, e.g. `ThrottlingError` or `NetworkError`
+    pub classification: String,
+    /// Error message
+    pub error: String,
+    /// Backoff before the next attempt, `None` if the attempt was not retried
+    pub backoff: Option<Duration>,
+    /// Time from the start of the history to the failure
+    pub elapsed: Duration,
+}
+
+/// Retries made during an execution
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetrySummary {
+    /// Failed attempts, in order
+    pub attempts: Vec<RetryAttempt>,
+    /// Total backoff delay before retries
+    pub total_backoff: Duration,
+    /// Times a circuit breaker opened
+    pub circuit_breaker_trips: u32,
+    /// Retries refused because their backoff would end after the deadline
+    #[serde(default)]
+    pub deadline_refusals: u32,
+}
+
+impl RetrySummary {
+    /// Number of failed attempts that were retried
+    pub fn retries(&self) -> usize {
+        self.attempts.iter().filter(|a| a.backoff.is_some()).count()
+    }
+
+    /// Whether nothing failed
+    pub fn is_empty(&self) -> bool {
+        self.attempts.is_empty() && self.circuit_breaker_trips == 0 && self.deadline_refusals == 0
+    }
+}
+
+/// Recorder of failed attempts across retry layers
+///
+/// Clones share the same history.
+#[derive(Debug, Clone)]
+pub struct RetryHistory {
+    started: Instant,
+    summary: Arc<Mutex<RetrySummary>>,
+}
+
+impl Default for RetryHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryHistory {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            summary: Arc::new(Mutex::new(RetrySummary::default())),
+        }
+    }
+
+    /// Record a failed attempt, retried after `backoff` if it is set
+    pub fn record_attempt<E: fmt::Debug + fmt::Display>(
+        &self,
+        layer: RetryLayer,
+        attempt: u32,
+        error: &E,
+        backoff: Option<Duration>,
+    ) {
+        let mut summary = self.summary.lock().unwrap_or_else(|e| e.into_inner());
+        summary.total_backoff += backoff.unwrap_or_default();
+        summary.attempts.push(RetryAttempt {
+            layer,
+            attempt,
+            classification: classify(error),
+            error: error.to_string(),
+            backoff,
+            elapsed: self.started.elapsed(),
+        });
+    }
+
+    /// Record that a circuit breaker opened
+    pub fn record_circuit_breaker_trip(&self) {
+        self.summary
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .circuit_breaker_trips += 1;
+    }
+
+    /// Record that a retry was refused because of the deadline
+    pub fn record_deadline_refusal(&self) {
+        self.summary
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .deadline_refusals += 1;
+    }
+
+    /// Retries refused because of the deadline so far
+    pub fn deadline_refusals(&self) -> u32 {
+        self.summary
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .deadline_refusals
+    }
+
+    /// What has been recorded so far
+    pub fn summary(&self) -> RetrySummary {
+        self.summary
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Run `future` with this history as the [`current`](Self::current) one
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_HISTORY.scope(self, future).await
+    }
+
+    /// The history of the enclosing [`scope`](Self::scope), if any
+    pub fn current() -> Option<Self> {
+        CURRENT_HISTORY.try_with(Clone::clone).ok()
+    }
+
+    /// Record a failed attempt in the current history, if any
+    pub(crate) fn record_current<E: fmt::Debug + fmt::Display>(
+        layer: RetryLayer,
+        attempt: u32,
+        error: &E,
+        backoff: Option<Duration>,
+    ) {
+        if let Some(history) = Self::current() {
+            history.record_attempt(layer, attempt, error, backoff);
+        }
+    }
+
+    /// Record a circuit breaker trip in the current history, if any
+    pub(crate) fn record_current_circuit_breaker_trip() {
+        if let Some(history) = Self::current() {
+            history.record_circuit_breaker_trip();
+        }
+    }
+
+    /// Record a retry refused because of the deadline in the current history, if any
+    pub(crate) fn record_current_deadline_refusal() {
+        if let Some(history) = Self::current() {
+            history.record_deadline_refusal();
+        }
+    }
+}
+
+/// Kind of an error: its variant name, or `Error` if it has none
+pub(crate) fn classify<E: fmt::Debug>(error: &E) -> String {
+    let debug = format!("{:?}", error);
+    let kind: String = debug
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    if kind.is_empty() {
+        "Error".to_string()
+    } else {
+        kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StoodError;
+
+    #[tokio::test]
+    async fn test_scope_records_attempts_and_trips() {
+        RetryHistory::record_current(RetryLayer::Error, 1, &"ignored", None);
+
+        let history = RetryHistory::new();
+        history
+            .clone()
+            .scope(async {
+                let throttled = StoodError::throttling_error("slow down");
+                RetryHistory::record_current(
+                    RetryLayer::Provider,
+                    1,
+                    &throttled,
+                    Some(Duration::from_millis(100)),
+                );
+                RetryHistory::record_current(
+                    RetryLayer::Provider,
+                    2,
+                    &throttled,
+                    Some(Duration::from_millis(200)),
+                );
+                RetryHistory::record_current(RetryLayer::Executor, 1, &"gave up", None);
+                RetryHistory::record_current_circuit_breaker_trip();
+            })
+            .await;
+
+        let summary = history.summary();
+        assert_eq!(summary.attempts.len(), 3);
+        assert_eq!(summary.retries(), 2);
+        assert_eq!(summary.total_backoff, Duration::from_millis(300));
+        assert_eq!(summary.circuit_breaker_trips, 1);
+        assert_eq!(summary.attempts[0].classification, "ThrottlingError");
+        assert_eq!(summary.attempts[2].classification, "Error");
+        assert_eq!(summary.attempts[2].error, "gave up");
+    }
+}
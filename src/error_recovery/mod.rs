@@ -10,11 +10,20 @@
 //! - `BackoffStrategy`: Different backoff strategies (exponential, fixed, linear)
 //! - `ContextRecovery`: Handle context window overflow by truncating messages
 //! - `CircuitBreaker`: Prevent cascading failures with circuit breaker pattern
+//! - `RetryBudget`: Bound the total retries of an execution across retry layers
+//! - `RetryHistory`: Record the failed attempts of an execution across retry layers
+
+pub mod budget;
+pub mod history;
+
+pub use budget::{RetryBudget, RetryBudgetUsage};
+pub use history::{RetryAttempt, RetryHistory, RetryLayer, RetrySummary};
 
 use std::time::{Duration, Instant};
-use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
+use crate::agent::deadline::Deadline;
+use crate::runtime::sleep;
 use crate::{types::Messages, Result, StoodError};
 
 /// Configuration for retry behavior and error recovery
@@ -82,6 +91,8 @@ pub struct RetryResult<T> {
     pub max_attempts_reached: bool,
     /// Whether the maximum duration was reached
     pub max_duration_reached: bool,
+    /// Whether the retry budget refused another retry
+    pub budget_exhausted: bool,
 }
 
 /// Error classifier that determines retry behavior
@@ -97,8 +108,13 @@ impl ErrorClassifier {
             StoodError::NetworkError { .. } => ErrorClassification::Retryable,
             StoodError::TimeoutError { .. } => ErrorClassification::Retryable,
 
+            // Session quotas only free up over time
+            StoodError::QuotaExceeded {
+                violation: Some(_), ..
+            } => ErrorClassification::NonRetryable,
+
             // Context overflow needs special handling
-            StoodError::QuotaExceeded { message }
+            StoodError::QuotaExceeded { message, .. }
                 if message.contains("context") || message.contains("token") =>
             {
                 ErrorClassification::ContextOverflow
@@ -163,6 +179,7 @@ impl ErrorClassifier {
 #[derive(Debug, Clone)]
 pub struct RetryExecutor {
     config: RetryConfig,
+    budget: Option<RetryBudget>,
 }
 
 impl Default for RetryExecutor {
@@ -174,7 +191,17 @@ impl Default for RetryExecutor {
 impl RetryExecutor {
     /// Create a new retry executor with the given configuration
     pub fn new(config: RetryConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            budget: None,
+        }
+    }
+
+    /// Draw retries from `budget` instead of the budget of the enclosing
+    /// [`RetryBudget::scope`]
+    pub fn with_budget(mut self, budget: RetryBudget) -> Self {
+        self.budget = Some(budget);
+        self
     }
 
     /// Execute an operation with retry logic
@@ -224,6 +251,7 @@ impl RetryExecutor {
                         total_duration: start_time.elapsed(),
                         max_attempts_reached: false,
                         max_duration_reached: false,
+                        budget_exhausted: false,
                     };
                 }
                 Err(error) => {
@@ -231,6 +259,12 @@ impl RetryExecutor {
 
                     // Check if we should retry
                     if classification != ErrorClassification::Retryable {
+                        RetryHistory::record_current(
+                            RetryLayer::Executor,
+                            attempts_made,
+                            &error,
+                            None,
+                        );
                         debug!("Error is not retryable: {:?}", classification);
                         return RetryResult {
                             result: Err(error),
@@ -238,11 +272,18 @@ impl RetryExecutor {
                             total_duration: start_time.elapsed(),
                             max_attempts_reached: false,
                             max_duration_reached: false,
+                            budget_exhausted: false,
                         };
                     }
 
                     // Check if we've reached maximum attempts
                     if attempts_made >= self.config.max_attempts {
+                        RetryHistory::record_current(
+                            RetryLayer::Executor,
+                            attempts_made,
+                            &error,
+                            None,
+                        );
                         let total_duration = start_time.elapsed();
                         error!(
                             "💥 Maximum retry attempts ({}) reached after {:.2}s - giving up",
@@ -255,6 +296,7 @@ impl RetryExecutor {
                             total_duration: start_time.elapsed(),
                             max_attempts_reached: true,
                             max_duration_reached: false,
+                            budget_exhausted: false,
                         };
                     }
 
@@ -262,6 +304,12 @@ impl RetryExecutor {
                     let elapsed = start_time.elapsed();
                     if let Some(max_duration) = self.config.max_total_duration {
                         if elapsed >= max_duration {
+                            RetryHistory::record_current(
+                                RetryLayer::Executor,
+                                attempts_made,
+                                &error,
+                                None,
+                            );
                             error!("⏰ Maximum retry duration ({:.1}s) reached after {} attempts - timing out",
                                 max_duration.as_secs_f64(), attempts_made);
                             return RetryResult {
@@ -270,6 +318,7 @@ impl RetryExecutor {
                                 total_duration: elapsed,
                                 max_attempts_reached: false,
                                 max_duration_reached: true,
+                                budget_exhausted: false,
                             };
                         }
                     }
@@ -299,6 +348,33 @@ impl RetryExecutor {
                         }
                     }
 
+                    let granted = match &self.budget {
+                        Some(budget) => {
+                            Deadline::allows_current_retry(delay) && budget.try_acquire(delay)
+                        }
+                        None => RetryBudget::acquire_current(delay),
+                    };
+                    RetryHistory::record_current(
+                        RetryLayer::Executor,
+                        attempts_made,
+                        &error,
+                        granted.then_some(delay),
+                    );
+                    if !granted {
+                        warn!(
+                            "Retry budget exhausted after {} attempts - giving up",
+                            attempts_made
+                        );
+                        return RetryResult {
+                            result: Err(error),
+                            attempts_made,
+                            total_duration: start_time.elapsed(),
+                            max_attempts_reached: false,
+                            max_duration_reached: false,
+                            budget_exhausted: true,
+                        };
+                    }
+
                     // Sleep before next retry
                     sleep(delay).await;
 
@@ -791,12 +867,14 @@ impl CircuitBreaker {
                     );
                     self.state = CircuitBreakerState::Open;
                     self.opened_at = Some(Instant::now());
+                    RetryHistory::record_current_circuit_breaker_trip();
                 }
             }
             CircuitBreakerState::HalfOpen => {
                 debug!("Circuit breaker opening again after failed test");
                 self.state = CircuitBreakerState::Open;
                 self.opened_at = Some(Instant::now());
+                RetryHistory::record_current_circuit_breaker_trip();
             }
             CircuitBreakerState::Open => {
                 // Already open, no action needed
@@ -862,7 +940,8 @@ mod tests {
         // Context overflow
         assert_eq!(
             ErrorClassifier::classify(&StoodError::QuotaExceeded {
-                message: "context limit exceeded".to_string()
+                message: "context limit exceeded".to_string(),
+                violation: None,
             }),
             ErrorClassification::ContextOverflow
         );
@@ -4,8 +4,12 @@
 
 use super::{CheckConfig, HealthCheck, HealthError, HealthResult, HealthStatus};
 use crate::config::{BedrockConfig, TelemetryConfig};
+use crate::llm::traits::LlmProvider;
+use crate::llm::http::HttpClientConfig;
+use crate::mcp::{MCPClient, MCPConnectionStatus};
 use async_trait::async_trait;
-use std::{collections::HashMap, time::Instant};
+use std::{collections::HashMap, sync::Arc, time::Instant};
+use tokio::sync::RwLock;
 use tracing::{debug, error, warn};
 
 /// Configuration validation health check
@@ -167,20 +171,15 @@ impl BedrockHealthCheck {
         let start_time = Instant::now();
 
         // Create AWS configuration
-        let aws_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+        let aws_config = crate::llm::http::aws_config_loader()
+            .map_err(|e| HealthError::Configuration(e.to_string()))?
             .region(aws_config::Region::new(self.bedrock_config.region.clone()))
             .load()
             .await;
 
-        // Create Bedrock Runtime client
-        let bedrock_client = aws_sdk_bedrockruntime::Client::new(&aws_config);
-
-        // Perform a lightweight operation to test connectivity
-        // Note: In a real implementation, you might want to use a specific operation
-        // that doesn't consume tokens, such as listing foundation models
         match tokio::time::timeout(
             self.bedrock_config.timeout,
-            self.test_bedrock_operation(&bedrock_client),
+            self.test_bedrock_operation(&aws_config),
         )
         .await
         {
@@ -192,15 +191,16 @@ impl BedrockHealthCheck {
 
     async fn test_bedrock_operation(
         &self,
-        _client: &aws_sdk_bedrockruntime::Client,
+        aws_config: &aws_config::SdkConfig,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // For this health check, we'll just verify that we can create the client
-        // In a real implementation, you might want to make a lightweight API call
-        // such as listing available models or making a minimal inference request
-
-        // Simulate a network check with a small delay
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-
+        use aws_credential_types::provider::ProvideCredentials;
+
+        // Resolve credentials through the chain the Bedrock client uses; this
+        // catches missing or expired credentials without spending tokens
+        let provider = aws_config
+            .credentials_provider()
+            .ok_or("No AWS credentials provider configured")?;
+        provider.provide_credentials().await?;
         Ok(())
     }
 }
@@ -453,9 +453,9 @@ impl TelemetryHealthCheck {
         let start_time = Instant::now();
 
         // Create HTTP client for connectivity test
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(5))
-            .build()
+        let client = HttpClientConfig::new()
+            .with_request_timeout(std::time::Duration::from_secs(5))
+            .build_client()
             .map_err(|e| HealthError::Configuration(e.to_string()))?;
 
         // Try to connect to the OTLP endpoint
@@ -479,6 +479,182 @@ impl TelemetryHealthCheck {
     }
 }
 
+/// Readiness check probing an LLM provider with its own
+/// [`health_check`](crate::llm::traits::LlmProvider::health_check)
+pub struct ProviderHealthCheck {
+    name: String,
+    provider: Arc<dyn LlmProvider>,
+    critical: bool,
+    check_config: CheckConfig,
+}
+
+impl ProviderHealthCheck {
+    /// Probe `provider`; the check is named `provider:<type>`
+    pub fn new(provider: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            name: format!("provider:{}", provider.provider_type()),
+            provider,
+            critical: true,
+            check_config: CheckConfig::default(),
+        }
+    }
+
+    /// Whether a failing provider makes the service unready (default: true)
+    pub fn with_critical(mut self, critical: bool) -> Self {
+        self.critical = critical;
+        self
+    }
+}
+
+#[async_trait]
+impl HealthCheck for ProviderHealthCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> Result<HealthResult, HealthError> {
+        let start_time = Instant::now();
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "provider".to_string(),
+            self.provider.provider_type().to_string(),
+        );
+
+        debug!("Performing provider health check: {}", self.name);
+
+        let (status, message) = match self.provider.health_check().await {
+            Ok(health) => {
+                if let Some(latency) = health.latency_ms {
+                    metadata.insert("latency_ms".to_string(), latency.to_string());
+                }
+                if health.healthy {
+                    (HealthStatus::Healthy, "Provider is reachable".to_string())
+                } else {
+                    let error = health.error.unwrap_or_else(|| "unknown error".to_string());
+                    warn!("Provider health check '{}' failed: {}", self.name, error);
+                    (
+                        HealthStatus::Unhealthy,
+                        format!("Provider is unavailable: {}", error),
+                    )
+                }
+            }
+            Err(e) => {
+                warn!("Provider health check '{}' failed: {}", self.name, e);
+                (
+                    HealthStatus::Unhealthy,
+                    format!("Provider health check failed: {}", e),
+                )
+            }
+        };
+
+        Ok(HealthResult {
+            name: self.name.clone(),
+            status,
+            duration: start_time.elapsed(),
+            message: Some(message),
+            timestamp: chrono::Utc::now(),
+            metadata,
+        })
+    }
+
+    fn config(&self) -> &CheckConfig {
+        &self.check_config
+    }
+
+    fn is_critical(&self) -> bool {
+        self.critical
+    }
+}
+
+/// Readiness check pinging an MCP server over an existing client connection
+///
+/// A client that is reconnecting is reported as degraded without a ping.
+pub struct McpHealthCheck {
+    name: String,
+    client: Arc<RwLock<MCPClient>>,
+    critical: bool,
+    check_config: CheckConfig,
+}
+
+impl McpHealthCheck {
+    /// Probe the server behind `client`; the check is named `mcp:<name>`
+    pub fn new(name: impl AsRef<str>, client: Arc<RwLock<MCPClient>>) -> Self {
+        Self {
+            name: format!("mcp:{}", name.as_ref()),
+            client,
+            critical: true,
+            check_config: CheckConfig::default(),
+        }
+    }
+
+    /// Whether a failing server makes the service unready (default: true)
+    pub fn with_critical(mut self, critical: bool) -> Self {
+        self.critical = critical;
+        self
+    }
+}
+
+#[async_trait]
+impl HealthCheck for McpHealthCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> Result<HealthResult, HealthError> {
+        let start_time = Instant::now();
+        let health = self.client.read().await.health();
+        let mut metadata = HashMap::new();
+        metadata.insert("server".to_string(), health.server().to_string());
+
+        debug!("Performing MCP health check: {}", self.name);
+
+        let (status, message) = match health.status() {
+            MCPConnectionStatus::Reconnecting => (
+                HealthStatus::Degraded,
+                "Reconnecting to MCP server".to_string(),
+            ),
+            MCPConnectionStatus::Disconnected => (
+                HealthStatus::Unhealthy,
+                "Not connected to MCP server".to_string(),
+            ),
+            MCPConnectionStatus::Connected => match self.client.write().await.ping().await {
+                Ok(()) => (
+                    HealthStatus::Healthy,
+                    "MCP server responded to ping".to_string(),
+                ),
+                Err(e) => {
+                    warn!("MCP health check '{}' failed: {}", self.name, e);
+                    (
+                        HealthStatus::Unhealthy,
+                        format!("MCP server did not respond: {}", e),
+                    )
+                }
+            },
+        };
+        metadata.insert(
+            "response_time_ms".to_string(),
+            start_time.elapsed().as_millis().to_string(),
+        );
+
+        Ok(HealthResult {
+            name: self.name.clone(),
+            status,
+            duration: start_time.elapsed(),
+            message: Some(message),
+            timestamp: chrono::Utc::now(),
+            metadata,
+        })
+    }
+
+    fn config(&self) -> &CheckConfig {
+        &self.check_config
+    }
+
+    fn is_critical(&self) -> bool {
+        self.critical
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -526,4 +702,17 @@ mod tests {
         assert_eq!(result.name, "bedrock-connectivity");
         // Status depends on AWS credentials availability
     }
+
+    #[tokio::test]
+    async fn test_provider_health_check_probes_provider() {
+        let provider = crate::llm::providers::LMStudioProvider::new("http://127.0.0.1:1".into())
+            .await
+            .unwrap();
+        let check = ProviderHealthCheck::new(Arc::new(provider)).with_critical(false);
+
+        let result = check.check().await.unwrap();
+        assert_eq!(result.name, "provider:lm_studio");
+        assert_eq!(result.status, HealthStatus::Unhealthy);
+        assert!(!check.is_critical());
+    }
 }
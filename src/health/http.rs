@@ -1,19 +1,34 @@
 //! HTTP Server for Health Check Endpoints
 //!
 //! This module provides HTTP endpoints for health checks, compatible with Kubernetes
-//! and container orchestration platforms.
+//! and container orchestration platforms:
+//!
+//! | Endpoint | Probe | Fails with 503 when |
+//! |---|---|---|
+//! | `/healthz`, `/health/live` | Liveness | The process cannot serve requests |
+//! | `/readyz`, `/health/ready` | Readiness | A critical dependency check fails |
+//! | `/health` | Full report | Any critical check fails |
+//! | `/metrics` | Prometheus | Never |
+//!
+//! Readiness responses list every dependency with its status, so a failing
+//! probe shows which provider, MCP server or exporter is down:
+//!
+//! ```json
+//! {
+//!   "status": "unhealthy",
+//!   "ready": false,
+//!   "checks": {
+//!     "provider:bedrock": { "status": "healthy", "critical": true, ... },
+//!     "mcp:filesystem": { "status": "unhealthy", "critical": true, "message": "..." }
+//!   }
+//! }
+//! ```
 
 #[cfg(feature = "http")]
 use {
-    super::{HealthChecker, HealthStatus},
-    axum::{
-        extract::State,
-        http::StatusCode,
-        response::Json,
-        routing::get,
-        Router,
-    },
-    serde_json::{json, Value},
+    super::{HealthChecker, HealthStatus, HealthSummary},
+    axum::{extract::State, http::StatusCode, response::Json, routing::get, Router},
+    serde_json::{json, Map, Value},
     std::sync::Arc,
     tokio::sync::Mutex,
     tower::ServiceBuilder,
@@ -21,10 +36,13 @@ use {
     tracing::{info, warn},
 };
 
+#[cfg(feature = "http")]
+type SharedChecker = Arc<Mutex<HealthChecker>>;
+
 #[cfg(feature = "http")]
 /// HTTP server for health check endpoints
 pub struct HealthHttpServer {
-    health_checker: Arc<Mutex<HealthChecker>>,
+    health_checker: SharedChecker,
     config: super::HttpConfig,
 }
 
@@ -40,7 +58,7 @@ impl HealthHttpServer {
 
     /// Start the HTTP server
     pub async fn start(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let app = self.create_router();
+        let app = self.router();
         let bind_addr = format!("{}:{}", self.config.host, self.config.port);
 
         info!("Starting health check HTTP server on {}", bind_addr);
@@ -52,59 +70,83 @@ impl HealthHttpServer {
         Ok(())
     }
 
-    /// Create the router with all health check endpoints
-    fn create_router(&self) -> Router<Arc<Mutex<HealthChecker>>> {
-        let state = self.health_checker.clone();
-
-        let mut router = Router::new()
-            .route("/health", get(health_handler))
-            .with_state(state);
+    /// Router with the enabled health endpoints, for mounting in a larger application
+    pub fn router(&self) -> Router {
+        let mut router = Router::new().route("/health", get(health_handler));
 
         if self.config.enable_liveness {
-            router = router.route("/health/live", get(liveness_handler));
+            router = router
+                .route("/healthz", get(liveness_handler))
+                .route("/health/live", get(liveness_handler));
         }
 
         if self.config.enable_readiness {
-            router = router.route("/health/ready", get(readiness_handler));
+            router = router
+                .route("/readyz", get(readiness_handler))
+                .route("/health/ready", get(readiness_handler));
         }
 
         if self.config.enable_metrics {
             router = router.route("/metrics", get(metrics_handler));
         }
 
-        // Add CORS and other middleware
-        router.layer(
-            ServiceBuilder::new()
-                .layer(CorsLayer::permissive())
-        )
+        router
+            .with_state(self.health_checker.clone())
+            .layer(ServiceBuilder::new().layer(CorsLayer::permissive()))
     }
 
     /// Start the server in the background and return a handle
-    pub async fn start_background(self) -> tokio::task::JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
-        tokio::spawn(async move {
-            self.start().await
-        })
+    pub async fn start_background(
+        self,
+    ) -> tokio::task::JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
+        tokio::spawn(async move { self.start().await })
+    }
+}
+
+#[cfg(feature = "http")]
+/// Status name used in responses
+fn status_name(status: &HealthStatus) -> String {
+    format!("{:?}", status).to_lowercase()
+}
+
+#[cfg(feature = "http")]
+/// Per-dependency statuses of `summary`
+fn checks_json(checker: &HealthChecker, summary: &HealthSummary) -> Value {
+    let mut checks = Map::new();
+    for (name, result) in &summary.checks {
+        checks.insert(
+            name.clone(),
+            json!({
+                "status": status_name(&result.status),
+                "critical": checker.is_critical(name),
+                "message": result.message,
+                "duration_ms": result.duration.as_millis(),
+                "timestamp": result.timestamp,
+                "metadata": result.metadata,
+            }),
+        );
     }
+    Value::Object(checks)
 }
 
 #[cfg(feature = "http")]
 /// Handler for the main health endpoint
-async fn health_handler(
-    State(health_checker): State<Arc<Mutex<HealthChecker>>>,
-) -> Result<Json<Value>, StatusCode> {
+async fn health_handler(State(health_checker): State<SharedChecker>) -> (StatusCode, Json<Value>) {
     let mut checker = match health_checker.try_lock() {
         Ok(checker) => checker,
         Err(_) => {
             warn!("Health checker is busy, returning cached results");
             let checker = health_checker.lock().await;
             let summary = checker.last_health_summary();
-            drop(checker);
-            return Ok(Json(json!({
-                "status": format!("{:?}", summary.status).to_lowercase(),
-                "timestamp": summary.timestamp,
-                "checks": summary.checks,
-                "note": "Using cached results - health checker was busy"
-            })));
+            return (
+                StatusCode::OK,
+                Json(json!({
+                    "status": status_name(&summary.status),
+                    "timestamp": summary.timestamp,
+                    "checks": checks_json(&checker, &summary),
+                    "note": "Using cached results - health checker was busy"
+                })),
+            );
         }
     };
 
@@ -115,61 +157,74 @@ async fn health_handler(
         HealthStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
     };
 
-    let response = json!({
-        "status": format!("{:?}", summary.status).to_lowercase(),
-        "timestamp": summary.timestamp,
-        "total_duration_ms": summary.total_duration.as_millis(),
-        "checks": summary.checks
-    });
-
-    if status_code == StatusCode::OK {
-        Ok(Json(response))
-    } else {
-        Err(status_code)
-    }
+    (
+        status_code,
+        Json(json!({
+            "status": status_name(&summary.status),
+            "timestamp": summary.timestamp,
+            "total_duration_ms": summary.total_duration.as_millis(),
+            "checks": checks_json(&checker, &summary)
+        })),
+    )
 }
 
 #[cfg(feature = "http")]
 /// Handler for the liveness probe endpoint
 async fn liveness_handler(
-    State(health_checker): State<Arc<Mutex<HealthChecker>>>,
-) -> Result<Json<Value>, StatusCode> {
+    State(health_checker): State<SharedChecker>,
+) -> (StatusCode, Json<Value>) {
     let checker = health_checker.lock().await;
-    let is_alive = checker.is_alive().await;
-
-    if is_alive {
-        Ok(Json(json!({
-            "status": "alive",
-            "timestamp": chrono::Utc::now()
-        })))
+    if checker.is_alive().await {
+        (
+            StatusCode::OK,
+            Json(json!({
+                "status": "alive",
+                "timestamp": chrono::Utc::now()
+            })),
+        )
     } else {
-        Err(StatusCode::SERVICE_UNAVAILABLE)
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({
+                "status": "dead",
+                "timestamp": chrono::Utc::now()
+            })),
+        )
     }
 }
 
 #[cfg(feature = "http")]
 /// Handler for the readiness probe endpoint
+///
+/// Probes every dependency and reports each one, failing with 503 when a
+/// critical check is unhealthy.
 async fn readiness_handler(
-    State(health_checker): State<Arc<Mutex<HealthChecker>>>,
-) -> Result<Json<Value>, StatusCode> {
+    State(health_checker): State<SharedChecker>,
+) -> (StatusCode, Json<Value>) {
     let mut checker = health_checker.lock().await;
-    let is_ready = checker.is_ready().await;
-
-    if is_ready {
-        Ok(Json(json!({
-            "status": "ready",
-            "timestamp": chrono::Utc::now()
-        })))
+    let summary = checker.check_health().await;
+    let ready = summary.status != HealthStatus::Unhealthy;
+    let status_code = if ready {
+        StatusCode::OK
     } else {
-        Err(StatusCode::SERVICE_UNAVAILABLE)
-    }
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status_code,
+        Json(json!({
+            "status": status_name(&summary.status),
+            "ready": ready,
+            "timestamp": summary.timestamp,
+            "total_duration_ms": summary.total_duration.as_millis(),
+            "checks": checks_json(&checker, &summary)
+        })),
+    )
 }
 
 #[cfg(feature = "http")]
 /// Handler for Prometheus-compatible metrics endpoint
-async fn metrics_handler(
-    State(health_checker): State<Arc<Mutex<HealthChecker>>>,
-) -> Result<String, StatusCode> {
+async fn metrics_handler(State(health_checker): State<SharedChecker>) -> String {
     let checker = health_checker.lock().await;
     let summary = checker.last_health_summary();
 
@@ -182,23 +237,17 @@ async fn metrics_handler(
         HealthStatus::Unhealthy => -1,
     };
 
-    metrics.push_str(&format!(
-        "# HELP stood_health_status Overall health status (-1=unhealthy, 0=degraded, 1=healthy)\n"
-    ));
-    metrics.push_str(&format!(
-        "# TYPE stood_health_status gauge\n"
-    ));
-    metrics.push_str(&format!(
-        "stood_health_status {}\n"
-    , overall_status_value));
+    metrics.push_str(
+        "# HELP stood_health_status Overall health status (-1=unhealthy, 0=degraded, 1=healthy)\n",
+    );
+    metrics.push_str("# TYPE stood_health_status gauge\n");
+    metrics.push_str(&format!("stood_health_status {}\n", overall_status_value));
 
     // Individual check metrics
-    metrics.push_str(&format!(
-        "# HELP stood_health_check_status Individual health check status (0=unhealthy, 1=healthy)\n"
-    ));
-    metrics.push_str(&format!(
-        "# TYPE stood_health_check_status gauge\n"
-    ));
+    metrics.push_str(
+        "# HELP stood_health_check_status Individual health check status (0=unhealthy, 1=healthy)\n",
+    );
+    metrics.push_str("# TYPE stood_health_check_status gauge\n");
 
     for (name, result) in &summary.checks {
         let status_value = match result.status {
@@ -214,21 +263,20 @@ async fn metrics_handler(
     }
 
     // Check duration metrics
-    metrics.push_str(&format!(
-        "# HELP stood_health_check_duration_seconds Duration of health checks in seconds\n"
-    ));
-    metrics.push_str(&format!(
-        "# TYPE stood_health_check_duration_seconds gauge\n"
-    ));
+    metrics.push_str(
+        "# HELP stood_health_check_duration_seconds Duration of health checks in seconds\n",
+    );
+    metrics.push_str("# TYPE stood_health_check_duration_seconds gauge\n");
 
     for (name, result) in &summary.checks {
         metrics.push_str(&format!(
             "stood_health_check_duration_seconds{{check=\"{}\"}} {:.6}\n",
-            name, result.duration.as_secs_f64()
+            name,
+            result.duration.as_secs_f64()
         ));
     }
 
-    Ok(metrics)
+    metrics
 }
 
 #[cfg(not(feature = "http"))]
@@ -245,7 +293,9 @@ impl HealthHttpServer {
         Err("HTTP feature is not enabled. Enable with --features http".into())
     }
 
-    pub async fn start_background(self) -> tokio::task::JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
+    pub async fn start_background(
+        self,
+    ) -> tokio::task::JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
         tokio::spawn(async move {
             Err("HTTP feature is not enabled. Enable with --features http".into())
         })
@@ -255,19 +305,97 @@ impl HealthHttpServer {
 #[cfg(all(test, feature = "http"))]
 mod tests {
     use super::*;
-    use crate::config::StoodConfig;
+    use crate::health::{CheckConfig, HealthCheck, HealthConfig, HealthError, HealthResult};
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    struct FixedCheck {
+        name: &'static str,
+        status: HealthStatus,
+        critical: bool,
+        config: CheckConfig,
+    }
 
-    #[tokio::test]
-    async fn test_health_http_server_creation() {
-        let config = StoodConfig::default();
-        let health_checker = HealthChecker::from_config(&config);
-        let http_config = super::super::HttpConfig::default();
+    #[async_trait]
+    impl HealthCheck for FixedCheck {
+        fn name(&self) -> &str {
+            self.name
+        }
 
-        let _server = HealthHttpServer::new(health_checker, http_config);
+        async fn check(&self) -> Result<HealthResult, HealthError> {
+            Ok(HealthResult {
+                name: self.name.to_string(),
+                status: self.status.clone(),
+                duration: Duration::ZERO,
+                message: None,
+                timestamp: chrono::Utc::now(),
+                metadata: HashMap::new(),
+            })
+        }
 
-        // Just verify we can create the server
-        // Actually starting it would require binding to a port
-        assert!(true);
+        fn config(&self) -> &CheckConfig {
+            &self.config
+        }
+
+        fn is_critical(&self) -> bool {
+            self.critical
+        }
+    }
+
+    fn checker(critical_status: HealthStatus) -> HealthChecker {
+        let mut checker = HealthChecker::new(HealthConfig::default());
+        checker.add_check(Box::new(FixedCheck {
+            name: "provider:bedrock",
+            status: critical_status,
+            critical: true,
+            config: CheckConfig::default(),
+        }));
+        checker.add_check(Box::new(FixedCheck {
+            name: "telemetry",
+            status: HealthStatus::Unhealthy,
+            critical: false,
+            config: CheckConfig::default(),
+        }));
+        checker
+    }
+
+    async fn get(router: Router, path: &str) -> (StatusCode, Value) {
+        let request = axum::http::Request::get(path)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_readiness_reports_each_dependency() {
+        let router =
+            HealthHttpServer::new(checker(HealthStatus::Healthy), Default::default()).router();
+
+        let (status, body) = get(router.clone(), "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "alive");
+
+        // A failing non-critical dependency degrades but does not unready
+        let (status, body) = get(router, "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["checks"]["provider:bedrock"]["status"], "healthy");
+        assert_eq!(body["checks"]["telemetry"]["status"], "unhealthy");
+        assert_eq!(body["checks"]["telemetry"]["critical"], false);
+
+        let router =
+            HealthHttpServer::new(checker(HealthStatus::Unhealthy), Default::default()).router();
+        let (status, body) = get(router, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["checks"]["provider:bedrock"]["critical"], true);
     }
 }
 
@@ -275,6 +403,7 @@ mod tests {
 mod tests {
     use super::*;
     use crate::config::StoodConfig;
+    use crate::health::HealthChecker;
 
     #[tokio::test]
     async fn test_health_http_server_stub() {
@@ -288,4 +417,4 @@ mod tests {
         let result = server.start().await;
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+}
@@ -3,6 +3,27 @@
 //! This module provides enterprise-grade health checking capabilities for the Stood agent library,
 //! including dependency verification, resource monitoring, and HTTP endpoint support for
 //! Kubernetes and container orchestration platforms.
+//!
+//! Readiness probes the service's actual dependencies: add a [`ProviderHealthCheck`] for
+//! each configured LLM provider and an [`McpHealthCheck`] for each MCP connection. Only
+//! critical checks can make the service unready; a failing non-critical check, such as
+//! the telemetry exporter, degrades it. With the `http` feature, [`HealthHttpServer`]
+//! serves `/healthz` (liveness) and `/readyz` (readiness with per-dependency statuses):
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use stood::config::StoodConfig;
+//! use stood::health::{HealthChecker, HealthHttpServer, HttpConfig, ProviderHealthCheck};
+//! use stood::llm::providers::BedrockProvider;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! let mut checker = HealthChecker::from_config(&StoodConfig::default());
+//! let provider = Arc::new(BedrockProvider::new(None).await?);
+//! checker.add_check(Box::new(ProviderHealthCheck::new(provider)));
+//!
+//! HealthHttpServer::new(checker, HttpConfig::default()).start().await
+//! # }
+//! ```
 
 use crate::config::StoodConfig;
 use async_trait::async_trait;
@@ -248,6 +269,7 @@ impl HealthChecker {
         let mut check_futures = Vec::new();
         for (name, check) in &self.checks {
             let check_name = name.clone();
+            let critical = check.is_critical();
             let timeout = check.config().timeout.unwrap_or(self.config.timeout);
             let check_future = async move {
                 let check_start = Instant::now();
@@ -283,11 +305,11 @@ impl HealthChecker {
                     }
                 }
             };
-            check_futures.push((name.clone(), check_future));
+            check_futures.push((name.clone(), critical, check_future));
         }
 
         // Wait for all checks to complete
-        for (name, future) in check_futures {
+        for (name, critical, future) in check_futures {
             let result = future.await;
 
             // Update failure count
@@ -295,19 +317,20 @@ impl HealthChecker {
                 let count = self.failure_counts.entry(name.clone()).or_insert(0);
                 *count += 1;
 
-                if *count >= self.config.max_failures {
+                if critical && *count >= self.config.max_failures {
                     overall_status = HealthStatus::Unhealthy;
                 }
             } else {
                 self.failure_counts.insert(name.clone(), 0);
             }
 
-            // Update overall status
-            match (&overall_status, &result.status) {
-                (HealthStatus::Healthy, HealthStatus::Degraded) => {
+            // Update overall status; non-critical failures only degrade it
+            match (&overall_status, &result.status, critical) {
+                (HealthStatus::Healthy, HealthStatus::Degraded, _)
+                | (HealthStatus::Healthy, HealthStatus::Unhealthy, false) => {
                     overall_status = HealthStatus::Degraded;
                 }
-                (_, HealthStatus::Unhealthy) => {
+                (_, HealthStatus::Unhealthy, true) => {
                     overall_status = HealthStatus::Unhealthy;
                 }
                 _ => {}
@@ -355,6 +378,14 @@ impl HealthChecker {
         }
     }
 
+    /// Whether the check `name` can make the service unready
+    pub fn is_critical(&self, name: &str) -> bool {
+        self.checks
+            .get(name)
+            .map(|check| check.is_critical())
+            .unwrap_or(false)
+    }
+
     /// Check if the service is ready (all critical checks passing)
     pub async fn is_ready(&mut self) -> bool {
         let summary = self.check_health().await;
@@ -393,12 +424,9 @@ impl HealthChecker {
 pub mod checks;
 pub use checks::*;
 
-// Optional HTTP server support
-// TODO: Re-enable HTTP support with proper axum integration
-// #[cfg(feature = "http")]
-// pub mod http;
-// #[cfg(feature = "http")]
-// pub use http::*;
+// HTTP endpoints; a stub that fails to start without the `http` feature
+pub mod http;
+pub use http::HealthHttpServer;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,9 @@
+//! Glue between agents and the transports applications serve them over.
+//!
+//! - [`ws`] - Stream agent events to WebSocket clients
+//! - `openai` - Serve an agent over the OpenAI chat completions API (requires
+//!   the `openai-server` feature)
+
+#[cfg(feature = "openai-server")]
+pub mod openai;
+pub mod ws;
@@ -0,0 +1,1091 @@
+//! Serve an agent over the OpenAI chat completions API.
+//!
+//! [`OpenAiServer`] exposes a stood agent at `POST /v1/chat/completions` and
+//! lists it at `GET /v1/models`, so chat UIs built for OpenAI (LibreChat,
+//! Open WebUI, ...) can talk to the agent as if it were an OpenAI model.
+//! Requires the `openai-server` feature.
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! use stood::integrations::openai::OpenAiServer;
+//!
+//! # async fn example() -> std::io::Result<()> {
+//! OpenAiServer::new("stood-assistant", || {
+//!     Agent::builder()
+//!         .system_prompt("You are a helpful assistant")
+//!         .with_builtin_tools()
+//! })
+//! .serve("127.0.0.1:8080")
+//! .await
+//! # }
+//! ```
+//!
+//! # Request handling
+//!
+//! The API is stateless: every request carries the whole conversation. Each
+//! request therefore builds a fresh agent from the factory, loads the earlier
+//! messages into its conversation and executes the last user message, running
+//! the agent's own tools as usual. System messages are appended to the
+//! agent's system prompt.
+//!
+//! Requests that define `tools` are passed through instead: the conversation
+//! and the client's tools are sent to the agent's model in a single call, and
+//! the model's tool calls are returned to the client to execute, as with any
+//! OpenAI model. `temperature` and `max_tokens` are only honored for these
+//! requests; agent executions use the agent's own settings.
+//!
+//! With `stream: true` the response is sent as server-sent events of
+//! `chat.completion.chunk` objects, ending with `data: [DONE]`. Agent text
+//! and passthrough text are relayed as the model streams them; passthrough
+//! tool calls are sent in one chunk once the model has finished them. An
+//! agent execution is stopped when its client disconnects.
+//!
+//! # Authentication
+//!
+//! The server accepts any request unless an API key is set with
+//! [`OpenAiServer::with_api_key`]. Requests must then send it as
+//! `Authorization: Bearer <key>`, like the OpenAI API, or get a
+//! `401 Unauthorized`.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+use crate::agent::callbacks::{CallbackError, CallbackEvent, CallbackHandler};
+use crate::agent::{AgentBuilder, AgentResult};
+use crate::llm::traits::{ChatConfig, ChatResponse, ContentBlockDelta, StreamEvent, Tool};
+use crate::types::{ContentBlock, Message, MessageRole, Messages, ToolChoice, ToolResultContent};
+
+type AgentFactory = dyn Fn() -> AgentBuilder + Send + Sync;
+
+/// OpenAI-compatible HTTP server for an agent
+#[derive(Clone)]
+pub struct OpenAiServer {
+    model_name: String,
+    factory: Arc<AgentFactory>,
+    api_key: Option<String>,
+}
+
+impl OpenAiServer {
+    /// Serve agents built by `factory` under the model name `model_name`
+    ///
+    /// The factory is called once per request. Any callback handler it sets
+    /// is replaced for streaming requests.
+    pub fn new<F>(model_name: impl Into<String>, factory: F) -> Self
+    where
+        F: Fn() -> AgentBuilder + Send + Sync + 'static,
+    {
+        Self {
+            model_name: model_name.into(),
+            factory: Arc::new(factory),
+            api_key: None,
+        }
+    }
+
+    /// Require `Authorization: Bearer <api_key>` on every request
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Router with the API routes, for mounting in a larger application
+    pub fn router(self) -> Router {
+        let server = Arc::new(self);
+        Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/models", get(list_models))
+            .route_layer(middleware::from_fn_with_state(
+                server.clone(),
+                require_api_key,
+            ))
+            .with_state(server)
+    }
+
+    /// Whether `authorization`, the request's `Authorization` header, carries
+    /// the API key
+    fn authorized(&self, authorization: Option<&str>) -> bool {
+        let Some(api_key) = &self.api_key else {
+            return true;
+        };
+        match authorization.and_then(|value| value.strip_prefix("Bearer ")) {
+            // Compare every byte so the time taken doesn't reveal the key
+            Some(token) => {
+                token.len() == api_key.len()
+                    && token
+                        .bytes()
+                        .zip(api_key.bytes())
+                        .fold(0, |diff, (a, b)| diff | (a ^ b))
+                        == 0
+            }
+            None => false,
+        }
+    }
+
+    /// Listen on `addr` and serve requests until the server fails
+    pub async fn serve(self, addr: impl tokio::net::ToSocketAddrs) -> std::io::Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!(
+            "Serving agent '{}' on {}",
+            self.model_name,
+            listener.local_addr()?
+        );
+        axum::serve(listener, self.router()).await
+    }
+}
+
+/// Body of `POST /v1/chat/completions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionRequest {
+    /// Model name; ignored, the server always runs its agent
+    #[serde(default)]
+    pub model: Option<String>,
+    /// The conversation
+    pub messages: Vec<ChatMessage>,
+    /// Send the response as server-sent events
+    #[serde(default)]
+    pub stream: bool,
+    /// Client-side tools the model may call
+    #[serde(default)]
+    pub tools: Vec<ToolDefinition>,
+    /// Sampling temperature (passthrough requests only)
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Maximum tokens to generate (passthrough requests only)
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+/// A message of the conversation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// `system`, `developer`, `user`, `assistant` or `tool`
+    pub role: String,
+    /// Message text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<MessageContent>,
+    /// Tool calls made by an assistant message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCallMessage>,
+    /// Call a `tool` message is the result of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+/// Message content, either a string or a list of parts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    /// Plain text
+    Text(String),
+    /// Content parts; only `text` parts are used
+    Parts(Vec<ContentPart>),
+}
+
+/// One part of a multi-part message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentPart {
+    /// Part type, e.g. `text` or `image_url`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Text of a `text` part
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl MessageContent {
+    /// The text of the content, with text parts joined by newlines
+    pub fn text(&self) -> String {
+        match self {
+            MessageContent::Text(text) => text.clone(),
+            MessageContent::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| part.text.as_deref())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// A client-side tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    /// Always `function`
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    /// The function
+    pub function: FunctionDefinition,
+}
+
+/// Name, description and parameters of a client-side tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionDefinition {
+    /// Function name
+    pub name: String,
+    /// What the function does
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON Schema of the arguments
+    #[serde(default)]
+    pub parameters: Option<Value>,
+}
+
+/// A tool call of an assistant message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallMessage {
+    /// Position of the call in a streamed response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    /// Call id
+    pub id: String,
+    /// Always `function`
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    /// The function called
+    pub function: FunctionCall,
+}
+
+/// Function name and arguments of a tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    /// Function name
+    pub name: String,
+    /// Arguments as a JSON string
+    pub arguments: String,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+/// Response of a non-streaming request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletion {
+    /// Completion id
+    pub id: String,
+    /// Always `chat.completion`
+    pub object: String,
+    /// Creation time in seconds since the Unix epoch
+    pub created: i64,
+    /// Model name of the server
+    pub model: String,
+    /// The single choice
+    pub choices: Vec<Choice>,
+    /// Token usage, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<CompletionUsage>,
+}
+
+/// A completion choice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Choice {
+    /// Always 0
+    pub index: usize,
+    /// The assistant message
+    pub message: ChatMessage,
+    /// `stop`, `tool_calls` or `error`
+    pub finish_reason: String,
+}
+
+/// Token usage of a completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionUsage {
+    /// Input tokens
+    pub prompt_tokens: u32,
+    /// Output tokens
+    pub completion_tokens: u32,
+    /// Input and output tokens
+    pub total_tokens: u32,
+}
+
+/// One server-sent event of a streaming request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    /// Completion id, the same for every chunk
+    pub id: String,
+    /// Always `chat.completion.chunk`
+    pub object: String,
+    /// Creation time in seconds since the Unix epoch
+    pub created: i64,
+    /// Model name of the server
+    pub model: String,
+    /// The single choice
+    pub choices: Vec<ChunkChoice>,
+}
+
+/// Choice of a streamed chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkChoice {
+    /// Always 0
+    pub index: usize,
+    /// What the chunk adds to the message
+    pub delta: ChunkDelta,
+    /// Set on the last chunk
+    pub finish_reason: Option<String>,
+}
+
+/// Message fragment of a streamed chunk
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChunkDelta {
+    /// `assistant` on the first chunk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Text since the previous chunk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Tool calls, each sent whole
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCallMessage>>,
+}
+
+/// Convert the request messages to a system prompt and stood messages
+///
+/// Consecutive `tool` messages are merged into one user message of tool
+/// results, as Bedrock requires.
+pub fn to_stood_messages(messages: &[ChatMessage]) -> Result<Messages, String> {
+    let mut system = Vec::new();
+    let mut converted = Messages::new();
+
+    for message in messages {
+        let text = message
+            .content
+            .as_ref()
+            .map(MessageContent::text)
+            .unwrap_or_default();
+        match message.role.as_str() {
+            "system" | "developer" => system.push(text),
+            "user" => converted.push(Message::user(text)),
+            "assistant" => {
+                let mut content = Vec::new();
+                if !text.is_empty() {
+                    content.push(ContentBlock::text(text));
+                }
+                for call in &message.tool_calls {
+                    let input = serde_json::from_str(&call.function.arguments)
+                        .unwrap_or_else(|_| json!({}));
+                    content.push(ContentBlock::tool_use(
+                        call.id.as_str(),
+                        call.function.name.as_str(),
+                        input,
+                    ));
+                }
+                converted.push(Message::new(MessageRole::Assistant, content));
+            }
+            "tool" => {
+                let tool_use_id = message
+                    .tool_call_id
+                    .clone()
+                    .ok_or("tool message without tool_call_id")?;
+                let result =
+                    ContentBlock::tool_result_success(tool_use_id, ToolResultContent::text(text));
+                match converted.messages.last_mut() {
+                    Some(last)
+                        if last.role == MessageRole::User
+                            && last.content.iter().all(ContentBlock::is_tool_result) =>
+                    {
+                        last.content.push(result)
+                    }
+                    _ => converted.push(Message::new(MessageRole::User, vec![result])),
+                }
+            }
+            other => return Err(format!("unsupported message role '{}'", other)),
+        }
+    }
+
+    if !system.is_empty() {
+        converted.system_prompt = Some(system.join("\n\n"));
+    }
+    Ok(converted)
+}
+
+struct Completion {
+    content: String,
+    tool_calls: Vec<ToolCallMessage>,
+    usage: Option<CompletionUsage>,
+}
+
+impl Completion {
+    fn from_agent_result(result: &AgentResult) -> Self {
+        Self {
+            content: result.response.clone(),
+            tool_calls: Vec::new(),
+            usage: result.execution.tokens.as_ref().map(|t| CompletionUsage {
+                prompt_tokens: t.input_tokens,
+                completion_tokens: t.output_tokens,
+                total_tokens: t.total_tokens,
+            }),
+        }
+    }
+
+    fn from_chat_response(response: ChatResponse) -> Self {
+        Self {
+            content: response.content,
+            tool_calls: response
+                .tool_calls
+                .into_iter()
+                .enumerate()
+                .map(|(i, call)| ToolCallMessage {
+                    index: Some(i),
+                    id: call.id,
+                    kind: function_type(),
+                    function: FunctionCall {
+                        name: call.name,
+                        arguments: call.input.to_string(),
+                    },
+                })
+                .collect(),
+            usage: response.usage.map(|u| CompletionUsage {
+                prompt_tokens: u.input_tokens,
+                completion_tokens: u.output_tokens,
+                total_tokens: u.total_tokens,
+            }),
+        }
+    }
+
+    fn finish_reason(&self) -> &'static str {
+        if self.tool_calls.is_empty() {
+            "stop"
+        } else {
+            "tool_calls"
+        }
+    }
+}
+
+struct ResponseIds {
+    id: String,
+    created: i64,
+    model: String,
+}
+
+impl ResponseIds {
+    fn new(model: &str) -> Self {
+        Self {
+            id: format!("chatcmpl-{}", uuid::Uuid::new_v4().simple()),
+            created: chrono::Utc::now().timestamp(),
+            model: model.to_string(),
+        }
+    }
+
+    fn completion(&self, completion: Completion) -> ChatCompletion {
+        let finish_reason = completion.finish_reason().to_string();
+        ChatCompletion {
+            id: self.id.clone(),
+            object: "chat.completion".to_string(),
+            created: self.created,
+            model: self.model.clone(),
+            choices: vec![Choice {
+                index: 0,
+                message: ChatMessage {
+                    role: "assistant".to_string(),
+                    content: Some(MessageContent::Text(completion.content)),
+                    tool_calls: completion.tool_calls,
+                    tool_call_id: None,
+                },
+                finish_reason,
+            }],
+            usage: completion.usage,
+        }
+    }
+
+    fn chunk(&self, delta: ChunkDelta, finish_reason: Option<&str>) -> Event {
+        Event::default().data(self.chunk_data(delta, finish_reason))
+    }
+
+    fn chunk_data(&self, delta: ChunkDelta, finish_reason: Option<&str>) -> String {
+        let chunk = ChatCompletionChunk {
+            id: self.id.clone(),
+            object: "chat.completion.chunk".to_string(),
+            created: self.created,
+            model: self.model.clone(),
+            choices: vec![ChunkChoice {
+                index: 0,
+                delta,
+                finish_reason: finish_reason.map(str::to_string),
+            }],
+        };
+        serde_json::to_string(&chunk).unwrap_or_default()
+    }
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    let kind = if status.is_client_error() {
+        "invalid_request_error"
+    } else {
+        "server_error"
+    };
+    (
+        status,
+        Json(json!({ "error": { "message": message.into(), "type": kind } })),
+    )
+        .into_response()
+}
+
+fn error_data(message: impl Into<String>) -> String {
+    json!({ "error": { "message": message.into(), "type": "server_error" } }).to_string()
+}
+
+async fn require_api_key(
+    State(server): State<Arc<OpenAiServer>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if !server.authorized(authorization) {
+        return error_response(StatusCode::UNAUTHORIZED, "Invalid API key");
+    }
+    next.run(request).await
+}
+
+async fn list_models(State(server): State<Arc<OpenAiServer>>) -> Json<Value> {
+    Json(json!({
+        "object": "list",
+        "data": [{ "id": server.model_name, "object": "model", "owned_by": "stood" }]
+    }))
+}
+
+async fn chat_completions(
+    State(server): State<Arc<OpenAiServer>>,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Response {
+    let messages = match to_stood_messages(&request.messages) {
+        Ok(messages) => messages,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+
+    if !request.tools.is_empty() {
+        return passthrough(&server, &request, messages).await;
+    }
+
+    let mut history = messages.messages;
+    let prompt = match history.pop() {
+        Some(last) if last.role == MessageRole::User && !last.has_tool_result() => {
+            last.text().unwrap_or_default()
+        }
+        _ => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "the last message must be a user message",
+            )
+        }
+    };
+    let ids = ResponseIds::new(&server.model_name);
+    let builder = (server.factory)();
+
+    if request.stream {
+        let (tx, rx) = mpsc::unbounded_channel();
+        // Cancelled when the client disconnects, which drops the event stream
+        let disconnected = CancellationToken::new();
+        let builder = builder.with_callback_handler(StreamingHandler {
+            tx: tx.clone(),
+            disconnected: disconnected.clone(),
+        });
+        let stop = disconnected.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                result = run_agent(builder, messages.system_prompt, history, prompt) => {
+                    let _ = tx.send(AgentUpdate::Done(result.map(|result| result.response)));
+                }
+                _ = stop.cancelled() => {
+                    tracing::debug!("Client disconnected, stopping the agent");
+                }
+            }
+        });
+        return Sse::new(agent_event_stream(ids, rx, disconnected.drop_guard()))
+            .keep_alive(KeepAlive::default())
+            .into_response();
+    }
+
+    match run_agent(builder, messages.system_prompt, history, prompt).await {
+        Ok(result) => Json(ids.completion(Completion::from_agent_result(&result))).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// Build the agent, load the conversation and execute `prompt`
+async fn run_agent(
+    builder: AgentBuilder,
+    system_prompt: Option<String>,
+    history: Vec<Message>,
+    prompt: String,
+) -> Result<AgentResult, String> {
+    let mut agent = builder.build().await.map_err(|e| e.to_string())?;
+    let conversation = agent.conversation_mut();
+    if let Some(system_prompt) = system_prompt {
+        let combined = match conversation.system_prompt() {
+            Some(own) => format!("{}\n\n{}", own, system_prompt),
+            None => system_prompt,
+        };
+        conversation.set_system_prompt(Some(combined));
+    }
+    for message in history {
+        conversation.add_message(message);
+    }
+    agent.execute(prompt).await.map_err(|e| e.to_string())
+}
+
+/// Send the conversation and the client's tools to the agent's model
+async fn passthrough(
+    server: &OpenAiServer,
+    request: &ChatCompletionRequest,
+    mut messages: Messages,
+) -> Response {
+    let agent = match (server.factory)().build().await {
+        Ok(agent) => agent,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    if messages.system_prompt.is_none() {
+        messages.system_prompt = agent.conversation().system_prompt().map(str::to_string);
+    }
+    let tools: Vec<Tool> = request
+        .tools
+        .iter()
+        .map(|tool| Tool {
+            name: tool.function.name.clone(),
+            description: tool.function.description.clone().unwrap_or_default(),
+            input_schema: tool
+                .function
+                .parameters
+                .clone()
+                .unwrap_or_else(|| json!({ "type": "object", "properties": {} })),
+        })
+        .collect();
+    let agent_config = agent.config();
+    let config = ChatConfig {
+        model_id: agent_config.model_id.clone(),
+        provider: agent_config.provider,
+        temperature: request.temperature.or(agent_config.temperature),
+        max_tokens: request.max_tokens.or(agent_config.max_tokens),
+        enable_thinking: false,
+        cache_strategy: agent_config.cache_strategy.clone(),
+        tool_choice: ToolChoice::Auto,
+        additional_params: HashMap::new(),
+    };
+
+    let ids = ResponseIds::new(&server.model_name);
+    let provider = agent.provider();
+    let model_id = &agent_config.model_id;
+    if !request.stream {
+        return match provider
+            .chat_with_tools(model_id, &messages, &tools, &config)
+            .await
+        {
+            Ok(response) => {
+                Json(ids.completion(Completion::from_chat_response(response))).into_response()
+            }
+            Err(e) => error_response(StatusCode::BAD_GATEWAY, e.to_string()),
+        };
+    }
+    match provider
+        .chat_streaming_with_tools(model_id, &messages, &tools, &config)
+        .await
+    {
+        Ok(events) => Sse::new(
+            passthrough_event_data(ids, events)
+                .map(|data| Ok::<_, Infallible>(Event::default().data(data))),
+        )
+        .keep_alive(KeepAlive::default())
+        .into_response(),
+        Err(e) => error_response(StatusCode::BAD_GATEWAY, e.to_string()),
+    }
+}
+
+/// A tool call being streamed by the model
+struct StreamedToolCall {
+    id: String,
+    name: String,
+    input: Value,
+    arguments: String,
+}
+
+impl StreamedToolCall {
+    fn into_message(self, index: usize) -> ToolCallMessage {
+        let arguments = if !self.arguments.is_empty() {
+            self.arguments
+        } else if !self.input.is_null() {
+            self.input.to_string()
+        } else {
+            "{}".to_string()
+        };
+        ToolCallMessage {
+            index: Some(index),
+            id: self.id,
+            kind: function_type(),
+            function: FunctionCall {
+                name: self.name,
+                arguments,
+            },
+        }
+    }
+}
+
+/// The call `id` of `tool_calls`, added if the model hasn't started it yet
+fn streamed_tool_call<'a>(
+    tool_calls: &'a mut Vec<StreamedToolCall>,
+    id: &str,
+) -> &'a mut StreamedToolCall {
+    match tool_calls.iter().position(|call| call.id == id) {
+        Some(i) => &mut tool_calls[i],
+        None => {
+            tool_calls.push(StreamedToolCall {
+                id: id.to_string(),
+                name: id.to_string(),
+                input: Value::Null,
+                arguments: String::new(),
+            });
+            tool_calls.last_mut().unwrap()
+        }
+    }
+}
+
+/// Event data of a streaming passthrough request, relaying the model's stream
+fn passthrough_event_data(
+    ids: ResponseIds,
+    mut events: Box<dyn Stream<Item = StreamEvent> + Send + Unpin>,
+) -> impl Stream<Item = String> {
+    async_stream::stream! {
+        yield ids.chunk_data(
+            ChunkDelta {
+                role: Some("assistant".to_string()),
+                ..ChunkDelta::default()
+            },
+            None,
+        );
+
+        let mut tool_calls = Vec::new();
+        while let Some(event) = events.next().await {
+            match event {
+                StreamEvent::ContentBlockDelta {
+                    delta: ContentBlockDelta::Text { text },
+                    ..
+                }
+                | StreamEvent::ContentDelta { delta: text, .. }
+                    if !text.is_empty() =>
+                {
+                    yield ids.chunk_data(
+                        ChunkDelta {
+                            content: Some(text),
+                            ..ChunkDelta::default()
+                        },
+                        None,
+                    );
+                }
+                StreamEvent::ToolCallStart { tool_call } => {
+                    let call = streamed_tool_call(&mut tool_calls, &tool_call.id);
+                    call.name = tool_call.name;
+                    call.input = tool_call.input;
+                }
+                StreamEvent::ContentBlockDelta {
+                    delta: ContentBlockDelta::ToolUse { tool_call_id, input_delta },
+                    ..
+                }
+                | StreamEvent::ToolCallDelta {
+                    tool_call_id,
+                    delta: input_delta,
+                } => {
+                    streamed_tool_call(&mut tool_calls, &tool_call_id)
+                        .arguments
+                        .push_str(&input_delta);
+                }
+                StreamEvent::MessageStop { .. } | StreamEvent::Done { .. } => break,
+                StreamEvent::Error { error } => {
+                    yield error_data(error);
+                    yield "[DONE]".to_string();
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        let finish_reason = if tool_calls.is_empty() {
+            "stop"
+        } else {
+            yield ids.chunk_data(
+                ChunkDelta {
+                    tool_calls: Some(
+                        tool_calls
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, call)| call.into_message(i))
+                            .collect(),
+                    ),
+                    ..ChunkDelta::default()
+                },
+                None,
+            );
+            "tool_calls"
+        };
+        yield ids.chunk_data(ChunkDelta::default(), Some(finish_reason));
+        yield "[DONE]".to_string();
+    }
+}
+
+enum AgentUpdate {
+    Delta(String),
+    Done(Result<String, String>),
+}
+
+/// Forwards response text of a streaming request to its event stream
+struct StreamingHandler {
+    tx: mpsc::UnboundedSender<AgentUpdate>,
+    /// Cancelled once the event stream is gone
+    disconnected: CancellationToken,
+}
+
+#[async_trait]
+impl CallbackHandler for StreamingHandler {
+    async fn handle_event(&self, event: CallbackEvent) -> Result<(), CallbackError> {
+        if let CallbackEvent::ContentDelta {
+            delta,
+            reasoning: false,
+            ..
+        } = event
+        {
+            if !delta.is_empty() && self.tx.send(AgentUpdate::Delta(delta)).is_err() {
+                self.disconnected.cancel();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Event stream of a streaming agent request
+///
+/// `disconnect` is dropped with the stream, stopping the agent if it is
+/// still running.
+fn agent_event_stream(
+    ids: ResponseIds,
+    mut rx: mpsc::UnboundedReceiver<AgentUpdate>,
+    disconnect: DropGuard,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    async_stream::stream! {
+        let _disconnect = disconnect;
+        yield Ok(ids.chunk(
+            ChunkDelta {
+                role: Some("assistant".to_string()),
+                ..ChunkDelta::default()
+            },
+            None,
+        ));
+
+        let mut streamed = false;
+        while let Some(update) = rx.recv().await {
+            match update {
+                AgentUpdate::Delta(text) => {
+                    streamed = true;
+                    yield Ok(ids.chunk(
+                        ChunkDelta {
+                            content: Some(text),
+                            ..ChunkDelta::default()
+                        },
+                        None,
+                    ));
+                }
+                AgentUpdate::Done(Ok(response)) => {
+                    // Agents with streaming disabled only produce the final response
+                    if !streamed {
+                        yield Ok(ids.chunk(
+                            ChunkDelta {
+                                content: Some(response),
+                                ..ChunkDelta::default()
+                            },
+                            None,
+                        ));
+                    }
+                    yield Ok(ids.chunk(ChunkDelta::default(), Some("stop")));
+                    break;
+                }
+                AgentUpdate::Done(Err(error)) => {
+                    yield Ok(Event::default().data(error_data(error)));
+                    break;
+                }
+            }
+        }
+        yield Ok(Event::default().data("[DONE]"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::Agent;
+    use crate::llm::traits::ToolCall;
+
+    #[test]
+    fn test_request_messages_convert_to_stood_messages() {
+        let request: ChatCompletionRequest = serde_json::from_value(json!({
+            "model": "stood",
+            "messages": [
+                { "role": "system", "content": "Be brief" },
+                { "role": "user", "content": [{ "type": "text", "text": "Weather in Paris and Rome?" }] },
+                {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [
+                        { "id": "c1", "type": "function", "function": { "name": "weather", "arguments": "{\"city\":\"Paris\"}" } },
+                        { "id": "c2", "type": "function", "function": { "name": "weather", "arguments": "{\"city\":\"Rome\"}" } }
+                    ]
+                },
+                { "role": "tool", "tool_call_id": "c1", "content": "Sunny" },
+                { "role": "tool", "tool_call_id": "c2", "content": "Rainy" }
+            ],
+            "tools": [{ "type": "function", "function": { "name": "weather", "parameters": { "type": "object" } } }],
+            "stream": true
+        }))
+        .unwrap();
+        assert!(request.stream);
+        assert_eq!(request.tools[0].function.name, "weather");
+
+        let messages = to_stood_messages(&request.messages).unwrap();
+        assert_eq!(messages.system_prompt.as_deref(), Some("Be brief"));
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages.messages[0].text().as_deref(),
+            Some("Weather in Paris and Rome?")
+        );
+        assert!(matches!(
+            &messages.messages[1].content[0],
+            ContentBlock::ToolUse { id, input, .. } if id == "c1" && input["city"] == "Paris"
+        ));
+        assert_eq!(messages.messages[2].role, MessageRole::User);
+        assert_eq!(messages.messages[2].tool_results().len(), 2);
+
+        let bad = [ChatMessage {
+            role: "tool".to_string(),
+            ..ChatMessage::default()
+        }];
+        assert!(to_stood_messages(&bad).is_err());
+    }
+
+    #[test]
+    fn test_completion_serializes_in_openai_format() {
+        let ids = ResponseIds::new("stood");
+        let completion = ids.completion(Completion {
+            content: String::new(),
+            tool_calls: vec![ToolCallMessage {
+                index: Some(0),
+                id: "c1".to_string(),
+                kind: function_type(),
+                function: FunctionCall {
+                    name: "weather".to_string(),
+                    arguments: "{}".to_string(),
+                },
+            }],
+            usage: None,
+        });
+        let value = serde_json::to_value(&completion).unwrap();
+        assert_eq!(value["object"], "chat.completion");
+        assert_eq!(value["model"], "stood");
+        assert_eq!(value["choices"][0]["finish_reason"], "tool_calls");
+        assert_eq!(value["choices"][0]["message"]["role"], "assistant");
+        assert_eq!(
+            value["choices"][0]["message"]["tool_calls"][0]["function"]["name"],
+            "weather"
+        );
+        assert!(value.get("usage").is_none());
+    }
+
+    #[test]
+    fn test_api_key_is_required_once_set() {
+        let open = OpenAiServer::new("stood", Agent::builder);
+        assert!(open.authorized(None));
+
+        let server = open.with_api_key("secret");
+        assert!(server.authorized(Some("Bearer secret")));
+        assert!(!server.authorized(Some("Bearer secrets")));
+        assert!(!server.authorized(Some("Bearer other")));
+        assert!(!server.authorized(Some("secret")));
+        assert!(!server.authorized(None));
+    }
+
+    #[tokio::test]
+    async fn test_passthrough_relays_the_model_stream() {
+        let events = vec![
+            StreamEvent::ContentDelta {
+                delta: "Checking".to_string(),
+                index: 0,
+            },
+            StreamEvent::ToolCallStart {
+                tool_call: ToolCall {
+                    id: "c1".to_string(),
+                    name: "weather".to_string(),
+                    input: Value::Null,
+                },
+            },
+            StreamEvent::ToolCallDelta {
+                tool_call_id: "c1".to_string(),
+                delta: "{\"city\":".to_string(),
+            },
+            StreamEvent::ToolCallDelta {
+                tool_call_id: "c1".to_string(),
+                delta: "\"Paris\"}".to_string(),
+            },
+            StreamEvent::Done { usage: None },
+        ];
+        let data: Vec<String> = passthrough_event_data(
+            ResponseIds::new("stood"),
+            Box::new(futures::stream::iter(events)),
+        )
+        .collect()
+        .await;
+
+        assert_eq!(data.len(), 5);
+        let chunks: Vec<Value> = data[..4]
+            .iter()
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect();
+        assert_eq!(chunks[0]["choices"][0]["delta"]["role"], "assistant");
+        assert_eq!(chunks[1]["choices"][0]["delta"]["content"], "Checking");
+        let call = &chunks[2]["choices"][0]["delta"]["tool_calls"][0];
+        assert_eq!(call["function"]["name"], "weather");
+        assert_eq!(call["function"]["arguments"], "{\"city\":\"Paris\"}");
+        assert_eq!(chunks[3]["choices"][0]["finish_reason"], "tool_calls");
+        assert_eq!(data[4], "[DONE]");
+    }
+
+    #[tokio::test]
+    async fn test_dropped_client_stops_the_agent() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let disconnected = CancellationToken::new();
+        let handler = StreamingHandler {
+            tx,
+            disconnected: disconnected.clone(),
+        };
+
+        let stream = agent_event_stream(ResponseIds::new("stood"), rx, disconnected.drop_guard());
+        drop(stream);
+        assert!(handler.disconnected.is_cancelled());
+
+        // The handler also notices a closed channel on its own
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handler = StreamingHandler {
+            tx,
+            disconnected: CancellationToken::new(),
+        };
+        drop(rx);
+        handler
+            .handle_event(CallbackEvent::ContentDelta {
+                delta: "Hello".to_string(),
+                complete: false,
+                reasoning: false,
+            })
+            .await
+            .unwrap();
+        assert!(handler.disconnected.is_cancelled());
+    }
+}
@@ -0,0 +1,334 @@
+//! Stream agent events to WebSocket clients.
+//!
+//! [`WsCallbackHandler`] is a [`CallbackHandler`] that forwards the events of
+//! an execution to a WebSocket sink as JSON text frames, so chat frontends can
+//! render tokens and tool activity as they happen without inventing their own
+//! protocol.
+//!
+//! # Wire format
+//!
+//! Every frame is one JSON object with a `type` field:
+//!
+//! | `type`          | Fields                                                  |
+//! |-----------------|---------------------------------------------------------|
+//! | `delta`         | `text`, `reasoning` (true for thinking output)          |
+//! | `tool_start`    | `tool_use_id`, `name`, `input`                          |
+//! | `tool_progress` | `tool_use_id`, `name`, `percent` (0 to 100), `message`  |
+//! | `tool_result`   | `tool_use_id`, `name`, `output`, `error`, `duration_ms` |
+//! | `done`          | `response`, `success`, `error`, `cycles`, `duration_ms` |
+//! | `error`         | `message`, `context`                                    |
+//!
+//! For example:
+//!
+//! ```text
+//! {"type":"delta","text":"The weather","reasoning":false}
+//! {"type":"tool_start","tool_use_id":"t1","name":"get_weather","input":{"city":"Paris"}}
+//! {"type":"tool_result","tool_use_id":"t1","name":"get_weather","output":"Sunny","error":null,"duration_ms":12}
+//! {"type":"done","response":"It is sunny in Paris.","success":true,"error":null,"cycles":2,"duration_ms":1840}
+//! ```
+//!
+//! A `done` or `error` frame ends the execution. Other callback events are not
+//! sent.
+//!
+//! # Usage
+//!
+//! The handler works with any [`Sink`] of messages that can be built from a
+//! `String`, such as the write half of a `tokio-tungstenite` stream:
+//!
+//! ```no_run
+//! use futures::StreamExt;
+//! use stood::agent::Agent;
+//! use stood::integrations::ws::WsCallbackHandler;
+//! use tokio::net::TcpListener;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let listener = TcpListener::bind("127.0.0.1:8080").await?;
+//! let (stream, _) = listener.accept().await?;
+//! let socket = tokio_tungstenite::accept_async(stream).await?;
+//! let (sink, mut incoming) = socket.split();
+//!
+//! let mut agent = Agent::builder()
+//!     .with_callback_handler(WsCallbackHandler::new(sink))
+//!     .build()
+//!     .await?;
+//!
+//! while let Some(Ok(message)) = incoming.next().await {
+//!     if let Ok(prompt) = message.to_text() {
+//!         agent.execute(prompt).await?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::{Sink, SinkExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::agent::callbacks::{CallbackError, CallbackEvent, CallbackHandler};
+
+/// One frame of the WebSocket wire format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsEvent {
+    /// Streamed response text
+    Delta {
+        /// Text since the previous delta
+        text: String,
+        /// Whether the text is thinking output rather than the response
+        reasoning: bool,
+    },
+    /// A tool call started
+    ToolStart {
+        /// Id linking the call to its result
+        tool_use_id: String,
+        /// Tool name
+        name: String,
+        /// Tool input
+        input: Value,
+    },
+    /// A running tool call reported its progress
+    ToolProgress {
+        /// Id of the call
+        tool_use_id: String,
+        /// Tool name
+        name: String,
+        /// How far along the call is, from 0 to 100
+        percent: f32,
+        /// What the tool is currently doing
+        message: String,
+    },
+    /// A tool call finished
+    ToolResult {
+        /// Id of the call
+        tool_use_id: String,
+        /// Tool name
+        name: String,
+        /// Tool output, if it succeeded
+        output: Option<Value>,
+        /// Error message, if it failed
+        error: Option<String>,
+        /// How long the call took
+        duration_ms: u64,
+    },
+    /// The execution finished
+    Done {
+        /// Final response text
+        response: String,
+        /// Whether the execution succeeded
+        success: bool,
+        /// Error message, if it failed
+        error: Option<String>,
+        /// Number of event loop cycles
+        cycles: u32,
+        /// Total execution time
+        duration_ms: u64,
+    },
+    /// The execution failed
+    Error {
+        /// Error message
+        message: String,
+        /// Where the error occurred
+        context: String,
+    },
+}
+
+impl WsEvent {
+    /// The frame for a callback event, if the event is part of the wire format
+    pub fn from_callback_event(event: &CallbackEvent) -> Option<Self> {
+        match event {
+            CallbackEvent::ContentDelta {
+                delta, reasoning, ..
+            } if !delta.is_empty() => Some(WsEvent::Delta {
+                text: delta.clone(),
+                reasoning: *reasoning,
+            }),
+            CallbackEvent::ToolStart {
+                tool_name,
+                tool_use_id,
+                input,
+            } => Some(WsEvent::ToolStart {
+                tool_use_id: tool_use_id.clone(),
+                name: tool_name.clone(),
+                input: input.clone(),
+            }),
+            CallbackEvent::ToolProgress { event } => Some(WsEvent::ToolProgress {
+                tool_use_id: event.tool_use_id.clone(),
+                name: event.tool_name.clone(),
+                percent: event.percent,
+                message: event.message.clone(),
+            }),
+            CallbackEvent::ToolComplete {
+                tool_name,
+                tool_use_id,
+                output,
+                error,
+                duration,
+                ..
+            } => Some(WsEvent::ToolResult {
+                tool_use_id: tool_use_id.clone(),
+                name: tool_name.clone(),
+                output: output.clone(),
+                error: error.clone(),
+                duration_ms: duration.as_millis() as u64,
+            }),
+            CallbackEvent::EventLoopComplete {
+                result,
+                total_duration,
+            } => Some(WsEvent::Done {
+                response: result.response.clone(),
+                success: result.success,
+                error: result.error.clone(),
+                cycles: result.cycles_executed,
+                duration_ms: total_duration.as_millis() as u64,
+            }),
+            CallbackEvent::Error { error, context } => Some(WsEvent::Error {
+                message: error.to_string(),
+                context: context.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Serialize the frame to its JSON text
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("WsEvent serialization cannot fail")
+    }
+}
+
+/// Callback handler that sends [`WsEvent`] frames to a WebSocket sink
+///
+/// `M` is the message type of the sink, e.g.
+/// `tokio_tungstenite::tungstenite::Message` or `axum::extract::ws::Message`.
+pub struct WsCallbackHandler<S, M = tokio_tungstenite::tungstenite::Message> {
+    sink: Mutex<S>,
+    _message: PhantomData<fn(String) -> M>,
+}
+
+impl<S, M> WsCallbackHandler<S, M>
+where
+    S: Sink<M> + Send + Unpin,
+    S::Error: std::fmt::Display,
+    M: From<String> + Send,
+{
+    /// Send frames to `sink`
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink: Mutex::new(sink),
+            _message: PhantomData,
+        }
+    }
+
+    /// Send one frame
+    pub async fn send(&self, event: &WsEvent) -> Result<(), CallbackError> {
+        self.sink
+            .lock()
+            .await
+            .send(M::from(event.to_json()))
+            .await
+            .map_err(|e| CallbackError::ExecutionFailed(format!("WebSocket send failed: {}", e)))
+    }
+
+    /// Take back the sink, e.g. to close the connection
+    pub fn into_inner(self) -> S {
+        self.sink.into_inner()
+    }
+}
+
+#[async_trait]
+impl<S, M> CallbackHandler for WsCallbackHandler<S, M>
+where
+    S: Sink<M> + Send + Unpin,
+    S::Error: std::fmt::Display,
+    M: From<String> + Send,
+{
+    async fn handle_event(&self, event: CallbackEvent) -> Result<(), CallbackError> {
+        match WsEvent::from_callback_event(&event) {
+            Some(frame) => self.send(&frame).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_events_are_sent_as_json_frames() {
+        let (tx, rx) = futures::channel::mpsc::unbounded::<String>();
+        let handler = WsCallbackHandler::<_, String>::new(tx);
+
+        handler
+            .handle_event(CallbackEvent::ContentDelta {
+                delta: "Hi".to_string(),
+                complete: false,
+                reasoning: false,
+            })
+            .await
+            .unwrap();
+        handler
+            .handle_event(CallbackEvent::ToolProgress {
+                event: crate::agent::callbacks::ToolProgressEvent {
+                    tool_name: "calc".to_string(),
+                    tool_use_id: "t1".to_string(),
+                    percent: 50.0,
+                    message: "adding".to_string(),
+                },
+            })
+            .await
+            .unwrap();
+        handler
+            .handle_event(CallbackEvent::ToolComplete {
+                tool_name: "calc".to_string(),
+                tool_use_id: "t1".to_string(),
+                output: Some(json!(4)),
+                error: None,
+                error_source: None,
+                duration: Duration::from_millis(5),
+            })
+            .await
+            .unwrap();
+        handler
+            .handle_event(CallbackEvent::ParallelStart {
+                tool_count: 1,
+                max_parallel: 1,
+            })
+            .await
+            .unwrap();
+        drop(handler);
+
+        let frames: Vec<Value> = rx
+            .map(|frame| serde_json::from_str(&frame).unwrap())
+            .collect()
+            .await;
+        assert_eq!(
+            frames,
+            vec![
+                json!({"type": "delta", "text": "Hi", "reasoning": false}),
+                json!({
+                    "type": "tool_progress",
+                    "tool_use_id": "t1",
+                    "name": "calc",
+                    "percent": 50.0,
+                    "message": "adding"
+                }),
+                json!({
+                    "type": "tool_result",
+                    "tool_use_id": "t1",
+                    "name": "calc",
+                    "output": 4,
+                    "error": null,
+                    "duration_ms": 5
+                }),
+            ]
+        );
+    }
+}
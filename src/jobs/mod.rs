@@ -0,0 +1,534 @@
+//! Background agent jobs.
+//!
+//! A [`JobRunner`] accepts agent tasks onto a queue and runs them in the
+//! background, at most [`JobRunnerConfig::max_concurrent_jobs`] at a time.
+//! Callers get a job ID back from [`JobRunner::submit`], then poll
+//! [`JobRunner::status`], wait with [`JobRunner::wait`], or receive the
+//! finished job on a webhook. Jobs and their results are kept in a
+//! [`JobStore`]: [`InMemoryJobStore`] for a single process, or
+//! `RedisJobStore` (behind the `redis` feature) to share the queue between
+//! worker processes and keep results across restarts.
+//!
+//! Jobs are run by a [`JobHandler`]. [`AgentPool`] is one, running each job
+//! on an agent checked out for the job's tenant and profile:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use stood::agent::pool::{AgentPool, AgentPoolConfig};
+//! use stood::agent::Agent;
+//! use stood::jobs::{InMemoryJobStore, JobRequest, JobRunner, JobRunnerConfig};
+//!
+//! # async fn example() -> Result<(), stood::StoodError> {
+//! let pool = AgentPool::new(AgentPoolConfig::new()).with_profile("research", || async {
+//!     Agent::builder().build().await
+//! });
+//! let runner = JobRunner::new(
+//!     pool,
+//!     Arc::new(InMemoryJobStore::new()),
+//!     JobRunnerConfig::new().with_max_concurrent_jobs(8),
+//! );
+//! runner.start();
+//!
+//! let id = runner
+//!     .submit(
+//!         JobRequest::new("research", "Compare the three largest cloud providers")
+//!             .with_webhook("https://example.com/hooks/jobs"),
+//!     )
+//!     .await?;
+//! let job = runner.wait(&id, Duration::from_secs(600)).await?;
+//! println!("{:?}: {:?}", job.status, job.output.map(|o| o.response));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod store;
+
+#[cfg(feature = "redis")]
+pub mod redis;
+
+pub use store::{InMemoryJobStore, JobStore};
+
+#[cfg(feature = "redis")]
+pub use self::redis::RedisJobStore;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Notify, Semaphore};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::agent::pool::AgentPool;
+use crate::agent::AgentResult;
+use crate::llm::http::HttpClientConfig;
+use crate::{Result, StoodError};
+
+/// Tenant of jobs submitted without one
+pub const DEFAULT_TENANT: &str = "default";
+
+/// An agent task to run in the background
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobRequest {
+    /// Prompt to execute
+    pub prompt: String,
+    /// Agent configuration profile, e.g. an [`AgentPool`] profile
+    pub profile: String,
+    /// Tenant the job runs for
+    pub tenant: String,
+    /// URL the finished job is POSTed to as JSON
+    pub webhook_url: Option<String>,
+    /// Caller-defined data kept with the job
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl JobRequest {
+    pub fn new(profile: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            profile: profile.into(),
+            tenant: DEFAULT_TENANT.to_string(),
+            webhook_url: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = tenant.into();
+        self
+    }
+
+    pub fn with_webhook(mut self, url: impl Into<String>) -> Self {
+        self.webhook_url = Some(url.into());
+        self
+    }
+
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Lifecycle state of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Whether the job has reached a final state
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// What a job produced
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JobOutput {
+    /// Final response of the agent
+    pub response: String,
+    /// Reasoning cycles executed
+    pub cycles: u32,
+    /// Tools called during the job
+    pub tools_called: Vec<String>,
+    /// Input tokens used, if reported
+    pub input_tokens: Option<u32>,
+    /// Output tokens used, if reported
+    pub output_tokens: Option<u32>,
+    /// Time the agent spent executing
+    pub duration: Duration,
+}
+
+impl From<&AgentResult> for JobOutput {
+    fn from(result: &AgentResult) -> Self {
+        let tokens = result.execution.tokens.as_ref();
+        Self {
+            response: result.response.clone(),
+            cycles: result.execution.cycles,
+            tools_called: result.tools_called.clone(),
+            input_tokens: tokens.map(|t| t.input_tokens),
+            output_tokens: tokens.map(|t| t.output_tokens),
+            duration: result.duration,
+        }
+    }
+}
+
+/// A submitted job and its state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub request: JobRequest,
+    pub status: JobStatus,
+    /// Output of a succeeded job
+    pub output: Option<JobOutput>,
+    /// Why a job failed
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl Job {
+    /// A queued job for `request` with a new ID
+    pub fn new(request: JobRequest) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            request,
+            status: JobStatus::Queued,
+            output: None,
+            error: None,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+
+    fn finish(&mut self, status: JobStatus) {
+        self.status = status;
+        self.finished_at = Some(Utc::now());
+    }
+}
+
+/// Runs the jobs of a [`JobRunner`]
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    async fn run(&self, request: &JobRequest) -> Result<JobOutput>;
+}
+
+#[async_trait]
+impl JobHandler for AgentPool {
+    /// Execute the prompt on an agent checked out for the job's tenant and profile
+    async fn run(&self, request: &JobRequest) -> Result<JobOutput> {
+        let mut agent = self.checkout(&request.tenant, &request.profile).await?;
+        let result = agent.execute(request.prompt.as_str()).await?;
+        match &result.error {
+            Some(error) if !result.success => Err(StoodError::model_error(error.clone())),
+            _ => Ok(JobOutput::from(&result)),
+        }
+    }
+}
+
+/// Limits of a [`JobRunner`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRunnerConfig {
+    /// Jobs run at the same time
+    pub max_concurrent_jobs: usize,
+    /// How often an idle runner checks the store for jobs submitted elsewhere
+    pub poll_interval: Duration,
+    /// Jobs running longer than this fail; unlimited when unset
+    pub job_timeout: Option<Duration>,
+    /// How long to wait for a webhook to respond
+    pub webhook_timeout: Duration,
+}
+
+impl Default for JobRunnerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_jobs: 4,
+            poll_interval: Duration::from_millis(500),
+            job_timeout: None,
+            webhook_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl JobRunnerConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_concurrent_jobs(mut self, max: usize) -> Self {
+        self.max_concurrent_jobs = max.max(1);
+        self
+    }
+
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    pub fn with_job_timeout(mut self, timeout: Duration) -> Self {
+        self.job_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_webhook_timeout(mut self, timeout: Duration) -> Self {
+        self.webhook_timeout = timeout;
+        self
+    }
+}
+
+/// Queue of background agent jobs
+///
+/// Clones share the same queue and workers.
+#[derive(Clone)]
+pub struct JobRunner {
+    inner: Arc<RunnerInner>,
+}
+
+struct RunnerInner {
+    handler: Arc<dyn JobHandler>,
+    store: Arc<dyn JobStore>,
+    config: JobRunnerConfig,
+    slots: Arc<Semaphore>,
+    submitted: Notify,
+    shutdown: CancellationToken,
+    http: reqwest::Client,
+}
+
+impl JobRunner {
+    pub fn new(
+        handler: impl JobHandler + 'static,
+        store: Arc<dyn JobStore>,
+        config: JobRunnerConfig,
+    ) -> Self {
+        Self {
+            inner: Arc::new(RunnerInner {
+                handler: Arc::new(handler),
+                store,
+                slots: Arc::new(Semaphore::new(config.max_concurrent_jobs.max(1))),
+                config,
+                submitted: Notify::new(),
+                shutdown: CancellationToken::new(),
+                http: HttpClientConfig::default()
+                    .build_client()
+                    .expect("Failed to create HTTP client"),
+            }),
+        }
+    }
+
+    /// Queue a job and return its ID
+    pub async fn submit(&self, request: JobRequest) -> Result<String> {
+        let job = Job::new(request);
+        self.inner.store.enqueue(&job).await?;
+        self.inner.submitted.notify_one();
+        tracing::debug!(job_id = %job.id, "Queued job");
+        Ok(job.id)
+    }
+
+    /// The current state of job `id`
+    pub async fn status(&self, id: &str) -> Result<Option<Job>> {
+        self.inner.store.get(id).await
+    }
+
+    /// Wait until job `id` has finished, polling the store
+    pub async fn wait(&self, id: &str, timeout: Duration) -> Result<Job> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let job = self
+                .status(id)
+                .await?
+                .ok_or_else(|| StoodError::resource_not_found(format!("No job '{}'", id)))?;
+            if job.status.is_finished() {
+                return Ok(job);
+            }
+            if Instant::now() >= deadline {
+                return Err(StoodError::timeout_error(timeout.as_millis() as u64));
+            }
+            crate::runtime::sleep(self.inner.config.poll_interval).await;
+        }
+    }
+
+    /// Cancel job `id` if it has not started yet
+    ///
+    /// Returns whether the job was cancelled.
+    pub async fn cancel(&self, id: &str) -> Result<bool> {
+        let Some(mut job) = self.inner.store.get(id).await? else {
+            return Ok(false);
+        };
+        if job.status != JobStatus::Queued {
+            return Ok(false);
+        }
+        job.finish(JobStatus::Cancelled);
+        self.inner.store.save(&job).await?;
+        Ok(true)
+    }
+
+    /// Start the worker that takes jobs off the queue
+    ///
+    /// Running jobs finish after [`stop`](Self::stop); queued jobs stay in
+    /// the store.
+    pub fn start(&self) -> crate::runtime::JoinHandle<()> {
+        let inner = self.inner.clone();
+        crate::runtime::spawn(async move { inner.work().await })
+    }
+
+    /// Stop taking jobs off the queue
+    pub fn stop(&self) {
+        self.inner.shutdown.cancel();
+    }
+
+    pub fn config(&self) -> &JobRunnerConfig {
+        &self.inner.config
+    }
+}
+
+impl RunnerInner {
+    async fn work(self: Arc<Self>) {
+        match self.store.recover().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Requeued {} interrupted jobs", count),
+            Err(e) => tracing::warn!("Failed to requeue interrupted jobs: {}", e),
+        }
+        loop {
+            let permit = tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                permit = self.slots.clone().acquire_owned() => match permit {
+                    Ok(permit) => permit,
+                    Err(_) => break,
+                },
+            };
+            match self.store.dequeue().await {
+                Ok(Some(job)) if job.status == JobStatus::Queued => {
+                    let inner = self.clone();
+                    crate::runtime::spawn(async move {
+                        inner.run(job).await;
+                        drop(permit);
+                    });
+                }
+                // Cancelled while queued
+                Ok(Some(job)) => self.complete(&job).await,
+                Ok(None) => {
+                    drop(permit);
+                    tokio::select! {
+                        _ = self.shutdown.cancelled() => break,
+                        _ = self.submitted.notified() => {}
+                        _ = crate::runtime::sleep(self.config.poll_interval) => {}
+                    }
+                }
+                Err(e) => {
+                    drop(permit);
+                    tracing::warn!("Failed to take a job off the queue: {}", e);
+                    crate::runtime::sleep(self.config.poll_interval).await;
+                }
+            }
+        }
+        tracing::debug!("Job runner stopped");
+    }
+
+    async fn run(&self, mut job: Job) {
+        job.status = JobStatus::Running;
+        job.started_at = Some(Utc::now());
+        if let Err(e) = self.store.save(&job).await {
+            tracing::warn!(job_id = %job.id, "Failed to save job state: {}", e);
+        }
+
+        let run = self.handler.run(&job.request);
+        let result = match self.config.job_timeout {
+            Some(timeout) => crate::runtime::timeout(timeout, run)
+                .await
+                .unwrap_or_else(|_| Err(StoodError::timeout_error(timeout.as_millis() as u64))),
+            None => run.await,
+        };
+        match result {
+            Ok(output) => {
+                job.output = Some(output);
+                job.finish(JobStatus::Succeeded);
+            }
+            Err(e) => {
+                tracing::warn!(job_id = %job.id, "Job failed: {}", e);
+                job.error = Some(e.to_string());
+                job.finish(JobStatus::Failed);
+            }
+        }
+        // A job whose result could not be saved is left to be run again
+        match self.store.save(&job).await {
+            Ok(()) => self.complete(&job).await,
+            Err(e) => tracing::error!(job_id = %job.id, "Failed to save job result: {}", e),
+        }
+        if let Some(url) = &job.request.webhook_url {
+            self.notify_webhook(url, &job).await;
+        }
+    }
+
+    async fn complete(&self, job: &Job) {
+        if let Err(e) = self.store.complete(&job.id).await {
+            tracing::warn!(job_id = %job.id, "Failed to complete job: {}", e);
+        }
+    }
+
+    async fn notify_webhook(&self, url: &str, job: &Job) {
+        let response = self
+            .http
+            .post(url)
+            .timeout(self.config.webhook_timeout)
+            .json(job)
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => tracing::warn!(
+                job_id = %job.id,
+                "Webhook {} responded with {}",
+                url,
+                response.status()
+            ),
+            Err(e) => tracing::warn!(job_id = %job.id, "Webhook {} failed: {}", url, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    #[async_trait]
+    impl JobHandler for Echo {
+        async fn run(&self, request: &JobRequest) -> Result<JobOutput> {
+            if request.prompt == "fail" {
+                return Err(StoodError::tool_error("boom"));
+            }
+            Ok(JobOutput {
+                response: request.prompt.to_uppercase(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runner_runs_and_cancels_jobs() {
+        let runner = JobRunner::new(
+            Echo,
+            Arc::new(InMemoryJobStore::new()),
+            JobRunnerConfig::new().with_poll_interval(Duration::from_millis(10)),
+        );
+        let queued = runner
+            .submit(JobRequest::new("echo", "never"))
+            .await
+            .unwrap();
+        assert!(runner.cancel(&queued).await.unwrap());
+
+        let ok = runner
+            .submit(JobRequest::new("echo", "hello"))
+            .await
+            .unwrap();
+        let failed = runner
+            .submit(JobRequest::new("echo", "fail"))
+            .await
+            .unwrap();
+        runner.start();
+
+        let job = runner.wait(&ok, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.output.unwrap().response, "HELLO");
+        let job = runner.wait(&failed, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert!(job.error.unwrap().contains("boom"));
+        let job = runner.status(&queued).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert!(job.started_at.is_none());
+        runner.stop();
+    }
+}
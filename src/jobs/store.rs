@@ -0,0 +1,102 @@
+//! Storage of job queues and results.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use super::Job;
+use crate::Result;
+
+/// Queue of pending jobs and record of every job's state
+///
+/// Several runners may share a store; [`dequeue`](Self::dequeue) must hand
+/// each job to only one of them. Stores that survive a crash keep dequeued
+/// jobs until [`complete`](Self::complete) and hand them out again from
+/// [`recover`](Self::recover), so a job is run at least once.
+#[async_trait]
+pub trait JobStore: Send + Sync + fmt::Debug {
+    /// Save a new job and add it to the end of the queue
+    async fn enqueue(&self, job: &Job) -> Result<()>;
+
+    /// Take the job at the front of the queue
+    ///
+    /// Jobs cancelled while queued are returned too, with their final status.
+    async fn dequeue(&self) -> Result<Option<Job>>;
+
+    /// Forget dequeued job `id`, whose final state has been saved
+    async fn complete(&self, _id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Put the jobs dequeued but not completed before a restart back on the
+    /// queue, returning how many there were
+    async fn recover(&self) -> Result<usize> {
+        Ok(0)
+    }
+
+    /// Save the current state of a job
+    async fn save(&self, job: &Job) -> Result<()>;
+
+    /// The saved state of job `id`
+    async fn get(&self, id: &str) -> Result<Option<Job>>;
+}
+
+/// Job store for a single process
+///
+/// Jobs and results are lost when the process exits.
+#[derive(Debug, Default)]
+pub struct InMemoryJobStore {
+    state: Mutex<InMemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryState {
+    queue: VecDeque<String>,
+    jobs: HashMap<String, Job>,
+}
+
+impl InMemoryJobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of jobs waiting in the queue
+    pub fn queued(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, InMemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl JobStore for InMemoryJobStore {
+    async fn enqueue(&self, job: &Job) -> Result<()> {
+        let mut state = self.lock();
+        state.jobs.insert(job.id.clone(), job.clone());
+        state.queue.push_back(job.id.clone());
+        Ok(())
+    }
+
+    async fn dequeue(&self) -> Result<Option<Job>> {
+        let mut state = self.lock();
+        while let Some(id) = state.queue.pop_front() {
+            if let Some(job) = state.jobs.get(&id) {
+                return Ok(Some(job.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn save(&self, job: &Job) -> Result<()> {
+        self.lock().jobs.insert(job.id.clone(), job.clone());
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Job>> {
+        Ok(self.lock().jobs.get(id).cloned())
+    }
+}
@@ -123,6 +123,9 @@
 //!
 //! - `telemetry` - OpenTelemetry integration for observability
 //! - `http` - HTTP health endpoints for service monitoring
+//! - `database` - SQL query and schema tools via sqlx (Postgres, MySQL, SQLite)
+//! - `plugins` - Tools loaded at runtime from WASM modules (see `plugins`)
+//! - `sled-store` / `sqlite-store` - Persistent [`storage::KvStore`] implementations
 //! - `examples` - Additional example code and development utilities
 //!
 //! # Module Organization
@@ -133,10 +136,18 @@
 //! - [`types`] - Shared data structures for messages, models, and configurations
 //! - [`mcp`] - Model Context Protocol client and server implementations
 //! - [`error`] - Comprehensive error types and recovery strategies
+//! - [`integrations`] - Helpers for serving agents, e.g. over WebSockets
+//! - [`jobs`] - Background agent jobs with queueing, status polling and webhooks
 //! - [`performance`] - Optimization utilities and metrics collection
+//! - [`proxy`] - Outbound proxy for providers, HTTP tools and MCP connections
+//! - [`runtime`] - Async runtime abstraction for spawning tasks and timers
+//! - [`schedule`] - Recurring agent executions on intervals or cron expressions
+//! - [`storage`] - Key-value storage shared by caching, reliability tracking and checkpoints
 //! - [`telemetry`] - Logging and observability integration
+//! - [`workflow`] - Multi-step agent workflows defined in YAML
 
 pub mod agent;
+pub mod audio;
 // Bedrock functionality now in llm::providers::bedrock
 pub mod config;
 pub mod context_manager;
@@ -144,17 +155,27 @@ pub mod conversation_manager;
 pub mod error;
 pub mod error_recovery;
 pub mod health;
+pub mod integrations;
+pub mod jobs;
 pub mod llm;
 pub mod mcp;
 pub mod message_processor;
 pub mod parallel;
 pub mod performance;
+#[cfg(feature = "plugins")]
+pub mod plugins;
+pub mod proxy;
+pub mod runtime;
+pub mod schedule;
+pub mod secrets;
 pub mod shutdown;
+pub mod storage;
 pub mod streaming;
 pub mod telemetry;
 pub mod tools;
 pub mod types;
 pub mod utils;
+pub mod workflow;
 
 #[cfg(feature = "perf-timing")]
 pub mod perf_timing;
@@ -196,6 +217,9 @@ pub use types::*;
 
 pub use stood_macros::tool;
 
+// Re-exported so extraction targets can derive `JsonSchema` without their own dependency
+pub use schemars;
+
 pub type Result<T> = std::result::Result<T, StoodError>;
 
 #[cfg(feature = "verification")]
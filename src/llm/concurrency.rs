@@ -0,0 +1,488 @@
+//! Throttling-aware concurrency control for provider requests.
+//!
+//! A static `max_parallel` setting is either too low to use the available
+//! quota or high enough to trigger throttling, after which every parallel
+//! request retries into the same quota. [`AdaptiveConcurrency`] adjusts the
+//! number of requests in flight with AIMD (additive increase, multiplicative
+//! decrease): each throttling error cuts the limit by
+//! [`AimdConfig::backoff_ratio`], and each run of successful requests as long
+//! as the current limit raises it by one, up to [`AimdConfig::max_limit`].
+//!
+//! Install a controller on a provider type and every request of that provider,
+//! from any agent, waits for a slot:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use stood::llm::concurrency::{AdaptiveConcurrency, AimdConfig};
+//! use stood::llm::registry::PROVIDER_REGISTRY;
+//! use stood::llm::traits::ProviderType;
+//!
+//! # async fn example() {
+//! let controller = Arc::new(AdaptiveConcurrency::new(AimdConfig::default()));
+//! PROVIDER_REGISTRY
+//!     .set_concurrency_controller(ProviderType::Bedrock, controller.clone())
+//!     .await;
+//! # }
+//! ```
+//!
+//! Parallel work that issues requests, such as parallel tool execution, can
+//! follow the same limit with [`AdaptiveConcurrency::acquire_task`] (see
+//! [`ExecutorConfig::adaptive_concurrency`]). Tasks and requests have separate
+//! slots, so a task waiting on its own model request never deadlocks.
+//!
+//! [`ExecutorConfig::adaptive_concurrency`]: crate::tools::ExecutorConfig::adaptive_concurrency
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::Stream;
+use tokio::sync::Notify;
+
+use crate::llm::traits::{
+    ChatConfig, ChatResponse, HealthStatus, LlmError, LlmProvider, ProviderCapabilities,
+    ProviderType, StreamEvent, Tool,
+};
+use crate::types::Messages;
+
+/// Limits and rates of an [`AdaptiveConcurrency`] controller
+#[derive(Debug, Clone, PartialEq)]
+pub struct AimdConfig {
+    /// Limit before any feedback
+    pub initial_limit: usize,
+    /// Limit never goes below this
+    pub min_limit: usize,
+    /// Limit never goes above this
+    pub max_limit: usize,
+    /// Factor the limit is multiplied by on throttling (0.0 to 1.0)
+    pub backoff_ratio: f64,
+    /// Throttling errors within this time of a decrease do not decrease the
+    /// limit again, since they come from requests sent before the decrease
+    pub decrease_cooldown: Duration,
+}
+
+impl Default for AimdConfig {
+    fn default() -> Self {
+        Self {
+            initial_limit: 4,
+            min_limit: 1,
+            max_limit: 32,
+            backoff_ratio: 0.5,
+            decrease_cooldown: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Adaptive limit on concurrent provider requests
+#[derive(Debug)]
+pub struct AdaptiveConcurrency {
+    config: AimdConfig,
+    state: Mutex<State>,
+    released: Notify,
+}
+
+#[derive(Debug)]
+struct State {
+    limit: usize,
+    requests: usize,
+    tasks: usize,
+    successes: usize,
+    throttles: u64,
+    last_decrease: Option<Instant>,
+}
+
+/// What a [`ConcurrencyPermit`] holds a slot for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotKind {
+    Request,
+    Task,
+}
+
+impl State {
+    fn in_use(&mut self, kind: SlotKind) -> &mut usize {
+        match kind {
+            SlotKind::Request => &mut self.requests,
+            SlotKind::Task => &mut self.tasks,
+        }
+    }
+}
+
+impl AdaptiveConcurrency {
+    /// Create a controller starting at `config.initial_limit`
+    pub fn new(config: AimdConfig) -> Self {
+        let min_limit = config.min_limit.max(1);
+        let limit = config
+            .initial_limit
+            .clamp(min_limit, config.max_limit.max(min_limit));
+        Self {
+            config,
+            state: Mutex::new(State {
+                limit,
+                requests: 0,
+                tasks: 0,
+                successes: 0,
+                throttles: 0,
+                last_decrease: None,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    /// The configuration
+    pub fn config(&self) -> &AimdConfig {
+        &self.config
+    }
+
+    /// Current concurrency limit
+    pub fn limit(&self) -> usize {
+        self.lock().limit
+    }
+
+    /// Requests currently in flight
+    pub fn in_flight(&self) -> usize {
+        self.lock().requests
+    }
+
+    /// Throttling errors seen so far
+    pub fn throttle_count(&self) -> u64 {
+        self.lock().throttles
+    }
+
+    /// Wait for a request slot
+    pub async fn acquire(self: &Arc<Self>) -> ConcurrencyPermit {
+        self.acquire_slot(SlotKind::Request).await
+    }
+
+    /// Wait for a slot for a unit of parallel work, e.g. a tool call
+    ///
+    /// Task slots follow the same limit as request slots but are counted
+    /// separately.
+    pub async fn acquire_task(self: &Arc<Self>) -> ConcurrencyPermit {
+        self.acquire_slot(SlotKind::Task).await
+    }
+
+    async fn acquire_slot(self: &Arc<Self>, kind: SlotKind) -> ConcurrencyPermit {
+        loop {
+            let released = self.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+            {
+                let mut state = self.lock();
+                let limit = state.limit;
+                let in_use = state.in_use(kind);
+                if *in_use < limit {
+                    *in_use += 1;
+                    return ConcurrencyPermit {
+                        controller: Arc::clone(self),
+                        kind,
+                    };
+                }
+            }
+            released.await;
+        }
+    }
+
+    /// Record a successful request, raising the limit by one after a run of
+    /// successes as long as the limit
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        state.successes += 1;
+        if state.successes >= state.limit && state.limit < self.config.max_limit {
+            state.limit += 1;
+            state.successes = 0;
+            tracing::debug!("Adaptive concurrency limit raised to {}", state.limit);
+            drop(state);
+            self.released.notify_waiters();
+        }
+    }
+
+    /// Record a throttling error, cutting the limit unless it was just cut
+    pub fn record_throttle(&self) {
+        let mut state = self.lock();
+        state.throttles += 1;
+        state.successes = 0;
+        if state
+            .last_decrease
+            .is_some_and(|at| at.elapsed() < self.config.decrease_cooldown)
+        {
+            return;
+        }
+        let reduced = (state.limit as f64 * self.config.backoff_ratio).floor() as usize;
+        state.limit = reduced.max(self.config.min_limit.max(1));
+        state.last_decrease = Some(Instant::now());
+        tracing::warn!(
+            "Throttled by provider, adaptive concurrency limit lowered to {}",
+            state.limit
+        );
+    }
+
+    /// Record the outcome of a request
+    pub fn record_result<T>(&self, result: &Result<T, LlmError>) {
+        match result {
+            Ok(_) => self.record_success(),
+            Err(error) if is_throttling_error(error) => self.record_throttle(),
+            Err(_) => {}
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A request or task slot, released on drop
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    controller: Arc<AdaptiveConcurrency>,
+    kind: SlotKind,
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        {
+            let mut state = self.controller.lock();
+            let in_use = state.in_use(self.kind);
+            *in_use = in_use.saturating_sub(1);
+        }
+        self.controller.released.notify_waiters();
+    }
+}
+
+/// Whether an error means the provider is throttling requests
+pub fn is_throttling_error(error: &LlmError) -> bool {
+    match error {
+        LlmError::RateLimitError { .. } => true,
+        LlmError::ProviderError { message, .. } | LlmError::NetworkError { message, .. } => {
+            is_throttling_message(message)
+        }
+        _ => false,
+    }
+}
+
+fn is_throttling_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("throttl")
+        || message.contains("too many requests")
+        || message.contains("rate exceeded")
+        || message.contains("429")
+}
+
+/// Provider whose requests wait for a slot of an [`AdaptiveConcurrency`]
+/// controller and report their outcome to it
+///
+/// Streaming requests hold their slot until the stream ends.
+#[derive(Debug)]
+pub struct ConcurrencyLimitedProvider {
+    inner: Arc<dyn LlmProvider>,
+    controller: Arc<AdaptiveConcurrency>,
+}
+
+impl ConcurrencyLimitedProvider {
+    /// Limit the requests of `inner` with `controller`
+    pub fn new(inner: Arc<dyn LlmProvider>, controller: Arc<AdaptiveConcurrency>) -> Self {
+        Self { inner, controller }
+    }
+
+    /// The controller limiting this provider
+    pub fn controller(&self) -> &Arc<AdaptiveConcurrency> {
+        &self.controller
+    }
+
+    fn limit_stream(
+        &self,
+        result: Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError>,
+        permit: ConcurrencyPermit,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        match result {
+            Ok(stream) => Ok(Box::new(PermitStream {
+                inner: stream,
+                permit: Some(permit),
+                failed: false,
+            })),
+            Err(error) => {
+                if is_throttling_error(&error) {
+                    self.controller.record_throttle();
+                }
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Stream that releases its permit and reports the outcome when it ends
+struct PermitStream {
+    inner: Box<dyn Stream<Item = StreamEvent> + Send + Unpin>,
+    permit: Option<ConcurrencyPermit>,
+    failed: bool,
+}
+
+impl Stream for PermitStream {
+    type Item = StreamEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<StreamEvent>> {
+        let poll = Pin::new(&mut self.inner).poll_next(cx);
+        match &poll {
+            Poll::Ready(Some(StreamEvent::Error { error })) => {
+                if let Some(permit) = &self.permit {
+                    if is_throttling_message(error) {
+                        permit.controller.record_throttle();
+                    }
+                }
+                self.failed = true;
+            }
+            Poll::Ready(None) => {
+                if let Some(permit) = self.permit.take() {
+                    if !self.failed {
+                        permit.controller.record_success();
+                    }
+                }
+            }
+            _ => {}
+        }
+        poll
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ConcurrencyLimitedProvider {
+    async fn chat(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        config: &ChatConfig,
+    ) -> Result<ChatResponse, LlmError> {
+        let _permit = self.controller.acquire().await;
+        let result = self.inner.chat(model_id, messages, config).await;
+        self.controller.record_result(&result);
+        result
+    }
+
+    async fn chat_with_tools(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+    ) -> Result<ChatResponse, LlmError> {
+        let _permit = self.controller.acquire().await;
+        let result = self
+            .inner
+            .chat_with_tools(model_id, messages, tools, config)
+            .await;
+        self.controller.record_result(&result);
+        result
+    }
+
+    async fn chat_streaming(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        config: &ChatConfig,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        let permit = self.controller.acquire().await;
+        let result = self.inner.chat_streaming(model_id, messages, config).await;
+        self.limit_stream(result, permit)
+    }
+
+    async fn chat_streaming_with_tools(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        let permit = self.controller.acquire().await;
+        let result = self
+            .inner
+            .chat_streaming_with_tools(model_id, messages, tools, config)
+            .await;
+        self.limit_stream(result, permit)
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, LlmError> {
+        self.inner.health_check().await
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        self.inner.provider_type()
+    }
+
+    fn supported_models(&self) -> Vec<&'static str> {
+        self.inner.supported_models()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self.inner.as_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller(initial_limit: usize) -> Arc<AdaptiveConcurrency> {
+        Arc::new(AdaptiveConcurrency::new(AimdConfig {
+            initial_limit,
+            max_limit: 8,
+            decrease_cooldown: Duration::from_secs(60),
+            ..AimdConfig::default()
+        }))
+    }
+
+    #[test]
+    fn test_limit_decreases_on_throttle_and_recovers() {
+        let controller = controller(8);
+        controller.record_result::<()>(&Err(LlmError::RateLimitError {
+            provider: ProviderType::Bedrock,
+            retry_after: None,
+        }));
+        assert_eq!(controller.limit(), 4);
+
+        // Throttles from requests sent before the decrease are ignored
+        controller.record_throttle();
+        assert_eq!(controller.limit(), 4);
+        assert_eq!(controller.throttle_count(), 2);
+
+        for _ in 0..4 {
+            controller.record_success();
+        }
+        assert_eq!(controller.limit(), 5);
+
+        assert!(is_throttling_error(&LlmError::ProviderError {
+            provider: ProviderType::Bedrock,
+            message: "🚨 ThrottlingException: Too many requests".to_string(),
+            source: None,
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_permits_wait_for_a_free_slot() {
+        let controller = controller(1);
+        let first = controller.acquire().await;
+        // Task slots are counted separately from request slots
+        let _task = controller.acquire_task().await;
+
+        let waiting = tokio::spawn({
+            let controller = Arc::clone(&controller);
+            async move {
+                let _second = controller.acquire().await;
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+        assert_eq!(controller.in_flight(), 1);
+
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(controller.in_flight(), 0);
+    }
+}
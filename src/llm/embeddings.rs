@@ -0,0 +1,119 @@
+//! Text embeddings for comparing responses by meaning.
+//!
+//! An [`Embedder`] maps texts to vectors whose [`cosine_similarity`] is high
+//! when the texts say the same thing in different words.
+//! [`BedrockEmbedder`] uses Amazon Titan Text Embeddings on Bedrock.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use aws_sdk_bedrockruntime::primitives::Blob;
+use aws_sdk_bedrockruntime::Client;
+
+use super::http::aws_config_loader;
+use super::traits::{LlmError, ProviderType};
+
+/// Converts texts to embedding vectors
+#[async_trait]
+pub trait Embedder: Send + Sync + std::fmt::Debug {
+    /// One vector per text, in order
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError>;
+}
+
+/// Cosine similarity of two vectors, from -1 to 1 (0 if either is zero or
+/// their lengths differ)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Embeddings from Amazon Titan Text Embeddings on Bedrock
+#[derive(Debug, Clone)]
+pub struct BedrockEmbedder {
+    client: Client,
+    model_id: Arc<str>,
+}
+
+impl BedrockEmbedder {
+    /// Titan Text Embeddings v2
+    pub const TITAN_TEXT_V2: &'static str = "amazon.titan-embed-text-v2:0";
+
+    /// Create a client for `model_id` using the default AWS credential chain
+    /// and the installed [proxy](crate::proxy)
+    pub async fn connect(model_id: impl Into<String>) -> Result<Self, LlmError> {
+        let sdk_config = aws_config_loader()?.load().await;
+        Ok(Self::from_client(Client::new(&sdk_config), model_id))
+    }
+
+    /// Use an existing Bedrock runtime client
+    pub fn from_client(client: Client, model_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            model_id: Arc::from(model_id.into()),
+        }
+    }
+
+    async fn embed_one(&self, text: &str) -> Result<Vec<f32>, LlmError> {
+        let body = serde_json::json!({ "inputText": text });
+        let output = self
+            .client
+            .invoke_model()
+            .model_id(self.model_id.as_ref())
+            .content_type("application/json")
+            .accept("application/json")
+            .body(Blob::new(body.to_string()))
+            .send()
+            .await
+            .map_err(|e| LlmError::ProviderError {
+                provider: ProviderType::Bedrock,
+                message: aws_sdk_bedrockruntime::error::DisplayErrorContext(e).to_string(),
+                source: None,
+            })?;
+        let response: serde_json::Value =
+            serde_json::from_slice(output.body().as_ref()).map_err(|e| {
+                LlmError::SerializationError {
+                    message: format!("Invalid embedding response: {}", e),
+                }
+            })?;
+        response["embedding"]
+            .as_array()
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_f64())
+                    .map(|v| v as f32)
+                    .collect()
+            })
+            .ok_or_else(|| LlmError::SerializationError {
+                message: "Embedding response has no embedding".to_string(),
+            })
+    }
+}
+
+#[async_trait]
+impl Embedder for BedrockEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        futures::future::try_join_all(texts.iter().map(|text| self.embed_one(text))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+}
@@ -0,0 +1,478 @@
+//! Request/response middleware for LLM providers.
+//!
+//! An [`LlmMiddleware`] sees every request a provider sends and every response
+//! it returns, which makes it the place for cross-cutting concerns such as
+//! request logging, prompt rewriting, extra request parameters or custom
+//! response caching, without changing the provider implementations.
+//!
+//! Middleware is installed per provider type on the [`ProviderRegistry`], and
+//! every agent using that provider goes through it:
+//!
+//! ```no_run
+//! use async_trait::async_trait;
+//! use stood::llm::middleware::{LlmMiddleware, LlmRequest};
+//! use stood::llm::traits::{ChatResponse, LlmError, ProviderType};
+//! use stood::llm::PROVIDER_REGISTRY;
+//! use std::sync::Arc;
+//!
+//! #[derive(Debug)]
+//! struct RequestLogger;
+//!
+//! #[async_trait]
+//! impl LlmMiddleware for RequestLogger {
+//!     async fn before_request(
+//!         &self,
+//!         request: &mut LlmRequest,
+//!     ) -> Result<Option<ChatResponse>, LlmError> {
+//!         println!("{} messages to {}", request.messages.len(), request.model_id);
+//!         Ok(None)
+//!     }
+//! }
+//!
+//! # async fn example() {
+//! PROVIDER_REGISTRY
+//!     .add_middleware(ProviderType::Bedrock, Arc::new(RequestLogger))
+//!     .await;
+//! # }
+//! ```
+//!
+//! [`ProviderRegistry`]: crate::llm::registry::ProviderRegistry
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::Stream;
+
+use crate::llm::traits::{
+    ChatConfig, ChatResponse, ContentBlockDelta, ContentBlockType, HealthStatus, LlmError,
+    LlmProvider, ProviderCapabilities, ProviderType, StreamEvent, Tool,
+    PROVIDER_LATENCY_METADATA_KEY,
+};
+use crate::types::{MessageRole, Messages};
+
+/// A request about to be sent to a provider
+///
+/// Middleware may rewrite any field; the provider receives the result.
+#[derive(Debug, Clone)]
+pub struct LlmRequest {
+    /// Provider the request is sent to
+    pub provider: ProviderType,
+    /// Model the request is for
+    pub model_id: String,
+    /// Conversation sent to the model
+    pub messages: Messages,
+    /// Tools offered to the model (empty for plain chat)
+    pub tools: Vec<Tool>,
+    /// Chat configuration, including provider-specific `additional_params`
+    pub config: ChatConfig,
+    /// Whether the response is streamed
+    pub streaming: bool,
+}
+
+/// Hooks around every request of a provider
+///
+/// Middleware runs in the order it was added for [`before_request`] and in
+/// reverse order for [`after_response`], so the first middleware added is the
+/// outermost one.
+///
+/// [`before_request`]: LlmMiddleware::before_request
+/// [`after_response`]: LlmMiddleware::after_response
+#[async_trait]
+pub trait LlmMiddleware: Send + Sync + std::fmt::Debug {
+    /// Inspect or rewrite a request before it is sent
+    ///
+    /// Returning a response answers the request without calling the provider
+    /// (e.g. from a cache); later middleware and the provider are skipped.
+    /// Returning an error fails the request.
+    async fn before_request(
+        &self,
+        _request: &mut LlmRequest,
+    ) -> Result<Option<ChatResponse>, LlmError> {
+        Ok(None)
+    }
+
+    /// Inspect or rewrite a response before it is returned
+    ///
+    /// Only called for non-streaming requests, since streamed responses are
+    /// consumed event by event.
+    async fn after_response(
+        &self,
+        _request: &LlmRequest,
+        _response: &mut ChatResponse,
+    ) -> Result<(), LlmError> {
+        Ok(())
+    }
+}
+
+/// A provider whose requests go through a middleware chain
+#[derive(Debug)]
+pub struct MiddlewareProvider {
+    inner: Arc<dyn LlmProvider>,
+    middleware: Vec<Arc<dyn LlmMiddleware>>,
+}
+
+impl MiddlewareProvider {
+    /// Wrap `inner` with the given middleware
+    pub fn new(inner: Arc<dyn LlmProvider>, middleware: Vec<Arc<dyn LlmMiddleware>>) -> Self {
+        Self { inner, middleware }
+    }
+
+    /// The wrapped provider
+    pub fn inner(&self) -> &Arc<dyn LlmProvider> {
+        &self.inner
+    }
+
+    fn request(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+        streaming: bool,
+    ) -> LlmRequest {
+        LlmRequest {
+            provider: self.inner.provider_type(),
+            model_id: model_id.to_string(),
+            messages: messages.clone(),
+            tools: tools.to_vec(),
+            config: config.clone(),
+            streaming,
+        }
+    }
+
+    /// Run `before_request` of each middleware until one answers the request
+    async fn before_request(
+        &self,
+        request: &mut LlmRequest,
+    ) -> Result<Option<ChatResponse>, LlmError> {
+        for middleware in &self.middleware {
+            if let Some(response) = middleware.before_request(request).await? {
+                return Ok(Some(response));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn call(&self, mut request: LlmRequest) -> Result<ChatResponse, LlmError> {
+        if let Some(response) = self.before_request(&mut request).await? {
+            return Ok(response);
+        }
+
+        let mut response = if request.tools.is_empty() {
+            self.inner
+                .chat(&request.model_id, &request.messages, &request.config)
+                .await?
+        } else {
+            self.inner
+                .chat_with_tools(
+                    &request.model_id,
+                    &request.messages,
+                    &request.tools,
+                    &request.config,
+                )
+                .await?
+        };
+
+        for middleware in self.middleware.iter().rev() {
+            middleware.after_response(&request, &mut response).await?;
+        }
+        Ok(response)
+    }
+
+    async fn call_streaming(
+        &self,
+        mut request: LlmRequest,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        if let Some(response) = self.before_request(&mut request).await? {
+            return Ok(Box::new(futures::stream::iter(response_events(response))));
+        }
+
+        if request.tools.is_empty() {
+            self.inner
+                .chat_streaming(&request.model_id, &request.messages, &request.config)
+                .await
+        } else {
+            self.inner
+                .chat_streaming_with_tools(
+                    &request.model_id,
+                    &request.messages,
+                    &request.tools,
+                    &request.config,
+                )
+                .await
+        }
+    }
+}
+
+/// Stream events that replay a complete response
+fn response_events(response: ChatResponse) -> Vec<StreamEvent> {
+    let mut events = vec![StreamEvent::MessageStart {
+        role: MessageRole::Assistant,
+    }];
+    if !response.content.is_empty() {
+        events.push(StreamEvent::ContentBlockStart {
+            block_type: ContentBlockType::Text,
+            block_index: 0,
+        });
+        events.push(StreamEvent::ContentBlockDelta {
+            delta: ContentBlockDelta::Text {
+                text: response.content,
+            },
+            block_index: 0,
+        });
+        events.push(StreamEvent::ContentBlockStop { block_index: 0 });
+    }
+    let stop_reason = if response.tool_calls.is_empty() {
+        "end_turn"
+    } else {
+        "tool_use"
+    };
+    for tool_call in response.tool_calls {
+        events.push(StreamEvent::ToolCallStart { tool_call });
+    }
+    events.push(StreamEvent::MessageStop {
+        stop_reason: Some(stop_reason.to_string()),
+    });
+    events.push(StreamEvent::Metadata {
+        usage: response.usage,
+        latency_ms: response
+            .metadata
+            .get(PROVIDER_LATENCY_METADATA_KEY)
+            .and_then(|latency| latency.as_u64()),
+    });
+    events
+}
+
+#[async_trait]
+impl LlmProvider for MiddlewareProvider {
+    async fn chat(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        config: &ChatConfig,
+    ) -> Result<ChatResponse, LlmError> {
+        self.call(self.request(model_id, messages, &[], config, false))
+            .await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+    ) -> Result<ChatResponse, LlmError> {
+        self.call(self.request(model_id, messages, tools, config, false))
+            .await
+    }
+
+    async fn chat_streaming(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        config: &ChatConfig,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        self.call_streaming(self.request(model_id, messages, &[], config, true))
+            .await
+    }
+
+    async fn chat_streaming_with_tools(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        self.call_streaming(self.request(model_id, messages, tools, config, true))
+            .await
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, LlmError> {
+        self.inner.health_check().await
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        self.inner.provider_type()
+    }
+
+    fn supported_models(&self) -> Vec<&'static str> {
+        self.inner.supported_models()
+    }
+
+    /// Downcasts reach the wrapped provider, so provider-specific methods
+    /// remain available behind middleware
+    fn as_any(&self) -> &dyn std::any::Any {
+        self.inner.as_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Provider that echoes the last message back
+    #[derive(Debug, Default)]
+    struct EchoProvider {
+        calls: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for EchoProvider {
+        async fn chat(
+            &self,
+            _model_id: &str,
+            messages: &Messages,
+            _config: &ChatConfig,
+        ) -> Result<ChatResponse, LlmError> {
+            *self.calls.lock().unwrap() += 1;
+            let last = messages.messages.last().and_then(|m| m.text());
+            Ok(ChatResponse {
+                content: last.unwrap_or_default(),
+                tool_calls: Vec::new(),
+                thinking: None,
+                usage: None,
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn chat_with_tools(
+            &self,
+            model_id: &str,
+            messages: &Messages,
+            _tools: &[Tool],
+            config: &ChatConfig,
+        ) -> Result<ChatResponse, LlmError> {
+            self.chat(model_id, messages, config).await
+        }
+
+        async fn chat_streaming(
+            &self,
+            _model_id: &str,
+            _messages: &Messages,
+            _config: &ChatConfig,
+        ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+            Err(LlmError::UnsupportedFeature {
+                feature: "streaming".to_string(),
+                provider: ProviderType::LmStudio,
+            })
+        }
+
+        async fn chat_streaming_with_tools(
+            &self,
+            model_id: &str,
+            messages: &Messages,
+            _tools: &[Tool],
+            config: &ChatConfig,
+        ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+            self.chat_streaming(model_id, messages, config).await
+        }
+
+        async fn health_check(&self) -> Result<HealthStatus, LlmError> {
+            unimplemented!()
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            unimplemented!()
+        }
+
+        fn provider_type(&self) -> ProviderType {
+            ProviderType::LmStudio
+        }
+
+        fn supported_models(&self) -> Vec<&'static str> {
+            vec!["echo"]
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    /// Rewrites prompts and tags responses
+    #[derive(Debug)]
+    struct Shout;
+
+    #[async_trait]
+    impl LlmMiddleware for Shout {
+        async fn before_request(
+            &self,
+            request: &mut LlmRequest,
+        ) -> Result<Option<ChatResponse>, LlmError> {
+            let text = request.messages.messages[0].text().unwrap_or_default();
+            request.messages =
+                Messages::from(vec![crate::types::Message::user(text.to_uppercase())]);
+            Ok(None)
+        }
+
+        async fn after_response(
+            &self,
+            _request: &LlmRequest,
+            response: &mut ChatResponse,
+        ) -> Result<(), LlmError> {
+            response.content.push('!');
+            Ok(())
+        }
+    }
+
+    /// Answers every request without calling the provider
+    #[derive(Debug)]
+    struct Cached;
+
+    #[async_trait]
+    impl LlmMiddleware for Cached {
+        async fn before_request(
+            &self,
+            _request: &mut LlmRequest,
+        ) -> Result<Option<ChatResponse>, LlmError> {
+            Ok(Some(ChatResponse {
+                content: "cached".to_string(),
+                tool_calls: Vec::new(),
+                thinking: None,
+                usage: None,
+                metadata: HashMap::new(),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_rewrites_and_short_circuits() {
+        let echo = Arc::new(EchoProvider::default());
+        let messages = Messages::from(vec![crate::types::Message::user("hello")]);
+
+        let provider = MiddlewareProvider::new(echo.clone(), vec![Arc::new(Shout)]);
+        let response = provider
+            .chat("echo", &messages, &ChatConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(response.content, "HELLO!");
+        assert!(provider.as_any().downcast_ref::<EchoProvider>().is_some());
+
+        let cached = MiddlewareProvider::new(echo.clone(), vec![Arc::new(Cached)]);
+        let response = cached
+            .chat("echo", &messages, &ChatConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(response.content, "cached");
+        assert_eq!(*echo.calls.lock().unwrap(), 1);
+
+        // A cached answer is replayed as a stream, even though the provider cannot stream
+        let events: Vec<_> = cached
+            .chat_streaming("echo", &messages, &ChatConfig::default())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(events.iter().any(|e| matches!(
+            e,
+            StreamEvent::ContentBlockDelta { delta: ContentBlockDelta::Text { text }, .. }
+                if text == "cached"
+        )));
+    }
+}
@@ -80,13 +80,20 @@
 //! - [`ChatResponse`] - Unified response format across providers
 
 pub mod client;
+pub mod concurrency;
 pub mod config;
+pub mod embeddings;
 pub mod error;
+pub mod http;
+pub mod middleware;
 pub mod models;
 pub mod providers;
 pub mod registry;
+pub mod response_cache;
 pub mod streaming;
+pub mod tool_schema;
 pub mod traits;
+pub mod translation;
 
 #[cfg(test)]
 pub mod integration_test;
@@ -103,5 +110,16 @@ pub use traits::{
 // Re-export model provider modules for the single API pattern
 pub use models::{Bedrock, LMStudio};
 
+// Re-export adaptive concurrency control for provider requests
+pub use concurrency::{AdaptiveConcurrency, AimdConfig, ConcurrencyLimitedProvider};
+
+// Re-export HTTP client settings for provider connections
+pub use http::HttpClientConfig;
+
+// Re-export middleware for provider request/response hooks
+pub use middleware::{LlmMiddleware, LlmRequest, MiddlewareProvider};
+
 // Re-export registry for configuration
 pub use registry::{ProviderConfig, ProviderRegistry, PROVIDER_REGISTRY};
+
+pub use response_cache::{CachingProvider, ResponseCache, ResponseCacheStats};
@@ -11,6 +11,7 @@ use crate::llm::traits::{
     ChatConfig, ChatResponse, HealthStatus, LlmError, LlmProvider, ProviderCapabilities,
     ProviderType, StreamEvent, Tool,
 };
+use crate::llm::translation::{AnthropicFormat, MessageFormat};
 use crate::types::Messages;
 use async_trait::async_trait;
 use futures::Stream;
@@ -42,6 +43,14 @@ impl AnthropicProvider {
             client,
         })
     }
+
+    /// Send requests through `client` instead of a default one
+    ///
+    /// Used to apply an [`HttpClientConfig`](crate::llm::http::HttpClientConfig).
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[async_trait]
@@ -267,6 +276,72 @@ impl LlmProvider for AnthropicProvider {
         })
     }
 
+    async fn count_tokens(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+    ) -> Result<Option<u32>, LlmError> {
+        let (anthropic_messages, system_message) = self.convert_messages_to_anthropic(messages)?;
+
+        let mut request_body = serde_json::json!({
+            "model": model_id,
+            "messages": anthropic_messages
+        });
+        if let Some(system) = system_message {
+            request_body["system"] = serde_json::json!(system);
+        }
+        if !tools.is_empty() {
+            request_body["tools"] = tools
+                .iter()
+                .map(|tool| {
+                    serde_json::json!({
+                        "name": tool.name,
+                        "description": tool.description,
+                        "input_schema": tool.input_schema
+                    })
+                })
+                .collect();
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/v1/messages/count_tokens", self.base_url))
+            .header("Content-Type", "application/json")
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| LlmError::NetworkError {
+                message: format!("Anthropic count_tokens request failed: {}", e),
+                source: Some(Box::new(e)),
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(LlmError::ProviderError {
+                provider: ProviderType::Anthropic,
+                message: format!("Anthropic count_tokens error {}: {}", status, error_text),
+                source: None,
+            });
+        }
+
+        let response_json: serde_json::Value =
+            response.json().await.map_err(|e| LlmError::ProviderError {
+                provider: ProviderType::Anthropic,
+                message: format!("Failed to parse Anthropic count_tokens response: {}", e),
+                source: Some(Box::new(e)),
+            })?;
+        Ok(response_json["input_tokens"]
+            .as_u64()
+            .map(|tokens| tokens as u32))
+    }
+
     async fn health_check(&self) -> Result<HealthStatus, LlmError> {
         // Test basic connectivity with a minimal request
         let start = std::time::Instant::now();
@@ -363,78 +438,8 @@ impl AnthropicProvider {
         &self,
         messages: &Messages,
     ) -> Result<(Vec<serde_json::Value>, Option<String>), LlmError> {
-        use crate::types::{ContentBlock, MessageRole};
-
-        let mut anthropic_messages = Vec::new();
-        let mut system_message = None;
-
-        for message in &messages.messages {
-            match message.role {
-                MessageRole::System => {
-                    // Extract system message text
-                    let text = message
-                        .content
-                        .iter()
-                        .filter_map(|block| match block {
-                            ContentBlock::Text { text } => Some(text.as_str()),
-                            _ => None,
-                        })
-                        .collect::<Vec<_>>()
-                        .join(" ");
-                    if !text.is_empty() {
-                        system_message = Some(text);
-                    }
-                }
-                MessageRole::User | MessageRole::Assistant => {
-                    let mut content = Vec::new();
-
-                    for block in &message.content {
-                        match block {
-                            ContentBlock::Text { text } => {
-                                content.push(serde_json::json!({
-                                    "type": "text",
-                                    "text": text
-                                }));
-                            }
-                            ContentBlock::ToolUse { id, name, input } => {
-                                content.push(serde_json::json!({
-                                    "type": "tool_use",
-                                    "id": id,
-                                    "name": name,
-                                    "input": input
-                                }));
-                            }
-                            ContentBlock::ToolResult {
-                                tool_use_id,
-                                content: tool_content,
-                                is_error,
-                            } => {
-                                content.push(serde_json::json!({
-                                    "type": "tool_result",
-                                    "tool_use_id": tool_use_id,
-                                    "content": tool_content.to_display_string(),
-                                    "is_error": is_error
-                                }));
-                            }
-                            _ => {} // Skip other content types for now
-                        }
-                    }
-
-                    if !content.is_empty() {
-                        anthropic_messages.push(serde_json::json!({
-                            "role": match message.role {
-                                MessageRole::User => "user",
-                                MessageRole::Assistant => "assistant",
-                                _ => unreachable!()
-                            },
-                            "content": content
-                        }));
-                    }
-                }
-            }
-        }
-
-        Ok((anthropic_messages, system_message))
+        let wire = AnthropicFormat.serialize(messages)?;
+        Ok((wire.messages, wire.system))
     }
 
     /// Convert Anthropic API response to ChatResponse
@@ -3,15 +3,20 @@
 //! This provider owns ALL Bedrock-specific logic including request formatting,
 //! response parsing, streaming, and error handling for Claude, Nova, and Llama models.
 
+use crate::llm::http::HttpClientConfig;
+use crate::llm::tool_schema::{translate_tool_schema, SchemaDialect};
 use crate::llm::traits::{
     CacheStrategy, ChatConfig, ChatResponse, HealthStatus, LlmError, LlmProvider,
     ProviderCapabilities, ProviderType, StreamEvent, Tool,
 };
+use crate::llm::translation::{AnthropicFormat, MessageFormat};
+use crate::types::tools::ToolChoice;
 use crate::types::{ContentBlock, MessageRole, Messages};
 use async_trait::async_trait;
 use aws_sdk_bedrockruntime::Client as BedrockRuntimeClient;
 #[allow(unused_imports)] // Used for future vision/image features
 use base64;
+use chrono::{DateTime, Utc};
 use futures::Stream;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -49,6 +54,9 @@ impl ToolState {
     }
 }
 
+/// Raw chunks of a streaming response, with the time each was received
+pub type RawStreamChunks = Vec<(DateTime<Utc>, String)>;
+
 /// AWS Bedrock provider
 ///
 /// This provider handles all AWS Bedrock models (Claude, Nova, Llama) and owns
@@ -57,11 +65,14 @@ impl ToolState {
 pub struct BedrockProvider {
     /// AWS Bedrock Runtime client
     client: BedrockRuntimeClient,
-    /// AWS config for the client
-    #[allow(dead_code)] // Stored for potential future features like region switching
+    /// AWS config for the client (credentials and region)
     aws_config: aws_config::SdkConfig,
     /// Last request JSON for raw capture (if enabled)
     last_request_json: std::sync::Arc<std::sync::Mutex<Option<String>>>,
+    /// Last non-streaming response body for raw capture
+    last_response_json: std::sync::Arc<std::sync::Mutex<Option<String>>>,
+    /// Chunks of the last streaming response, with the time each was received
+    last_stream_chunks: std::sync::Arc<std::sync::Mutex<RawStreamChunks>>,
 }
 
 impl BedrockProvider {
@@ -92,6 +103,8 @@ impl BedrockProvider {
             client,
             aws_config,
             last_request_json: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            last_response_json: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            last_stream_chunks: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
         })
     }
 
@@ -162,14 +175,39 @@ impl BedrockProvider {
             client,
             aws_config,
             last_request_json: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            last_response_json: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            last_stream_chunks: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
         })
     }
 
+    /// Rebuild the client with the pool and timeout settings of `http`
+    ///
+    /// Region and credentials are kept. Fails if `http` sets a proxy, which
+    /// the AWS SDK client cannot use.
+    pub fn with_http_config(mut self, http: &HttpClientConfig) -> Result<Self, LlmError> {
+        let timeouts = http.aws_timeout_config(self.aws_config.timeout_config());
+        let config = aws_sdk_bedrockruntime::config::Builder::from(&self.aws_config)
+            .http_client(http.build_aws_client()?)
+            .timeout_config(timeouts)
+            .build();
+        self.client = BedrockRuntimeClient::from_conf(config);
+        Ok(self)
+    }
+
     /// Store the last request JSON for raw capture
+    ///
+    /// Also forgets the previous response, so the raw response getters only
+    /// ever describe the response to this request.
     fn store_request_json(&self, request_json: &str) {
         if let Ok(mut last_request) = self.last_request_json.lock() {
             *last_request = Some(request_json.to_string());
         }
+        if let Ok(mut last_response) = self.last_response_json.lock() {
+            *last_response = None;
+        }
+        if let Ok(mut chunks) = self.last_stream_chunks.lock() {
+            chunks.clear();
+        }
     }
 
     /// Get the last request JSON for raw capture (returns None if capture disabled or no request)
@@ -177,6 +215,55 @@ impl BedrockProvider {
         self.last_request_json.lock().ok()?.clone()
     }
 
+    /// Store the last non-streaming response body for raw capture
+    fn store_response_json(&self, response_json: &str) {
+        if let Ok(mut last_response) = self.last_response_json.lock() {
+            *last_response = Some(response_json.to_string());
+        }
+    }
+
+    /// Get the body of the last non-streaming response, as returned by Bedrock
+    ///
+    /// Returns None if the last request was streamed or failed.
+    pub fn get_last_response_json(&self) -> Option<String> {
+        self.last_response_json.lock().ok()?.clone()
+    }
+
+    /// Get the raw chunks of the last streaming response received so far,
+    /// with the time each chunk was received
+    pub fn get_last_stream_chunks(&self) -> RawStreamChunks {
+        self.last_stream_chunks
+            .lock()
+            .map(|chunks| chunks.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record a raw stream chunk for raw capture
+    fn store_stream_chunk(chunks: &std::sync::Mutex<RawStreamChunks>, chunk_bytes: &[u8]) {
+        let chunk = String::from_utf8_lossy(chunk_bytes).into_owned();
+        if let Ok(mut chunks) = chunks.lock() {
+            chunks.push((Utc::now(), chunk));
+        }
+    }
+
+    /// Token counts and latency from the `amazon-bedrock-invocationMetrics`
+    /// Bedrock appends to the last chunk of a stream
+    fn invocation_metrics(chunk_bytes: &[u8]) -> Option<(crate::llm::traits::Usage, u64)> {
+        let chunk: serde_json::Value = serde_json::from_slice(chunk_bytes).ok()?;
+        let metrics = chunk.get("amazon-bedrock-invocationMetrics")?;
+        let count = |key: &str| metrics.get(key).and_then(|v| v.as_u64());
+        let input_tokens = count("inputTokenCount").unwrap_or(0) as u32;
+        let output_tokens = count("outputTokenCount").unwrap_or(0) as u32;
+        let usage = crate::llm::traits::Usage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+            cache_read_tokens: count("cacheReadInputTokenCount").map(|n| n as u32),
+            cache_write_tokens: count("cacheWriteInputTokenCount").map(|n| n as u32),
+        };
+        Some((usage, count("invocationLatency")?))
+    }
+
     /// Check if a model supports prompt caching
     ///
     /// Returns `true` if the model supports prompt caching on AWS Bedrock.
@@ -257,86 +344,10 @@ impl BedrockProvider {
         config: &ChatConfig,
         operation_id: &str,
     ) -> Result<String, LlmError> {
-        let mut request_messages = Vec::new();
-
-        // Use system_prompt from Messages struct if available, otherwise extract from System role messages
-        let mut system_prompt = messages.system_prompt.clone();
-
-        // Process messages
-        for message in &messages.messages {
-            match message.role {
-                MessageRole::System => {
-                    // Extract system prompt from messages (fallback if not in Messages struct)
-                    if system_prompt.is_none() {
-                        let text = message
-                            .content
-                            .iter()
-                            .filter_map(|block| match block {
-                                ContentBlock::Text { text } => Some(text.as_str()),
-                                _ => None,
-                            })
-                            .collect::<Vec<_>>()
-                            .join(" ");
-                        if !text.is_empty() {
-                            system_prompt = Some(text);
-                        }
-                    }
-                }
-                MessageRole::User | MessageRole::Assistant => {
-                    let mut content = Vec::new();
-
-                    for block in &message.content {
-                        match block {
-                            ContentBlock::Text { text } => {
-                                content.push(json!({
-                                    "type": "text",
-                                    "text": text
-                                }));
-                            }
-                            ContentBlock::ToolUse { id, name, input } => {
-                                // Claude tool use format - ensure input is never null or invalid
-                                let safe_input = if input.is_null() || !input.is_object() {
-                                    // If input is null or not an object, use empty object
-                                    serde_json::Value::Object(serde_json::Map::new())
-                                } else {
-                                    input.clone()
-                                };
-                                content.push(json!({
-                                    "type": "tool_use",
-                                    "id": id,
-                                    "name": name,
-                                    "input": safe_input
-                                }));
-                            }
-                            ContentBlock::ToolResult {
-                                tool_use_id,
-                                content: tool_content,
-                                is_error,
-                            } => {
-                                content.push(json!({
-                                    "type": "tool_result",
-                                    "tool_use_id": tool_use_id,
-                                    "content": [{"type": "text", "text": tool_content.to_display_string()}],
-                                    "is_error": is_error
-                                }));
-                            }
-                            _ => {} // Skip other content types for now
-                        }
-                    }
-
-                    if !content.is_empty() {
-                        request_messages.push(json!({
-                            "role": match message.role {
-                                MessageRole::User => "user",
-                                MessageRole::Assistant => "assistant",
-                                _ => unreachable!()
-                            },
-                            "content": content
-                        }));
-                    }
-                }
-            }
-        }
+        // Messages and system prompt in the Claude wire format
+        let wire = AnthropicFormat.serialize(messages)?;
+        let request_messages = wire.messages;
+        let system_prompt = wire.system;
 
         // Build request
         let mut request = json!({
@@ -414,7 +425,7 @@ impl BedrockProvider {
                 .collect();
 
             request["tools"] = json!(claude_tools);
-            request["tool_choice"] = json!({"type": "auto"});
+            request["tool_choice"] = claude_tool_choice(&config.tool_choice);
         }
 
         debug!(
@@ -582,12 +593,30 @@ impl BedrockProvider {
             let nova_tools: Vec<Value> = tools
                 .iter()
                 .map(|tool| {
+                    // Nova rejects anyOf, $ref and other constructs common in
+                    // generated schemas, so rewrite them into supported ones
+                    let translation =
+                        translate_tool_schema(SchemaDialect::Nova, &tool.input_schema);
+                    for adaptation in &translation.adaptations {
+                        debug!(
+                            "[{}] 🔧 Nova schema of '{}': {}",
+                            operation_id, tool.name, adaptation
+                        );
+                    }
+                    for issue in &translation.issues {
+                        tracing::warn!(
+                            "[{}] Tool '{}' schema is not supported by Nova: {}",
+                            operation_id,
+                            tool.name,
+                            issue
+                        );
+                    }
                     json!({
                         "toolSpec": {
                             "name": tool.name,
                             "description": tool.description,
                             "inputSchema": {
-                                "json": tool.input_schema
+                                "json": translation.schema
                             }
                         }
                     })
@@ -596,7 +625,7 @@ impl BedrockProvider {
 
             request["toolConfig"] = json!({
                 "tools": nova_tools,
-                "toolChoice": {"auto": {}}
+                "toolChoice": nova_tool_choice(&config.tool_choice)
             });
         }
 
@@ -761,7 +790,11 @@ impl BedrockProvider {
         // Add tools if provided
         if !mistral_tools.is_empty() {
             request["tools"] = json!(mistral_tools);
-            request["tool_choice"] = json!("auto");
+            // Mistral cannot force a specific tool, so any tool call is required
+            request["tool_choice"] = match config.tool_choice {
+                ToolChoice::Auto => json!("auto"),
+                ToolChoice::Any | ToolChoice::Tool { .. } => json!("any"),
+            };
         }
 
         debug!(
@@ -1097,10 +1130,12 @@ impl BedrockProvider {
         let event_stream = response.body;
 
         let is_nova = model_id.contains("amazon.nova");
+        let raw_chunks = self.last_stream_chunks.clone();
         let converted_stream = async_stream::stream! {
             tracing::debug!("🌊 Starting Bedrock stream processing for {} model...", if is_nova { "Nova" } else { "Claude" });
             let mut chunk_count = 0;
             let mut total_content = String::new();
+            let mut invocation_metrics = None;
 
             // AWS Bedrock streaming works with EventReceiver
             let mut stream = event_stream;
@@ -1115,6 +1150,10 @@ impl BedrockProvider {
                             aws_sdk_bedrockruntime::types::ResponseStream::Chunk(chunk) => {
                                 // Parse the chunk bytes as JSON
                                 let chunk_bytes = chunk.bytes().map(|b| b.as_ref()).unwrap_or(&[]);
+                                Self::store_stream_chunk(&raw_chunks, chunk_bytes);
+                                if let Some(metrics) = Self::invocation_metrics(chunk_bytes) {
+                                    invocation_metrics = Some(metrics);
+                                }
 
                                 if is_nova {
                                     // Nova streaming: decode base64 content from body.chunk.bytes
@@ -1169,6 +1208,15 @@ impl BedrockProvider {
                     Ok(None) => {
                         tracing::debug!("🌊 Bedrock stream ended");
 
+                        if let Some((usage, latency_ms)) = invocation_metrics.take() {
+                            yield StreamEvent::Metadata {
+                                usage: Some(usage.clone()),
+                                latency_ms: Some(latency_ms),
+                            };
+                            yield StreamEvent::Done { usage: Some(usage) };
+                            break;
+                        }
+
                         // Estimate token usage based on content length (approximation)
                         // Typical ratio is ~4 characters per token for English text
                         let output_tokens = (total_content.len() / 4).max(1) as u32;
@@ -1238,12 +1286,14 @@ impl BedrockProvider {
         };
 
         let event_stream = response.body;
+        let raw_chunks = self.last_stream_chunks.clone();
 
         let converted_stream = async_stream::stream! {
             tracing::debug!("🔧🌊 Starting Bedrock stream processing with tools for model type: {:?}", model_type);
             let mut chunk_count = 0;
             let mut total_content = String::new();
             let mut tool_state = ToolState::new(model_type.clone());
+            let mut invocation_metrics = None;
 
             // AWS Bedrock streaming works with EventReceiver
             let mut stream = event_stream;
@@ -1258,6 +1308,10 @@ impl BedrockProvider {
                             aws_sdk_bedrockruntime::types::ResponseStream::Chunk(chunk) => {
                                 // Parse the chunk bytes - model-aware processing
                                 let chunk_bytes = chunk.bytes().map(|b| b.as_ref()).unwrap_or(&[]);
+                                Self::store_stream_chunk(&raw_chunks, chunk_bytes);
+                                if let Some(metrics) = Self::invocation_metrics(chunk_bytes) {
+                                    invocation_metrics = Some(metrics);
+                                }
 
                                 match tool_state.model_type {
                                     ModelType::Claude => {
@@ -1298,6 +1352,15 @@ impl BedrockProvider {
                     Ok(None) => {
                         tracing::info!("🔧🌊 Bedrock stream with tools ended after {} chunks", chunk_count);
 
+                        if let Some((usage, latency_ms)) = invocation_metrics.take() {
+                            yield StreamEvent::Metadata {
+                                usage: Some(usage.clone()),
+                                latency_ms: Some(latency_ms),
+                            };
+                            yield StreamEvent::Done { usage: Some(usage) };
+                            break;
+                        }
+
                         // Estimate token usage based on content length (approximation)
                         // Typical ratio is ~4 characters per token for English text
                         let output_tokens = (total_content.len() / 4).max(1) as u32;
@@ -1590,6 +1653,9 @@ impl LlmProvider for BedrockProvider {
             }
         })?;
 
+        // Store response JSON for raw capture
+        self.store_response_json(&response_body);
+
         // Route to appropriate response parser based on model family
         crate::perf_timed!("stood.bedrock.parse_response", {
             if model_id.contains("amazon.nova") {
@@ -1697,14 +1763,70 @@ impl LlmProvider for BedrockProvider {
         Ok(stream)
     }
 
+    async fn count_tokens(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+    ) -> Result<Option<u32>, LlmError> {
+        // CountTokens only supports Claude, and takes the foundation model ID
+        // rather than a cross-region inference profile
+        let Some(start) = model_id.find("anthropic.claude") else {
+            return Ok(None);
+        };
+        let request_body =
+            self.build_request_body(messages, model_id, tools, &ChatConfig::default())?;
+        let request = aws_sdk_bedrockruntime::types::InvokeModelTokensRequest::builder()
+            .body(aws_sdk_bedrockruntime::primitives::Blob::new(
+                request_body.as_bytes(),
+            ))
+            .build()
+            .map_err(|e| LlmError::SerializationError {
+                message: format!("Invalid CountTokens request: {}", e),
+            })?;
+
+        let response = self
+            .client
+            .count_tokens()
+            .model_id(&model_id[start..])
+            .input(aws_sdk_bedrockruntime::types::CountTokensInput::InvokeModel(request))
+            .send()
+            .await
+            .map_err(|e| LlmError::ProviderError {
+                provider: ProviderType::Bedrock,
+                message: format!(
+                    "CountTokens failed: {}",
+                    aws_sdk_bedrockruntime::error::DisplayErrorContext(&e)
+                ),
+                source: Some(Box::new(e)),
+            })?;
+        Ok(Some(response.input_tokens().max(0) as u32))
+    }
+
     async fn health_check(&self) -> Result<HealthStatus, LlmError> {
-        // Use the existing BedrockClient health check
-        // TODO: Implement direct health check
+        use aws_credential_types::provider::ProvideCredentials;
+
+        // Resolve credentials through the same provider chain the client uses, so
+        // missing or expired credentials are reported before the first request
+        let start = Instant::now();
+        let error = if self.aws_config.region().is_none() {
+            Some("No AWS region configured".to_string())
+        } else {
+            match self.aws_config.credentials_provider() {
+                None => Some("No AWS credentials provider configured".to_string()),
+                Some(provider) => provider
+                    .provide_credentials()
+                    .await
+                    .err()
+                    .map(|e| format!("Failed to resolve AWS credentials: {}", e)),
+            }
+        };
+
         Ok(HealthStatus {
-            healthy: true,
+            healthy: error.is_none(),
             provider: ProviderType::Bedrock,
-            latency_ms: Some(0),
-            error: None,
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            error,
         })
     }
 
@@ -1972,3 +2094,21 @@ impl BedrockProvider {
         None
     }
 }
+
+/// Anthropic Messages API `tool_choice`
+fn claude_tool_choice(tool_choice: &ToolChoice) -> Value {
+    match tool_choice {
+        ToolChoice::Auto => json!({"type": "auto"}),
+        ToolChoice::Any => json!({"type": "any"}),
+        ToolChoice::Tool { name } => json!({"type": "tool", "name": name}),
+    }
+}
+
+/// Nova `toolConfig.toolChoice`
+fn nova_tool_choice(tool_choice: &ToolChoice) -> Value {
+    match tool_choice {
+        ToolChoice::Auto => json!({"auto": {}}),
+        ToolChoice::Any => json!({"any": {}}),
+        ToolChoice::Tool { name } => json!({"tool": {"name": name}}),
+    }
+}
@@ -8,7 +8,9 @@ use crate::llm::traits::{
     ChatConfig, ChatResponse, HealthStatus, LlmError, LlmProvider, ProviderCapabilities,
     ProviderType, StreamEvent, Tool,
 };
-use crate::types::{ContentBlock, MessageRole, Messages};
+use crate::llm::translation::{MessageFormat, OpenAiFormat};
+use crate::types::tools::ToolChoice;
+use crate::types::Messages;
 use async_trait::async_trait;
 use futures::Stream;
 use serde_json::Value;
@@ -111,6 +113,14 @@ impl LMStudioProvider {
         })
     }
 
+    /// Send requests through `client` instead of a default one
+    ///
+    /// Used to apply an [`HttpClientConfig`](crate::llm::http::HttpClientConfig).
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Get the current retry configuration
     pub fn retry_config(&self) -> &RetryConfig {
         &self.retry_config
@@ -339,7 +349,14 @@ impl LlmProvider for LMStudioProvider {
             "model": model_id,
             "messages": openai_messages,
             "tools": openai_tools,
-            "tool_choice": "auto", // Let the model decide when to use tools
+            "tool_choice": match &config.tool_choice {
+                ToolChoice::Auto => serde_json::json!("auto"),
+                ToolChoice::Any => serde_json::json!("required"),
+                ToolChoice::Tool { name } => serde_json::json!({
+                    "type": "function",
+                    "function": {"name": name}
+                }),
+            },
             "temperature": config.temperature.unwrap_or(0.7),
             "max_tokens": config.max_tokens,
         });
@@ -708,84 +725,7 @@ impl LlmProvider for LMStudioProvider {
 impl LMStudioProvider {
     /// Convert Stood Messages format to OpenAI chat completion format
     fn convert_messages_to_openai(&self, messages: &Messages) -> Result<Vec<Value>, LlmError> {
-        let mut openai_messages = Vec::new();
-
-        for message in &messages.messages {
-            let role = match message.role {
-                MessageRole::User => "user",
-                MessageRole::Assistant => "assistant",
-                MessageRole::System => "system",
-            };
-
-            // Convert content blocks to OpenAI format (handle tool use and results)
-            let mut content_parts = Vec::new();
-            let mut tool_calls = Vec::new();
-            let mut tool_results = Vec::new();
-
-            for block in &message.content {
-                match block {
-                    ContentBlock::Text { text } => {
-                        content_parts.push(text.clone());
-                    }
-                    ContentBlock::ToolUse { id, name, input } => {
-                        // Convert tool use to OpenAI tool call format
-                        // Ensure arguments is always a valid JSON object string
-                        let arguments_str = if input.is_null() || !input.is_object() {
-                            "{}".to_string()
-                        } else {
-                            serde_json::to_string(input).unwrap_or_else(|_| "{}".to_string())
-                        };
-                        tool_calls.push(serde_json::json!({
-                            "id": id,
-                            "type": "function",
-                            "function": {
-                                "name": name,
-                                "arguments": arguments_str
-                            }
-                        }));
-                    }
-                    ContentBlock::ToolResult {
-                        tool_use_id,
-                        content: tool_content,
-                        ..
-                    } => {
-                        // Convert tool result to OpenAI tool message format
-                        // Tool results should always be converted to tool messages regardless of the containing message role
-                        tool_results.push(serde_json::json!({
-                            "role": "tool",
-                            "tool_call_id": tool_use_id,
-                            "content": tool_content.to_display_string()
-                        }));
-                    }
-                    _ => {
-                        // Skip other content types for now
-                    }
-                }
-            }
-
-            let content = content_parts.join(" ");
-
-            if !content.is_empty() || !tool_calls.is_empty() {
-                let mut message_json = serde_json::json!({
-                    "role": role,
-                    "content": if content.is_empty() { None } else { Some(content) }
-                });
-
-                // Add tool calls if present
-                if !tool_calls.is_empty() {
-                    message_json["tool_calls"] = serde_json::Value::Array(tool_calls);
-                }
-
-                openai_messages.push(message_json);
-            }
-
-            // Add tool results after the current message
-            for tool_result in tool_results {
-                openai_messages.push(tool_result);
-            }
-        }
-
-        Ok(openai_messages)
+        Ok(OpenAiFormat::new().serialize(messages)?.messages)
     }
 
     /// Convert Stood Tool format to OpenAI tools format
@@ -1338,7 +1278,10 @@ impl LMStudioProvider {
                             };
 
                             tracing::debug!("🌊 Usage metadata: {:?}", usage);
-                            events.push(StreamEvent::Metadata { usage: Some(usage) });
+                            events.push(StreamEvent::Metadata {
+                                usage: Some(usage),
+                                latency_ms: None,
+                            });
                         } else {
                             tracing::debug!("🌊 Skipping zero-token usage metadata, allowing fallback estimation");
                         }
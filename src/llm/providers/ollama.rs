@@ -35,6 +35,14 @@ impl OllamaProvider {
 
         Ok(Self { base_url, client })
     }
+
+    /// Send requests through `client` instead of a default one
+    ///
+    /// Used to apply an [`HttpClientConfig`](crate::llm::http::HttpClientConfig).
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[async_trait]
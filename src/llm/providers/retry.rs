@@ -2,12 +2,17 @@
 //!
 //! This module provides configurable retry logic with exponential backoff
 //! to handle temporary failures like model loading delays in LM Studio.
+//!
+//! Retries also draw from the [`RetryBudget`] of the enclosing scope, if any,
+//! so provider retries and outer retry layers share one limit, and failed
+//! attempts are recorded in the current [`RetryHistory`].
 
+use crate::error_recovery::{RetryBudget, RetryHistory, RetryLayer};
 use crate::llm::traits::LlmError;
+use crate::runtime::sleep;
 use std::future::Future;
 use std::pin::Pin;
 use std::time::Duration;
-use tokio::time::sleep;
 
 /// Configuration for retry behavior with exponential backoff
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -169,6 +174,7 @@ pub async fn retry_with_backoff<F, T, E>(
 ) -> Result<T, E>
 where
     F: FnMut() -> BoxFuture<'static, Result<T, E>>,
+    E: std::fmt::Debug + std::fmt::Display,
 {
     let mut _last_error: Option<E> = None;
 
@@ -177,6 +183,7 @@ where
         Ok(result) => return Ok(result),
         Err(error) => {
             if config.max_attempts == 0 || should_retry(&error) == RetryDecision::FailImmediately {
+                RetryHistory::record_current(RetryLayer::Provider, 1, &error, None);
                 return Err(error);
             }
             _last_error = Some(error);
@@ -186,6 +193,22 @@ where
     // Retry attempts (attempts 1 through max_attempts)
     for attempt in 1..=config.max_attempts {
         let delay = calculate_backoff_delay(attempt - 1, config);
+        let granted = RetryBudget::acquire_current(delay);
+        if let Some(error) = &_last_error {
+            RetryHistory::record_current(
+                RetryLayer::Provider,
+                attempt,
+                error,
+                granted.then_some(delay),
+            );
+        }
+        if !granted {
+            tracing::warn!(
+                "❌ Retry refused by the retry budget or deadline (attempt {})",
+                attempt
+            );
+            break;
+        }
 
         tracing::debug!(
             "🔄 Retrying operation after {} ms (attempt {}/{})",
@@ -203,6 +226,7 @@ where
             }
             Err(error) => {
                 if should_retry(&error) == RetryDecision::FailImmediately {
+                    RetryHistory::record_current(RetryLayer::Provider, attempt + 1, &error, None);
                     tracing::warn!(
                         "❌ Operation failed with non-retryable error on attempt {}",
                         attempt
@@ -211,6 +235,7 @@ where
                 }
 
                 if attempt == config.max_attempts {
+                    RetryHistory::record_current(RetryLayer::Provider, attempt + 1, &error, None);
                     tracing::error!(
                         "❌ Operation failed after {} retry attempts",
                         config.max_attempts
@@ -223,7 +248,7 @@ where
         }
     }
 
-    // This should never be reached, but handle it gracefully
+    // Reached when the retry budget refuses a retry
     Err(_last_error.unwrap())
 }
 
@@ -3,11 +3,15 @@
 //! The registry handles configuration discovery, provider instantiation, and sharing
 //! across multiple agent instances to optimize resource usage.
 
+use crate::llm::concurrency::{AdaptiveConcurrency, ConcurrencyLimitedProvider};
+use crate::llm::http::HttpClientConfig;
+use crate::llm::middleware::{LlmMiddleware, MiddlewareProvider};
 use crate::llm::providers::retry::RetryConfig;
 use crate::llm::providers::{
     AnthropicProvider, BedrockProvider, CandleProvider, LMStudioProvider, OllamaProvider,
     OpenAIProvider, OpenRouterProvider,
 };
+use crate::llm::response_cache::{CachingProvider, ResponseCache};
 use crate::llm::traits::{LlmError, LlmProvider, ProviderType};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -34,6 +38,14 @@ pub struct ProviderRegistry {
     configs: RwLock<HashMap<ProviderType, ProviderConfig>>,
     /// Instantiated provider instances (shared across agents)
     providers: RwLock<HashMap<ProviderType, Arc<dyn LlmProvider>>>,
+    /// Middleware installed on each provider type, in order of installation
+    middleware: RwLock<HashMap<ProviderType, Vec<Arc<dyn LlmMiddleware>>>>,
+    /// Adaptive concurrency controllers limiting each provider type's requests
+    concurrency: RwLock<HashMap<ProviderType, Arc<AdaptiveConcurrency>>>,
+    /// Response caches answering repeated requests of each provider type
+    response_caches: RwLock<HashMap<ProviderType, Arc<ResponseCache>>>,
+    /// HTTP client settings each provider type is created with
+    http_configs: RwLock<HashMap<ProviderType, HttpClientConfig>>,
 }
 
 /// Configuration for each provider type
@@ -81,6 +93,10 @@ impl ProviderRegistry {
         Self {
             configs: RwLock::new(HashMap::new()),
             providers: RwLock::new(HashMap::new()),
+            middleware: RwLock::new(HashMap::new()),
+            concurrency: RwLock::new(HashMap::new()),
+            response_caches: RwLock::new(HashMap::new()),
+            http_configs: RwLock::new(HashMap::new()),
         }
     }
 
@@ -212,6 +228,22 @@ impl ProviderRegistry {
                 message: format!("No configuration found for provider {:?}. Make sure to call ProviderRegistry::configure() first or set required environment variables (AWS_ACCESS_KEY_ID, AWS_REGION, or AWS_PROFILE for Bedrock).", provider_type),
             })?;
 
+        // Without settings of their own, providers still go through the
+        // installed proxy
+        let http = match self.http_configs.read().await.get(&provider_type) {
+            Some(http) => Some(http.clone()),
+            None if crate::proxy::proxy().is_some() => Some(HttpClientConfig::default()),
+            None => None,
+        };
+        let http_client = match &http {
+            Some(http)
+                if !matches!(provider_type, ProviderType::Bedrock | ProviderType::Candle) =>
+            {
+                Some(http.build_client()?)
+            }
+            _ => None,
+        };
+
         // Create provider based on configuration
         let provider: Arc<dyn LlmProvider> = match (provider_type, config) {
             (
@@ -221,27 +253,31 @@ impl ProviderRegistry {
                     credentials,
                 },
             ) => {
-                let bedrock_provider = crate::perf_timed!("stood.registry.create_bedrock_provider", {
-                    if let Some(creds) = credentials {
-                        // Use custom credentials
-                        BedrockProvider::with_credentials(
-                            region.clone(),
-                            creds.access_key.clone(),
-                            creds.secret_key.clone(),
-                            creds.session_token.clone(),
-                        )
-                        .await
-                    } else {
-                        // Use default credential chain
-                        BedrockProvider::new(region.clone()).await
-                    }
-                })
-                .map_err(|e| LlmError::ProviderError {
-                    provider: provider_type,
-                    message: format!("Failed to create Bedrock provider: {}", e),
-                    source: Some(Box::new(e)),
-                })?;
-                Arc::new(bedrock_provider)
+                let bedrock_provider =
+                    crate::perf_timed!("stood.registry.create_bedrock_provider", {
+                        if let Some(creds) = credentials {
+                            // Use custom credentials
+                            BedrockProvider::with_credentials(
+                                region.clone(),
+                                creds.access_key.clone(),
+                                creds.secret_key.clone(),
+                                creds.session_token.clone(),
+                            )
+                            .await
+                        } else {
+                            // Use default credential chain
+                            BedrockProvider::new(region.clone()).await
+                        }
+                    })
+                    .map_err(|e| LlmError::ProviderError {
+                        provider: provider_type,
+                        message: format!("Failed to create Bedrock provider: {}", e),
+                        source: Some(Box::new(e)),
+                    })?;
+                match &http {
+                    Some(http) => Arc::new(bedrock_provider.with_http_config(http)?),
+                    None => Arc::new(bedrock_provider),
+                }
             }
             (
                 ProviderType::LmStudio,
@@ -261,7 +297,10 @@ impl ProviderRegistry {
                             message: format!("Failed to create LM Studio provider: {}", e),
                             source: Some(Box::new(e)),
                         })?;
-                Arc::new(lm_studio_provider)
+                match http_client {
+                    Some(client) => Arc::new(lm_studio_provider.with_http_client(client)),
+                    None => Arc::new(lm_studio_provider),
+                }
             }
             (ProviderType::Anthropic, ProviderConfig::Anthropic { api_key, base_url }) => {
                 let anthropic_provider = AnthropicProvider::new(api_key.clone(), base_url.clone())
@@ -271,7 +310,10 @@ impl ProviderRegistry {
                         message: format!("Failed to create Anthropic provider: {}", e),
                         source: Some(Box::new(e)),
                     })?;
-                Arc::new(anthropic_provider)
+                match http_client {
+                    Some(client) => Arc::new(anthropic_provider.with_http_client(client)),
+                    None => Arc::new(anthropic_provider),
+                }
             }
             (
                 ProviderType::OpenAI,
@@ -289,7 +331,10 @@ impl ProviderRegistry {
                             message: format!("Failed to create OpenAI provider: {}", e),
                             source: Some(Box::new(e)),
                         })?;
-                Arc::new(openai_provider)
+                match http_client {
+                    Some(client) => Arc::new(openai_provider.with_http_client(client)),
+                    None => Arc::new(openai_provider),
+                }
             }
             (ProviderType::Ollama, ProviderConfig::Ollama { base_url }) => {
                 let ollama_provider = OllamaProvider::new(base_url.clone()).await.map_err(|e| {
@@ -299,7 +344,10 @@ impl ProviderRegistry {
                         source: Some(Box::new(e)),
                     }
                 })?;
-                Arc::new(ollama_provider)
+                match http_client {
+                    Some(client) => Arc::new(ollama_provider.with_http_client(client)),
+                    None => Arc::new(ollama_provider),
+                }
             }
             (ProviderType::OpenRouter, ProviderConfig::OpenRouter { api_key, base_url }) => {
                 let openrouter_provider =
@@ -310,7 +358,10 @@ impl ProviderRegistry {
                             message: format!("Failed to create OpenRouter provider: {}", e),
                             source: Some(Box::new(e)),
                         })?;
-                Arc::new(openrouter_provider)
+                match http_client {
+                    Some(client) => Arc::new(openrouter_provider.with_http_client(client)),
+                    None => Arc::new(openrouter_provider),
+                }
             }
             (ProviderType::Candle, ProviderConfig::Candle { cache_dir, device }) => {
                 let candle_provider = CandleProvider::new(cache_dir.clone(), device.clone())
@@ -330,6 +381,36 @@ impl ProviderRegistry {
             }
         };
 
+        // Limit concurrent requests, inside the middleware so requests answered
+        // by middleware do not take a slot
+        let controller = self.concurrency.read().await.get(&provider_type).cloned();
+        let provider: Arc<dyn LlmProvider> = match controller {
+            Some(controller) => Arc::new(ConcurrencyLimitedProvider::new(provider, controller)),
+            None => provider,
+        };
+
+        // Answer repeated requests from the cache, outside the concurrency limit
+        // so cache hits do not take a slot
+        let cache = self
+            .response_caches
+            .read()
+            .await
+            .get(&provider_type)
+            .cloned();
+        let provider: Arc<dyn LlmProvider> = match cache {
+            Some(cache) => Arc::new(CachingProvider::new(provider, cache)),
+            None => provider,
+        };
+
+        // Route requests through the installed middleware, if any
+        let middleware = self.middleware.read().await.get(&provider_type).cloned();
+        let provider: Arc<dyn LlmProvider> = match middleware {
+            Some(middleware) if !middleware.is_empty() => {
+                Arc::new(MiddlewareProvider::new(provider, middleware))
+            }
+            _ => provider,
+        };
+
         // Cache the provider for future use
         crate::perf_timed!("stood.registry.cache_write", {
             let mut providers = self.providers.write().await;
@@ -360,6 +441,116 @@ impl ProviderRegistry {
         configs.insert(provider_type, config);
     }
 
+    /// Install middleware on a provider type
+    ///
+    /// Every request of the provider goes through its middleware, in the order
+    /// it was added. The cached provider instance is dropped so the middleware
+    /// applies from the next [`get_provider`](Self::get_provider) call; agents
+    /// that already hold the provider keep using it without the middleware.
+    pub async fn add_middleware(
+        &self,
+        provider_type: ProviderType,
+        middleware: Arc<dyn LlmMiddleware>,
+    ) {
+        self.middleware
+            .write()
+            .await
+            .entry(provider_type)
+            .or_default()
+            .push(middleware);
+        self.providers.write().await.remove(&provider_type);
+    }
+
+    /// Remove all middleware from a provider type
+    pub async fn clear_middleware(&self, provider_type: ProviderType) {
+        self.middleware.write().await.remove(&provider_type);
+        self.providers.write().await.remove(&provider_type);
+    }
+
+    /// Limit the concurrent requests of a provider type with an adaptive
+    /// controller
+    ///
+    /// Replaces any previous controller. Like middleware, it applies from the
+    /// next [`get_provider`](Self::get_provider) call.
+    pub async fn set_concurrency_controller(
+        &self,
+        provider_type: ProviderType,
+        controller: Arc<AdaptiveConcurrency>,
+    ) {
+        self.concurrency
+            .write()
+            .await
+            .insert(provider_type, controller);
+        self.providers.write().await.remove(&provider_type);
+    }
+
+    /// The concurrency controller of a provider type, if one is set
+    pub async fn concurrency_controller(
+        &self,
+        provider_type: ProviderType,
+    ) -> Option<Arc<AdaptiveConcurrency>> {
+        self.concurrency.read().await.get(&provider_type).cloned()
+    }
+
+    /// Remove the concurrency controller of a provider type
+    pub async fn clear_concurrency_controller(&self, provider_type: ProviderType) {
+        self.concurrency.write().await.remove(&provider_type);
+        self.providers.write().await.remove(&provider_type);
+    }
+
+    /// Answer repeated requests of a provider type from `cache`
+    ///
+    /// Requests are matched after middleware has rewritten them. Replaces any
+    /// previous cache and applies from the next
+    /// [`get_provider`](Self::get_provider) call. See
+    /// [`response_cache`](crate::llm::response_cache).
+    pub async fn set_response_cache(&self, provider_type: ProviderType, cache: Arc<ResponseCache>) {
+        self.response_caches
+            .write()
+            .await
+            .insert(provider_type, cache);
+        self.providers.write().await.remove(&provider_type);
+    }
+
+    /// The response cache of a provider type, if one is set
+    pub async fn response_cache(&self, provider_type: ProviderType) -> Option<Arc<ResponseCache>> {
+        self.response_caches
+            .read()
+            .await
+            .get(&provider_type)
+            .cloned()
+    }
+
+    /// Stop caching the responses of a provider type
+    pub async fn clear_response_cache(&self, provider_type: ProviderType) {
+        self.response_caches.write().await.remove(&provider_type);
+        self.providers.write().await.remove(&provider_type);
+    }
+
+    /// Create a provider type's HTTP client with `config`
+    ///
+    /// Replaces any previous settings and applies from the next
+    /// [`get_provider`](Self::get_provider) call. See
+    /// [`http`](crate::llm::http).
+    pub async fn set_http_config(&self, provider_type: ProviderType, config: HttpClientConfig) {
+        self.http_configs
+            .write()
+            .await
+            .insert(provider_type, config);
+        self.providers.write().await.remove(&provider_type);
+    }
+
+    /// The HTTP client settings of a provider type, if any are set
+    pub async fn http_config(&self, provider_type: ProviderType) -> Option<HttpClientConfig> {
+        self.http_configs.read().await.get(&provider_type).cloned()
+    }
+
+    /// Go back to the default HTTP client for a provider type
+    pub async fn clear_http_config(&self, provider_type: ProviderType) {
+        self.http_configs.write().await.remove(&provider_type);
+        self.providers.write().await.remove(&provider_type);
+    }
+
     /// Clear all cached providers (useful for testing)
     pub async fn clear_cache(&self) {
         let mut providers = self.providers.write().await;
@@ -432,4 +623,44 @@ mod tests {
             LlmError::ConfigurationError { .. }
         ));
     }
+
+    #[tokio::test]
+    async fn test_http_config() {
+        let registry = ProviderRegistry::new();
+        registry
+            .add_config(
+                ProviderType::LmStudio,
+                ProviderConfig::LMStudio {
+                    base_url: "http://localhost:1234".to_string(),
+                    retry_config: None,
+                },
+            )
+            .await;
+
+        let http = HttpClientConfig::new().with_pool_max_idle_per_host(4);
+        registry
+            .set_http_config(ProviderType::LmStudio, http.clone())
+            .await;
+        assert_eq!(
+            registry.http_config(ProviderType::LmStudio).await,
+            Some(http)
+        );
+        assert!(registry.get_provider(ProviderType::LmStudio).await.is_ok());
+
+        // An invalid proxy fails provider creation instead of being bypassed
+        registry
+            .set_http_config(
+                ProviderType::LmStudio,
+                HttpClientConfig::new().with_proxy("not a url"),
+            )
+            .await;
+        assert!(matches!(
+            registry.get_provider(ProviderType::LmStudio).await,
+            Err(LlmError::ConfigurationError { .. })
+        ));
+
+        registry.clear_http_config(ProviderType::LmStudio).await;
+        assert!(registry.http_config(ProviderType::LmStudio).await.is_none());
+        assert!(registry.get_provider(ProviderType::LmStudio).await.is_ok());
+    }
 }
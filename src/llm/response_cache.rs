@@ -0,0 +1,735 @@
+//! Exact-match caching of provider responses.
+//!
+//! Test suites and idempotent re-runs send the same requests again and again;
+//! calling the model each time costs money and gives different answers. A
+//! [`ResponseCache`] stores each response under a hash of the full request
+//! (provider, model, messages, tools and chat configuration) and answers an
+//! identical request from the cache, for the configured TTL. Streamed
+//! responses are stored as their events and replayed as a stream.
+//!
+//! Install a cache on a provider type and every agent using that provider
+//! shares it:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use stood::llm::registry::PROVIDER_REGISTRY;
+//! use stood::llm::response_cache::ResponseCache;
+//! use stood::llm::traits::ProviderType;
+//!
+//! # async fn example() -> std::io::Result<()> {
+//! let cache = ResponseCache::load_or_new("tests/fixtures/responses.json")?
+//!     .with_ttl(Duration::from_secs(7 * 24 * 60 * 60));
+//! let cache = Arc::new(cache);
+//! PROVIDER_REGISTRY
+//!     .set_response_cache(ProviderType::Bedrock, cache.clone())
+//!     .await;
+//!
+//! // ... run the agents ...
+//!
+//! cache.save("tests/fixtures/responses.json")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Instead of a JSON file, the cache can be kept in a shared
+//! [`KvStore`](crate::storage::KvStore) with
+//! [`load_from_store`](ResponseCache::load_from_store) and
+//! [`save_to_store`](ResponseCache::save_to_store).
+//!
+//! Message ids, timestamps and metadata are not part of the key, so a rebuilt
+//! conversation with the same content hits the cache. Replayed responses keep
+//! their original tool call ids, so a whole multi-turn execution replays as
+//! long as its tools return the same results.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::llm::traits::{
+    ChatConfig, ChatResponse, HealthStatus, LlmError, LlmProvider, ProviderCapabilities,
+    ProviderType, StreamEvent, Tool,
+};
+use crate::storage::{KvStore, KvStoreExt};
+use crate::types::Messages;
+
+/// A cached response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CachedResponse {
+    /// Response of a non-streaming request
+    Response(ChatResponse),
+    /// Events of a streaming request
+    Stream(Vec<StreamEvent>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    created_at: DateTime<Utc>,
+    response: CachedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    entries: HashMap<String, CacheEntry>,
+}
+
+/// Hit and miss counts of a [`ResponseCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseCacheStats {
+    /// Requests answered from the cache
+    pub hits: u64,
+    /// Requests sent to the provider
+    pub misses: u64,
+}
+
+/// Exact-match cache of provider responses keyed by the full request
+///
+/// Entries never expire unless a TTL is set, and the cache grows without
+/// bound unless a maximum number of entries is set. Only successful responses
+/// are stored; streams that report an error are not.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    ttl: Option<Duration>,
+    max_entries: Option<usize>,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    stats: Mutex<ResponseCacheStats>,
+}
+
+impl ResponseCache {
+    /// Empty cache whose entries never expire
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expire entries `ttl` after they were stored
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Keep at most `max_entries` entries, evicting the oldest first
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self.evict_oldest(&mut self.lock_entries());
+        self
+    }
+
+    /// Load a cache saved with [`save`](Self::save)
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file: CacheFile = serde_json::from_slice(&std::fs::read(path)?)?;
+        Ok(Self {
+            entries: Mutex::new(file.entries),
+            ..Self::default()
+        })
+    }
+
+    /// Load a saved cache, or start an empty one if `path` does not exist
+    pub fn load_or_new(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        match Self::load(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            result => result,
+        }
+    }
+
+    /// Write the unexpired entries to `path` as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        self.evict_expired();
+        let file = CacheFile {
+            entries: self.lock_entries().clone(),
+        };
+        std::fs::write(path, serde_json::to_vec_pretty(&file)?)
+    }
+
+    /// Load the entries saved in `store` under `prefix` with
+    /// [`save_to_store`](Self::save_to_store)
+    pub async fn load_from_store(store: &dyn KvStore, prefix: &str) -> crate::Result<Self> {
+        let mut entries = HashMap::new();
+        for store_key in store.keys(prefix).await? {
+            if let Some(entry) = store.get_json::<CacheEntry>(&store_key).await? {
+                entries.insert(store_key[prefix.len()..].to_string(), entry);
+            }
+        }
+        Ok(Self {
+            entries: Mutex::new(entries),
+            ..Self::default()
+        })
+    }
+
+    /// Write the unexpired entries to `store`, one key per entry under
+    /// `prefix`, removing entries no longer in the cache
+    pub async fn save_to_store(&self, store: &dyn KvStore, prefix: &str) -> crate::Result<()> {
+        self.evict_expired();
+        let entries = self.lock_entries().clone();
+        for store_key in store.keys(prefix).await? {
+            if !entries.contains_key(&store_key[prefix.len()..]) {
+                store.delete(&store_key).await?;
+            }
+        }
+        for (key, entry) in &entries {
+            store.put_json(&format!("{}{}", prefix, key), entry).await?;
+        }
+        Ok(())
+    }
+
+    /// Cache key of a request: the hex SHA-256 of its canonical JSON form
+    pub fn request_key(
+        provider: ProviderType,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+        streaming: bool,
+    ) -> String {
+        let message_list: Vec<Value> = messages
+            .messages
+            .iter()
+            .map(|m| json!({"role": m.role, "content": m.content}))
+            .collect();
+        let request = json!({
+            "provider": provider,
+            "model_id": model_id,
+            "system_prompt": messages.system_prompt,
+            "messages": message_list,
+            "tools": tools,
+            "config": config,
+            "streaming": streaming,
+        });
+        hex::encode(Sha256::digest(canonical(request).to_string()))
+    }
+
+    /// Number of stored entries, including expired ones not yet evicted
+    pub fn len(&self) -> usize {
+        self.lock_entries().len()
+    }
+
+    /// Whether the cache has no entries
+    pub fn is_empty(&self) -> bool {
+        self.lock_entries().is_empty()
+    }
+
+    /// Remove all entries and reset the statistics
+    pub fn clear(&self) {
+        self.lock_entries().clear();
+        *self.lock_stats() = ResponseCacheStats::default();
+    }
+
+    /// Hit and miss counts since creation or the last [`clear`](Self::clear)
+    pub fn stats(&self) -> ResponseCacheStats {
+        *self.lock_stats()
+    }
+
+    fn is_expired(&self, entry: &CacheEntry) -> bool {
+        self.ttl.is_some_and(|ttl| {
+            (Utc::now() - entry.created_at)
+                .to_std()
+                .is_ok_and(|age| age >= ttl)
+        })
+    }
+
+    fn evict_expired(&self) {
+        let mut entries = self.lock_entries();
+        entries.retain(|_, entry| !self.is_expired(entry));
+    }
+
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.lock_entries();
+        let hit = match entries.get(key) {
+            Some(entry) if self.is_expired(entry) => {
+                entries.remove(key);
+                None
+            }
+            entry => entry.map(|entry| entry.response.clone()),
+        };
+        let mut stats = self.lock_stats();
+        if hit.is_some() {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+        hit
+    }
+
+    fn insert(&self, key: String, response: CachedResponse) {
+        let mut entries = self.lock_entries();
+        entries.insert(
+            key,
+            CacheEntry {
+                created_at: Utc::now(),
+                response,
+            },
+        );
+        self.evict_oldest(&mut entries);
+    }
+
+    /// Remove expired entries, then the oldest ones, until `entries` fits
+    /// the maximum
+    fn evict_oldest(&self, entries: &mut HashMap<String, CacheEntry>) {
+        let Some(max_entries) = self.max_entries else {
+            return;
+        };
+        if entries.len() <= max_entries {
+            return;
+        }
+        entries.retain(|_, entry| !self.is_expired(entry));
+        if entries.len() <= max_entries {
+            return;
+        }
+        let mut by_age: Vec<(DateTime<Utc>, String)> = entries
+            .iter()
+            .map(|(key, entry)| (entry.created_at, key.clone()))
+            .collect();
+        by_age.sort();
+        for (_, key) in by_age.into_iter().take(entries.len() - max_entries) {
+            entries.remove(&key);
+        }
+    }
+
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, CacheEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_stats(&self) -> std::sync::MutexGuard<'_, ResponseCacheStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// `value` with object keys sorted, so equal requests serialize identically
+fn canonical(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonical(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonical).collect()),
+        value => value,
+    }
+}
+
+/// Provider whose responses are answered from a [`ResponseCache`] when the
+/// same request was seen before
+#[derive(Debug)]
+pub struct CachingProvider {
+    inner: Arc<dyn LlmProvider>,
+    cache: Arc<ResponseCache>,
+}
+
+impl CachingProvider {
+    /// Cache the responses of `inner` in `cache`
+    pub fn new(inner: Arc<dyn LlmProvider>, cache: Arc<ResponseCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    /// The cache used by this provider
+    pub fn cache(&self) -> &Arc<ResponseCache> {
+        &self.cache
+    }
+
+    fn key(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+        streaming: bool,
+    ) -> String {
+        ResponseCache::request_key(
+            self.inner.provider_type(),
+            model_id,
+            messages,
+            tools,
+            config,
+            streaming,
+        )
+    }
+
+    fn cached_stream(
+        &self,
+        key: String,
+        result: Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError>,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        let stream = result?;
+        Ok(Box::new(RecordingStream {
+            inner: stream,
+            key,
+            cache: Some(self.cache.clone()),
+            events: Vec::new(),
+        }))
+    }
+
+    fn replay(
+        &self,
+        key: &str,
+    ) -> Option<Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError>> {
+        match self.cache.get(key)? {
+            CachedResponse::Stream(events) => Some(Ok(Box::new(futures::stream::iter(events)))),
+            CachedResponse::Response(_) => None,
+        }
+    }
+
+    fn lookup(&self, key: &str) -> Option<ChatResponse> {
+        match self.cache.get(key)? {
+            CachedResponse::Response(response) => Some(response),
+            CachedResponse::Stream(_) => None,
+        }
+    }
+}
+
+/// Stream that stores its events in the cache once it ends without error
+struct RecordingStream {
+    inner: Box<dyn Stream<Item = StreamEvent> + Send + Unpin>,
+    key: String,
+    cache: Option<Arc<ResponseCache>>,
+    events: Vec<StreamEvent>,
+}
+
+impl Stream for RecordingStream {
+    type Item = StreamEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<StreamEvent>> {
+        let poll = Pin::new(&mut self.inner).poll_next(cx);
+        match &poll {
+            Poll::Ready(Some(StreamEvent::Error { .. })) => self.cache = None,
+            Poll::Ready(Some(event)) => {
+                if self.cache.is_some() {
+                    let event = event.clone();
+                    self.events.push(event);
+                }
+            }
+            Poll::Ready(None) => {
+                if let Some(cache) = self.cache.take() {
+                    let events = std::mem::take(&mut self.events);
+                    let key = std::mem::take(&mut self.key);
+                    cache.insert(key, CachedResponse::Stream(events));
+                }
+            }
+            Poll::Pending => {}
+        }
+        poll
+    }
+}
+
+#[async_trait]
+impl LlmProvider for CachingProvider {
+    async fn chat(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        config: &ChatConfig,
+    ) -> Result<ChatResponse, LlmError> {
+        let key = self.key(model_id, messages, &[], config, false);
+        if let Some(response) = self.lookup(&key) {
+            return Ok(response);
+        }
+        let response = self.inner.chat(model_id, messages, config).await?;
+        self.cache
+            .insert(key, CachedResponse::Response(response.clone()));
+        Ok(response)
+    }
+
+    async fn chat_with_tools(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+    ) -> Result<ChatResponse, LlmError> {
+        let key = self.key(model_id, messages, tools, config, false);
+        if let Some(response) = self.lookup(&key) {
+            return Ok(response);
+        }
+        let response = self
+            .inner
+            .chat_with_tools(model_id, messages, tools, config)
+            .await?;
+        self.cache
+            .insert(key, CachedResponse::Response(response.clone()));
+        Ok(response)
+    }
+
+    async fn chat_streaming(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        config: &ChatConfig,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        let key = self.key(model_id, messages, &[], config, true);
+        if let Some(stream) = self.replay(&key) {
+            return stream;
+        }
+        let result = self.inner.chat_streaming(model_id, messages, config).await;
+        self.cached_stream(key, result)
+    }
+
+    async fn chat_streaming_with_tools(
+        &self,
+        model_id: &str,
+        messages: &Messages,
+        tools: &[Tool],
+        config: &ChatConfig,
+    ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+        let key = self.key(model_id, messages, tools, config, true);
+        if let Some(stream) = self.replay(&key) {
+            return stream;
+        }
+        let result = self
+            .inner
+            .chat_streaming_with_tools(model_id, messages, tools, config)
+            .await;
+        self.cached_stream(key, result)
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, LlmError> {
+        self.inner.health_check().await
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        self.inner.provider_type()
+    }
+
+    fn supported_models(&self) -> Vec<&'static str> {
+        self.inner.supported_models()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self.inner.as_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::traits::ContentBlockDelta;
+    use crate::types::Message;
+    use futures::StreamExt;
+
+    /// Provider that answers with the number of calls made so far
+    #[derive(Debug, Default)]
+    struct CountingProvider {
+        calls: Mutex<usize>,
+    }
+
+    impl CountingProvider {
+        fn next(&self) -> String {
+            let mut calls = self.calls.lock().unwrap();
+            *calls += 1;
+            format!("answer {}", calls)
+        }
+    }
+
+    #[async_trait]
+    impl LlmProvider for CountingProvider {
+        async fn chat(
+            &self,
+            _model_id: &str,
+            _messages: &Messages,
+            _config: &ChatConfig,
+        ) -> Result<ChatResponse, LlmError> {
+            Ok(ChatResponse {
+                content: self.next(),
+                tool_calls: Vec::new(),
+                thinking: None,
+                usage: None,
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn chat_with_tools(
+            &self,
+            model_id: &str,
+            messages: &Messages,
+            _tools: &[Tool],
+            config: &ChatConfig,
+        ) -> Result<ChatResponse, LlmError> {
+            self.chat(model_id, messages, config).await
+        }
+
+        async fn chat_streaming(
+            &self,
+            _model_id: &str,
+            _messages: &Messages,
+            _config: &ChatConfig,
+        ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+            let events = vec![StreamEvent::ContentBlockDelta {
+                delta: ContentBlockDelta::Text { text: self.next() },
+                block_index: 0,
+            }];
+            Ok(Box::new(futures::stream::iter(events)))
+        }
+
+        async fn chat_streaming_with_tools(
+            &self,
+            model_id: &str,
+            messages: &Messages,
+            _tools: &[Tool],
+            config: &ChatConfig,
+        ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError> {
+            self.chat_streaming(model_id, messages, config).await
+        }
+
+        async fn health_check(&self) -> Result<HealthStatus, LlmError> {
+            unimplemented!()
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            unimplemented!()
+        }
+
+        fn provider_type(&self) -> ProviderType {
+            ProviderType::LmStudio
+        }
+
+        fn supported_models(&self) -> Vec<&'static str> {
+            vec!["counting"]
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    fn streamed_text(events: &[StreamEvent]) -> String {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                StreamEvent::ContentBlockDelta {
+                    delta: ContentBlockDelta::Text { text },
+                    ..
+                } => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_identical_requests_hit_the_cache() {
+        let cache = Arc::new(ResponseCache::new());
+        let provider = CachingProvider::new(Arc::new(CountingProvider::default()), cache.clone());
+        let config = ChatConfig::default();
+        let question = || Messages::from(vec![Message::user("What is 2 + 2?")]);
+
+        // Rebuilt messages have new ids and timestamps but the same key
+        let first = provider.chat("m", &question(), &config).await.unwrap();
+        let second = provider.chat("m", &question(), &config).await.unwrap();
+        assert_eq!(first.content, "answer 1");
+        assert_eq!(second.content, "answer 1");
+
+        let other = Messages::from(vec![Message::user("What is 3 + 3?")]);
+        let third = provider.chat("m", &other, &config).await.unwrap();
+        assert_eq!(third.content, "answer 2");
+
+        let mut warmer = config.clone();
+        warmer.temperature = Some(1.0);
+        let fourth = provider.chat("m", &question(), &warmer).await.unwrap();
+        assert_eq!(fourth.content, "answer 3");
+
+        for _ in 0..2 {
+            let events: Vec<_> = provider
+                .chat_streaming("m", &question(), &config)
+                .await
+                .unwrap()
+                .collect()
+                .await;
+            assert_eq!(streamed_text(&events), "answer 4");
+        }
+        assert_eq!(cache.stats(), ResponseCacheStats { hits: 2, misses: 4 });
+    }
+
+    #[tokio::test]
+    async fn test_ttl_and_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("responses.json");
+        let messages = Messages::from(vec![Message::user("hello")]);
+        let config = ChatConfig::default();
+
+        let cache = Arc::new(ResponseCache::load_or_new(&path).unwrap());
+        let provider = CachingProvider::new(Arc::new(CountingProvider::default()), cache.clone());
+        provider.chat("m", &messages, &config).await.unwrap();
+        cache.save(&path).unwrap();
+
+        // A new process replays the saved response
+        let loaded = Arc::new(ResponseCache::load(&path).unwrap());
+        let provider = CachingProvider::new(Arc::new(CountingProvider::default()), loaded);
+        let response = provider.chat("m", &messages, &config).await.unwrap();
+        assert_eq!(response.content, "answer 1");
+
+        let expiring = Arc::new(ResponseCache::load(&path).unwrap().with_ttl(Duration::ZERO));
+        let provider = CachingProvider::new(Arc::new(CountingProvider::default()), expiring);
+        let response = provider.chat("m", &messages, &config).await.unwrap();
+        assert_eq!(response.content, "answer 1");
+        assert_eq!(provider.cache().stats().misses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_store_persistence() {
+        let store = crate::storage::InMemoryKvStore::new();
+        let messages = Messages::from(vec![Message::user("hello")]);
+        let config = ChatConfig::default();
+
+        let cache = Arc::new(ResponseCache::new());
+        let provider = CachingProvider::new(Arc::new(CountingProvider::default()), cache.clone());
+        provider.chat("m", &messages, &config).await.unwrap();
+        cache.save_to_store(&store, "cache/").await.unwrap();
+        assert_eq!(store.keys("cache/").await.unwrap().len(), 1);
+
+        let loaded = Arc::new(
+            ResponseCache::load_from_store(&store, "cache/")
+                .await
+                .unwrap(),
+        );
+        let provider = CachingProvider::new(Arc::new(CountingProvider::default()), loaded);
+        let response = provider.chat("m", &messages, &config).await.unwrap();
+        assert_eq!(response.content, "answer 1");
+
+        // Saving mirrors the cache, dropping entries it no longer has
+        provider.cache().clear();
+        provider
+            .cache()
+            .save_to_store(&store, "cache/")
+            .await
+            .unwrap();
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_max_entries_evicts_the_oldest() {
+        let cache = Arc::new(ResponseCache::new().with_max_entries(2));
+        let provider = CachingProvider::new(Arc::new(CountingProvider::default()), cache.clone());
+        let config = ChatConfig::default();
+        let question = |text: &str| Messages::from(vec![Message::user(text)]);
+
+        for text in ["one", "two", "three"] {
+            provider.chat("m", &question(text), &config).await.unwrap();
+        }
+        assert_eq!(cache.len(), 2);
+
+        let response = provider
+            .chat("m", &question("three"), &config)
+            .await
+            .unwrap();
+        assert_eq!(response.content, "answer 3");
+        let response = provider.chat("m", &question("one"), &config).await.unwrap();
+        assert_eq!(response.content, "answer 4");
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -0,0 +1,463 @@
+//! Adapting tool input schemas to what each model family accepts.
+//!
+//! Tools describe their input with JSON Schema, often generated by `schemars`,
+//! which uses `$ref`, `anyOf` for optional values and `type: [T, "null"]`.
+//! Claude accepts these, but Amazon Nova only accepts a subset: an object at
+//! the root, plain `type`s, and no `anyOf`, `oneOf`, `allOf` or `$ref`.
+//!
+//! [`translate_tool_schema`] rewrites a schema for a [`SchemaDialect`]. Common
+//! constructs are rewritten into equivalent supported ones (references are
+//! inlined, optional values lose their `null` alternative, `const` becomes a
+//! one-value `enum`) and anything that cannot be expressed is reported as a
+//! [`SchemaIssue`]. The Bedrock provider applies the translation to every
+//! request, and [`AgentBuilder::build`] rejects tools whose schemas have
+//! issues, so they fail when the agent is built instead of on the first
+//! request.
+//!
+//! ```
+//! use serde_json::json;
+//! use stood::llm::tool_schema::{translate_tool_schema, SchemaDialect};
+//!
+//! let schema = json!({
+//!     "type": "object",
+//!     "properties": {
+//!         "limit": { "anyOf": [{ "type": "integer" }, { "type": "null" }] }
+//!     }
+//! });
+//! let dialect = SchemaDialect::for_model("us.amazon.nova-lite-v1:0");
+//! let translation = translate_tool_schema(dialect, &schema);
+//! assert!(translation.is_supported());
+//! assert_eq!(translation.schema["properties"]["limit"], json!({ "type": "integer" }));
+//! ```
+//!
+//! [`AgentBuilder::build`]: crate::agent::AgentBuilder::build
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+/// JSON Schema subset accepted by a model family
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaDialect {
+    /// Full JSON Schema; schemas are sent unchanged
+    JsonSchema,
+    /// Amazon Nova's subset of JSON Schema
+    Nova,
+}
+
+impl SchemaDialect {
+    /// Dialect of the model with the given id
+    pub fn for_model(model_id: &str) -> Self {
+        if model_id.contains("amazon.nova") {
+            SchemaDialect::Nova
+        } else {
+            SchemaDialect::JsonSchema
+        }
+    }
+}
+
+/// A schema construct the dialect cannot express
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaIssue {
+    /// Location of the construct, e.g. `properties.filter.oneOf`
+    pub path: String,
+    /// What is unsupported
+    pub message: String,
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Result of [`translate_tool_schema`]
+#[derive(Debug, Clone)]
+pub struct SchemaTranslation {
+    /// The schema rewritten for the dialect
+    pub schema: Value,
+    /// Rewrites that were applied, for logging
+    pub adaptations: Vec<String>,
+    /// Constructs left in the schema that the dialect does not support,
+    /// ordered by path
+    pub issues: Vec<SchemaIssue>,
+}
+
+impl SchemaTranslation {
+    /// Whether the translated schema only uses supported constructs
+    pub fn is_supported(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Keywords Nova rejects that carry no meaning for the model
+const NOVA_DROPPED_KEYWORDS: &[&str] = &["$schema", "$id", "additionalProperties", "examples"];
+
+/// Rewrite `schema` into a form `dialect` accepts
+pub fn translate_tool_schema(dialect: SchemaDialect, schema: &Value) -> SchemaTranslation {
+    let mut translation = SchemaTranslation {
+        schema: schema.clone(),
+        adaptations: Vec::new(),
+        issues: Vec::new(),
+    };
+    if dialect == SchemaDialect::JsonSchema {
+        return translation;
+    }
+
+    let mut root = schema.clone();
+    let definitions = match root.as_object_mut() {
+        Some(object) => {
+            let mut definitions = Map::new();
+            for key in ["$defs", "definitions"] {
+                if let Some(Value::Object(defs)) = object.remove(key) {
+                    definitions.extend(defs);
+                }
+            }
+            definitions
+        }
+        None => {
+            translation.issues.push(SchemaIssue {
+                path: "$".to_string(),
+                message: "schema must be a JSON object".to_string(),
+            });
+            return translation;
+        }
+    };
+
+    let mut translator = NovaTranslator {
+        definitions,
+        resolving: Vec::new(),
+        adaptations: Vec::new(),
+        issues: Vec::new(),
+    };
+    translator.translate(&mut root, "$");
+
+    if root.get("type").is_none() {
+        root["type"] = Value::from("object");
+        translator
+            .adaptations
+            .push("$: added missing `type: object`".to_string());
+    }
+    if root.get("type") != Some(&Value::from("object")) {
+        translator.issues.push(SchemaIssue {
+            path: "$".to_string(),
+            message: "root schema must have `type: object`".to_string(),
+        });
+    }
+
+    translation.schema = root;
+    translation.adaptations = translator.adaptations;
+    translation.issues = translator.issues;
+    // Property order depends on whether serde_json preserves insertion order
+    translation.issues.sort_by(|a, b| a.path.cmp(&b.path));
+    translation
+}
+
+struct NovaTranslator {
+    definitions: Map<String, Value>,
+    resolving: Vec<String>,
+    adaptations: Vec<String>,
+    issues: Vec<SchemaIssue>,
+}
+
+impl NovaTranslator {
+    fn adapt(&mut self, path: &str, what: &str) {
+        self.adaptations.push(format!("{}: {}", path, what));
+    }
+
+    fn unsupported(&mut self, path: String, message: impl Into<String>) {
+        self.issues.push(SchemaIssue {
+            path,
+            message: message.into(),
+        });
+    }
+
+    fn translate(&mut self, node: &mut Value, path: &str) {
+        let Some(object) = node.as_object_mut() else {
+            return;
+        };
+
+        for keyword in NOVA_DROPPED_KEYWORDS {
+            if object.remove(*keyword).is_some() {
+                self.adapt(path, &format!("removed `{}`", keyword));
+            }
+        }
+
+        if let Some(reference) = object.remove("$ref") {
+            self.inline_reference(object, &reference, path);
+        }
+        if let Some(Value::Array(variants)) = object.remove("allOf") {
+            self.merge_all_of(object, variants, path);
+        }
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(variants)) = object.remove(keyword) {
+                self.collapse_variants(object, keyword, variants, path);
+            }
+        }
+        if let Some(constant) = object.remove("const") {
+            object.insert("enum".to_string(), Value::Array(vec![constant]));
+            self.adapt(path, "replaced `const` with a one-value `enum`");
+        }
+        if let Some(Value::Array(types)) = object.get("type") {
+            let non_null: Vec<Value> = types
+                .iter()
+                .filter(|t| t.as_str() != Some("null"))
+                .cloned()
+                .collect();
+            if non_null.len() == 1 {
+                object.insert("type".to_string(), non_null[0].clone());
+                self.adapt(path, "removed `null` from the type list");
+            } else {
+                self.unsupported(format!("{}.type", path), "multiple types are not supported");
+            }
+        }
+
+        if let Some(Value::Object(properties)) = object.get_mut("properties") {
+            let mut invalid_names = Vec::new();
+            for (name, property) in properties.iter_mut() {
+                if !is_valid_property_name(name) {
+                    invalid_names.push(name.clone());
+                }
+                self.translate(property, &format!("{}.properties.{}", path, name));
+            }
+            for name in invalid_names {
+                self.unsupported(
+                    format!("{}.properties.{}", path, name),
+                    "property names may only contain letters, digits and underscores",
+                );
+            }
+        }
+        if let Some(items) = object.get_mut("items") {
+            self.translate(items, &format!("{}.items", path));
+        }
+    }
+
+    fn inline_reference(&mut self, object: &mut Map<String, Value>, reference: &Value, path: &str) {
+        let name = reference
+            .as_str()
+            .and_then(|r| {
+                r.strip_prefix("#/$defs/")
+                    .or_else(|| r.strip_prefix("#/definitions/"))
+            })
+            .map(str::to_string);
+        let Some(name) = name else {
+            self.unsupported(
+                format!("{}.$ref", path),
+                format!("unresolvable reference {}", reference),
+            );
+            return;
+        };
+        if self.resolving.contains(&name) {
+            self.unsupported(
+                format!("{}.$ref", path),
+                format!("recursive reference to `{}`", name),
+            );
+            return;
+        }
+        let Some(mut target) = self.definitions.get(&name).cloned() else {
+            self.unsupported(
+                format!("{}.$ref", path),
+                format!("undefined reference to `{}`", name),
+            );
+            return;
+        };
+
+        self.resolving.push(name.clone());
+        self.translate(&mut target, path);
+        self.resolving.pop();
+        if let Value::Object(target) = target {
+            for (key, value) in target {
+                object.entry(key).or_insert(value);
+            }
+        }
+        self.adapt(path, &format!("inlined reference to `{}`", name));
+    }
+
+    fn merge_all_of(&mut self, object: &mut Map<String, Value>, variants: Vec<Value>, path: &str) {
+        for (i, mut variant) in variants.into_iter().enumerate() {
+            self.translate(&mut variant, &format!("{}.allOf[{}]", path, i));
+            let Value::Object(variant) = variant else {
+                continue;
+            };
+            for (key, value) in variant {
+                match (key.as_str(), object.get_mut(&key), value) {
+                    ("properties", Some(Value::Object(existing)), Value::Object(added)) => {
+                        existing.extend(added)
+                    }
+                    ("required", Some(Value::Array(existing)), Value::Array(added)) => {
+                        for name in added {
+                            if !existing.contains(&name) {
+                                existing.push(name);
+                            }
+                        }
+                    }
+                    (_, Some(_), _) => {}
+                    (_, None, value) => {
+                        object.insert(key, value);
+                    }
+                }
+            }
+        }
+        self.adapt(path, "merged `allOf` into one schema");
+    }
+
+    fn collapse_variants(
+        &mut self,
+        object: &mut Map<String, Value>,
+        keyword: &str,
+        variants: Vec<Value>,
+        path: &str,
+    ) {
+        let mut variants: Vec<Value> = variants
+            .into_iter()
+            .filter(|variant| variant.get("type").and_then(Value::as_str) != Some("null"))
+            .collect();
+        for (i, variant) in variants.iter_mut().enumerate() {
+            self.translate(variant, &format!("{}.{}[{}]", path, keyword, i));
+        }
+
+        if variants.len() == 1 {
+            if let Some(Value::Object(variant)) = variants.pop() {
+                for (key, value) in variant {
+                    object.entry(key).or_insert(value);
+                }
+            }
+            self.adapt(path, &format!("collapsed optional `{}`", keyword));
+            return;
+        }
+
+        // Alternatives that are each a single value of the same type, as
+        // generated for unit enum variants, become one `enum`
+        let values: Option<Vec<Vec<Value>>> = variants
+            .iter()
+            .map(|variant| match variant.get("enum") {
+                Some(Value::Array(values)) => Some(values.clone()),
+                _ => None,
+            })
+            .collect();
+        let types: Vec<Option<&Value>> = variants.iter().map(|v| v.get("type")).collect();
+        if let Some(values) = values {
+            if !values.is_empty() && types.windows(2).all(|pair| pair[0] == pair[1]) {
+                if let Some(Some(kind)) = types.first() {
+                    object.insert("type".to_string(), (*kind).clone());
+                }
+                object.insert("enum".to_string(), Value::Array(values.concat()));
+                self.adapt(
+                    path,
+                    &format!("replaced `{}` of values with `enum`", keyword),
+                );
+                return;
+            }
+        }
+
+        self.unsupported(
+            format!("{}.{}", path, keyword),
+            format!(
+                "`{}` with {} alternatives is not supported",
+                keyword,
+                variants.len()
+            ),
+        );
+        object.insert(keyword.to_string(), Value::Array(variants));
+    }
+}
+
+fn is_valid_property_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_nova_translation_rewrites_schemars_output() {
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "SearchInput",
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "limit": { "type": ["integer", "null"], "format": "uint32" },
+                "sort": { "$ref": "#/definitions/Sort" },
+                "filter": {
+                    "anyOf": [{ "$ref": "#/definitions/Filter" }, { "type": "null" }]
+                }
+            },
+            "required": ["query"],
+            "definitions": {
+                "Sort": {
+                    "oneOf": [
+                        { "type": "string", "enum": ["asc"] },
+                        { "type": "string", "const": "desc" }
+                    ]
+                },
+                "Filter": {
+                    "type": "object",
+                    "properties": { "tag": { "type": "string" } },
+                    "additionalProperties": false
+                }
+            }
+        });
+
+        let translation = translate_tool_schema(SchemaDialect::Nova, &schema);
+        assert!(translation.is_supported(), "{:?}", translation.issues);
+        assert_eq!(
+            translation.schema,
+            json!({
+                "title": "SearchInput",
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "format": "uint32" },
+                    "sort": { "type": "string", "enum": ["asc", "desc"] },
+                    "filter": {
+                        "type": "object",
+                        "properties": { "tag": { "type": "string" } }
+                    }
+                },
+                "required": ["query"]
+            })
+        );
+
+        let unchanged = translate_tool_schema(SchemaDialect::JsonSchema, &schema);
+        assert_eq!(unchanged.schema, schema);
+        assert!(unchanged.adaptations.is_empty());
+    }
+
+    #[test]
+    fn test_nova_translation_reports_unsupported_constructs() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "value": { "anyOf": [{ "type": "string" }, { "type": "integer" }] },
+                "user-id": { "type": "string" },
+                "node": { "$ref": "#/$defs/Node" }
+            },
+            "$defs": {
+                "Node": {
+                    "type": "object",
+                    "properties": { "child": { "$ref": "#/$defs/Node" } }
+                }
+            }
+        });
+
+        let translation = translate_tool_schema(SchemaDialect::Nova, &schema);
+        let issues: Vec<String> = translation.issues.iter().map(|i| i.path.clone()).collect();
+        assert_eq!(
+            issues,
+            vec![
+                "$.properties.node.properties.child.$ref",
+                "$.properties.user-id",
+                "$.properties.value.anyOf",
+            ]
+        );
+
+        let scalar = translate_tool_schema(SchemaDialect::Nova, &json!({ "type": "string" }));
+        assert_eq!(
+            scalar.issues[0].to_string(),
+            "$: root schema must have `type: object`"
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! This module defines the fundamental traits that enable a unified interface
 //! across multiple LLM providers while maintaining type safety and performance.
 
+use crate::types::tools::ToolChoice;
 use crate::types::Messages;
 use async_trait::async_trait;
 use futures::Stream;
@@ -91,6 +92,19 @@ pub trait LlmProvider: Send + Sync + std::fmt::Debug {
         config: &ChatConfig,
     ) -> Result<Box<dyn Stream<Item = StreamEvent> + Send + Unpin>, LlmError>;
 
+    /// Count the input tokens of a request with the provider's count-tokens API
+    ///
+    /// Returns `Ok(None)` when the provider or model cannot count tokens, so
+    /// callers fall back to a local estimate.
+    async fn count_tokens(
+        &self,
+        _model_id: &str,
+        _messages: &Messages,
+        _tools: &[Tool],
+    ) -> Result<Option<u32>, LlmError> {
+        Ok(None)
+    }
+
     /// Health check
     async fn health_check(&self) -> Result<HealthStatus, LlmError>;
 
@@ -223,6 +237,12 @@ pub struct ChatConfig {
     /// See [`CacheStrategy`] for available options.
     #[serde(default)]
     pub cache_strategy: CacheStrategy,
+    /// How the model should choose among the provided tools
+    ///
+    /// Ignored when no tools are provided. Providers without forced tool choice
+    /// fall back to the closest supported option.
+    #[serde(default)]
+    pub tool_choice: ToolChoice,
     /// Additional model-specific parameters
     #[serde(default)]
     pub additional_params: HashMap<String, serde_json::Value>,
@@ -238,6 +258,7 @@ impl ChatConfig {
             max_tokens: agent_config.max_tokens,
             enable_thinking: agent_config.enable_thinking,
             cache_strategy: agent_config.cache_strategy.clone(),
+            tool_choice: ToolChoice::Auto,
             additional_params: agent_config.additional_params.clone(),
         }
     }
@@ -265,6 +286,10 @@ pub struct ChatResponse {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+/// [`ChatResponse::metadata`] key for the latency the provider reports for
+/// the call, in milliseconds
+pub const PROVIDER_LATENCY_METADATA_KEY: &str = "provider_latency_ms";
+
 /// Tool call request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCall {
@@ -331,7 +356,12 @@ pub enum StreamEvent {
     /// Message stops
     MessageStop { stop_reason: Option<String> },
     /// Stream metadata (usage, etc.)
-    Metadata { usage: Option<Usage> },
+    Metadata {
+        usage: Option<Usage>,
+        /// Latency the provider reports for the whole call, in milliseconds
+        #[serde(default)]
+        latency_ms: Option<u64>,
+    },
     /// Error in stream
     Error { error: String },
 
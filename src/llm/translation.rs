@@ -0,0 +1,633 @@
+//! Conversation translation between provider message formats.
+//!
+//! A session keeps its history as [`Messages`], which can hold everything any
+//! provider returns: reasoning with its signature, tool calls in the order the
+//! model made them and several content parts per message. Each provider
+//! family has a [`MessageFormat`] that serializes that history into its wire
+//! format for a request and deserializes a wire conversation back, so a
+//! session can switch between Bedrock and an OpenAI-compatible provider
+//! mid-conversation without losing the turns already made.
+//!
+//! | Format | Providers |
+//! |---|---|
+//! | [`AnthropicFormat`] | Bedrock (Claude), Anthropic |
+//! | [`OpenAiFormat`] | LM Studio, OpenAI, Ollama, OpenRouter, Candle |
+//!
+//! Some details cannot be expressed by every wire format. They stay in the
+//! session history and are sent again to providers that support them:
+//!
+//! - Claude only accepts reasoning with a signature, so unsigned reasoning
+//!   (from OpenAI-compatible models or the think tool) is not sent to it.
+//! - OpenAI-compatible requests carry reasoning as `reasoning_content` only
+//!   with [`OpenAiFormat::with_reasoning`], since some servers reject it. They
+//!   have no reasoning signatures, no error flag on tool results and only
+//!   text tool results.
+//! - JSON tool results are sent as text and come back as text.
+//!
+//! Tool results are always placed right after the call they answer: OpenAI
+//! tool messages come before any text of the same user turn, and consecutive
+//! tool messages come back as one user turn, as Claude expects.
+//!
+//! ```
+//! use stood::llm::translation::{AnthropicFormat, MessageFormat, OpenAiFormat};
+//! use stood::types::{Message, Messages};
+//!
+//! # fn example() -> Result<(), stood::llm::LlmError> {
+//! let mut messages = Messages::with_system_prompt("Be brief".to_string());
+//! messages.push(Message::user("Hello"));
+//!
+//! let openai = OpenAiFormat::new().serialize(&messages)?;
+//! let claude = AnthropicFormat.serialize(&OpenAiFormat::new().deserialize(&openai)?)?;
+//! assert_eq!(claude.system.as_deref(), Some("Be brief"));
+//! # Ok(())
+//! # }
+//! ```
+
+use serde_json::{json, Map, Value};
+
+use crate::llm::traits::{LlmError, ProviderType};
+use crate::types::{
+    ContentBlock, Message, MessageRole, Messages, ReasoningContentBlock, ToolResultContent,
+};
+
+/// A conversation in a provider's wire format
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WireMessages {
+    /// Top-level system prompt, for formats that keep it outside the messages
+    pub system: Option<String>,
+    /// The messages of the request body
+    pub messages: Vec<Value>,
+}
+
+/// Serializer and deserializer of a provider family's message format
+pub trait MessageFormat: Send + Sync {
+    /// Short name of the format, for logs and errors
+    fn name(&self) -> &'static str;
+
+    /// The conversation in this format, ready for a request body
+    fn serialize(&self, messages: &Messages) -> Result<WireMessages, LlmError>;
+
+    /// A conversation in this format as [`Messages`]
+    fn deserialize(&self, wire: &WireMessages) -> Result<Messages, LlmError>;
+}
+
+/// The message format of `provider`
+pub fn format_for(provider: ProviderType) -> Box<dyn MessageFormat> {
+    match provider {
+        ProviderType::Bedrock | ProviderType::Anthropic => Box::new(AnthropicFormat),
+        ProviderType::LmStudio
+        | ProviderType::OpenAI
+        | ProviderType::Ollama
+        | ProviderType::OpenRouter
+        | ProviderType::Candle => Box::new(OpenAiFormat::new()),
+    }
+}
+
+/// Convert a wire conversation from one format to another
+pub fn translate(
+    wire: &WireMessages,
+    from: &dyn MessageFormat,
+    to: &dyn MessageFormat,
+) -> Result<WireMessages, LlmError> {
+    to.serialize(&from.deserialize(wire)?)
+}
+
+/// Anthropic Messages format, used by Claude on Bedrock and the Anthropic API
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnthropicFormat;
+
+impl MessageFormat for AnthropicFormat {
+    fn name(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn serialize(&self, messages: &Messages) -> Result<WireMessages, LlmError> {
+        let mut wire = WireMessages {
+            system: messages.system_prompt.clone(),
+            messages: Vec::new(),
+        };
+
+        for message in &messages.messages {
+            let role = match message.role {
+                MessageRole::System => {
+                    // Fallback when the system prompt is not set on `messages`
+                    if wire.system.is_none() {
+                        let text = message
+                            .content
+                            .iter()
+                            .filter_map(|block| match block {
+                                ContentBlock::Text { text } => Some(text.as_str()),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        wire.system = Some(text).filter(|text| !text.is_empty());
+                    }
+                    continue;
+                }
+                MessageRole::User => "user",
+                MessageRole::Assistant => "assistant",
+            };
+
+            let content: Vec<Value> = message.content.iter().filter_map(anthropic_block).collect();
+            if !content.is_empty() {
+                wire.messages
+                    .push(json!({ "role": role, "content": content }));
+            }
+        }
+
+        Ok(wire)
+    }
+
+    fn deserialize(&self, wire: &WireMessages) -> Result<Messages, LlmError> {
+        let mut messages = Messages::new();
+        messages.system_prompt = wire.system.clone();
+
+        for message in &wire.messages {
+            let role = match message["role"].as_str() {
+                Some("user") => MessageRole::User,
+                Some("assistant") => MessageRole::Assistant,
+                other => return Err(invalid(self, format!("unknown role {:?}", other))),
+            };
+            let content = match &message["content"] {
+                Value::String(text) => vec![ContentBlock::text(text)],
+                Value::Array(blocks) => blocks
+                    .iter()
+                    .map(|block| self.block_from_wire(block))
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(invalid(self, "message without content")),
+            };
+            messages.push(Message::new(role, content));
+        }
+
+        Ok(messages)
+    }
+}
+
+impl AnthropicFormat {
+    fn block_from_wire(&self, block: &Value) -> Result<ContentBlock, LlmError> {
+        match block["type"].as_str() {
+            Some("text") => Ok(ContentBlock::text(str_field(self, block, "text")?)),
+            Some("thinking") => Ok(ContentBlock::ReasoningContent {
+                reasoning: ReasoningContentBlock::new(
+                    str_field(self, block, "thinking")?.to_string(),
+                    block["signature"].as_str().map(str::to_string),
+                ),
+            }),
+            Some("tool_use") => Ok(ContentBlock::ToolUse {
+                id: str_field(self, block, "id")?.to_string(),
+                name: str_field(self, block, "name")?.to_string(),
+                input: block["input"].clone(),
+            }),
+            Some("tool_result") => Ok(ContentBlock::ToolResult {
+                tool_use_id: str_field(self, block, "tool_use_id")?.to_string(),
+                content: match &block["content"] {
+                    Value::String(text) => ToolResultContent::text(text),
+                    Value::Array(parts) => {
+                        let mut parts: Vec<ToolResultContent> = parts
+                            .iter()
+                            .map(|part| self.tool_result_part(part))
+                            .collect::<Result<_, _>>()?;
+                        if parts.len() == 1 {
+                            parts.remove(0)
+                        } else {
+                            ToolResultContent::Multiple { blocks: parts }
+                        }
+                    }
+                    _ => ToolResultContent::text(""),
+                },
+                is_error: block["is_error"].as_bool().unwrap_or(false),
+            }),
+            other => Err(invalid(self, format!("unsupported block type {:?}", other))),
+        }
+    }
+
+    fn tool_result_part(&self, part: &Value) -> Result<ToolResultContent, LlmError> {
+        match part["type"].as_str() {
+            Some("text") => Ok(ToolResultContent::text(str_field(self, part, "text")?)),
+            Some("image") => Ok(ToolResultContent::Binary {
+                data: str_field(self, &part["source"], "data")?.to_string(),
+                mime_type: str_field(self, &part["source"], "media_type")?.to_string(),
+            }),
+            other => Err(invalid(
+                self,
+                format!("unsupported tool result part {:?}", other),
+            )),
+        }
+    }
+}
+
+/// A content block as sent to Claude; `None` for blocks Claude does not accept
+fn anthropic_block(block: &ContentBlock) -> Option<Value> {
+    match block {
+        ContentBlock::Text { text } => Some(json!({ "type": "text", "text": text })),
+        ContentBlock::ToolUse { id, name, input } => Some(json!({
+            "type": "tool_use",
+            "id": id,
+            "name": name,
+            "input": object_or_empty(input),
+        })),
+        ContentBlock::ToolResult {
+            tool_use_id,
+            content,
+            is_error,
+        } => {
+            let mut parts = Vec::new();
+            anthropic_tool_result_parts(content, &mut parts);
+            Some(json!({
+                "type": "tool_result",
+                "tool_use_id": tool_use_id,
+                "content": parts,
+                "is_error": is_error,
+            }))
+        }
+        ContentBlock::ReasoningContent { reasoning } => {
+            let signature = reasoning.reasoning_text.signature.as_ref()?;
+            Some(json!({
+                "type": "thinking",
+                "thinking": reasoning.text(),
+                "signature": signature,
+            }))
+        }
+        ContentBlock::Thinking { .. } => None,
+    }
+}
+
+fn anthropic_tool_result_parts(content: &ToolResultContent, parts: &mut Vec<Value>) {
+    match content {
+        ToolResultContent::Multiple { blocks } => {
+            for block in blocks {
+                anthropic_tool_result_parts(block, parts);
+            }
+        }
+        ToolResultContent::Binary { data, mime_type } if mime_type.starts_with("image/") => {
+            parts.push(json!({
+                "type": "image",
+                "source": { "type": "base64", "media_type": mime_type, "data": data },
+            }));
+        }
+        other => parts.push(json!({ "type": "text", "text": other.to_display_string() })),
+    }
+}
+
+/// OpenAI chat completions format, used by OpenAI-compatible providers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenAiFormat {
+    include_reasoning: bool,
+}
+
+impl OpenAiFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send reasoning of assistant turns as `reasoning_content`
+    ///
+    /// Off by default, since some OpenAI-compatible servers reject requests
+    /// carrying it. Reasoning is always read when deserializing.
+    pub fn with_reasoning(mut self, include_reasoning: bool) -> Self {
+        self.include_reasoning = include_reasoning;
+        self
+    }
+}
+
+impl MessageFormat for OpenAiFormat {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn serialize(&self, messages: &Messages) -> Result<WireMessages, LlmError> {
+        let mut wire = Vec::new();
+        if let Some(system) = &messages.system_prompt {
+            wire.push(json!({ "role": "system", "content": system }));
+        }
+
+        for message in &messages.messages {
+            let mut texts = Vec::new();
+            let mut reasoning = Vec::new();
+            let mut tool_calls = Vec::new();
+
+            for block in &message.content {
+                match block {
+                    ContentBlock::Text { text } => texts.push(text.as_str()),
+                    ContentBlock::ToolUse { id, name, input } => tool_calls.push(json!({
+                        "id": id,
+                        "type": "function",
+                        "function": {
+                            "name": name,
+                            "arguments": object_or_empty(input).to_string(),
+                        },
+                    })),
+                    // Tool messages must directly follow the assistant's calls
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        ..
+                    } => wire.push(json!({
+                        "role": "tool",
+                        "tool_call_id": tool_use_id,
+                        "content": content.to_display_string(),
+                    })),
+                    ContentBlock::ReasoningContent { reasoning: block } => {
+                        reasoning.push(block.text())
+                    }
+                    ContentBlock::Thinking { content, .. } => reasoning.push(content.as_str()),
+                }
+            }
+
+            let role = match message.role {
+                MessageRole::User => "user",
+                MessageRole::Assistant => "assistant",
+                MessageRole::System => "system",
+            };
+            let mut message_json = json!({ "role": role, "content": openai_content(&texts) });
+            if !tool_calls.is_empty() {
+                message_json["tool_calls"] = Value::Array(tool_calls);
+            }
+            let has_reasoning = self.include_reasoning && !reasoning.is_empty();
+            if has_reasoning {
+                message_json["reasoning_content"] = json!(reasoning.join("\n\n"));
+            }
+            if !texts.is_empty() || message_json.get("tool_calls").is_some() || has_reasoning {
+                wire.push(message_json);
+            }
+        }
+
+        Ok(WireMessages {
+            system: None,
+            messages: wire,
+        })
+    }
+
+    fn deserialize(&self, wire: &WireMessages) -> Result<Messages, LlmError> {
+        let mut messages = Messages::new();
+        messages.system_prompt = wire.system.clone();
+        // User turn collecting tool messages and the user text following them
+        let mut tool_turn: Option<Message> = None;
+
+        for (index, message) in wire.messages.iter().enumerate() {
+            let role = message["role"].as_str().unwrap_or_default();
+            if role == "tool" {
+                let content = self.text_parts(&message["content"])?.join("\n");
+                tool_turn
+                    .get_or_insert_with(|| Message::new(MessageRole::User, Vec::new()))
+                    .content
+                    .push(ContentBlock::ToolResult {
+                        tool_use_id: str_field(self, message, "tool_call_id")?.to_string(),
+                        content: ToolResultContent::text(content),
+                        is_error: false,
+                    });
+                continue;
+            }
+
+            let texts = self
+                .text_parts(&message["content"])?
+                .into_iter()
+                .map(ContentBlock::text);
+            match role {
+                "system" if index == 0 && messages.system_prompt.is_none() => {
+                    messages.system_prompt = Some(self.text_parts(&message["content"])?.join("\n"));
+                }
+                "system" => {
+                    messages.extend(tool_turn.take());
+                    messages.push(Message::new(MessageRole::System, texts.collect()));
+                }
+                "user" => {
+                    let mut turn = tool_turn
+                        .take()
+                        .unwrap_or_else(|| Message::new(MessageRole::User, Vec::new()));
+                    turn.content.extend(texts);
+                    messages.push(turn);
+                }
+                "assistant" => {
+                    messages.extend(tool_turn.take());
+                    let mut content = Vec::new();
+                    let reasoning = message
+                        .get("reasoning_content")
+                        .or_else(|| message.get("reasoning"))
+                        .and_then(Value::as_str)
+                        .filter(|reasoning| !reasoning.is_empty());
+                    if let Some(reasoning) = reasoning {
+                        content.push(ContentBlock::ReasoningContent {
+                            reasoning: ReasoningContentBlock::new(reasoning.to_string(), None),
+                        });
+                    }
+                    content.extend(texts);
+                    for call in message["tool_calls"].as_array().into_iter().flatten() {
+                        content.push(ContentBlock::ToolUse {
+                            id: str_field(self, call, "id")?.to_string(),
+                            name: str_field(self, &call["function"], "name")?.to_string(),
+                            input: tool_arguments(&call["function"]["arguments"]),
+                        });
+                    }
+                    messages.push(Message::new(MessageRole::Assistant, content));
+                }
+                other => return Err(invalid(self, format!("unknown role {:?}", other))),
+            }
+        }
+        messages.extend(tool_turn);
+
+        Ok(messages)
+    }
+}
+
+impl OpenAiFormat {
+    fn text_parts(&self, content: &Value) -> Result<Vec<String>, LlmError> {
+        match content {
+            Value::Null => Ok(Vec::new()),
+            Value::String(text) => Ok(vec![text.clone()]),
+            Value::Array(parts) => parts
+                .iter()
+                .map(|part| match part["type"].as_str() {
+                    Some("text") => Ok(str_field(self, part, "text")?.to_string()),
+                    other => Err(invalid(
+                        self,
+                        format!("unsupported content part {:?}", other),
+                    )),
+                })
+                .collect(),
+            _ => Err(invalid(self, "content is neither text nor parts")),
+        }
+    }
+}
+
+/// A single text as a string, several as content parts
+fn openai_content(texts: &[&str]) -> Value {
+    match texts {
+        [] => Value::Null,
+        [text] => json!(text),
+        texts => Value::Array(
+            texts
+                .iter()
+                .map(|text| json!({ "type": "text", "text": text }))
+                .collect(),
+        ),
+    }
+}
+
+/// Tool arguments, which servers send as a JSON string or an object
+fn tool_arguments(arguments: &Value) -> Value {
+    match arguments {
+        Value::String(text) => {
+            serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.clone()))
+        }
+        Value::Null => Value::Object(Map::new()),
+        other => other.clone(),
+    }
+}
+
+/// Providers require tool input to be an object
+fn object_or_empty(input: &Value) -> Value {
+    if input.is_object() {
+        input.clone()
+    } else {
+        Value::Object(Map::new())
+    }
+}
+
+fn str_field<'a>(
+    format: &dyn MessageFormat,
+    value: &'a Value,
+    field: &str,
+) -> Result<&'a str, LlmError> {
+    value[field]
+        .as_str()
+        .ok_or_else(|| invalid(format, format!("missing string field '{}'", field)))
+}
+
+fn invalid(format: &dyn MessageFormat, problem: impl std::fmt::Display) -> LlmError {
+    LlmError::SerializationError {
+        message: format!("Invalid {} conversation: {}", format.name(), problem),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(signature: Option<&str>) -> Messages {
+        let mut messages = Messages::with_system_prompt("You are a weather bot".to_string());
+        messages.push(Message::new(
+            MessageRole::User,
+            vec![
+                ContentBlock::text("Weather in Paris?"),
+                ContentBlock::text("And in Rome?"),
+            ],
+        ));
+        messages.push(Message::new(
+            MessageRole::Assistant,
+            vec![
+                ContentBlock::ReasoningContent {
+                    reasoning: ReasoningContentBlock::new(
+                        "Two cities, two calls".to_string(),
+                        signature.map(str::to_string),
+                    ),
+                },
+                ContentBlock::text("Checking both."),
+                ContentBlock::ToolUse {
+                    id: "call_1".to_string(),
+                    name: "weather".to_string(),
+                    input: json!({"city": "Paris"}),
+                },
+                ContentBlock::ToolUse {
+                    id: "call_2".to_string(),
+                    name: "weather".to_string(),
+                    input: json!({"city": "Rome"}),
+                },
+            ],
+        ));
+        messages.push(Message::new(
+            MessageRole::User,
+            vec![
+                ContentBlock::ToolResult {
+                    tool_use_id: "call_1".to_string(),
+                    content: ToolResultContent::text("sunny"),
+                    is_error: false,
+                },
+                ContentBlock::ToolResult {
+                    tool_use_id: "call_2".to_string(),
+                    content: ToolResultContent::text("rain"),
+                    is_error: false,
+                },
+                ContentBlock::text("Also give a tip."),
+            ],
+        ));
+        messages.push(Message::assistant(
+            "Paris is sunny; bring an umbrella in Rome.",
+        ));
+        messages
+    }
+
+    fn turns(messages: &Messages) -> Vec<(MessageRole, Vec<ContentBlock>)> {
+        messages
+            .messages
+            .iter()
+            .map(|message| (message.role.clone(), message.content.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_anthropic_round_trip() {
+        let messages = conversation(Some("sig"));
+        let wire = AnthropicFormat.serialize(&messages).unwrap();
+        assert_eq!(wire.system.as_deref(), Some("You are a weather bot"));
+        assert_eq!(wire.messages[1]["content"][0]["type"], "thinking");
+
+        let restored = AnthropicFormat.deserialize(&wire).unwrap();
+        assert_eq!(restored.system_prompt, messages.system_prompt);
+        assert_eq!(turns(&restored), turns(&messages));
+    }
+
+    #[test]
+    fn test_openai_round_trip() {
+        let messages = conversation(None);
+        let format = OpenAiFormat::new().with_reasoning(true);
+        let wire = format.serialize(&messages).unwrap();
+
+        let roles: Vec<_> = wire
+            .messages
+            .iter()
+            .map(|message| message["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            roles,
+            [
+                "system",
+                "user",
+                "assistant",
+                "tool",
+                "tool",
+                "user",
+                "assistant"
+            ]
+        );
+        assert_eq!(wire.messages[1]["content"][1]["text"], "And in Rome?");
+        assert_eq!(wire.messages[2]["tool_calls"][1]["id"], "call_2");
+
+        let restored = format.deserialize(&wire).unwrap();
+        assert_eq!(restored.system_prompt, messages.system_prompt);
+        assert_eq!(turns(&restored), turns(&messages));
+    }
+
+    #[test]
+    fn test_switching_providers_keeps_tool_pairs() {
+        let wire = OpenAiFormat::new().serialize(&conversation(None)).unwrap();
+        assert!(wire.messages[2].get("reasoning_content").is_none());
+
+        let claude = translate(&wire, &OpenAiFormat::new(), &AnthropicFormat).unwrap();
+        let blocks = |index: usize| -> Vec<&str> {
+            claude.messages[index]["content"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|block| block["type"].as_str().unwrap())
+                .collect()
+        };
+        assert_eq!(blocks(1), ["text", "tool_use", "tool_use"]);
+        assert_eq!(blocks(2), ["tool_result", "tool_result", "text"]);
+        assert_eq!(claude.messages[2]["content"][1]["tool_use_id"], "call_2");
+
+        let back = translate(&claude, &AnthropicFormat, &OpenAiFormat::new()).unwrap();
+        assert_eq!(back, wire);
+    }
+}
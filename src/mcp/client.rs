@@ -57,11 +57,13 @@
 //! - Memory usage: ~1KB per active session plus message buffers
 
 use crate::mcp::error::MCPOperationError;
+use crate::mcp::health::{reconnect_backoff, MCPConnectionStatus, MCPHealth, MCPHealthEventKind};
 use crate::mcp::transport::{MCPTransport, TransportStreams};
 use crate::mcp::types::{
-    ClientCapabilities, Content, MCPMessage, MCPNotification, MCPRequest, MCPResponse,
-    MCPResponsePayload, ServerCapabilities, Tool,
+    ClientCapabilities, Content, ElicitationCapability, MCPMessage, MCPNotification, MCPRequest,
+    MCPResponse, MCPResponsePayload, ServerCapabilities, Tool,
 };
+use crate::tools::elicitation::{ElicitationRequest, ElicitationResponse, Elicitor};
 use futures::{SinkExt, StreamExt};
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -89,8 +91,16 @@ pub struct MCPClientConfig {
     pub max_concurrent_requests: usize,
     /// Enable automatic reconnection on connection loss
     pub auto_reconnect: bool,
-    /// Reconnection delay in milliseconds
+    /// Reconnection delay in milliseconds (doubled after each failed attempt)
     pub reconnect_delay_ms: u64,
+    /// Upper bound for the reconnection delay in milliseconds
+    pub max_reconnect_delay_ms: u64,
+    /// Reconnection attempts before giving up (0 retries forever)
+    pub max_reconnect_attempts: u32,
+    /// Interval between keepalive pings in milliseconds (`None` disables health checks)
+    pub keepalive_interval_ms: Option<u64>,
+    /// How long to wait for a keepalive response in milliseconds
+    pub keepalive_timeout_ms: u64,
 }
 
 impl Default for MCPClientConfig {
@@ -102,7 +112,11 @@ impl Default for MCPClientConfig {
             request_timeout_ms: 30_000, // 30 seconds
             max_concurrent_requests: 100,
             auto_reconnect: true,
-            reconnect_delay_ms: 5_000, // 5 seconds
+            reconnect_delay_ms: 5_000,      // 5 seconds
+            max_reconnect_delay_ms: 60_000, // 1 minute
+            max_reconnect_attempts: 10,
+            keepalive_interval_ms: Some(30_000), // 30 seconds
+            keepalive_timeout_ms: 10_000,        // 10 seconds
         }
     }
 }
@@ -196,6 +210,10 @@ pub struct MCPClient {
     shutdown_tx: Arc<Mutex<Option<mpsc::UnboundedSender<()>>>>,
     /// Write channel for sending messages
     write_tx: Arc<Mutex<Option<mpsc::UnboundedSender<MCPMessage>>>>,
+    /// Connection health shared with tool adapters and the health monitor
+    health: Arc<MCPHealth>,
+    /// Answers the server's `elicitation/create` requests
+    elicitor: Option<Arc<dyn Elicitor>>,
 }
 
 impl MCPClient {
@@ -225,9 +243,11 @@ impl MCPClient {
     /// let client = MCPClient::new(config, transport);
     /// ```
     pub fn new(config: MCPClientConfig, transport: Box<dyn MCPTransport>) -> Self {
+        let health = Arc::new(MCPHealth::new(transport.transport_info().endpoint));
         Self {
             config,
             transport,
+            health,
             session: Arc::new(RwLock::new(MCPSession::new())),
             request_id_counter: AtomicI64::new(1),
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
@@ -235,9 +255,22 @@ impl MCPClient {
             background_handle: Arc::new(Mutex::new(None)),
             shutdown_tx: Arc::new(Mutex::new(None)),
             write_tx: Arc::new(Mutex::new(None)),
+            elicitor: None,
         }
     }
 
+    /// Answer the server's requests for user input with `elicitor`
+    ///
+    /// Advertises the elicitation capability, so it must be set before
+    /// [`connect`](Self::connect). Each `elicitation/create` request is passed to
+    /// the elicitor with the server's name; without one the client does not
+    /// advertise the capability and servers should not ask.
+    pub fn with_elicitor(mut self, elicitor: Arc<dyn Elicitor>) -> Self {
+        self.config.client_capabilities.elicitation = Some(ElicitationCapability {});
+        self.elicitor = Some(elicitor);
+        self
+    }
+
     /// Connect to the MCP server and establish a session
     ///
     /// This method establishes the underlying transport connection, performs the MCP
@@ -293,6 +326,8 @@ impl MCPClient {
         // Perform MCP handshake
         self.initialize_session().await?;
 
+        self.health.set_status(MCPConnectionStatus::Connected);
+
         Ok(())
     }
 
@@ -326,6 +361,7 @@ impl MCPClient {
 
         // Mark as disconnected
         self.is_connected.store(false, Ordering::Relaxed);
+        self.health.set_status(MCPConnectionStatus::Disconnected);
 
         Ok(())
     }
@@ -335,6 +371,155 @@ impl MCPClient {
         self.is_connected.load(Ordering::Relaxed)
     }
 
+    /// Get the client's connection health handle
+    ///
+    /// The handle can be queried without locking the client, and listeners added
+    /// to it receive keepalive and reconnection events. See [`crate::mcp::health`].
+    pub fn health(&self) -> Arc<MCPHealth> {
+        self.health.clone()
+    }
+
+    /// Send a keepalive ping and wait for the server's response
+    ///
+    /// Any response counts as alive, including an error from servers that do
+    /// not implement `ping`.
+    pub async fn ping(&mut self) -> Result<(), MCPOperationError> {
+        let timeout = Duration::from_millis(self.config.keepalive_timeout_ms);
+        match tokio::time::timeout(timeout, self.send_request("ping", None)).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(MCPOperationError::timeout(timeout)),
+        }
+    }
+
+    /// Tear down the current connection (if any) and establish a new session
+    ///
+    /// For stdio servers this restarts the server process.
+    pub async fn reconnect(&mut self) -> Result<(), MCPOperationError> {
+        info!("Reconnecting to MCP server...");
+
+        // Clean up unconditionally; the connection may already be half-closed
+        if let Some(shutdown_tx) = self.shutdown_tx.lock().await.take() {
+            let _ = shutdown_tx.send(());
+        }
+        if let Some(handle) = self.background_handle.lock().await.take() {
+            handle.abort();
+        }
+        self.write_tx.lock().await.take();
+        if let Err(e) = self.transport.disconnect().await {
+            debug!(
+                "Ignoring transport disconnect error during reconnect: {}",
+                e
+            );
+        }
+        self.is_connected.store(false, Ordering::Relaxed);
+        *self.session.write().await = MCPSession::new();
+
+        self.connect().await
+    }
+
+    /// Start a background task that pings the server and reconnects on failure
+    ///
+    /// Pings are sent every [`MCPClientConfig::keepalive_interval_ms`]. When a ping
+    /// fails, the client's [`health`](Self::health) becomes
+    /// [`Reconnecting`](MCPConnectionStatus::Reconnecting) and, if
+    /// [`MCPClientConfig::auto_reconnect`] is set, reconnection is attempted with
+    /// exponential backoff. The task ends when the client is dropped or
+    /// reconnection is abandoned. Returns `None` if keepalive is disabled.
+    pub async fn start_health_monitor(
+        client: Arc<RwLock<MCPClient>>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let (config, health) = {
+            let client = client.read().await;
+            (client.config.clone(), client.health())
+        };
+        let interval = Duration::from_millis(config.keepalive_interval_ms?);
+        let weak = Arc::downgrade(&client);
+        drop(client);
+
+        Some(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(client) = weak.upgrade() else {
+                    return;
+                };
+
+                let check = {
+                    let mut client = client.write().await;
+                    if client.is_connected() {
+                        client.ping().await
+                    } else {
+                        Err(MCPOperationError::connection_lost("Connection closed"))
+                    }
+                };
+                drop(client);
+                let reason = match check {
+                    Ok(()) => continue,
+                    Err(e) => e.to_string(),
+                };
+
+                warn!("MCP server '{}' is unhealthy: {}", health.server(), reason);
+                health.emit(MCPHealthEventKind::ConnectionLost { reason });
+                if !config.auto_reconnect {
+                    health.set_status(MCPConnectionStatus::Disconnected);
+                    return;
+                }
+                health.set_status(MCPConnectionStatus::Reconnecting);
+
+                let down_since = std::time::Instant::now();
+                let mut attempt = 0;
+                loop {
+                    attempt += 1;
+                    let delay = reconnect_backoff(
+                        attempt,
+                        Duration::from_millis(config.reconnect_delay_ms),
+                        Duration::from_millis(config.max_reconnect_delay_ms),
+                    );
+                    health.emit(MCPHealthEventKind::Reconnecting { attempt, delay });
+                    tokio::time::sleep(delay).await;
+
+                    let Some(client) = weak.upgrade() else {
+                        return;
+                    };
+                    let result = client.write().await.reconnect().await;
+                    match result {
+                        Ok(()) => {
+                            info!(
+                                "Reconnected to MCP server '{}' after {} attempt(s)",
+                                health.server(),
+                                attempt
+                            );
+                            health.emit(MCPHealthEventKind::Reconnected {
+                                attempts: attempt,
+                                downtime: down_since.elapsed(),
+                            });
+                            break;
+                        }
+                        Err(e)
+                            if config.max_reconnect_attempts != 0
+                                && attempt >= config.max_reconnect_attempts =>
+                        {
+                            error!(
+                                "Giving up reconnecting to MCP server '{}': {}",
+                                health.server(),
+                                e
+                            );
+                            health.set_status(MCPConnectionStatus::Disconnected);
+                            health.emit(MCPHealthEventKind::ReconnectFailed {
+                                attempts: attempt,
+                                last_error: e.to_string(),
+                            });
+                            return;
+                        }
+                        Err(e) => {
+                            warn!("Reconnection attempt {} failed: {}", attempt, e);
+                        }
+                    }
+                }
+            }
+        }))
+    }
+
     /// Start the background message handler
     async fn start_message_handler(
         &mut self,
@@ -368,6 +553,7 @@ impl MCPClient {
         let pending_requests = self.pending_requests.clone();
         let session = self.session.clone();
         let is_connected = self.is_connected.clone();
+        let elicitor = self.elicitor.clone();
 
         // Spawn read task
         let handle = tokio::spawn(async move {
@@ -377,7 +563,14 @@ impl MCPClient {
                     msg = read_stream.next() => {
                         match msg {
                             Some(Ok(message)) => {
-                                Self::handle_message(message, &pending_requests, &session).await;
+                                Self::handle_message(
+                                    message,
+                                    &pending_requests,
+                                    &session,
+                                    elicitor.as_ref(),
+                                    &write_tx,
+                                )
+                                .await;
                             }
                             Some(Err(e)) => {
                                 error!("Error reading message: {}", e);
@@ -423,6 +616,8 @@ impl MCPClient {
         message: MCPMessage,
         pending_requests: &Arc<Mutex<HashMap<Value, PendingRequest>>>,
         session: &Arc<RwLock<MCPSession>>,
+        elicitor: Option<&Arc<dyn Elicitor>>,
+        write_tx: &mpsc::UnboundedSender<MCPMessage>,
     ) {
         match message {
             MCPMessage::Response(response) => {
@@ -435,8 +630,21 @@ impl MCPClient {
                     warn!("Received response for unknown request ID: {:?}", id);
                 }
             }
+            MCPMessage::Request(request) if request.method == "elicitation/create" => {
+                let Some(elicitor) = elicitor.cloned() else {
+                    warn!("Received elicitation request without an elicitor");
+                    return;
+                };
+                let server = session.read().await.server_name.clone();
+                let write_tx = write_tx.clone();
+                // Waiting for the user must not block responses to other requests
+                tokio::spawn(async move {
+                    let response = Self::answer_elicitation(request, server, elicitor).await;
+                    let _ = write_tx.send(MCPMessage::Response(response));
+                });
+            }
             MCPMessage::Request(request) => {
-                // MCP servers shouldn't send requests to clients in the current protocol
+                // Elicitation is the only server request the client handles
                 warn!(
                     "Received unexpected request from server: {}",
                     request.method
@@ -449,6 +657,39 @@ impl MCPClient {
         }
     }
 
+    /// Ask the user to answer an `elicitation/create` request
+    async fn answer_elicitation(
+        request: MCPRequest,
+        server: Option<String>,
+        elicitor: Arc<dyn Elicitor>,
+    ) -> MCPResponse {
+        let params = request.params.unwrap_or_default();
+        let prompt = params
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let schema = params.get("requestedSchema").cloned().unwrap_or_default();
+
+        let response = elicitor
+            .elicit(ElicitationRequest {
+                tool_name: None,
+                tool_use_id: None,
+                server,
+                prompt,
+                schema,
+            })
+            .await;
+        let result = match response {
+            ElicitationResponse::Accept(content) => {
+                json!({"action": "accept", "content": content})
+            }
+            ElicitationResponse::Decline => json!({"action": "decline"}),
+            ElicitationResponse::Cancel => json!({"action": "cancel"}),
+        };
+        MCPResponse::success(request.id, result)
+    }
+
     /// Handle an incoming notification
     async fn handle_notification(
         notification: MCPNotification,
@@ -887,6 +1128,7 @@ mod tests {
         assert_eq!(config.max_concurrent_requests, 100);
         assert!(config.auto_reconnect);
         assert_eq!(config.reconnect_delay_ms, 5_000);
+        assert_eq!(config.keepalive_interval_ms, Some(30_000));
     }
 
     #[test]
@@ -925,6 +1167,21 @@ mod tests {
         assert!(client.disconnect().await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_health_monitor_requires_keepalive() {
+        let config = MCPClientConfig {
+            keepalive_interval_ms: None,
+            ..Default::default()
+        };
+        let client = MCPClient::new(config, Box::new(MockTransport::new()));
+        let health = client.health();
+        assert_eq!(health.server(), "mock://test");
+        assert_eq!(health.status(), MCPConnectionStatus::Disconnected);
+
+        let client = Arc::new(RwLock::new(client));
+        assert!(MCPClient::start_health_monitor(client).await.is_none());
+    }
+
     #[tokio::test]
     async fn test_session_duration() {
         let config = MCPClientConfig::default();
@@ -938,4 +1195,41 @@ mod tests {
         let duration = client.session_duration().await;
         assert!(duration >= Duration::from_millis(100));
     }
+
+    #[derive(Debug)]
+    struct ShareLocation;
+
+    #[async_trait]
+    impl Elicitor for ShareLocation {
+        async fn elicit(&self, request: ElicitationRequest) -> ElicitationResponse {
+            assert_eq!(request.server.as_deref(), Some("weather"));
+            assert_eq!(request.prompt, "Which city?");
+            ElicitationResponse::Accept(json!({"city": "Porto"}))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_elicitation_request_is_answered() {
+        let client = MCPClient::new(MCPClientConfig::default(), Box::new(MockTransport::new()))
+            .with_elicitor(Arc::new(ShareLocation));
+        assert!(client.config.client_capabilities.elicitation.is_some());
+
+        let request = MCPRequest::new(
+            json!(7),
+            "elicitation/create",
+            Some(json!({"message": "Which city?", "requestedSchema": {"type": "object"}})),
+        );
+        let response = MCPClient::answer_elicitation(
+            request,
+            Some("weather".to_string()),
+            Arc::new(ShareLocation),
+        )
+        .await;
+        assert_eq!(response.id, json!(7));
+        assert!(matches!(
+            response.payload,
+            MCPResponsePayload::Success { result }
+                if result == json!({"action": "accept", "content": {"city": "Porto"}})
+        ));
+    }
 }
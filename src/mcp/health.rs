@@ -0,0 +1,222 @@
+//! Connection health tracking for MCP clients.
+//!
+//! Every [`MCPClient`] owns an [`MCPHealth`] handle that records whether the
+//! connection is usable. The handle can be read without locking the client, which
+//! lets tool adapters report themselves unavailable while the server is down.
+//!
+//! [`MCPClient::start_health_monitor`] spawns a background task that sends
+//! keepalive pings and, when a ping fails or the connection drops, reconnects with
+//! exponential backoff. Each state change is reported to registered listeners as an
+//! [`MCPHealthEvent`]; agents forward these to their callback handler as
+//! [`CallbackEvent::McpHealth`](crate::agent::callbacks::CallbackEvent::McpHealth).
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use stood::mcp::{MCPClient, MCPClientConfig};
+//! use stood::mcp::transport::{StdioConfig, TransportFactory};
+//! use tokio::sync::RwLock;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = MCPClientConfig {
+//!     keepalive_interval_ms: Some(15_000),
+//!     ..Default::default()
+//! };
+//! let transport = TransportFactory::stdio(StdioConfig {
+//!     command: "my-mcp-server".to_string(),
+//!     ..Default::default()
+//! });
+//! let mut client = MCPClient::new(config, transport);
+//! client.connect().await?;
+//!
+//! let health = client.health();
+//! health.add_listener(|event| println!("{}: {:?}", event.server, event.kind));
+//!
+//! let client = Arc::new(RwLock::new(client));
+//! MCPClient::start_health_monitor(client.clone()).await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+#[cfg(doc)]
+use crate::mcp::client::MCPClient;
+
+/// Connection state of an MCP client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MCPConnectionStatus {
+    /// Connected and responding
+    Connected,
+    /// Connection lost; reconnection in progress
+    Reconnecting,
+    /// Not connected and not reconnecting
+    Disconnected,
+}
+
+impl MCPConnectionStatus {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Connected,
+            1 => Self::Reconnecting,
+            _ => Self::Disconnected,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Self::Connected => 0,
+            Self::Reconnecting => 1,
+            Self::Disconnected => 2,
+        }
+    }
+}
+
+/// What happened to an MCP connection
+#[derive(Debug, Clone, PartialEq)]
+pub enum MCPHealthEventKind {
+    /// A keepalive ping failed or the connection closed
+    ConnectionLost { reason: String },
+    /// A reconnection attempt is scheduled after `delay`
+    Reconnecting { attempt: u32, delay: Duration },
+    /// The connection was re-established
+    Reconnected { attempts: u32, downtime: Duration },
+    /// Reconnection was abandoned after `attempts` failures
+    ReconnectFailed { attempts: u32, last_error: String },
+}
+
+/// A health change of an MCP connection
+#[derive(Debug, Clone)]
+pub struct MCPHealthEvent {
+    /// Server endpoint the event refers to
+    pub server: String,
+    /// What happened
+    pub kind: MCPHealthEventKind,
+    /// When it happened
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Listener notified of health events
+pub type MCPHealthListener = Arc<dyn Fn(&MCPHealthEvent) + Send + Sync>;
+
+/// Shared, lock-free view of an MCP connection's health
+pub struct MCPHealth {
+    server: String,
+    status: AtomicU8,
+    listeners: Mutex<Vec<MCPHealthListener>>,
+}
+
+impl std::fmt::Debug for MCPHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MCPHealth")
+            .field("server", &self.server)
+            .field("status", &self.status())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MCPHealth {
+    /// Create a health handle for a server, initially disconnected
+    pub fn new(server: impl Into<String>) -> Self {
+        Self {
+            server: server.into(),
+            status: AtomicU8::new(MCPConnectionStatus::Disconnected.as_u8()),
+            listeners: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Server endpoint this handle tracks
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Current connection status
+    pub fn status(&self) -> MCPConnectionStatus {
+        MCPConnectionStatus::from_u8(self.status.load(Ordering::Relaxed))
+    }
+
+    /// Whether tools on this connection can be called right now
+    pub fn is_available(&self) -> bool {
+        self.status() == MCPConnectionStatus::Connected
+    }
+
+    pub(crate) fn set_status(&self, status: MCPConnectionStatus) {
+        self.status.store(status.as_u8(), Ordering::Relaxed);
+    }
+
+    /// Register a listener for health events
+    pub fn add_listener<F>(&self, listener: F)
+    where
+        F: Fn(&MCPHealthEvent) + Send + Sync + 'static,
+    {
+        self.listeners
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::new(listener));
+    }
+
+    pub(crate) fn emit(&self, kind: MCPHealthEventKind) {
+        let event = MCPHealthEvent {
+            server: self.server.clone(),
+            kind,
+            timestamp: Utc::now(),
+        };
+        // Clone the listeners so a listener can register another without deadlocking
+        let listeners = self
+            .listeners
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        for listener in listeners {
+            listener(&event);
+        }
+    }
+}
+
+/// Delay before reconnection attempt `attempt` (starting at 1), doubling from
+/// `initial` up to `max`
+pub fn reconnect_backoff(attempt: u32, initial: Duration, max: Duration) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    initial.saturating_mul(factor).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let initial = Duration::from_millis(500);
+        let max = Duration::from_secs(10);
+        assert_eq!(reconnect_backoff(1, initial, max), initial);
+        assert_eq!(reconnect_backoff(3, initial, max), Duration::from_secs(2));
+        assert_eq!(reconnect_backoff(10, initial, max), max);
+        assert_eq!(reconnect_backoff(u32::MAX, initial, max), max);
+    }
+
+    #[test]
+    fn test_health_status_and_listeners() {
+        let health = MCPHealth::new("stdio://server");
+        assert!(!health.is_available());
+
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counter = seen.clone();
+        health.add_listener(move |event| {
+            assert_eq!(event.server, "stdio://server");
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        health.set_status(MCPConnectionStatus::Connected);
+        assert!(health.is_available());
+        health.set_status(MCPConnectionStatus::Reconnecting);
+        assert!(!health.is_available());
+        health.emit(MCPHealthEventKind::ConnectionLost {
+            reason: "ping timed out".to_string(),
+        });
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+    }
+}
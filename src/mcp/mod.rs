@@ -85,6 +85,7 @@
 //! - **Namespace Support** - Tool prefixing to prevent conflicts with multiple MCP servers
 //! - **Multiple Transports** - WebSocket for network servers, stdio for local processes
 //! - **Session Management** - Automatic connection handling and error recovery
+//! - **Health Checks** - Keepalive pings with automatic reconnection and backoff
 //! - **Type Safety** - Full Rust type system integration with MCP message formats
 //! - **Tool Discovery** - Automatic schema validation and tool registration
 //!
@@ -104,6 +105,7 @@
 
 pub mod client;
 pub mod error;
+pub mod health;
 pub mod server;
 pub mod test_utils;
 pub mod transport;
@@ -115,13 +117,16 @@ pub use client::{MCPClient, MCPClientConfig};
 // Re-export comprehensive error types for proper error handling
 pub use error::{JsonRpcError, MCPOperationError, SessionError, TransportError};
 
+// Re-export connection health types
+pub use health::{MCPConnectionStatus, MCPHealth, MCPHealthEvent, MCPHealthEventKind};
+
 // Re-export server implementation types
 pub use server::{MCPServer, MCPServerConfig, MCPServerHandler, StoodMCPServer};
 
 // Re-export transport implementations and configurations
 pub use transport::{
-    MCPTransport, StdioConfig, StdioTransport, TransportFactory, TransportInfo, TransportStreams,
-    WebSocketConfig,
+    MCPTransport, StderrMode, StdioConfig, StdioTransport, TransportFactory, TransportInfo,
+    TransportStreams, WebSocketConfig,
 };
 
 // Re-export all MCP protocol types and message structures
@@ -259,10 +259,17 @@ impl MCPServer for StoodMCPServer {
         let mut mcp_tools = Vec::new();
 
         for schema in tool_schemas {
+            let name = schema["name"].as_str().unwrap_or("unknown").to_string();
+            let annotations = self
+                .tool_registry
+                .get_tool_annotations(&name)
+                .await
+                .filter(|annotations| !annotations.is_empty());
             let mcp_tool = MCPTool {
-                name: schema["name"].as_str().unwrap_or("unknown").to_string(),
+                name,
                 description: schema["description"].as_str().unwrap_or("").to_string(),
                 input_schema: schema["input_schema"].clone(),
+                annotations,
             };
             mcp_tools.push(mcp_tool);
         }
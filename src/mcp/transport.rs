@@ -66,7 +66,7 @@ use futures::stream::{Stream, StreamExt};
 use std::pin::Pin;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+use tokio_tungstenite::{client_async_tls, connect_async, tungstenite::Message as WsMessage};
 use url::Url;
 
 /// Type alias for message streams
@@ -242,6 +242,31 @@ pub struct StdioConfig {
     pub startup_timeout_ms: u64,
     /// Maximum message size in bytes
     pub max_message_size: Option<usize>,
+    /// Run the server in its own process group (Unix) or job object (Windows)
+    /// so that the whole process tree is terminated on disconnect
+    pub process_group: bool,
+    /// Kill the server process tree when the transport is dropped without
+    /// being disconnected
+    pub kill_on_drop: bool,
+    /// How long to wait for a graceful exit before force-killing on disconnect
+    pub shutdown_timeout_ms: u64,
+    /// What to do with the server's stderr
+    pub stderr: StderrMode,
+    /// On Windows, launch commands that are not `.exe`/`.com` files through
+    /// `cmd /C` so batch shims such as `npx` and `uvx` resolve
+    pub windows_cmd_wrapper: bool,
+}
+
+/// Handling of an MCP server's stderr output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StderrMode {
+    /// Forward each stderr line to `tracing` (target `stood::mcp::stderr`)
+    #[default]
+    Tracing,
+    /// Let the server write to this process's stderr
+    Inherit,
+    /// Discard stderr output
+    Discard,
 }
 
 impl Default for StdioConfig {
@@ -253,6 +278,102 @@ impl Default for StdioConfig {
             env_vars: std::collections::HashMap::new(),
             startup_timeout_ms: 10_000,               // 10 seconds
             max_message_size: Some(16 * 1024 * 1024), // 16MB
+            process_group: true,
+            kill_on_drop: true,
+            shutdown_timeout_ms: 5_000, // 5 seconds
+            stderr: StderrMode::Tracing,
+            windows_cmd_wrapper: true,
+        }
+    }
+}
+
+/// Resolve the program and arguments to spawn for a stdio server.
+///
+/// Windows cannot execute `.cmd`/`.bat` files (which is how `npx`, `uvx` and most
+/// package-manager shims are installed) without going through the command
+/// interpreter, so with `windows` set anything that is not an `.exe` or `.com`
+/// file is wrapped as `cmd /C <command> <args>`.
+fn resolve_stdio_command(
+    command: &str,
+    args: &[String],
+    windows: bool,
+    cmd_wrapper: bool,
+) -> (String, Vec<String>) {
+    let lower = command.to_ascii_lowercase();
+    let is_binary = lower.ends_with(".exe") || lower.ends_with(".com");
+    if windows && cmd_wrapper && !is_binary {
+        let mut wrapped = vec!["/C".to_string(), command.to_string()];
+        wrapped.extend(args.iter().cloned());
+        ("cmd".to_string(), wrapped)
+    } else {
+        (command.to_string(), args.to_vec())
+    }
+}
+
+/// Windows job object holding an MCP server's process tree
+///
+/// Created with `JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE`, so closing the last
+/// handle (including when this process exits) terminates every process in it.
+#[cfg(windows)]
+struct JobObject(windows_sys::Win32::Foundation::HANDLE);
+
+// Job handles are kernel handles that may be used from any thread
+#[cfg(windows)]
+unsafe impl Send for JobObject {}
+#[cfg(windows)]
+unsafe impl Sync for JobObject {}
+
+#[cfg(windows)]
+impl JobObject {
+    /// Create a job object and assign `child` to it
+    ///
+    /// Processes the child starts from then on join the job too.
+    fn assign(child: &tokio::process::Child) -> std::io::Result<Self> {
+        use windows_sys::Win32::System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        };
+
+        let process = child
+            .raw_handle()
+            .ok_or_else(|| std::io::Error::other("process has already exited"))?;
+        unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                return Err(std::io::Error::last_os_error());
+            }
+            let job = Self(handle);
+            let mut limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            if SetInformationJobObject(
+                job.0,
+                JobObjectExtendedLimitInformation,
+                &limits as *const _ as *const std::ffi::c_void,
+                std::mem::size_of_val(&limits) as u32,
+            ) == 0
+                || AssignProcessToJobObject(job.0, process) == 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(job)
+        }
+    }
+
+    /// Terminate every process in the job
+    fn terminate(&self) {
+        // Fails only when the processes are already gone
+        unsafe {
+            windows_sys::Win32::System::JobObjects::TerminateJobObject(self.0, 1);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for JobObject {
+    fn drop(&mut self) {
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
         }
     }
 }
@@ -330,8 +451,17 @@ impl MCPTransport for WebSocketTransport {
         // Create a timeout for the connection
         let connect_timeout = Duration::from_millis(self.config.connect_timeout_ms);
 
-        // Attempt to connect with timeout
-        let (ws_stream, _response) = tokio::time::timeout(connect_timeout, connect_async(&url))
+        // Attempt to connect with timeout, through the proxy if one applies
+        let connect = async {
+            let Some(proxy) = crate::proxy::proxy_for(&url) else {
+                return connect_async(&url).await;
+            };
+            let host = url.host_str().unwrap_or_default();
+            let port = url.port_or_known_default().unwrap_or(80);
+            let stream = proxy.connect(host, port).await?;
+            client_async_tls(url.as_str(), stream).await
+        };
+        let (ws_stream, _response) = tokio::time::timeout(connect_timeout, connect)
             .await
             .map_err(|_| MCPOperationError::timeout(connect_timeout))?
             .map_err(|e| {
@@ -465,6 +595,9 @@ pub struct StdioTransport {
     connected: bool,
     // Store process handle and close sender for lifecycle management
     process_handle: Option<tokio::process::Child>,
+    // Job object holding the server's process tree on Windows
+    #[cfg(windows)]
+    job: Option<JobObject>,
     close_sender: Option<mpsc::UnboundedSender<()>>,
     // Store external handles when using from_handles
     external_handles: Option<(tokio::process::ChildStdin, tokio::process::ChildStdout)>,
@@ -476,6 +609,8 @@ impl StdioTransport {
             config,
             connected: false,
             process_handle: None,
+            #[cfg(windows)]
+            job: None,
             close_sender: None,
             external_handles: None,
         }
@@ -497,17 +632,40 @@ impl StdioTransport {
             env_vars: std::collections::HashMap::new(),
             startup_timeout_ms: 5000,
             max_message_size: None,
+            ..Default::default()
         };
 
         Self {
             config,
             connected: false,
             process_handle: None,
+            #[cfg(windows)]
+            job: None,
             close_sender: None,
             external_handles: Some((stdin, stdout)),
         }
     }
 
+    /// Forcefully terminate the server process and, when it runs in its own
+    /// process group or job object, every process in it. Safe to call from `Drop`.
+    fn kill_process_tree(&self, child: &mut tokio::process::Child) {
+        #[cfg(unix)]
+        if self.config.process_group {
+            if let Some(pid) = child.id() {
+                unsafe {
+                    libc::killpg(pid as i32, libc::SIGKILL);
+                }
+            }
+        }
+
+        #[cfg(windows)]
+        if let Some(job) = &self.job {
+            job.terminate();
+        }
+
+        let _ = child.start_kill();
+    }
+
     /// Connect using pre-existing handles instead of spawning a new process
     async fn connect_with_handles(
         &mut self,
@@ -643,11 +801,37 @@ impl MCPTransport for StdioTransport {
         }
 
         // Build the command
-        let mut cmd = Command::new(&self.config.command);
-        cmd.args(&self.config.args)
+        let (program, args) = resolve_stdio_command(
+            &self.config.command,
+            &self.config.args,
+            cfg!(windows),
+            self.config.windows_cmd_wrapper,
+        );
+        let mut cmd = Command::new(program);
+        cmd.args(args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(match self.config.stderr {
+                StderrMode::Tracing => Stdio::piped(),
+                StderrMode::Inherit => Stdio::inherit(),
+                StderrMode::Discard => Stdio::null(),
+            })
+            .kill_on_drop(self.config.kill_on_drop);
+
+        if self.config.process_group {
+            // A new process group lets disconnect terminate servers that fork
+            // workers (e.g. `npx` launching `node`) instead of orphaning them
+            #[cfg(unix)]
+            cmd.process_group(0);
+
+            // On Windows the server is put in a job object once spawned
+            #[cfg(windows)]
+            {
+                const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+                const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+                cmd.creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW);
+            }
+        }
 
         // Set working directory if specified
         if let Some(ref dir) = self.config.working_dir {
@@ -667,6 +851,21 @@ impl MCPTransport for StdioTransport {
             ))
         })?;
 
+        #[cfg(windows)]
+        if self.config.process_group {
+            // Assigned right after spawn, before the server is likely to have
+            // started any processes of its own
+            match JobObject::assign(&child) {
+                Ok(job) => self.job = Some(job),
+                Err(e) => tracing::warn!(
+                    "Failed to put MCP server '{}' in a job object, only the server \
+                     process will be terminated: {}",
+                    self.config.command,
+                    e
+                ),
+            }
+        }
+
         // Get stdin and stdout handles
         let mut stdin = child
             .stdin
@@ -677,6 +876,18 @@ impl MCPTransport for StdioTransport {
             .take()
             .ok_or_else(|| MCPOperationError::stdio("Failed to get stdout handle"))?;
 
+        // Forward stderr to tracing; an unread pipe would eventually fill up and
+        // block the server
+        if let Some(stderr) = child.stderr.take() {
+            let server = self.config.command.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::info!(target: "stood::mcp::stderr", server = %server, "{}", line);
+                }
+            });
+        }
+
         // Create channels for communication
         let (read_tx, read_rx) = mpsc::unbounded_channel();
         let (write_tx, mut write_rx) = mpsc::unbounded_channel();
@@ -793,19 +1004,30 @@ impl MCPTransport for StdioTransport {
             #[cfg(unix)]
             {
                 if let Some(pid) = child.id() {
-                    // Send SIGTERM for graceful shutdown
+                    // Send SIGTERM for graceful shutdown, to the whole group if
+                    // the server runs in its own process group
                     unsafe {
-                        libc::kill(pid as i32, libc::SIGTERM);
+                        if self.config.process_group {
+                            libc::killpg(pid as i32, libc::SIGTERM);
+                        } else {
+                            libc::kill(pid as i32, libc::SIGTERM);
+                        }
                     }
 
-                    // Wait up to 5 seconds for graceful shutdown
-                    match tokio::time::timeout(Duration::from_secs(5), child.wait()).await {
+                    let timeout = Duration::from_millis(self.config.shutdown_timeout_ms);
+                    match tokio::time::timeout(timeout, child.wait()).await {
                         Ok(Ok(_)) => {
-                            // Process exited gracefully
+                            // Process exited gracefully; clean up anything it left
+                            // behind in its group
+                            if self.config.process_group {
+                                unsafe {
+                                    libc::killpg(pid as i32, libc::SIGKILL);
+                                }
+                            }
                         }
                         _ => {
                             // Force kill if graceful shutdown failed
-                            let _ = child.kill().await;
+                            self.kill_process_tree(&mut child);
                             let _ = child.wait().await;
                         }
                     }
@@ -814,10 +1036,16 @@ impl MCPTransport for StdioTransport {
 
             #[cfg(not(unix))]
             {
-                // On non-Unix systems, just force kill
-                let _ = child.kill().await;
+                // Windows has no SIGTERM equivalent for console-less processes,
+                // so terminate the process tree directly
+                self.kill_process_tree(&mut child);
                 let _ = child.wait().await;
             }
+
+            #[cfg(windows)]
+            {
+                self.job = None;
+            }
         }
 
         self.connected = false;
@@ -838,6 +1066,27 @@ impl MCPTransport for StdioTransport {
     }
 }
 
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.process_handle.take() {
+            if self.config.kill_on_drop {
+                tracing::debug!(
+                    "Killing MCP server process '{}' on drop",
+                    self.config.command
+                );
+                self.kill_process_tree(&mut child);
+            }
+        }
+        // Closing the job would kill the tree the caller asked to keep
+        #[cfg(windows)]
+        if !self.config.kill_on_drop {
+            if let Some(job) = self.job.take() {
+                std::mem::forget(job);
+            }
+        }
+    }
+}
+
 /// Create test message streams for unit testing
 ///
 /// This function creates connected message streams that can be used in tests
@@ -912,6 +1161,67 @@ mod tests {
         assert_eq!(config.max_message_size, Some(16 * 1024 * 1024));
         assert!(config.args.is_empty());
         assert!(config.env_vars.is_empty());
+        assert!(config.process_group);
+        assert!(config.kill_on_drop);
+        assert_eq!(config.stderr, StderrMode::Tracing);
+    }
+
+    #[test]
+    fn test_resolve_stdio_command() {
+        let args = vec!["-y".to_string(), "server".to_string()];
+
+        let (program, resolved) = resolve_stdio_command("npx", &args, true, true);
+        assert_eq!(program, "cmd");
+        assert_eq!(resolved, vec!["/C", "npx", "-y", "server"]);
+
+        let (program, resolved) = resolve_stdio_command("python.EXE", &args, true, true);
+        assert_eq!(program, "python.EXE");
+        assert_eq!(resolved, args);
+
+        let (program, _) = resolve_stdio_command("npx", &args, false, true);
+        assert_eq!(program, "npx");
+        let (program, _) = resolve_stdio_command("npx", &args, true, false);
+        assert_eq!(program, "npx");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_disconnect_kills_process_group() {
+        // The shell forks a long-running child; disconnect must take it down too
+        let pid_file = std::env::temp_dir().join(format!("stood_mcp_pg_{}", std::process::id()));
+        let config = StdioConfig {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                format!("sleep 60 & echo $! > {}; wait", pid_file.display()),
+            ],
+            shutdown_timeout_ms: 1_000,
+            ..Default::default()
+        };
+        let mut transport = StdioTransport::new(config);
+        let _streams = transport.connect().await.unwrap();
+
+        let mut grandchild = None;
+        for _ in 0..50 {
+            if let Some(pid) = std::fs::read_to_string(&pid_file)
+                .ok()
+                .and_then(|s| s.trim().parse::<i32>().ok())
+            {
+                grandchild = Some(pid);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let grandchild = grandchild.expect("child process did not start");
+
+        transport.disconnect().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // A reparented child may linger briefly as a zombie, which counts as dead
+        let alive = std::fs::read_to_string(format!("/proc/{}/stat", grandchild))
+            .map(|stat| !stat.contains(") Z "))
+            .unwrap_or(false);
+        let _ = std::fs::remove_file(&pid_file);
+        assert!(!alive, "forked child survived disconnect");
     }
 
     #[test]
@@ -1165,6 +1475,7 @@ mod tests {
             env_vars: [("MCP_SERVER_MODE".to_string(), "production".to_string())].into(),
             startup_timeout_ms: 15_000,
             max_message_size: Some(8 * 1024 * 1024),
+            ..Default::default()
         };
         assert_eq!(custom_config.command, "python");
         assert_eq!(custom_config.args.len(), 2);
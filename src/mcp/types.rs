@@ -259,12 +259,19 @@ pub struct ClientCapabilities {
     /// Whether the client supports root directory operations
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roots: Option<RootsCapability>,
+    /// Whether the client answers `elicitation/create` requests for user input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<ElicitationCapability>,
 }
 
 /// Sampling-related client capabilities
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct SamplingCapability {}
 
+/// Elicitation-related client capabilities
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ElicitationCapability {}
+
 /// Root directory-related client capabilities
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct RootsCapability {
@@ -334,6 +341,9 @@ pub struct Tool {
     /// JSON schema for the tool's input parameters
     #[serde(rename = "inputSchema")]
     pub input_schema: serde_json::Value,
+    /// Hints about the tool's behavior, such as whether it is read-only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<crate::tools::ToolAnnotations>,
 }
 
 /// Tool execution results containing content and error status
@@ -583,6 +593,7 @@ mod tests {
             roots: Some(RootsCapability {
                 list_changed: Some(true),
             }),
+            elicitation: Some(ElicitationCapability {}),
         };
 
         // Test serialization and deserialization
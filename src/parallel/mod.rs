@@ -35,12 +35,12 @@
 //! providing automatic parallelization when `max_parallel_tools > 1` is configured.
 
 use crate::error::StoodError;
+use crate::runtime::timeout;
 use crate::Result as StoodResult;
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::time::Duration;
-use tokio::time::timeout;
 use tracing::{debug, error, info};
 
 /// Parallel execution configuration
@@ -187,7 +187,8 @@ pub struct TokioExecutor {
     /// Task tracking and metrics
     metrics: std::sync::Arc<tokio::sync::Mutex<TaskMetrics>>,
     /// Task handles for cancellation
-    task_handles: std::sync::Arc<tokio::sync::Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
+    task_handles:
+        std::sync::Arc<tokio::sync::Mutex<HashMap<String, crate::runtime::JoinHandle<()>>>>,
     /// Semaphore to limit concurrency
     semaphore: std::sync::Arc<tokio::sync::Semaphore>,
     /// Shutdown signal
@@ -305,7 +306,7 @@ impl ParallelExecutor for TokioExecutor {
         let task_timeout = self.config.task_timeout;
 
         // Spawn the task
-        let handle = tokio::spawn(async move {
+        let handle = crate::runtime::spawn(async move {
             // Acquire semaphore permit
             let _permit = semaphore
                 .acquire()
@@ -424,7 +425,7 @@ impl ParallelExecutor for TokioExecutor {
                             }
                         }
                     }
-                    _ = tokio::time::sleep(Duration::from_millis(10)) => {
+                    _ = crate::runtime::sleep(Duration::from_millis(10)) => {
                         // Continue polling for task completion
                     }
                 }
@@ -497,7 +498,7 @@ impl ParallelExecutor for TokioExecutor {
                                     return Some((task_result, (receiver, handles_ref)));
                                 }
                             }
-                            _ = tokio::time::sleep(Duration::from_millis(100)) => {
+                            _ = crate::runtime::sleep(Duration::from_millis(100)) => {
                                 // Check if all tasks are done
                                 if handles_ref.lock().await.is_empty() {
                                     return None;
@@ -39,7 +39,6 @@
 //! 5. **Expiration** - Automatic removal after idle timeout
 
 use super::PerformanceConfig;
-use aws_config::BehaviorVersion;
 use aws_sdk_bedrockruntime::Client as BedrockClient;
 use std::{
     sync::{
@@ -198,7 +197,7 @@ impl BedrockConnectionPool {
     pub async fn new(
         config: PerformanceConfig,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let aws_config = aws_config::defaults(BehaviorVersion::latest()).load().await;
+        let aws_config = crate::llm::http::aws_config_loader()?.load().await;
 
         // Create channel for async connection returns (optimization: avoid task spawning)
         let (return_sender, mut return_receiver) = mpsc::unbounded_channel::<ConnectionReturn>();
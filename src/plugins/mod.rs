@@ -0,0 +1,384 @@
+//! Tools loaded at runtime from WASM plugins (requires the `plugins` feature).
+//!
+//! A plugin is a WebAssembly module that implements one tool. Tool authors
+//! compile it from any language that targets `wasm32` and ship the `.wasm`
+//! file; the host application loads it with a [`PluginLoader`] and registers
+//! the resulting [`WasmTool`] like any other tool, without being recompiled.
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! use stood::plugins::PluginLoader;
+//! use stood::tools::Tool;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let loader = PluginLoader::new()?.with_fuel(500_000_000);
+//! let tools = loader
+//!     .load_dir("./plugins")?
+//!     .into_iter()
+//!     .map(|tool| Box::new(tool) as Box<dyn Tool>)
+//!     .collect();
+//!
+//! let agent = Agent::builder().tools(tools).build().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Plugin Interface
+//!
+//! Data crosses the boundary as UTF-8 JSON in the plugin's linear memory,
+//! passed as a pointer and a length. A plugin exports:
+//!
+//! - `memory` - its linear memory
+//! - `stood_alloc(len: i32) -> i32` - reserves `len` bytes for the host to
+//!   write a call's input into and returns their address
+//! - `stood_describe()` - reports the tool descriptor through `tool_result`:
+//!   `{"name": ..., "description": ..., "parameters_schema": {...}}`, plus an
+//!   optional `"version"`
+//! - `stood_execute(ptr: i32, len: i32)` - runs the tool on the JSON
+//!   parameters at `ptr` (`null` when the model passed none)
+//!
+//! and may import these host functions from the `stood` module:
+//!
+//! - `tool_result(ptr: i32, len: i32)` - the JSON content of a successful
+//!   [`ToolResult`](crate::tools::ToolResult)
+//! - `tool_error(ptr: i32, len: i32)` - a UTF-8 message for a failed result
+//! - `log(level: i32, ptr: i32, len: i32)` - a UTF-8 message logged with
+//!   `tracing` at error (0), warn (1), info (2), debug (3) or trace (other)
+//!
+//! An execution that calls neither `tool_result` nor `tool_error`, traps or
+//! runs out of fuel fails with a [`ToolError`](crate::tools::ToolError).
+//!
+//! # Sandbox
+//!
+//! Plugins get no WASI imports: no file system, network, clock or environment,
+//! only the host functions above. Each execution runs in a fresh instance, so
+//! no state carries over between calls, with its memory capped by
+//! [`PluginLoader::with_max_memory`] and its instructions metered by
+//! [`PluginLoader::with_fuel`]. Execution happens on Tokio's blocking pool; a
+//! cancelled call keeps running there until it finishes or its fuel runs out.
+//!
+//! Native shared libraries are deliberately not supported: they run with the
+//! full permissions of the host and Rust has no stable ABI to load them with.
+//!
+//! # Manifests
+//!
+//! A signed manifest (see [`crate::tools::manifest`]) can be shipped next to
+//! the module, as `search.manifest.json` for `search.wasm`. It is returned by
+//! [`Tool::manifest`](crate::tools::Tool::manifest) and checked when the tool
+//! is registered with a [`ManifestVerifier`](crate::tools::manifest::ManifestVerifier)
+//! installed. The manifest must cover the module's bytes, signed with
+//! [`ToolManifest::with_module`](crate::tools::manifest::ToolManifest::with_module):
+//! [`PluginLoader::load_file`] refuses a manifest for different bytes, and the
+//! verifier refuses a manifest that doesn't cover the module at all. Plugins report [`ToolSource::Plugin`](crate::tools::ToolSource::Plugin),
+//! so a verifier requires them to be signed unless told otherwise.
+
+mod wasm;
+
+pub use wasm::WasmTool;
+
+use std::path::{Path, PathBuf};
+
+use crate::tools::manifest::{self, SignedManifest};
+
+/// Default fuel per call, roughly one unit per executed WASM instruction
+pub const DEFAULT_FUEL: u64 = 1_000_000_000;
+
+/// Default cap on a plugin instance's linear memory
+pub const DEFAULT_MAX_MEMORY: usize = 64 * 1024 * 1024;
+
+/// Error loading a plugin
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    /// The WASM engine could not be created
+    #[error("Failed to create WASM engine: {0}")]
+    Engine(String),
+
+    /// Reading the module or its manifest failed
+    #[error("Failed to read plugin {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The bytes are not a valid WASM module
+    #[error("Invalid WASM module: {0}")]
+    Compile(String),
+
+    /// The module does not implement the plugin interface
+    #[error("Module does not implement the plugin interface: {0}")]
+    Interface(String),
+
+    /// `stood_describe` failed or reported an invalid descriptor
+    #[error("Invalid plugin descriptor: {0}")]
+    Descriptor(String),
+
+    /// The sidecar manifest could not be parsed
+    #[error("Invalid plugin manifest {path}: {message}")]
+    Manifest { path: PathBuf, message: String },
+}
+
+/// Limits applied to every plugin call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PluginLimits {
+    pub(crate) fuel: u64,
+    pub(crate) max_memory: usize,
+}
+
+/// Compiles WASM modules into [`WasmTool`]s
+///
+/// Tools loaded by one loader share its engine and limits.
+#[derive(Clone)]
+pub struct PluginLoader {
+    engine: wasmtime::Engine,
+    limits: PluginLimits,
+}
+
+impl std::fmt::Debug for PluginLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginLoader")
+            .field("fuel", &self.limits.fuel)
+            .field("max_memory", &self.limits.max_memory)
+            .finish()
+    }
+}
+
+impl PluginLoader {
+    /// Create a loader with [`DEFAULT_FUEL`] and [`DEFAULT_MAX_MEMORY`]
+    pub fn new() -> Result<Self, PluginError> {
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true);
+        let engine =
+            wasmtime::Engine::new(&config).map_err(|e| PluginError::Engine(e.to_string()))?;
+        Ok(Self {
+            engine,
+            limits: PluginLimits {
+                fuel: DEFAULT_FUEL,
+                max_memory: DEFAULT_MAX_MEMORY,
+            },
+        })
+    }
+
+    /// Fuel available to each call before it is aborted
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.limits.fuel = fuel;
+        self
+    }
+
+    /// Maximum linear memory, in bytes, a plugin instance may grow to
+    pub fn with_max_memory(mut self, bytes: usize) -> Self {
+        self.limits.max_memory = bytes;
+        self
+    }
+
+    /// Load a plugin from a `.wasm` (or `.wat`) file
+    ///
+    /// Picks up a signed manifest from `<stem>.manifest.json` next to the
+    /// file when one exists.
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<WasmTool, PluginError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|source| PluginError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let tool = self.load_bytes(&bytes)?;
+
+        let manifest_path = path.with_extension("manifest.json");
+        if !manifest_path.exists() {
+            return Ok(tool);
+        }
+        let json = std::fs::read_to_string(&manifest_path).map_err(|source| PluginError::Io {
+            path: manifest_path.clone(),
+            source,
+        })?;
+        let manifest: SignedManifest =
+            serde_json::from_str(&json).map_err(|e| PluginError::Manifest {
+                path: manifest_path.clone(),
+                message: e.to_string(),
+            })?;
+        if let Some(expected) = &manifest.manifest.module_hash {
+            let actual = manifest::module_hash(&bytes);
+            if *expected != actual {
+                return Err(PluginError::Manifest {
+                    path: manifest_path,
+                    message: format!(
+                        "module_hash is '{}' but {} has '{}'",
+                        expected,
+                        path.display(),
+                        actual
+                    ),
+                });
+            }
+        }
+        Ok(tool.with_manifest(manifest))
+    }
+
+    /// Load a plugin from module bytes, in binary or text format
+    pub fn load_bytes(&self, bytes: &[u8]) -> Result<WasmTool, PluginError> {
+        let module = wasmtime::Module::new(&self.engine, bytes)
+            .map_err(|e| PluginError::Compile(format!("{:#}", e)))?;
+        WasmTool::load(
+            &self.engine,
+            module,
+            manifest::module_hash(bytes),
+            self.limits,
+        )
+    }
+
+    /// Load every `.wasm` file in `dir`, in file name order
+    ///
+    /// Fails on the first plugin that doesn't load.
+    pub fn load_dir(&self, dir: impl AsRef<Path>) -> Result<Vec<WasmTool>, PluginError> {
+        let dir = dir.as_ref();
+        let io_error = |source| PluginError::Io {
+            path: dir.to_path_buf(),
+            source,
+        };
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "wasm") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        paths.iter().map(|path| self.load_file(path)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::manifest::{ManifestError, ManifestVerifier, ToolManifest};
+    use crate::tools::{Tool, ToolSource};
+    use serde_json::json;
+
+    const ECHO: &str = r#"{"name":"echo","description":"Echoes its input","parameters_schema":{"type":"object"},"version":"1.2.0"}"#;
+
+    /// A plugin module reporting `descriptor`, whose `stood_execute` is `execute`
+    fn plugin(descriptor: &str, execute: &str) -> String {
+        format!(
+            r#"(module
+                (import "stood" "tool_result" (func $result (param i32 i32)))
+                (import "stood" "tool_error" (func $error (param i32 i32)))
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 4096))
+                (data (i32.const 0) "{}")
+                (data (i32.const 2048) "bad input")
+                (func (export "stood_alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $ptr))
+                (func (export "stood_describe")
+                    (call $result (i32.const 0) (i32.const {})))
+                (func (export "stood_execute") (param $ptr i32) (param $len i32)
+                    {}))"#,
+            descriptor.replace('"', "\\\""),
+            descriptor.len(),
+            execute
+        )
+    }
+
+    fn echo() -> String {
+        plugin(ECHO, "(call $result (local.get $ptr) (local.get $len))")
+    }
+
+    #[tokio::test]
+    async fn test_load_and_execute() {
+        let tool = PluginLoader::new()
+            .unwrap()
+            .load_bytes(echo().as_bytes())
+            .unwrap();
+        assert_eq!(tool.name(), "echo");
+        assert_eq!(tool.description(), "Echoes its input");
+        assert_eq!(tool.version(), "1.2.0");
+        assert_eq!(tool.parameters_schema(), json!({"type": "object"}));
+        assert_eq!(tool.source(), ToolSource::Plugin);
+
+        let result = tool
+            .execute(Some(json!({"a": [1, 2]})), None)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.content, json!({"a": [1, 2]}));
+
+        let result = tool.execute(None, None).await.unwrap();
+        assert_eq!(result.content, json!(null));
+    }
+
+    #[tokio::test]
+    async fn test_errors_and_limits() {
+        let loader = PluginLoader::new().unwrap().with_fuel(100_000);
+
+        let failing = loader
+            .load_bytes(plugin(ECHO, "(call $error (i32.const 2048) (i32.const 9))").as_bytes())
+            .unwrap();
+        let result = failing.execute(None, None).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("bad input"));
+
+        let silent = loader.load_bytes(plugin(ECHO, "").as_bytes()).unwrap();
+        assert!(silent.execute(None, None).await.is_err());
+
+        let looping = loader
+            .load_bytes(plugin(ECHO, "(loop $spin (br $spin))").as_bytes())
+            .unwrap();
+        let error = looping.execute(None, None).await.unwrap_err();
+        assert!(error.to_string().contains("fuel"), "{}", error);
+
+        let invalid = loader.load_bytes(plugin(r#"{"name":"x"}"#, "").as_bytes());
+        assert!(matches!(invalid, Err(PluginError::Descriptor(_))));
+
+        let missing = loader.load_bytes(b"(module (memory (export \"memory\") 1))");
+        assert!(matches!(missing, Err(PluginError::Interface(_))));
+    }
+
+    #[test]
+    fn test_load_dir_with_manifest() {
+        use ring::signature::KeyPair;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("echo.wasm"), echo()).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a plugin").unwrap();
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = ring::signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key_pair = ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let verifier = ManifestVerifier::new().with_key("publisher", key_pair.public_key());
+        let manifest = ToolManifest::new("echo", "1.2.0", &json!({"type": "object"}));
+        let signed = manifest
+            .clone()
+            .with_module(echo().as_bytes())
+            .sign("publisher", pkcs8.as_ref())
+            .unwrap();
+        let manifest_path = dir.path().join("echo.manifest.json");
+        std::fs::write(&manifest_path, serde_json::to_string(&signed).unwrap()).unwrap();
+
+        let loader = PluginLoader::new().unwrap();
+        let tools = loader.load_dir(dir.path()).unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].manifest(), Some(signed));
+        assert_eq!(verifier.verify(&tools[0]), Ok(()));
+
+        // A module swapped after signing is refused on load
+        let swapped = plugin(ECHO, "(call $error (i32.const 2048) (i32.const 9))");
+        std::fs::write(dir.path().join("echo.wasm"), swapped).unwrap();
+        assert!(matches!(
+            loader.load_dir(dir.path()),
+            Err(PluginError::Manifest { .. })
+        ));
+
+        // A manifest that doesn't cover the module is refused on verification
+        let unbound = manifest.sign("publisher", pkcs8.as_ref()).unwrap();
+        std::fs::write(&manifest_path, serde_json::to_string(&unbound).unwrap()).unwrap();
+        let tools = loader.load_dir(dir.path()).unwrap();
+        assert!(matches!(
+            verifier.verify(&tools[0]),
+            Err(ManifestError::Mismatch {
+                field: "module_hash",
+                ..
+            })
+        ));
+    }
+}
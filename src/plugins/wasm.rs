@@ -0,0 +1,301 @@
+//! [`WasmTool`] and the host side of the plugin interface.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use wasmtime::{
+    Caller, Engine, InstancePre, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+};
+
+use super::{PluginError, PluginLimits};
+use crate::tools::manifest::SignedManifest;
+use crate::tools::{versioning, Tool, ToolError, ToolResult, ToolSource};
+
+/// What a plugin reports from `stood_describe`
+#[derive(Debug, Clone, Deserialize)]
+struct Descriptor {
+    name: String,
+    description: String,
+    parameters_schema: Value,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// Per-call state of a plugin instance
+struct HostState {
+    plugin: String,
+    limits: StoreLimits,
+    output: Option<Result<Vec<u8>, String>>,
+}
+
+/// A tool implemented by a WASM plugin
+///
+/// Created by [`PluginLoader`](super::PluginLoader). Cloning is cheap; clones
+/// share the compiled module.
+#[derive(Clone)]
+pub struct WasmTool {
+    descriptor: Descriptor,
+    engine: Engine,
+    instance_pre: InstancePre<HostState>,
+    limits: PluginLimits,
+    module_hash: String,
+    manifest: Option<SignedManifest>,
+}
+
+impl std::fmt::Debug for WasmTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmTool")
+            .field("name", &self.descriptor.name)
+            .field("version", &self.version())
+            .field("signed", &self.manifest.is_some())
+            .finish()
+    }
+}
+
+impl WasmTool {
+    /// Link `module` against the host functions and ask it to describe itself
+    pub(super) fn load(
+        engine: &Engine,
+        module: Module,
+        module_hash: String,
+        limits: PluginLimits,
+    ) -> Result<Self, PluginError> {
+        for export in ["memory", "stood_alloc", "stood_describe", "stood_execute"] {
+            if module.get_export(export).is_none() {
+                return Err(PluginError::Interface(format!(
+                    "missing export `{}`",
+                    export
+                )));
+            }
+        }
+        let instance_pre = linker(engine)
+            .instantiate_pre(&module)
+            .map_err(|e| PluginError::Interface(format!("{:#}", e)))?;
+
+        let output = run(engine, &instance_pre, limits, "<loading>", None)
+            .map_err(PluginError::Descriptor)?
+            .map_err(|message| PluginError::Descriptor(format!("plugin error: {}", message)))?;
+        let descriptor: Descriptor =
+            serde_json::from_slice(&output).map_err(|e| PluginError::Descriptor(e.to_string()))?;
+
+        Ok(Self {
+            descriptor,
+            engine: engine.clone(),
+            instance_pre,
+            limits,
+            module_hash,
+            manifest: None,
+        })
+    }
+
+    /// Attach the publisher's signed manifest
+    pub fn with_manifest(mut self, manifest: SignedManifest) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for WasmTool {
+    fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    fn description(&self) -> &str {
+        &self.descriptor.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        self.descriptor.parameters_schema.clone()
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<Value>,
+        _agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let input = serde_json::to_vec(&parameters.unwrap_or(Value::Null)).map_err(|e| {
+            ToolError::InvalidParameters {
+                message: e.to_string(),
+            }
+        })?;
+        let tool = self.clone();
+        let output = tokio::task::spawn_blocking(move || {
+            run(
+                &tool.engine,
+                &tool.instance_pre,
+                tool.limits,
+                &tool.descriptor.name,
+                Some(&input),
+            )
+        })
+        .await
+        .map_err(|e| ToolError::execution_failed(format!("Plugin task failed: {}", e)))?
+        .map_err(|message| {
+            ToolError::execution_failed(format!("Plugin {} failed: {}", self.name(), message))
+        })?;
+
+        match output {
+            Ok(bytes) => {
+                let content = serde_json::from_slice(&bytes).map_err(|e| {
+                    ToolError::execution_failed(format!(
+                        "Plugin {} returned invalid JSON: {}",
+                        self.name(),
+                        e
+                    ))
+                })?;
+                Ok(ToolResult::success(content))
+            }
+            Err(message) => Ok(ToolResult::error(message)),
+        }
+    }
+
+    fn version(&self) -> &str {
+        self.descriptor
+            .version
+            .as_deref()
+            .unwrap_or(versioning::DEFAULT_VERSION)
+    }
+
+    fn manifest(&self) -> Option<SignedManifest> {
+        self.manifest.clone()
+    }
+
+    fn module_hash(&self) -> Option<String> {
+        Some(self.module_hash.clone())
+    }
+
+    fn source(&self) -> ToolSource {
+        ToolSource::Plugin
+    }
+}
+
+/// Run `stood_execute` on `input`, or `stood_describe` without one, in a fresh instance
+///
+/// The outer error is a trap or interface failure; the inner result is what
+/// the plugin reported through `tool_result` or `tool_error`.
+fn run(
+    engine: &Engine,
+    instance_pre: &InstancePre<HostState>,
+    limits: PluginLimits,
+    plugin: &str,
+    input: Option<&[u8]>,
+) -> Result<Result<Vec<u8>, String>, String> {
+    let state = HostState {
+        plugin: plugin.to_string(),
+        limits: StoreLimitsBuilder::new()
+            .memory_size(limits.max_memory)
+            .instances(1)
+            .build(),
+        output: None,
+    };
+    let mut store = Store::new(engine, state);
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(limits.fuel).map_err(|e| e.to_string())?;
+
+    let instance = instance_pre
+        .instantiate(&mut store)
+        .map_err(|e| format!("{:#}", e))?;
+    match input {
+        Some(input) => {
+            let len = i32::try_from(input.len()).map_err(|_| "input too large".to_string())?;
+            let alloc = instance
+                .get_typed_func::<i32, i32>(&mut store, "stood_alloc")
+                .map_err(|e| format!("{:#}", e))?;
+            let ptr = alloc.call(&mut store, len).map_err(trap_message)?;
+            let memory = instance
+                .get_memory(&mut store, "memory")
+                .ok_or("missing export `memory`")?;
+            memory
+                .write(&mut store, ptr as u32 as usize, input)
+                .map_err(|_| "stood_alloc returned memory out of bounds".to_string())?;
+
+            instance
+                .get_typed_func::<(i32, i32), ()>(&mut store, "stood_execute")
+                .map_err(|e| format!("{:#}", e))?
+                .call(&mut store, (ptr, len))
+                .map_err(trap_message)?;
+        }
+        None => {
+            instance
+                .get_typed_func::<(), ()>(&mut store, "stood_describe")
+                .map_err(|e| format!("{:#}", e))?
+                .call(&mut store, ())
+                .map_err(trap_message)?;
+        }
+    }
+
+    store
+        .into_data()
+        .output
+        .ok_or_else(|| "called neither tool_result nor tool_error".to_string())
+}
+
+fn trap_message(error: wasmtime::Error) -> String {
+    match error.downcast_ref::<wasmtime::Trap>() {
+        Some(wasmtime::Trap::OutOfFuel) => "ran out of fuel".to_string(),
+        _ => format!("{:#}", error),
+    }
+}
+
+/// The host functions plugins may import from the `stood` module
+fn linker(engine: &Engine) -> Linker<HostState> {
+    let mut linker = Linker::new(engine);
+    linker
+        .func_wrap(
+            "stood",
+            "tool_result",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                let bytes = read(&mut caller, ptr, len)?;
+                caller.data_mut().output = Some(Ok(bytes));
+                Ok(())
+            },
+        )
+        .expect("tool_result is defined once");
+    linker
+        .func_wrap(
+            "stood",
+            "tool_error",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                let message = String::from_utf8_lossy(&read(&mut caller, ptr, len)?).into_owned();
+                caller.data_mut().output = Some(Err(message));
+                Ok(())
+            },
+        )
+        .expect("tool_error is defined once");
+    linker
+        .func_wrap(
+            "stood",
+            "log",
+            |mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32| {
+                let bytes = read(&mut caller, ptr, len)?;
+                let message = String::from_utf8_lossy(&bytes);
+                let plugin = caller.data().plugin.as_str();
+                match level {
+                    0 => tracing::error!(plugin, "{}", message),
+                    1 => tracing::warn!(plugin, "{}", message),
+                    2 => tracing::info!(plugin, "{}", message),
+                    3 => tracing::debug!(plugin, "{}", message),
+                    _ => tracing::trace!(plugin, "{}", message),
+                }
+                Ok(())
+            },
+        )
+        .expect("log is defined once");
+    linker
+}
+
+/// Copy `len` bytes at `ptr` out of the caller's memory
+fn read(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> wasmtime::Result<Vec<u8>> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+        .ok_or_else(|| wasmtime::Error::msg("missing export `memory`"))?;
+    let start = ptr as u32 as usize;
+    let end = start + len as u32 as usize;
+    memory
+        .data(&caller)
+        .get(start..end)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| wasmtime::Error::msg("pointer out of bounds"))
+}
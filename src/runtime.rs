@@ -0,0 +1,278 @@
+//! Async runtime abstraction for spawning tasks and timers.
+//!
+//! The agent loop, tool executor, retry logic and callback batching spawn
+//! tasks and wait on timers through this module instead of calling tokio
+//! directly, so applications on async-std, smol or a custom executor can run
+//! agents on their own runtime. Install it once at startup, before building
+//! agents:
+//!
+//! ```
+//! use std::time::Duration;
+//! use futures::future::BoxFuture;
+//! use stood::runtime::{self, Runtime};
+//!
+//! /// Runs every task on its own thread (stand-in for e.g. `async_std::task::spawn`)
+//! struct ThreadRuntime;
+//!
+//! impl Runtime for ThreadRuntime {
+//!     fn spawn(&self, task: BoxFuture<'static, ()>) {
+//!         std::thread::spawn(move || futures::executor::block_on(task));
+//!     }
+//!
+//!     fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+//!         let (done, wait) = futures::channel::oneshot::channel::<()>();
+//!         std::thread::spawn(move || {
+//!             std::thread::sleep(duration);
+//!             let _ = done.send(());
+//!         });
+//!         Box::pin(async move {
+//!             let _ = wait.await;
+//!         })
+//!     }
+//!
+//!     fn name(&self) -> &str {
+//!         "threads"
+//!     }
+//! }
+//!
+//! runtime::set_runtime(std::sync::Arc::new(ThreadRuntime)).ok();
+//!
+//! futures::executor::block_on(async {
+//!     let answer = runtime::spawn(async { 42 }).await.unwrap();
+//!     assert_eq!(answer, 42);
+//!     assert!(runtime::timeout(Duration::from_millis(10), futures::future::pending::<()>())
+//!         .await
+//!         .is_err());
+//! });
+//! ```
+//!
+//! Without [`set_runtime`], the tokio runtime of the calling task is used.
+//!
+//! Channels and locks need no abstraction: the crate's `tokio::sync`
+//! primitives and the [`channel`] re-exports work on any executor.
+//!
+//! # Limits
+//!
+//! tokio remains a required dependency, and only the code listed above goes
+//! through this module. Everything else still needs a tokio runtime (or at
+//! least its reactor and timer, e.g. through `async-compat`):
+//!
+//! - the provider HTTP clients (the AWS SDK and `reqwest`); alternatively
+//!   implement [`LlmProvider`](crate::llm::traits::LlmProvider) on your
+//!   runtime's HTTP client
+//! - the MCP stdio and WebSocket transports
+//! - builtin tools that touch files, processes or the network (`tokio::fs`,
+//!   `tokio::process`), the code runner and the database tool's query timeouts
+//! - the telemetry exporter, shutdown signal handling, the HTTP servers in
+//!   [`integrations`](crate::integrations) and the `performance` module
+//!
+//! A custom runtime therefore lets the agent loop run on another executor,
+//! not the crate run without tokio.
+
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::channel::oneshot;
+use futures::future::{AbortHandle, Abortable, BoxFuture, Either};
+use futures::FutureExt;
+use once_cell::sync::OnceCell;
+use thiserror::Error;
+
+/// Runtime-agnostic channels
+pub mod channel {
+    pub use futures::channel::{mpsc, oneshot};
+}
+
+/// Executor used by the crate to spawn tasks and wait on timers
+pub trait Runtime: Send + Sync + 'static {
+    /// Run `task` in the background
+    fn spawn(&self, task: BoxFuture<'static, ()>);
+
+    /// Future that completes after `duration`
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Name of the runtime for logging
+    fn name(&self) -> &str;
+}
+
+/// The tokio runtime of the calling task (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRuntime;
+
+impl Runtime for TokioRuntime {
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        tokio::spawn(task);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn name(&self) -> &str {
+        "tokio"
+    }
+}
+
+static RUNTIME: OnceCell<Arc<dyn Runtime>> = OnceCell::new();
+
+/// Install the runtime used by the crate
+///
+/// Can only be set once, before the runtime is first used; otherwise
+/// `runtime` is returned back.
+pub fn set_runtime(runtime: Arc<dyn Runtime>) -> Result<(), Arc<dyn Runtime>> {
+    RUNTIME.set(runtime)
+}
+
+/// The installed runtime, [`TokioRuntime`] by default
+pub fn runtime() -> &'static dyn Runtime {
+    RUNTIME.get_or_init(|| Arc::new(TokioRuntime)).as_ref()
+}
+
+/// Spawn `future` on the installed runtime
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    spawn_on(runtime(), future)
+}
+
+/// Wait for `duration` on the installed runtime
+pub async fn sleep(duration: Duration) {
+    runtime().sleep(duration).await
+}
+
+/// Run `future`, failing with [`Elapsed`] if it does not complete within
+/// `duration`
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    timeout_on(runtime(), duration, future).await
+}
+
+/// Spawn `future` on `runtime`
+///
+/// Dropping the returned handle detaches the task; [`JoinHandle::abort`]
+/// cancels it.
+pub fn spawn_on<F>(runtime: &dyn Runtime, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    let (abort, registration) = AbortHandle::new_pair();
+    let task = Abortable::new(
+        async move {
+            let output = AssertUnwindSafe(future).catch_unwind().await;
+            let _ = sender.send(output);
+        },
+        registration,
+    );
+    runtime.spawn(Box::pin(task.map(|_| ())));
+    JoinHandle { receiver, abort }
+}
+
+/// Run `future` with a timer of `runtime`
+pub async fn timeout_on<F: Future>(
+    runtime: &dyn Runtime,
+    duration: Duration,
+    future: F,
+) -> Result<F::Output, Elapsed> {
+    let future = std::pin::pin!(future);
+    match futures::future::select(future, runtime.sleep(duration)).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Elapsed(duration)),
+    }
+}
+
+/// A future did not complete before its timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("deadline of {0:?} has elapsed")]
+pub struct Elapsed(pub Duration);
+
+/// Why a spawned task produced no output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum JoinError {
+    /// The task was aborted or dropped by the runtime
+    #[error("task was cancelled")]
+    Cancelled,
+    /// The task panicked
+    #[error("task panicked")]
+    Panicked,
+}
+
+/// Handle to the output of a task spawned with [`spawn`]
+#[derive(Debug)]
+pub struct JoinHandle<T> {
+    receiver: oneshot::Receiver<std::thread::Result<T>>,
+    abort: AbortHandle,
+}
+
+impl<T> JoinHandle<T> {
+    /// Cancel the task; awaiting the handle then fails with
+    /// [`JoinError::Cancelled`]
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver.poll_unpin(cx).map(|result| match result {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(_)) => Err(JoinError::Panicked),
+            Err(oneshot::Canceled) => Err(JoinError::Cancelled),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs each task and timer on its own thread, without tokio
+    struct ThreadRuntime;
+
+    impl Runtime for ThreadRuntime {
+        fn spawn(&self, task: BoxFuture<'static, ()>) {
+            std::thread::spawn(move || futures::executor::block_on(task));
+        }
+
+        fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+            let (done, wait) = oneshot::channel::<()>();
+            std::thread::spawn(move || {
+                std::thread::sleep(duration);
+                let _ = done.send(());
+            });
+            Box::pin(wait.map(|_| ()))
+        }
+
+        fn name(&self) -> &str {
+            "threads"
+        }
+    }
+
+    #[test]
+    fn test_spawn_abort_and_timeout_without_tokio() {
+        futures::executor::block_on(async {
+            let runtime = ThreadRuntime;
+            assert_eq!(spawn_on(&runtime, async { 1 + 1 }).await, Ok(2));
+
+            let panicked = spawn_on(&runtime, async { panic!("boom") });
+            assert_eq!(panicked.await, Err::<(), _>(JoinError::Panicked));
+
+            let never = spawn_on(&runtime, futures::future::pending::<()>());
+            never.abort();
+            assert_eq!(never.await, Err(JoinError::Cancelled));
+
+            let slow = timeout_on(&runtime, Duration::from_millis(5), async {
+                runtime.sleep(Duration::from_millis(200)).await
+            });
+            assert_eq!(slow.await, Err(Elapsed(Duration::from_millis(5))));
+        });
+    }
+}
@@ -0,0 +1,278 @@
+//! Cron expressions.
+//!
+//! [`CronSchedule`] parses the standard five fields — minute, hour, day of
+//! month, month and day of week — evaluated in UTC. Fields accept `*`,
+//! numbers, ranges (`1-5`), lists (`1,15`) and steps (`*/15`, `8-18/2`);
+//! months and weekdays also accept three-letter names (`JAN`, `MON`).
+//! Sunday is `0` or `7`. As in Vixie cron, when both the day of month and
+//! the day of week are restricted, a day matching either one fires.
+//!
+//! The shortcuts `@yearly` (`@annually`), `@monthly`, `@weekly`, `@daily`
+//! (`@midnight`) and `@hourly` are also accepted.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+use crate::{Result, StoodError};
+
+/// How far ahead [`CronSchedule::next_after`] looks for a matching time
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day of month field is not `*`
+    days_restricted: bool,
+    /// Whether the day of week field is not `*`
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression or an `@` shortcut
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(StoodError::invalid_input(format!(
+                "Cron expression '{}' must have 5 fields, found {}",
+                expression,
+                fields.len()
+            )));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, &WEEKDAY_NAMES, 0, expression)?;
+        // Sunday is both 0 and 7
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            minutes: parse_field(minute, 0, 59, &[], 0, expression)?,
+            hours: parse_field(hour, 0, 23, &[], 0, expression)?,
+            days: parse_field(day, 1, 31, &[], 0, expression)?,
+            months: parse_field(month, 1, 12, &MONTH_NAMES, 1, expression)?,
+            weekdays,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+
+    /// The first matching minute strictly after `after`
+    ///
+    /// Returns `None` if nothing matches within five years, e.g. for
+    /// `0 0 30 2 *`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = after + Duration::days(MAX_LOOKAHEAD_DAYS);
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        while time <= limit {
+            if !has(self.months, time.month()) {
+                let (year, month) = match time.month() {
+                    12 => (time.year() + 1, 1),
+                    month => (time.year(), month + 1),
+                };
+                time = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.day_matches(&time) {
+                time = Utc
+                    .with_ymd_and_hms(time.year(), time.month(), time.day(), 0, 0, 0)
+                    .single()?
+                    + Duration::days(1);
+            } else if !has(self.hours, time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !has(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    /// The expression this schedule was parsed from
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    fn day_matches(&self, time: &DateTime<Utc>) -> bool {
+        let day = has(self.days, time.day());
+        let weekday = has(self.weekdays, time.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = StoodError;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        Self::parse(expression)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parse one field into a bit set of the values it matches
+///
+/// `names` are accepted for the values starting at `first_name`.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    first_name: u32,
+    expression: &str,
+) -> Result<u64> {
+    let invalid = |reason: String| {
+        StoodError::invalid_input(format!(
+            "Invalid cron field '{}' in '{}': {}",
+            field, expression, reason
+        ))
+    };
+    let value = |text: &str| -> Result<u32> {
+        let upper = text.to_ascii_uppercase();
+        let value = match names.iter().position(|name| *name == upper) {
+            Some(index) => index as u32 + first_name,
+            None => text
+                .parse()
+                .map_err(|_| invalid(format!("'{}' is not a number", text)))?,
+        };
+        if value < min || value > max {
+            return Err(invalid(format!("{} is outside {}-{}", value, min, max)));
+        }
+        Ok(value)
+    };
+
+    let mut set = 0;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| invalid(format!("'{}' is not a step", step)))?;
+                if step == 0 {
+                    return Err(invalid("step must be at least 1".to_string()));
+                }
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` runs from 5 to the end of the range
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let single = value(range)?;
+                    (single, single)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid(format!("range {}-{} is reversed", start, end)));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_next_after() {
+        let weekdays = CronSchedule::parse("30 9 * * MON-FRI").unwrap();
+        // Friday 2026-10-16 after 09:30 -> Monday
+        assert_eq!(
+            weekdays.next_after(at("2026-10-16T09:30:00Z")),
+            Some(at("2026-10-19T09:30:00Z"))
+        );
+
+        let quarter_hours = CronSchedule::parse("*/15 8-18/2 * * *").unwrap();
+        assert_eq!(
+            quarter_hours.next_after(at("2026-10-17T09:59:10Z")),
+            Some(at("2026-10-17T10:00:00Z"))
+        );
+        assert_eq!(
+            quarter_hours.next_after(at("2026-10-17T18:45:00Z")),
+            Some(at("2026-10-18T08:00:00Z"))
+        );
+
+        let yearly = CronSchedule::parse("@yearly").unwrap();
+        assert_eq!(
+            yearly.next_after(at("2026-10-17T00:00:00Z")),
+            Some(at("2027-01-01T00:00:00Z"))
+        );
+
+        // Day of month or Sunday (7)
+        let either = CronSchedule::parse("0 0 1 * 7").unwrap();
+        assert_eq!(
+            either.next_after(at("2026-10-17T12:00:00Z")),
+            Some(at("2026-10-18T00:00:00Z"))
+        );
+
+        let never = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(never.next_after(at("2026-10-17T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expression in [
+            "* * * *",
+            "60 * * * *",
+            "* * * * MON-",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "{} should not parse",
+                expression
+            );
+        }
+        assert_eq!(
+            "0 6 * * *".parse::<CronSchedule>().unwrap().to_string(),
+            "0 6 * * *"
+        );
+    }
+}
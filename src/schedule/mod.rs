@@ -0,0 +1,523 @@
+//! Recurring agent executions.
+//!
+//! A [`Scheduler`] runs [`ScheduledTask`]s — a prompt and a factory that
+//! builds the agent to run it — on a fixed interval or at the times of a
+//! cron expression, inside the application's existing tokio runtime. Each
+//! run gets a fresh agent, so recurring runs don't accumulate history.
+//!
+//! When a run is due while the previous one is still going, the task's
+//! [`OverlapPolicy`] decides whether it is skipped, queued behind it, or run
+//! alongside it. Every finished run is sent to the task's notification
+//! sinks; use a sink's `failures_only()` to be alerted only when a run fails,
+//! or let a sink without it deliver each report:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use stood::agent::callbacks::{PayloadTemplate, SlackSink, WebhookSink};
+//! use stood::agent::Agent;
+//! use stood::schedule::{OverlapPolicy, ScheduledTask, Scheduler, Trigger};
+//!
+//! # async fn example() -> Result<(), stood::StoodError> {
+//! let scheduler = Scheduler::new()
+//!     .with_task(
+//!         ScheduledTask::new(
+//!             "daily-sales-report",
+//!             Trigger::cron("0 7 * * MON-FRI")?,
+//!             "Write yesterday's sales report",
+//!             || async { Agent::builder().build().await },
+//!         )
+//!         .with_timeout(Duration::from_secs(900))
+//!         .with_notification_sink(WebhookSink::new("https://example.com/hooks/reports"))
+//!         .with_notification_sink(
+//!             SlackSink::new("https://hooks.slack.com/services/T000/B000/XXXX")
+//!                 .with_template(PayloadTemplate::text("Sales report failed: {{error}}"))
+//!                 .failures_only(),
+//!         ),
+//!     )
+//!     .with_task(
+//!         ScheduledTask::new(
+//!             "queue-check",
+//!             Trigger::every(Duration::from_secs(300)),
+//!             "Check the support queue for tickets waiting over an hour",
+//!             || async { Agent::builder().build().await },
+//!         )
+//!         .with_overlap(OverlapPolicy::Skip),
+//!     );
+//! let handle = scheduler.start();
+//!
+//! // On shutdown; runs in progress finish
+//! scheduler.stop();
+//! let _ = handle.await;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod cron;
+
+pub use cron::CronSchedule;
+
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::agent::callbacks::{Notification, NotificationCallbackHandler, NotificationSink};
+use crate::agent::pool::AgentFactory;
+use crate::agent::{Agent, AgentResult};
+use crate::{Result, StoodError};
+
+/// When a scheduled task runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    /// Every interval, the first run one interval after the scheduler starts
+    Every(Duration),
+    /// At the times matching a cron expression, in UTC
+    Cron(CronSchedule),
+}
+
+impl Trigger {
+    pub fn every(interval: Duration) -> Self {
+        Self::Every(interval)
+    }
+
+    /// Parse a cron expression; see [`CronSchedule`] for the syntax
+    pub fn cron(expression: &str) -> Result<Self> {
+        CronSchedule::parse(expression).map(Self::Cron)
+    }
+
+    /// The first run time strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(interval) => {
+                let interval = (*interval).max(Duration::from_millis(1));
+                chrono::Duration::from_std(interval)
+                    .ok()
+                    .map(|interval| after + interval)
+            }
+            Self::Cron(schedule) => schedule.next_after(after),
+        }
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Every(interval) => write!(f, "every {:?}", interval),
+            Self::Cron(schedule) => write!(f, "cron '{}'", schedule),
+        }
+    }
+}
+
+/// What to do when a run is due while the previous one is still running
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Skip the run
+    #[default]
+    Skip,
+    /// Start the run when the previous one finishes; at most one run waits
+    Queue,
+    /// Start the run alongside the previous one
+    Allow,
+}
+
+/// A prompt run on a schedule
+#[derive(Clone)]
+pub struct ScheduledTask {
+    name: String,
+    prompt: String,
+    trigger: Trigger,
+    factory: AgentFactory,
+    overlap: OverlapPolicy,
+    timeout: Option<Duration>,
+    run_on_start: bool,
+    sinks: Vec<Arc<dyn NotificationSink>>,
+}
+
+impl ScheduledTask {
+    /// Run `prompt` on an agent built by `factory` whenever `trigger` fires
+    pub fn new<F, Fut>(
+        name: impl Into<String>,
+        trigger: Trigger,
+        prompt: impl Into<String>,
+        factory: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Agent>> + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            prompt: prompt.into(),
+            trigger,
+            factory: Arc::new(move || Box::pin(factory())),
+            overlap: OverlapPolicy::default(),
+            timeout: None,
+            run_on_start: false,
+            sinks: Vec::new(),
+        }
+    }
+
+    pub fn with_overlap(mut self, overlap: OverlapPolicy) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Fail runs, including building the agent, that take longer than `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run once as soon as the scheduler starts, then on the trigger
+    pub fn with_run_on_start(mut self, run_on_start: bool) -> Self {
+        self.run_on_start = run_on_start;
+        self
+    }
+
+    /// Send a notification to `sink` when a run completes or fails
+    pub fn with_notification_sink<S: NotificationSink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn trigger(&self) -> &Trigger {
+        &self.trigger
+    }
+}
+
+impl fmt::Debug for ScheduledTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScheduledTask")
+            .field("name", &self.name)
+            .field("trigger", &self.trigger)
+            .field("overlap", &self.overlap)
+            .field("timeout", &self.timeout)
+            .field("run_on_start", &self.run_on_start)
+            .field("sinks", &self.sinks)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Run history of a scheduled task
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    /// When the task runs next; `None` before the scheduler starts, after it
+    /// stops, or when a cron expression never matches again
+    pub next_run: Option<DateTime<Utc>>,
+    /// When the last run started
+    pub last_run: Option<DateTime<Utc>>,
+    /// Runs in progress
+    pub running: usize,
+    /// Finished runs, including failed ones
+    pub runs: u64,
+    pub failures: u64,
+    /// Runs skipped because of the overlap policy
+    pub skipped: u64,
+    /// Error of the last failed run
+    pub last_error: Option<String>,
+}
+
+/// Runs scheduled tasks in the background
+///
+/// Clones share the same tasks. Task names should be unique;
+/// [`status`](Self::status) reports the first task with a name.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    tasks: Vec<Arc<TaskState>>,
+    shutdown: CancellationToken,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a task; tasks added after [`start`](Self::start) are not run
+    pub fn with_task(mut self, task: ScheduledTask) -> Self {
+        let status = TaskStatus {
+            name: task.name.clone(),
+            ..TaskStatus::default()
+        };
+        self.tasks.push(Arc::new(TaskState {
+            notifications: NotificationCallbackHandler::new(task.sinks.clone()),
+            task,
+            status: Mutex::new(status),
+            run_lock: Arc::new(tokio::sync::Mutex::new(())),
+            queued: AtomicBool::new(false),
+        }));
+        self
+    }
+
+    /// Start running the tasks on their triggers
+    ///
+    /// The returned handle completes after [`stop`](Self::stop), or once no
+    /// task will run again.
+    pub fn start(&self) -> crate::runtime::JoinHandle<()> {
+        let tasks = self.tasks.clone();
+        let shutdown = self.shutdown.clone();
+        crate::runtime::spawn(async move {
+            let schedules = tasks
+                .into_iter()
+                .map(|task| task.schedule(shutdown.clone()));
+            futures::future::join_all(schedules).await;
+        })
+    }
+
+    /// Stop scheduling runs; runs in progress and queued runs finish
+    pub fn stop(&self) {
+        self.shutdown.cancel();
+    }
+
+    /// Run history of the task named `name`
+    pub fn status(&self, name: &str) -> Option<TaskStatus> {
+        self.tasks
+            .iter()
+            .find(|state| state.task.name == name)
+            .map(|state| state.status().clone())
+    }
+
+    /// Run history of every task, in the order they were added
+    pub fn statuses(&self) -> Vec<TaskStatus> {
+        self.tasks
+            .iter()
+            .map(|state| state.status().clone())
+            .collect()
+    }
+}
+
+impl fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("tasks", &self.statuses())
+            .field("stopped", &self.shutdown.is_cancelled())
+            .finish()
+    }
+}
+
+struct TaskState {
+    task: ScheduledTask,
+    notifications: NotificationCallbackHandler,
+    status: Mutex<TaskStatus>,
+    /// Held by runs under the skip and queue overlap policies
+    run_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Whether a queued run is waiting for the lock
+    queued: AtomicBool,
+}
+
+impl TaskState {
+    fn status(&self) -> MutexGuard<'_, TaskStatus> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn schedule(self: Arc<Self>, shutdown: CancellationToken) {
+        let trigger = &self.task.trigger;
+        let mut next = if self.task.run_on_start {
+            Some(Utc::now())
+        } else {
+            trigger.next_after(Utc::now())
+        };
+        while let Some(at) = next {
+            self.status().next_run = Some(at);
+            let wait = (at - Utc::now()).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = crate::runtime::sleep(wait) => {}
+            }
+            self.clone().fire();
+
+            // Runs missed while the host was suspended are not caught up
+            let now = Utc::now();
+            next = trigger
+                .next_after(at)
+                .filter(|next| *next > now)
+                .or_else(|| trigger.next_after(now));
+        }
+        self.status().next_run = None;
+        tracing::debug!("Stopped scheduling task '{}'", self.task.name);
+    }
+
+    /// Start a run, following the overlap policy
+    fn fire(self: Arc<Self>) {
+        match self.task.overlap {
+            OverlapPolicy::Allow => {
+                crate::runtime::spawn(async move { self.run().await });
+            }
+            OverlapPolicy::Skip => match self.run_lock.clone().try_lock_owned() {
+                Ok(guard) => {
+                    crate::runtime::spawn(async move {
+                        self.run().await;
+                        drop(guard);
+                    });
+                }
+                Err(_) => self.skip(),
+            },
+            OverlapPolicy::Queue => {
+                if self.queued.swap(true, Ordering::SeqCst) {
+                    self.skip();
+                    return;
+                }
+                crate::runtime::spawn(async move {
+                    let guard = self.run_lock.clone().lock_owned().await;
+                    self.queued.store(false, Ordering::SeqCst);
+                    self.run().await;
+                    drop(guard);
+                });
+            }
+        }
+    }
+
+    fn skip(&self) {
+        tracing::info!(
+            "Skipping run of task '{}': the previous run is still in progress",
+            self.task.name
+        );
+        self.status().skipped += 1;
+    }
+
+    async fn run(&self) {
+        let started_at = Instant::now();
+        {
+            let mut status = self.status();
+            status.running += 1;
+            status.last_run = Some(Utc::now());
+        }
+        tracing::debug!("Running scheduled task '{}'", self.task.name);
+
+        let (result, error) = match self.execute().await {
+            Ok(result) => (result, None),
+            Err(e) => (
+                AgentResult::error(e.to_string(), started_at.elapsed()),
+                Some(e),
+            ),
+        };
+        let notification = Notification::from_result(&result, error.as_ref());
+        {
+            let mut status = self.status();
+            status.running -= 1;
+            status.runs += 1;
+            if notification.is_failure() {
+                tracing::warn!(
+                    "Scheduled task '{}' failed: {}",
+                    self.task.name,
+                    notification.error.as_deref().unwrap_or("unknown error")
+                );
+                status.failures += 1;
+                status.last_error = notification.error.clone();
+            }
+        }
+        self.notifications.dispatch(&notification).await;
+    }
+
+    async fn execute(&self) -> Result<AgentResult> {
+        let run = async {
+            let mut agent = (self.task.factory)().await?;
+            // Boxed: the execution future is too large for a task's stack
+            Box::pin(agent.execute(self.task.prompt.as_str())).await
+        };
+        match self.task.timeout {
+            Some(timeout) => crate::runtime::timeout(timeout, run)
+                .await
+                .unwrap_or_else(|_| Err(StoodError::timeout_error(timeout.as_millis() as u64))),
+            None => run.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Records the notifications it receives
+    #[derive(Debug, Default, Clone)]
+    struct Recorder(Arc<Mutex<Vec<Notification>>>);
+
+    #[async_trait]
+    impl NotificationSink for Recorder {
+        async fn notify(&self, notification: &Notification) -> Result<()> {
+            self.0.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    /// A task whose agent fails to build after `delay`
+    fn failing_task(name: &str, delay: Duration) -> ScheduledTask {
+        ScheduledTask::new(
+            name,
+            Trigger::every(Duration::from_millis(20)),
+            "Write the report",
+            move || async move {
+                tokio::time::sleep(delay).await;
+                Err(StoodError::configuration_error("No model credentials"))
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_failed_runs_are_counted_and_notified() {
+        let recorder = Recorder::default();
+        let scheduler = Scheduler::new().with_task(
+            failing_task("report", Duration::ZERO)
+                .with_run_on_start(true)
+                .with_notification_sink(recorder.clone()),
+        );
+        let handle = scheduler.start();
+        tokio::time::sleep(Duration::from_millis(70)).await;
+        scheduler.stop();
+        handle.await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let status = scheduler.status("report").unwrap();
+        assert!(status.runs >= 2, "{:?}", status);
+        assert_eq!(status.failures, status.runs);
+        assert_eq!(status.next_run, None);
+        assert!(status.last_error.unwrap().contains("No model credentials"));
+
+        let notifications = recorder.0.lock().unwrap();
+        assert_eq!(notifications.len() as u64, status.runs);
+        assert!(notifications.iter().all(Notification::is_failure));
+        assert_eq!(
+            notifications[0].error_kind.as_deref(),
+            Some("ConfigurationError")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overlap_policies() {
+        let scheduler = Scheduler::new()
+            .with_task(failing_task("skip", Duration::from_millis(70)))
+            .with_task(
+                failing_task("queue", Duration::from_millis(70)).with_overlap(OverlapPolicy::Queue),
+            )
+            .with_task(
+                failing_task("allow", Duration::from_millis(70)).with_overlap(OverlapPolicy::Allow),
+            );
+        let handle = scheduler.start();
+        tokio::time::sleep(Duration::from_millis(110)).await;
+        scheduler.stop();
+        handle.await.unwrap();
+
+        let skip = scheduler.status("skip").unwrap();
+        assert_eq!(skip.running, 1);
+        assert!(skip.skipped >= 2, "{:?}", skip);
+
+        // One run in progress, one waiting behind it, the rest skipped
+        let queue = scheduler.status("queue").unwrap();
+        assert_eq!(queue.running, 1);
+        assert!(queue.skipped >= 1, "{:?}", queue);
+
+        let allow = scheduler.status("allow").unwrap();
+        assert!(allow.running >= 3, "{:?}", allow);
+        assert_eq!(allow.skipped, 0);
+    }
+}
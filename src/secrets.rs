@@ -0,0 +1,408 @@
+//! Scoped secret and environment variable resolution for tools.
+//!
+//! Tools that need configuration values or credentials resolve them through a
+//! [`SecretsProvider`] carried on the [`AgentContext`](crate::agent::AgentContext)
+//! instead of reading the process environment directly. Providers are available
+//! for environment variables ([`EnvSecretsProvider`]) and, with the
+//! `aws-secrets` feature, AWS Secrets Manager (`AwsSecretsManagerProvider`)
+//! and SSM Parameter Store (`SsmParameterProvider`).
+//!
+//! Wrap a provider in [`ScopedSecrets`] to restrict which names can be resolved.
+//! Names outside the allowlist resolve to `None`, exactly like missing values, so
+//! the model can neither read nor probe for variables such as
+//! `AWS_SECRET_ACCESS_KEY`. Agents without a configured provider use
+//! [`ScopedSecrets::default_env`], which only exposes a few harmless variables.
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use stood::agent::Agent;
+//! use stood::secrets::{AwsSecretsManagerProvider, ScopedSecrets};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let secrets = ScopedSecrets::new(Arc::new(
+//!     AwsSecretsManagerProvider::new("us-east-1").with_prefix("my-app/"),
+//! ))
+//! .allow("GITHUB_TOKEN")
+//! .allow("DATABASE_*");
+//!
+//! let agent = Agent::builder()
+//!     .with_builtin_tools()
+//!     .with_secrets(Arc::new(secrets))
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+#[cfg(feature = "aws-secrets")]
+use aws_sdk_secretsmanager::config::http::HttpResponse;
+#[cfg(feature = "aws-secrets")]
+use aws_sdk_secretsmanager::error::{DisplayErrorContext, SdkError};
+
+#[cfg(feature = "aws-secrets")]
+use crate::llm::http::LazyAwsClient;
+
+/// Environment variables readable when no secrets provider is configured
+pub const DEFAULT_ENV_ALLOWLIST: &[&str] = &[
+    "HOME", "LANG", "LC_ALL", "PATH", "PWD", "SHELL", "TERM", "TZ", "USER",
+];
+
+/// Errors that occur while resolving a secret
+#[derive(Debug, thiserror::Error)]
+pub enum SecretsError {
+    /// The client for the secret backend could not be configured
+    #[error("Configuration error: {0}")]
+    Configuration(String),
+
+    /// The backend could not be reached
+    #[error("Network error: {0}")]
+    Network(String),
+
+    /// The backend rejected the request
+    #[error("{backend} error: {message}")]
+    Backend { backend: String, message: String },
+}
+
+/// Source of secrets and configuration values for tools
+#[async_trait]
+pub trait SecretsProvider: Send + Sync + std::fmt::Debug {
+    /// Resolve a secret by name, returning `Ok(None)` if it does not exist
+    async fn get_secret(&self, name: &str) -> Result<Option<String>, SecretsError>;
+
+    /// Name of the backend for logging
+    fn name(&self) -> &str;
+}
+
+/// Reads secrets from process environment variables
+#[derive(Debug, Clone, Default)]
+pub struct EnvSecretsProvider;
+
+impl EnvSecretsProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for EnvSecretsProvider {
+    async fn get_secret(&self, name: &str) -> Result<Option<String>, SecretsError> {
+        Ok(std::env::var(name).ok())
+    }
+
+    fn name(&self) -> &str {
+        "env"
+    }
+}
+
+/// Restricts an inner provider to an allowlist of names.
+///
+/// Patterns are exact names or prefixes ending in `*` (`"APP_*"`). Requests for
+/// names that are not allowed return `Ok(None)` and are logged.
+#[derive(Debug, Clone)]
+pub struct ScopedSecrets {
+    inner: Arc<dyn SecretsProvider>,
+    allowlist: Vec<String>,
+}
+
+impl ScopedSecrets {
+    /// Wrap a provider with an empty allowlist (nothing can be read)
+    pub fn new(inner: Arc<dyn SecretsProvider>) -> Self {
+        Self {
+            inner,
+            allowlist: Vec::new(),
+        }
+    }
+
+    /// Environment variables restricted to [`DEFAULT_ENV_ALLOWLIST`]
+    pub fn default_env() -> Self {
+        Self::new(Arc::new(EnvSecretsProvider)).allow_all(DEFAULT_ENV_ALLOWLIST.iter().copied())
+    }
+
+    /// Allow a name or `PREFIX*` pattern
+    pub fn allow(mut self, pattern: impl Into<String>) -> Self {
+        self.allowlist.push(pattern.into());
+        self
+    }
+
+    /// Allow several names or patterns
+    pub fn allow_all<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowlist.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Whether `name` matches the allowlist
+    pub fn is_allowed(&self, name: &str) -> bool {
+        self.allowlist
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => pattern == name,
+            })
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for ScopedSecrets {
+    async fn get_secret(&self, name: &str) -> Result<Option<String>, SecretsError> {
+        if !self.is_allowed(name) {
+            tracing::warn!(
+                secret = name,
+                backend = self.inner.name(),
+                "Denied access to secret outside the allowlist"
+            );
+            return Ok(None);
+        }
+        self.inner.get_secret(name).await
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+/// Classify a failed AWS request: errors returned by the service are backend
+/// errors, everything else is a network error
+#[cfg(feature = "aws-secrets")]
+fn sdk_error<E>(backend: &str, error: SdkError<E, HttpResponse>) -> SecretsError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    match error {
+        SdkError::ServiceError(e) => SecretsError::Backend {
+            backend: backend.to_string(),
+            message: format!(
+                "HTTP {}: {}",
+                e.raw().status().as_u16(),
+                DisplayErrorContext(e.err())
+            ),
+        },
+        e => SecretsError::Network(DisplayErrorContext(&e).to_string()),
+    }
+}
+
+/// Reads secrets from AWS Secrets Manager (`GetSecretValue`, requires the
+/// `aws-secrets` feature).
+///
+/// The secret id is the optional prefix followed by the requested name. Only
+/// `SecretString` values are supported.
+#[cfg(feature = "aws-secrets")]
+#[derive(Debug, Clone)]
+pub struct AwsSecretsManagerProvider {
+    client: LazyAwsClient<aws_sdk_secretsmanager::Client>,
+    prefix: String,
+}
+
+#[cfg(feature = "aws-secrets")]
+impl AwsSecretsManagerProvider {
+    /// Create a provider for a region using the default AWS credential chain
+    pub fn new(region: impl AsRef<str>) -> Self {
+        Self {
+            client: LazyAwsClient::new(region.as_ref()),
+            prefix: String::new(),
+        }
+    }
+
+    /// Use an existing Secrets Manager client
+    pub fn from_client(client: aws_sdk_secretsmanager::Client) -> Self {
+        Self {
+            client: LazyAwsClient::from_client(client),
+            prefix: String::new(),
+        }
+    }
+
+    /// Prefix prepended to every secret name (e.g. `"prod/my-app/"`)
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+#[cfg(feature = "aws-secrets")]
+#[async_trait]
+impl SecretsProvider for AwsSecretsManagerProvider {
+    async fn get_secret(&self, name: &str) -> Result<Option<String>, SecretsError> {
+        let client = self
+            .client
+            .get(aws_sdk_secretsmanager::Client::new)
+            .await
+            .map_err(|e| SecretsError::Configuration(e.to_string()))?;
+        let result = client
+            .get_secret_value()
+            .secret_id(format!("{}{}", self.prefix, name))
+            .send()
+            .await;
+        match result {
+            Ok(output) => Ok(output.secret_string().map(str::to_string)),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_resource_not_found_exception()) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(sdk_error(self.name(), e)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "secretsmanager"
+    }
+}
+
+/// Reads secrets from SSM Parameter Store (`GetParameter` with decryption,
+/// requires the `aws-secrets` feature)
+#[cfg(feature = "aws-secrets")]
+#[derive(Debug, Clone)]
+pub struct SsmParameterProvider {
+    client: LazyAwsClient<aws_sdk_ssm::Client>,
+    path_prefix: String,
+}
+
+#[cfg(feature = "aws-secrets")]
+impl SsmParameterProvider {
+    /// Create a provider for a region using the default AWS credential chain
+    pub fn new(region: impl AsRef<str>) -> Self {
+        Self {
+            client: LazyAwsClient::new(region.as_ref()),
+            path_prefix: String::new(),
+        }
+    }
+
+    /// Use an existing SSM client
+    pub fn from_client(client: aws_sdk_ssm::Client) -> Self {
+        Self {
+            client: LazyAwsClient::from_client(client),
+            path_prefix: String::new(),
+        }
+    }
+
+    /// Path prepended to every parameter name (e.g. `"/my-app/prod/"`)
+    pub fn with_path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = prefix.into();
+        self
+    }
+}
+
+#[cfg(feature = "aws-secrets")]
+#[async_trait]
+impl SecretsProvider for SsmParameterProvider {
+    async fn get_secret(&self, name: &str) -> Result<Option<String>, SecretsError> {
+        let client = self
+            .client
+            .get(aws_sdk_ssm::Client::new)
+            .await
+            .map_err(|e| SecretsError::Configuration(e.to_string()))?;
+        let result = client
+            .get_parameter()
+            .name(format!("{}{}", self.path_prefix, name))
+            .with_decryption(true)
+            .send()
+            .await;
+        match result {
+            Ok(output) => Ok(output
+                .parameter()
+                .and_then(|parameter| parameter.value())
+                .map(str::to_string)),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_parameter_not_found()) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(sdk_error(self.name(), e)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "ssm"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scoped_secrets_allowlist() {
+        std::env::set_var("STOOD_SECRETS_TEST_VALUE", "visible");
+        std::env::set_var("STOOD_SECRETS_HIDDEN", "hidden");
+        let secrets =
+            ScopedSecrets::new(Arc::new(EnvSecretsProvider)).allow("STOOD_SECRETS_TEST_*");
+
+        assert!(secrets.is_allowed("STOOD_SECRETS_TEST_VALUE"));
+        assert!(!secrets.is_allowed("AWS_SECRET_ACCESS_KEY"));
+        assert_eq!(
+            secrets
+                .get_secret("STOOD_SECRETS_TEST_VALUE")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("visible")
+        );
+        assert_eq!(
+            secrets.get_secret("STOOD_SECRETS_HIDDEN").await.unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_default_env_excludes_credentials() {
+        let secrets = ScopedSecrets::default_env();
+        assert!(secrets.is_allowed("HOME"));
+        assert!(!secrets.is_allowed("AWS_SECRET_ACCESS_KEY"));
+        assert!(!secrets.is_allowed("AWS_SESSION_TOKEN"));
+    }
+
+    #[cfg(feature = "aws-secrets")]
+    #[tokio::test]
+    async fn test_secrets_manager_responses() {
+        use aws_sdk_secretsmanager::config::{BehaviorVersion, Credentials, Region};
+        use aws_smithy_http_client::test_util::infallible_client_fn;
+
+        let http_client = infallible_client_fn(|request| {
+            let body = String::from_utf8_lossy(request.body().bytes().unwrap_or_default());
+            let (status, payload) = if body.contains("app/missing") {
+                (
+                    400,
+                    r#"{"__type":"ResourceNotFoundException","message":"Secrets Manager can't find the specified secret."}"#,
+                )
+            } else if body.contains("app/blocked") {
+                (403, "<html><body>Forbidden by proxy</body></html>")
+            } else {
+                (200, r#"{"Name":"app/token","SecretString":"s3cret"}"#)
+            };
+            http::Response::builder()
+                .status(status)
+                .body(payload)
+                .unwrap()
+        });
+        let config = aws_sdk_secretsmanager::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .credentials_provider(Credentials::new("AKID", "SECRET", None, None, "test"))
+            .http_client(http_client)
+            .build();
+        let provider = AwsSecretsManagerProvider::from_client(
+            aws_sdk_secretsmanager::Client::from_conf(config),
+        )
+        .with_prefix("app/");
+
+        assert_eq!(
+            provider.get_secret("token").await.unwrap().as_deref(),
+            Some("s3cret")
+        );
+        assert_eq!(provider.get_secret("missing").await.unwrap(), None);
+        let error = provider.get_secret("blocked").await.unwrap_err();
+        assert!(
+            matches!(&error, SecretsError::Backend { message, .. } if message.starts_with("HTTP 403")),
+            "{:?}",
+            error
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Tracking of in-flight agent executions for graceful shutdown.
+//!
+//! Agents built with [`AgentBuilder::with_shutdown`] register every execution
+//! with an [`InFlightExecutions`] tracker. When the [`ShutdownManager`] shuts
+//! down it stops new executions from starting, waits for the running ones to
+//! finish their cycles, cancels whatever is still running after
+//! [`ShutdownConfig::request_timeout`] (cancelled executions return their
+//! partial results), and flushes the telemetry of executions that never
+//! finished.
+//!
+//! [`AgentBuilder::with_shutdown`]: crate::agent::AgentBuilder::with_shutdown
+//! [`ShutdownManager`]: super::ShutdownManager
+//! [`ShutdownConfig::request_timeout`]: super::ShutdownConfig::request_timeout
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+use crate::telemetry::StoodTracer;
+use crate::StoodError;
+
+/// An execution that has not finished yet
+#[derive(Debug, Clone)]
+pub struct InFlightExecution {
+    /// Id of the agent running the execution
+    pub agent_id: String,
+    /// When the execution started
+    pub started_at: Instant,
+}
+
+struct Entry {
+    execution: InFlightExecution,
+    cancellation: Option<CancellationToken>,
+    tracer: Option<StoodTracer>,
+}
+
+struct Inner {
+    accepting: AtomicBool,
+    next_id: AtomicU64,
+    active: Mutex<HashMap<u64, Entry>>,
+    idle: Notify,
+}
+
+/// Registry of running agent executions
+///
+/// Clones share the same executions.
+#[derive(Clone)]
+pub struct InFlightExecutions {
+    inner: Arc<Inner>,
+}
+
+impl Default for InFlightExecutions {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                accepting: AtomicBool::new(true),
+                next_id: AtomicU64::new(0),
+                active: Mutex::new(HashMap::new()),
+                idle: Notify::new(),
+            }),
+        }
+    }
+}
+
+impl InFlightExecutions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an execution of `agent_id`
+    ///
+    /// `cancellation` is triggered if the execution is still running when the
+    /// shutdown deadline passes. Fails with
+    /// [`StoodError::ServiceUnavailable`] once shutdown has started.
+    pub fn begin(
+        &self,
+        agent_id: impl Into<String>,
+        cancellation: Option<CancellationToken>,
+        tracer: Option<StoodTracer>,
+    ) -> Result<ExecutionGuard, StoodError> {
+        if !self.is_accepting() {
+            return Err(StoodError::ServiceUnavailable {
+                message: "Shutting down, not accepting new executions".to_string(),
+            });
+        }
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().insert(
+            id,
+            Entry {
+                execution: InFlightExecution {
+                    agent_id: agent_id.into(),
+                    started_at: Instant::now(),
+                },
+                cancellation,
+                tracer,
+            },
+        );
+        Ok(ExecutionGuard {
+            executions: self.clone(),
+            id,
+        })
+    }
+
+    /// Refuse new executions from now on
+    pub fn stop_accepting(&self) {
+        self.inner.accepting.store(false, Ordering::Release);
+    }
+
+    /// Whether new executions may start
+    pub fn is_accepting(&self) -> bool {
+        self.inner.accepting.load(Ordering::Acquire)
+    }
+
+    /// Number of running executions
+    pub fn count(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// The running executions, oldest first
+    pub fn executions(&self) -> Vec<InFlightExecution> {
+        let mut executions: Vec<InFlightExecution> =
+            self.lock().values().map(|e| e.execution.clone()).collect();
+        executions.sort_by_key(|e| e.started_at);
+        executions
+    }
+
+    /// Wait until no execution is running or `timeout` elapses
+    ///
+    /// Returns whether all executions finished.
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let wait = async {
+            loop {
+                let notified = self.inner.idle.notified();
+                if self.count() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        crate::runtime::timeout(timeout, wait).await.is_ok()
+    }
+
+    /// Trigger the cancellation token of every running execution
+    ///
+    /// Returns the number of executions cancelled; executions without a token
+    /// keep running.
+    pub fn cancel_all(&self) -> usize {
+        let active = self.lock();
+        let mut cancelled = 0;
+        for token in active.values().filter_map(|e| e.cancellation.as_ref()) {
+            token.cancel();
+            cancelled += 1;
+        }
+        cancelled
+    }
+
+    /// Export the pending telemetry of executions that are still running
+    pub async fn flush_telemetry(&self) {
+        let tracers: Vec<StoodTracer> = self
+            .lock()
+            .values()
+            .filter_map(|e| e.tracer.clone())
+            .collect();
+        for tracer in tracers {
+            if let Err(e) = tracer.flush().await {
+                tracing::warn!("Failed to flush telemetry of in-flight execution: {}", e);
+            }
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        let remaining = {
+            let mut active = self.lock();
+            active.remove(&id);
+            active.len()
+        };
+        if remaining == 0 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Entry>> {
+        self.inner.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for InFlightExecutions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InFlightExecutions")
+            .field("accepting", &self.is_accepting())
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+/// Marks an execution as running until dropped
+#[derive(Debug)]
+pub struct ExecutionGuard {
+    executions: InFlightExecutions,
+    id: u64,
+}
+
+impl Drop for ExecutionGuard {
+    fn drop(&mut self) {
+        self.executions.finish(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_waits_for_executions_and_refuses_new_ones() {
+        let executions = InFlightExecutions::new();
+        let token = CancellationToken::new();
+        let guard = executions
+            .begin("agent-1", Some(token.clone()), None)
+            .unwrap();
+        assert_eq!(executions.count(), 1);
+
+        executions.stop_accepting();
+        assert!(matches!(
+            executions.begin("agent-2", None, None),
+            Err(StoodError::ServiceUnavailable { .. })
+        ));
+        assert!(!executions.wait_idle(Duration::from_millis(10)).await);
+
+        assert_eq!(executions.cancel_all(), 1);
+        assert!(token.is_cancelled());
+
+        let finisher = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(guard);
+        });
+        assert!(executions.wait_idle(Duration::from_secs(1)).await);
+        finisher.await.unwrap();
+        assert!(executions.executions().is_empty());
+    }
+}
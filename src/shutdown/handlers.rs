@@ -89,12 +89,11 @@ impl ShutdownHandler for TelemetryShutdownHandler {
         debug!("Shutting down telemetry system");
 
         // Flush and shutdown OpenTelemetry
-        use opentelemetry::global;
-
         // Force flush with timeout
         let flush_result = timeout(self.flush_timeout, async {
             // Flush tracer provider
-            global::shutdown_tracer_provider();
+            #[cfg(feature = "telemetry")]
+            opentelemetry::global::shutdown_tracer_provider();
 
             info!("Telemetry system flushed and shut down successfully");
             Ok(())
@@ -18,7 +18,9 @@ use tokio::{
 use tracing::{debug, error, info, warn};
 
 // Import built-in shutdown handlers
+pub mod executions;
 pub mod handlers;
+pub use executions::{ExecutionGuard, InFlightExecution, InFlightExecutions};
 pub use handlers::*;
 
 /// Shutdown signals and reasons
@@ -47,6 +49,10 @@ pub struct ShutdownConfig {
     pub force_after_timeout: bool,
     /// Enable telemetry flush during shutdown
     pub flush_telemetry: bool,
+    /// Cancel executions still running after `request_timeout`
+    pub cancel_in_flight: bool,
+    /// Time cancelled executions get to return their partial results
+    pub cancel_grace_period: Duration,
 }
 
 impl Default for ShutdownConfig {
@@ -57,6 +63,8 @@ impl Default for ShutdownConfig {
             cleanup_timeout: Duration::from_secs(10),
             force_after_timeout: true,
             flush_telemetry: true,
+            cancel_in_flight: true,
+            cancel_grace_period: Duration::from_secs(5),
         }
     }
 }
@@ -68,6 +76,7 @@ pub struct ShutdownManager {
     is_shutting_down: Arc<AtomicBool>,
     shutdown_complete: Arc<Notify>,
     cleanup_handlers: Vec<Box<dyn ShutdownHandler>>,
+    executions: InFlightExecutions,
 }
 
 /// Trait for components that need cleanup during shutdown
@@ -107,6 +116,7 @@ impl ShutdownManager {
             is_shutting_down: Arc::new(AtomicBool::new(false)),
             shutdown_complete: Arc::new(Notify::new()),
             cleanup_handlers: Vec::new(),
+            executions: InFlightExecutions::new(),
         }
     }
 
@@ -120,6 +130,12 @@ impl ShutdownManager {
         self.is_shutting_down.load(Ordering::Acquire)
     }
 
+    /// Tracker of in-flight agent executions, for
+    /// [`AgentBuilder::with_shutdown`](crate::agent::AgentBuilder::with_shutdown)
+    pub fn executions(&self) -> InFlightExecutions {
+        self.executions.clone()
+    }
+
     /// Register a shutdown handler
     pub fn register_handler(&mut self, handler: Box<dyn ShutdownHandler>) {
         debug!("Registering shutdown handler: {}", handler.name());
@@ -130,6 +146,7 @@ impl ShutdownManager {
     pub async fn listen_for_signals(&self) {
         let shutdown_sender = self.shutdown_sender.clone();
         let is_shutting_down = self.is_shutting_down.clone();
+        let executions = self.executions.clone();
 
         tokio::spawn(async move {
             #[cfg(unix)]
@@ -143,11 +160,13 @@ impl ShutdownManager {
                     _ = sigterm.recv() => {
                         info!("Received SIGTERM, initiating graceful shutdown");
                         is_shutting_down.store(true, Ordering::Release);
+                        executions.stop_accepting();
                         let _ = shutdown_sender.send(ShutdownReason::Graceful);
                     }
                     _ = sigint.recv() => {
                         info!("Received SIGINT, initiating immediate shutdown");
                         is_shutting_down.store(true, Ordering::Release);
+                        executions.stop_accepting();
                         let _ = shutdown_sender.send(ShutdownReason::Immediate);
                     }
                 }
@@ -160,6 +179,7 @@ impl ShutdownManager {
                     Ok(()) => {
                         info!("Received Ctrl+C, initiating graceful shutdown");
                         is_shutting_down.store(true, Ordering::Release);
+                        executions.stop_accepting();
                         let _ = shutdown_sender.send(ShutdownReason::Graceful);
                     }
                     Err(err) => {
@@ -178,6 +198,7 @@ impl ShutdownManager {
             .is_ok()
         {
             info!("Initiating shutdown: {:?}", reason);
+            self.executions.stop_accepting();
             let _ = self.shutdown_sender.send(reason);
         } else {
             debug!("Shutdown already in progress");
@@ -232,6 +253,9 @@ impl ShutdownManager {
         &mut self,
         reason: ShutdownReason,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Step 0: Let in-flight executions finish before tearing anything down
+        self.drain_executions(&reason).await;
+
         // Step 1: Sort handlers by priority
         self.cleanup_handlers.sort_by_key(|h| h.priority());
 
@@ -284,6 +308,53 @@ impl ShutdownManager {
         }
     }
 
+    /// Stop new executions and wait for running ones, cancelling them after
+    /// `request_timeout` (immediately for [`ShutdownReason::Immediate`])
+    async fn drain_executions(&self, reason: &ShutdownReason) {
+        self.executions.stop_accepting();
+        let in_flight = self.executions.count();
+        if in_flight == 0 {
+            return;
+        }
+
+        let wait = match reason {
+            ShutdownReason::Immediate => Duration::ZERO,
+            _ => self.config.request_timeout,
+        };
+        info!(
+            "Waiting up to {:?} for {} in-flight executions",
+            wait, in_flight
+        );
+        if self.executions.wait_idle(wait).await {
+            debug!("All in-flight executions finished");
+            return;
+        }
+
+        if !self.config.cancel_in_flight {
+            warn!(
+                "{} executions still running after {:?}",
+                self.executions.count(),
+                wait
+            );
+            return;
+        }
+        let cancelled = self.executions.cancel_all();
+        warn!(
+            "Cancelled {} in-flight executions after {:?}",
+            cancelled, wait
+        );
+        if !self
+            .executions
+            .wait_idle(self.config.cancel_grace_period)
+            .await
+        {
+            warn!(
+                "{} executions still running after cancellation",
+                self.executions.count()
+            );
+        }
+    }
+
     /// Force immediate shutdown
     async fn force_shutdown(&self) {
         warn!("Performing forced shutdown");
@@ -300,11 +371,12 @@ impl ShutdownManager {
     async fn flush_telemetry(&self) {
         debug!("Flushing telemetry data");
 
-        {
-            use opentelemetry::global;
-            global::shutdown_tracer_provider();
-            debug!("Telemetry flushed successfully");
-        }
+        // Executions that did not finish never reached their own flush
+        self.executions.flush_telemetry().await;
+
+        #[cfg(feature = "telemetry")]
+        opentelemetry::global::shutdown_tracer_provider();
+        debug!("Telemetry flushed successfully");
     }
 }
 
@@ -470,6 +542,32 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_shutdown_cancels_in_flight_executions_after_request_timeout() {
+        let config = ShutdownConfig {
+            request_timeout: Duration::from_millis(20),
+            ..ShutdownConfig::default()
+        };
+        let mut manager = ShutdownManager::new(config);
+        let executions = manager.executions();
+
+        let token = tokio_util::sync::CancellationToken::new();
+        let guard = executions
+            .begin("agent", Some(token.clone()), None)
+            .unwrap();
+        let execution = tokio::spawn(async move {
+            token.cancelled().await;
+            drop(guard);
+        });
+
+        let result = manager.execute_shutdown(ShutdownReason::Graceful).await;
+
+        assert!(result.is_ok());
+        assert_eq!(executions.count(), 0);
+        assert!(executions.begin("agent", None, None).is_err());
+        execution.await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_immediate_shutdown() {
         let counter = Arc::new(AtomicU32::new(0));
@@ -0,0 +1,190 @@
+//! Key-value storage shared by the persistence-dependent subsystems.
+//!
+//! The [`ResponseCache`](crate::llm::response_cache::ResponseCache), the
+//! [`ToolReliabilityTracker`](crate::tools::reliability::ToolReliabilityTracker)
+//! and conversation checkpoints of the
+//! [`ConversationManager`](crate::agent::conversation::ConversationManager)
+//! all save their state to a [`KvStore`], so one store configured by the
+//! application holds everything that should survive a restart:
+//!
+//! | Store | Feature | Keeps data |
+//! |---|---|---|
+//! | [`InMemoryKvStore`] | | Until the process exits |
+//! | `SledKvStore` | `sled-store` | In an embedded sled database directory |
+//! | `SqliteKvStore` | `sqlite-store` | In a table of an SQLite database |
+//!
+//! Keys are strings, values are bytes; [`KvStoreExt`] adds JSON helpers.
+//! Each subsystem writes under a prefix chosen by the caller, so several can
+//! share one store.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use stood::llm::response_cache::ResponseCache;
+//! use stood::storage::{InMemoryKvStore, KvStore};
+//! use stood::tools::reliability::ToolReliabilityTracker;
+//!
+//! # async fn example() -> stood::Result<()> {
+//! let store: Arc<dyn KvStore> = Arc::new(InMemoryKvStore::new());
+//!
+//! let cache = ResponseCache::load_from_store(store.as_ref(), "cache/").await?;
+//! let reliability = ToolReliabilityTracker::new();
+//! reliability.load_from_store(store.as_ref(), "reliability/").await?;
+//!
+//! // ... run the agents ...
+//!
+//! cache.save_to_store(store.as_ref(), "cache/").await?;
+//! reliability.save_to_store(store.as_ref(), "reliability/").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{Result, StoodError};
+
+#[cfg(feature = "sled-store")]
+pub mod sled;
+#[cfg(feature = "sqlite-store")]
+pub mod sqlite;
+
+#[cfg(feature = "sled-store")]
+pub use self::sled::SledKvStore;
+#[cfg(feature = "sqlite-store")]
+pub use self::sqlite::SqliteKvStore;
+
+/// Byte values stored under string keys
+#[async_trait]
+pub trait KvStore: Send + Sync + fmt::Debug {
+    /// The value stored under `key`
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Store `value` under `key`, replacing any previous value
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<()>;
+
+    /// Remove `key`, returning whether it existed
+    async fn delete(&self, key: &str) -> Result<bool>;
+
+    /// Keys starting with `prefix`, in ascending order
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>>;
+}
+
+/// JSON values on top of any [`KvStore`]
+#[async_trait]
+pub trait KvStoreExt: KvStore {
+    /// The value stored under `key`, deserialized from JSON
+    async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        match self.get(key).await? {
+            Some(bytes) => serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+                StoodError::serialization_error(format!("Invalid value under '{}': {}", key, e))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Store `value` under `key` as JSON
+    async fn put_json<T: Serialize + Sync>(&self, key: &str, value: &T) -> Result<()> {
+        let bytes = serde_json::to_vec(value).map_err(|e| {
+            StoodError::serialization_error(format!("Failed to serialize '{}': {}", key, e))
+        })?;
+        self.put(key, bytes).await
+    }
+}
+
+impl<S: KvStore + ?Sized> KvStoreExt for S {}
+
+/// Key-value store for a single process
+///
+/// Values are lost when the process exits.
+#[derive(Debug, Default)]
+pub struct InMemoryKvStore {
+    entries: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl InMemoryKvStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored keys
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether nothing is stored
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl KvStore for InMemoryKvStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.lock().get(key).cloned())
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        self.lock().insert(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        Ok(self.lock().remove(key).is_some())
+    }
+
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .lock()
+            .range(prefix.to_string()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_store() {
+        let store = InMemoryKvStore::new();
+        store.put("cache/b", b"2".to_vec()).await.unwrap();
+        store.put("cache/a", b"1".to_vec()).await.unwrap();
+        store.put("checkpoints", b"[]".to_vec()).await.unwrap();
+
+        assert_eq!(store.get("cache/a").await.unwrap(), Some(b"1".to_vec()));
+        assert_eq!(store.get("cache/c").await.unwrap(), None);
+        assert_eq!(
+            store.keys("cache/").await.unwrap(),
+            vec!["cache/a", "cache/b"]
+        );
+
+        assert!(store.delete("cache/a").await.unwrap());
+        assert!(!store.delete("cache/a").await.unwrap());
+        assert_eq!(store.len(), 2);
+
+        store.put_json("numbers", &vec![1, 2, 3]).await.unwrap();
+        let numbers: Option<Vec<u32>> = store.get_json("numbers").await.unwrap();
+        assert_eq!(numbers, Some(vec![1, 2, 3]));
+        assert!(store
+            .get_json::<Vec<u32>>("checkpoints/x")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            store.get_json::<u32>("cache/b").await,
+            Ok(Some(2))
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+//! Key-value store in an embedded sled database.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+
+use super::KvStore;
+use crate::{Result, StoodError};
+
+/// Key-value store kept in a sled database directory
+///
+/// Writes are flushed to disk in the background by sled; call
+/// [`flush`](Self::flush) to wait for them, e.g. before exiting.
+#[derive(Debug, Clone)]
+pub struct SledKvStore {
+    tree: ::sled::Tree,
+}
+
+impl SledKvStore {
+    /// Open or create the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let db = ::sled::open(path).map_err(|e| {
+            StoodError::configuration_error(format!(
+                "Failed to open sled database '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self::from_tree((*db).clone()))
+    }
+
+    /// Store values in `tree` of an already open database
+    pub fn from_tree(tree: ::sled::Tree) -> Self {
+        Self { tree }
+    }
+
+    /// Wait until all writes so far are on disk
+    pub async fn flush(&self) -> Result<()> {
+        self.tree.flush_async().await.map_err(sled_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KvStore for SledKvStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let value = self.tree.get(key).map_err(sled_error)?;
+        Ok(value.map(|value| value.to_vec()))
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        self.tree.insert(key, value).map_err(sled_error)?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        Ok(self.tree.remove(key).map_err(sled_error)?.is_some())
+    }
+
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>> {
+        self.tree
+            .scan_prefix(prefix)
+            .keys()
+            .map(|key| {
+                let key = key.map_err(sled_error)?;
+                String::from_utf8(key.to_vec()).map_err(|e| {
+                    StoodError::serialization_error(format!("Invalid key in sled store: {}", e))
+                })
+            })
+            .collect()
+    }
+}
+
+fn sled_error(e: ::sled::Error) -> StoodError {
+    StoodError::internal_error(format!("sled store error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sled_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SledKvStore::open(dir.path().join("store")).unwrap();
+        store.put("cache/a", b"1".to_vec()).await.unwrap();
+        store.put("cachex", b"2".to_vec()).await.unwrap();
+        store.flush().await.unwrap();
+
+        assert_eq!(store.get("cache/a").await.unwrap(), Some(b"1".to_vec()));
+        assert_eq!(store.keys("cache/").await.unwrap(), vec!["cache/a"]);
+        assert!(store.delete("cachex").await.unwrap());
+        assert!(!store.delete("cachex").await.unwrap());
+        assert_eq!(store.get("cachex").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,138 @@
+//! Key-value store in an SQLite table.
+
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use sqlx::Row;
+
+use super::KvStore;
+use crate::{Result, StoodError};
+
+/// Default name of the table holding the values
+pub const DEFAULT_TABLE: &str = "stood_kv";
+
+/// Key-value store kept in a table of an SQLite database
+///
+/// The table is created on [`open`](Self::open) if it does not exist.
+#[derive(Debug, Clone)]
+pub struct SqliteKvStore {
+    pool: SqlitePool,
+    table: String,
+}
+
+impl SqliteKvStore {
+    /// Open or create the database at `url`, e.g. `sqlite://state.db`, and
+    /// store values in the [`DEFAULT_TABLE`]
+    pub async fn open(url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)
+            .map_err(|e| {
+                StoodError::configuration_error(format!("Invalid SQLite URL '{}': {}", url, e))
+            })?
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await.map_err(|e| {
+            StoodError::configuration_error(format!(
+                "Failed to open SQLite database '{}': {}",
+                url, e
+            ))
+        })?;
+        Self::with_pool(pool, DEFAULT_TABLE).await
+    }
+
+    /// Store values in `table` of the database behind `pool`
+    ///
+    /// `table` may only contain ASCII letters, digits and underscores.
+    pub async fn with_pool(pool: SqlitePool, table: &str) -> Result<Self> {
+        if table.is_empty() || !table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(StoodError::configuration_error(format!(
+                "Invalid SQLite table name '{}'",
+                table
+            )));
+        }
+        sqlx::query(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (key TEXT PRIMARY KEY, value BLOB NOT NULL)",
+            table
+        ))
+        .execute(&pool)
+        .await
+        .map_err(sqlite_error)?;
+        Ok(Self {
+            pool,
+            table: table.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl KvStore for SqliteKvStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let row = sqlx::query(&format!("SELECT value FROM {} WHERE key = ?", self.table))
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(sqlite_error)?;
+        row.map(|row| row.try_get("value").map_err(sqlite_error))
+            .transpose()
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        sqlx::query(&format!(
+            "INSERT INTO {} (key, value) VALUES (?, ?) \
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            self.table
+        ))
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await
+        .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let result = sqlx::query(&format!("DELETE FROM {} WHERE key = ?", self.table))
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(sqlite_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query(&format!(
+            "SELECT key FROM {} WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key",
+            self.table
+        ))
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(sqlite_error)?;
+        rows.iter()
+            .map(|row| row.try_get("key").map_err(sqlite_error))
+            .collect()
+    }
+}
+
+fn sqlite_error(e: sqlx::Error) -> StoodError {
+    StoodError::internal_error(format!("SQLite store error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sqlite_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("state.db").display());
+        let store = SqliteKvStore::open(&url).await.unwrap();
+
+        store.put("cache/a", b"1".to_vec()).await.unwrap();
+        store.put("cache/a", b"2".to_vec()).await.unwrap();
+        store.put("cachex", b"3".to_vec()).await.unwrap();
+        assert_eq!(store.get("cache/a").await.unwrap(), Some(b"2".to_vec()));
+        assert_eq!(store.keys("cache/").await.unwrap(), vec!["cache/a"]);
+        assert!(store.delete("cache/a").await.unwrap());
+        assert!(!store.delete("cache/a").await.unwrap());
+    }
+}
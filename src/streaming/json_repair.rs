@@ -0,0 +1,194 @@
+//! Repair of nearly valid JSON.
+//!
+//! Streamed structured output often ends just short of valid JSON: the stream
+//! stops inside a string, an object is never closed, or the model leaves a
+//! trailing comma. [`repair_json`] fixes these mechanically so the value can
+//! be validated without asking the model to try again:
+//!
+//! - trailing commas before `}` and `]` are removed
+//! - an unterminated string is closed, dropping a dangling `\`
+//! - raw newlines and tabs inside strings are escaped
+//! - a key without a value, or a dangling `:`, gets `null`
+//! - truncated `true`, `false` and `null` literals and numbers are completed
+//! - unclosed objects and arrays are closed, and text after the top-level
+//!   value is dropped
+//!
+//! ```
+//! use stood::streaming::json_repair::parse_repaired;
+//!
+//! let (value, repaired) = parse_repaired(r#"{"name": "Ada", "tags": ["math",], "bio": "Count"#).unwrap();
+//! assert!(repaired);
+//! assert_eq!(value["tags"][0], "math");
+//! assert_eq!(value["bio"], "Count");
+//! ```
+
+use serde_json::Value;
+
+/// Fix common defects of truncated or sloppy JSON
+///
+/// Valid JSON is returned unchanged. The result is not guaranteed to be valid;
+/// text that is not JSON-like stays invalid.
+pub fn repair_json(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 8);
+    // Open containers, innermost last
+    let mut stack: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    // Whether the string being read (or just read) is an object key
+    let mut string_is_key = false;
+    let mut key_pending = false;
+
+    for c in text.trim_start().chars() {
+        if in_string {
+            match c {
+                _ if escaped => {
+                    escaped = false;
+                    out.push(c);
+                }
+                '\\' => {
+                    escaped = true;
+                    out.push(c);
+                }
+                '"' => {
+                    in_string = false;
+                    key_pending = string_is_key;
+                    out.push(c);
+                }
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                _ => out.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                string_is_key = stack.last() == Some(&'{')
+                    && matches!(last_significant(&out), Some('{') | Some(','));
+                in_string = true;
+                out.push(c);
+            }
+            '{' | '[' => {
+                stack.push(c);
+                out.push(c);
+            }
+            '}' | ']' => {
+                let Some(open) = stack.pop() else {
+                    break;
+                };
+                close_value(&mut out, key_pending);
+                key_pending = false;
+                out.push(if open == '{' { '}' } else { ']' });
+                if stack.is_empty() {
+                    break;
+                }
+            }
+            ':' => {
+                key_pending = false;
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+
+    if in_string {
+        if escaped {
+            out.pop();
+        }
+        out.push('"');
+        key_pending = string_is_key;
+    }
+    while let Some(open) = stack.pop() {
+        close_value(&mut out, key_pending);
+        key_pending = false;
+        out.push(if open == '{' { '}' } else { ']' });
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Parse `text` as JSON, repairing it if it is not valid as it is
+///
+/// Returns the value and whether it needed repair.
+pub fn parse_repaired(text: &str) -> Option<(Value, bool)> {
+    if let Ok(value) = serde_json::from_str(text) {
+        return Some((value, false));
+    }
+    serde_json::from_str(&repair_json(text))
+        .ok()
+        .map(|value| (value, true))
+}
+
+/// Make the end of `out` a complete value before a container is closed
+fn close_value(out: &mut String, key_pending: bool) {
+    out.truncate(out.trim_end().len());
+    if key_pending {
+        out.push_str(": null");
+        return;
+    }
+    match out.chars().last() {
+        Some(',') => {
+            out.pop();
+            out.truncate(out.trim_end().len());
+        }
+        Some(':') => out.push_str(" null"),
+        _ => complete_scalar(out),
+    }
+}
+
+/// Complete a truncated literal or number at the end of `out`
+fn complete_scalar(out: &mut String) {
+    let tail_start = out
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')))
+        .map_or(0, |i| i + 1);
+    let tail = &out[tail_start..];
+    if tail.is_empty() {
+        return;
+    }
+    let completion = ["true", "false", "null"]
+        .into_iter()
+        .find(|literal| literal.starts_with(tail) && *literal != tail)
+        .map(|literal| literal[tail.len()..].to_string());
+    if let Some(rest) = completion {
+        out.push_str(&rest);
+    } else if tail.ends_with(['-', '+', '.', 'e', 'E'])
+        && tail.starts_with(|c: char| c == '-' || c.is_ascii_digit())
+    {
+        out.push('0');
+    }
+}
+
+fn last_significant(out: &str) -> Option<char> {
+    out.chars().rev().find(|c| !c.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_repairs_truncated_and_sloppy_json() {
+        let valid = r#"{"a": [1, 2], "b": "x, y]"}"#;
+        assert_eq!(repair_json(valid), valid);
+
+        let cases = [
+            (r#"{"a": 1, "b": [1, 2,],}"#, json!({"a": 1, "b": [1, 2]})),
+            (r#"{"text": "line one"#, json!({"text": "line one"})),
+            ("{\"text\": \"two\nlines\"}", json!({"text": "two\nlines"})),
+            (r#"{"path": "C:\"#, json!({"path": "C:"})),
+            (r#"{"a": 1, "b""#, json!({"a": 1, "b": null})),
+            (r#"{"a": 1, "b": "#, json!({"a": 1, "b": null})),
+            (r#"{"ok": tr"#, json!({"ok": true})),
+            (r#"[1, 2.5, 3."#, json!([1, 2.5, 3.0])),
+            (r#"[{"a": {"b": [1"#, json!([{"a": {"b": [1]}}])),
+            (r#"{"a": 1} and some prose"#, json!({"a": 1})),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_repaired(input), Some((expected, true)), "{}", input);
+        }
+        assert_eq!(parse_repaired("[1]"), Some((json!([1]), false)));
+        assert_eq!(parse_repaired("not json"), None);
+    }
+}
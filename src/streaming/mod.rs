@@ -9,6 +9,8 @@
 //! - `StreamProcessor`: Process AWS SDK streams into typed events
 //! - `StreamingMessage`: Incremental message building from stream chunks
 //! - `StreamConfig`: Configuration for streaming behavior
+//! - `StreamingToolInputs`: Detects completed tool inputs while they are streamed
+//! - `json_repair`: Fixes truncated or sloppy JSON at the end of a stream
 
 use futures::Stream;
 use serde::{Deserialize, Serialize};
@@ -19,6 +21,11 @@ use crate::{
     Result, StoodError,
 };
 
+pub mod json_repair;
+pub mod tool_input;
+
+pub use tool_input::{PartialJson, StreamingToolInputs};
+
 /// Usage information for token consumption
 pub use crate::types::TokenUsage as Usage;
 
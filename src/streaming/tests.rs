@@ -218,4 +218,25 @@ mod tests {
         // Just verify we can create it without panics
         assert_eq!(processor.config.buffer_size, 100);
     }
+
+    #[test]
+    fn test_streaming_tool_inputs_detect_completion() {
+        let mut inputs = StreamingToolInputs::new();
+        assert_eq!(inputs.push("t1", "{\"path\": \"a}b"), None);
+        assert_eq!(inputs.push("t2", "{\"expr\": [1, "), None);
+        assert_eq!(inputs.push("t1", "\\\"\", \"n\": {}"), None);
+        assert_eq!(
+            inputs.push("t1", "}"),
+            Some(serde_json::json!({"path": "a}b\"", "n": {}}))
+        );
+        assert_eq!(inputs.push("t1", " "), None);
+        assert!(inputs.completed("t1").is_some());
+        assert!(inputs.completed("t2").is_none());
+
+        let mut json = PartialJson::new();
+        assert!(json.push("[1]"));
+        json.push("[2]");
+        assert!(!json.is_complete());
+        assert_eq!(json.as_str(), "[1][2]");
+    }
 }
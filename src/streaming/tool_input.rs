@@ -0,0 +1,134 @@
+//! Incremental parsing of streamed tool inputs.
+//!
+//! Models stream the JSON input of a tool call in fragments. [`PartialJson`]
+//! tracks the nesting of the fragments received so far and reports the moment
+//! the top-level object is closed, so a tool call can be started before the
+//! model has finished the rest of its turn. [`StreamingToolInputs`] does this
+//! for all tool calls of a response, keyed by tool use id.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// A JSON object or array received in fragments
+#[derive(Debug, Clone, Default)]
+pub struct PartialJson {
+    buffer: String,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    started: bool,
+    complete: bool,
+    trailing: bool,
+}
+
+impl PartialJson {
+    /// Create an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a fragment, returning `true` if it closed the top-level value
+    pub fn push(&mut self, fragment: &str) -> bool {
+        let was_complete = self.complete;
+        self.buffer.push_str(fragment);
+
+        for c in fragment.chars() {
+            if self.complete {
+                if !c.is_whitespace() {
+                    self.trailing = true;
+                }
+                continue;
+            }
+            if self.in_string {
+                match c {
+                    _ if self.escaped => self.escaped = false,
+                    '\\' => self.escaped = true,
+                    '"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' if self.started => self.in_string = true,
+                '{' | '[' => {
+                    self.started = true;
+                    self.depth += 1;
+                }
+                '}' | ']' if self.depth > 0 => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        self.complete = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        !was_complete && self.is_complete()
+    }
+
+    /// Whether the top-level object or array has been closed
+    ///
+    /// Text after the closing bracket (other than whitespace) makes the value
+    /// incomplete again, since it can no longer be parsed as a whole.
+    pub fn is_complete(&self) -> bool {
+        self.complete && !self.trailing
+    }
+
+    /// The fragments received so far
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// The parsed value, once it is complete and valid JSON
+    pub fn parse(&self) -> Option<Value> {
+        if !self.is_complete() {
+            return None;
+        }
+        serde_json::from_str(&self.buffer).ok()
+    }
+
+    /// Best-effort value of the fragments received so far, e.g. when the
+    /// stream ended before the value was closed
+    ///
+    /// See [`repair_json`](super::json_repair::repair_json).
+    pub fn parse_repaired(&self) -> Option<Value> {
+        super::json_repair::parse_repaired(&self.buffer).map(|(value, _)| value)
+    }
+}
+
+/// Streamed inputs of the tool calls in one model response
+#[derive(Debug, Clone, Default)]
+pub struct StreamingToolInputs {
+    inputs: HashMap<String, PartialJson>,
+}
+
+impl StreamingToolInputs {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an input fragment of `tool_use_id`
+    ///
+    /// Returns the parsed input when this fragment completed it.
+    pub fn push(&mut self, tool_use_id: &str, fragment: &str) -> Option<Value> {
+        let input = self.inputs.entry(tool_use_id.to_string()).or_default();
+        if input.push(fragment) {
+            input.parse()
+        } else {
+            None
+        }
+    }
+
+    /// Forget the input of `tool_use_id`, e.g. when the provider restarts it
+    pub fn reset(&mut self, tool_use_id: &str) {
+        self.inputs.remove(tool_use_id);
+    }
+
+    /// The parsed input of `tool_use_id`, if it is complete
+    pub fn completed(&self, tool_use_id: &str) -> Option<Value> {
+        self.inputs.get(tool_use_id).and_then(PartialJson::parse)
+    }
+}
@@ -0,0 +1,306 @@
+//! Anonymization of captured content before export.
+//!
+//! With [`TelemetryConfig::with_content_anonymization`], the prompts, responses
+//! and tool payloads recorded in log events are rewritten before they leave the
+//! process, in two stages:
+//!
+//! 1. **Redaction** replaces direct identifiers (email addresses, AWS access
+//!    keys, card numbers, social security numbers, IP addresses, phone numbers
+//!    and any custom patterns) with typed placeholders such as `<EMAIL>`.
+//! 2. **Generalization** replaces quasi-identifiers with their category,
+//!    k-anonymity style: names become `<PERSON>` and numbers become `<NUM>`.
+//!
+//! Names are detected heuristically: runs of two or more capitalized words, and
+//! single capitalized words that do not start a sentence or line. Names added
+//! with [`ContentAnonymizer::with_name`] are always generalized, and terms added
+//! with [`ContentAnonymizer::with_allowed_term`] are kept. Only message content is
+//! rewritten, so exported events keep the structure evaluations rely on.
+//!
+//! ```
+//! use stood::telemetry::ContentAnonymizer;
+//!
+//! let anonymizer = ContentAnonymizer::new();
+//! assert_eq!(
+//!     anonymizer.anonymize("Mail alice@example.com and ask Bob for 3 copies"),
+//!     "Mail <EMAIL> and ask <PERSON> for <NUM> copies"
+//! );
+//! ```
+//!
+//! [`TelemetryConfig::with_content_anonymization`]: super::TelemetryConfig::with_content_anonymization
+
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+use super::log_event::LogEvent;
+use crate::StoodError;
+
+/// Placeholder for generalized names
+pub const PERSON_PLACEHOLDER: &str = "<PERSON>";
+
+/// Placeholder for generalized numbers
+pub const NUMBER_PLACEHOLDER: &str = "<NUM>";
+
+static BUILTIN_REDACTIONS: Lazy<Vec<Redaction>> = Lazy::new(|| {
+    [
+        (r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}", "<EMAIL>"),
+        (r"\b(?:AKIA|ASIA)[A-Z0-9]{16}\b", "<CREDENTIAL>"),
+        (r"\b(?:\d[ -]?){12,18}\d\b", "<CARD>"),
+        (r"\b\d{3}-\d{2}-\d{4}\b", "<SSN>"),
+        (r"\b(?:\d{1,3}\.){3}\d{1,3}\b", "<IP>"),
+        (
+            r"(?:\+\d{1,3}[ .-]?)?\(?\b\d{3}\)?[ .-]?\d{3}[ .-]\d{4}\b",
+            "<PHONE>",
+        ),
+    ]
+    .into_iter()
+    .map(|(pattern, placeholder)| Redaction {
+        pattern: Regex::new(pattern).expect("valid redaction regex"),
+        placeholder: placeholder.to_string(),
+    })
+    .collect()
+});
+
+static CAPITALIZED_RUN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b\p{Lu}\p{Ll}+(?:[ \t]+\p{Lu}\p{Ll}+)*\b").expect("valid name regex")
+});
+
+static NUMBER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\d+(?:[.,]\d+)*").expect("valid number regex"));
+
+#[derive(Debug, Clone)]
+struct Redaction {
+    pattern: Regex,
+    placeholder: String,
+}
+
+/// Redacts and generalizes captured content
+#[derive(Debug, Clone)]
+pub struct ContentAnonymizer {
+    redactions: Vec<Redaction>,
+    generalize_names: bool,
+    generalize_numbers: bool,
+    names: Option<Regex>,
+    name_list: Vec<String>,
+    allowed_terms: HashSet<String>,
+}
+
+impl Default for ContentAnonymizer {
+    fn default() -> Self {
+        Self {
+            redactions: BUILTIN_REDACTIONS.clone(),
+            generalize_names: true,
+            generalize_numbers: true,
+            names: None,
+            name_list: Vec::new(),
+            allowed_terms: HashSet::new(),
+        }
+    }
+}
+
+impl ContentAnonymizer {
+    /// Built-in redactions followed by name and number generalization
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Built-in redactions only, without generalization
+    pub fn redaction_only() -> Self {
+        Self::default()
+            .with_name_generalization(false)
+            .with_number_generalization(false)
+    }
+
+    /// Redact matches of `pattern` as `placeholder`
+    ///
+    /// Custom patterns run after the built-in ones.
+    pub fn with_pattern(
+        mut self,
+        pattern: &str,
+        placeholder: impl Into<String>,
+    ) -> Result<Self, StoodError> {
+        let pattern = Regex::new(pattern).map_err(|e| {
+            StoodError::configuration_error(format!("Invalid redaction pattern: {}", e))
+        })?;
+        self.redactions.push(Redaction {
+            pattern,
+            placeholder: placeholder.into(),
+        });
+        Ok(self)
+    }
+
+    /// Replace detected names with `<PERSON>`
+    pub fn with_name_generalization(mut self, enabled: bool) -> Self {
+        self.generalize_names = enabled;
+        self
+    }
+
+    /// Replace numbers with `<NUM>`
+    pub fn with_number_generalization(mut self, enabled: bool) -> Self {
+        self.generalize_numbers = enabled;
+        self
+    }
+
+    /// Always generalize `name`, whatever its capitalization or position
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name_list.push(name.into());
+        let alternatives: Vec<String> = self
+            .name_list
+            .iter()
+            .map(|name| regex::escape(name))
+            .collect();
+        self.names = Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives.join("|"))).ok();
+        self
+    }
+
+    /// Never generalize capitalized runs containing `term`, e.g. product names
+    pub fn with_allowed_term(mut self, term: impl Into<String>) -> Self {
+        self.allowed_terms.insert(term.into());
+        self
+    }
+
+    /// Anonymize a piece of text
+    pub fn anonymize(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for redaction in &self.redactions {
+            text = redaction
+                .pattern
+                .replace_all(&text, redaction.placeholder.as_str())
+                .into_owned();
+        }
+        if let Some(names) = &self.names {
+            text = names.replace_all(&text, PERSON_PLACEHOLDER).into_owned();
+        }
+        if self.generalize_names {
+            text = self.generalize_capitalized(&text);
+        }
+        if self.generalize_numbers {
+            text = NUMBER.replace_all(&text, NUMBER_PLACEHOLDER).into_owned();
+        }
+        text
+    }
+
+    /// Anonymize the message content of a log event
+    ///
+    /// Messages in LangChain format only have their `content` values rewritten.
+    /// GenAI message events have their content and every string in their tool
+    /// call arguments rewritten.
+    pub fn anonymize_log_event(&self, event: &mut LogEvent) {
+        if let Some(content) = event.body.content.as_mut() {
+            *content = self.anonymize(content);
+        }
+        for call in &mut event.body.tool_calls {
+            self.anonymize_strings(&mut call.function.arguments);
+        }
+        let lists = [event.body.input.as_mut(), event.body.output.as_mut()];
+        for list in lists.into_iter().flatten() {
+            for message in &mut list.messages {
+                message.content = match serde_json::from_str::<Value>(&message.content) {
+                    Ok(mut value @ (Value::Object(_) | Value::Array(_))) => {
+                        self.anonymize_content_values(&mut value);
+                        serde_json::to_string(&value).unwrap_or_default()
+                    }
+                    _ => self.anonymize(&message.content),
+                };
+            }
+        }
+    }
+
+    fn anonymize_strings(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.anonymize(text),
+            Value::Object(map) => map
+                .values_mut()
+                .for_each(|value| self.anonymize_strings(value)),
+            Value::Array(items) => items
+                .iter_mut()
+                .for_each(|item| self.anonymize_strings(item)),
+            _ => {}
+        }
+    }
+
+    fn anonymize_content_values(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    match value {
+                        Value::String(text) if key == "content" => *text = self.anonymize(text),
+                        _ => self.anonymize_content_values(value),
+                    }
+                }
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .for_each(|item| self.anonymize_content_values(item)),
+            _ => {}
+        }
+    }
+
+    fn generalize_capitalized(&self, text: &str) -> String {
+        CAPITALIZED_RUN
+            .replace_all(text, |captures: &regex::Captures<'_>| {
+                let run = captures.get(0).expect("whole match");
+                let words: Vec<&str> = run.as_str().split_whitespace().collect();
+                let allowed = words.iter().any(|w| self.allowed_terms.contains(*w));
+                if allowed || (words.len() == 1 && starts_sentence(&text[..run.start()])) {
+                    run.as_str().to_string()
+                } else {
+                    PERSON_PLACEHOLDER.to_string()
+                }
+            })
+            .into_owned()
+    }
+}
+
+/// Whether text following `preceding` starts a sentence or line
+fn starts_sentence(preceding: &str) -> bool {
+    let trimmed = preceding.trim_end_matches([' ', '\t', '"', '\'', '(', '-', '*', '#']);
+    trimmed.is_empty() || trimmed.ends_with(['.', '!', '?', ':', '\n', '>'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_then_generalizes() {
+        let anonymizer = ContentAnonymizer::new()
+            .with_name("zoë")
+            .with_allowed_term("Bedrock")
+            .with_pattern(r"ORD-\d+", "<ORDER>")
+            .unwrap();
+
+        let text = "Customer Jane Doe (jane.doe@corp.com, 192.168.1.20) called about ORD-991. \
+                    Zoë said Bedrock billed 2 cards, refund $1,250.50 to Mark.";
+        assert_eq!(
+            anonymizer.anonymize(text),
+            "<PERSON> (<EMAIL>, <IP>) called about <ORDER>. \
+             <PERSON> said Bedrock billed <NUM> cards, refund $<NUM> to <PERSON>."
+        );
+
+        let redacted =
+            ContentAnonymizer::redaction_only().anonymize("Ask Mark, card 4111 1111 1111 1111");
+        assert_eq!(redacted, "Ask Mark, card <CARD>");
+    }
+
+    #[test]
+    fn test_log_event_keeps_langchain_structure() {
+        let mut event = LogEvent::for_agent_invocation(
+            "trace",
+            "span",
+            "session",
+            None,
+            "What did Alice Smith order on 2024-05-01?",
+            "She ordered 3 lamps.",
+        );
+        ContentAnonymizer::new().anonymize_log_event(&mut event);
+
+        let input = &event.body.input.as_ref().unwrap().messages[0].content;
+        assert!(input.contains("HumanMessage"));
+        assert!(input.contains("What did <PERSON> order on <NUM>-<NUM>-<NUM>?"));
+        let output = &event.body.output.as_ref().unwrap().messages[0].content;
+        assert!(output.contains("She ordered <NUM> lamps."));
+    }
+}
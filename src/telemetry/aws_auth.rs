@@ -76,7 +76,10 @@ impl AwsCredentialsProvider {
         // 1. Environment variables (AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY)
         // 2. Shared credentials file (~/.aws/credentials)
         // 3. IAM role (if running on AWS)
-        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let config = crate::llm::http::aws_config_loader()
+            .map_err(|e| AuthError::Credentials(e.to_string()))?
+            .load()
+            .await;
 
         config
             .credentials_provider()
@@ -90,7 +93,8 @@ impl AwsCredentialsProvider {
 
     /// Load credentials from a specific AWS profile
     async fn load_from_profile(&self, profile: &str) -> Result<Credentials, AuthError> {
-        let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+        let config = crate::llm::http::aws_config_loader()
+            .map_err(|e| AuthError::Credentials(e.to_string()))?
             .profile_name(profile)
             .load()
             .await;
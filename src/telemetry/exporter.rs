@@ -575,6 +575,7 @@ pub mod otlp {
 // CloudWatch Exporter
 // ============================================================================
 
+use crate::llm::http::HttpClientConfig;
 use super::aws_auth::{
     cloudwatch_logs_endpoint, sign_request, xray_otlp_endpoint, AwsCredentialsProvider,
 };
@@ -633,9 +634,9 @@ impl CloudWatchExporter {
             agent_id: service_name.clone(), // Default to service_name
             service_name,
             service_version: service_version.into(),
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
+            client: HttpClientConfig::new()
+                .with_request_timeout(Duration::from_secs(10))
+                .build_client()
                 .expect("Failed to create HTTP client"),
             healthy: Arc::new(AtomicBool::new(true)),
             timeout: Duration::from_secs(10),
@@ -646,9 +647,9 @@ impl CloudWatchExporter {
     /// Set custom timeout
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
-        self.client = reqwest::Client::builder()
-            .timeout(timeout)
-            .build()
+        self.client = HttpClientConfig::new()
+            .with_request_timeout(timeout)
+            .build_client()
             .expect("Failed to create HTTP client");
         self
     }
@@ -1216,6 +1217,18 @@ fn truncate_log_event(event: &LogEvent) -> LogEvent {
         }
     }
 
+    // Truncate body.content of GenAI message events
+    if let Some(ref mut content) = event.body.content {
+        if content.len() > MAX_CONTENT_FIELD_SIZE {
+            *content = truncate_content_smart(
+                content,
+                MAX_CONTENT_FIELD_SIZE,
+                TRUNCATION_HEAD_SIZE,
+                TRUNCATION_TAIL_SIZE,
+            );
+        }
+    }
+
     event
 }
 
@@ -1411,8 +1424,8 @@ mod tests {
         assert_eq!(int_val.int_value, Some("42".to_string()));
 
         // Float
-        let float_val: otlp::AnyValue = (&AttributeValue::Float(3.14)).into();
-        assert_eq!(float_val.double_value, Some(3.14));
+        let float_val: otlp::AnyValue = (&AttributeValue::Float(2.5)).into();
+        assert_eq!(float_val.double_value, Some(2.5));
 
         // String array
         let str_array: otlp::AnyValue =
@@ -1451,7 +1464,7 @@ mod tests {
         let i: AttributeValue = 42i64.into();
         assert!(matches!(i, AttributeValue::Int(42)));
 
-        let f: AttributeValue = 3.14f64.into();
+        let f: AttributeValue = 2.5f64.into();
         assert!(matches!(f, AttributeValue::Float(_)));
     }
 
@@ -263,6 +263,15 @@ pub mod attrs {
     /// Tool execution ID
     pub const STOOD_TOOL_EXECUTION_ID: &str = "stood.tool.execution_id";
 
+    /// Average time to the first streamed token of the model calls, in milliseconds
+    pub const STOOD_TIME_TO_FIRST_TOKEN_MS: &str = "stood.model.time_to_first_token_ms";
+
+    /// Output tokens per second of model generation
+    pub const STOOD_TOKENS_PER_SECOND: &str = "stood.model.tokens_per_second";
+
+    /// Latency reported by the provider for the model calls, in milliseconds
+    pub const STOOD_PROVIDER_LATENCY_MS: &str = "stood.model.provider_latency_ms";
+
     // ========================================================================
     // AWS CloudWatch GenAI Dashboard attributes
     // ========================================================================
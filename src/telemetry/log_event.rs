@@ -11,11 +11,21 @@
 //! We use the LangChain telemetry format (`opentelemetry.instrumentation.langchain`)
 //! which is reliably parsed by the AgentCore Evaluations API. The body uses nested
 //! JSON with `kwargs.content` structure per LangChain conventions.
+//!
+//! ## GenAI message events
+//!
+//! [`LogEvent::genai_conversation`] additionally emits one event per message
+//! following the OpenTelemetry GenAI semantic conventions
+//! (`gen_ai.system.message`, `gen_ai.user.message`, `gen_ai.assistant.message`
+//! and `gen_ai.tool.message`), so evaluations get message-level data including
+//! tool calls and tool results.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::types::{ContentBlock, MessageRole};
+
 /// LangChain message format helpers
 ///
 /// The AgentCore Evaluations API expects messages in LangChain format with
@@ -150,6 +160,9 @@ pub struct LogScope {
 /// The scope name used for LangChain format (required for AgentCore Evaluations)
 pub const LANGCHAIN_SCOPE: &str = "opentelemetry.instrumentation.langchain";
 
+/// The scope name used for GenAI semantic convention message events
+pub const GENAI_SCOPE: &str = "stood.telemetry.genai";
+
 impl Default for LogScope {
     fn default() -> Self {
         Self {
@@ -169,6 +182,39 @@ pub struct LogEventBody {
     /// Output messages (assistant response)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<MessageList>,
+
+    /// Message text of a GenAI message event
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub content: Option<String>,
+
+    /// Tool calls of a `gen_ai.assistant.message` event
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tool_calls: Vec<GenAiToolCall>,
+
+    /// Tool call answered by a `gen_ai.tool.message` event
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+}
+
+/// A tool call in a `gen_ai.assistant.message` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenAiToolCall {
+    /// Tool call ID
+    pub id: String,
+    /// Always `function`
+    #[serde(rename = "type")]
+    pub call_type: String,
+    /// Called tool and its arguments
+    pub function: GenAiFunctionCall,
+}
+
+/// The function part of a [`GenAiToolCall`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenAiFunctionCall {
+    /// Tool name
+    pub name: String,
+    /// Tool input
+    pub arguments: serde_json::Value,
 }
 
 /// List of messages
@@ -211,7 +257,14 @@ pub mod event_names {
     pub const CONTENT_COMPLETION: &str = "gen_ai.content.completion";
     #[allow(dead_code)]
     pub const CHOICE: &str = "gen_ai.choice";
-    #[allow(dead_code)]
+
+    /// GenAI message event for the system prompt
+    pub const SYSTEM_MESSAGE: &str = "gen_ai.system.message";
+    /// GenAI message event for a user message
+    pub const USER_MESSAGE: &str = "gen_ai.user.message";
+    /// GenAI message event for an assistant message
+    pub const ASSISTANT_MESSAGE: &str = "gen_ai.assistant.message";
+    /// GenAI message event for a tool result
     pub const TOOL_MESSAGE: &str = "gen_ai.tool.message";
 }
 
@@ -440,6 +493,92 @@ impl LogEvent {
     }
 }
 
+impl LogEvent {
+    /// Create GenAI message events for a conversation
+    ///
+    /// Emits a `gen_ai.system.message` for the system prompt, then one event
+    /// per message in order: user text becomes `gen_ai.user.message`,
+    /// assistant text and tool calls become `gen_ai.assistant.message`, and
+    /// each tool result becomes a `gen_ai.tool.message`. Messages without
+    /// text, tool calls or tool results (e.g. only reasoning) are skipped.
+    pub fn genai_conversation(
+        trace_id: &str,
+        span_id: &str,
+        session_id: &str,
+        provider: &str,
+        system_prompt: Option<&str>,
+        messages: &[crate::types::Message],
+    ) -> Vec<Self> {
+        let event = |event_name: &str, body: LogEventBody| {
+            let mut event = Self::new(trace_id, span_id)
+                .with_event_name(event_name)
+                .with_session_id(session_id)
+                .with_attribute(super::genai::attrs::PROVIDER_NAME, provider);
+            event.scope.name = GENAI_SCOPE.to_string();
+            event.body = body;
+            event
+        };
+
+        let mut events = Vec::new();
+        if let Some(system) = system_prompt {
+            events.push(event(
+                event_names::SYSTEM_MESSAGE,
+                LogEventBody {
+                    content: Some(system.to_string()),
+                    ..LogEventBody::default()
+                },
+            ));
+        }
+        for message in messages {
+            let mut text = Vec::new();
+            let mut tool_calls = Vec::new();
+            for block in &message.content {
+                match block {
+                    ContentBlock::Text { text: part } => text.push(part.as_str()),
+                    ContentBlock::ToolUse { id, name, input } => tool_calls.push(GenAiToolCall {
+                        id: id.clone(),
+                        call_type: "function".to_string(),
+                        function: GenAiFunctionCall {
+                            name: name.clone(),
+                            arguments: input.clone(),
+                        },
+                    }),
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        ..
+                    } => events.push(event(
+                        event_names::TOOL_MESSAGE,
+                        LogEventBody {
+                            content: Some(content.to_display_string()),
+                            id: Some(tool_use_id.clone()),
+                            ..LogEventBody::default()
+                        },
+                    )),
+                    _ => {}
+                }
+            }
+            if text.is_empty() && tool_calls.is_empty() {
+                continue;
+            }
+            let event_name = match message.role {
+                MessageRole::User => event_names::USER_MESSAGE,
+                MessageRole::Assistant => event_names::ASSISTANT_MESSAGE,
+                MessageRole::System => event_names::SYSTEM_MESSAGE,
+            };
+            events.push(event(
+                event_name,
+                LogEventBody {
+                    content: (!text.is_empty()).then(|| text.join("\n")),
+                    tool_calls,
+                    ..LogEventBody::default()
+                },
+            ));
+        }
+        events
+    }
+}
+
 impl Message {
     /// Create a new message
     pub fn new(role: impl Into<String>, content: impl Into<String>) -> Self {
@@ -521,6 +660,69 @@ mod tests {
         assert_eq!(assistant.role, "assistant");
     }
 
+    #[test]
+    fn test_genai_conversation() {
+        use crate::types::{ContentBlock, Message as ConversationMessage, ToolResultContent};
+
+        let messages = vec![
+            ConversationMessage::user("What's the weather in Paris?"),
+            ConversationMessage::new(
+                MessageRole::Assistant,
+                vec![
+                    ContentBlock::text("Let me check."),
+                    ContentBlock::tool_use(
+                        "call-1",
+                        "weather",
+                        serde_json::json!({"city": "Paris"}),
+                    ),
+                ],
+            ),
+            ConversationMessage::new(
+                MessageRole::User,
+                vec![ContentBlock::ToolResult {
+                    tool_use_id: "call-1".to_string(),
+                    content: ToolResultContent::text("18°C, sunny"),
+                    is_error: false,
+                }],
+            ),
+            ConversationMessage::assistant("It's 18°C and sunny."),
+        ];
+
+        let events = LogEvent::genai_conversation(
+            "trace1",
+            "span1",
+            "session1",
+            "aws.bedrock",
+            Some("You are helpful."),
+            &messages,
+        );
+        let names: Vec<&str> = events
+            .iter()
+            .map(|e| e.attributes["event.name"].as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "gen_ai.system.message",
+                "gen_ai.user.message",
+                "gen_ai.assistant.message",
+                "gen_ai.tool.message",
+                "gen_ai.assistant.message",
+            ]
+        );
+        assert!(events.iter().all(|e| e.scope.name == GENAI_SCOPE));
+        assert_eq!(events[3].body.id.as_deref(), Some("call-1"));
+
+        let json = serde_json::to_value(&events[2]).unwrap();
+        assert_eq!(json["body"]["content"], "Let me check.");
+        assert_eq!(json["body"]["tool_calls"][0]["type"], "function");
+        assert_eq!(
+            json["body"]["tool_calls"][0]["function"]["arguments"]["city"],
+            "Paris"
+        );
+        assert!(json["body"].get("input").is_none());
+    }
+
     #[test]
     fn test_default_scope() {
         let scope = LogScope::default();
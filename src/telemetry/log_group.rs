@@ -142,7 +142,8 @@ impl LogGroupManager {
         crate::perf_checkpoint!("log_group_manager.new.start", &region);
 
         let config = crate::perf_timed!("log_group_manager.aws_config_load", {
-            aws_config::defaults(aws_config::BehaviorVersion::latest())
+            crate::llm::http::aws_config_loader()
+                .map_err(|e| LogGroupError::SdkConfig(e.to_string()))?
                 .region(aws_types::region::Region::new(region.clone()))
                 .load()
                 .await
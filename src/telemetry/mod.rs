@@ -3,6 +3,20 @@
 //! This module provides telemetry integration with AWS CloudWatch
 //! Gen AI Observability dashboards.
 //!
+//! # Feature Flags
+//!
+//! - `telemetry` (default) - the span tracer, the CloudWatch OTLP exporter and
+//!   the SigV4 signing in `aws_auth` it uses. Without it, [`StoodTracer`] is a
+//!   no-op facade whose `init` always returns `Ok(None)`, and neither the
+//!   OpenTelemetry dependencies nor `aws-sigv4` are compiled.
+//! - `cloudwatch-logs` (default) - [`LogGroupManager`], which creates the log
+//!   groups the GenAI Dashboard reads from, and the CloudWatch audit sink.
+//!   Without it, the tracer skips the log group check as if
+//!   `skip_log_group_check` were set.
+//!
+//! Configuration, metrics, sessions and log events are always available.
+//! Minimal builds use `default-features = false`.
+//!
 //! # Quick Start
 //!
 //! ```no_run
@@ -28,6 +42,7 @@ use crate::StoodError;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
@@ -35,37 +50,59 @@ use uuid::Uuid;
 pub mod logging;
 
 // Span exporter traits
+#[cfg(feature = "telemetry")]
 pub mod exporter;
 
 // GenAI semantic conventions
 pub mod genai;
 
 // Tracer implementation
+#[cfg(feature = "telemetry")]
 pub mod tracer;
 
+// Tracer facade for builds without telemetry
+#[cfg(not(feature = "telemetry"))]
+mod noop;
+
 // Session management
 pub mod session;
 
 // AWS authentication and SigV4 signing
+#[cfg(feature = "telemetry")]
 pub mod aws_auth;
 
 // CloudWatch Log Group management for GenAI Dashboard
+#[cfg(feature = "cloudwatch-logs")]
 pub mod log_group;
 
 // OTEL Log Events for AgentCore Evaluations
 pub mod log_event;
 
+// Redaction and generalization of captured content
+pub mod anonymize;
+
+pub use anonymize::ContentAnonymizer;
+#[cfg(feature = "telemetry")]
 pub use aws_auth::{xray_otlp_endpoint, AuthError, AwsCredentialsProvider};
+#[cfg(feature = "telemetry")]
 pub use exporter::{ExportError, NoOpExporter, SpanData, SpanExporter};
 pub use genai::{attrs, GenAiOperation, GenAiProvider, GenAiToolType};
-pub use log_event::{LogEvent, LogEventBody, LogResource, LogScope, Message, MessageList};
+pub use log_event::{
+    GenAiFunctionCall, GenAiToolCall, LogEvent, LogEventBody, LogResource, LogScope, Message,
+    MessageList,
+};
+#[cfg(feature = "cloudwatch-logs")]
 pub use log_group::{AgentLogGroup, LogGroupError, LogGroupManager};
 pub use logging::*;
+#[cfg(not(feature = "telemetry"))]
+pub use noop::{Context, KeyValue, StoodSpan, StoodTracer, SESSION_BAGGAGE_KEY};
 pub use session::{Session, SessionManager};
+#[cfg(feature = "telemetry")]
 pub use tracer::{StoodSpan, StoodTracer, SESSION_BAGGAGE_KEY};
 
 // Re-export for backwards compatibility during transition
-pub use opentelemetry::KeyValue;
+#[cfg(feature = "telemetry")]
+pub use opentelemetry::{Context, KeyValue};
 
 /// Log level for telemetry output control
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
@@ -184,6 +221,8 @@ pub enum TelemetryConfig {
         agent_id: Option<String>,
         /// Capture message content (PII risk - default false)
         content_capture: bool,
+        /// Anonymize captured content before export
+        content_anonymization: Option<Arc<ContentAnonymizer>>,
         /// Log level for console output
         log_level: LogLevel,
         /// Skip log group existence check (assume pre-created)
@@ -217,6 +256,7 @@ impl TelemetryConfig {
             service_version: env!("CARGO_PKG_VERSION").to_string(),
             agent_id: None,
             content_capture: false,
+            content_anonymization: None,
             log_level: LogLevel::INFO,
             skip_log_group_check: false,
         }
@@ -234,6 +274,7 @@ impl TelemetryConfig {
             service_version: env!("CARGO_PKG_VERSION").to_string(),
             agent_id: None,
             content_capture: false,
+            content_anonymization: None,
             log_level: LogLevel::INFO,
             skip_log_group_check: false,
         }
@@ -251,6 +292,7 @@ impl TelemetryConfig {
             service_version: env!("CARGO_PKG_VERSION").to_string(),
             agent_id: None,
             content_capture: false,
+            content_anonymization: None,
             log_level: LogLevel::INFO,
             skip_log_group_check: false,
         }
@@ -315,6 +357,7 @@ impl TelemetryConfig {
                     service_version: env!("CARGO_PKG_VERSION").to_string(),
                     agent_id: None,
                     content_capture: false,
+                    content_anonymization: None,
                     log_level,
                     skip_log_group_check: false,
                 },
@@ -349,6 +392,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
                 ..
@@ -359,6 +403,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
             },
@@ -375,6 +420,7 @@ impl TelemetryConfig {
                 service_name,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
                 ..
@@ -385,6 +431,7 @@ impl TelemetryConfig {
                 service_version: version.into(),
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
             },
@@ -405,6 +452,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 skip_log_group_check,
                 ..
             } => Self::CloudWatch {
@@ -414,6 +462,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level: level,
                 skip_log_group_check,
             },
@@ -444,6 +493,7 @@ impl TelemetryConfig {
                 service_name,
                 service_version,
                 agent_id,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
                 ..
@@ -454,12 +504,56 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture: capture,
+                content_anonymization,
+                log_level,
+                skip_log_group_check,
+            },
+        }
+    }
+
+    /// Anonymize captured content before export
+    ///
+    /// Prompts, responses and tool payloads go through the anonymizer's
+    /// redaction and generalization stages before they are exported, so content
+    /// capture can be enabled under strict data governance.
+    pub fn with_content_anonymization(self, anonymizer: ContentAnonymizer) -> Self {
+        match self {
+            Self::Disabled { .. } => self,
+            Self::CloudWatch {
+                region,
+                credentials,
+                service_name,
+                service_version,
+                agent_id,
+                content_capture,
+                log_level,
+                skip_log_group_check,
+                ..
+            } => Self::CloudWatch {
+                region,
+                credentials,
+                service_name,
+                service_version,
+                agent_id,
+                content_capture,
+                content_anonymization: Some(Arc::new(anonymizer)),
                 log_level,
                 skip_log_group_check,
             },
         }
     }
 
+    /// Get the content anonymizer, if captured content is anonymized
+    pub fn content_anonymizer(&self) -> Option<&ContentAnonymizer> {
+        match self {
+            Self::Disabled { .. } => None,
+            Self::CloudWatch {
+                content_anonymization,
+                ..
+            } => content_anonymization.as_deref(),
+        }
+    }
+
     /// Set AWS region
     pub fn with_region(self, region: impl Into<String>) -> Self {
         match self {
@@ -470,6 +564,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
                 ..
@@ -480,6 +575,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
             },
@@ -496,6 +592,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
                 ..
@@ -506,6 +603,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
             },
@@ -528,6 +626,7 @@ impl TelemetryConfig {
                 service_name,
                 service_version,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
                 ..
@@ -538,6 +637,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id: Some(agent_id.into()),
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check,
             },
@@ -562,6 +662,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 ..
             } => Self::CloudWatch {
@@ -571,6 +672,7 @@ impl TelemetryConfig {
                 service_version,
                 agent_id,
                 content_capture,
+                content_anonymization,
                 log_level,
                 skip_log_group_check: skip,
             },
@@ -652,55 +754,18 @@ impl TelemetryConfig {
     /// - `STOOD_CLOUDWATCH_ENABLED`: Enable CloudWatch export (default: false)
     /// - `AWS_REGION`: AWS region (default: us-east-1)
     /// - `OTEL_SERVICE_NAME`: Service name (default: stood-agent)
+    /// - `OTEL_SERVICE_VERSION`: Service version (default: crate version)
+    /// - `STOOD_AGENT_ID`: Agent ID for log group naming
     /// - `STOOD_GENAI_CONTENT_CAPTURE`: Capture message content (default: false)
+    /// - `STOOD_GENAI_CONTENT_ANONYMIZE`: Anonymize captured content (default: false)
     ///
     /// Legacy variables (still supported):
     /// - `OTEL_ENABLED`: Enable telemetry (default: false)
+    ///
+    /// Invalid values are ignored; use [`crate::config::from_env`] for a
+    /// report of them.
     pub fn from_env() -> Self {
-        // Check for new CloudWatch-specific env var first
-        if let Ok(enabled) = std::env::var("STOOD_CLOUDWATCH_ENABLED") {
-            if enabled.to_lowercase() == "true" || enabled == "1" {
-                return Self::CloudWatch {
-                    region: std::env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
-                    credentials: AwsCredentialSource::Environment,
-                    service_name: std::env::var("OTEL_SERVICE_NAME")
-                        .unwrap_or_else(|_| "stood-agent".to_string()),
-                    service_version: std::env::var("OTEL_SERVICE_VERSION")
-                        .unwrap_or_else(|_| env!("CARGO_PKG_VERSION").to_string()),
-                    agent_id: std::env::var("STOOD_AGENT_ID").ok(),
-                    content_capture: std::env::var("STOOD_GENAI_CONTENT_CAPTURE")
-                        .map(|v| v.to_lowercase() == "true" || v == "1")
-                        .unwrap_or(false),
-                    log_level: LogLevel::INFO,
-                    skip_log_group_check: false,
-                };
-            }
-        }
-
-        // Legacy: Check OTEL_ENABLED
-        if let Ok(enabled) = std::env::var("OTEL_ENABLED") {
-            if enabled.to_lowercase() == "true" || enabled == "1" {
-                return Self::CloudWatch {
-                    region: std::env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
-                    credentials: AwsCredentialSource::Environment,
-                    service_name: std::env::var("OTEL_SERVICE_NAME")
-                        .unwrap_or_else(|_| "stood-agent".to_string()),
-                    service_version: std::env::var("OTEL_SERVICE_VERSION")
-                        .unwrap_or_else(|_| env!("CARGO_PKG_VERSION").to_string()),
-                    agent_id: std::env::var("STOOD_AGENT_ID").ok(),
-                    content_capture: false,
-                    log_level: LogLevel::INFO,
-                    skip_log_group_check: false,
-                };
-            }
-        }
-
-        // Default: disabled
-        Self::Disabled {
-            service_name: std::env::var("OTEL_SERVICE_NAME")
-                .unwrap_or_else(|_| "stood-agent".to_string()),
-            log_level: LogLevel::INFO,
-        }
+        crate::config::environment::EnvReader::from_process().telemetry()
     }
 
     /// Create a minimal configuration for testing
@@ -712,6 +777,7 @@ impl TelemetryConfig {
             service_version: env!("CARGO_PKG_VERSION").to_string(),
             agent_id: Some("stood-agent-test".to_string()),
             content_capture: false,
+            content_anonymization: None,
             log_level: LogLevel::DEBUG,
             skip_log_group_check: false,
         }
@@ -779,6 +845,8 @@ pub struct EventLoopMetrics {
     pub total_duration: Duration,
     /// All tool executions with timing and status
     pub tool_executions: Vec<ToolExecutionMetric>,
+    /// All model calls with their latency breakdown
+    pub model_calls: Vec<ModelCallMetric>,
     /// Trace information for correlation
     pub traces: Vec<TraceInfo>,
     /// Accumulated metrics for summary reporting
@@ -810,6 +878,11 @@ impl EventLoopMetrics {
         self.tool_executions.push(execution);
     }
 
+    /// Add a model call to the metrics
+    pub fn add_model_call(&mut self, call: ModelCallMetric) {
+        self.model_calls.push(call);
+    }
+
     /// Add trace information
     pub fn add_trace(&mut self, trace: TraceInfo) {
         self.traces.push(trace);
@@ -852,7 +925,22 @@ impl EventLoopMetrics {
 
     /// Get total time spent on model calls
     pub fn total_model_time(&self) -> Duration {
-        self.total_duration / 2
+        self.model_calls.iter().map(|call| call.duration).sum()
+    }
+
+    /// Average time to the first token of streamed model calls
+    pub fn average_time_to_first_token(&self) -> Option<Duration> {
+        ModelCallMetric::average_time_to_first_token(&self.model_calls)
+    }
+
+    /// Output tokens per second of generation across all model calls
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        ModelCallMetric::tokens_per_second(&self.model_calls)
+    }
+
+    /// Total latency reported by providers, for the calls that report it
+    pub fn total_provider_latency(&self) -> Option<Duration> {
+        ModelCallMetric::total_provider_latency(&self.model_calls)
     }
 
     /// Get total time spent on tool execution
@@ -919,6 +1007,7 @@ impl EventLoopMetrics {
                     .clone()
                     .unwrap_or_else(|| "Unknown error".to_string()),
                 duration: t.duration,
+                input: t.input.clone(),
             })
             .collect()
     }
@@ -1030,6 +1119,63 @@ pub struct ToolExecutionMetric {
     pub start_time: DateTime<Utc>,
     pub input_size_bytes: Option<usize>,
     pub output_size_bytes: Option<usize>,
+    /// Input of the call, kept for failed calls only
+    pub input: Option<serde_json::Value>,
+}
+
+/// Latency breakdown of a single model call
+///
+/// Compare `time_to_first_token` and `provider_latency` with `duration` to
+/// tell a slow provider from a slow network, and the cycle's tool time to
+/// tell slow models from slow tools.
+#[derive(Debug, Clone)]
+pub struct ModelCallMetric {
+    /// From sending the request to the complete response
+    pub duration: Duration,
+    /// From sending the request to the first streamed content; `None` without streaming
+    pub time_to_first_token: Option<Duration>,
+    /// Latency the provider reported for the call, if it reports one
+    pub provider_latency: Option<Duration>,
+    pub output_tokens: u32,
+    pub streamed: bool,
+    pub start_time: DateTime<Utc>,
+}
+
+impl ModelCallMetric {
+    /// Output tokens per second of generation, counted from the first token when streamed
+    pub fn output_tokens_per_second(&self) -> Option<f64> {
+        Self::tokens_per_second(std::slice::from_ref(self))
+    }
+
+    /// Time spent generating the response, after the first token when streamed
+    fn generation_time(&self) -> Duration {
+        self.duration
+            .saturating_sub(self.time_to_first_token.unwrap_or_default())
+    }
+
+    /// Average time to the first token of the streamed calls in `calls`
+    pub fn average_time_to_first_token(calls: &[ModelCallMetric]) -> Option<Duration> {
+        let times: Vec<Duration> = calls
+            .iter()
+            .filter_map(|call| call.time_to_first_token)
+            .collect();
+        (!times.is_empty()).then(|| times.iter().sum::<Duration>() / times.len() as u32)
+    }
+
+    /// Output tokens per second of generation across `calls`
+    pub fn tokens_per_second(calls: &[ModelCallMetric]) -> Option<f64> {
+        let tokens: u32 = calls.iter().map(|call| call.output_tokens).sum();
+        let generation: Duration = calls.iter().map(Self::generation_time).sum();
+        (tokens > 0 && !generation.is_zero()).then(|| tokens as f64 / generation.as_secs_f64())
+    }
+
+    /// Total provider-reported latency of the calls in `calls` that report it
+    pub fn total_provider_latency(calls: &[ModelCallMetric]) -> Option<Duration> {
+        calls
+            .iter()
+            .filter_map(|call| call.provider_latency)
+            .reduce(|total, latency| total + latency)
+    }
 }
 
 /// Trace information for correlation
@@ -1193,6 +1339,44 @@ mod tests {
         assert_eq!(summary.total_duration, Duration::from_millis(100));
     }
 
+    #[test]
+    fn test_model_call_latency_breakdown() {
+        let call =
+            |duration_ms, ttft_ms: Option<u64>, latency_ms: Option<u64>, tokens| ModelCallMetric {
+                duration: Duration::from_millis(duration_ms),
+                time_to_first_token: ttft_ms.map(Duration::from_millis),
+                provider_latency: latency_ms.map(Duration::from_millis),
+                output_tokens: tokens,
+                streamed: ttft_ms.is_some(),
+                start_time: Utc::now(),
+            };
+        let mut metrics = EventLoopMetrics::new();
+        metrics.add_model_call(call(1500, Some(500), Some(1200), 100));
+        metrics.add_model_call(call(2000, Some(1000), None, 50));
+
+        assert_eq!(metrics.total_model_time(), Duration::from_millis(3500));
+        assert_eq!(
+            metrics.average_time_to_first_token(),
+            Some(Duration::from_millis(750))
+        );
+        assert_eq!(metrics.tokens_per_second(), Some(75.0));
+        assert_eq!(
+            metrics.total_provider_latency(),
+            Some(Duration::from_millis(1200))
+        );
+        assert_eq!(
+            metrics.model_calls[0].output_tokens_per_second(),
+            Some(100.0)
+        );
+
+        let unstreamed = call(400, None, None, 0);
+        assert_eq!(unstreamed.output_tokens_per_second(), None);
+        assert_eq!(
+            ModelCallMetric::average_time_to_first_token(&[unstreamed]),
+            None
+        );
+    }
+
     #[test]
     fn test_token_usage() {
         let mut usage = TokenUsage::new(100, 50);
@@ -0,0 +1,299 @@
+//! No-op tracer for builds without the `telemetry` feature
+//!
+//! Mirrors the API of the CloudWatch tracer so instrumented code compiles
+//! unchanged, but never records or exports anything. [`StoodTracer::init`]
+//! and [`StoodTracer::init_async`] always return `Ok(None)`, so agents run
+//! exactly as they do with telemetry disabled.
+
+use super::log_event::LogEvent;
+use super::session::Session;
+use super::TelemetryConfig;
+use crate::StoodError;
+
+/// Baggage key for session ID - used by CloudWatch Gen AI Observability
+/// to group spans into sessions in the dashboard
+pub const SESSION_BAGGAGE_KEY: &str = "session.id";
+
+/// Placeholder for the OpenTelemetry context propagated between spans
+#[derive(Debug, Clone, Default)]
+pub struct Context;
+
+impl Context {
+    pub fn current() -> Self {
+        Self
+    }
+}
+
+/// Placeholder for an OpenTelemetry span event attribute
+#[derive(Debug, Clone)]
+pub struct KeyValue;
+
+impl KeyValue {
+    pub fn new<K, V>(_key: K, _value: V) -> Self {
+        Self
+    }
+}
+
+/// Tracer that discards everything
+///
+/// Never returned by [`StoodTracer::init`]; only exists so code holding an
+/// `Option<StoodTracer>` compiles without the `telemetry` feature.
+#[derive(Debug, Clone)]
+pub struct StoodTracer {
+    config: TelemetryConfig,
+}
+
+impl StoodTracer {
+    /// Always `Ok(None)`; warns if `config` enables telemetry
+    pub fn init(config: TelemetryConfig) -> Result<Option<Self>, StoodError> {
+        if config.is_enabled() {
+            tracing::warn!(
+                "Telemetry is configured but stood was built without the `telemetry` feature; no spans will be exported"
+            );
+        }
+        Ok(None)
+    }
+
+    /// Always `Ok(None)`; warns if `config` enables telemetry
+    pub async fn init_async(config: TelemetryConfig) -> Result<Option<Self>, StoodError> {
+        Self::init(config)
+    }
+
+    pub fn init_tracing_subscriber() -> Result<(), StoodError> {
+        Ok(())
+    }
+
+    pub fn start_session(&self) -> Session {
+        Session::new()
+    }
+
+    pub fn start_session_with_id(&self, conversation_id: impl Into<String>) -> Session {
+        Session::with_conversation_id(conversation_id)
+    }
+
+    pub fn start_session_with_ids(
+        &self,
+        session_id: impl Into<String>,
+        conversation_id: impl Into<String>,
+    ) -> Session {
+        Session::with_ids(session_id, conversation_id)
+    }
+
+    pub fn set_session(&self, _session: Session) {}
+
+    pub fn current_session(&self) -> Option<Session> {
+        None
+    }
+
+    pub fn current_session_id(&self) -> Option<String> {
+        None
+    }
+
+    pub fn current_conversation_id(&self) -> Option<String> {
+        None
+    }
+
+    pub fn clear_session(&self) {}
+
+    pub fn start_trace(&self) {}
+
+    pub fn current_trace_id(&self) -> Option<String> {
+        None
+    }
+
+    pub fn start_chat_span(&self, _model: &str) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_invoke_agent_span(&self, _agent_name: &str, _agent_id: Option<&str>) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_execute_tool_span(
+        &self,
+        _tool_name: &str,
+        _tool_call_id: Option<&str>,
+    ) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_span(&self, _name: &str) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_agent_span(&self, _name: &str) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_cycle_span_with_dynamic_attributes(
+        &self,
+        _name: &str,
+        _cycle_id: &str,
+        _attributes: Vec<(&str, String)>,
+        _parent_context: Option<Context>,
+    ) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_model_span_with_dynamic_attributes(
+        &self,
+        _name: &str,
+        _model: &str,
+        _attributes: Vec<(&str, String)>,
+        _parent_context: Option<Context>,
+    ) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_tool_span(&self, _name: &str) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_tool_span_with_parent_context(
+        &self,
+        _name: &str,
+        _parent_context: &Context,
+    ) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_tool_span_with_name_and_parent(
+        &self,
+        _tool_name: &str,
+        _parent_context: Option<Context>,
+    ) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn start_model_span(&self, _name: &str) -> StoodSpan {
+        StoodSpan::new()
+    }
+
+    pub fn queue_log_event(&self, _event: LogEvent) {}
+
+    pub fn queue_agent_invocation_log(
+        &self,
+        _trace_id: &str,
+        _span_id: &str,
+        _system_prompt: Option<&str>,
+        _user_prompt: &str,
+        _assistant_response: &str,
+    ) {
+    }
+
+    pub fn queue_agent_invocation_with_tools_log(
+        &self,
+        _trace_id: &str,
+        _span_id: &str,
+        _system_prompt: Option<&str>,
+        _user_prompt: &str,
+        _tool_results: &[(String, String, String)],
+        _assistant_response: &str,
+    ) {
+    }
+
+    pub fn queue_tool_execution_log(
+        &self,
+        _trace_id: &str,
+        _span_id: &str,
+        _tool_name: &str,
+        _tool_input: &str,
+        _tool_output: &str,
+    ) {
+    }
+
+    pub fn queue_chat_completion_log(
+        &self,
+        _trace_id: &str,
+        _span_id: &str,
+        _model: &str,
+        _user_input: &str,
+        _assistant_output: &str,
+    ) {
+    }
+
+    pub fn queue_genai_message_logs(
+        &self,
+        _trace_id: &str,
+        _span_id: &str,
+        _system_prompt: Option<&str>,
+        _messages: &[crate::types::Message],
+    ) {
+    }
+
+    pub fn pending_log_events_count(&self) -> usize {
+        0
+    }
+
+    pub fn shutdown(&self) {}
+
+    pub async fn flush(&self) -> Result<(), std::convert::Infallible> {
+        Ok(())
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        true
+    }
+
+    pub fn config(&self) -> &TelemetryConfig {
+        &self.config
+    }
+
+    pub fn can_export_log_events(&self) -> bool {
+        false
+    }
+}
+
+/// Span that records nothing
+#[derive(Debug, Default)]
+pub struct StoodSpan {
+    context: Context,
+}
+
+impl StoodSpan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn context(&self) -> Context {
+        self.context.clone()
+    }
+
+    pub fn trace_id(&self) -> &str {
+        ""
+    }
+
+    pub fn span_id(&self) -> &str {
+        ""
+    }
+
+    pub fn set_attribute<V>(&mut self, _key: &str, _value: V) {}
+
+    pub fn set_string_attribute(&mut self, _key: &str, _value: impl ToString) {}
+
+    pub fn set_int_attribute(&mut self, _key: &str, _value: i64) {}
+
+    pub fn set_float_attribute(&mut self, _key: &str, _value: f64) {}
+
+    pub fn set_bool_attribute(&mut self, _key: &str, _value: bool) {}
+
+    pub fn record_error(&mut self, _error: &str) {}
+
+    pub fn set_error(&mut self, _error: &str) {}
+
+    pub fn set_success(&mut self) {}
+
+    pub fn add_event(&mut self, _name: &str, _attributes: Vec<KeyValue>) {}
+
+    pub fn record_tokens(&mut self, _input_tokens: u32, _output_tokens: u32) {}
+
+    pub fn record_latency(&mut self, _calls: &[super::ModelCallMetric]) {}
+
+    pub fn record_response(&mut self, _response_id: &str, _finish_reasons: &[&str]) {}
+
+    pub fn finish(self) {}
+
+    pub fn finish_with_duration(self, _duration: std::time::Duration) {}
+
+    pub fn end(self) {}
+}
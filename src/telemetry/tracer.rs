@@ -9,7 +9,7 @@ use super::exporter::{
 use super::genai::{attrs, GenAiOperation, GenAiProvider};
 use super::log_event::LogEvent;
 use super::session::Session;
-use super::TelemetryConfig;
+use super::{ModelCallMetric, TelemetryConfig};
 use crate::StoodError;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -310,58 +310,7 @@ impl StoodTracer {
                         effective_agent_id
                     );
                 } else {
-                    tracing::info!(
-                        "LOG_GROUP_CHECK: Performing log group check for agent_id={}",
-                        effective_agent_id
-                    );
-                    crate::perf_checkpoint!("stood.tracer.log_group.start");
-                    let log_group_result = crate::perf_timed!("stood.tracer.log_group_manager_new", {
-                        super::log_group::LogGroupManager::new(region.clone()).await
-                    });
-                    match log_group_result {
-                        Ok(manager) => {
-                            let log_group_config =
-                                super::log_group::AgentLogGroup::new(&effective_agent_id);
-                            let ensure_result = crate::perf_timed!("stood.tracer.log_group_ensure_exists", {
-                                manager.ensure_exists(&log_group_config).await
-                            });
-                            match ensure_result {
-                                Ok(created) => {
-                                    if created {
-                                        crate::perf_checkpoint!("stood.tracer.log_group.created", &log_group_config.log_group_name());
-                                        tracing::info!(
-                                            "Created CloudWatch log group: {}",
-                                            log_group_config.log_group_name()
-                                        );
-                                    } else {
-                                        crate::perf_checkpoint!("stood.tracer.log_group.exists", &log_group_config.log_group_name());
-                                        tracing::debug!(
-                                            "CloudWatch log group already exists: {}",
-                                            log_group_config.log_group_name()
-                                        );
-                                    }
-                                }
-                                Err(e) => {
-                                    crate::perf_checkpoint!("stood.tracer.log_group.error", &e.to_string());
-                                    // Log warning but continue - telemetry will still work,
-                                    // just won't appear in GenAI Dashboard
-                                    tracing::warn!(
-                                        "Failed to create log group '{}': {}. Spans may not appear in GenAI Dashboard.",
-                                        log_group_config.log_group_name(),
-                                        e
-                                    );
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            crate::perf_checkpoint!("stood.tracer.log_group_manager.error", &e.to_string());
-                            tracing::warn!(
-                                "Failed to initialize log group manager: {}. Spans may not appear in GenAI Dashboard.",
-                                e
-                            );
-                        }
-                    }
-                    crate::perf_checkpoint!("stood.tracer.log_group.end");
+                    Self::ensure_log_group(region, &effective_agent_id).await;
                 }
 
                 // Create CloudWatch exporter with configured credentials
@@ -390,6 +339,79 @@ impl StoodTracer {
         }
     }
 
+    /// Create the GenAI Dashboard log group of `agent_id` if it does not exist
+    ///
+    /// Failures are logged; telemetry still works, but spans may not appear
+    /// in the dashboard.
+    #[cfg(feature = "cloudwatch-logs")]
+    async fn ensure_log_group(region: &str, agent_id: &str) {
+        tracing::info!(
+            "LOG_GROUP_CHECK: Performing log group check for agent_id={}",
+            agent_id
+        );
+        crate::perf_checkpoint!("stood.tracer.log_group.start");
+        let log_group_result = crate::perf_timed!("stood.tracer.log_group_manager_new", {
+            super::log_group::LogGroupManager::new(region.to_string()).await
+        });
+        match log_group_result {
+            Ok(manager) => {
+                let log_group_config = super::log_group::AgentLogGroup::new(agent_id);
+                let ensure_result = crate::perf_timed!("stood.tracer.log_group_ensure_exists", {
+                    manager.ensure_exists(&log_group_config).await
+                });
+                match ensure_result {
+                    Ok(created) => {
+                        if created {
+                            crate::perf_checkpoint!(
+                                "stood.tracer.log_group.created",
+                                &log_group_config.log_group_name()
+                            );
+                            tracing::info!(
+                                "Created CloudWatch log group: {}",
+                                log_group_config.log_group_name()
+                            );
+                        } else {
+                            crate::perf_checkpoint!(
+                                "stood.tracer.log_group.exists",
+                                &log_group_config.log_group_name()
+                            );
+                            tracing::debug!(
+                                "CloudWatch log group already exists: {}",
+                                log_group_config.log_group_name()
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        crate::perf_checkpoint!("stood.tracer.log_group.error", &e.to_string());
+                        // Log warning but continue - telemetry will still work,
+                        // just won't appear in GenAI Dashboard
+                        tracing::warn!(
+                            "Failed to create log group '{}': {}. Spans may not appear in GenAI Dashboard.",
+                            log_group_config.log_group_name(),
+                            e
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                crate::perf_checkpoint!("stood.tracer.log_group_manager.error", &e.to_string());
+                tracing::warn!(
+                    "Failed to initialize log group manager: {}. Spans may not appear in GenAI Dashboard.",
+                    e
+                );
+            }
+        }
+        crate::perf_checkpoint!("stood.tracer.log_group.end");
+    }
+
+    #[cfg(not(feature = "cloudwatch-logs"))]
+    async fn ensure_log_group(_region: &str, agent_id: &str) {
+        tracing::debug!(
+            "Built without the `cloudwatch-logs` feature; skipping log group check for agent_id={}",
+            agent_id
+        );
+    }
+
     /// Initialize tracing subscriber for log correlation
     pub fn init_tracing_subscriber() -> Result<(), StoodError> {
         // This will be implemented when we add log correlation
@@ -674,8 +696,12 @@ impl StoodTracer {
     ///
     /// Log events contain the prompt/response content that AgentCore Evaluations
     /// like Correctness and Conciseness require. They are exported to CloudWatch
-    /// Logs alongside spans.
-    pub fn queue_log_event(&self, event: LogEvent) {
+    /// Logs alongside spans. Their content is anonymized first when the
+    /// configuration has [`TelemetryConfig::with_content_anonymization`].
+    pub fn queue_log_event(&self, mut event: LogEvent) {
+        if let Some(anonymizer) = self.config.content_anonymizer() {
+            anonymizer.anonymize_log_event(&mut event);
+        }
         if let Ok(mut pending) = self.pending_log_events.lock() {
             pending.push(event);
         }
@@ -797,6 +823,34 @@ impl StoodTracer {
         self.queue_log_event(event);
     }
 
+    /// Create and queue GenAI message events for a conversation
+    ///
+    /// Emits `gen_ai.*.message` events per the GenAI semantic conventions in
+    /// addition to the LangChain-format events, giving evaluations
+    /// message-level data. See [`LogEvent::genai_conversation`].
+    pub fn queue_genai_message_logs(
+        &self,
+        trace_id: &str,
+        span_id: &str,
+        system_prompt: Option<&str>,
+        messages: &[crate::types::Message],
+    ) {
+        let session_id = self
+            .current_session_id()
+            .unwrap_or_else(|| "unknown".to_string());
+
+        for event in LogEvent::genai_conversation(
+            trace_id,
+            span_id,
+            &session_id,
+            GenAiProvider::AwsBedrock.as_str(),
+            system_prompt,
+            messages,
+        ) {
+            self.queue_log_event(event);
+        }
+    }
+
     /// Get the number of pending log events
     pub fn pending_log_events_count(&self) -> usize {
         self.pending_log_events.lock().map(|p| p.len()).unwrap_or(0)
@@ -1036,6 +1090,19 @@ impl StoodSpan {
         self.set_int_attribute(attrs::USAGE_OUTPUT_TOKENS, output_tokens as i64);
     }
 
+    /// Record the latency breakdown of model calls
+    pub fn record_latency(&mut self, calls: &[ModelCallMetric]) {
+        if let Some(ttft) = ModelCallMetric::average_time_to_first_token(calls) {
+            self.set_int_attribute(attrs::STOOD_TIME_TO_FIRST_TOKEN_MS, ttft.as_millis() as i64);
+        }
+        if let Some(rate) = ModelCallMetric::tokens_per_second(calls) {
+            self.set_float_attribute(attrs::STOOD_TOKENS_PER_SECOND, rate);
+        }
+        if let Some(latency) = ModelCallMetric::total_provider_latency(calls) {
+            self.set_int_attribute(attrs::STOOD_PROVIDER_LATENCY_MS, latency.as_millis() as i64);
+        }
+    }
+
     /// Record response metadata
     pub fn record_response(&mut self, response_id: &str, finish_reasons: &[&str]) {
         self.set_string_attribute(attrs::RESPONSE_ID, response_id);
@@ -0,0 +1,459 @@
+//! Tamper-evident audit log of tool executions.
+//!
+//! An [`AuditLog`] attached to a [`ToolRegistry`](super::ToolRegistry) appends
+//! an [`AuditRecord`] for every tool the registry executes: the tool name, the
+//! calling agent, SHA-256 hashes of the parameters and of the result, and a
+//! timestamp. Each record also carries the hash of the previous record, so
+//! editing, removing or reordering records breaks the chain and is detected by
+//! [`verify_chain`]. Records are written to one or more [`AuditSink`]s, such as
+//! a JSON Lines file ([`FileAuditSink`]) or CloudWatch Logs
+//! ([`CloudWatchAuditSink`]).
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! use stood::tools::audit::{verify_chain, AuditLog, FileAuditSink};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let path = "tool-audit.jsonl";
+//! // Continue the chain already in the file
+//! let existing = FileAuditSink::read_records(path).await?;
+//! verify_chain(&existing)?;
+//! let audit = AuditLog::new()
+//!     .continue_from(existing.last())
+//!     .with_sink(FileAuditSink::open(path).await?);
+//!
+//! let mut agent = Agent::builder().with_audit_log(audit).build().await?;
+//! agent.execute("What is 6 * 7?").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Parameters and results are only stored as hashes, so the log can be kept
+//! without retaining sensitive tool data. Failing to write a record is logged
+//! and does not fail the tool call.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+#[cfg(feature = "cloudwatch-logs")]
+use aws_sdk_cloudwatchlogs::types::InputLogEvent;
+#[cfg(feature = "cloudwatch-logs")]
+use aws_sdk_cloudwatchlogs::Client as CloudWatchLogsClient;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use super::{ToolError, ToolResult};
+use crate::{Result, StoodError};
+
+/// `previous_hash` of the first record of a chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One tool execution in the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Position in the chain, starting at 0
+    pub sequence: u64,
+    /// When the tool execution finished
+    pub timestamp: DateTime<Utc>,
+    /// Name of the executed tool
+    pub tool_name: String,
+    /// Agent that called the tool, if known
+    pub agent_id: Option<String>,
+    /// SHA-256 of the parameters the tool was executed with
+    pub params_hash: String,
+    /// SHA-256 of the result, or of the error the tool failed with
+    pub result_hash: String,
+    /// Whether the tool succeeded
+    pub success: bool,
+    /// `hash` of the previous record ([`GENESIS_HASH`] for the first one)
+    pub previous_hash: String,
+    /// SHA-256 of this record's other fields
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// Hash of every field but `hash`
+    pub fn compute_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for field in [
+            self.sequence.to_string().as_str(),
+            &self.timestamp.to_rfc3339(),
+            &self.tool_name,
+            self.agent_id.as_deref().unwrap_or(""),
+            &self.params_hash,
+            &self.result_hash,
+            if self.success { "ok" } else { "error" },
+            &self.previous_hash,
+        ] {
+            hasher.update(field.as_bytes());
+            hasher.update([0]);
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Hex SHA-256 of a JSON value
+pub fn hash_value(value: &Value) -> String {
+    hex::encode(Sha256::digest(value.to_string().as_bytes()))
+}
+
+/// Why [`verify_chain`] rejected a list of records
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AuditChainError {
+    /// The record's content does not match its hash
+    #[error("audit record {sequence} was modified")]
+    HashMismatch { sequence: u64 },
+    /// The record does not point to the hash of the record before it
+    #[error("audit record {sequence} does not follow the previous record")]
+    BrokenLink { sequence: u64 },
+    /// Records are missing before this one
+    #[error("audit records are missing before record {sequence}")]
+    SequenceGap { sequence: u64 },
+}
+
+/// Check that `records` are unmodified and form an unbroken chain
+///
+/// The list may start part-way through a chain (e.g. after log rotation);
+/// a list starting at sequence 0 must start from [`GENESIS_HASH`].
+pub fn verify_chain(records: &[AuditRecord]) -> std::result::Result<(), AuditChainError> {
+    let mut previous: Option<&AuditRecord> = None;
+    for record in records {
+        if record.compute_hash() != record.hash {
+            return Err(AuditChainError::HashMismatch {
+                sequence: record.sequence,
+            });
+        }
+        let expected_previous = match previous {
+            Some(previous) => {
+                if record.sequence != previous.sequence + 1 {
+                    return Err(AuditChainError::SequenceGap {
+                        sequence: record.sequence,
+                    });
+                }
+                Some(previous.hash.as_str())
+            }
+            None if record.sequence == 0 => Some(GENESIS_HASH),
+            None => None,
+        };
+        if expected_previous.is_some_and(|hash| hash != record.previous_hash) {
+            return Err(AuditChainError::BrokenLink {
+                sequence: record.sequence,
+            });
+        }
+        previous = Some(record);
+    }
+    Ok(())
+}
+
+/// Destination of audit records
+#[async_trait]
+pub trait AuditSink: Send + Sync + fmt::Debug {
+    /// Append `record`; records are written one at a time, in chain order
+    async fn write(&self, record: &AuditRecord) -> Result<()>;
+}
+
+/// Appends records as JSON Lines to a file
+#[derive(Debug)]
+pub struct FileAuditSink {
+    file: Mutex<tokio::fs::File>,
+}
+
+impl FileAuditSink {
+    /// Open `path` for appending, creating it if needed
+    pub async fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())
+            .await?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Records stored in `path`; empty if the file does not exist
+    pub async fn read_records(path: impl AsRef<Path>) -> std::io::Result<Vec<AuditRecord>> {
+        let content = match tokio::fs::read_to_string(path.as_ref()).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(std::io::Error::from))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    async fn write(&self, record: &AuditRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let mut file = self.file.lock().await;
+        async {
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await
+        }
+        .await
+        .map_err(|e| StoodError::internal_error(format!("Failed to write audit record: {}", e)))
+    }
+}
+
+/// Sends records as JSON log events to a CloudWatch Logs stream
+///
+/// The log group and stream must already exist. Requires the
+/// `cloudwatch-logs` feature.
+#[cfg(feature = "cloudwatch-logs")]
+#[derive(Debug, Clone)]
+pub struct CloudWatchAuditSink {
+    client: CloudWatchLogsClient,
+    log_group: String,
+    log_stream: String,
+}
+
+#[cfg(feature = "cloudwatch-logs")]
+impl CloudWatchAuditSink {
+    /// Write to `log_stream` in `log_group` with `client`
+    pub fn new(
+        client: CloudWatchLogsClient,
+        log_group: impl Into<String>,
+        log_stream: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            log_group: log_group.into(),
+            log_stream: log_stream.into(),
+        }
+    }
+}
+
+#[cfg(feature = "cloudwatch-logs")]
+#[async_trait]
+impl AuditSink for CloudWatchAuditSink {
+    async fn write(&self, record: &AuditRecord) -> Result<()> {
+        let event = InputLogEvent::builder()
+            .timestamp(record.timestamp.timestamp_millis())
+            .message(serde_json::to_string(record)?)
+            .build()
+            .map_err(|e| StoodError::configuration_error(e.to_string()))?;
+        self.client
+            .put_log_events()
+            .log_group_name(&self.log_group)
+            .log_stream_name(&self.log_stream)
+            .log_events(event)
+            .send()
+            .await
+            .map_err(|e| {
+                StoodError::network_error(format!(
+                    "Failed to write audit record to CloudWatch Logs: {}",
+                    e
+                ))
+            })?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ChainState {
+    next_sequence: u64,
+    last_hash: String,
+}
+
+/// Hash chain of tool executions written to [`AuditSink`]s
+///
+/// Clones append to the same chain.
+#[derive(Clone)]
+pub struct AuditLog {
+    state: Arc<Mutex<ChainState>>,
+    sinks: Vec<Arc<dyn AuditSink>>,
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("sinks", &self.sinks)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuditLog {
+    /// Start a new chain with no sinks
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ChainState {
+                next_sequence: 0,
+                last_hash: GENESIS_HASH.to_string(),
+            })),
+            sinks: Vec::new(),
+        }
+    }
+
+    /// Write records to `sink` as well
+    pub fn with_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Continue the chain after `last`, the final record written by a previous
+    /// process; `None` starts a new chain
+    pub fn continue_from(self, last: Option<&AuditRecord>) -> Self {
+        if let Some(last) = last {
+            let mut state = self.state.try_lock().expect("audit log not yet shared");
+            state.next_sequence = last.sequence + 1;
+            state.last_hash = last.hash.clone();
+        }
+        self
+    }
+
+    /// Append a record of a tool execution and write it to every sink
+    ///
+    /// All sinks are attempted; the first sink error is returned.
+    pub async fn record(
+        &self,
+        tool_name: &str,
+        agent_id: Option<&str>,
+        params: &Value,
+        outcome: std::result::Result<&ToolResult, &ToolError>,
+    ) -> Result<AuditRecord> {
+        let (result_hash, success) = match outcome {
+            Ok(result) => (hash_value(&serde_json::to_value(result)?), result.success),
+            Err(error) => (
+                hash_value(&serde_json::json!({ "error": error.to_string() })),
+                false,
+            ),
+        };
+
+        // Held while writing so records reach the sinks in chain order
+        let mut state = self.state.lock().await;
+        let mut record = AuditRecord {
+            sequence: state.next_sequence,
+            timestamp: Utc::now(),
+            tool_name: tool_name.to_string(),
+            agent_id: agent_id.map(str::to_string),
+            params_hash: hash_value(params),
+            result_hash,
+            success,
+            previous_hash: state.last_hash.clone(),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+        state.next_sequence += 1;
+        state.last_hash = record.hash.clone();
+
+        let mut first_error = None;
+        for sink in &self.sinks {
+            if let Err(e) = sink.write(&record).await {
+                first_error.get_or_insert(e);
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(record),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Default)]
+    struct MemorySink(std::sync::Mutex<Vec<AuditRecord>>);
+
+    #[async_trait]
+    impl AuditSink for Arc<MemorySink> {
+        async fn write(&self, record: &AuditRecord) -> Result<()> {
+            self.0.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_form_verifiable_chain() {
+        let sink = Arc::new(MemorySink::default());
+        let audit = AuditLog::new().with_sink(sink.clone());
+        let params = json!({"a": 1});
+        audit
+            .record(
+                "calc",
+                Some("agent-1"),
+                &params,
+                Ok(&ToolResult::success(json!(1))),
+            )
+            .await
+            .unwrap();
+        let error = ToolError::execution_failed("boom");
+        audit
+            .record("calc", None, &params, Err(&error))
+            .await
+            .unwrap();
+
+        let mut records = sink.0.lock().unwrap().clone();
+        assert_eq!(records[0].previous_hash, GENESIS_HASH);
+        assert_eq!(records[1].previous_hash, records[0].hash);
+        assert_eq!(records[0].params_hash, records[1].params_hash);
+        assert!(!records[1].success);
+        assert_eq!(verify_chain(&records), Ok(()));
+        assert_eq!(verify_chain(&records[1..]), Ok(()));
+
+        records[0].tool_name = "other".to_string();
+        assert_eq!(
+            verify_chain(&records),
+            Err(AuditChainError::HashMismatch { sequence: 0 })
+        );
+        assert_eq!(
+            verify_chain(&[records[1].clone(), records[1].clone()]),
+            Err(AuditChainError::SequenceGap { sequence: 1 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_round_trip_and_continue_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let audit = AuditLog::new().with_sink(FileAuditSink::open(&path).await.unwrap());
+        audit
+            .record(
+                "echo",
+                None,
+                &json!("hi"),
+                Ok(&ToolResult::success(json!("hi"))),
+            )
+            .await
+            .unwrap();
+
+        let existing = FileAuditSink::read_records(&path).await.unwrap();
+        let resumed = AuditLog::new()
+            .continue_from(existing.last())
+            .with_sink(FileAuditSink::open(&path).await.unwrap());
+        let record = resumed
+            .record(
+                "echo",
+                None,
+                &json!("again"),
+                Ok(&ToolResult::success(json!(null))),
+            )
+            .await
+            .unwrap();
+        assert_eq!(record.sequence, 1);
+
+        let records = FileAuditSink::read_records(&path).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(verify_chain(&records), Ok(()));
+    }
+}
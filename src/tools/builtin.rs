@@ -10,9 +10,15 @@
 //! - **[`FileReadTool`]** - Read text files from the filesystem
 //! - **[`FileWriteTool`]** - Write content to text files
 //! - **[`FileListTool`]** - List directory contents with metadata
+//! - **[`FileEditTool`]** - Apply search/replace edits or unified diffs with dry-run and backup
+//!
+//! ## Search
+//! - **[`GrepTool`]** - Regex search across a directory tree with context lines
+//! - **[`GlobTool`]** - Find files by glob pattern, honoring `.gitignore`
 //!
 //! ## Web & Network
 //! - **[`HttpRequestTool`]** - Make HTTP requests to external APIs
+//! - **[`WebPageTool`]** - Fetch a page and extract readable markdown or text
 //!
 //! ## Calculations & Data
 //! - **[`CalculatorTool`]** - Evaluate mathematical expressions
@@ -260,9 +266,11 @@
 //! - **Size limits**: No built-in limits on file sizes or directory listings
 //!
 //! ## HTTP Requests
-//! - **SSRF protection**: No built-in restrictions on target URLs
-//! - **Request size**: No limits on request/response body sizes
-//! - **Timeouts**: Uses default reqwest timeouts
+//! - **SSRF protection**: Targets are checked against the installed
+//!   [`NetworkPolicy`](super::network::NetworkPolicy), which allows everything
+//!   unless configured
+//! - **Request size**: Response bodies are limited by the network policy
+//! - **Timeouts**: 30 seconds per request
 //!
 //! ## Environment Variables
 //! - **Sensitive data**: Environment variables may contain secrets
@@ -281,9 +289,9 @@
 //! - **Directory listing**: Memory usage scales with number of entries
 //!
 //! ## HTTP Tool
-//! - **Connection pooling**: Uses reqwest's built-in connection management
+//! - **Connection pooling**: A new client per request, pinned to the addresses the network policy allowed
 //! - **Memory usage**: Buffers entire response body in memory
-//! - **Timeout behavior**: Respects reqwest's default timeouts
+//! - **Timeout behavior**: 30 second timeout per request
 //!
 //! # Architecture
 //!
@@ -311,7 +319,8 @@
 //! - **Registry integration**: ~5µs registration overhead per tool
 //! - **Memory usage**: <1KB per tool instance (excluding execution state)
 
-use crate::tools::{Tool, ToolError, ToolRegistry, ToolResult};
+use crate::secrets::{ScopedSecrets, SecretsProvider};
+use crate::tools::{Tool, ToolAnnotations, ToolError, ToolRegistry, ToolResult};
 use std::collections::HashMap;
 
 /// A simple calculator tool implementation
@@ -357,6 +366,10 @@ impl Tool for CalculatorTool {
         })
     }
 
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only().with_open_world(false)
+    }
+
     async fn execute(
         &self,
         parameters: Option<serde_json::Value>,
@@ -391,8 +404,8 @@ impl Tool for CalculatorTool {
         match result {
             Ok(value) => {
                 let result_json =
-                    serde_json::to_value(value).map_err(|e| ToolError::ExecutionFailed {
-                        message: format!("Failed to serialize result: {}", e),
+                    serde_json::to_value(value).map_err(|e| {
+                        ToolError::with_source(format!("Failed to serialize result: {}", e), e)
                     })?;
                 Ok(ToolResult::success(result_json))
             }
@@ -522,6 +535,10 @@ impl Tool for FileReadTool {
         })
     }
 
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only().with_open_world(false)
+    }
+
     async fn execute(
         &self,
         parameters: Option<serde_json::Value>,
@@ -604,6 +621,10 @@ impl Tool for FileWriteTool {
         })
     }
 
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::destructive().with_open_world(false)
+    }
+
     async fn execute(
         &self,
         parameters: Option<serde_json::Value>,
@@ -693,6 +714,10 @@ impl Tool for FileListTool {
         })
     }
 
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only().with_open_world(false)
+    }
+
     async fn execute(
         &self,
         parameters: Option<serde_json::Value>,
@@ -765,58 +790,99 @@ impl Tool for FileListTool {
     }
 }
 
-/// HTTP request tool for making HTTP calls
+/// File edit tool for applying targeted edits to existing text files
+///
+/// Supports search/replace edits and single-file unified diffs. Edits are applied in memory
+/// and only written once every edit has matched, so a partially-applied change
+/// never reaches disk. A `.bak` copy of the original is written by default.
 #[derive(Debug)]
-pub struct HttpRequestTool;
+pub struct FileEditTool;
 
-impl HttpRequestTool {
+impl FileEditTool {
     pub fn new() -> Self {
         Self
     }
 }
 
-impl Default for HttpRequestTool {
+impl Default for FileEditTool {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// A single search/replace edit for [`FileEditTool`]
+#[derive(Debug, Clone, serde::Deserialize)]
+struct SearchReplaceEdit {
+    old_string: String,
+    new_string: String,
+    #[serde(default)]
+    replace_all: bool,
+}
+
 #[async_trait::async_trait]
-impl Tool for HttpRequestTool {
+impl Tool for FileEditTool {
     fn name(&self) -> &str {
-        "http_request"
+        "file_edit"
     }
 
     fn description(&self) -> &str {
-        "Make HTTP requests to external APIs"
+        "Edit an existing text file using search/replace blocks or a unified diff, with optional dry-run preview"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
         serde_json::json!({
             "type": "object",
             "properties": {
-                "url": {
+                "path": {
                     "type": "string",
-                    "description": "URL to make the request to"
+                    "description": "Path to the file to edit"
                 },
-                "method": {
+                "edits": {
+                    "type": "array",
+                    "description": "Search/replace edits applied in order. Each old_string must match exactly once unless replace_all is set",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "old_string": {
+                                "type": "string",
+                                "description": "Exact text to find"
+                            },
+                            "new_string": {
+                                "type": "string",
+                                "description": "Replacement text"
+                            },
+                            "replace_all": {
+                                "type": "boolean",
+                                "description": "Replace every occurrence instead of requiring a unique match",
+                                "default": false
+                            }
+                        },
+                        "required": ["old_string", "new_string"]
+                    }
+                },
+                "diff": {
                     "type": "string",
-                    "description": "HTTP method (GET, POST, PUT, DELETE, PATCH)",
-                    "default": "GET"
+                    "description": "Unified diff of this one file to apply, with @@ -a,b +c,d @@ hunk headers (alternative to edits)"
                 },
-                "headers": {
-                    "type": "object",
-                    "description": "HTTP headers to include"
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Preview the change without writing the file",
+                    "default": false
                 },
-                "body": {
-                    "type": "string",
-                    "description": "Request body content"
+                "backup": {
+                    "type": "boolean",
+                    "description": "Write a .bak copy of the original file before editing",
+                    "default": true
                 }
             },
-            "required": ["url"]
+            "required": ["path"]
         })
     }
 
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::destructive().with_open_world(false)
+    }
+
     async fn execute(
         &self,
         parameters: Option<serde_json::Value>,
@@ -829,183 +895,648 @@ impl Tool for HttpRequestTool {
                 message: "Tool input must be a JSON object".to_string(),
             })?;
 
-        let url = input_obj
-            .get("url")
+        let file_path = input_obj
+            .get("path")
             .ok_or_else(|| ToolError::InvalidParameters {
-                message: "Missing required parameter: url".to_string(),
+                message: "Missing required parameter: path".to_string(),
             })?;
-        let url: String =
-            serde_json::from_value(url.clone()).map_err(|e| ToolError::InvalidParameters {
-                message: format!("Invalid parameter url: {}", e),
+        let file_path: String = serde_json::from_value(file_path.clone()).map_err(|e| {
+            ToolError::InvalidParameters {
+                message: format!("Invalid parameter path: {}", e),
+            }
+        })?;
+
+        let edits: Option<Vec<SearchReplaceEdit>> = input_obj
+            .get("edits")
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid parameter edits: {}", e),
+            })?;
+        let diff: Option<String> = input_obj
+            .get("diff")
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid parameter diff: {}", e),
             })?;
+        let dry_run = input_obj
+            .get("dry_run")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let backup = input_obj
+            .get("backup")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        if edits.is_some() == diff.is_some() {
+            return Err(ToolError::InvalidParameters {
+                message: "Exactly one of 'edits' or 'diff' must be provided".to_string(),
+            });
+        }
 
-        let method = input_obj
-            .get("method")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_else(|| "GET".to_string());
+        let original = match tokio::fs::read_to_string(&file_path).await {
+            Ok(content) => content,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Failed to read file {}: {}",
+                    file_path, e
+                )))
+            }
+        };
 
-        let headers = input_obj
-            .get("headers")
-            .and_then(|v| v.as_object())
-            .cloned()
-            .unwrap_or_default();
+        let (updated, replacements) = if let Some(edits) = edits {
+            match apply_search_replace(&original, &edits) {
+                Ok(result) => result,
+                Err(e) => return Ok(ToolResult::error(format!("{} in {}", e, file_path))),
+            }
+        } else {
+            match apply_unified_diff(&original, diff.as_deref().unwrap_or_default()) {
+                Ok(result) => result,
+                Err(e) => return Ok(ToolResult::error(format!("{} in {}", e, file_path))),
+            }
+        };
 
-        let body: Option<String> = input_obj
-            .get("body")
-            .and_then(|v| serde_json::from_value(v.clone()).ok());
+        let preview = render_line_diff(&original, &updated);
 
-        let client = reqwest::Client::new();
-        let mut request = match method.to_uppercase().as_str() {
-            "GET" => client.get(&url),
-            "POST" => client.post(&url),
-            "PUT" => client.put(&url),
-            "DELETE" => client.delete(&url),
-            "PATCH" => client.patch(&url),
-            _ => {
+        if dry_run {
+            return Ok(ToolResult::success(serde_json::json!({
+                "path": file_path,
+                "dry_run": true,
+                "changed": original != updated,
+                "replacements": replacements,
+                "preview": preview
+            })));
+        }
+
+        let backup_path = if backup {
+            let backup_path = format!("{}.bak", file_path);
+            if let Err(e) = tokio::fs::write(&backup_path, &original).await {
                 return Ok(ToolResult::error(format!(
-                    "Unsupported HTTP method: {}",
-                    method
-                )))
+                    "Failed to write backup {}: {}",
+                    backup_path, e
+                )));
             }
+            Some(backup_path)
+        } else {
+            None
         };
 
-        // Add headers
-        for (key, value) in headers {
-            if let Some(value_str) = value.as_str() {
-                request = request.header(&key, value_str);
-            }
+        match tokio::fs::write(&file_path, &updated).await {
+            Ok(_) => Ok(ToolResult::success(serde_json::json!({
+                "path": file_path,
+                "dry_run": false,
+                "changed": original != updated,
+                "replacements": replacements,
+                "backup_path": backup_path,
+                "bytes_written": updated.len(),
+                "preview": preview
+            }))),
+            Err(e) => Ok(ToolResult::error(format!(
+                "Failed to write file {}: {}",
+                file_path, e
+            ))),
         }
+    }
+}
 
-        // Add body if provided
-        if let Some(body_str) = body {
-            request = request.body(body_str);
+/// Apply search/replace edits in order, returning the new content and the
+/// total number of replacements made
+fn apply_search_replace(
+    content: &str,
+    edits: &[SearchReplaceEdit],
+) -> Result<(String, usize), String> {
+    let mut current = content.to_string();
+    let mut replacements = 0;
+
+    for (index, edit) in edits.iter().enumerate() {
+        if edit.old_string.is_empty() {
+            return Err(format!("Edit {} has an empty old_string", index));
+        }
+        let occurrences = current.matches(&edit.old_string).count();
+        match occurrences {
+            0 => return Err(format!("Edit {}: old_string not found", index)),
+            1 => {
+                current = current.replacen(&edit.old_string, &edit.new_string, 1);
+                replacements += 1;
+            }
+            n if edit.replace_all => {
+                current = current.replace(&edit.old_string, &edit.new_string);
+                replacements += n;
+            }
+            n => {
+                return Err(format!(
+                    "Edit {}: old_string matches {} times; add more context or set replace_all",
+                    index, n
+                ))
+            }
         }
+    }
 
-        match request.send().await {
-            Ok(response) => {
-                let status = response.status().as_u16();
-                let headers: HashMap<String, String> = response
-                    .headers()
-                    .iter()
-                    .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
-                    .collect();
+    Ok((current, replacements))
+}
 
-                match response.text().await {
-                    Ok(body) => {
-                        let result = serde_json::json!({
-                            "status": status,
-                            "headers": headers,
-                            "body": body
-                        });
-                        Ok(ToolResult::success(result))
-                    }
-                    Err(e) => Ok(ToolResult::error(format!(
-                        "Failed to read response body: {}",
-                        e
-                    ))),
+/// Apply a unified diff, returning the new content and the number of hunks applied
+///
+/// Hunks are located by their context and removed lines rather than trusting the
+/// header line numbers, so diffs against a slightly shifted file still apply.
+fn apply_unified_diff(content: &str, diff: &str) -> Result<(String, usize), String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let trailing_newline = content.ends_with('\n');
+    // Write the lines back with the file's own line endings
+    let line_ending = match content.find('\n') {
+        Some(end) if content[..end].ends_with('\r') => "\r\n",
+        _ => "\n",
+    };
+
+    let mut hunks: Vec<(Vec<String>, Vec<String>, usize)> = Vec::new();
+    // Lines of the current hunk still to come from the old and the new file;
+    // "---" and "+++" lines are file headers only outside hunks
+    let (mut old_left, mut new_left) = (0usize, 0usize);
+    let mut file_headers = 0;
+    for line in diff.lines() {
+        if old_left == 0 && new_left == 0 {
+            if line.starts_with("@@") {
+                let (start, old_count, new_count) = parse_hunk_header(line)
+                    .ok_or_else(|| format!("Malformed hunk header: {}", line))?;
+                // A hunk without old lines inserts after its start line
+                let hint = if old_count == 0 {
+                    start
+                } else {
+                    start.saturating_sub(1)
+                };
+                hunks.push((Vec::new(), Vec::new(), hint));
+                (old_left, new_left) = (old_count, new_count);
+            } else if line.starts_with("--- ") {
+                file_headers += 1;
+                if file_headers > 1 {
+                    return Err(
+                        "Diff changes more than one file; pass the diff of one file".to_string()
+                    );
                 }
+            } else if !hunks.is_empty()
+                && !line.starts_with("+++ ")
+                && line.starts_with([' ', '+', '-'])
+            {
+                return Err(format!(
+                    "Hunk {} has more lines than its header states",
+                    hunks.len() - 1
+                ));
             }
-            Err(e) => Ok(ToolResult::error(format!("HTTP request failed: {}", e))),
+            continue;
+        }
+
+        let (old, new, _) = hunks.last_mut().expect("hunk started");
+        let (is_old, is_new, text) = if let Some(rest) = line.strip_prefix('+') {
+            (false, true, rest)
+        } else if let Some(rest) = line.strip_prefix('-') {
+            (true, false, rest)
+        } else if let Some(rest) = line.strip_prefix(' ') {
+            (true, true, rest)
+        } else if line.is_empty() {
+            (true, true, "")
+        } else if line.starts_with('\\') {
+            // "\ No newline at end of file"
+            continue;
+        } else {
+            return Err(format!("Malformed diff line: {}", line));
+        };
+        if (is_old && old_left == 0) || (is_new && new_left == 0) {
+            return Err(format!(
+                "Hunk {} has more lines than its header states",
+                hunks.len() - 1
+            ));
         }
+        if is_old {
+            old.push(text.to_string());
+            old_left -= 1;
+        }
+        if is_new {
+            new.push(text.to_string());
+            new_left -= 1;
+        }
+    }
+
+    if hunks.is_empty() {
+        return Err("Diff contains no hunks".to_string());
+    }
+    if old_left > 0 || new_left > 0 {
+        return Err(format!(
+            "Hunk {} has fewer lines than its header states",
+            hunks.len() - 1
+        ));
+    }
+
+    // Offset between original line numbers and the file as hunks are applied
+    let mut offset: isize = 0;
+    for (index, (old, new, hint)) in hunks.iter().enumerate() {
+        let expected = (*hint as isize + offset).max(0) as usize;
+        let position = find_hunk(&lines, old, expected)
+            .ok_or_else(|| format!("Hunk {} does not match file content", index))?;
+        lines.splice(position..position + old.len(), new.iter().cloned());
+        offset += new.len() as isize - old.len() as isize;
     }
+
+    let mut result = lines.join(line_ending);
+    if trailing_newline && !result.is_empty() {
+        result.push_str(line_ending);
+    }
+    Ok((result, hunks.len()))
 }
 
-/// Current time tool for getting the current date and time
+/// Parse a `@@ -a,b +c,d @@` hunk header into the old start line and the old
+/// and new line counts; omitted counts are 1
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize)> {
+    let mut ranges = line.strip_prefix("@@ ")?.split_whitespace();
+    let parse = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (start, old_count) = parse(ranges.next()?.strip_prefix('-')?)?;
+    let (_, new_count) = parse(ranges.next()?.strip_prefix('+')?)?;
+    Some((start, old_count, new_count))
+}
+
+/// Find the hunk position closest to the expected line
+fn find_hunk(lines: &[String], old: &[String], expected: usize) -> Option<usize> {
+    if old.is_empty() {
+        return Some(expected.min(lines.len()));
+    }
+    if old.len() > lines.len() {
+        return None;
+    }
+    (0..=lines.len() - old.len())
+        .filter(|&start| lines[start..start + old.len()] == *old)
+        .min_by_key(|&start| start.abs_diff(expected))
+}
+
+/// Render a compact single-hunk diff between two versions of a file
+fn render_line_diff(before: &str, after: &str) -> String {
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+
+    let prefix = before
+        .iter()
+        .zip(after.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let removed = &before[prefix..before.len() - suffix];
+    let added = &after[prefix..after.len() - suffix];
+    if removed.is_empty() && added.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!(
+        "@@ -{},{} +{},{} @@\n",
+        prefix + 1,
+        removed.len(),
+        prefix + 1,
+        added.len()
+    );
+    for line in removed {
+        out.push_str(&format!("-{}\n", line));
+    }
+    for line in added {
+        out.push_str(&format!("+{}\n", line));
+    }
+    out
+}
+
+/// Default cap on matches returned by [`GrepTool`]
+const GREP_DEFAULT_MAX_RESULTS: usize = 100;
+/// Default cap on paths returned by [`GlobTool`]
+const GLOB_DEFAULT_MAX_RESULTS: usize = 200;
+/// Hard upper bound on results for search tools regardless of request
+const SEARCH_MAX_RESULTS_LIMIT: usize = 1000;
+/// Lines longer than this are truncated in grep output
+const GREP_MAX_LINE_LENGTH: usize = 500;
+
+/// Regex search tool for finding content across a directory tree
+///
+/// Walks the tree honoring `.gitignore`, `.ignore` and hidden-file rules, skips
+/// binary files, and caps the number of matches so results stay small enough to
+/// hand back to the model.
 #[derive(Debug)]
-pub struct CurrentTimeTool;
+pub struct GrepTool;
 
-impl CurrentTimeTool {
+impl GrepTool {
     pub fn new() -> Self {
         Self
     }
 }
 
-impl Default for CurrentTimeTool {
+impl Default for GrepTool {
     fn default() -> Self {
         Self::new()
     }
 }
 
 #[async_trait::async_trait]
-impl Tool for CurrentTimeTool {
+impl Tool for GrepTool {
     fn name(&self) -> &str {
-        "current_time"
+        "grep"
     }
 
     fn description(&self) -> &str {
-        "Get the current date and time in UTC"
+        "Search file contents with a regular expression, returning matching lines with optional context"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
         serde_json::json!({
             "type": "object",
-            "properties": {},
-            "required": []
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "description": "Regular expression to search for"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File or directory to search (defaults to the current directory)"
+                },
+                "glob": {
+                    "type": "string",
+                    "description": "Only search files whose path matches this glob, e.g. \"*.rs\""
+                },
+                "case_insensitive": {
+                    "type": "boolean",
+                    "description": "Match without regard to case",
+                    "default": false
+                },
+                "context_lines": {
+                    "type": "integer",
+                    "description": "Number of lines of context to include before and after each match",
+                    "default": 0,
+                    "minimum": 0,
+                    "maximum": 10
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum number of matches to return",
+                    "default": GREP_DEFAULT_MAX_RESULTS,
+                    "minimum": 1,
+                    "maximum": SEARCH_MAX_RESULTS_LIMIT
+                }
+            },
+            "required": ["pattern"]
         })
     }
 
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only().with_open_world(false)
+    }
+
     async fn execute(
         &self,
-        _parameters: Option<serde_json::Value>,
+        parameters: Option<serde_json::Value>,
         _agent_context: Option<&crate::agent::AgentContext>,
     ) -> Result<ToolResult, ToolError> {
-        use chrono::Utc;
-        let now = Utc::now();
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let input_obj = params
+            .as_object()
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Tool input must be a JSON object".to_string(),
+            })?;
 
-        let result = serde_json::json!({
-            "current_time": now.to_rfc3339(),
-            "timezone": "UTC",
-            "timestamp": now.timestamp(),
-            "formatted": now.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
-            "message": format!("The current time is {}", now.format("%Y-%m-%d %H:%M:%S UTC"))
-        });
-        Ok(ToolResult::success(result))
+        let pattern = input_obj
+            .get("pattern")
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Missing required parameter: pattern".to_string(),
+            })?;
+        let pattern: String =
+            serde_json::from_value(pattern.clone()).map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid parameter pattern: {}", e),
+            })?;
+        let search_path = input_obj
+            .get("path")
+            .and_then(|v| v.as_str())
+            .unwrap_or(".")
+            .to_string();
+        let case_insensitive = input_obj
+            .get("case_insensitive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let context_lines = input_obj
+            .get("context_lines")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+            .min(10) as usize;
+        let max_results = input_obj
+            .get("max_results")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(GREP_DEFAULT_MAX_RESULTS)
+            .clamp(1, SEARCH_MAX_RESULTS_LIMIT);
+
+        let regex = regex::RegexBuilder::new(&pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid parameter pattern: {}", e),
+            })?;
+        let file_filter = match input_obj.get("glob").and_then(|v| v.as_str()) {
+            Some(glob) => Some(build_glob_matcher(glob)?),
+            None => None,
+        };
+
+        if !std::path::Path::new(&search_path).exists() {
+            return Ok(ToolResult::error(format!(
+                "Failed to search {}: path does not exist",
+                search_path
+            )));
+        }
+
+        let root = search_path.clone();
+        let search = tokio::task::spawn_blocking(move || {
+            grep_tree(
+                &root,
+                &regex,
+                file_filter.as_ref(),
+                context_lines,
+                max_results,
+            )
+        })
+        .await
+        .map_err(|e| ToolError::with_source(format!("Search task failed: {}", e), e))?;
+
+        Ok(ToolResult::success(serde_json::json!({
+            "pattern": pattern,
+            "path": search_path,
+            "matches": search.matches,
+            "count": search.matches.len(),
+            "files_searched": search.files_searched,
+            "truncated": search.truncated
+        })))
+    }
+}
+
+/// Outcome of walking a tree for [`GrepTool`]
+struct GrepSearch {
+    matches: Vec<serde_json::Value>,
+    files_searched: usize,
+    truncated: bool,
+}
+
+fn grep_tree(
+    root: &str,
+    regex: &regex::Regex,
+    file_filter: Option<&globset::GlobMatcher>,
+    context_lines: usize,
+    max_results: usize,
+) -> GrepSearch {
+    let root_path = std::path::Path::new(root);
+    let mut search = GrepSearch {
+        matches: Vec::new(),
+        files_searched: 0,
+        truncated: false,
+    };
+
+    for entry in ignore::WalkBuilder::new(root).build().flatten() {
+        if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+        let path = entry.path();
+        let relative = path.strip_prefix(root_path).unwrap_or(path);
+        if let Some(filter) = file_filter {
+            if !filter.is_match(relative) && !path.file_name().is_some_and(|n| filter.is_match(n)) {
+                continue;
+            }
+        }
+
+        let Ok(bytes) = std::fs::read(path) else {
+            continue;
+        };
+        // Skip binary files the same way grep does: a NUL byte near the start
+        if bytes.iter().take(8192).any(|b| *b == 0) {
+            continue;
+        }
+        search.files_searched += 1;
+
+        let text = String::from_utf8_lossy(&bytes);
+        let lines: Vec<&str> = text.lines().collect();
+        for (index, line) in lines.iter().enumerate() {
+            if !regex.is_match(line) {
+                continue;
+            }
+            if search.matches.len() >= max_results {
+                search.truncated = true;
+                return search;
+            }
+            let before_start = index.saturating_sub(context_lines);
+            let after_end = (index + 1 + context_lines).min(lines.len());
+            let mut entry = serde_json::json!({
+                "path": path.to_string_lossy(),
+                "line_number": index + 1,
+                "line": truncate_line(line)
+            });
+            if context_lines > 0 {
+                entry["before"] = lines[before_start..index]
+                    .iter()
+                    .map(|l| truncate_line(l))
+                    .collect();
+                entry["after"] = lines[index + 1..after_end]
+                    .iter()
+                    .map(|l| truncate_line(l))
+                    .collect();
+            }
+            search.matches.push(entry);
+        }
+    }
+
+    search
+}
+
+fn truncate_line(line: &str) -> serde_json::Value {
+    if line.len() <= GREP_MAX_LINE_LENGTH {
+        return serde_json::Value::String(line.to_string());
     }
+    let mut end = GREP_MAX_LINE_LENGTH;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    serde_json::Value::String(format!("{}...", &line[..end]))
+}
+
+fn build_glob_matcher(pattern: &str) -> Result<globset::GlobMatcher, ToolError> {
+    globset::GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| ToolError::InvalidParameters {
+            message: format!("Invalid glob pattern: {}", e),
+        })
 }
 
-/// Environment variable tool for accessing system environment
+/// Glob tool for discovering files by path pattern
+///
+/// Honors `.gitignore`, `.ignore` and hidden-file rules so build output and
+/// vendored directories don't flood results.
 #[derive(Debug)]
-pub struct EnvVarTool;
+pub struct GlobTool;
 
-impl EnvVarTool {
+impl GlobTool {
     pub fn new() -> Self {
         Self
     }
 }
 
-impl Default for EnvVarTool {
+impl Default for GlobTool {
     fn default() -> Self {
         Self::new()
     }
 }
 
 #[async_trait::async_trait]
-impl Tool for EnvVarTool {
+impl Tool for GlobTool {
     fn name(&self) -> &str {
-        "env_var"
+        "glob"
     }
 
     fn description(&self) -> &str {
-        "Get environment variable values"
+        "Find files whose paths match a glob pattern such as \"src/**/*.rs\""
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
         serde_json::json!({
             "type": "object",
             "properties": {
-                "name": {
+                "pattern": {
                     "type": "string",
-                    "description": "Name of the environment variable"
+                    "description": "Glob pattern relative to the search root, e.g. \"**/*.toml\""
                 },
-                "default": {
+                "path": {
                     "type": "string",
-                    "description": "Default value if environment variable is not set"
+                    "description": "Directory to search from (defaults to the current directory)"
+                },
+                "include_hidden": {
+                    "type": "boolean",
+                    "description": "Include hidden files and directories",
+                    "default": false
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum number of paths to return",
+                    "default": GLOB_DEFAULT_MAX_RESULTS,
+                    "minimum": 1,
+                    "maximum": SEARCH_MAX_RESULTS_LIMIT
                 }
             },
-            "required": ["name"]
+            "required": ["pattern"]
         })
     }
 
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only().with_open_world(false)
+    }
+
     async fn execute(
         &self,
         parameters: Option<serde_json::Value>,
@@ -1018,22 +1549,783 @@ impl Tool for EnvVarTool {
                 message: "Tool input must be a JSON object".to_string(),
             })?;
 
-        let var_name = input_obj
-            .get("name")
+        let pattern = input_obj
+            .get("pattern")
             .ok_or_else(|| ToolError::InvalidParameters {
-                message: "Missing required parameter: name".to_string(),
+                message: "Missing required parameter: pattern".to_string(),
             })?;
-        let var_name: String =
-            serde_json::from_value(var_name.clone()).map_err(|e| ToolError::InvalidParameters {
-                message: format!("Invalid parameter name: {}", e),
+        let pattern: String =
+            serde_json::from_value(pattern.clone()).map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid parameter pattern: {}", e),
             })?;
+        let root = input_obj
+            .get("path")
+            .and_then(|v| v.as_str())
+            .unwrap_or(".")
+            .to_string();
+        let include_hidden = input_obj
+            .get("include_hidden")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let max_results = input_obj
+            .get("max_results")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(GLOB_DEFAULT_MAX_RESULTS)
+            .clamp(1, SEARCH_MAX_RESULTS_LIMIT);
+
+        let matcher = build_glob_matcher(&pattern)?;
+
+        if !std::path::Path::new(&root).is_dir() {
+            return Ok(ToolResult::error(format!(
+                "Failed to search {}: not a directory",
+                root
+            )));
+        }
 
-        let default_value: Option<String> = input_obj
-            .get("default")
-            .and_then(|v| serde_json::from_value(v.clone()).ok());
-
-        match std::env::var(&var_name) {
-            Ok(value) => {
+        let walk_root = root.clone();
+        let (files, truncated) = tokio::task::spawn_blocking(move || {
+            let root_path = std::path::Path::new(&walk_root);
+            let mut files = Vec::new();
+            let mut truncated = false;
+            for entry in ignore::WalkBuilder::new(&walk_root)
+                .hidden(!include_hidden)
+                .build()
+                .flatten()
+            {
+                if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                    continue;
+                }
+                let relative = entry.path().strip_prefix(root_path).unwrap_or(entry.path());
+                if matcher.is_match(relative) {
+                    if files.len() >= max_results {
+                        truncated = true;
+                        break;
+                    }
+                    files.push(relative.to_string_lossy().to_string());
+                }
+            }
+            files.sort();
+            (files, truncated)
+        })
+        .await
+        .map_err(|e| ToolError::with_source(format!("Search task failed: {}", e), e))?;
+
+        Ok(ToolResult::success(serde_json::json!({
+            "pattern": pattern,
+            "path": root,
+            "files": files,
+            "count": files.len(),
+            "truncated": truncated
+        })))
+    }
+}
+
+/// HTTP request tool for making HTTP calls
+#[derive(Debug)]
+pub struct HttpRequestTool;
+
+impl HttpRequestTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for HttpRequestTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for HttpRequestTool {
+    fn name(&self) -> &str {
+        "http_request"
+    }
+
+    fn description(&self) -> &str {
+        "Make HTTP requests to external APIs"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "URL to make the request to"
+                },
+                "method": {
+                    "type": "string",
+                    "description": "HTTP method (GET, POST, PUT, DELETE, PATCH)",
+                    "default": "GET"
+                },
+                "headers": {
+                    "type": "object",
+                    "description": "HTTP headers to include"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Request body content"
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        _agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let input_obj = params
+            .as_object()
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Tool input must be a JSON object".to_string(),
+            })?;
+
+        let url = input_obj
+            .get("url")
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Missing required parameter: url".to_string(),
+            })?;
+        let url: String =
+            serde_json::from_value(url.clone()).map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid parameter url: {}", e),
+            })?;
+
+        let method = input_obj
+            .get("method")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_else(|| "GET".to_string());
+
+        let headers = input_obj
+            .get("headers")
+            .and_then(|v| v.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        let body: Option<String> = input_obj
+            .get("body")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+        let policy = super::network::network_policy();
+        let client = match policy.client_for(&url).await {
+            Ok(client) => client,
+            Err(super::network::NetworkPolicyError::Violation(violation)) => {
+                return Ok(ToolResult::error(violation.to_string()))
+            }
+            Err(e) => return Ok(ToolResult::error(format!("HTTP client error: {}", e))),
+        };
+        let mut request = match method.to_uppercase().as_str() {
+            "GET" => client.get(&url),
+            "POST" => client.post(&url),
+            "PUT" => client.put(&url),
+            "DELETE" => client.delete(&url),
+            "PATCH" => client.patch(&url),
+            _ => {
+                return Ok(ToolResult::error(format!(
+                    "Unsupported HTTP method: {}",
+                    method
+                )))
+            }
+        };
+
+        // Add headers
+        for (key, value) in headers {
+            if let Some(value_str) = value.as_str() {
+                request = request.header(&key, value_str);
+            }
+        }
+
+        // Add body if provided
+        if let Some(body_str) = body {
+            request = request.body(body_str);
+        }
+
+        match request.send().await {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let headers: HashMap<String, String> = response
+                    .headers()
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+                    .collect();
+
+                match policy.read_body(response).await {
+                    Ok(body) => {
+                        let result = serde_json::json!({
+                            "status": status,
+                            "headers": headers,
+                            "body": String::from_utf8_lossy(&body)
+                        });
+                        Ok(ToolResult::success(result))
+                    }
+                    Err(super::network::NetworkPolicyError::Violation(violation)) => {
+                        Ok(ToolResult::error(violation.to_string()))
+                    }
+                    Err(e) => Ok(ToolResult::error(format!(
+                        "Failed to read response body: {}",
+                        e
+                    ))),
+                }
+            }
+            Err(e) => Ok(ToolResult::error(format!("HTTP request failed: {}", e))),
+        }
+    }
+}
+
+/// Default cap on characters returned by [`WebPageTool`]
+const WEB_PAGE_DEFAULT_MAX_CHARS: usize = 20_000;
+/// Largest response body [`WebPageTool`] will download
+const WEB_PAGE_MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+/// Maximum redirects followed by [`WebPageTool`]
+const WEB_PAGE_MAX_REDIRECTS: usize = 5;
+
+/// Web page tool that fetches a URL and returns readable content
+///
+/// Unlike [`HttpRequestTool`], which returns the raw body, this tool strips
+/// scripts, styles and markup and returns markdown or plain text, optionally
+/// limited to the elements matching a CSS selector. Output is capped so a
+/// single page can't flood the context window.
+#[derive(Debug)]
+pub struct WebPageTool;
+
+impl WebPageTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for WebPageTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for WebPageTool {
+    fn name(&self) -> &str {
+        "web_page"
+    }
+
+    fn description(&self) -> &str {
+        "Fetch a web page and return its readable content as markdown or plain text"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "URL of the page to fetch"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["markdown", "text"],
+                    "description": "Output format",
+                    "default": "markdown"
+                },
+                "selector": {
+                    "type": "string",
+                    "description": "CSS selector limiting extraction to matching elements, e.g. \"article\" or \"#content\""
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Maximum characters of content to return",
+                    "default": WEB_PAGE_DEFAULT_MAX_CHARS
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only()
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        _agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let input_obj = params
+            .as_object()
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Tool input must be a JSON object".to_string(),
+            })?;
+
+        let url = input_obj
+            .get("url")
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Missing required parameter: url".to_string(),
+            })?;
+        let url: String =
+            serde_json::from_value(url.clone()).map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid parameter url: {}", e),
+            })?;
+        let markdown = match input_obj.get("format").and_then(|v| v.as_str()) {
+            None | Some("markdown") => true,
+            Some("text") => false,
+            Some(other) => {
+                return Err(ToolError::InvalidParameters {
+                    message: format!("Invalid parameter format: {}", other),
+                })
+            }
+        };
+        let selector = match input_obj.get("selector").and_then(|v| v.as_str()) {
+            Some(selector) => Some(scraper::Selector::parse(selector).map_err(|e| {
+                ToolError::InvalidParameters {
+                    message: format!("Invalid parameter selector: {}", e),
+                }
+            })?),
+            None => None,
+        };
+        let max_chars = input_obj
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(WEB_PAGE_DEFAULT_MAX_CHARS);
+
+        let policy = super::network::network_policy();
+        let redirect_limited = super::network::NetworkPolicy::clone(&policy)
+            .with_max_redirects(WEB_PAGE_MAX_REDIRECTS.min(policy.max_redirects()));
+        let client = match redirect_limited.client_for(&url).await {
+            Ok(client) => client,
+            Err(super::network::NetworkPolicyError::Violation(violation)) => {
+                return Ok(ToolResult::error(violation.to_string()))
+            }
+            Err(e) => return Ok(ToolResult::error(format!("HTTP client error: {}", e))),
+        };
+        let max_body_bytes = policy
+            .max_response_bytes()
+            .map_or(WEB_PAGE_MAX_BODY_BYTES, |max| {
+                max.min(WEB_PAGE_MAX_BODY_BYTES)
+            });
+
+        let mut response = match client.get(&url).send().await {
+            Ok(response) => response,
+            Err(e) => return Ok(ToolResult::error(format!("HTTP request failed: {}", e))),
+        };
+        let status = response.status().as_u16();
+        let final_url = response.url().clone();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+
+        // Read the body in chunks so oversized pages are cut off early
+        let mut body = Vec::new();
+        let mut body_truncated = false;
+        loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    let remaining = max_body_bytes - body.len();
+                    if chunk.len() > remaining {
+                        body.extend_from_slice(&chunk[..remaining]);
+                        body_truncated = true;
+                        break;
+                    }
+                    body.extend_from_slice(&chunk);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    return Ok(ToolResult::error(format!(
+                        "Failed to read response body: {}",
+                        e
+                    )))
+                }
+            }
+        }
+        let body = String::from_utf8_lossy(&body);
+
+        let is_html = content_type.is_empty() || content_type.contains("html");
+        let (title, content) = if is_html {
+            extract_page_content(&body, selector.as_ref(), Some(&final_url), markdown)
+        } else {
+            (None, body.trim().to_string())
+        };
+
+        let total_chars = content.chars().count();
+        let content: String = content.chars().take(max_chars).collect();
+
+        Ok(ToolResult::success(serde_json::json!({
+            "url": url,
+            "final_url": final_url.as_str(),
+            "redirected": url::Url::parse(&url).is_ok_and(|requested| requested != final_url),
+            "status": status,
+            "content_type": content_type,
+            "title": title,
+            "content": content,
+            "total_chars": total_chars,
+            "truncated": body_truncated || total_chars > max_chars
+        })))
+    }
+}
+
+/// Nesting depth below which HTML elements are rendered as plain text, so
+/// that deeply nested pages cannot overflow the stack
+const MAX_RENDER_DEPTH: usize = 256;
+
+/// HTML elements whose content is never readable text
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "head",
+];
+
+/// Extract the title and readable content from an HTML document
+fn extract_page_content(
+    html: &str,
+    selector: Option<&scraper::Selector>,
+    base_url: Option<&url::Url>,
+    markdown: bool,
+) -> (Option<String>, String) {
+    let document = scraper::Html::parse_document(html);
+    let title = scraper::Selector::parse("title")
+        .ok()
+        .and_then(|s| document.select(&s).next())
+        .map(|t| collapse_whitespace(&t.text().collect::<String>()))
+        .filter(|t| !t.is_empty());
+
+    let mut out = String::new();
+    match selector {
+        Some(selector) => {
+            for element in document.select(selector) {
+                render_node(*element, base_url, markdown, 0, &mut out);
+                out.push_str("\n\n");
+            }
+        }
+        None => render_node(*document.root_element(), base_url, markdown, 0, &mut out),
+    }
+
+    (title, tidy_blank_lines(&out))
+}
+
+fn render_node(
+    node: ego_tree::NodeRef<'_, scraper::Node>,
+    base_url: Option<&url::Url>,
+    markdown: bool,
+    depth: usize,
+    out: &mut String,
+) {
+    if depth > MAX_RENDER_DEPTH {
+        let text: Vec<&str> = node
+            .descendants()
+            .filter_map(|descendant| match descendant.value() {
+                scraper::Node::Text(text) => Some(&**text),
+                _ => None,
+            })
+            .collect();
+        let collapsed = collapse_whitespace(&text.join(" "));
+        if !collapsed.is_empty() {
+            out.push(' ');
+            out.push_str(&collapsed);
+        }
+        return;
+    }
+    let element = match node.value() {
+        scraper::Node::Text(text) => {
+            let collapsed = collapse_whitespace(text);
+            if !collapsed.is_empty() {
+                if text.starts_with(char::is_whitespace) && !out.ends_with(['\n', ' ']) {
+                    out.push(' ');
+                }
+                out.push_str(&collapsed);
+                if text.ends_with(char::is_whitespace) {
+                    out.push(' ');
+                }
+            }
+            return;
+        }
+        scraper::Node::Element(element) => element,
+        _ => {
+            for child in node.children() {
+                render_node(child, base_url, markdown, depth + 1, out);
+            }
+            return;
+        }
+    };
+
+    let name = element.name();
+    if SKIPPED_ELEMENTS.contains(&name) {
+        return;
+    }
+    let render_children = |out: &mut String| {
+        for child in node.children() {
+            render_node(child, base_url, markdown, depth + 1, out);
+        }
+    };
+
+    match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            out.push_str("\n\n");
+            if markdown {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                out.push_str(&"#".repeat(level));
+                out.push(' ');
+            }
+            render_children(out);
+            out.push_str("\n\n");
+        }
+        "br" => out.push('\n'),
+        "hr" => out.push_str(if markdown { "\n\n---\n\n" } else { "\n\n" }),
+        "li" => {
+            out.push_str("\n- ");
+            render_children(out);
+        }
+        "pre" => {
+            let code: String = scraper::ElementRef::wrap(node)
+                .map(|e| e.text().collect())
+                .unwrap_or_default();
+            if markdown {
+                out.push_str(&format!("\n\n```\n{}\n```\n\n", code.trim_end()));
+            } else {
+                out.push_str(&format!("\n\n{}\n\n", code.trim_end()));
+            }
+        }
+        "code" if markdown => {
+            out.push('`');
+            render_children(out);
+            out.push('`');
+        }
+        "strong" | "b" if markdown => {
+            out.push_str("**");
+            render_children(out);
+            out.push_str("**");
+        }
+        "em" | "i" if markdown => {
+            out.push('*');
+            render_children(out);
+            out.push('*');
+        }
+        "a" if markdown => {
+            let href = element
+                .attr("href")
+                .filter(|h| !h.starts_with("javascript:") && !h.starts_with('#'))
+                .map(|h| match base_url {
+                    Some(base) => base.join(h).map(|u| u.to_string()).unwrap_or(h.to_string()),
+                    None => h.to_string(),
+                });
+            let mut text = String::new();
+            render_children(&mut text);
+            let text = text.trim();
+            match href {
+                Some(href) if !text.is_empty() => out.push_str(&format!("[{}]({})", text, href)),
+                _ => out.push_str(text),
+            }
+        }
+        "img" => {
+            if let Some(alt) = element.attr("alt").filter(|a| !a.trim().is_empty()) {
+                if markdown {
+                    out.push_str(&format!("[image: {}]", alt.trim()));
+                } else {
+                    out.push_str(alt.trim());
+                }
+            }
+        }
+        "tr" => {
+            out.push('\n');
+            if markdown {
+                out.push('|');
+            }
+            render_children(out);
+        }
+        "td" | "th" => {
+            out.push(' ');
+            render_children(out);
+            out.push_str(if markdown { " |" } else { "\t" });
+        }
+        "p" | "div" | "section" | "article" | "main" | "header" | "footer" | "nav" | "aside"
+        | "ul" | "ol" | "table" | "blockquote" | "figure" | "form" | "dl" | "dt" | "dd" => {
+            out.push_str("\n\n");
+            render_children(out);
+            out.push_str("\n\n");
+        }
+        _ => render_children(out),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Trim trailing spaces and collapse runs of blank lines into a single one
+fn tidy_blank_lines(text: &str) -> String {
+    let mut result = String::new();
+    let mut blank_run = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        let line = line.strip_prefix(' ').unwrap_or(line);
+        if line.is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if !result.is_empty() {
+            result.push_str(if blank_run > 0 { "\n\n" } else { "\n" });
+        }
+        blank_run = 0;
+        result.push_str(line);
+    }
+    result
+}
+
+/// Current time tool for getting the current date and time
+#[derive(Debug)]
+pub struct CurrentTimeTool;
+
+impl CurrentTimeTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CurrentTimeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for CurrentTimeTool {
+    fn name(&self) -> &str {
+        "current_time"
+    }
+
+    fn description(&self) -> &str {
+        "Get the current date and time in UTC"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {},
+            "required": []
+        })
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only().with_open_world(false)
+    }
+
+    async fn execute(
+        &self,
+        _parameters: Option<serde_json::Value>,
+        _agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        use chrono::Utc;
+        let now = Utc::now();
+
+        let result = serde_json::json!({
+            "current_time": now.to_rfc3339(),
+            "timezone": "UTC",
+            "timestamp": now.timestamp(),
+            "formatted": now.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            "message": format!("The current time is {}", now.format("%Y-%m-%d %H:%M:%S UTC"))
+        });
+        Ok(ToolResult::success(result))
+    }
+}
+
+/// Environment variable tool for reading configuration values.
+///
+/// Values are resolved through the agent's
+/// [`SecretsProvider`](crate::secrets::SecretsProvider), so only names allowed by
+/// the configured scope can be read. Without a provider, only the variables in
+/// [`DEFAULT_ENV_ALLOWLIST`](crate::secrets::DEFAULT_ENV_ALLOWLIST) are visible.
+/// Names outside the scope are reported as not found.
+#[derive(Debug)]
+pub struct EnvVarTool;
+
+impl EnvVarTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for EnvVarTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for EnvVarTool {
+    fn name(&self) -> &str {
+        "env_var"
+    }
+
+    fn description(&self) -> &str {
+        "Get the value of an allowed environment variable or configuration secret"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Name of the environment variable"
+                },
+                "default": {
+                    "type": "string",
+                    "description": "Default value if environment variable is not set"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only().with_open_world(false)
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let input_obj = params
+            .as_object()
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Tool input must be a JSON object".to_string(),
+            })?;
+
+        let var_name = input_obj
+            .get("name")
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Missing required parameter: name".to_string(),
+            })?;
+        let var_name: String =
+            serde_json::from_value(var_name.clone()).map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid parameter name: {}", e),
+            })?;
+
+        let default_value: Option<String> = input_obj
+            .get("default")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+        let secrets: std::sync::Arc<dyn SecretsProvider> =
+            match agent_context.and_then(|ctx| ctx.secrets.clone()) {
+                Some(secrets) => secrets,
+                None => std::sync::Arc::new(ScopedSecrets::default_env()),
+            };
+        let value = match secrets.get_secret(&var_name).await {
+            Ok(value) => value,
+            Err(e) => {
+                return Err(ToolError::with_source(
+                    format!("Failed to resolve '{}': {}", var_name, e),
+                    e,
+                ))
+            }
+        };
+
+        match value {
+            Some(value) => {
                 let result = serde_json::json!({
                     "name": var_name,
                     "value": value,
@@ -1041,7 +2333,7 @@ impl Tool for EnvVarTool {
                 });
                 Ok(ToolResult::success(result))
             }
-            Err(_) => {
+            None => {
                 if let Some(default) = default_value {
                     let result = serde_json::json!({
                         "name": var_name,
@@ -1052,7 +2344,7 @@ impl Tool for EnvVarTool {
                     Ok(ToolResult::success(result))
                 } else {
                     Ok(ToolResult::error(format!(
-                        "Environment variable '{}' not found",
+                        "Environment variable '{}' not found or not accessible",
                         var_name
                     )))
                 }
@@ -1078,9 +2370,15 @@ pub async fn create_builtin_tools() -> Result<ToolRegistry, crate::tools::ToolEr
     registry
         .register_tool(Box::new(FileListTool::new()))
         .await?;
+    registry
+        .register_tool(Box::new(FileEditTool::new()))
+        .await?;
+    registry.register_tool(Box::new(GrepTool::new())).await?;
+    registry.register_tool(Box::new(GlobTool::new())).await?;
     registry
         .register_tool(Box::new(HttpRequestTool::new()))
         .await?;
+    registry.register_tool(Box::new(WebPageTool::new())).await?;
     registry
         .register_tool(Box::new(CurrentTimeTool::new()))
         .await?;
@@ -1148,6 +2446,10 @@ impl Tool for ThinkTool {
         })
     }
 
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::read_only().with_open_world(false)
+    }
+
     async fn execute(
         &self,
         parameters: Option<serde_json::Value>,
@@ -1183,6 +2485,78 @@ impl Tool for ThinkTool {
     }
 }
 
+/// Name of the [`CompactContextTool`]
+pub const COMPACT_CONTEXT_TOOL_NAME: &str = "compact_context";
+
+/// A tool the model can call to compact its own conversation history
+///
+/// The tool itself only records the request; the event loop performs the
+/// compaction after the tool batch completes (see
+/// [`CompactionConfig`](crate::context_manager::CompactionConfig)) and replaces
+/// this result with the number of messages compacted and the tokens freed.
+/// Register it with
+/// [`AgentBuilder::with_context_compaction`](crate::agent::AgentBuilder::with_context_compaction).
+#[derive(Debug, Clone, Default)]
+pub struct CompactContextTool;
+
+impl CompactContextTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for CompactContextTool {
+    fn name(&self) -> &str {
+        COMPACT_CONTEXT_TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "Summarize older parts of this conversation to free context space. Use this when \
+         the conversation has grown long and earlier details (such as large tool outputs) \
+         are no longer needed verbatim. Recent messages are kept as-is."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "keep_recent_messages": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Number of most recent messages to keep unchanged"
+                },
+                "focus": {
+                    "type": "string",
+                    "description": "What the summary should preserve in particular"
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        _context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let parameters = parameters.unwrap_or_else(|| serde_json::json!({}));
+        if let Some(keep) = parameters.get("keep_recent_messages") {
+            if !keep.is_u64() {
+                return Err(ToolError::InvalidParameters {
+                    message: "'keep_recent_messages' must be a non-negative integer".to_string(),
+                });
+            }
+        }
+
+        Ok(ToolResult::success(serde_json::json!({
+            "status": "compaction_requested",
+            "keep_recent_messages": parameters.get("keep_recent_messages"),
+            "focus": parameters.get("focus"),
+        })))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1269,17 +2643,25 @@ mod tests {
         assert!(registry.has_tool("file_read").await);
         assert!(registry.has_tool("file_write").await);
         assert!(registry.has_tool("file_list").await);
+        assert!(registry.has_tool("file_edit").await);
+        assert!(registry.has_tool("grep").await);
+        assert!(registry.has_tool("glob").await);
         assert!(registry.has_tool("http_request").await);
+        assert!(registry.has_tool("web_page").await);
         assert!(registry.has_tool("current_time").await);
         assert!(registry.has_tool("env_var").await);
 
         let tool_names = registry.tool_names().await;
-        assert_eq!(tool_names.len(), 8);
+        assert_eq!(tool_names.len(), 12);
         assert!(tool_names.contains(&"calculator".to_string()));
         assert!(tool_names.contains(&"file_read".to_string()));
         assert!(tool_names.contains(&"file_write".to_string()));
         assert!(tool_names.contains(&"file_list".to_string()));
+        assert!(tool_names.contains(&"file_edit".to_string()));
+        assert!(tool_names.contains(&"grep".to_string()));
+        assert!(tool_names.contains(&"glob".to_string()));
         assert!(tool_names.contains(&"http_request".to_string()));
+        assert!(tool_names.contains(&"web_page".to_string()));
         assert!(tool_names.contains(&"current_time".to_string()));
         assert!(tool_names.contains(&"env_var".to_string()));
         assert!(tool_names.contains(&"think".to_string()));
@@ -1377,6 +2759,283 @@ mod tests {
         assert_eq!(result.content.get("path").unwrap(), ".");
     }
 
+    #[tokio::test]
+    async fn test_file_edit_tool_search_replace() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.rs");
+        std::fs::write(&path, "fn main() {\n    println!(\"hello\");\n}\n").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+
+        let tool = FileEditTool::new();
+        assert_eq!(tool.name(), "file_edit");
+
+        // Dry run leaves the file untouched
+        let result = tool
+            .execute(
+                Some(json!({
+                    "path": path_str,
+                    "edits": [{"old_string": "hello", "new_string": "goodbye"}],
+                    "dry_run": true
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.content["preview"]
+            .as_str()
+            .unwrap()
+            .contains("+    println!(\"goodbye\");"));
+        assert!(std::fs::read_to_string(&path).unwrap().contains("hello"));
+
+        let result = tool
+            .execute(
+                Some(json!({
+                    "path": path_str,
+                    "edits": [{"old_string": "hello", "new_string": "goodbye"}]
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.content["replacements"], 1);
+        assert!(std::fs::read_to_string(&path).unwrap().contains("goodbye"));
+
+        // Original content is kept in the backup
+        let backup = result.content["backup_path"].as_str().unwrap();
+        assert!(std::fs::read_to_string(backup).unwrap().contains("hello"));
+    }
+
+    #[tokio::test]
+    async fn test_file_edit_tool_ambiguous_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.txt");
+        std::fs::write(&path, "a\na\n").unwrap();
+        let tool = FileEditTool::new();
+
+        let result = tool
+            .execute(
+                Some(json!({
+                    "path": path.to_string_lossy(),
+                    "edits": [{"old_string": "a", "new_string": "b"}]
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("matches 2 times"));
+
+        let result = tool
+            .execute(
+                Some(json!({
+                    "path": path.to_string_lossy(),
+                    "edits": [{"old_string": "a", "new_string": "b", "replace_all": true}],
+                    "backup": false
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "b\nb\n");
+        assert!(!dir.path().join("data.txt.bak").exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_edit_tool_unified_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("list.txt");
+        std::fs::write(&path, "one\ntwo\nthree\nfour\n").unwrap();
+        let tool = FileEditTool::new();
+
+        let diff = "--- a/list.txt\n+++ b/list.txt\n@@ -2,2 +2,3 @@\n two\n-three\n+THREE\n+three and a half\n";
+        let result = tool
+            .execute(
+                Some(json!({
+                    "path": path.to_string_lossy(),
+                    "diff": diff,
+                    "backup": false
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "one\ntwo\nTHREE\nthree and a half\nfour\n"
+        );
+
+        // A hunk whose context no longer matches is rejected without writing
+        let result = tool
+            .execute(
+                Some(json!({
+                    "path": path.to_string_lossy(),
+                    "diff": "@@ -1,1 +1,1 @@\n-missing\n+found\n"
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_unified_diff_body_lines_that_look_like_headers() {
+        let content = "SELECT 1;\n-- old comment\n---\nx\n";
+        let diff = "--- a/q.sql\n+++ b/q.sql\n@@ -1,4 +1,3 @@\n SELECT 1;\n--- old comment\n----\n+++i;\n x\n";
+        let (result, hunks) = apply_unified_diff(content, diff).unwrap();
+        assert_eq!(hunks, 1);
+        assert_eq!(result, "SELECT 1;\n++i;\nx\n");
+
+        // Insertion-only hunks go after their start line
+        let (result, _) = apply_unified_diff("a\nb\n", "@@ -1,0 +2 @@\n+inserted\n").unwrap();
+        assert_eq!(result, "a\ninserted\nb\n");
+
+        let err = apply_unified_diff(content, "@@ -1,1 +1,1 @@\n-SELECT 1;\n+SELECT 2;\n x\n")
+            .unwrap_err();
+        assert!(err.contains("more lines than its header"), "{}", err);
+    }
+
+    #[test]
+    fn test_unified_diff_rejects_multiple_files() {
+        let diff = "--- a/one.txt\n+++ b/one.txt\n@@ -1 +1 @@\n-a\n+b\n\
+                    --- a/two.txt\n+++ b/two.txt\n@@ -1 +1 @@\n-a\n+c\n";
+        let err = apply_unified_diff("a\n", diff).unwrap_err();
+        assert!(err.contains("more than one file"), "{}", err);
+    }
+
+    #[test]
+    fn test_unified_diff_keeps_crlf_line_endings() {
+        let content = "one\r\ntwo\r\nthree\r\n";
+        let diff = "@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n";
+        let (result, _) = apply_unified_diff(content, diff).unwrap();
+        assert_eq!(result, "one\r\n2\r\nthree\r\n");
+
+        // Diffs generated on Windows carry CRLF too
+        let (result, _) = apply_unified_diff(content, &diff.replace('\n', "\r\n")).unwrap();
+        assert_eq!(result, "one\r\n2\r\nthree\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_file_edit_tool_requires_edits_or_diff() {
+        let tool = FileEditTool::new();
+        let result = tool.execute(Some(json!({"path": "/tmp/x"})), None).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters { .. })));
+    }
+
+    fn create_search_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        // ignore only honors .gitignore inside a git repository
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub mod a;\n// TODO: first\nfn x() {}\n// TODO: second\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("src/notes.md"), "todo: lowercase\n").unwrap();
+        std::fs::write(dir.path().join("target/out.rs"), "// TODO: ignored\n").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_grep_tool() {
+        let dir = create_search_tree();
+        let tool = GrepTool::new();
+        assert_eq!(tool.name(), "grep");
+
+        let result = tool
+            .execute(
+                Some(json!({
+                    "pattern": "TODO",
+                    "path": dir.path().to_string_lossy(),
+                    "context_lines": 1
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.success);
+        // target/ is gitignored and notes.md only matches case-insensitively
+        assert_eq!(result.content["count"], 2);
+        let first = &result.content["matches"][0];
+        assert_eq!(first["line_number"], 2);
+        assert_eq!(first["before"][0], "pub mod a;");
+        assert_eq!(first["after"][0], "fn x() {}");
+
+        let result = tool
+            .execute(
+                Some(json!({
+                    "pattern": "todo",
+                    "path": dir.path().to_string_lossy(),
+                    "case_insensitive": true,
+                    "glob": "*.md"
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.content["count"], 1);
+
+        let result = tool
+            .execute(
+                Some(json!({
+                    "pattern": "TODO",
+                    "path": dir.path().to_string_lossy(),
+                    "max_results": 1
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.content["count"], 1);
+        assert_eq!(result.content["truncated"], true);
+    }
+
+    #[tokio::test]
+    async fn test_grep_tool_invalid_regex() {
+        let tool = GrepTool::new();
+        let result = tool.execute(Some(json!({"pattern": "("})), None).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_glob_tool() {
+        let dir = create_search_tree();
+        let tool = GlobTool::new();
+        assert_eq!(tool.name(), "glob");
+
+        let result = tool
+            .execute(
+                Some(json!({
+                    "pattern": "**/*.rs",
+                    "path": dir.path().to_string_lossy()
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.content["files"], json!(["src/lib.rs"]));
+
+        let result = tool
+            .execute(
+                Some(json!({
+                    "pattern": "*.rs",
+                    "path": dir.path().to_string_lossy()
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.content["count"], 0);
+    }
+
     #[tokio::test]
     async fn test_http_request_tool() {
         let tool = HttpRequestTool::new();
@@ -1395,6 +3054,127 @@ mod tests {
         assert!(result.error.is_some());
     }
 
+    #[test]
+    fn test_web_page_markdown_extraction() {
+        let html = r#"<html><head><title> Example  Page </title><style>p{}</style></head>
+            <body><nav><a href="/home">Home</a></nav>
+            <article><h1>Hello</h1><p>Some <strong>bold</strong> and <a href="/docs">docs</a>.</p>
+            <ul><li>one</li><li>two</li></ul><script>alert(1)</script></article></body></html>"#;
+        let base = url::Url::parse("https://example.com/a/").unwrap();
+
+        let (title, content) = extract_page_content(html, None, Some(&base), true);
+        assert_eq!(title.as_deref(), Some("Example Page"));
+        assert!(content.contains("# Hello"));
+        assert!(content.contains("Some **bold** and [docs](https://example.com/docs)."));
+        assert!(content.contains("- one\n- two"));
+        assert!(!content.contains("alert"));
+
+        let selector = scraper::Selector::parse("article p").unwrap();
+        let (_, content) = extract_page_content(html, Some(&selector), Some(&base), false);
+        assert_eq!(content, "Some bold and docs.");
+    }
+
+    #[test]
+    fn test_web_page_deeply_nested_html() {
+        let depth = 5_000;
+        let html = format!(
+            "<html><body>{}deep text{}</body></html>",
+            "<div>".repeat(depth),
+            "</div>".repeat(depth)
+        );
+        let (_, content) = extract_page_content(&html, None, None, true);
+        assert_eq!(content, "deep text");
+    }
+
+    #[tokio::test]
+    async fn test_web_page_tool_normalized_url_is_not_a_redirect() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await.unwrap();
+            let body = "<p>home</p>";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        // Requested without a path, fetched as "/"
+        let result = WebPageTool::new()
+            .execute(Some(json!({ "url": format!("http://{}", addr) })), None)
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.content["redirected"], false);
+    }
+
+    #[tokio::test]
+    async fn test_web_page_tool_follows_redirects_and_truncates() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 1024];
+                let n = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let response = if request.starts_with("GET /old") {
+                    "HTTP/1.1 301 Moved Permanently\r\nLocation: /new\r\nContent-Length: 0\r\n\r\n"
+                        .to_string()
+                } else {
+                    let body = "<html><body><p>0123456789 abcdefghij</p></body></html>";
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let tool = WebPageTool::new();
+        assert_eq!(tool.name(), "web_page");
+        let result = tool
+            .execute(
+                Some(json!({
+                    "url": format!("http://{}/old", addr),
+                    "max_chars": 10
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.content["redirected"], true);
+        assert!(result.content["final_url"]
+            .as_str()
+            .unwrap()
+            .ends_with("/new"));
+        assert_eq!(result.content["content"], "0123456789");
+        assert_eq!(result.content["truncated"], true);
+    }
+
+    #[tokio::test]
+    async fn test_web_page_tool_invalid_selector() {
+        let tool = WebPageTool::new();
+        let result = tool
+            .execute(
+                Some(json!({"url": "http://localhost", "selector": "<<"})),
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters { .. })));
+    }
+
     #[tokio::test]
     async fn test_current_time_tool() {
         let tool = CurrentTimeTool::new();
@@ -1456,6 +3236,22 @@ mod tests {
         assert_eq!(result.content.get("found").unwrap(), false);
         assert_eq!(result.content.get("used_default").unwrap(), true);
         assert_eq!(result.content.get("value").unwrap(), "default_value");
+
+        // Variables outside the allowlist are hidden unless the context allows them
+        std::env::set_var("STOOD_ENV_TOOL_PRIVATE", "secret");
+        let input = json!({ "name": "STOOD_ENV_TOOL_PRIVATE" });
+        let result = tool.execute(Some(input.clone()), None).await.unwrap();
+        assert!(!result.success);
+
+        let mut context = crate::agent::AgentContext::new("agent", None, "test");
+        context.secrets = Some(std::sync::Arc::new(
+            crate::secrets::ScopedSecrets::new(std::sync::Arc::new(
+                crate::secrets::EnvSecretsProvider,
+            ))
+            .allow("STOOD_ENV_TOOL_*"),
+        ));
+        let result = tool.execute(Some(input), Some(&context)).await.unwrap();
+        assert_eq!(result.content.get("value").unwrap(), "secret");
     }
 
     #[test]
@@ -1471,6 +3267,24 @@ mod tests {
         assert!(evaluate_expression("2 + + 3").is_err());
     }
 
+    #[tokio::test]
+    async fn test_compact_context_tool() {
+        let tool = CompactContextTool::new();
+        assert_eq!(tool.name(), COMPACT_CONTEXT_TOOL_NAME);
+
+        let result = tool
+            .execute(Some(json!({"keep_recent_messages": 4})), None)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.content["status"], "compaction_requested");
+
+        let invalid = tool
+            .execute(Some(json!({"keep_recent_messages": "all"})), None)
+            .await;
+        assert!(invalid.is_err());
+    }
+
     #[tokio::test]
     async fn test_think_tool_basic() {
         let tool = ThinkTool::default();
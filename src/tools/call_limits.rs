@@ -0,0 +1,188 @@
+//! Limits on how often the model may call a tool in one execution.
+//!
+//! Some tools are expensive or risky to call repeatedly: an agent that fetches
+//! dozens of URLs or rewrites the same file again and again in one request is
+//! usually stuck. [`ToolCallLimits`] caps the calls to individual tools within
+//! one agent execution. Each limit is stated in the tool's description so the
+//! model can plan around it. A call over the limit is not executed; the model
+//! gets an error result saying the limit was reached and what to do instead.
+//!
+//! ```
+//! use stood::tools::call_limits::ToolCallLimits;
+//!
+//! let limits = ToolCallLimits::new()
+//!     .with_limit("http_request", 10)
+//!     .with_limit("file_write", 3)
+//!     .with_alternative("file_write", "Put the remaining changes in one file_write call.");
+//!
+//! assert!(limits.check("file_write", 2).is_ok());
+//! let reached = limits.check("file_write", 3).unwrap_err();
+//! assert_eq!(
+//!     reached.to_string(),
+//!     "Call limit reached: file_write can be called at most 3 times per request and \
+//!      was already called 3 times. Do not call it again. Put the remaining changes \
+//!      in one file_write call."
+//! );
+//! ```
+//!
+//! Limits are set with
+//! [`AgentBuilder::with_tool_call_limits`](crate::agent::AgentBuilder::with_tool_call_limits)
+//! and counted per call to `execute`, across all of its cycles.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Guidance when a limited tool has no configured alternative
+const DEFAULT_ALTERNATIVE: &str =
+    "Continue with the results you already have, or use a different tool.";
+
+/// Per-tool caps on calls within one execution
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallLimits {
+    max_calls: HashMap<String, u32>,
+    alternatives: HashMap<String, String>,
+}
+
+/// A call refused because its tool reached its limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallLimitReached {
+    /// Name of the tool
+    pub tool: String,
+    /// Calls allowed per execution
+    pub max_calls: u32,
+    /// What the model should do instead
+    pub alternative: String,
+}
+
+impl fmt::Display for CallLimitReached {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.max_calls == 0 {
+            return write!(
+                f,
+                "Call limit reached: {} cannot be called in this request. {}",
+                self.tool, self.alternative
+            );
+        }
+        write!(
+            f,
+            "Call limit reached: {} can be called at most {} per request and was already \
+             called {}. Do not call it again. {}",
+            self.tool,
+            times(self.max_calls),
+            times(self.max_calls),
+            self.alternative
+        )
+    }
+}
+
+impl std::error::Error for CallLimitReached {}
+
+impl ToolCallLimits {
+    /// Create an empty set of limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow `tool` to be called at most `max_calls` times per execution
+    pub fn with_limit(mut self, tool: impl Into<String>, max_calls: u32) -> Self {
+        self.max_calls.insert(tool.into(), max_calls);
+        self
+    }
+
+    /// What to tell the model to do once `tool` reached its limit
+    pub fn with_alternative(
+        mut self,
+        tool: impl Into<String>,
+        guidance: impl Into<String>,
+    ) -> Self {
+        self.alternatives.insert(tool.into(), guidance.into());
+        self
+    }
+
+    /// Add the limits and alternatives of `other`, replacing those for the same tools
+    pub fn extend(&mut self, other: ToolCallLimits) {
+        self.max_calls.extend(other.max_calls);
+        self.alternatives.extend(other.alternatives);
+    }
+
+    /// Whether no tool is limited
+    pub fn is_empty(&self) -> bool {
+        self.max_calls.is_empty()
+    }
+
+    /// Calls allowed per execution for `tool`, if it is limited
+    pub fn limit_for(&self, tool: &str) -> Option<u32> {
+        self.max_calls.get(tool).copied()
+    }
+
+    /// Check whether `tool` may be called again after `calls_made` calls
+    pub fn check(&self, tool: &str, calls_made: u32) -> Result<(), CallLimitReached> {
+        match self.limit_for(tool) {
+            Some(max_calls) if calls_made >= max_calls => Err(CallLimitReached {
+                tool: tool.to_string(),
+                max_calls,
+                alternative: self
+                    .alternatives
+                    .get(tool)
+                    .cloned()
+                    .unwrap_or_else(|| DEFAULT_ALTERNATIVE.to_string()),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// `description` with the limit of `tool` appended, if it has one
+    pub fn describe(&self, tool: &str, description: &str) -> String {
+        match self.limit_for(tool) {
+            Some(max_calls) => format!(
+                "{}\n\nLimit: call this tool at most {} per request.",
+                description.trim_end(),
+                times(max_calls)
+            ),
+            None => description.to_string(),
+        }
+    }
+}
+
+fn times(count: u32) -> String {
+    match count {
+        1 => "once".to_string(),
+        n => format!("{} times", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_and_describe() {
+        let limits = ToolCallLimits::new()
+            .with_limit("http_request", 1)
+            .with_alternative("http_request", "Use web_search instead.");
+
+        assert!(limits.check("http_request", 0).is_ok());
+        assert!(limits.check("file_read", 100).is_ok());
+        let reached = limits.check("http_request", 1).unwrap_err();
+        assert_eq!(reached.max_calls, 1);
+        assert!(reached.to_string().ends_with("Use web_search instead."));
+
+        assert_eq!(
+            limits.describe("http_request", "Fetch a URL.\n"),
+            "Fetch a URL.\n\nLimit: call this tool at most once per request."
+        );
+        assert_eq!(limits.describe("file_read", "Read a file."), "Read a file.");
+    }
+
+    #[test]
+    fn test_extend_replaces_limits() {
+        let mut limits = ToolCallLimits::new().with_limit("file_write", 3);
+        assert!(!limits.is_empty());
+        limits.extend(ToolCallLimits::new().with_limit("file_write", 5));
+        assert_eq!(limits.limit_for("file_write"), Some(5));
+        assert_eq!(
+            limits.check("file_write", 5).unwrap_err().alternative,
+            DEFAULT_ALTERNATIVE
+        );
+    }
+}
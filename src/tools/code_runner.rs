@@ -0,0 +1,674 @@
+//! Sandboxed code execution tool (requires the `code-runner` feature).
+//!
+//! [`CodeRunnerTool`] (`run_code`) lets the model write and run Python, Node.js
+//! or Bash snippets, for example to compute statistics or draw charts. Each run
+//! executes in a fresh subprocess inside a per-session workspace directory. Files
+//! written by earlier runs stay available to later ones, but interpreter state
+//! (variables, imports) does not. Each agent gets its own subdirectory of the
+//! workspace, named after its agent id, so agents sharing the tool do not see
+//! each other's files.
+//!
+//! # Quick Start
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! use stood::tools::code_runner::{CodeLanguage, CodeRunnerConfig, CodeRunnerTool};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let runner = CodeRunnerTool::new(
+//!     CodeRunnerConfig::default()
+//!         .with_languages(vec![CodeLanguage::Python])
+//!         .with_timeout(Duration::from_secs(20)),
+//! )?;
+//! println!("Workspace: {}", runner.workspace().display());
+//!
+//! let agent = Agent::builder().tool(Box::new(runner)).build().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Sandbox
+//!
+//! The subprocess runs with a cleared environment (only `PATH`, `HOME` set to the
+//! workspace, `LANG` and `MPLBACKEND=Agg`), no stdin, in its own process group,
+//! and is killed with its children when the timeout expires or once it exits,
+//! so background processes it started do not outlive the run. On Unix it also runs
+//! under resource limits for address space, CPU time and file size. This guards
+//! against runaway code, not against deliberately malicious code: the process
+//! can still read files and use the network with the permissions of the host
+//! process. Run the agent in a container or VM when that matters.
+//!
+//! # Plots and Files
+//!
+//! Files created or modified by a run are reported in the result's `files`
+//! array; images are additionally listed under `plots`. For Python, calling
+//! `matplotlib.pyplot.show()` saves each open figure as a PNG in the workspace.
+
+use crate::tools::{Tool, ToolError, ToolResult};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncReadExt;
+use tokio_util::sync::CancellationToken;
+
+/// Image extensions reported as plots
+/// How long output is still read once the run has ended, in case a process
+/// outside its group holds the pipes open
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+const PLOT_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "svg", "gif"];
+
+/// Saves open matplotlib figures when `plt.show()` is called, then runs the cell
+/// with its original file name so tracebacks point at the right lines
+const PYTHON_LAUNCHER: &str = r#"
+import runpy, sys
+try:
+    if "matplotlib" in open(sys.argv[1], encoding="utf-8").read():
+        import matplotlib
+        matplotlib.use("Agg")
+        import matplotlib.pyplot as _plt
+        def _show(*args, **kwargs):
+            for num in _plt.get_fignums():
+                _plt.figure(num).savefig(f"{sys.argv[2]}_figure_{num}.png", bbox_inches="tight")
+            _plt.close("all")
+        _plt.show = _show
+except ImportError:
+    pass
+sys.argv = sys.argv[1:2]
+runpy.run_path(sys.argv[0], run_name="__main__")
+"#;
+
+/// Languages the tool can run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodeLanguage {
+    Python,
+    JavaScript,
+    Bash,
+}
+
+impl CodeLanguage {
+    /// Name used in the tool schema
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::Bash => "bash",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "python" | "python3" | "py" => Some(Self::Python),
+            "javascript" | "js" | "node" => Some(Self::JavaScript),
+            "bash" | "sh" | "shell" => Some(Self::Bash),
+            _ => None,
+        }
+    }
+
+    fn default_interpreter(&self) -> &'static str {
+        match self {
+            Self::Python => "python3",
+            Self::JavaScript => "node",
+            Self::Bash => "bash",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Python => "py",
+            Self::JavaScript => "js",
+            Self::Bash => "sh",
+        }
+    }
+}
+
+/// Configuration for [`CodeRunnerTool`]
+#[derive(Debug, Clone)]
+pub struct CodeRunnerConfig {
+    /// Languages the model may use (default: Python)
+    pub languages: Vec<CodeLanguage>,
+    /// Interpreter overrides, e.g. a virtualenv's `python`
+    pub interpreters: HashMap<CodeLanguage, PathBuf>,
+    /// Workspace directory; a new temporary directory is created if unset
+    pub workspace: Option<PathBuf>,
+    /// Wall-clock limit per run (default: 30s)
+    pub timeout: Duration,
+    /// CPU time limit per run in seconds (Unix only, default: 30)
+    pub cpu_time_secs: u64,
+    /// Address space limit in bytes (Unix only, default: 1 GiB)
+    pub memory_limit_bytes: u64,
+    /// Largest file a run may write in bytes (Unix only, default: 64 MiB)
+    pub max_file_size_bytes: u64,
+    /// Maximum bytes of stdout and stderr each returned to the model (default: 32 KiB)
+    pub max_output_bytes: usize,
+}
+
+impl Default for CodeRunnerConfig {
+    fn default() -> Self {
+        Self {
+            languages: vec![CodeLanguage::Python],
+            interpreters: HashMap::new(),
+            workspace: None,
+            timeout: Duration::from_secs(30),
+            cpu_time_secs: 30,
+            memory_limit_bytes: 1024 * 1024 * 1024,
+            max_file_size_bytes: 64 * 1024 * 1024,
+            max_output_bytes: 32 * 1024,
+        }
+    }
+}
+
+impl CodeRunnerConfig {
+    pub fn with_languages(mut self, languages: Vec<CodeLanguage>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    pub fn with_interpreter(mut self, language: CodeLanguage, path: impl Into<PathBuf>) -> Self {
+        self.interpreters.insert(language, path.into());
+        self
+    }
+
+    pub fn with_workspace(mut self, workspace: impl Into<PathBuf>) -> Self {
+        self.workspace = Some(workspace.into());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_memory_limit(mut self, bytes: u64) -> Self {
+        self.memory_limit_bytes = bytes;
+        self
+    }
+
+    pub fn with_max_output_bytes(mut self, bytes: usize) -> Self {
+        self.max_output_bytes = bytes;
+        self
+    }
+}
+
+/// Runs model-generated code in a sandboxed subprocess
+#[derive(Debug)]
+pub struct CodeRunnerTool {
+    config: CodeRunnerConfig,
+    workspace: PathBuf,
+    runs: AtomicU64,
+}
+
+impl CodeRunnerTool {
+    /// Create the tool and its workspace directory
+    pub fn new(config: CodeRunnerConfig) -> Result<Self, ToolError> {
+        if config.languages.is_empty() {
+            return Err(ToolError::InvalidParameters {
+                message: "At least one language must be enabled".to_string(),
+            });
+        }
+        let workspace = match &config.workspace {
+            Some(path) => path.clone(),
+            None => std::env::temp_dir().join(format!("stood-code-{}", uuid::Uuid::new_v4())),
+        };
+        std::fs::create_dir_all(&workspace).map_err(|e| {
+            ToolError::with_source(
+                format!("Failed to create workspace {}: {}", workspace.display(), e),
+                e,
+            )
+        })?;
+
+        Ok(Self {
+            config,
+            workspace,
+            runs: AtomicU64::new(0),
+        })
+    }
+
+    /// Directory in which code runs and files persist between runs
+    ///
+    /// Runs of an agent use its subdirectory (see [`session_workspace`](Self::session_workspace)).
+    pub fn workspace(&self) -> &Path {
+        &self.workspace
+    }
+
+    /// Workspace of the agent `agent_id`
+    pub fn session_workspace(&self, agent_id: &str) -> PathBuf {
+        let name: String = agent_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.workspace.join(format!("agent-{}", name))
+    }
+
+    async fn run(
+        &self,
+        language: CodeLanguage,
+        code: &str,
+        workspace: &Path,
+    ) -> Result<serde_json::Value, String> {
+        tokio::fs::create_dir_all(workspace)
+            .await
+            .map_err(|e| format!("Failed to create workspace {}: {}", workspace.display(), e))?;
+        let run = self.runs.fetch_add(1, Ordering::Relaxed) + 1;
+        let stem = format!("cell_{}", run);
+        let script = workspace.join(format!("{}.{}", stem, language.extension()));
+        tokio::fs::write(&script, code)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", script.display(), e))?;
+
+        let before = snapshot(workspace);
+        let interpreter = self
+            .config
+            .interpreters
+            .get(&language)
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(language.default_interpreter()));
+
+        let mut cmd = tokio::process::Command::new(&interpreter);
+        match language {
+            CodeLanguage::Python => {
+                cmd.arg("-c").arg(PYTHON_LAUNCHER).arg(&script).arg(&stem);
+            }
+            _ => {
+                cmd.arg(&script);
+            }
+        }
+        cmd.current_dir(workspace)
+            .env_clear()
+            .env("PATH", std::env::var("PATH").unwrap_or_default())
+            .env("HOME", workspace)
+            .env("LANG", "C.UTF-8")
+            .env("MPLBACKEND", "Agg")
+            .env("PYTHONUNBUFFERED", "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        self.apply_limits(&mut cmd);
+
+        let started = std::time::Instant::now();
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", interpreter.display(), e))?;
+
+        // The child leads its process group; its id is gone once it is reaped
+        let group = child.id();
+        let limit = self.config.max_output_bytes;
+        let stop_reading = CancellationToken::new();
+        let stdout = tokio::spawn(read_capped(
+            child.stdout.take(),
+            limit,
+            stop_reading.clone(),
+        ));
+        let stderr = tokio::spawn(read_capped(
+            child.stderr.take(),
+            limit,
+            stop_reading.clone(),
+        ));
+
+        let (status, timed_out) =
+            match tokio::time::timeout(self.config.timeout, child.wait()).await {
+                Ok(status) => (Some(status), false),
+                Err(_) => (None, true),
+            };
+        kill_group(&mut child, group);
+        if timed_out {
+            let _ = child.wait().await;
+        }
+
+        let drain_timeout = tokio::spawn(async move {
+            tokio::time::sleep(OUTPUT_DRAIN_TIMEOUT).await;
+            stop_reading.cancel();
+        });
+        let (stdout, stdout_truncated) = stdout.await.unwrap_or_default();
+        let (stderr, stderr_truncated) = stderr.await.unwrap_or_default();
+        drain_timeout.abort();
+        let exit_code = match status {
+            Some(status) => status.map_err(|e| e.to_string())?.code(),
+            None => None,
+        };
+
+        let files = changed_files(workspace, &before, &script);
+        let plots: Vec<&serde_json::Value> =
+            files.iter().filter(|f| f["kind"] == "image").collect();
+
+        Ok(serde_json::json!({
+            "language": language.name(),
+            "exit_code": exit_code,
+            "timed_out": timed_out,
+            "stdout": stdout,
+            "stderr": stderr,
+            "output_truncated": stdout_truncated || stderr_truncated,
+            "duration_ms": started.elapsed().as_millis() as u64,
+            "files": files,
+            "plots": plots,
+            "workspace": workspace.display().to_string()
+        }))
+    }
+
+    #[cfg(unix)]
+    fn apply_limits(&self, cmd: &mut tokio::process::Command) {
+        let limits = [
+            (libc::RLIMIT_AS, self.config.memory_limit_bytes),
+            (libc::RLIMIT_CPU, self.config.cpu_time_secs),
+            (libc::RLIMIT_FSIZE, self.config.max_file_size_bytes),
+            (libc::RLIMIT_CORE, 0),
+        ];
+        cmd.process_group(0);
+        // SAFETY: setrlimit is async-signal-safe and the closure does not allocate
+        unsafe {
+            cmd.pre_exec(move || {
+                for (resource, value) in limits {
+                    let limit = libc::rlimit {
+                        rlim_cur: value as libc::rlim_t,
+                        rlim_max: value as libc::rlim_t,
+                    };
+                    if libc::setrlimit(resource, &limit) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn apply_limits(&self, _cmd: &mut tokio::process::Command) {}
+}
+
+/// Kill the run and any processes it started in its process group `group`
+fn kill_group(child: &mut tokio::process::Child, group: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pid) = group {
+        unsafe {
+            libc::killpg(pid as i32, libc::SIGKILL);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = group;
+    let _ = child.start_kill();
+}
+
+/// Read a stream to the end or until `stop`, keeping at most `limit` bytes
+///
+/// Output cut short by `stop` counts as truncated.
+async fn read_capped<R>(stream: Option<R>, limit: usize, stop: CancellationToken) -> (String, bool)
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let Some(mut stream) = stream else {
+        return (String::new(), false);
+    };
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut buf = [0u8; 8192];
+    // Keep draining after the limit so the child never blocks on a full pipe
+    loop {
+        let n = tokio::select! {
+            read = stream.read(&mut buf) => match read {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            },
+            _ = stop.cancelled() => {
+                truncated = true;
+                break;
+            }
+        };
+        let room = limit.saturating_sub(kept.len());
+        kept.extend_from_slice(&buf[..n.min(room)]);
+        truncated |= n > room;
+    }
+    (String::from_utf8_lossy(&kept).into_owned(), truncated)
+}
+
+fn snapshot(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    let mut files = HashMap::new();
+    for entry in ignore::WalkBuilder::new(dir)
+        .standard_filters(false)
+        .build()
+        .flatten()
+    {
+        if let Ok(metadata) = entry.metadata() {
+            if metadata.is_file() {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.insert(entry.into_path(), modified);
+            }
+        }
+    }
+    files
+}
+
+/// Files created or modified since `before`, excluding the script itself
+fn changed_files(
+    dir: &Path,
+    before: &HashMap<PathBuf, SystemTime>,
+    script: &Path,
+) -> Vec<serde_json::Value> {
+    let mut changed: Vec<_> = snapshot(dir)
+        .into_iter()
+        .filter(|(path, modified)| {
+            path != script && before.get(path).is_none_or(|previous| modified > previous)
+        })
+        .map(|(path, _)| path)
+        .collect();
+    changed.sort();
+
+    changed
+        .into_iter()
+        .map(|path| {
+            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let is_image = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| PLOT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+            serde_json::json!({
+                "path": path.strip_prefix(dir).unwrap_or(&path).display().to_string(),
+                "size": size,
+                "kind": if is_image { "image" } else { "file" }
+            })
+        })
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl Tool for CodeRunnerTool {
+    fn name(&self) -> &str {
+        "run_code"
+    }
+
+    fn description(&self) -> &str {
+        "Run a code snippet in a sandboxed workspace and return stdout, stderr, the exit code \
+         and files it created. Files persist between runs; variables do not. Save charts to \
+         image files (or call plt.show() in Python) to return them as plots"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let languages: Vec<&str> = self.config.languages.iter().map(|l| l.name()).collect();
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "Complete program to run; print results to stdout"
+                },
+                "language": {
+                    "type": "string",
+                    "enum": languages,
+                    "description": format!("Language of the code (default: {})", languages[0])
+                }
+            },
+            "required": ["code"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let code = params.get("code").and_then(|v| v.as_str()).ok_or_else(|| {
+            ToolError::InvalidParameters {
+                message: "Missing required parameter: code".to_string(),
+            }
+        })?;
+        let language = match params.get("language").and_then(|v| v.as_str()) {
+            None => self.config.languages[0],
+            Some(name) => match CodeLanguage::from_name(name) {
+                Some(language) if self.config.languages.contains(&language) => language,
+                _ => {
+                    return Err(ToolError::InvalidParameters {
+                        message: format!("Language '{}' is not enabled", name),
+                    })
+                }
+            },
+        };
+
+        let workspace = match agent_context {
+            Some(context) => self.session_workspace(&context.agent_id),
+            None => self.workspace.clone(),
+        };
+        match self.run(language, code, &workspace).await {
+            Ok(result) => {
+                let failed = result["timed_out"] == true || result["exit_code"] != 0;
+                if failed {
+                    // Return the full result so the model can read the traceback
+                    let mut tool_result = ToolResult::success(result.clone());
+                    tool_result.success = false;
+                    tool_result.error = Some(if result["timed_out"] == true {
+                        format!("Execution timed out after {:?}", self.config.timeout)
+                    } else {
+                        format!("Process exited with code {}", result["exit_code"])
+                    });
+                    Ok(tool_result)
+                } else {
+                    Ok(ToolResult::success(result))
+                }
+            }
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn runner(config: CodeRunnerConfig) -> (tempfile::TempDir, CodeRunnerTool) {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = CodeRunnerTool::new(config.with_workspace(dir.path())).unwrap();
+        (dir, tool)
+    }
+
+    #[tokio::test]
+    async fn test_run_persists_files_between_runs() {
+        let (_dir, tool) = runner(
+            CodeRunnerConfig::default()
+                .with_languages(vec![CodeLanguage::Bash, CodeLanguage::Python]),
+        );
+
+        let result = tool
+            .execute(
+                Some(json!({"code": "echo 42 > answer.txt; echo done", "language": "bash"})),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result);
+        assert_eq!(result.content["stdout"], "done\n");
+        assert_eq!(result.content["files"][0]["path"], "answer.txt");
+
+        let err = tool
+            .execute(Some(json!({"code": "1", "language": "ruby"})), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not enabled"));
+
+        let result = tool
+            .execute(
+                Some(json!({"code": "cat answer.txt; exit 3", "language": "sh"})),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.content["stdout"], "42\n");
+        assert_eq!(result.content["exit_code"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_output_limit() {
+        let (_dir, tool) = runner(
+            CodeRunnerConfig::default()
+                .with_languages(vec![CodeLanguage::Bash])
+                .with_timeout(Duration::from_millis(500))
+                .with_max_output_bytes(10),
+        );
+
+        let result = tool
+            .execute(Some(json!({"code": "seq 1 1000; sleep 30"})), None)
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.content["timed_out"], true);
+        assert_eq!(result.content["output_truncated"], true);
+        assert_eq!(result.content["stdout"].as_str().unwrap().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_background_processes_end_with_the_run() {
+        let (_dir, tool) = runner(
+            CodeRunnerConfig::default()
+                .with_languages(vec![CodeLanguage::Bash])
+                .with_timeout(Duration::from_secs(20)),
+        );
+
+        // The background sleep holds stdout open until it is killed
+        let started = std::time::Instant::now();
+        let result = tool
+            .execute(Some(json!({"code": "sleep 30 & echo started"})), None)
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result);
+        assert_eq!(result.content["stdout"], "started\n");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_agents_get_their_own_workspace() {
+        let (_dir, tool) =
+            runner(CodeRunnerConfig::default().with_languages(vec![CodeLanguage::Bash]));
+        let alice = crate::agent::AgentContext::new("alice", None, "agent");
+        let bob = crate::agent::AgentContext::new("bob", None, "agent");
+
+        let result = tool
+            .execute(
+                Some(json!({"code": "echo secret > notes.txt"})),
+                Some(&alice),
+            )
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result);
+        assert!(tool.session_workspace("alice").join("notes.txt").exists());
+
+        let result = tool
+            .execute(Some(json!({"code": "cat notes.txt"})), Some(&bob))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let result = tool
+            .execute(Some(json!({"code": "cat notes.txt"})), Some(&alice))
+            .await
+            .unwrap();
+        assert_eq!(result.content["stdout"], "secret\n");
+    }
+}
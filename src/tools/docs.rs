@@ -0,0 +1,393 @@
+//! Documentation generated from the registered tools.
+//!
+//! [`ToolRegistry::generate_markdown_docs`] renders a human-readable catalog
+//! of every tool: its description, annotations, versions, parameters and
+//! examples. [`ToolRegistry::generate_openapi`] describes the same tools as an
+//! OpenAPI 3.0 document with one `POST /tools/{name}` operation per tool, for
+//! review tools and API portals.
+//!
+//! Both document the version offered to models. Deprecated versions (see
+//! [`Tool::deprecation`]) carry a warning in the catalog and are marked
+//! `deprecated` in the OpenAPI document.
+//!
+//! Examples come from the `examples` keyword of the parameter schema, as in
+//! JSON Schema:
+//!
+//! ```json
+//! {"type": "object", "properties": {"city": {"type": "string"}}, "examples": [{"city": "Paris"}]}
+//! ```
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+
+use super::{Tool, ToolRegistry, ToolSource};
+
+impl ToolRegistry {
+    /// Render a Markdown catalog of all registered tools, sorted by name
+    pub async fn generate_markdown_docs(&self) -> String {
+        let tools = self.sorted_tools().await;
+        let mut doc = String::from("# Tool Catalog\n\n");
+        let _ = writeln!(doc, "{} tools available.\n", tools.len());
+
+        for tool in &tools {
+            let _ = writeln!(doc, "- [`{}`](#{})", tool.name(), tool.name());
+        }
+
+        for tool in &tools {
+            let schema = tool.parameters_schema();
+            let _ = write!(doc, "\n## {}\n\n", tool.name());
+            if let Some(title) = &tool.annotations().title {
+                let _ = write!(doc, "**{}**\n\n", title);
+            }
+            if let Some(reason) = tool.deprecation() {
+                let _ = write!(doc, "> **Deprecated:** {}\n\n", reason);
+            }
+            let _ = write!(doc, "{}\n\n", tool.description());
+            let _ = write!(
+                doc,
+                "- **Source:** {}\n- **Behavior:** {}\n- **Version:** {}\n",
+                source_name(&tool.source()),
+                behavior(tool.as_ref()),
+                tool.version()
+            );
+            let others = self.other_versions(tool.as_ref()).await;
+            if !others.is_empty() {
+                let _ = writeln!(doc, "- **Other versions:** {}", others.join(", "));
+            }
+            doc.push('\n');
+
+            let properties = schema["properties"].as_object().filter(|p| !p.is_empty());
+            match properties {
+                Some(properties) => {
+                    doc.push_str("| Parameter | Type | Required | Description |\n");
+                    doc.push_str("|-----------|------|----------|-------------|\n");
+                    for (name, property) in properties {
+                        let _ = writeln!(
+                            doc,
+                            "| `{}` | {} | {} | {} |",
+                            name,
+                            type_name(property),
+                            if is_required(&schema, name) {
+                                "yes"
+                            } else {
+                                "no"
+                            },
+                            table_cell(property["description"].as_str().unwrap_or(""))
+                        );
+                    }
+                    doc.push('\n');
+                }
+                None => doc.push_str("No parameters.\n\n"),
+            }
+
+            for example in examples(&schema) {
+                let _ = write!(
+                    doc,
+                    "Example input:\n\n```json\n{}\n```\n\n",
+                    serde_json::to_string_pretty(example).unwrap_or_default()
+                );
+            }
+        }
+
+        doc.truncate(doc.trim_end().len());
+        doc.push('\n');
+        doc
+    }
+
+    /// Describe all registered tools as an OpenAPI 3.0 document
+    ///
+    /// Each tool is a `POST /tools/{name}` operation whose request body is the
+    /// tool's parameter schema and whose response is a `ToolResult`.
+    pub async fn generate_openapi(&self) -> Value {
+        let mut paths = Map::new();
+        for tool in self.sorted_tools().await {
+            let schema = tool.parameters_schema();
+            let mut media_type = json!({ "schema": schema });
+            if let Some(example) = examples(&schema).first() {
+                media_type["example"] = (*example).clone();
+            }
+
+            let annotations = tool.annotations();
+            let mut operation = json!({
+                "operationId": tool.name(),
+                "summary": annotations.title.clone().unwrap_or_else(|| tool.name().to_string()),
+                "description": tool.description(),
+                "tags": [source_name(&tool.source())],
+                "requestBody": {
+                    "required": true,
+                    "content": { "application/json": media_type }
+                },
+                "responses": {
+                    "200": {
+                        "description": "Tool result",
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/ToolResult" }
+                            }
+                        }
+                    }
+                }
+            });
+            if !annotations.is_empty() {
+                operation["x-tool-annotations"] = json!(annotations);
+            }
+            operation["x-tool-version"] = json!(tool.version());
+            if let Some(reason) = tool.deprecation() {
+                operation["deprecated"] = json!(true);
+                operation["x-deprecation"] = json!(reason);
+            }
+            paths.insert(
+                format!("/tools/{}", tool.name()),
+                json!({ "post": operation }),
+            );
+        }
+
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "Tool Catalog",
+                "version": env!("CARGO_PKG_VERSION")
+            },
+            "paths": paths,
+            "components": {
+                "schemas": {
+                    "ToolResult": {
+                        "type": "object",
+                        "properties": {
+                            "success": { "type": "boolean" },
+                            "content": {},
+                            "error": { "type": "string", "nullable": true }
+                        },
+                        "required": ["success", "content"]
+                    }
+                }
+            }
+        })
+    }
+
+    async fn sorted_tools(&self) -> Vec<Arc<dyn Tool>> {
+        let tools = self.tools.read().await;
+        let mut tools: Vec<_> = tools.values().cloned().collect();
+        tools.sort_by(|a, b| a.name().cmp(b.name()));
+        tools
+    }
+
+    /// Registered versions of `tool`'s name other than its own, newest first
+    async fn other_versions(&self, tool: &dyn Tool) -> Vec<String> {
+        let versions = self.versions.read().await;
+        versions
+            .get(tool.name())
+            .into_iter()
+            .flatten()
+            .rev()
+            .filter(|other| other.version() != tool.version())
+            .map(|other| match other.deprecation() {
+                Some(reason) => format!("{} (deprecated: {})", other.version(), reason),
+                None => other.version().to_string(),
+            })
+            .collect()
+    }
+}
+
+fn source_name(source: &ToolSource) -> &'static str {
+    match source {
+        ToolSource::Builtin => "builtin",
+        ToolSource::MCP => "mcp",
+        ToolSource::Custom => "custom",
+        ToolSource::Plugin => "plugin",
+    }
+}
+
+fn behavior(tool: &dyn Tool) -> String {
+    let annotations = tool.annotations();
+    let mut traits = vec![if annotations.is_read_only() {
+        "read-only"
+    } else if annotations.is_destructive() {
+        "destructive"
+    } else {
+        "modifies state"
+    }];
+    if annotations.is_idempotent() {
+        traits.push("idempotent");
+    }
+    if annotations.is_open_world() {
+        traits.push("open world");
+    }
+    let mut behavior = traits.join(", ");
+    if annotations.is_empty() {
+        behavior.push_str(" (not annotated)");
+    }
+    behavior
+}
+
+fn type_name(property: &Value) -> String {
+    match &property["type"] {
+        Value::String(ty) if ty == "array" => match property["items"]["type"].as_str() {
+            Some(item) => format!("array of {}", item),
+            None => "array".to_string(),
+        },
+        Value::String(ty) => ty.clone(),
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" \\| "),
+        _ => match property["enum"].as_array() {
+            Some(values) => format!(
+                "one of {}",
+                values
+                    .iter()
+                    .map(Value::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => "any".to_string(),
+        },
+    }
+}
+
+fn is_required(schema: &Value, name: &str) -> bool {
+    schema["required"]
+        .as_array()
+        .is_some_and(|required| required.iter().any(|r| r == name))
+}
+
+fn examples(schema: &Value) -> Vec<&Value> {
+    schema["examples"]
+        .as_array()
+        .map(|examples| examples.iter().collect())
+        .unwrap_or_default()
+}
+
+/// Keep a description on one table row
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{ToolAnnotations, ToolError, ToolResult};
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    struct WeatherTool;
+
+    #[async_trait]
+    impl Tool for WeatherTool {
+        fn name(&self) -> &str {
+            "weather"
+        }
+
+        fn description(&self) -> &str {
+            "Current weather for a city"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({
+                "type": "object",
+                "properties": {
+                    "city": {"type": "string", "description": "City name"},
+                    "units": {"type": "string", "enum": ["metric", "imperial"]}
+                },
+                "required": ["city"],
+                "examples": [{"city": "Paris"}]
+            })
+        }
+
+        fn annotations(&self) -> ToolAnnotations {
+            ToolAnnotations::read_only()
+        }
+
+        async fn execute(
+            &self,
+            _parameters: Option<Value>,
+            _agent_context: Option<&crate::agent::AgentContext>,
+        ) -> Result<ToolResult, ToolError> {
+            Ok(ToolResult::success(json!("sunny")))
+        }
+    }
+
+    #[derive(Debug)]
+    struct LegacyWeatherTool;
+
+    #[async_trait]
+    impl Tool for LegacyWeatherTool {
+        fn name(&self) -> &str {
+            "weather"
+        }
+
+        fn description(&self) -> &str {
+            "Current weather for a location code"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object", "properties": {"code": {"type": "string"}}})
+        }
+
+        fn version(&self) -> &str {
+            "0.9.0"
+        }
+
+        fn deprecation(&self) -> Option<&str> {
+            Some("use 1.0.0 with city names")
+        }
+
+        async fn execute(
+            &self,
+            _parameters: Option<Value>,
+            _agent_context: Option<&crate::agent::AgentContext>,
+        ) -> Result<ToolResult, ToolError> {
+            Ok(ToolResult::success(json!("sunny")))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_docs_surface_versions_and_deprecations() {
+        let registry = ToolRegistry::new();
+        registry.register_tool(Box::new(WeatherTool)).await.unwrap();
+        registry
+            .register_tool(Box::new(LegacyWeatherTool))
+            .await
+            .unwrap();
+
+        let markdown = registry.generate_markdown_docs().await;
+        assert!(markdown
+            .contains("- **Other versions:** 0.9.0 (deprecated: use 1.0.0 with city names)\n"));
+
+        registry.pin_version("weather", "0.9.0").await.unwrap();
+        let markdown = registry.generate_markdown_docs().await;
+        assert!(markdown.contains("> **Deprecated:** use 1.0.0 with city names\n"));
+        assert!(markdown.contains("- **Version:** 0.9.0\n- **Other versions:** 1.0.0\n"));
+
+        let openapi = registry.generate_openapi().await;
+        let operation = &openapi["paths"]["/tools/weather"]["post"];
+        assert_eq!(operation["deprecated"], true);
+        assert_eq!(operation["x-tool-version"], "0.9.0");
+    }
+
+    #[tokio::test]
+    async fn test_generate_docs_for_registered_tools() {
+        let registry = ToolRegistry::new();
+        registry.register_tool(Box::new(WeatherTool)).await.unwrap();
+
+        let markdown = registry.generate_markdown_docs().await;
+        assert!(markdown.contains("## weather\n\nCurrent weather for a city\n"));
+        assert!(markdown.contains("- **Behavior:** read-only, idempotent, open world\n"));
+        assert!(markdown.contains("- **Version:** 1.0.0\n\n"));
+        assert!(markdown.contains("| `city` | string | yes | City name |"));
+        assert!(markdown.contains("| `units` | string | no |  |"));
+        assert!(markdown.contains("\"city\": \"Paris\""));
+
+        let openapi = registry.generate_openapi().await;
+        let operation = &openapi["paths"]["/tools/weather"]["post"];
+        assert_eq!(operation["operationId"], "weather");
+        assert_eq!(
+            operation["requestBody"]["content"]["application/json"]["example"],
+            json!({"city": "Paris"})
+        );
+        assert_eq!(operation["x-tool-annotations"]["readOnlyHint"], true);
+    }
+}
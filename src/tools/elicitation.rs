@@ -0,0 +1,242 @@
+//! Tools asking the user for missing input.
+//!
+//! A tool that cannot run without more information from the user returns
+//! [`ToolResult::needs_input`] with a prompt and a JSON schema of the answer.
+//! The tool call pauses while the request is passed to the application's
+//! [`Elicitor`], and the tool runs again with the answer merged into its
+//! parameters. Agents pass requests to
+//! [`CallbackHandler::on_elicitation`](crate::agent::callbacks::CallbackHandler::on_elicitation).
+//! If no handler answers, the model gets an error result with the prompt and
+//! can ask the user itself.
+//!
+//! ```no_run
+//! use serde_json::{json, Value};
+//! use stood::tools::ToolResult;
+//!
+//! fn book_flight(params: &Value) -> ToolResult {
+//!     let Some(date) = params.get("date").and_then(Value::as_str) else {
+//!         return ToolResult::needs_input(
+//!             "Which day do you want to fly?",
+//!             json!({
+//!                 "type": "object",
+//!                 "properties": {"date": {"type": "string", "format": "date"}},
+//!                 "required": ["date"]
+//!             }),
+//!         );
+//!     };
+//!     ToolResult::success(json!({"booked": date}))
+//! }
+//! ```
+//!
+//! MCP servers ask for input with `elicitation/create` requests, which
+//! [`MCPClient::with_elicitor`](crate::mcp::MCPClient::with_elicitor) passes to
+//! the same kind of [`Elicitor`].
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::ToolResult;
+use crate::agent::callbacks::CallbackHandler;
+use crate::agent::AgentContext;
+
+/// Times a tool call may ask for input before its request is returned to the model
+pub const MAX_ELICITATION_ROUNDS: usize = 3;
+
+/// Input a tool needs before it can run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputRequest {
+    /// Question shown to the user
+    pub prompt: String,
+    /// JSON schema of the answer, usually an object of the missing parameters
+    pub schema: Value,
+}
+
+/// A request for user input, from a tool or an MCP server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElicitationRequest {
+    /// Tool asking, if known
+    pub tool_name: Option<String>,
+    /// Id of the tool call asking, if known
+    pub tool_use_id: Option<String>,
+    /// MCP server asking, for `elicitation/create` requests
+    pub server: Option<String>,
+    /// Question shown to the user
+    pub prompt: String,
+    /// JSON schema of the answer
+    pub schema: Value,
+}
+
+/// The user's reply to an [`ElicitationRequest`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElicitationResponse {
+    /// The answer, matching the requested schema
+    Accept(Value),
+    /// The user chose not to answer
+    Decline,
+    /// The user dismissed the request
+    Cancel,
+}
+
+/// Asks the user for input on behalf of tools and MCP servers
+#[async_trait]
+pub trait Elicitor: Send + Sync + std::fmt::Debug {
+    async fn elicit(&self, request: ElicitationRequest) -> ElicitationResponse;
+}
+
+/// [`Elicitor`] passing requests to a callback handler
+#[derive(Clone)]
+pub struct CallbackElicitor(Arc<dyn CallbackHandler>);
+
+impl CallbackElicitor {
+    pub fn new(handler: Arc<dyn CallbackHandler>) -> Self {
+        Self(handler)
+    }
+}
+
+impl std::fmt::Debug for CallbackElicitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CallbackElicitor")
+    }
+}
+
+#[async_trait]
+impl Elicitor for CallbackElicitor {
+    async fn elicit(&self, request: ElicitationRequest) -> ElicitationResponse {
+        self.0.on_elicitation(&request).await
+    }
+}
+
+/// Answer the input request of `result`, if it has one
+///
+/// Returns `true` when the answer was merged into `parameters` and the tool
+/// should run again. Otherwise `result` is final: unchanged if it needs no
+/// input or there is no elicitor, an error if the user declined.
+pub(crate) async fn answer_input_request(
+    result: &mut ToolResult,
+    parameters: &mut Option<Value>,
+    agent_context: Option<&AgentContext>,
+    tool_name: &str,
+    tool_use_id: Option<&str>,
+) -> bool {
+    let (Some(request), Some(elicitor)) = (
+        &result.needs_input,
+        agent_context.and_then(|context| context.elicitor.as_ref()),
+    ) else {
+        return false;
+    };
+
+    let response = elicitor
+        .elicit(ElicitationRequest {
+            tool_name: Some(tool_name.to_string()),
+            tool_use_id: tool_use_id.map(str::to_string),
+            server: None,
+            prompt: request.prompt.clone(),
+            schema: request.schema.clone(),
+        })
+        .await;
+    match response {
+        ElicitationResponse::Accept(answer) => {
+            merge_answer(parameters, answer);
+            true
+        }
+        ElicitationResponse::Decline | ElicitationResponse::Cancel => {
+            tracing::debug!("User did not answer the input request of {}", tool_name);
+            *result = ToolResult::error(format!("The user declined to answer: {}", request.prompt));
+            false
+        }
+    }
+}
+
+/// Merge the fields of an object `answer` into the parameters, replacing them otherwise
+fn merge_answer(parameters: &mut Option<Value>, answer: Value) {
+    match (parameters.as_mut(), answer) {
+        (Some(Value::Object(params)), Value::Object(fields)) => params.extend(fields),
+        (_, answer) => *parameters = Some(answer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{Tool, ToolError, ToolRegistry};
+    use serde_json::json;
+
+    #[derive(Debug)]
+    struct BookFlight;
+
+    #[async_trait]
+    impl Tool for BookFlight {
+        fn name(&self) -> &str {
+            "book_flight"
+        }
+
+        fn description(&self) -> &str {
+            "Book a flight"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object", "properties": {"to": {"type": "string"}}})
+        }
+
+        async fn execute(
+            &self,
+            parameters: Option<Value>,
+            _agent_context: Option<&AgentContext>,
+        ) -> Result<ToolResult, ToolError> {
+            let params = parameters.unwrap_or_default();
+            Ok(match params.get("date") {
+                Some(date) => ToolResult::success(json!({"to": params["to"], "date": date})),
+                None => ToolResult::needs_input("Which day?", json!({"type": "object"})),
+            })
+        }
+    }
+
+    struct Answering(ElicitationResponse);
+
+    #[async_trait]
+    impl CallbackHandler for Answering {
+        async fn on_elicitation(&self, request: &ElicitationRequest) -> ElicitationResponse {
+            assert_eq!(request.tool_name.as_deref(), Some("book_flight"));
+            assert_eq!(request.prompt, "Which day?");
+            self.0.clone()
+        }
+    }
+
+    async fn book(answer: Option<ElicitationResponse>) -> ToolResult {
+        let registry = ToolRegistry::new();
+        registry.register_tool(Box::new(BookFlight)).await.unwrap();
+        let mut context = AgentContext::new("agent", None, "agent");
+        if let Some(answer) = answer {
+            context =
+                context.with_elicitor(Arc::new(CallbackElicitor::new(Arc::new(Answering(answer)))));
+        }
+        registry
+            .execute_tool("book_flight", Some(json!({"to": "LIS"})), Some(&context))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tool_resumes_with_answer() {
+        let answer = ElicitationResponse::Accept(json!({"date": "2026-11-02"}));
+        let result = book(Some(answer)).await;
+        assert!(result.success);
+        assert_eq!(result.content, json!({"to": "LIS", "date": "2026-11-02"}));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_request_reaches_model_as_error() {
+        let result = book(None).await;
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Which day?"));
+
+        let result = book(Some(ElicitationResponse::Decline)).await;
+        assert_eq!(
+            result.error.as_deref(),
+            Some("The user declined to answer: Which day?")
+        );
+    }
+}
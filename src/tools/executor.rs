@@ -188,7 +188,7 @@
 //! #     fn description(&self) -> &str { "Tool that fails" }
 //! #     fn parameters_schema(&self) -> serde_json::Value { json!({}) }
 //! #     async fn execute(&self, _: Option<serde_json::Value>) -> Result<stood::tools::ToolResult, stood::tools::ToolError> {
-//! #         Err(stood::tools::ToolError::ExecutionFailed { message: "Something went wrong".to_string() })
+//! #         Err(stood::tools::ToolError::execution_failed("Something went wrong"))
 //! #     }
 //! # }
 //! let tool: Arc<dyn Tool> = Arc::new(FailingTool);
@@ -290,14 +290,18 @@
 //! - **Metrics collection**: ~5µs per execution
 //! - **Memory usage**: O(1) per executor + O(n) for active executions
 
+use crate::agent::deadline::Deadline;
+use crate::context_manager::ToolOutputBudget;
 use crate::error::StoodError;
+use crate::llm::concurrency::AdaptiveConcurrency;
 use crate::parallel::{ParallelConfig, ParallelExecutor, TokioExecutor};
-use crate::tools::{Tool, ToolResult, ToolUse};
+use crate::runtime::timeout;
+use crate::tools::elicitation::{answer_input_request, MAX_ELICITATION_ROUNDS};
+use crate::tools::{Tool, ToolCancelReason, ToolResult, ToolUse};
 use serde_json::Value;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
-use tokio::time::timeout;
 use tracing::debug;
 
 /// Parallel execution strategy for tool execution
@@ -326,6 +330,15 @@ pub struct ExecutorConfig {
     pub capture_metrics: bool,
     /// Parallel execution strategy to use
     pub execution_strategy: ExecutionStrategy,
+    /// Size budget applied to tool results before they enter the conversation
+    /// (default: None, results are passed through unchanged)
+    pub output_budget: Option<ToolOutputBudget>,
+    /// Adaptive controller whose limit also caps parallel tool executions
+    /// (default: None, only `max_concurrent` applies)
+    ///
+    /// Use the controller installed on the model provider so tool parallelism
+    /// shrinks along with model requests when the provider throttles.
+    pub adaptive_concurrency: Option<Arc<AdaptiveConcurrency>>,
 }
 
 impl Default for ExecutorConfig {
@@ -338,6 +351,8 @@ impl Default for ExecutorConfig {
             validate_inputs: true,
             capture_metrics: true,
             execution_strategy: ExecutionStrategy::default(),
+            output_budget: None,
+            adaptive_concurrency: None,
         }
     }
 }
@@ -359,6 +374,8 @@ impl ExecutorConfig {
             validate_inputs: true,
             capture_metrics: true,
             execution_strategy: ExecutionStrategy::default(),
+            output_budget: None,
+            adaptive_concurrency: None,
         })
     }
 
@@ -371,9 +388,23 @@ impl ExecutorConfig {
             validate_inputs: true,
             capture_metrics: true,
             execution_strategy: ExecutionStrategy::Legacy,
+            output_budget: None,
+            adaptive_concurrency: None,
         }
     }
 
+    /// Set the size budget for tool results
+    pub fn with_output_budget(mut self, budget: ToolOutputBudget) -> Self {
+        self.output_budget = Some(budget);
+        self
+    }
+
+    /// Cap parallel tool executions with an adaptive concurrency controller
+    pub fn with_adaptive_concurrency(mut self, controller: Arc<AdaptiveConcurrency>) -> Self {
+        self.adaptive_concurrency = Some(controller);
+        self
+    }
+
     /// Check if this config uses parallel execution
     pub fn is_parallel(&self) -> bool {
         self.max_parallel_tools > 1
@@ -412,6 +443,8 @@ pub struct ToolExecutor {
     semaphore: Arc<Semaphore>,
     /// Parallel executor for new execution strategy
     parallel_executor: Option<Arc<TokioExecutor>>,
+    /// Aborts in-flight executions when triggered
+    cancellation_token: Option<tokio_util::sync::CancellationToken>,
 }
 
 impl ToolExecutor {
@@ -454,6 +487,7 @@ impl ToolExecutor {
             config,
             semaphore,
             parallel_executor,
+            cancellation_token: None,
         }
     }
 
@@ -471,8 +505,17 @@ impl ToolExecutor {
             config,
             semaphore,
             parallel_executor: Some(executor),
+            cancellation_token: None,
         }
     }
+
+    /// Abort in-flight tool executions when `token` is cancelled
+    ///
+    /// Aborted tools receive [`Tool::on_cancel`] with [`ToolCancelReason::Cancelled`].
+    pub fn with_cancellation_token(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
 }
 
 impl Default for ToolExecutor {
@@ -531,6 +574,12 @@ impl ToolExecutor {
             }
         };
 
+        // Follow the adaptive limit shared with model requests, if configured
+        let _task_permit = match &self.config.adaptive_concurrency {
+            Some(controller) => Some(controller.acquire_task().await),
+            None => None,
+        };
+
         // Validate input if configured
         if self.config.validate_inputs {
             if let Err(validation_error) = self.validate_tool_input(&tool, &tool_use.input) {
@@ -553,15 +602,55 @@ impl ToolExecutor {
             }
         }
 
-        // Execute the tool with timeout
-        crate::perf_checkpoint!("stood.tool.execute.invoke.start", &format!("tool={}", tool_use.name));
-        let execution_result = crate::perf_timed!("stood.tool.invoke", {
-            timeout(
-                self.config.execution_timeout,
-                tool.execute(Some(tool_use.input.clone()), agent_context),
-            )
-            .await
-        });
+        // Attribute progress reports to this call
+        let call_context = agent_context
+            .filter(|context| context.progress.is_some())
+            .map(|context| {
+                context
+                    .clone()
+                    .for_tool_call(&tool_use.name, &tool_use.tool_use_id)
+            });
+        let agent_context = call_context.as_ref().or(agent_context);
+
+        // Execute the tool with timeout, aborting early if the agent is cancelled
+        crate::perf_checkpoint!(
+            "stood.tool.execute.invoke.start",
+            &format!("tool={}", tool_use.name)
+        );
+        let mut params = Some(tool_use.input.clone());
+        let mut rounds = 0;
+        // Runs again with the user's answer while the tool asks for input; the
+        // timeout covers each run but not the wait for the answer
+        let execution_result = loop {
+            let mut execution_result = crate::perf_timed!("stood.tool.invoke", {
+                let limit = Deadline::cap_current(self.config.execution_timeout);
+                let invocation = timeout(limit, tool.execute(params.clone(), agent_context));
+                let timed_out = |_| ToolCancelReason::Timeout(limit);
+                match &self.cancellation_token {
+                    Some(token) => tokio::select! {
+                        result = invocation => result.map_err(timed_out),
+                        _ = token.cancelled() => Err(ToolCancelReason::Cancelled),
+                    },
+                    None => invocation.await.map_err(timed_out),
+                }
+            });
+            if let Ok(Ok(result)) = &mut execution_result {
+                if rounds < MAX_ELICITATION_ROUNDS
+                    && answer_input_request(
+                        result,
+                        &mut params,
+                        agent_context,
+                        &tool_use.name,
+                        Some(&tool_use.tool_use_id),
+                    )
+                    .await
+                {
+                    rounds += 1;
+                    continue;
+                }
+            }
+            break execution_result;
+        };
 
         let (result, success) = match execution_result {
             Ok(Ok(tool_result)) => {
@@ -584,16 +673,17 @@ impl ToolExecutor {
             }
             Ok(Err(tool_error)) => {
                 // Tool execution failed
-                let result = ToolResult::error(format!("Tool execution failed: {}", tool_error));
+                let result = ToolResult {
+                    source: tool_error.error_source().cloned(),
+                    ..ToolResult::error(format!("Tool execution failed: {}", tool_error))
+                };
                 crate::perf_checkpoint!("stood.tool.execute.failed", &format!("tool={}, error={}", tool_use.name, tool_error));
                 (result, false)
             }
-            Err(_) => {
-                // Timeout occurred
-                let result = ToolResult::error(format!(
-                    "Tool execution timed out after {} seconds",
-                    self.config.execution_timeout.as_secs()
-                ));
+            Err(reason) => {
+                // Timeout or cancellation dropped the execution, let the tool clean up
+                tool.on_cancel(reason).await;
+                let result = ToolResult::error(format!("Tool execution {}", reason));
                 crate::perf_checkpoint!("stood.tool.execute.timeout", &format!("tool={}, timeout_secs={}", tool_use.name, self.config.execution_timeout.as_secs()));
                 (result, false)
             }
@@ -710,7 +800,9 @@ impl ToolExecutor {
             executions.len()
         );
 
-        // Submit all tasks to the parallel executor
+        // Submit all tasks to the parallel executor; spawned tasks do not
+        // inherit the deadline, so each one is run under it
+        let deadline = Deadline::current();
         for (i, (tool, tool_use)) in executions.iter().enumerate() {
             let task_id = format!("tool_{}_{}", i, tool_use.name);
             let tool_clone = tool.clone();
@@ -723,7 +815,7 @@ impl ToolExecutor {
                 task_id, tool_use.name
             );
 
-            let future = async move {
+            let future = Deadline::scope_opt(deadline, async move {
                 debug!(
                     "PARALLEL_EXEC: Starting execution of task {}",
                     tool_use_clone.name
@@ -738,7 +830,7 @@ impl ToolExecutor {
                     tool_use_clone.name, duration
                 );
                 Ok((result, metrics))
-            };
+            });
 
             if let Err(e) = executor.submit_task(task_id, future).await {
                 tracing::error!(
@@ -770,6 +862,8 @@ impl ToolExecutor {
                                     "task_id": task_result.task_id
                                 }),
                                 error: Some(error.to_string()),
+                                source: None,
+                                needs_input: None,
                             };
                             results.push((error_result, None));
                         }
@@ -882,6 +976,7 @@ mod tests {
         should_error: bool,
         execution_delay: Duration,
         execution_count: Arc<AtomicU32>,
+        cancellations: Arc<std::sync::Mutex<Vec<ToolCancelReason>>>,
     }
 
     impl MockTool {
@@ -891,6 +986,7 @@ mod tests {
                 should_error: false,
                 execution_delay: Duration::from_millis(10),
                 execution_count: Arc::new(AtomicU32::new(0)),
+                cancellations: Arc::new(std::sync::Mutex::new(Vec::new())),
             }
         }
 
@@ -943,9 +1039,7 @@ mod tests {
             tokio::time::sleep(self.execution_delay).await;
 
             if self.should_error {
-                Err(crate::tools::ToolError::ExecutionFailed {
-                    message: "Mock tool error".to_string(),
-                })
+                Err(crate::tools::ToolError::execution_failed("Mock tool error"))
             } else {
                 let input = parameters.unwrap_or(json!({}));
                 let result = json!({
@@ -956,6 +1050,10 @@ mod tests {
                 Ok(crate::tools::ToolResult::success(result))
             }
         }
+
+        async fn on_cancel(&self, reason: ToolCancelReason) {
+            self.cancellations.lock().unwrap().push(reason);
+        }
     }
 
     #[tokio::test]
@@ -967,6 +1065,8 @@ mod tests {
             validate_inputs: true,
             capture_metrics: true,
             execution_strategy: ExecutionStrategy::Legacy,
+            output_budget: None,
+            adaptive_concurrency: None,
         };
 
         let executor = ToolExecutor::new(config.clone());
@@ -1054,6 +1154,43 @@ mod tests {
         assert!(!metrics.success);
     }
 
+    #[tokio::test]
+    async fn test_on_cancel_called_for_timeout_and_cancellation() {
+        let tool = Arc::new(MockTool::new("slow_tool").with_delay(Duration::from_secs(10)));
+        let cancellations = tool.cancellations.clone();
+        let tool_use = ToolUse {
+            tool_use_id: "cancel_id".to_string(),
+            name: "slow_tool".to_string(),
+            input: json!({"message": "never finishes"}),
+        };
+
+        let timeout = Duration::from_millis(50);
+        let executor = ToolExecutor::new(ExecutorConfig {
+            execution_timeout: timeout,
+            ..Default::default()
+        });
+        let (result, _) = executor.execute_tool(tool.clone(), &tool_use, None).await;
+        assert!(!result.success);
+
+        let token = tokio_util::sync::CancellationToken::new();
+        let executor = ToolExecutor::default().with_cancellation_token(token.clone());
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let (result, _) = executor.execute_tool(tool, &tool_use, None).await;
+        assert_eq!(result.error.as_deref(), Some("Tool execution cancelled"));
+
+        assert_eq!(
+            *cancellations.lock().unwrap(),
+            vec![
+                ToolCancelReason::Timeout(timeout),
+                ToolCancelReason::Cancelled
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_parallel_tool_execution() {
         let config = ExecutorConfig {
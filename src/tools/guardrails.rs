@@ -0,0 +1,429 @@
+//! Declarative constraints on tool parameters.
+//!
+//! JSON schemas describe the shape of tool parameters; [`ToolGuardrails`]
+//! restrict their values, such as keeping file paths inside a workspace or
+//! URLs on an allowlist of hosts. The [`ToolRegistry`](super::ToolRegistry)
+//! checks the guardrails before executing a tool. A call that violates one is
+//! not executed; the model gets an error result explaining which constraint
+//! failed so it can correct the call.
+//!
+//! Guardrails are written one per line as `tool.param <constraint>`, where
+//! `tool` may be `*` to match every tool. Lines starting with `#` are comments:
+//!
+//! ```
+//! use serde_json::json;
+//! use stood::tools::guardrails::ToolGuardrails;
+//!
+//! let guardrails = ToolGuardrails::parse(
+//!     r#"
+//!     *.path under /workspace
+//!     http_request.url host in example.com, *.example.org
+//!     sql_query.query not contains DROP, DELETE
+//!     sql_query.limit between 1 and 1000
+//!     "#,
+//! )
+//! .unwrap();
+//!
+//! assert!(guardrails.check("file_read", &json!({"path": "/workspace/a.txt"})).is_ok());
+//! let violation = guardrails
+//!     .check("sql_query", &json!({"query": "drop table users"}))
+//!     .unwrap_err();
+//! assert_eq!(
+//!     violation.to_string(),
+//!     "Parameter 'query' of tool 'sql_query' must not contain 'DROP' (got 'drop table users')"
+//! );
+//! ```
+//!
+//! | Constraint | Applies to |
+//! |---|---|
+//! | `under <dir>` | Paths, normalized without touching the filesystem |
+//! | `host in <host>, ...` | URLs; `*.domain` matches subdomains |
+//! | `not contains <text>, ...` | Strings, ignoring case |
+//! | `matches <regex>` | Strings, anywhere in the value unless anchored |
+//! | `one of <value>, ...` | Strings, numbers and booleans |
+//! | `max length <n>` | Strings (characters) and arrays (items) |
+//! | `between <min> and <max>` | Numbers |
+//!
+//! Nested parameters are addressed with dots (`request.url`). A parameter that
+//! is absent is not checked; mark it required in the schema instead.
+
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+use regex::Regex;
+use serde_json::Value;
+
+/// Constraint on the value of one parameter
+#[derive(Debug, Clone)]
+pub enum ParamConstraint {
+    /// Path inside a directory
+    PathUnder(PathBuf),
+    /// URL whose host is in the list (`*.domain` matches subdomains)
+    HostIn(Vec<String>),
+    /// String containing none of the substrings, ignoring case
+    NotContains(Vec<String>),
+    /// String matching the regex
+    Matches(Regex),
+    /// Value equal to one of the listed values
+    OneOf(Vec<String>),
+    /// String of at most this many characters, or array of at most this many items
+    MaxLength(usize),
+    /// Number in the inclusive range
+    Between(f64, f64),
+}
+
+impl ParamConstraint {
+    /// Check `value`, returning what it must satisfy if it does not
+    fn check(&self, value: &Value) -> Result<(), String> {
+        let ok = match self {
+            ParamConstraint::PathUnder(root) => {
+                let path = value.as_str().ok_or("must be a path")?;
+                normalize(Path::new(path)).starts_with(normalize(root))
+            }
+            ParamConstraint::HostIn(hosts) => {
+                let text = value.as_str().ok_or("must be a URL")?;
+                let url = url::Url::parse(text).map_err(|_| "must be a valid URL")?;
+                let host = url.host_str().unwrap_or("").to_ascii_lowercase();
+                hosts.iter().any(|allowed| host_matches(&host, allowed))
+            }
+            ParamConstraint::NotContains(needles) => {
+                let text = value.as_str().ok_or("must be a string")?.to_lowercase();
+                if let Some(needle) = needles
+                    .iter()
+                    .find(|needle| text.contains(&needle.to_lowercase()))
+                {
+                    return Err(format!("must not contain '{}'", needle));
+                }
+                true
+            }
+            ParamConstraint::Matches(regex) => {
+                regex.is_match(value.as_str().ok_or("must be a string")?)
+            }
+            ParamConstraint::OneOf(allowed) => {
+                let text = match value {
+                    Value::String(s) => s.clone(),
+                    Value::Number(_) | Value::Bool(_) => value.to_string(),
+                    _ => return Err(self.to_string()),
+                };
+                allowed.contains(&text)
+            }
+            ParamConstraint::MaxLength(max) => match value {
+                Value::String(s) => s.chars().count() <= *max,
+                Value::Array(items) => items.len() <= *max,
+                _ => return Err("must be a string or an array".to_string()),
+            },
+            ParamConstraint::Between(min, max) => {
+                let n = value.as_f64().ok_or("must be a number")?;
+                (*min..=*max).contains(&n)
+            }
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(self.to_string())
+        }
+    }
+}
+
+impl fmt::Display for ParamConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamConstraint::PathUnder(root) => write!(f, "must be under {}", root.display()),
+            ParamConstraint::HostIn(hosts) => {
+                write!(f, "must be a URL with host in: {}", hosts.join(", "))
+            }
+            ParamConstraint::NotContains(needles) => {
+                write!(f, "must not contain any of: {}", needles.join(", "))
+            }
+            ParamConstraint::Matches(regex) => write!(f, "must match /{}/", regex.as_str()),
+            ParamConstraint::OneOf(allowed) => write!(f, "must be one of: {}", allowed.join(", ")),
+            ParamConstraint::MaxLength(max) => write!(f, "must have a length of at most {}", max),
+            ParamConstraint::Between(min, max) => write!(f, "must be between {} and {}", min, max),
+        }
+    }
+}
+
+/// Resolve `.` and `..` and make `path` absolute, without following symlinks
+fn normalize(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn host_matches(host: &str, allowed: &str) -> bool {
+    let allowed = allowed.to_ascii_lowercase();
+    match allowed.strip_prefix("*.") {
+        Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+        None => host == allowed,
+    }
+}
+
+/// A constraint on one parameter of one tool (or of every tool)
+#[derive(Debug, Clone)]
+pub struct Guardrail {
+    /// Tool name, or `*` for every tool
+    pub tool: String,
+    /// Parameter name; dots address nested parameters
+    pub param: String,
+    /// What the value must satisfy
+    pub constraint: ParamConstraint,
+}
+
+/// A tool call that violates a guardrail
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardrailViolation {
+    /// Called tool
+    pub tool: String,
+    /// Parameter that violates the guardrail
+    pub param: String,
+    /// What the parameter must satisfy
+    pub requirement: String,
+    /// The rejected value
+    pub value: Value,
+}
+
+impl fmt::Display for GuardrailViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match &self.value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        write!(
+            f,
+            "Parameter '{}' of tool '{}' {} (got '{}')",
+            self.param, self.tool, self.requirement, value
+        )
+    }
+}
+
+impl std::error::Error for GuardrailViolation {}
+
+/// A line of guardrail text that could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid guardrail on line {line}: {message}")]
+pub struct GuardrailParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// What is wrong with the line
+    pub message: String,
+}
+
+/// Constraints on tool parameters, checked before tools run
+#[derive(Debug, Clone, Default)]
+pub struct ToolGuardrails {
+    rules: Vec<Guardrail>,
+}
+
+impl ToolGuardrails {
+    /// No guardrails
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constrain `param` of `tool` (`*` for every tool)
+    pub fn constrain(
+        mut self,
+        tool: impl Into<String>,
+        param: impl Into<String>,
+        constraint: ParamConstraint,
+    ) -> Self {
+        self.rules.push(Guardrail {
+            tool: tool.into(),
+            param: param.into(),
+            constraint,
+        });
+        self
+    }
+
+    /// Parse guardrails written one per line as `tool.param <constraint>`
+    ///
+    /// Blank lines and lines starting with `#` are ignored. See the
+    /// [module documentation](self) for the constraints.
+    pub fn parse(text: &str) -> Result<Self, GuardrailParseError> {
+        let mut guardrails = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let rule = parse_rule(line).map_err(|message| GuardrailParseError {
+                line: index + 1,
+                message,
+            })?;
+            guardrails.rules.push(rule);
+        }
+        Ok(guardrails)
+    }
+
+    /// Add the guardrails of `other`
+    pub fn extend(&mut self, other: ToolGuardrails) {
+        self.rules.extend(other.rules);
+    }
+
+    /// All guardrails, in the order they are checked
+    pub fn rules(&self) -> &[Guardrail] {
+        &self.rules
+    }
+
+    /// Whether there are no guardrails
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Check the parameters of a call to `tool` against every matching guardrail
+    pub fn check(&self, tool: &str, params: &Value) -> Result<(), Box<GuardrailViolation>> {
+        for rule in &self.rules {
+            if rule.tool != "*" && rule.tool != tool {
+                continue;
+            }
+            let Some(value) = rule
+                .param
+                .split('.')
+                .try_fold(params, |value, key| value.get(key))
+            else {
+                continue;
+            };
+            if let Err(requirement) = rule.constraint.check(value) {
+                return Err(Box::new(GuardrailViolation {
+                    tool: tool.to_string(),
+                    param: rule.param.clone(),
+                    requirement,
+                    value: value.clone(),
+                }));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_rule(line: &str) -> Result<Guardrail, String> {
+    let (target, constraint) = line
+        .split_once(char::is_whitespace)
+        .ok_or("expected `tool.param <constraint>`")?;
+    let (tool, param) = target
+        .split_once('.')
+        .ok_or_else(|| format!("expected `tool.param`, got '{}'", target))?;
+    if tool.is_empty() || param.is_empty() {
+        return Err(format!("expected `tool.param`, got '{}'", target));
+    }
+    Ok(Guardrail {
+        tool: tool.to_string(),
+        param: param.to_string(),
+        constraint: parse_constraint(constraint.trim())?,
+    })
+}
+
+fn parse_constraint(text: &str) -> Result<ParamConstraint, String> {
+    let list = |rest: &str| -> Result<Vec<String>, String> {
+        let items: Vec<String> = rest
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect();
+        if items.is_empty() {
+            return Err(format!("'{}' needs at least one value", text));
+        }
+        Ok(items)
+    };
+
+    if let Some(dir) = text.strip_prefix("under ") {
+        Ok(ParamConstraint::PathUnder(PathBuf::from(dir.trim())))
+    } else if let Some(rest) = text.strip_prefix("host in ") {
+        Ok(ParamConstraint::HostIn(list(rest)?))
+    } else if let Some(rest) = text.strip_prefix("not contains ") {
+        Ok(ParamConstraint::NotContains(list(rest)?))
+    } else if let Some(pattern) = text.strip_prefix("matches ") {
+        Regex::new(pattern.trim())
+            .map(ParamConstraint::Matches)
+            .map_err(|e| format!("invalid regex: {}", e))
+    } else if let Some(rest) = text.strip_prefix("one of ") {
+        Ok(ParamConstraint::OneOf(list(rest)?))
+    } else if let Some(max) = text.strip_prefix("max length ") {
+        max.trim()
+            .parse()
+            .map(ParamConstraint::MaxLength)
+            .map_err(|_| format!("invalid length '{}'", max.trim()))
+    } else if let Some(range) = text.strip_prefix("between ") {
+        let (min, max) = range
+            .split_once(" and ")
+            .ok_or("expected `between <min> and <max>`")?;
+        let number = |s: &str| {
+            s.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number '{}'", s.trim()))
+        };
+        Ok(ParamConstraint::Between(number(min)?, number(max)?))
+    } else {
+        Err(format!("unknown constraint '{}'", text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_check_constraints() {
+        let guardrails = ToolGuardrails::parse(
+            "*.path under /workspace\n\
+             fetch.request.url host in example.com, *.example.org\n\
+             run.mode one of fast, 1, true\n\
+             run.args max length 2\n\
+             run.name matches ^[a-z]+$",
+        )
+        .unwrap();
+
+        assert!(guardrails
+            .check("read", &json!({"path": "/workspace/src/../a.txt"}))
+            .is_ok());
+        let violation = guardrails
+            .check("read", &json!({"path": "/workspace/../etc/passwd"}))
+            .unwrap_err();
+        assert_eq!(violation.requirement, "must be under /workspace");
+
+        assert!(guardrails
+            .check(
+                "fetch",
+                &json!({"request": {"url": "https://api.example.org/x"}})
+            )
+            .is_ok());
+        assert!(guardrails
+            .check(
+                "fetch",
+                &json!({"request": {"url": "https://example.org.evil.com"}})
+            )
+            .is_err());
+        // Absent parameters are not checked
+        assert!(guardrails.check("fetch", &json!({})).is_ok());
+
+        assert!(guardrails.check("run", &json!({"mode": 1})).is_ok());
+        assert!(guardrails.check("run", &json!({"mode": "slow"})).is_err());
+        assert!(guardrails
+            .check("run", &json!({"args": [1, 2, 3]}))
+            .is_err());
+        assert!(guardrails.check("run", &json!({"name": "Bad"})).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = ToolGuardrails::parse("# comment\n\nsql.query sounds safe").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.message, "unknown constraint 'sounds safe'");
+        assert!(ToolGuardrails::parse("sql under /tmp").is_err());
+        assert!(ToolGuardrails::parse("sql.limit between 1 and x").is_err());
+    }
+}
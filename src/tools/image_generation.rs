@@ -0,0 +1,515 @@
+//! Image generation with Amazon Bedrock.
+//!
+//! [`ImageGenerationTool`] (`generate_image`) turns a text prompt into PNG
+//! images with Amazon Titan Image Generator or Amazon Nova Canvas. The images
+//! are not sent back to the model; they are added as [`Artifact`]s to the
+//! execution and returned in [`AgentResult::artifacts`], while the model gets
+//! their names, sizes and locations.
+//!
+//! Where the images go is set by [`ImageOutput`]:
+//!
+//! - [`ImageOutput::Base64`] (default) - the image bytes are kept in the
+//!   artifact, which serializes them as base64
+//! - `ImageOutput::S3` (requires the `s3` feature) - the images are uploaded
+//!   through an [`S3Connection`](super::s3::S3Connection) and the artifacts
+//!   refer to their `s3://` URIs
+//!
+//! # Quick Start
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! use stood::tools::image_generation::{ImageGenerationConfig, ImageGenerationTool, ImageModel};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let images = ImageGenerationTool::connect(
+//!     ImageGenerationConfig::new()
+//!         .with_model(ImageModel::NovaCanvas)
+//!         .with_max_images(2),
+//! )
+//! .await?;
+//!
+//! let mut agent = Agent::builder().tool(Box::new(images)).build().await?;
+//! let result = agent.execute("Draw a logo for a bakery called Crumb").await?;
+//! for artifact in &result.artifacts {
+//!     println!("{} ({} bytes)", artifact.name, artifact.size());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Credentials come from the standard AWS credential chain, the same one used
+//! by the Bedrock provider.
+//!
+//! [`AgentResult::artifacts`]: crate::agent::AgentResult::artifacts
+
+use crate::agent::artifacts::Artifact;
+use crate::llm::http::aws_config_loader;
+use crate::llm::traits::LlmError;
+use crate::tools::{Tool, ToolError, ToolResult};
+use aws_sdk_bedrockruntime::primitives::Blob;
+use aws_sdk_bedrockruntime::Client;
+use base64::Engine;
+use std::sync::Arc;
+
+/// Bedrock model used to generate images
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageModel {
+    /// Amazon Titan Image Generator v2
+    TitanImageV2,
+    /// Amazon Nova Canvas
+    NovaCanvas,
+    /// Another model ID accepting the Titan request format
+    Custom(String),
+}
+
+impl ImageModel {
+    pub fn model_id(&self) -> &str {
+        match self {
+            ImageModel::TitanImageV2 => "amazon.titan-image-generator-v2:0",
+            ImageModel::NovaCanvas => "amazon.nova-canvas-v1:0",
+            ImageModel::Custom(model_id) => model_id,
+        }
+    }
+}
+
+/// Where generated images are stored
+#[derive(Debug, Clone, Default)]
+pub enum ImageOutput {
+    /// Keep the image bytes in the artifacts (serialized as base64)
+    #[default]
+    Base64,
+    /// Upload the images to S3 and return their URIs
+    #[cfg(feature = "s3")]
+    S3 {
+        connection: super::s3::S3Connection,
+        bucket: String,
+        /// Key prefix of the uploaded images, e.g. `generated/`
+        prefix: String,
+    },
+}
+
+/// Configuration for an [`ImageGenerationTool`]
+#[derive(Debug, Clone)]
+pub struct ImageGenerationConfig {
+    /// Model generating the images (default: Titan Image Generator v2)
+    pub model: ImageModel,
+    /// Where the images are stored (default: base64 artifacts)
+    pub output: ImageOutput,
+    /// Region override; defaults to the region from the AWS configuration
+    pub region: Option<String>,
+    /// Maximum images per call (default: 4)
+    pub max_images: u32,
+    /// Width used when the model does not pass one (default: 1024)
+    pub default_width: u32,
+    /// Height used when the model does not pass one (default: 1024)
+    pub default_height: u32,
+    /// `standard` or `premium` (default: `standard`)
+    pub quality: String,
+    /// How closely images follow the prompt, 1.1 to 10 (default: 8)
+    pub cfg_scale: f32,
+}
+
+impl Default for ImageGenerationConfig {
+    fn default() -> Self {
+        Self {
+            model: ImageModel::TitanImageV2,
+            output: ImageOutput::Base64,
+            region: None,
+            max_images: 4,
+            default_width: 1024,
+            default_height: 1024,
+            quality: "standard".to_string(),
+            cfg_scale: 8.0,
+        }
+    }
+}
+
+impl ImageGenerationConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_model(mut self, model: ImageModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn with_output(mut self, output: ImageOutput) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Upload images to `bucket` under `prefix` (requires writes to be allowed
+    /// on `connection`)
+    #[cfg(feature = "s3")]
+    pub fn with_s3_output(
+        self,
+        connection: super::s3::S3Connection,
+        bucket: impl Into<String>,
+        prefix: impl Into<String>,
+    ) -> Self {
+        self.with_output(ImageOutput::S3 {
+            connection,
+            bucket: bucket.into(),
+            prefix: prefix.into(),
+        })
+    }
+
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    pub fn with_max_images(mut self, max_images: u32) -> Self {
+        self.max_images = max_images.max(1);
+        self
+    }
+
+    pub fn with_default_size(mut self, width: u32, height: u32) -> Self {
+        self.default_width = width;
+        self.default_height = height;
+        self
+    }
+
+    pub fn with_quality(mut self, quality: impl Into<String>) -> Self {
+        self.quality = quality.into();
+        self
+    }
+
+    pub fn with_cfg_scale(mut self, cfg_scale: f32) -> Self {
+        self.cfg_scale = cfg_scale;
+        self
+    }
+}
+
+/// Parameters of one image generation request
+#[derive(Debug, Clone, PartialEq)]
+struct ImageRequest {
+    prompt: String,
+    negative_prompt: Option<String>,
+    width: u32,
+    height: u32,
+    number_of_images: u32,
+    seed: Option<u64>,
+}
+
+impl ImageRequest {
+    fn from_params(
+        params: &serde_json::Value,
+        config: &ImageGenerationConfig,
+    ) -> Result<Self, ToolError> {
+        let prompt = params
+            .get("prompt")
+            .and_then(|v| v.as_str())
+            .filter(|prompt| !prompt.trim().is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters {
+                message: "Missing required parameter: prompt".to_string(),
+            })?;
+        let dimension = |name: &str, default: u32| {
+            params
+                .get(name)
+                .and_then(|v| v.as_u64())
+                .map_or(default, |v| v as u32)
+        };
+        Ok(Self {
+            prompt: prompt.to_string(),
+            negative_prompt: params
+                .get("negative_prompt")
+                .and_then(|v| v.as_str())
+                .filter(|text| !text.trim().is_empty())
+                .map(str::to_string),
+            width: dimension("width", config.default_width),
+            height: dimension("height", config.default_height),
+            number_of_images: dimension("number_of_images", 1).clamp(1, config.max_images),
+            seed: params.get("seed").and_then(|v| v.as_u64()),
+        })
+    }
+
+    /// `TEXT_IMAGE` request body shared by Titan Image Generator and Nova Canvas
+    fn body(&self, config: &ImageGenerationConfig) -> serde_json::Value {
+        let mut text_params = serde_json::json!({ "text": self.prompt });
+        if let Some(negative) = &self.negative_prompt {
+            text_params["negativeText"] = serde_json::json!(negative);
+        }
+        let mut generation = serde_json::json!({
+            "numberOfImages": self.number_of_images,
+            "width": self.width,
+            "height": self.height,
+            "quality": config.quality,
+            "cfgScale": config.cfg_scale
+        });
+        if let Some(seed) = self.seed {
+            generation["seed"] = serde_json::json!(seed);
+        }
+        serde_json::json!({
+            "taskType": "TEXT_IMAGE",
+            "textToImageParams": text_params,
+            "imageGenerationConfig": generation
+        })
+    }
+}
+
+/// Decode the images of a Titan / Nova Canvas response
+fn parse_images(body: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let response: serde_json::Value =
+        serde_json::from_slice(body).map_err(|e| format!("Invalid response: {}", e))?;
+    if let Some(error) = response.get("error").and_then(|v| v.as_str()) {
+        return Err(error.to_string());
+    }
+    let images = response
+        .get("images")
+        .and_then(|v| v.as_array())
+        .filter(|images| !images.is_empty())
+        .ok_or("The response contains no images")?;
+    images
+        .iter()
+        .map(|image| {
+            let encoded = image.as_str().ok_or("Image is not a base64 string")?;
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| format!("Invalid image data: {}", e))
+        })
+        .collect()
+}
+
+/// Generates images from text prompts with Amazon Bedrock
+#[derive(Debug, Clone)]
+pub struct ImageGenerationTool {
+    client: Client,
+    config: Arc<ImageGenerationConfig>,
+}
+
+impl ImageGenerationTool {
+    /// Create a Bedrock client using the default AWS credential chain
+    ///
+    /// Connects through the installed [proxy](crate::proxy), which must not be
+    /// a SOCKS proxy.
+    pub async fn connect(config: ImageGenerationConfig) -> Result<Self, LlmError> {
+        let mut loader = aws_config_loader()?;
+        if let Some(region) = &config.region {
+            loader = loader.region(aws_config::Region::new(region.clone()));
+        }
+        let sdk_config = loader.load().await;
+        Ok(Self::from_client(Client::new(&sdk_config), config))
+    }
+
+    /// Use an existing Bedrock runtime client
+    pub fn from_client(client: Client, config: ImageGenerationConfig) -> Self {
+        Self {
+            client,
+            config: Arc::new(config),
+        }
+    }
+
+    pub fn config(&self) -> &ImageGenerationConfig {
+        &self.config
+    }
+
+    async fn generate(&self, request: &ImageRequest) -> Result<Vec<Vec<u8>>, String> {
+        let body = serde_json::to_vec(&request.body(&self.config)).map_err(|e| e.to_string())?;
+        let output = self
+            .client
+            .invoke_model()
+            .model_id(self.config.model.model_id())
+            .content_type("application/json")
+            .accept("application/json")
+            .body(Blob::new(body))
+            .send()
+            .await
+            .map_err(|e| aws_sdk_bedrockruntime::error::DisplayErrorContext(e).to_string())?;
+        parse_images(output.body().as_ref())
+    }
+
+    /// Store `image` as configured, returning its artifact and its entry in
+    /// the tool result
+    async fn store(
+        &self,
+        name: String,
+        image: Vec<u8>,
+    ) -> Result<(Artifact, serde_json::Value), String> {
+        let size = image.len();
+        match &self.config.output {
+            ImageOutput::Base64 => Ok((
+                Artifact::data(name.clone(), "image/png", image),
+                serde_json::json!({ "name": name, "size": size }),
+            )),
+            #[cfg(feature = "s3")]
+            ImageOutput::S3 {
+                connection,
+                bucket,
+                prefix,
+            } => {
+                let key = format!("{}{}", prefix, name);
+                connection.upload(bucket, &key, image, "image/png").await?;
+                let uri = format!("s3://{}/{}", bucket, key);
+                Ok((
+                    Artifact::uri(name.clone(), "image/png", uri.clone()),
+                    serde_json::json!({ "name": name, "size": size, "uri": uri }),
+                ))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for ImageGenerationTool {
+    fn name(&self) -> &str {
+        "generate_image"
+    }
+
+    fn description(&self) -> &str {
+        "Generate PNG images from a text description. The images are delivered \
+         to the user; describe the subject, style, composition and colors"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "prompt": {
+                    "type": "string",
+                    "description": "Description of the image (at most 1024 characters)"
+                },
+                "negative_prompt": {
+                    "type": "string",
+                    "description": "What the image must not contain"
+                },
+                "width": {
+                    "type": "integer",
+                    "description": format!(
+                        "Width in pixels, a multiple of 64 (default: {})",
+                        self.config.default_width
+                    )
+                },
+                "height": {
+                    "type": "integer",
+                    "description": format!(
+                        "Height in pixels, a multiple of 64 (default: {})",
+                        self.config.default_height
+                    )
+                },
+                "number_of_images": {
+                    "type": "integer",
+                    "description": format!(
+                        "Number of variations to generate (1 to {})",
+                        self.config.max_images
+                    )
+                },
+                "seed": {
+                    "type": "integer",
+                    "description": "Seed to reproduce a previous image"
+                }
+            },
+            "required": ["prompt"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let request = ImageRequest::from_params(&params, &self.config)?;
+
+        let images = match self.generate(&request).await {
+            Ok(images) => images,
+            Err(e) => return Ok(ToolResult::error(format!("Image generation failed: {}", e))),
+        };
+
+        let batch = uuid::Uuid::new_v4().simple().to_string();
+        let mut entries = Vec::with_capacity(images.len());
+        for (index, image) in images.into_iter().enumerate() {
+            let name = format!("image-{}-{}.png", &batch[..8], index + 1);
+            let (artifact, mut entry) = match self.store(name, image).await {
+                Ok(stored) => stored,
+                Err(e) => return Ok(ToolResult::error(format!("Storing image failed: {}", e))),
+            };
+            match agent_context {
+                Some(ctx) => ctx.artifacts.push(artifact.with_tool(self.name())),
+                // Without an execution to collect artifacts, return the images inline
+                None => {
+                    if let Ok(bytes) = artifact.bytes() {
+                        entry["base64"] = serde_json::json!(
+                            base64::engine::general_purpose::STANDARD.encode(bytes)
+                        );
+                    }
+                }
+            }
+            entries.push(entry);
+        }
+
+        Ok(ToolResult::success(serde_json::json!({
+            "model": self.config.model.model_id(),
+            "width": request.width,
+            "height": request.height,
+            "seed": request.seed,
+            "count": entries.len(),
+            "images": entries
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offline_tool(config: ImageGenerationConfig) -> ImageGenerationTool {
+        let sdk_config = aws_sdk_bedrockruntime::Config::builder()
+            .behavior_version(aws_sdk_bedrockruntime::config::BehaviorVersion::latest())
+            .region(aws_sdk_bedrockruntime::config::Region::new("us-east-1"))
+            .build();
+        ImageGenerationTool::from_client(Client::from_conf(sdk_config), config)
+    }
+
+    #[test]
+    fn test_request_body() {
+        let config = ImageGenerationConfig::new().with_max_images(2);
+        let request = ImageRequest::from_params(
+            &serde_json::json!({
+                "prompt": "A lighthouse at dusk",
+                "negative_prompt": "people",
+                "width": 512,
+                "number_of_images": 5,
+                "seed": 42
+            }),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(request.number_of_images, 2);
+
+        let body = request.body(&config);
+        assert_eq!(body["taskType"], "TEXT_IMAGE");
+        assert_eq!(body["textToImageParams"]["negativeText"], "people");
+        let generation = &body["imageGenerationConfig"];
+        assert_eq!(
+            (&generation["width"], &generation["height"]),
+            (&serde_json::json!(512), &serde_json::json!(1024))
+        );
+        assert_eq!(generation["seed"], 42);
+        assert_eq!(generation["numberOfImages"], 2);
+    }
+
+    #[test]
+    fn test_parse_images() {
+        let body = serde_json::json!({"images": ["iVBORw==", "AAEC"], "error": null});
+        let images = parse_images(body.to_string().as_bytes()).unwrap();
+        assert_eq!(images[1], vec![0, 1, 2]);
+
+        let error = serde_json::json!({"images": [], "error": "Blocked by content filters"});
+        assert_eq!(
+            parse_images(error.to_string().as_bytes()).unwrap_err(),
+            "Blocked by content filters"
+        );
+        assert!(parse_images(b"{\"images\": []}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_missing_prompt_is_rejected() {
+        let tool = offline_tool(ImageGenerationConfig::new());
+        let result = tool
+            .execute(Some(serde_json::json!({"prompt": "  "})), None)
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters { .. })));
+    }
+}
@@ -0,0 +1,205 @@
+//! Interim results for slow tools.
+//!
+//! By default the event loop waits for every tool of a batch before the next
+//! model call. With [`InterimResultConfig`] set on
+//! [`EventLoopConfig::interim_tool_results`](crate::agent::EventLoopConfig::interim_tool_results),
+//! tools still running when the deadline passes are answered with an interim
+//! result ("tool still running, partial output: …") and keep running in the
+//! background. Their final result, or their latest partial output if they are
+//! still running, is added to the next tool result message the model sees.
+//! Background tools that have not finished when the execution ends are
+//! cancelled.
+//!
+//! Tools report partial output through
+//! [`AgentContext::report_partial_output`](crate::agent::AgentContext::report_partial_output):
+//!
+//! ```no_run
+//! use stood::agent::AgentContext;
+//! use stood::tools::{Tool, ToolError, ToolResult};
+//! use serde_json::{json, Value};
+//!
+//! #[derive(Debug)]
+//! struct Crawler;
+//!
+//! #[async_trait::async_trait]
+//! impl Tool for Crawler {
+//!     fn name(&self) -> &str { "crawl" }
+//!     fn description(&self) -> &str { "Crawl a site" }
+//!     fn parameters_schema(&self) -> Value { json!({"type": "object"}) }
+//!
+//!     async fn execute(
+//!         &self,
+//!         _parameters: Option<Value>,
+//!         agent_context: Option<&AgentContext>,
+//!     ) -> Result<ToolResult, ToolError> {
+//!         let mut pages = Vec::new();
+//!         for page in ["/", "/about", "/pricing"] {
+//!             pages.push(page);
+//!             if let Some(ctx) = agent_context {
+//!                 ctx.report_partial_output(format!("crawled {}\n", page));
+//!             }
+//!         }
+//!         Ok(ToolResult::success(json!({ "pages": pages })))
+//!     }
+//! }
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// When tools get interim results instead of blocking the model call
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterimResultConfig {
+    /// How long a tool batch may run before unfinished tools get interim results
+    pub deadline: Duration,
+    /// Maximum characters of partial output shown to the model; the most
+    /// recent output is kept
+    pub max_partial_chars: usize,
+}
+
+impl Default for InterimResultConfig {
+    fn default() -> Self {
+        Self {
+            deadline: Duration::from_secs(30),
+            max_partial_chars: 2_000,
+        }
+    }
+}
+
+impl InterimResultConfig {
+    pub fn new(deadline: Duration) -> Self {
+        Self {
+            deadline,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_partial_chars(mut self, max_partial_chars: usize) -> Self {
+        self.max_partial_chars = max_partial_chars;
+        self
+    }
+
+    /// Output of a tool that is still running after `elapsed`
+    pub(crate) fn interim_output(
+        &self,
+        tool_name: &str,
+        elapsed: Duration,
+        partial: &PartialOutput,
+    ) -> Value {
+        json!({
+            "status": "running",
+            "message": format!(
+                "Tool '{}' is still running after {}s. Its result will be provided in a later message.",
+                tool_name,
+                elapsed.as_secs()
+            ),
+            "partial_output": partial.tail(self.max_partial_chars),
+        })
+    }
+
+    /// Update on a background tool that is still running, for the next tool result message
+    pub(crate) fn running_update(
+        &self,
+        tool_name: &str,
+        tool_use_id: &str,
+        elapsed: Duration,
+        partial: &PartialOutput,
+    ) -> String {
+        let output = partial.tail(self.max_partial_chars);
+        format!(
+            "Tool '{}' ({}) is still running after {}s, partial output: {}",
+            tool_name,
+            tool_use_id,
+            elapsed.as_secs(),
+            if output.is_empty() {
+                "(none yet)"
+            } else {
+                &output
+            }
+        )
+    }
+}
+
+/// Final result of a background tool, for the next tool result message
+pub(crate) fn finished_update(
+    tool_name: &str,
+    tool_use_id: &str,
+    output: &Value,
+    success: bool,
+) -> String {
+    format!(
+        "Tool '{}' ({}) that was still running has {}: {}",
+        tool_name,
+        tool_use_id,
+        if success { "finished" } else { "failed" },
+        output
+    )
+}
+
+/// Output a tool has produced so far
+///
+/// Clones share the same buffer.
+#[derive(Debug, Clone, Default)]
+pub struct PartialOutput {
+    buffer: Arc<Mutex<String>>,
+}
+
+impl PartialOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append to the output
+    pub fn push(&self, output: &str) {
+        self.lock().push_str(output);
+    }
+
+    /// Replace the output, e.g. with a progress summary
+    pub fn set(&self, output: impl Into<String>) {
+        *self.lock() = output.into();
+    }
+
+    /// The output so far
+    pub fn snapshot(&self) -> String {
+        self.lock().clone()
+    }
+
+    /// The last `max_chars` characters of the output
+    pub fn tail(&self, max_chars: usize) -> String {
+        let buffer = self.lock();
+        let len = buffer.chars().count();
+        if len <= max_chars {
+            return buffer.clone();
+        }
+        let tail: String = buffer.chars().skip(len - max_chars).collect();
+        format!("…{}", tail)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, String> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interim_output_shows_latest_partial_output() {
+        let config = InterimResultConfig::new(Duration::from_secs(5)).with_max_partial_chars(9);
+        let partial = PartialOutput::new();
+        let shared = partial.clone();
+        shared.push("page 1 ok\n");
+        shared.push("page 2 ok");
+
+        let output = config.interim_output("crawl", Duration::from_secs(5), &partial);
+        assert_eq!(output["status"], "running");
+        assert_eq!(output["partial_output"], "…page 2 ok");
+        assert!(output["message"].as_str().unwrap().contains("after 5s"));
+
+        partial.set("done");
+        assert_eq!(shared.snapshot(), "done");
+    }
+}
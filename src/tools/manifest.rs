@@ -0,0 +1,480 @@
+//! Signed tool manifests for verifying tools before they are registered.
+//!
+//! Tools distributed as plugins can be tampered with between their publisher
+//! and the deployment that loads them. A tool can supply a [`SignedManifest`]
+//! from [`Tool::manifest`]: its name, version, a SHA-256 hash of its
+//! parameter schema and, for tools loaded from code at runtime, a SHA-256
+//! hash of that code, signed with the publisher's Ed25519 key. Once a
+//! [`ManifestVerifier`] holding the trusted public keys is installed with
+//! [`ToolRegistry::set_manifest_verifier`](super::ToolRegistry::set_manifest_verifier)
+//! (or [`AgentBuilder::with_tool_manifest_verifier`](crate::agent::AgentBuilder::with_tool_manifest_verifier)),
+//! the registry refuses tools whose manifest is missing, signed by an unknown
+//! key, has an invalid signature or no longer matches the tool.
+//!
+//! ```
+//! use stood::tools::manifest::{ManifestVerifier, SignedManifest, ToolManifest};
+//!
+//! # fn example(publisher_pkcs8: &[u8], publisher_public_key: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+//! // Publisher side, when releasing version 1.2.0 of the tool
+//! let schema = serde_json::json!({"type": "object", "properties": {"query": {"type": "string"}}});
+//! let module = std::fs::read("search.wasm")?;
+//! let signed = ToolManifest::new("search", "1.2.0", &schema)
+//!     .with_module(&module)
+//!     .sign("acme-2024", publisher_pkcs8)?;
+//! let shipped = serde_json::to_string(&signed)?;
+//!
+//! // Deployment side
+//! let verifier = ManifestVerifier::new().with_key("acme-2024", publisher_public_key);
+//! let manifest: SignedManifest = serde_json::from_str(&shipped)?;
+//! verifier.verify_manifest(&manifest)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Built-in tools ship with the library and are accepted without a manifest;
+//! [`ManifestVerifier::allow_unsigned`] extends that to other sources such as
+//! MCP servers. Schema hashes are taken over the schema with object keys
+//! sorted, so key order doesn't matter.
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use ring::signature::{Ed25519KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::{Tool, ToolSource};
+
+/// Prefix of the signed bytes, so manifest signatures can't be replayed as
+/// signatures of anything else
+const SIGNING_CONTEXT: &[u8] = b"stood-tool-manifest-v1\n";
+
+/// What a manifest vouches for: a tool's name, version, parameter schema and
+/// the code it was loaded from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolManifest {
+    /// Tool name
+    pub name: String,
+    /// Tool version
+    pub version: String,
+    /// Hex SHA-256 of the parameter schema (see [`schema_hash`])
+    pub schema_hash: String,
+    /// Hex SHA-256 of the module the tool is loaded from (see [`module_hash`])
+    ///
+    /// Required for tools that report a [`Tool::module_hash`], such as
+    /// plugins; left out of the signed bytes when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_hash: Option<String>,
+}
+
+impl ToolManifest {
+    /// Manifest of a tool with this name, version and parameter schema
+    pub fn new(name: impl Into<String>, version: impl Into<String>, schema: &Value) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            schema_hash: schema_hash(schema),
+            module_hash: None,
+        }
+    }
+
+    /// Cover the module the tool is loaded from, such as a plugin's `.wasm` file
+    pub fn with_module(mut self, bytes: &[u8]) -> Self {
+        self.module_hash = Some(module_hash(bytes));
+        self
+    }
+
+    /// Manifest describing `tool` as it is now
+    pub fn for_tool(tool: &dyn Tool) -> Self {
+        Self {
+            module_hash: tool.module_hash(),
+            ..Self::new(tool.name(), tool.version(), &tool.parameters_schema())
+        }
+    }
+
+    /// Sign the manifest with an Ed25519 key in PKCS#8 DER form
+    ///
+    /// `key_id` tells verifiers which public key to check the signature with.
+    pub fn sign(
+        self,
+        key_id: impl Into<String>,
+        pkcs8: &[u8],
+    ) -> Result<SignedManifest, ManifestError> {
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(pkcs8)
+            .map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+        let signature = key_pair.sign(&self.signing_bytes());
+        Ok(SignedManifest {
+            manifest: self,
+            key_id: key_id.into(),
+            signature: base64::engine::general_purpose::STANDARD.encode(signature.as_ref()),
+        })
+    }
+
+    /// Bytes covered by the signature
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = SIGNING_CONTEXT.to_vec();
+        bytes.extend_from_slice(
+            canonical(&serde_json::to_value(self).unwrap_or_default())
+                .to_string()
+                .as_bytes(),
+        );
+        bytes
+    }
+}
+
+/// A manifest with its publisher's signature
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedManifest {
+    /// The signed manifest
+    #[serde(flatten)]
+    pub manifest: ToolManifest,
+    /// Name of the key that signed it, as configured in the verifier
+    pub key_id: String,
+    /// Base64 Ed25519 signature
+    pub signature: String,
+}
+
+/// Why a tool's manifest was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ManifestError {
+    /// The tool supplies no manifest
+    #[error("tool has no signed manifest")]
+    Missing,
+    /// The manifest describes a different name, version, schema or module
+    #[error("manifest {field} is '{expected}' but the tool has '{actual}'")]
+    Mismatch {
+        /// `name`, `version`, `schema_hash` or `module_hash`
+        field: &'static str,
+        /// Value in the manifest
+        expected: String,
+        /// Value of the tool
+        actual: String,
+    },
+    /// The manifest is signed by a key the verifier doesn't trust
+    #[error("manifest signed by unknown key '{0}'")]
+    UnknownKey(String),
+    /// The signature doesn't match the manifest and key
+    #[error("manifest signature is invalid")]
+    InvalidSignature,
+    /// A signing or public key couldn't be read
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+}
+
+/// Trusted publisher keys and which tools may go without a manifest
+#[derive(Debug, Clone)]
+pub struct ManifestVerifier {
+    keys: HashMap<String, Vec<u8>>,
+    unsigned_sources: Vec<ToolSource>,
+}
+
+impl Default for ManifestVerifier {
+    fn default() -> Self {
+        Self {
+            keys: HashMap::new(),
+            unsigned_sources: vec![ToolSource::Builtin],
+        }
+    }
+}
+
+impl ManifestVerifier {
+    /// A verifier trusting no keys yet, accepting unsigned built-in tools
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust manifests signed by `public_key`, a raw 32-byte Ed25519 key
+    pub fn with_key(mut self, key_id: impl Into<String>, public_key: impl AsRef<[u8]>) -> Self {
+        self.keys
+            .insert(key_id.into(), public_key.as_ref().to_vec());
+        self
+    }
+
+    /// Trust manifests signed by a base64-encoded Ed25519 public key
+    pub fn with_key_base64(
+        self,
+        key_id: impl Into<String>,
+        public_key: &str,
+    ) -> Result<Self, ManifestError> {
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(public_key.trim())
+            .map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+        if key.len() != 32 {
+            return Err(ManifestError::InvalidKey(format!(
+                "Ed25519 public keys are 32 bytes, got {}",
+                key.len()
+            )));
+        }
+        Ok(self.with_key(key_id, key))
+    }
+
+    /// Accept tools from `source` without a manifest
+    ///
+    /// Tools that do supply one are still verified.
+    pub fn allow_unsigned(mut self, source: ToolSource) -> Self {
+        if !self.unsigned_sources.contains(&source) {
+            self.unsigned_sources.push(source);
+        }
+        self
+    }
+
+    /// Check that `manifest` is signed by a trusted key
+    pub fn verify_manifest(&self, manifest: &SignedManifest) -> Result<(), ManifestError> {
+        let key = self
+            .keys
+            .get(&manifest.key_id)
+            .ok_or_else(|| ManifestError::UnknownKey(manifest.key_id.clone()))?;
+        let signature = base64::engine::general_purpose::STANDARD
+            .decode(&manifest.signature)
+            .map_err(|_| ManifestError::InvalidSignature)?;
+        UnparsedPublicKey::new(&ED25519, key)
+            .verify(&manifest.manifest.signing_bytes(), &signature)
+            .map_err(|_| ManifestError::InvalidSignature)
+    }
+
+    /// Check that `tool` supplies a trusted manifest describing it
+    pub fn verify(&self, tool: &dyn Tool) -> Result<(), ManifestError> {
+        let Some(signed) = tool.manifest() else {
+            if self.unsigned_sources.contains(&tool.source()) {
+                return Ok(());
+            }
+            return Err(ManifestError::Missing);
+        };
+        self.verify_manifest(&signed)?;
+
+        let actual = ToolManifest::for_tool(tool);
+        let fields = [
+            ("name", &signed.manifest.name, &actual.name),
+            ("version", &signed.manifest.version, &actual.version),
+            (
+                "schema_hash",
+                &signed.manifest.schema_hash,
+                &actual.schema_hash,
+            ),
+        ];
+        for (field, expected, actual) in fields {
+            if expected != actual {
+                return Err(ManifestError::Mismatch {
+                    field,
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                });
+            }
+        }
+        // A manifest without a module hash doesn't vouch for a loaded module
+        if signed.manifest.module_hash != actual.module_hash {
+            let describe = |hash: &Option<String>| hash.clone().unwrap_or_else(|| "none".into());
+            return Err(ManifestError::Mismatch {
+                field: "module_hash",
+                expected: describe(&signed.manifest.module_hash),
+                actual: describe(&actual.module_hash),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A tool registered while a verifier was installed whose manifest no longer
+/// verifies (see [`ToolRegistry::verify_manifests`](super::ToolRegistry::verify_manifests))
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestViolation {
+    /// Tool name
+    pub tool: String,
+    /// Tool version
+    pub version: String,
+    /// Why verification failed
+    pub error: ManifestError,
+}
+
+/// Hex SHA-256 of a parameter schema, with object keys sorted
+pub fn schema_hash(schema: &Value) -> String {
+    hex::encode(Sha256::digest(canonical(schema).to_string().as_bytes()))
+}
+
+/// Hex SHA-256 of a module's bytes
+pub fn module_hash(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// `value` with object keys sorted at every level
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), canonical(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.iter().map(canonical).collect()),
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{ToolError, ToolResult};
+    use async_trait::async_trait;
+    use ring::rand::SystemRandom;
+    use ring::signature::KeyPair;
+
+    #[derive(Debug)]
+    struct PluginTool {
+        schema: Value,
+        manifest: Option<SignedManifest>,
+    }
+
+    #[async_trait]
+    impl Tool for PluginTool {
+        fn name(&self) -> &str {
+            "lookup"
+        }
+
+        fn description(&self) -> &str {
+            "Look something up"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            self.schema.clone()
+        }
+
+        fn version(&self) -> &str {
+            "2.0.0"
+        }
+
+        fn manifest(&self) -> Option<SignedManifest> {
+            self.manifest.clone()
+        }
+
+        async fn execute(
+            &self,
+            _parameters: Option<Value>,
+            _agent_context: Option<&crate::agent::AgentContext>,
+        ) -> Result<ToolResult, ToolError> {
+            Ok(ToolResult::success(Value::Null))
+        }
+    }
+
+    fn key_pair() -> (Vec<u8>, Vec<u8>) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let public = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+            .unwrap()
+            .public_key()
+            .as_ref()
+            .to_vec();
+        (pkcs8.as_ref().to_vec(), public)
+    }
+
+    #[test]
+    fn test_schema_hash_ignores_key_order() {
+        let a = serde_json::json!({"type": "object", "properties": {"a": {"type": "string"}, "b": {"type": "number"}}});
+        let b = serde_json::json!({"properties": {"b": {"type": "number"}, "a": {"type": "string"}}, "type": "object"});
+        assert_eq!(schema_hash(&a), schema_hash(&b));
+        assert_ne!(schema_hash(&a), schema_hash(&serde_json::json!({})));
+    }
+
+    #[test]
+    fn test_verify() {
+        let (pkcs8, public) = key_pair();
+        let schema = serde_json::json!({"type": "object", "properties": {"q": {"type": "string"}}});
+        let signed = ToolManifest::new("lookup", "2.0.0", &schema)
+            .sign("publisher", &pkcs8)
+            .unwrap();
+        let verifier = ManifestVerifier::new().with_key("publisher", &public);
+
+        let tool = PluginTool {
+            schema: schema.clone(),
+            manifest: Some(signed.clone()),
+        };
+        assert_eq!(verifier.verify(&tool), Ok(()));
+
+        // Round trip through the shipped JSON form
+        let shipped: SignedManifest =
+            serde_json::from_str(&serde_json::to_string(&signed).unwrap()).unwrap();
+        assert_eq!(verifier.verify_manifest(&shipped), Ok(()));
+
+        let tampered_schema = PluginTool {
+            schema: serde_json::json!({"type": "object"}),
+            manifest: Some(signed.clone()),
+        };
+        assert!(matches!(
+            verifier.verify(&tampered_schema),
+            Err(ManifestError::Mismatch {
+                field: "schema_hash",
+                ..
+            })
+        ));
+
+        let mut forged = signed.clone();
+        forged.manifest.version = "3.0.0".to_string();
+        assert_eq!(
+            verifier.verify_manifest(&forged),
+            Err(ManifestError::InvalidSignature)
+        );
+
+        let (_, other_public) = key_pair();
+        let untrusted = ManifestVerifier::new().with_key("other", &other_public);
+        assert_eq!(
+            untrusted.verify(&tool),
+            Err(ManifestError::UnknownKey("publisher".to_string()))
+        );
+
+        let unsigned = PluginTool {
+            schema,
+            manifest: None,
+        };
+        assert_eq!(verifier.verify(&unsigned), Err(ManifestError::Missing));
+        assert_eq!(
+            verifier
+                .allow_unsigned(ToolSource::Custom)
+                .verify(&unsigned),
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_registry_refuses_unverified_tools() {
+        let (pkcs8, public) = key_pair();
+        let schema = serde_json::json!({"type": "object"});
+        let registry = crate::tools::ToolRegistry::new();
+        let unsigned = || PluginTool {
+            schema: schema.clone(),
+            manifest: None,
+        };
+
+        // Registered before verification was turned on
+        registry.register_tool(Box::new(unsigned())).await.unwrap();
+        registry
+            .set_manifest_verifier(ManifestVerifier::new().with_key("publisher", &public))
+            .await;
+
+        let signed = PluginTool {
+            schema: schema.clone(),
+            manifest: Some(
+                ToolManifest::new("lookup", "2.0.0", &schema)
+                    .sign("publisher", &pkcs8)
+                    .unwrap(),
+            ),
+        };
+        let fresh = crate::tools::ToolRegistry::new();
+        fresh
+            .set_manifest_verifier(registry.manifest_verifier().await.unwrap().as_ref().clone())
+            .await;
+        assert!(matches!(
+            fresh.register_tool(Box::new(unsigned())).await,
+            Err(ToolError::ManifestRejected { .. })
+        ));
+        assert!(fresh.register_tool(Box::new(signed)).await.is_ok());
+        assert!(fresh.verify_manifests().await.is_empty());
+
+        let violations = registry.verify_manifests().await;
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].tool, "lookup");
+        assert_eq!(violations[0].error, ManifestError::Missing);
+    }
+}
@@ -6,6 +6,7 @@
 
 use crate::error::StoodError;
 use crate::mcp::client::MCPClient;
+use crate::mcp::health::MCPHealth;
 use crate::mcp::types::{Content, Tool as MCPTool};
 use crate::tools::{Tool, ToolError, ToolResult};
 use crate::types::content::ToolResultContent;
@@ -24,6 +25,8 @@ pub struct MCPAgentTool {
     namespace: Option<String>,
     /// Cached prefixed name for efficient access
     prefixed_name: String,
+    /// Connection health of the client, used to report availability
+    health: Option<Arc<MCPHealth>>,
 }
 
 impl MCPAgentTool {
@@ -43,9 +46,16 @@ impl MCPAgentTool {
             mcp_client,
             namespace,
             prefixed_name,
+            health: None,
         }
     }
 
+    /// Report this tool as unavailable while the client's connection is down
+    pub fn with_health(mut self, health: Arc<MCPHealth>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
     /// Get the prefixed tool name with namespace
     pub fn prefixed_name(&self) -> &str {
         &self.prefixed_name
@@ -257,9 +267,10 @@ impl Tool for MCPAgentTool {
                     duration,
                     e
                 );
-                ToolError::ExecutionFailed {
-                    message: format!("MCP Tool '{}' execution failed: {}", self.prefixed_name, e),
-                }
+                ToolError::with_source(
+                    format!("MCP Tool '{}' execution failed: {}", self.prefixed_name, e),
+                    e,
+                )
             })?;
 
         let duration = start_time.elapsed();
@@ -278,6 +289,18 @@ impl Tool for MCPAgentTool {
         }
     }
 
+    fn is_available(&self) -> bool {
+        // Calls made while reconnecting fail fast with `ToolNotAvailable`
+        // instead of waiting for a request timeout
+        self.health
+            .as_ref()
+            .is_none_or(|health| health.is_available())
+    }
+
+    fn annotations(&self) -> crate::tools::ToolAnnotations {
+        self.mcp_tool.annotations.clone().unwrap_or_default()
+    }
+
     fn source(&self) -> crate::tools::ToolSource {
         crate::tools::ToolSource::MCP
     }
@@ -322,18 +345,20 @@ impl MCPToolRegistry {
         let mut registered_tools = Vec::new();
 
         // Get the available tools from the MCP client
-        let tools = {
+        let (tools, health) = {
             let client = mcp_client.read().await;
-            client
+            let tools = client
                 .list_tools()
                 .await
-                .map_err(|e| StoodError::tool_error(format!("Failed to list MCP tools: {}", e)))?
+                .map_err(|e| StoodError::tool_error(format!("Failed to list MCP tools: {}", e)))?;
+            (tools, client.health())
         };
 
         // Register each tool using the new Tool trait
         for mcp_tool in tools {
             let adapter =
-                MCPAgentTool::new(mcp_tool.clone(), mcp_client.clone(), namespace.clone());
+                MCPAgentTool::new(mcp_tool.clone(), mcp_client.clone(), namespace.clone())
+                    .with_health(health.clone());
 
             let tool_name = adapter.prefixed_name().to_string();
 
@@ -482,6 +507,7 @@ mod tests {
                     },
                     "required": ["expression"]
                 }),
+                annotations: None,
             };
 
             self.tool_handlers.insert(
@@ -528,6 +554,7 @@ mod tests {
                     },
                     "required": ["message"]
                 }),
+                annotations: None,
             };
 
             self.tool_handlers.insert(
@@ -565,6 +592,7 @@ mod tests {
                     },
                     "required": ["error_type"]
                 }),
+                annotations: None,
             };
 
             self.tool_handlers.insert(
@@ -649,6 +677,7 @@ mod tests {
                 },
                 "required": ["message"]
             }),
+            annotations: None,
         }
     }
 
@@ -662,6 +691,20 @@ mod tests {
         assert_eq!(adapter.prefixed_name(), "test_namespacetest_tool");
     }
 
+    #[tokio::test]
+    async fn test_mcp_agent_tool_availability_follows_health() {
+        let client = create_mock_mcp_client();
+        let health = client.health();
+        let adapter =
+            MCPAgentTool::new(create_mock_mcp_tool(), Arc::new(RwLock::new(client)), None);
+        assert!(adapter.is_available());
+
+        let adapter = adapter.with_health(health.clone());
+        assert!(!adapter.is_available());
+        health.set_status(crate::mcp::MCPConnectionStatus::Connected);
+        assert!(adapter.is_available());
+    }
+
     #[tokio::test]
     async fn test_mcp_tool_specification() {
         let mcp_tool = create_mock_mcp_tool();
@@ -848,6 +891,7 @@ mod tests {
                 },
                 "required": ["expression"]
             }),
+            annotations: None,
         };
 
         // Create adapter for the tool
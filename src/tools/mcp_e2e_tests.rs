@@ -63,6 +63,7 @@ impl E2EMockTransport {
                 },
                 "required": ["expression"]
             }),
+            annotations: None,
         };
 
         self.tool_handlers.insert(
@@ -103,6 +104,7 @@ impl E2EMockTransport {
                 },
                 "required": ["text", "operation"]
             }),
+            annotations: None,
         };
 
         self.tool_handlers.insert(
@@ -145,6 +147,7 @@ impl E2EMockTransport {
                 },
                 "required": ["data", "format"]
             }),
+            annotations: None,
         };
 
         self.tool_handlers.insert(
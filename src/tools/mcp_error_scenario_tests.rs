@@ -122,6 +122,7 @@ impl ErrorScenarioTransport {
                     },
                     "required": ["data"]
                 }),
+                annotations: None,
             },
             MCPTool {
                 name: "timeout_tool".to_string(),
@@ -136,6 +137,7 @@ impl ErrorScenarioTransport {
                     },
                     "required": ["delay_ms"]
                 }),
+                annotations: None,
             },
             MCPTool {
                 name: "reliable_tool".to_string(),
@@ -150,6 +152,7 @@ impl ErrorScenarioTransport {
                     },
                     "required": ["message"]
                 }),
+                annotations: None,
             },
         ];
 
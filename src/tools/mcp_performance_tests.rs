@@ -141,6 +141,7 @@ impl PerformanceMockTransport {
                 },
                 "required": ["expression"]
             }),
+            annotations: None,
         };
         self.tools.push(tool);
     }
@@ -160,6 +161,7 @@ impl PerformanceMockTransport {
                 },
                 "required": ["message"]
             }),
+            annotations: None,
         };
         self.tools.push(tool);
     }
@@ -179,6 +181,7 @@ impl PerformanceMockTransport {
                 },
                 "required": ["iterations"]
             }),
+            annotations: None,
         };
         self.tools.push(tool);
     }
@@ -446,6 +449,7 @@ impl MCPPerformanceBenchmark {
                     name: format!("test_tool_{}", i),
                     description: "Test tool for benchmarking".to_string(),
                     input_schema: json!({"type": "object", "properties": {}}),
+                    annotations: None,
                 };
 
                 let mcp_client_config = MCPClientConfig::default();
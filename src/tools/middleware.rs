@@ -58,7 +58,7 @@ use serde_json::Value;
 use std::sync::Arc;
 use std::time::Instant;
 
-use super::ToolResult;
+use super::{ToolAnnotations, ToolResult};
 
 /// Context provided to middleware during tool execution.
 ///
@@ -78,6 +78,8 @@ pub struct ToolContext {
     pub tool_count_this_turn: usize,
     /// Total conversation message count
     pub message_count: usize,
+    /// Behavior hints of the tool being called
+    pub annotations: ToolAnnotations,
 }
 
 impl ToolContext {
@@ -90,6 +92,7 @@ impl ToolContext {
             execution_start: Instant::now(),
             tool_count_this_turn: 0,
             message_count: 0,
+            annotations: ToolAnnotations::default(),
         }
     }
 
@@ -102,6 +105,7 @@ impl ToolContext {
             execution_start: Instant::now(),
             tool_count_this_turn: 0,
             message_count: 0,
+            annotations: ToolAnnotations::default(),
         }
     }
 
@@ -133,6 +137,12 @@ impl ToolContext {
         self.message_count = count;
         self
     }
+
+    /// Builder pattern: set tool annotations
+    pub fn with_annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.annotations = annotations;
+        self
+    }
 }
 
 /// Action to take before tool execution.
@@ -394,6 +404,128 @@ impl MiddlewareStack {
     }
 }
 
+/// What [`ApprovalMiddleware`] does with a tool call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalRule {
+    /// Run without asking
+    Approve,
+    /// Ask the [`ToolApprover`] first
+    Ask,
+    /// Refuse without asking
+    Deny,
+}
+
+/// Decides whether a tool call may run, e.g. by prompting the user
+#[async_trait]
+pub trait ToolApprover: Send + Sync + std::fmt::Debug {
+    /// Return `true` to run the call
+    async fn approve(&self, tool_name: &str, params: &Value, ctx: &ToolContext) -> bool;
+}
+
+/// Middleware that requires approval for tool calls based on their annotations
+///
+/// By default read-only tools run without approval, and all other tools,
+/// including tools without annotations, ask the approver. Calls that are
+/// denied get an error result telling the model the call was not approved.
+#[derive(Debug)]
+pub struct ApprovalMiddleware {
+    approver: Arc<dyn ToolApprover>,
+    read_only: ApprovalRule,
+    destructive: ApprovalRule,
+    other: ApprovalRule,
+    overrides: std::collections::HashMap<String, ApprovalRule>,
+}
+
+impl ApprovalMiddleware {
+    /// Create middleware asking `approver` for non-read-only tools
+    pub fn new(approver: Arc<dyn ToolApprover>) -> Self {
+        Self {
+            approver,
+            read_only: ApprovalRule::Approve,
+            destructive: ApprovalRule::Ask,
+            other: ApprovalRule::Ask,
+            overrides: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Rule for read-only tools (default [`ApprovalRule::Approve`])
+    pub fn read_only(mut self, rule: ApprovalRule) -> Self {
+        self.read_only = rule;
+        self
+    }
+
+    /// Rule for destructive tools (default [`ApprovalRule::Ask`])
+    pub fn destructive(mut self, rule: ApprovalRule) -> Self {
+        self.destructive = rule;
+        self
+    }
+
+    /// Rule for tools that modify but do not destroy (default [`ApprovalRule::Ask`])
+    pub fn non_destructive(mut self, rule: ApprovalRule) -> Self {
+        self.other = rule;
+        self
+    }
+
+    /// Rule for one tool, regardless of its annotations
+    pub fn for_tool(mut self, tool_name: impl Into<String>, rule: ApprovalRule) -> Self {
+        self.overrides.insert(tool_name.into(), rule);
+        self
+    }
+
+    /// The rule that applies to a call of `tool_name`
+    pub fn rule_for(&self, tool_name: &str, annotations: &ToolAnnotations) -> ApprovalRule {
+        if let Some(rule) = self.overrides.get(tool_name) {
+            *rule
+        } else if annotations.is_read_only() {
+            self.read_only
+        } else if annotations.is_destructive() {
+            self.destructive
+        } else {
+            self.other
+        }
+    }
+}
+
+#[async_trait]
+impl ToolMiddleware for ApprovalMiddleware {
+    async fn before_tool(
+        &self,
+        tool_name: &str,
+        params: &Value,
+        ctx: &ToolContext,
+    ) -> ToolMiddlewareAction {
+        let approved = match self.rule_for(tool_name, &ctx.annotations) {
+            ApprovalRule::Approve => true,
+            ApprovalRule::Ask => self.approver.approve(tool_name, params, ctx).await,
+            ApprovalRule::Deny => false,
+        };
+        if approved {
+            ToolMiddlewareAction::Continue
+        } else {
+            ToolMiddlewareAction::Abort {
+                reason: format!("Tool {} was not approved", tool_name),
+                synthetic_result: Some(ToolResult::error(format!(
+                    "The call to {} was not approved by the user",
+                    tool_name
+                ))),
+            }
+        }
+    }
+
+    async fn after_tool(
+        &self,
+        _tool_name: &str,
+        _result: &ToolResult,
+        _ctx: &ToolContext,
+    ) -> AfterToolAction {
+        AfterToolAction::PassThrough
+    }
+
+    fn name(&self) -> &str {
+        "approval_middleware"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -638,4 +770,47 @@ mod tests {
         assert_eq!(ctx.tool_count_this_turn, 5);
         assert_eq!(ctx.message_count, 10);
     }
+
+    #[derive(Debug)]
+    struct DenyAll;
+
+    #[async_trait]
+    impl ToolApprover for DenyAll {
+        async fn approve(&self, _tool_name: &str, _params: &Value, _ctx: &ToolContext) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approval_follows_annotations() {
+        let approval =
+            ApprovalMiddleware::new(Arc::new(DenyAll)).for_tool("rm", ApprovalRule::Deny);
+        let read_ctx = ToolContext::new("test-agent".to_string())
+            .with_annotations(ToolAnnotations::read_only());
+        let unannotated_ctx = ToolContext::new("test-agent".to_string());
+
+        let action = approval.before_tool("search", &json!({}), &read_ctx).await;
+        assert!(matches!(action, ToolMiddlewareAction::Continue));
+
+        // Unannotated tools are treated as destructive and ask the approver
+        assert_eq!(
+            approval.rule_for("write", &unannotated_ctx.annotations),
+            ApprovalRule::Ask
+        );
+        let action = approval
+            .before_tool("write", &json!({}), &unannotated_ctx)
+            .await;
+        match action {
+            ToolMiddlewareAction::Abort {
+                synthetic_result: Some(result),
+                ..
+            } => assert!(!result.success),
+            other => panic!("expected abort, got {:?}", other),
+        }
+
+        assert_eq!(
+            approval.rule_for("rm", &read_ctx.annotations),
+            ApprovalRule::Deny
+        );
+    }
 }
@@ -74,10 +74,33 @@
 //! - [`ToolResult`] - Standardized tool execution results
 //! - [`ToolError`] - Comprehensive error handling for tool operations
 
+pub mod audit;
 pub mod builtin;
+pub mod call_limits;
+#[cfg(feature = "code-runner")]
+pub mod code_runner;
+#[cfg(feature = "database")]
+pub mod database;
+pub mod docs;
+pub mod elicitation;
 pub mod executor;
+pub mod guardrails;
+pub mod image_generation;
+pub mod interim;
+pub mod manifest;
 pub mod mcp_adapter;
 pub mod middleware;
+pub mod network;
+pub mod plan;
+pub mod progress;
+pub mod reliability;
+pub mod scaffold;
+pub mod simulation;
+pub mod templating;
+pub mod versioning;
+pub mod workspace;
+#[cfg(feature = "s3")]
+pub mod s3;
 
 #[cfg(test)]
 mod mcp_e2e_tests;
@@ -95,10 +118,30 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+pub use audit::{AuditLog, AuditRecord, AuditSink};
+pub use call_limits::{CallLimitReached, ToolCallLimits};
+pub use elicitation::{
+    CallbackElicitor, ElicitationRequest, ElicitationResponse, Elicitor, InputRequest,
+};
 pub use executor::{ExecutionMetrics, ExecutorConfig, ToolExecutor};
+pub use guardrails::{GuardrailViolation, ParamConstraint, ToolGuardrails};
+pub use image_generation::{ImageGenerationConfig, ImageGenerationTool, ImageModel, ImageOutput};
+pub use interim::{InterimResultConfig, PartialOutput};
+pub use manifest::{ManifestError, ManifestVerifier, SignedManifest, ToolManifest};
 pub use middleware::{
-    AfterToolAction, MiddlewareStack, ToolContext, ToolMiddleware, ToolMiddlewareAction,
+    AfterToolAction, ApprovalMiddleware, ApprovalRule, MiddlewareStack, ToolApprover, ToolContext,
+    ToolMiddleware, ToolMiddlewareAction,
+};
+pub use network::{
+    network_policy, set_network_policy, NetworkPolicy, NetworkPolicyError, NetworkPolicyViolation,
+    TlsVersion,
 };
+pub use plan::{Plan, PlanState, PlanTool};
+pub use progress::ProgressReporter;
+pub use reliability::{FlakyToolPolicy, ToolReliability, ToolReliabilityTracker};
+pub use simulation::{SimulatedTool, ToolFixtures};
+pub use templating::DescriptionContext;
+pub use workspace::Workspace;
 
 // Note: Unified tool system types are defined below and exported automatically
 
@@ -232,6 +275,38 @@ pub trait Tool: Send + Sync + std::fmt::Debug {
         agent_context: Option<&crate::agent::AgentContext>,
     ) -> Result<ToolResult, ToolError>;
 
+    /// Version of the tool's contract, such as `"2.1.0"`
+    ///
+    /// Several versions of a tool can be registered under one name; models see
+    /// the latest unless an agent pins another (see [`versioning`]).
+    fn version(&self) -> &str {
+        versioning::DEFAULT_VERSION
+    }
+
+    /// Why this version should no longer be used, if it is deprecated
+    ///
+    /// Shown in the generated tool documentation and logged when the version
+    /// is registered or pinned.
+    fn deprecation(&self) -> Option<&str> {
+        None
+    }
+
+    /// The publisher's signed manifest of this tool, if it has one
+    ///
+    /// Checked at registration when the registry has a
+    /// [`ManifestVerifier`] (see [`manifest`]).
+    fn manifest(&self) -> Option<SignedManifest> {
+        None
+    }
+
+    /// Hex SHA-256 of the module this tool was loaded from at runtime
+    ///
+    /// Set by tools whose code isn't part of the application, such as WASM
+    /// plugins; their manifest must then cover the same module.
+    fn module_hash(&self) -> Option<String> {
+        None
+    }
+
     /// Check if the tool is available for use
     fn is_available(&self) -> bool {
         true
@@ -241,6 +316,157 @@ pub trait Tool: Send + Sync + std::fmt::Debug {
     fn source(&self) -> ToolSource {
         ToolSource::Custom
     }
+
+    /// Hints about the tool's behavior, such as whether it modifies anything
+    ///
+    /// Surfaced to MCP clients and used by
+    /// [`ApprovalMiddleware`](middleware::ApprovalMiddleware) to decide which
+    /// calls need approval. Tools without annotations are treated as
+    /// potentially destructive.
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations::default()
+    }
+
+    /// Called after an in-flight execution was aborted by cancellation or timeout
+    ///
+    /// The execution future has already been dropped when this runs, so no code
+    /// after its last `.await` point executed. Override this to release external
+    /// resources such as temp files, browser sessions or open transactions.
+    async fn on_cancel(&self, reason: ToolCancelReason) {
+        let _ = reason;
+    }
+
+    /// Result to return instead of executing, in dry-run mode
+    ///
+    /// Override this to return a realistic result without side effects.
+    /// Returning `None` falls back to a generic simulated result (see
+    /// [`simulation`]).
+    async fn simulate(&self, parameters: Option<Value>) -> Option<ToolResult> {
+        let _ = parameters;
+        None
+    }
+}
+
+/// Why an in-flight tool execution was aborted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolCancelReason {
+    /// The agent's cancellation token was triggered
+    Cancelled,
+    /// The execution exceeded the executor's timeout
+    Timeout(std::time::Duration),
+}
+
+impl std::fmt::Display for ToolCancelReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::Timeout(after) => write!(f, "timed out after {} seconds", after.as_secs()),
+        }
+    }
+}
+
+/// Behavior hints of a tool, following the MCP tool annotation spec
+///
+/// Each hint is optional; the `is_*` methods apply the spec defaults for
+/// hints that are not set. Hints describe the tool, they are not enforced.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// Human-readable title of the tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The tool does not modify its environment
+    #[serde(
+        default,
+        rename = "readOnlyHint",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub read_only: Option<bool>,
+    /// The tool may perform destructive updates, such as deleting data
+    #[serde(
+        default,
+        rename = "destructiveHint",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub destructive: Option<bool>,
+    /// Repeating a call with the same arguments has no additional effect
+    #[serde(
+        default,
+        rename = "idempotentHint",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub idempotent: Option<bool>,
+    /// The tool interacts with external entities, such as the web
+    #[serde(
+        default,
+        rename = "openWorldHint",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub open_world: Option<bool>,
+}
+
+impl ToolAnnotations {
+    /// Annotations of a tool that only reads
+    pub fn read_only() -> Self {
+        Self {
+            read_only: Some(true),
+            destructive: Some(false),
+            idempotent: Some(true),
+            ..Self::default()
+        }
+    }
+
+    /// Annotations of a tool that may destroy data
+    pub fn destructive() -> Self {
+        Self {
+            read_only: Some(false),
+            destructive: Some(true),
+            ..Self::default()
+        }
+    }
+
+    /// Set the title
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the idempotent hint
+    pub fn with_idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = Some(idempotent);
+        self
+    }
+
+    /// Set the open world hint
+    pub fn with_open_world(mut self, open_world: bool) -> Self {
+        self.open_world = Some(open_world);
+        self
+    }
+
+    /// Whether the tool only reads (default `false`)
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.unwrap_or(false)
+    }
+
+    /// Whether the tool may destroy data (default `true` unless read-only)
+    pub fn is_destructive(&self) -> bool {
+        !self.is_read_only() && self.destructive.unwrap_or(true)
+    }
+
+    /// Whether repeated calls are harmless (default `false`, always `true` if read-only)
+    pub fn is_idempotent(&self) -> bool {
+        self.is_read_only() || self.idempotent.unwrap_or(false)
+    }
+
+    /// Whether the tool reaches outside the local environment (default `true`)
+    pub fn is_open_world(&self) -> bool {
+        self.open_world.unwrap_or(true)
+    }
+
+    /// Whether no hint is set
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
 }
 
 /// Source type for tools in the unified system
@@ -252,6 +478,8 @@ pub enum ToolSource {
     MCP,
     /// Custom tools defined by users
     Custom,
+    /// Tools loaded at runtime from plugin modules (see `stood::plugins`)
+    Plugin,
 }
 
 /// Result from executing a tool
@@ -263,6 +491,12 @@ pub struct ToolResult {
     pub content: Value,
     /// Optional error message if execution failed
     pub error: Option<String>,
+    /// Typed error behind `error`, for callers that downcast it (not serialized)
+    #[serde(skip)]
+    pub source: Option<ToolErrorSource>,
+    /// Input the tool needs from the user before it can run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub needs_input: Option<InputRequest>,
 }
 
 impl ToolResult {
@@ -272,6 +506,8 @@ impl ToolResult {
             success: true,
             content,
             error: None,
+            source: None,
+            needs_input: None,
         }
     }
 
@@ -281,8 +517,94 @@ impl ToolResult {
             success: false,
             content: Value::Null,
             error: Some(message.into()),
+            source: None,
+            needs_input: None,
+        }
+    }
+
+    /// Ask the user for input and run the tool again with the answer
+    ///
+    /// The answer, matching `schema`, is merged into the tool's parameters
+    /// (see [`elicitation`]). Without anyone to ask, the model gets an error
+    /// result with the prompt.
+    pub fn needs_input(prompt: impl Into<String>, schema: Value) -> Self {
+        let prompt = prompt.into();
+        Self {
+            needs_input: Some(InputRequest {
+                prompt: prompt.clone(),
+                schema,
+            }),
+            ..Self::error(format!("Input needed from the user: {}", prompt))
+        }
+    }
+
+    /// Create an error tool result that keeps the typed error behind it
+    pub fn error_with_source<S, E>(message: S, source: E) -> Self
+    where
+        S: Into<String>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self {
+            source: Some(ToolErrorSource::new(source)),
+            ..Self::error(message)
         }
     }
+
+    /// The typed error behind a failed result, if it is an `E`
+    pub fn downcast_error<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        self.source.as_ref()?.downcast_ref()
+    }
+}
+
+/// Shared, typed error behind a tool failure
+///
+/// Applications get their own error types back from callback handlers and
+/// middleware with [`downcast_ref`](Self::downcast_ref), which also searches
+/// the error's `source()` chain. Shared so errors and results stay `Clone`;
+/// equal when they are the same error instance.
+#[derive(Debug, Clone)]
+pub struct ToolErrorSource(Arc<dyn std::error::Error + Send + Sync>);
+
+impl ToolErrorSource {
+    /// Wrap `error`
+    pub fn new<E: std::error::Error + Send + Sync + 'static>(error: E) -> Self {
+        Self(Arc::new(error))
+    }
+
+    /// The first error of type `E` in the error and its `source()` chain
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        let mut error: Option<&(dyn std::error::Error + 'static)> = Some(self.0.as_ref());
+        while let Some(current) = error {
+            if let Some(found) = current.downcast_ref::<E>() {
+                return Some(found);
+            }
+            error = current.source();
+        }
+        None
+    }
+
+    /// Whether the error or its `source()` chain contains an `E`
+    pub fn is<E: std::error::Error + 'static>(&self) -> bool {
+        self.downcast_ref::<E>().is_some()
+    }
+}
+
+impl std::fmt::Display for ToolErrorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for ToolErrorSource {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl PartialEq for ToolErrorSource {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 /// Specialized tool error type for the unified system
@@ -302,13 +624,102 @@ pub enum ToolError {
 
     /// Tool execution failed
     #[error("Tool execution failed: {message}")]
-    ExecutionFailed { message: String },
+    ExecutionFailed {
+        message: String,
+        /// Typed error that caused the failure, if the tool kept it
+        #[source]
+        source: Option<ToolErrorSource>,
+    },
 
     /// Tool is not available
     #[error("Tool not available: {name}")]
     ToolNotAvailable { name: String },
+
+    /// Tool refused at registration because its manifest did not verify
+    #[error("Tool manifest rejected for {name}: {reason}")]
+    ManifestRejected { name: String, reason: String },
+}
+
+impl ToolError {
+    /// Execution failure with only a message
+    pub fn execution_failed<S: Into<String>>(message: S) -> Self {
+        Self::ExecutionFailed {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Execution failure caused by `source`
+    pub fn with_source<S, E>(message: S, source: E) -> Self
+    where
+        S: Into<String>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self::ExecutionFailed {
+            message: message.into(),
+            source: Some(ToolErrorSource::new(source)),
+        }
+    }
+
+    /// Execution failure caused by `source`, using its message
+    pub fn from_source<E: std::error::Error + Send + Sync + 'static>(source: E) -> Self {
+        Self::with_source(source.to_string(), source)
+    }
+
+    /// The typed error behind an execution failure
+    pub fn error_source(&self) -> Option<&ToolErrorSource> {
+        match self {
+            Self::ExecutionFailed { source, .. } => source.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// The typed error behind an execution failure, if it is an `E`
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        self.error_source()?.downcast_ref()
+    }
+}
+
+/// Build a [`ToolError::ExecutionFailed`] that keeps a typed error
+///
+/// `tool_error!(err)` uses the error's message; `tool_error!(err, "format",
+/// args...)` sets a message of its own. Callback handlers and middleware get
+/// `err` back with [`ToolError::downcast_ref`] or
+/// [`ToolErrorSource::downcast_ref`].
+///
+/// ```
+/// use stood::tool_error;
+/// use stood::tools::ToolError;
+///
+/// #[derive(Debug, thiserror::Error)]
+/// #[error("rate limited, retry in {0}s")]
+/// struct RateLimited(u64);
+///
+/// let error: ToolError = tool_error!(RateLimited(30), "weather API unavailable");
+/// assert_eq!(error.to_string(), "Tool execution failed: weather API unavailable");
+/// assert_eq!(error.downcast_ref::<RateLimited>().map(|e| e.0), Some(30));
+/// ```
+#[macro_export]
+macro_rules! tool_error {
+    ($source:expr $(,)?) => {
+        $crate::tools::ToolError::from_source($source)
+    };
+    ($source:expr, $($arg:tt)+) => {
+        $crate::tools::ToolError::with_source(format!($($arg)+), $source)
+    };
 }
 
+/// Return early from a tool with [`tool_error!`]
+#[macro_export]
+macro_rules! tool_bail {
+    ($($arg:tt)+) => {
+        return Err($crate::tool_error!($($arg)+))
+    };
+}
+
+/// Registered versions of one tool, oldest first
+type ToolVersions = Vec<Arc<dyn Tool>>;
+
 /// Thread-safe registry for managing tool collections across multiple agents and providers.
 ///
 /// The `ToolRegistry` serves as the central hub for tool management, providing
@@ -419,8 +830,21 @@ pub enum ToolError {
 /// - **Clone Friendly** - Cheap cloning enables easy sharing between agents
 #[derive(Clone)]
 pub struct ToolRegistry {
+    /// The version of each tool offered to models
     tools: Arc<RwLock<HashMap<String, Arc<dyn Tool>>>>,
+    /// Every registered version of each tool, oldest first
+    versions: Arc<RwLock<HashMap<String, ToolVersions>>>,
+    /// Versions pinned by name instead of the latest
+    pins: Arc<RwLock<HashMap<String, String>>>,
     middleware: Arc<RwLock<MiddlewareStack>>,
+    reliability: ToolReliabilityTracker,
+    audit_log: Arc<RwLock<Option<AuditLog>>>,
+    guardrails: Arc<RwLock<ToolGuardrails>>,
+    description_context: Arc<RwLock<DescriptionContext>>,
+    /// Fixtures of a dry-run registry; only this clone simulates its tools
+    simulation: Option<ToolFixtures>,
+    /// Verifies tool manifests before registration, when set
+    manifest_verifier: Arc<RwLock<Option<Arc<ManifestVerifier>>>>,
 }
 
 impl ToolRegistry {
@@ -428,10 +852,107 @@ impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: Arc::new(RwLock::new(HashMap::new())),
+            versions: Arc::new(RwLock::new(HashMap::new())),
+            pins: Arc::new(RwLock::new(HashMap::new())),
             middleware: Arc::new(RwLock::new(MiddlewareStack::new())),
+            reliability: ToolReliabilityTracker::new(),
+            audit_log: Arc::new(RwLock::new(None)),
+            guardrails: Arc::new(RwLock::new(ToolGuardrails::new())),
+            description_context: Arc::new(RwLock::new(DescriptionContext::new())),
+            simulation: None,
+            manifest_verifier: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// A clone of this registry that simulates tool calls instead of executing them
+    ///
+    /// Calls are answered from `fixtures`, then [`Tool::simulate`], then a
+    /// generic simulated result. Middleware and guardrails still run; nothing
+    /// is written to the audit log. The registry itself is unaffected.
+    pub fn dry_run(&self, fixtures: ToolFixtures) -> Self {
+        Self {
+            simulation: Some(fixtures),
+            ..self.clone()
+        }
+    }
+
+    /// A clone of this registry offering only the tools named in `names`
+    ///
+    /// Middleware, guardrails, the audit log and reliability history are
+    /// shared with this registry; tools registered later are not.
+    pub async fn restricted_to(&self, names: &[String]) -> Self {
+        let tools = self
+            .tools
+            .read()
+            .await
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .map(|(name, tool)| (name.clone(), tool.clone()))
+            .collect();
+        Self {
+            tools: Arc::new(RwLock::new(tools)),
+            ..self.clone()
+        }
+    }
+
+    /// Whether tool calls are simulated (see [`dry_run`](Self::dry_run))
+    pub fn is_dry_run(&self) -> bool {
+        self.simulation.is_some()
+    }
+
+    /// Wrap `tool` in a [`SimulatedTool`] if this is a dry-run registry
+    fn for_execution(&self, tool: &Arc<dyn Tool>) -> Arc<dyn Tool> {
+        match &self.simulation {
+            Some(fixtures) => Arc::new(SimulatedTool::new(tool.clone(), fixtures.clone())),
+            None => tool.clone(),
+        }
+    }
+
+    /// Success rates and last errors of the tools called by agents using this registry
+    ///
+    /// Shared by clones of the registry, so the history is kept across executions.
+    pub fn reliability(&self) -> &ToolReliabilityTracker {
+        &self.reliability
+    }
+
+    /// Record every tool execution in `audit_log` (see [`audit`])
+    ///
+    /// Replaces any audit log set before; clones of the registry share it.
+    pub async fn set_audit_log(&self, audit_log: AuditLog) {
+        *self.audit_log.write().await = Some(audit_log);
+    }
+
+    /// The audit log tool executions are recorded in, if any
+    pub async fn audit_log(&self) -> Option<AuditLog> {
+        self.audit_log.read().await.clone()
+    }
+
+    /// Check tool parameters against `guardrails` before every execution
+    ///
+    /// Added to the guardrails already set. A call that violates a guardrail is
+    /// not executed and returns an error result explaining the violation.
+    pub async fn add_guardrails(&self, guardrails: ToolGuardrails) {
+        self.guardrails.write().await.extend(guardrails);
+    }
+
+    /// Set a variable for templated tool descriptions (see [`templating`])
+    ///
+    /// Takes effect the next time tool schemas are generated; clones of the
+    /// registry share the variables.
+    pub async fn set_description_variable(&self, name: impl Into<String>, value: impl ToString) {
+        self.description_context.write().await.set(name, value);
+    }
+
+    /// Set all variables of `variables` (see [`set_description_variable`](Self::set_description_variable))
+    pub async fn add_description_variables(&self, variables: &DescriptionContext) {
+        self.description_context.write().await.extend(variables);
+    }
+
+    /// The variables tool descriptions are rendered with
+    pub async fn description_context(&self) -> DescriptionContext {
+        self.description_context.read().await.clone()
+    }
+
     /// Add middleware to the tool registry.
     ///
     /// Middleware is executed in registration order for `before_tool`
@@ -462,6 +983,49 @@ impl ToolRegistry {
         stack.add(middleware);
     }
 
+    /// Refuse to register tools whose manifest doesn't verify
+    ///
+    /// Applies to tools registered from now on, in this registry and its
+    /// clones; use [`verify_manifests`](Self::verify_manifests) to check the
+    /// ones already registered. See [`manifest`].
+    pub async fn set_manifest_verifier(&self, verifier: ManifestVerifier) {
+        *self.manifest_verifier.write().await = Some(Arc::new(verifier));
+    }
+
+    /// The manifest verifier, if one is set
+    pub async fn manifest_verifier(&self) -> Option<Arc<ManifestVerifier>> {
+        self.manifest_verifier.read().await.clone()
+    }
+
+    /// Check every registered version of every tool against the manifest
+    /// verifier
+    ///
+    /// Catches tools registered before the verifier was set and tools whose
+    /// schema changed since registration. Returns nothing if no verifier is
+    /// set.
+    pub async fn verify_manifests(&self) -> Vec<manifest::ManifestViolation> {
+        let Some(verifier) = self.manifest_verifier().await else {
+            return Vec::new();
+        };
+        let versions = self.versions.read().await;
+        let mut violations: Vec<_> = versions
+            .values()
+            .flatten()
+            .filter_map(|tool| {
+                verifier
+                    .verify(tool.as_ref())
+                    .err()
+                    .map(|error| manifest::ManifestViolation {
+                        tool: tool.name().to_string(),
+                        version: tool.version().to_string(),
+                        error,
+                    })
+            })
+            .collect();
+        violations.sort_by(|a, b| (&a.tool, &a.version).cmp(&(&b.tool, &b.version)));
+        violations
+    }
+
     /// Check if middleware is configured
     pub async fn has_middleware(&self) -> bool {
         let stack = self.middleware.read().await;
@@ -471,7 +1035,9 @@ impl ToolRegistry {
     /// Register a new tool in the registry for use by agents.
     ///
     /// Adds a tool to the registry, making it available for execution by any agent
-    /// using this registry. Tool names must be unique within the registry.
+    /// using this registry. A name can be registered again with a different
+    /// [`Tool::version`]; models are offered the latest version unless another
+    /// one is pinned (see [`versioning`]).
     ///
     /// # Arguments
     ///
@@ -479,8 +1045,10 @@ impl ToolRegistry {
     ///
     /// # Returns
     ///
-    /// Success on successful registration, or [`ToolError::DuplicateTool`]
-    /// if a tool with the same name already exists.
+    /// Success on successful registration, [`ToolError::DuplicateTool`]
+    /// if a tool with the same name and version already exists, or
+    /// [`ToolError::ManifestRejected`] if a [manifest verifier](Self::set_manifest_verifier)
+    /// refuses the tool.
     ///
     /// # Examples
     ///
@@ -515,7 +1083,7 @@ impl ToolRegistry {
     /// // First registration succeeds
     /// registry.register_tool(tool1).await.unwrap();
     ///
-    /// // Second registration with same name fails
+    /// // Second registration with same name and version fails
     /// match registry.register_tool(tool2).await {
     ///     Err(ToolError::DuplicateTool { name }) => {
     ///         println!("Tool '{}' already registered", name);
@@ -531,44 +1099,103 @@ impl ToolRegistry {
     /// no race conditions during registration even with concurrent access.
     pub async fn register_tool(&self, tool: Box<dyn Tool>) -> Result<(), ToolError> {
         let tool_name = tool.name().to_string();
-        let tool_arc = Arc::from(tool);
+        if let Some(verifier) = self.manifest_verifier().await {
+            verifier
+                .verify(tool.as_ref())
+                .map_err(|e| ToolError::ManifestRejected {
+                    name: tool_name.clone(),
+                    reason: e.to_string(),
+                })?;
+        }
+        let tool_arc: Arc<dyn Tool> = Arc::from(tool);
 
         let mut tools = self.tools.write().await;
+        let mut versions = self.versions.write().await;
+        let registered = versions.entry(tool_name.clone()).or_default();
 
-        // Check for duplicate names
-        if tools.contains_key(&tool_name) {
+        // Check for duplicate name and version
+        if registered
+            .iter()
+            .any(|other| other.version() == tool_arc.version())
+        {
             return Err(ToolError::DuplicateTool { name: tool_name });
         }
 
-        tools.insert(tool_name.clone(), tool_arc);
+        let position = registered.partition_point(|other| {
+            versioning::compare_versions(other.version(), tool_arc.version()).is_lt()
+        });
+        registered.insert(position, tool_arc.clone());
+
+        // Offer the latest version unless another one is pinned
+        let is_latest = position + 1 == registered.len();
+        if is_latest && !self.pins.read().await.contains_key(&tool_name) {
+            tools.insert(tool_name.clone(), tool_arc.clone());
+        }
 
-        tracing::info!("Registered unified tool: {}", tool_name);
+        if let Some(reason) = tool_arc.deprecation() {
+            tracing::warn!(
+                "Registered deprecated tool {} {}: {}",
+                tool_name,
+                tool_arc.version(),
+                reason
+            );
+        }
+        tracing::info!(
+            "Registered unified tool: {} {}",
+            tool_name,
+            tool_arc.version()
+        );
         Ok(())
     }
 
     /// Get tool schemas for LLM consumption
     pub async fn get_tool_schemas(&self) -> Vec<Value> {
+        self.get_tool_schemas_with_context(&DescriptionContext::new())
+            .await
+    }
+
+    /// Get tool schemas, rendering descriptions with the registry's variables
+    /// and then `defaults`
+    pub async fn get_tool_schemas_with_context(&self, defaults: &DescriptionContext) -> Vec<Value> {
+        let context = self.description_context().await.with_defaults(defaults);
         let tools = self.tools.read().await;
         tools
             .values()
             .map(|tool| {
                 serde_json::json!({
                     "name": tool.name(),
-                    "description": tool.description(),
+                    "description": context.render(tool.description()),
                     "input_schema": tool.parameters_schema()
                 })
             })
             .collect()
     }
 
+    /// Get the annotations of a registered tool
+    pub async fn get_tool_annotations(&self, name: &str) -> Option<ToolAnnotations> {
+        let tools = self.tools.read().await;
+        tools.get(name).map(|tool| tool.annotations())
+    }
+
     /// Convert tool registry to LLM Tool format for provider consumption
     pub async fn to_llm_tools(&self) -> Vec<crate::llm::traits::Tool> {
+        self.to_llm_tools_with_context(&DescriptionContext::new())
+            .await
+    }
+
+    /// Convert to LLM tools, rendering descriptions with the registry's
+    /// variables and then `defaults`
+    pub async fn to_llm_tools_with_context(
+        &self,
+        defaults: &DescriptionContext,
+    ) -> Vec<crate::llm::traits::Tool> {
+        let context = self.description_context().await.with_defaults(defaults);
         let tools = self.tools.read().await;
         tools
             .values()
             .map(|tool| crate::llm::traits::Tool {
                 name: tool.name().to_string(),
-                description: tool.description().to_string(),
+                description: context.render(tool.description()),
                 input_schema: tool.parameters_schema(),
             })
             .collect()
@@ -655,6 +1282,7 @@ impl ToolRegistry {
         let tool = tools.get(name).ok_or_else(|| ToolError::ToolNotFound {
             name: name.to_string(),
         })?;
+        let tool = self.for_execution(tool);
 
         if !tool.is_available() {
             return Err(ToolError::ToolNotAvailable {
@@ -667,7 +1295,8 @@ impl ToolRegistry {
             ToolContext::from_agent_context(agent_ctx)
         } else {
             ToolContext::new("unknown".to_string())
-        };
+        }
+        .with_annotations(tool.annotations());
 
         // Get middleware stack
         let middleware_stack = self.middleware.read().await;
@@ -679,6 +1308,7 @@ impl ToolRegistry {
             .await;
 
         // Handle middleware action
+        let mut executed_params = None;
         let result = match action {
             ToolMiddlewareAction::Continue | ToolMiddlewareAction::ModifyParams(_) => {
                 // Execute tool with (potentially modified) parameters
@@ -687,7 +1317,43 @@ impl ToolRegistry {
                 } else {
                     parameters
                 };
-                tool.execute(exec_params, agent_context).await?
+                let mut exec_params = exec_params;
+                let mut rounds = 0;
+                // Runs again with the user's answer while the tool asks for input
+                loop {
+                    let audited_params = exec_params.clone().unwrap_or(Value::Null);
+                    if let Err(violation) =
+                        self.guardrails.read().await.check(name, &audited_params)
+                    {
+                        tracing::info!("Tool {} blocked by guardrail: {}", name, violation);
+                        return Ok(ToolResult::error(violation.to_string()));
+                    }
+                    let mut result = match tool.execute(exec_params.clone(), agent_context).await {
+                        Ok(result) => result,
+                        Err(e) => {
+                            if !self.is_dry_run() {
+                                self.audit(name, &audited_params, agent_context, Err(&e))
+                                    .await;
+                            }
+                            return Err(e);
+                        }
+                    };
+                    if rounds < elicitation::MAX_ELICITATION_ROUNDS
+                        && elicitation::answer_input_request(
+                            &mut result,
+                            &mut exec_params,
+                            agent_context,
+                            name,
+                            None,
+                        )
+                        .await
+                    {
+                        rounds += 1;
+                        continue;
+                    }
+                    executed_params = Some(audited_params);
+                    break result;
+                }
             }
             ToolMiddlewareAction::Abort { reason, synthetic_result } => {
                 tracing::info!("Tool {} aborted by middleware: {}", name, reason);
@@ -705,15 +1371,38 @@ impl ToolRegistry {
             .await;
 
         // Handle after-tool action
-        match after_action {
-            AfterToolAction::PassThrough => Ok(final_result),
-            AfterToolAction::ModifyResult(modified) => Ok(modified),
+        let final_result = match after_action {
+            AfterToolAction::PassThrough => final_result,
+            AfterToolAction::ModifyResult(modified) => modified,
             AfterToolAction::InjectContext(context) => {
                 // For now, log the context injection - actual injection requires
                 // changes to the agent event loop to handle the injected context
                 tracing::debug!("Middleware injected context: {}", context);
-                Ok(final_result)
+                final_result
             }
+        };
+
+        if let Some(params) = executed_params.filter(|_| !self.is_dry_run()) {
+            self.audit(name, &params, agent_context, Ok(&final_result))
+                .await;
+        }
+        Ok(final_result)
+    }
+
+    /// Append a tool execution to the audit log, if one is set
+    async fn audit(
+        &self,
+        name: &str,
+        params: &Value,
+        agent_context: Option<&crate::agent::AgentContext>,
+        outcome: Result<&ToolResult, &ToolError>,
+    ) {
+        let Some(audit_log) = self.audit_log.read().await.clone() else {
+            return;
+        };
+        let agent_id = agent_context.map(|ctx| ctx.agent_id.as_str());
+        if let Err(e) = audit_log.record(name, agent_id, params, outcome).await {
+            tracing::error!("Failed to record audit entry for tool {}: {}", name, e);
         }
     }
 
@@ -731,7 +1420,17 @@ impl ToolRegistry {
 
     /// Get tool configuration for LLM integration (compatibility method)
     pub async fn get_tool_config(&self) -> crate::types::tools::ToolConfig {
-        let schemas = self.get_tool_schemas().await;
+        self.get_tool_config_with_context(&DescriptionContext::new())
+            .await
+    }
+
+    /// Get tool configuration, rendering descriptions with the registry's
+    /// variables and then `defaults`
+    pub async fn get_tool_config_with_context(
+        &self,
+        defaults: &DescriptionContext,
+    ) -> crate::types::tools::ToolConfig {
+        let schemas = self.get_tool_schemas_with_context(defaults).await;
         let tools: Vec<crate::types::tools::Tool> = schemas
             .into_iter()
             .map(|schema| {
@@ -749,9 +1448,11 @@ impl ToolRegistry {
     }
 
     /// Get a tool by name for direct execution
+    ///
+    /// On a dry-run registry the tool is wrapped in a [`SimulatedTool`].
     pub async fn get_tool(&self, name: &str) -> Option<Arc<dyn Tool>> {
         let tools = self.tools.read().await;
-        tools.get(name).cloned()
+        tools.get(name).map(|tool| self.for_execution(tool))
     }
 }
 
@@ -1208,4 +1909,54 @@ mod tests {
         assert_eq!(schema["description"], "Schema test tool");
         assert!(schema["input_schema"].is_object());
     }
+
+    #[test]
+    fn test_tool_annotations_follow_mcp_defaults() {
+        let unannotated = ToolAnnotations::default();
+        assert!(unannotated.is_empty());
+        assert!(!unannotated.is_read_only());
+        assert!(unannotated.is_destructive());
+        assert!(unannotated.is_open_world());
+
+        let read_only = ToolAnnotations::read_only().with_title("Search");
+        assert!(!read_only.is_destructive());
+        assert!(read_only.is_idempotent());
+        assert_eq!(
+            serde_json::to_value(&read_only).unwrap(),
+            json!({
+                "title": "Search",
+                "readOnlyHint": true,
+                "destructiveHint": false,
+                "idempotentHint": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_tool_error_source_downcasts_through_chain() {
+        #[derive(Debug, thiserror::Error)]
+        #[error("quota exhausted")]
+        struct QuotaExhausted;
+
+        #[derive(Debug, thiserror::Error)]
+        #[error("billing lookup failed")]
+        struct BillingError(#[source] QuotaExhausted);
+
+        let error = crate::tool_error!(BillingError(QuotaExhausted));
+        assert_eq!(
+            error.to_string(),
+            "Tool execution failed: billing lookup failed"
+        );
+        assert!(error.downcast_ref::<QuotaExhausted>().is_some());
+        assert!(ToolError::execution_failed("plain")
+            .downcast_ref::<QuotaExhausted>()
+            .is_none());
+
+        let result = ToolResult {
+            source: error.error_source().cloned(),
+            ..ToolResult::error(error.to_string())
+        };
+        assert!(result.downcast_error::<BillingError>().is_some());
+        assert_eq!(result.clone().source, result.source);
+    }
 }
@@ -0,0 +1,588 @@
+//! Egress control for tools that make HTTP requests.
+//!
+//! A [`NetworkPolicy`] constrains what agents can reach: an allowlist of
+//! hosts, whether private and loopback addresses are off limits, the largest
+//! response body a tool will read, and TLS requirements. One policy is
+//! installed process-wide with [`set_network_policy`] and enforced by
+//! [`HttpRequestTool`](super::builtin::HttpRequestTool),
+//! [`WebPageTool`](super::builtin::WebPageTool) and any tool that builds its
+//! client through [`NetworkPolicy::client_for`].
+//!
+//! ```
+//! use stood::tools::network::NetworkPolicy;
+//!
+//! let policy = NetworkPolicy::new()
+//!     .with_allowed_hosts(["api.example.com", "*.example.org"])
+//!     .with_deny_private_ranges(true)
+//!     .with_require_https(true);
+//!
+//! assert!(policy.check_url("https://docs.example.org/guide").is_ok());
+//! assert!(policy.check_url("http://api.example.com/").is_err());
+//! let violation = policy.check_url("https://evil.test/").unwrap_err();
+//! assert_eq!(
+//!     violation.to_string(),
+//!     "Request to 'https://evil.test/' blocked by network policy: host 'evil.test' is not on the allowlist"
+//! );
+//! ```
+//!
+//! The default policy allows everything, so installing nothing keeps the
+//! previous behavior.
+//!
+//! Host names are resolved before connecting and the client is pinned to the
+//! addresses that passed the policy, so a name can't be rebound to a private
+//! address between the check and the connection. Redirects are checked hop by
+//! hop; when private ranges are denied, a redirect to a different host name is
+//! refused because its addresses can't be checked up front. Requests to hosts
+//! not bypassing the installed [proxy](crate::proxy) are connected by the
+//! proxy, so there the check covers the addresses stood resolves, not the
+//! ones the proxy connects to.
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use url::{Host, Url};
+
+/// Timeout applied to clients built by [`NetworkPolicy::client_for`]
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Redirects followed when the policy doesn't set a limit, as in reqwest
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+static POLICY: Lazy<RwLock<Arc<NetworkPolicy>>> =
+    Lazy::new(|| RwLock::new(Arc::new(NetworkPolicy::default())));
+
+/// Install the network policy enforced by HTTP tools
+pub fn set_network_policy(policy: NetworkPolicy) {
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(policy);
+}
+
+/// The installed network policy, allowing everything by default
+pub fn network_policy() -> Arc<NetworkPolicy> {
+    POLICY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Minimum TLS version for HTTPS connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsVersion {
+    /// TLS 1.2
+    Tls1_2,
+    /// TLS 1.3
+    Tls1_3,
+}
+
+impl TlsVersion {
+    fn to_reqwest(self) -> reqwest::tls::Version {
+        match self {
+            TlsVersion::Tls1_2 => reqwest::tls::Version::TLS_1_2,
+            TlsVersion::Tls1_3 => reqwest::tls::Version::TLS_1_3,
+        }
+    }
+}
+
+/// One allowlist or proxy bypass entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HostPattern {
+    /// Exact host name
+    Domain(String),
+    /// `*.domain`: the domain and its subdomains
+    Subdomains(String),
+    /// IP address or CIDR range
+    Network(IpAddr, u8),
+}
+
+impl HostPattern {
+    pub(crate) fn parse(pattern: &str) -> Self {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        if let Some(domain) = pattern.strip_prefix("*.") {
+            return HostPattern::Subdomains(domain.to_string());
+        }
+        let (address, prefix) = match pattern.split_once('/') {
+            Some((address, prefix)) => (address, prefix.parse().ok()),
+            None => (pattern.as_str(), None),
+        };
+        let address = address.trim_start_matches('[').trim_end_matches(']');
+        match IpAddr::from_str(address) {
+            Ok(ip) => {
+                let max = if ip.is_ipv4() { 32 } else { 128 };
+                HostPattern::Network(ip, prefix.unwrap_or(max).min(max))
+            }
+            Err(_) => HostPattern::Domain(pattern),
+        }
+    }
+
+    pub(crate) fn matches_name(&self, host: &str) -> bool {
+        match self {
+            HostPattern::Domain(domain) => host == domain,
+            HostPattern::Subdomains(domain) => {
+                host == domain || host.ends_with(&format!(".{}", domain))
+            }
+            HostPattern::Network(..) => false,
+        }
+    }
+
+    pub(crate) fn matches_ip(&self, ip: IpAddr) -> bool {
+        let HostPattern::Network(network, prefix) = self else {
+            return false;
+        };
+        match (canonical(*network), canonical(ip)) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - *prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - *prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// What the network policy allows HTTP tools to reach
+#[derive(Debug, Clone, Default)]
+pub struct NetworkPolicy {
+    allowed_hosts: Vec<HostPattern>,
+    deny_private_ranges: bool,
+    max_response_bytes: Option<usize>,
+    require_https: bool,
+    min_tls_version: Option<TlsVersion>,
+    max_redirects: Option<usize>,
+}
+
+impl NetworkPolicy {
+    /// A policy that allows everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allow these hosts
+    ///
+    /// Entries are host names, `*.domain` for a domain and its subdomains, IP
+    /// addresses, or CIDR ranges such as `10.1.0.0/16`. A host name that isn't
+    /// listed is still allowed if it resolves into a listed range. With no
+    /// entries every host is allowed.
+    pub fn with_allowed_hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.allowed_hosts = hosts
+            .into_iter()
+            .map(|host| HostPattern::parse(host.as_ref()))
+            .collect();
+        self
+    }
+
+    /// Block loopback, private, link-local and other non-public addresses
+    ///
+    /// This also covers cloud metadata endpoints such as `169.254.169.254`.
+    pub fn with_deny_private_ranges(mut self, deny: bool) -> Self {
+        self.deny_private_ranges = deny;
+        self
+    }
+
+    /// Largest response body a tool will read
+    pub fn with_max_response_bytes(mut self, max: usize) -> Self {
+        self.max_response_bytes = Some(max);
+        self
+    }
+
+    /// Only allow `https` URLs, including on redirects
+    pub fn with_require_https(mut self, require: bool) -> Self {
+        self.require_https = require;
+        self
+    }
+
+    /// Refuse HTTPS connections below this TLS version
+    pub fn with_min_tls_version(mut self, version: TlsVersion) -> Self {
+        self.min_tls_version = Some(version);
+        self
+    }
+
+    /// Follow at most this many redirects
+    pub fn with_max_redirects(mut self, max: usize) -> Self {
+        self.max_redirects = Some(max);
+        self
+    }
+
+    /// Largest response body a tool will read, if limited
+    pub fn max_response_bytes(&self) -> Option<usize> {
+        self.max_response_bytes
+    }
+
+    /// Most redirects a client follows
+    pub fn max_redirects(&self) -> usize {
+        self.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS)
+    }
+
+    /// Check the scheme and host of a URL without resolving it
+    ///
+    /// Host names that only an IP range could allow pass here and are
+    /// checked by [`resolve`](Self::resolve).
+    pub fn check_url(&self, url: &str) -> Result<Url, NetworkPolicyViolation> {
+        let parsed = Url::parse(url).map_err(|e| NetworkPolicyViolation {
+            url: url.to_string(),
+            reason: format!("invalid URL: {}", e),
+        })?;
+        self.check_parsed(&parsed)?;
+        Ok(parsed)
+    }
+
+    /// Whether the policy allows connecting to `ip`
+    pub fn allows_ip(&self, ip: IpAddr) -> bool {
+        if self.deny_private_ranges && !is_public(ip) {
+            return false;
+        }
+        self.allowed_hosts.is_empty() || self.allowed_hosts.iter().any(|p| p.matches_ip(ip))
+    }
+
+    /// Resolve the host of `url` to the addresses the policy allows
+    ///
+    /// Fails if none of the resolved addresses are allowed.
+    pub async fn resolve(&self, url: &Url) -> Result<Vec<SocketAddr>, NetworkPolicyViolation> {
+        self.check_parsed(url)?;
+        let port = url.port_or_known_default().unwrap_or(80);
+        let name = match url.host() {
+            Some(Host::Domain(name)) => name.to_ascii_lowercase(),
+            Some(Host::Ipv4(ip)) => return Ok(vec![SocketAddr::new(IpAddr::V4(ip), port)]),
+            Some(Host::Ipv6(ip)) => return Ok(vec![SocketAddr::new(IpAddr::V6(ip), port)]),
+            None => return Err(self.violation(url, "URL has no host")),
+        };
+        let addresses = tokio::net::lookup_host((name.as_str(), port))
+            .await
+            .map_err(|e| self.violation(url, format!("could not resolve '{}': {}", name, e)))?;
+
+        let name_allowed = self.allows_name(&name);
+        let allowed: Vec<SocketAddr> = addresses
+            .filter(|address| {
+                let ip = address.ip();
+                if self.deny_private_ranges && !is_public(ip) {
+                    return false;
+                }
+                name_allowed || self.allowed_hosts.iter().any(|p| p.matches_ip(ip))
+            })
+            .collect();
+        if allowed.is_empty() {
+            return Err(self.violation(
+                url,
+                format!("'{}' does not resolve to an allowed address", name),
+            ));
+        }
+        Ok(allowed)
+    }
+
+    /// A client builder carrying the policy's TLS, HTTPS and redirect rules
+    /// and the installed [proxy](crate::proxy)
+    ///
+    /// Use [`client_for`](Self::client_for) instead where possible; this
+    /// builder doesn't pin resolved addresses.
+    pub fn client_builder(&self) -> reqwest::ClientBuilder {
+        let mut builder = reqwest::Client::builder()
+            .https_only(self.require_https)
+            .redirect(self.redirect_policy(None));
+        if let Some(version) = self.min_tls_version {
+            builder = builder.min_tls_version(version.to_reqwest());
+        }
+        if let Some(proxy) = crate::proxy::proxy() {
+            builder = builder.proxy(proxy.to_reqwest());
+        }
+        builder
+    }
+
+    /// A client for requests to `url` that enforces the policy
+    ///
+    /// Checks and resolves the host of `url` and pins the client to the
+    /// allowed addresses. Applies a 30 second timeout, which callers can
+    /// override per request.
+    pub async fn client_for(&self, url: &str) -> Result<reqwest::Client, NetworkPolicyError> {
+        let parsed = self.check_url(url)?;
+        let mut builder = self
+            .client_builder()
+            .redirect(self.redirect_policy(parsed.host_str()))
+            .timeout(DEFAULT_REQUEST_TIMEOUT);
+        if let Some(Host::Domain(name)) = parsed.host() {
+            let addresses = self.resolve(&parsed).await?;
+            builder = builder.resolve_to_addrs(name, &addresses);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Read a response body, failing if it exceeds the policy limit
+    pub async fn read_body(
+        &self,
+        mut response: reqwest::Response,
+    ) -> Result<Vec<u8>, NetworkPolicyError> {
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if let Some(max) = self.max_response_bytes {
+                if body.len() + chunk.len() > max {
+                    return Err(NetworkPolicyError::Violation(NetworkPolicyViolation {
+                        url: response.url().to_string(),
+                        reason: format!("response body exceeds {} bytes", max),
+                    }));
+                }
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    fn allows_name(&self, host: &str) -> bool {
+        self.allowed_hosts.is_empty() || self.allowed_hosts.iter().any(|p| p.matches_name(host))
+    }
+
+    fn has_ip_patterns(&self) -> bool {
+        self.allowed_hosts
+            .iter()
+            .any(|p| matches!(p, HostPattern::Network(..)))
+    }
+
+    fn check_parsed(&self, url: &Url) -> Result<(), NetworkPolicyViolation> {
+        match url.scheme() {
+            "https" => {}
+            "http" if !self.require_https => {}
+            "http" => return Err(self.violation(url, "only https URLs are allowed")),
+            other => return Err(self.violation(url, format!("scheme '{}' is not allowed", other))),
+        }
+        let ip = match url.host() {
+            Some(Host::Domain(name)) => {
+                let name = name.to_ascii_lowercase();
+                if self.allows_name(&name) || self.has_ip_patterns() {
+                    return Ok(());
+                }
+                return Err(self.violation(url, format!("host '{}' is not on the allowlist", name)));
+            }
+            Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+            Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+            None => return Err(self.violation(url, "URL has no host")),
+        };
+        if self.deny_private_ranges && !is_public(ip) {
+            return Err(self.violation(url, format!("address {} is not public", ip)));
+        }
+        if !self.allows_ip(ip) {
+            return Err(self.violation(url, format!("address {} is not on the allowlist", ip)));
+        }
+        Ok(())
+    }
+
+    /// Redirect policy checking every hop
+    ///
+    /// `pinned_host` is the host whose addresses were checked up front.
+    fn redirect_policy(&self, pinned_host: Option<&str>) -> reqwest::redirect::Policy {
+        let policy = self.clone();
+        let pinned_host = pinned_host.map(str::to_ascii_lowercase);
+        let max_redirects = self.max_redirects();
+        reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() > max_redirects {
+                return attempt.error(format!("too many redirects (limit {})", max_redirects));
+            }
+            if let Err(violation) = policy.check_parsed(attempt.url()) {
+                return attempt.error(violation);
+            }
+            let unchecked_name = match attempt.url().host() {
+                Some(Host::Domain(name)) => {
+                    pinned_host.as_deref() != Some(name.to_ascii_lowercase().as_str())
+                }
+                _ => false,
+            };
+            if unchecked_name
+                && (policy.deny_private_ranges || !policy.allows_name_strictly(attempt.url()))
+            {
+                let violation = policy.violation(
+                    attempt.url(),
+                    "redirect to another host name can't be checked against the address rules",
+                );
+                return attempt.error(violation);
+            }
+            attempt.follow()
+        })
+    }
+
+    /// Whether the host name of `url` is allowed without resolving it
+    fn allows_name_strictly(&self, url: &Url) -> bool {
+        url.host_str()
+            .map(|host| self.allows_name(&host.to_ascii_lowercase()))
+            .unwrap_or(false)
+    }
+
+    fn violation(&self, url: &Url, reason: impl Into<String>) -> NetworkPolicyViolation {
+        NetworkPolicyViolation {
+            url: url.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// A request the network policy does not allow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkPolicyViolation {
+    /// The rejected URL
+    pub url: String,
+    /// Why it was rejected
+    pub reason: String,
+}
+
+impl fmt::Display for NetworkPolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Request to '{}' blocked by network policy: {}",
+            self.url, self.reason
+        )
+    }
+}
+
+impl std::error::Error for NetworkPolicyViolation {}
+
+/// Failure to build a client or read a response under a network policy
+#[derive(Debug, thiserror::Error)]
+pub enum NetworkPolicyError {
+    #[error(transparent)]
+    Violation(#[from] NetworkPolicyViolation),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// Map IPv4-mapped IPv6 addresses to IPv4
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => IpAddr::V6(v6),
+        },
+        v4 => v4,
+    }
+}
+
+/// Whether `ip` is a publicly routable address
+fn is_public(ip: IpAddr) -> bool {
+    match canonical(ip) {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => is_public_v6(ip),
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8, carrier-grade NAT 100.64.0.0/10 and reserved 240.0.0.0/4
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local fc00::/7 and link-local fe80::/10
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_url() {
+        let policy = NetworkPolicy::new()
+            .with_allowed_hosts(["*.example.com", "10.1.0.0/16"])
+            .with_deny_private_ranges(true)
+            .with_require_https(true);
+
+        assert!(policy.check_url("https://example.com/").is_ok());
+        assert!(policy.check_url("https://API.example.com/v1").is_ok());
+        // Names outside the domain list may still resolve into 10.1.0.0/16,
+        // which resolve() checks
+        assert!(policy.check_url("https://other.test/").is_ok());
+
+        let reason = |url: &str| policy.check_url(url).unwrap_err().reason;
+        assert_eq!(reason("http://example.com/"), "only https URLs are allowed");
+        assert_eq!(reason("ftp://example.com/"), "scheme 'ftp' is not allowed");
+        assert_eq!(
+            reason("https://10.1.2.3/"),
+            "address 10.1.2.3 is not public"
+        );
+        assert_eq!(
+            reason("https://[::ffff:169.254.169.254]/"),
+            "address ::ffff:169.254.169.254 is not public"
+        );
+        assert_eq!(
+            reason("https://8.8.8.8/"),
+            "address 8.8.8.8 is not on the allowlist"
+        );
+
+        let domains_only = NetworkPolicy::new().with_allowed_hosts(["example.com"]);
+        assert_eq!(
+            domains_only
+                .check_url("https://sub.example.com/")
+                .unwrap_err()
+                .reason,
+            "host 'sub.example.com' is not on the allowlist"
+        );
+        assert!(NetworkPolicy::new()
+            .check_url("http://127.0.0.1:8080/")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_address_rules() {
+        for private in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+        ] {
+            assert!(
+                !is_public(private.parse().unwrap()),
+                "{} should be private",
+                private
+            );
+        }
+        for public in ["8.8.8.8", "2606:4700::1111"] {
+            assert!(
+                is_public(public.parse().unwrap()),
+                "{} should be public",
+                public
+            );
+        }
+
+        let policy = NetworkPolicy::new().with_allowed_hosts(["203.0.113.0/24", "2001:db8::/32"]);
+        assert!(policy.allows_ip("203.0.113.200".parse().unwrap()));
+        assert!(!policy.allows_ip("203.0.114.1".parse().unwrap()));
+        assert!(policy.allows_ip("2001:db8:1::5".parse().unwrap()));
+        assert!(!policy.allows_ip("2001:db9::5".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_filters_addresses() {
+        let policy = NetworkPolicy::new().with_deny_private_ranges(true);
+        let url = Url::parse("http://localhost:8080/").unwrap();
+        let violation = policy.resolve(&url).await.unwrap_err();
+        assert_eq!(
+            violation.reason,
+            "'localhost' does not resolve to an allowed address"
+        );
+
+        let addresses = NetworkPolicy::new().resolve(&url).await.unwrap();
+        assert!(addresses
+            .iter()
+            .all(|a| a.port() == 8080 && a.ip().is_loopback()));
+    }
+}
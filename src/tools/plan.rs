@@ -0,0 +1,536 @@
+//! Built-in planning tool with plan state kept across cycles.
+//!
+//! [`PlanTool`] lets the model write down a structured plan for a long task
+//! (a goal and numbered steps with a status and notes) and update it as it
+//! works. The plan lives in a [`PlanState`] shared through
+//! [`AgentContext::plan`](crate::agent::AgentContext::plan), so it survives
+//! across cycles and executions of the agent. It is returned in
+//! [`AgentResult::plan`](crate::agent::AgentResult::plan), and
+//! [`PlanPromptHook`] can render it into the system prompt before every model
+//! call so the model keeps the plan in view.
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut agent = Agent::builder().with_plan_tool(true).build().await?;
+//! let result = agent
+//!     .execute("Migrate the three services in ./services to the new logging crate")
+//!     .await?;
+//! if let Some(plan) = &result.plan {
+//!     println!("{}", plan.render());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{Tool, ToolAnnotations, ToolError, ToolResult};
+use crate::agent::hooks::CycleHook;
+use crate::agent::CycleHookContext;
+use crate::types::Messages;
+
+/// Marks the start of the plan section appended to the system prompt
+const PLAN_SECTION_START: &str = "<current_plan>";
+/// Marks the end of the plan section appended to the system prompt
+const PLAN_SECTION_END: &str = "</current_plan>";
+
+/// Progress of a plan step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// Not started
+    #[default]
+    Pending,
+    /// Being worked on
+    InProgress,
+    /// Done
+    Completed,
+    /// Cannot continue until something changes
+    Blocked,
+    /// Deliberately not done
+    Skipped,
+}
+
+impl fmt::Display for StepStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StepStatus::Pending => "pending",
+            StepStatus::InProgress => "in_progress",
+            StepStatus::Completed => "completed",
+            StepStatus::Blocked => "blocked",
+            StepStatus::Skipped => "skipped",
+        })
+    }
+}
+
+/// One step of a [`Plan`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// 1-based step number
+    pub id: u32,
+    /// What the step does
+    pub title: String,
+    /// Progress of the step
+    pub status: StepStatus,
+    /// Findings or reasons recorded by the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// A task plan written by the model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    /// What the plan achieves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
+    /// Steps in order
+    pub steps: Vec<PlanStep>,
+    /// When the plan last changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Plan {
+    /// Whether the plan has no goal and no steps
+    pub fn is_empty(&self) -> bool {
+        self.goal.is_none() && self.steps.is_empty()
+    }
+
+    /// Completed or skipped steps, and the total number of steps
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self
+            .steps
+            .iter()
+            .filter(|step| matches!(step.status, StepStatus::Completed | StepStatus::Skipped))
+            .count();
+        (done, self.steps.len())
+    }
+
+    /// The first step that is in progress, or else the first pending step
+    pub fn current_step(&self) -> Option<&PlanStep> {
+        self.steps
+            .iter()
+            .find(|step| step.status == StepStatus::InProgress)
+            .or_else(|| {
+                self.steps
+                    .iter()
+                    .find(|step| step.status == StepStatus::Pending)
+            })
+    }
+
+    /// Plain-text rendering of the plan, one line per step
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        if let Some(goal) = &self.goal {
+            text.push_str(&format!("Goal: {}\n", goal));
+        }
+        let (done, total) = self.progress();
+        text.push_str(&format!("Progress: {}/{} steps done\n", done, total));
+        for step in &self.steps {
+            text.push_str(&format!("{}. [{}] {}", step.id, step.status, step.title));
+            if let Some(notes) = &step.notes {
+                text.push_str(&format!(" ({})", notes));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    fn push_step(&mut self, title: String, status: StepStatus, notes: Option<String>) {
+        let id = self.steps.last().map_or(1, |step| step.id + 1);
+        self.steps.push(PlanStep {
+            id,
+            title,
+            status,
+            notes,
+        });
+    }
+}
+
+/// Shared handle to the plan of an agent
+///
+/// Clones share the same plan.
+#[derive(Debug, Clone, Default)]
+pub struct PlanState {
+    plan: Arc<RwLock<Plan>>,
+}
+
+impl PlanState {
+    /// An empty plan
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy of the current plan
+    pub fn snapshot(&self) -> Plan {
+        self.plan.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the plan
+    pub fn set(&self, plan: Plan) {
+        *self.plan.write().unwrap_or_else(|e| e.into_inner()) = plan;
+    }
+
+    /// Remove the goal and all steps
+    pub fn clear(&self) {
+        self.set(Plan::default());
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut Plan) -> T) -> T {
+        let mut plan = self.plan.write().unwrap_or_else(|e| e.into_inner());
+        let result = f(&mut plan);
+        plan.updated_at = Some(Utc::now());
+        result
+    }
+}
+
+/// Step as given by the model: a title, or an object with a title, status and notes
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StepInput {
+    Title(String),
+    Full {
+        title: String,
+        #[serde(default)]
+        status: StepStatus,
+        notes: Option<String>,
+    },
+}
+
+impl StepInput {
+    fn into_parts(self) -> (String, StepStatus, Option<String>) {
+        match self {
+            StepInput::Title(title) => (title, StepStatus::Pending, None),
+            StepInput::Full {
+                title,
+                status,
+                notes,
+            } => (title, status, notes),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum PlanAction {
+    Create {
+        goal: Option<String>,
+        steps: Vec<StepInput>,
+    },
+    AddSteps {
+        steps: Vec<StepInput>,
+    },
+    UpdateStep {
+        step_id: u32,
+        status: Option<StepStatus>,
+        notes: Option<String>,
+        title: Option<String>,
+    },
+    View,
+}
+
+/// Tool the model uses to create, update and read its task plan
+///
+/// The plan is read from and written to the [`PlanState`] of the calling agent
+/// ([`AgentContext::plan`](crate::agent::AgentContext::plan)); outside an
+/// agent, or for an agent without plan state, the tool's own state is used.
+#[derive(Debug, Clone, Default)]
+pub struct PlanTool {
+    state: PlanState,
+}
+
+impl PlanTool {
+    /// Planning tool with its own, initially empty, plan state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Planning tool using `state` when the caller has no plan state
+    pub fn with_state(state: PlanState) -> Self {
+        Self { state }
+    }
+
+    /// Plan state used when the caller has no plan state
+    pub fn state(&self) -> &PlanState {
+        &self.state
+    }
+}
+
+#[async_trait]
+impl Tool for PlanTool {
+    fn name(&self) -> &str {
+        "plan"
+    }
+
+    fn description(&self) -> &str {
+        "Create and maintain a step-by-step plan for multi-step tasks. Use 'create' to write \
+         the plan before starting, 'update_step' to mark steps in_progress, completed, blocked \
+         or skipped and record notes as you work, 'add_steps' when new work is discovered, and \
+         'view' to read the plan."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let status = json!({
+            "type": "string",
+            "enum": ["pending", "in_progress", "completed", "blocked", "skipped"]
+        });
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["create", "add_steps", "update_step", "view"],
+                    "description": "What to do with the plan"
+                },
+                "goal": {
+                    "type": "string",
+                    "description": "Goal of the plan (create)"
+                },
+                "steps": {
+                    "type": "array",
+                    "description": "Step titles, in order (create, add_steps)",
+                    "items": { "type": "string" }
+                },
+                "step_id": {
+                    "type": "integer",
+                    "description": "Number of the step to update (update_step)"
+                },
+                "status": status,
+                "notes": {
+                    "type": "string",
+                    "description": "Findings or reasons to record on the step (update_step)"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "New title of the step (update_step)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        ToolAnnotations {
+            read_only: Some(false),
+            destructive: Some(false),
+            ..ToolAnnotations::default()
+        }
+        .with_open_world(false)
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<Value>,
+        agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let action: PlanAction = serde_json::from_value(parameters.unwrap_or(Value::Null))
+            .map_err(|e| ToolError::InvalidParameters {
+                message: format!("Invalid plan action: {}", e),
+            })?;
+        let state = agent_context
+            .and_then(|ctx| ctx.plan.as_ref())
+            .unwrap_or(&self.state);
+
+        match action {
+            PlanAction::Create { goal, steps } => state.update(|plan| {
+                *plan = Plan {
+                    goal,
+                    ..Plan::default()
+                };
+                for step in steps {
+                    let (title, status, notes) = step.into_parts();
+                    plan.push_step(title, status, notes);
+                }
+            }),
+            PlanAction::AddSteps { steps } => state.update(|plan| {
+                for step in steps {
+                    let (title, status, notes) = step.into_parts();
+                    plan.push_step(title, status, notes);
+                }
+            }),
+            PlanAction::UpdateStep {
+                step_id,
+                status,
+                notes,
+                title,
+            } => {
+                let found = state.update(|plan| {
+                    let Some(step) = plan.steps.iter_mut().find(|step| step.id == step_id) else {
+                        return false;
+                    };
+                    if let Some(status) = status {
+                        step.status = status;
+                    }
+                    if let Some(title) = title {
+                        step.title = title;
+                    }
+                    if notes.is_some() {
+                        step.notes = notes;
+                    }
+                    true
+                });
+                if !found {
+                    return Ok(ToolResult::error(format!(
+                        "Step {} does not exist; the plan has {} steps",
+                        step_id,
+                        state.snapshot().steps.len()
+                    )));
+                }
+            }
+            PlanAction::View => {}
+        }
+
+        let plan = state.snapshot();
+        Ok(ToolResult::success(json!({
+            "plan": plan.render(),
+            "current_step": plan.current_step().map(|step| step.id),
+        })))
+    }
+}
+
+/// Cycle hook that keeps the current plan at the end of the system prompt
+///
+/// The plan section is replaced before every model call, so it always shows
+/// the latest plan and is never duplicated.
+#[derive(Debug, Clone)]
+pub struct PlanPromptHook {
+    state: PlanState,
+}
+
+impl PlanPromptHook {
+    /// Render the plan in `state`
+    pub fn new(state: PlanState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl CycleHook for PlanPromptHook {
+    async fn before_model_call(
+        &self,
+        messages: &mut Messages,
+        _ctx: &CycleHookContext,
+    ) -> crate::Result<()> {
+        let base = messages
+            .system_prompt
+            .as_deref()
+            .map(|prompt| match prompt.find(PLAN_SECTION_START) {
+                Some(start) => prompt[..start].trim_end(),
+                None => prompt,
+            })
+            .unwrap_or("");
+        let plan = self.state.snapshot();
+        messages.system_prompt = if plan.is_empty() {
+            (!base.is_empty()).then(|| base.to_string())
+        } else {
+            let section = format!(
+                "{}\nYour plan for the current task (update it with the plan tool):\n{}{}",
+                PLAN_SECTION_START,
+                plan.render(),
+                PLAN_SECTION_END
+            );
+            Some(if base.is_empty() {
+                section
+            } else {
+                format!("{}\n\n{}", base, section)
+            })
+        };
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "plan_prompt"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_plan_tool_actions() {
+        let tool = PlanTool::new();
+        let result = tool
+            .execute(
+                Some(json!({
+                    "action": "create",
+                    "goal": "Ship the release",
+                    "steps": ["Run tests", "Tag version"]
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.content["current_step"], 1);
+
+        tool.execute(
+            Some(json!({"action": "update_step", "step_id": 1, "status": "completed", "notes": "all green"})),
+            None,
+        )
+        .await
+        .unwrap();
+        tool.execute(
+            Some(json!({"action": "add_steps", "steps": ["Publish"]})),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let plan = tool.state().snapshot();
+        assert_eq!(plan.progress(), (1, 3));
+        assert_eq!(plan.steps[2].id, 3);
+        assert_eq!(
+            plan.render(),
+            "Goal: Ship the release\nProgress: 1/3 steps done\n\
+             1. [completed] Run tests (all green)\n2. [pending] Tag version\n3. [pending] Publish\n"
+        );
+
+        let missing = tool
+            .execute(
+                Some(json!({"action": "update_step", "step_id": 9, "status": "completed"})),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!missing.success);
+        assert!(tool
+            .execute(Some(json!({"action": "delete"})), None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prompt_hook_replaces_plan_section() {
+        let state = PlanState::new();
+        let hook = PlanPromptHook::new(state.clone());
+        let ctx = CycleHookContext {
+            agent_id: "agent".to_string(),
+            agent_name: None,
+            model_call_number: 1,
+        };
+        let mut messages = Messages::new();
+        messages.system_prompt = Some("Be concise.".to_string());
+
+        hook.before_model_call(&mut messages, &ctx).await.unwrap();
+        assert_eq!(messages.system_prompt.as_deref(), Some("Be concise."));
+
+        PlanTool::with_state(state.clone())
+            .execute(Some(json!({"action": "create", "steps": ["One"]})), None)
+            .await
+            .unwrap();
+        hook.before_model_call(&mut messages, &ctx).await.unwrap();
+        hook.before_model_call(&mut messages, &ctx).await.unwrap();
+        let prompt = messages.system_prompt.unwrap();
+        assert!(prompt.starts_with("Be concise.\n\n<current_plan>"));
+        assert_eq!(prompt.matches(PLAN_SECTION_START).count(), 1);
+        assert!(prompt.contains("1. [pending] One"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Progress reporting for long-running tools.
+//!
+//! Tools that take a while (downloads, builds, large queries) can report how
+//! far along they are through the [`ProgressReporter`] on their
+//! [`AgentContext`](crate::agent::AgentContext). Each report becomes a
+//! [`CallbackEvent::ToolProgress`](crate::agent::callbacks::CallbackEvent::ToolProgress)
+//! for the agent's callback handler, so CLIs and web UIs can draw progress bars.
+//!
+//! ```no_run
+//! use async_trait::async_trait;
+//! use serde_json::Value;
+//! use stood::agent::AgentContext;
+//! use stood::tools::{Tool, ToolError, ToolResult};
+//!
+//! #[derive(Debug)]
+//! struct BuildTool;
+//!
+//! #[async_trait]
+//! impl Tool for BuildTool {
+//!     fn name(&self) -> &str {
+//!         "build"
+//!     }
+//!
+//!     fn description(&self) -> &str {
+//!         "Build the project"
+//!     }
+//!
+//!     fn parameters_schema(&self) -> Value {
+//!         serde_json::json!({ "type": "object", "properties": {} })
+//!     }
+//!
+//!     async fn execute(
+//!         &self,
+//!         _parameters: Option<Value>,
+//!         agent_context: Option<&AgentContext>,
+//!     ) -> Result<ToolResult, ToolError> {
+//!         let steps = ["fetch", "compile", "link"];
+//!         for (i, step) in steps.iter().enumerate() {
+//!             if let Some(ctx) = agent_context {
+//!                 ctx.report_progress(100.0 * i as f32 / steps.len() as f32, *step);
+//!             }
+//!             // ... run the step ...
+//!         }
+//!         Ok(ToolResult::success(serde_json::json!("built")))
+//!     }
+//! }
+//! ```
+//!
+//! Reports are delivered to the callback handler in order but asynchronously,
+//! so the last ones may arrive shortly after the tool has returned. Without a
+//! callback handler, tools get no reporter and reporting does nothing.
+
+use tokio::sync::mpsc;
+
+use crate::agent::callbacks::ToolProgressEvent;
+
+/// Handle a tool uses to report its progress
+///
+/// Clones send to the same receiver.
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    sender: mpsc::UnboundedSender<ToolProgressEvent>,
+    tool_name: String,
+    tool_use_id: String,
+}
+
+impl ProgressReporter {
+    /// A reporter not yet attributed to a tool call, and the receiver of its reports
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<ToolProgressEvent>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let reporter = Self {
+            sender,
+            tool_name: String::new(),
+            tool_use_id: String::new(),
+        };
+        (reporter, receiver)
+    }
+
+    /// A reporter attributing its reports to the call `tool_use_id` of `tool_name`
+    pub fn for_tool(&self, tool_name: impl Into<String>, tool_use_id: impl Into<String>) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            tool_name: tool_name.into(),
+            tool_use_id: tool_use_id.into(),
+        }
+    }
+
+    /// Report that the tool is `percent` (0 to 100) done, currently doing `message`
+    ///
+    /// Out of range values are clamped. Does nothing once the receiver is gone.
+    pub fn report(&self, percent: f32, message: impl Into<String>) {
+        let percent = if percent.is_nan() {
+            0.0
+        } else {
+            percent.clamp(0.0, 100.0)
+        };
+        let _ = self.sender.send(ToolProgressEvent {
+            tool_name: self.tool_name.clone(),
+            tool_use_id: self.tool_use_id.clone(),
+            percent,
+            message: message.into(),
+        });
+    }
+
+    pub fn tool_name(&self) -> &str {
+        &self.tool_name
+    }
+
+    pub fn tool_use_id(&self) -> &str {
+        &self.tool_use_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reports_are_attributed_and_clamped() {
+        let (reporter, mut receiver) = ProgressReporter::channel();
+        let download = reporter.for_tool("download", "t1");
+        download.report(42.0, "fetching");
+        download.report(150.0, "done");
+        download.report(f32::NAN, "restarting");
+        drop((reporter, download));
+
+        let first = receiver.recv().await.unwrap();
+        assert_eq!(first.tool_name, "download");
+        assert_eq!(first.tool_use_id, "t1");
+        assert_eq!(first.percent, 42.0);
+        assert_eq!(first.message, "fetching");
+        assert_eq!(receiver.recv().await.unwrap().percent, 100.0);
+        assert_eq!(receiver.recv().await.unwrap().percent, 0.0);
+        assert!(receiver.recv().await.is_none());
+    }
+}
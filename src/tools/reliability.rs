@@ -0,0 +1,272 @@
+//! Memory of tool failures across executions.
+//!
+//! Every [`ToolRegistry`](super::ToolRegistry) owns a [`ToolReliabilityTracker`]
+//! that records the outcome of each tool call made by the agent loop. Clones of
+//! the registry share the tracker, so the statistics survive across executions
+//! of an agent, and they can be saved to and loaded from a JSON file or a
+//! [`KvStore`](crate::storage::KvStore) to survive process restarts.
+//!
+//! With [`AgentBuilder::with_flaky_tool_notes`](crate::agent::AgentBuilder::with_flaky_tool_notes),
+//! a short note listing tools that keep failing is appended to the system prompt
+//! so the model stops retrying broken tools.
+//!
+//! ```no_run
+//! # use stood::agent::Agent;
+//! # async fn example(agent: Agent) {
+//! let reliability = agent.tool_registry().reliability();
+//! for (tool, stats) in reliability.snapshot() {
+//!     println!(
+//!         "{}: {:.0}% success over {} calls, last error: {:?}",
+//!         tool,
+//!         stats.success_rate() * 100.0,
+//!         stats.calls(),
+//!         stats.last_error
+//!     );
+//! }
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{KvStore, KvStoreExt};
+
+/// Maximum characters of an error message kept per tool
+const MAX_ERROR_CHARS: usize = 200;
+
+/// Success and failure history of one tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolReliability {
+    /// Successful calls
+    pub successes: u64,
+    /// Failed calls
+    pub failures: u64,
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    /// Most recent error message
+    pub last_error: Option<String>,
+    /// When the tool last failed
+    pub last_failure_at: Option<DateTime<Utc>>,
+    /// When the tool last succeeded
+    pub last_success_at: Option<DateTime<Utc>>,
+}
+
+impl ToolReliability {
+    /// Total recorded calls
+    pub fn calls(&self) -> u64 {
+        self.successes + self.failures
+    }
+
+    /// Fraction of calls that succeeded, or 1.0 if the tool was never called
+    pub fn success_rate(&self) -> f64 {
+        match self.calls() {
+            0 => 1.0,
+            calls => self.successes as f64 / calls as f64,
+        }
+    }
+}
+
+/// When a tool is considered flaky
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlakyToolPolicy {
+    /// Calls required before the success rate is considered (default: 3)
+    pub min_calls: u64,
+    /// Tools at or below this success rate are flaky (default: 0.5)
+    pub max_success_rate: f64,
+    /// Tools failing this many times in a row are flaky regardless of their
+    /// success rate (default: 3)
+    pub consecutive_failures: u32,
+}
+
+impl Default for FlakyToolPolicy {
+    fn default() -> Self {
+        Self {
+            min_calls: 3,
+            max_success_rate: 0.5,
+            consecutive_failures: 3,
+        }
+    }
+}
+
+impl FlakyToolPolicy {
+    /// Whether `stats` describe a flaky tool
+    pub fn is_flaky(&self, stats: &ToolReliability) -> bool {
+        stats.consecutive_failures >= self.consecutive_failures
+            || (stats.calls() >= self.min_calls && stats.success_rate() <= self.max_success_rate)
+    }
+}
+
+/// Per-tool success rates and last errors, shared by clones
+#[derive(Debug, Clone, Default)]
+pub struct ToolReliabilityTracker {
+    stats: Arc<RwLock<BTreeMap<String, ToolReliability>>>,
+}
+
+impl ToolReliabilityTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a successful call of `tool`
+    pub fn record_success(&self, tool: &str) {
+        let mut stats = self.stats.write().unwrap();
+        let entry = stats.entry(tool.to_string()).or_default();
+        entry.successes += 1;
+        entry.consecutive_failures = 0;
+        entry.last_success_at = Some(Utc::now());
+    }
+
+    /// Record a failed call of `tool`
+    pub fn record_failure(&self, tool: &str, error: Option<&str>) {
+        let mut stats = self.stats.write().unwrap();
+        let entry = stats.entry(tool.to_string()).or_default();
+        entry.failures += 1;
+        entry.consecutive_failures += 1;
+        entry.last_failure_at = Some(Utc::now());
+        if let Some(error) = error {
+            entry.last_error = Some(error.chars().take(MAX_ERROR_CHARS).collect());
+        }
+    }
+
+    /// History of one tool
+    pub fn get(&self, tool: &str) -> Option<ToolReliability> {
+        self.stats.read().unwrap().get(tool).cloned()
+    }
+
+    /// History of every tool called so far, by tool name
+    pub fn snapshot(&self) -> BTreeMap<String, ToolReliability> {
+        self.stats.read().unwrap().clone()
+    }
+
+    /// Tools considered flaky under `policy`, least reliable first
+    pub fn flaky_tools(&self, policy: &FlakyToolPolicy) -> Vec<(String, ToolReliability)> {
+        let mut flaky: Vec<_> = self
+            .stats
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, stats)| policy.is_flaky(stats))
+            .map(|(name, stats)| (name.clone(), stats.clone()))
+            .collect();
+        flaky.sort_by(|(_, a), (_, b)| a.success_rate().total_cmp(&b.success_rate()));
+        flaky
+    }
+
+    /// Note for the system prompt listing flaky tools, or `None` if there are none
+    pub fn flaky_tools_note(&self, policy: &FlakyToolPolicy) -> Option<String> {
+        let flaky = self.flaky_tools(policy);
+        if flaky.is_empty() {
+            return None;
+        }
+
+        let mut note = String::from(
+            "Known flaky tools (these failed repeatedly in earlier runs; prefer alternatives \
+             and do not retry them more than once):",
+        );
+        for (name, stats) in flaky {
+            note.push_str(&format!(
+                "\n- {}: failed {} of {} calls",
+                name,
+                stats.failures,
+                stats.calls()
+            ));
+            if let Some(error) = &stats.last_error {
+                note.push_str(&format!(" (last error: {})", error));
+            }
+        }
+        Some(note)
+    }
+
+    /// Forget the history of one tool, e.g. after it was fixed
+    pub fn reset(&self, tool: &str) {
+        self.stats.write().unwrap().remove(tool);
+    }
+
+    /// Forget the history of every tool
+    pub fn clear(&self) {
+        self.stats.write().unwrap().clear();
+    }
+
+    /// Save the history to a JSON file
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.snapshot())?;
+        std::fs::write(path, json)
+    }
+
+    /// Merge the history saved in a JSON file into this tracker
+    ///
+    /// Tools already tracked are replaced by the saved history.
+    pub fn load_from_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = std::fs::read_to_string(path)?;
+        let saved: BTreeMap<String, ToolReliability> = serde_json::from_str(&json)?;
+        self.stats.write().unwrap().extend(saved);
+        Ok(())
+    }
+
+    /// Save the history to `store`, one key per tool under `prefix`
+    pub async fn save_to_store(&self, store: &dyn KvStore, prefix: &str) -> crate::Result<()> {
+        for (tool, stats) in self.snapshot() {
+            store
+                .put_json(&format!("{}{}", prefix, tool), &stats)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Merge the history saved in `store` under `prefix` into this tracker
+    ///
+    /// Tools already tracked are replaced by the saved history.
+    pub async fn load_from_store(&self, store: &dyn KvStore, prefix: &str) -> crate::Result<()> {
+        let mut saved = BTreeMap::new();
+        for key in store.keys(prefix).await? {
+            if let Some(stats) = store.get_json::<ToolReliability>(&key).await? {
+                saved.insert(key[prefix.len()..].to_string(), stats);
+            }
+        }
+        self.stats.write().unwrap().extend(saved);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_reports_flaky_tools() {
+        let tracker = ToolReliabilityTracker::new();
+        let shared = tracker.clone();
+        shared.record_success("calculator");
+        shared.record_success("web_page");
+        for _ in 0..3 {
+            shared.record_failure("web_page", Some("connection timed out"));
+        }
+
+        let web = tracker.get("web_page").unwrap();
+        assert_eq!(web.calls(), 4);
+        assert_eq!(web.success_rate(), 0.25);
+        assert_eq!(web.last_error.as_deref(), Some("connection timed out"));
+
+        let policy = FlakyToolPolicy::default();
+        let flaky = tracker.flaky_tools(&policy);
+        assert_eq!(flaky.len(), 1);
+        assert_eq!(flaky[0].0, "web_page");
+        let note = tracker.flaky_tools_note(&policy).unwrap();
+        assert!(note.contains("web_page: failed 3 of 4 calls (last error: connection timed out)"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reliability.json");
+        tracker.save_to_file(&path).unwrap();
+        let restored = ToolReliabilityTracker::new();
+        restored.load_from_file(&path).unwrap();
+        assert_eq!(restored.snapshot(), tracker.snapshot());
+
+        tracker.reset("web_page");
+        assert!(tracker.flaky_tools_note(&policy).is_none());
+    }
+}
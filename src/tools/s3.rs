@@ -0,0 +1,689 @@
+//! Amazon S3 tools for reading and writing working data (requires the `s3` feature).
+//!
+//! This module provides three companion tools backed by a shared client:
+//!
+//! - **[`S3ReadTool`]** (`s3_read`) - Read an object as text, truncated to a size cap
+//! - **[`S3WriteTool`]** (`s3_write`) - Write text to an object
+//! - **[`S3ListTool`]** (`s3_list`) - List objects under a prefix
+//!
+//! Credentials come from the standard AWS credential chain, the same one used by
+//! the Bedrock provider.
+//!
+//! # Quick Start
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! use stood::tools::s3::{S3Config, S3Connection};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let s3 = S3Connection::connect(
+//!     S3Config::new()
+//!         .allow("analytics-data/reports/")
+//!         .allow("analytics-scratch")
+//!         .with_max_read_bytes(256 * 1024),
+//! )
+//! .await?;
+//!
+//! let agent = Agent::builder()
+//!     .tool(Box::new(s3.read_tool()))
+//!     .tool(Box::new(s3.list_tool()))
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Safety Model
+//!
+//! Every request is checked against the allowlist before it is sent. Entries are
+//! either a bucket name (`my-bucket`, the whole bucket) or a bucket and key prefix
+//! (`my-bucket/data/`). An empty allowlist denies everything. Writes are disabled
+//! unless [`S3Config::with_writes`] is set, and objects larger than
+//! [`S3Config::max_read_bytes`] are streamed only up to the cap and returned
+//! truncated.
+
+use crate::llm::http::aws_config_loader;
+use crate::llm::traits::LlmError;
+use crate::tools::{Tool, ToolError, ToolResult};
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+use std::sync::Arc;
+
+/// Configuration for an [`S3Connection`]
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    /// Buckets (`bucket`) and prefixes (`bucket/prefix/`) the tools may access
+    pub allowed_prefixes: Vec<String>,
+    /// Region override; defaults to the region from the AWS configuration
+    pub region: Option<String>,
+    /// Maximum bytes downloaded per read (default: 1 MiB)
+    pub max_read_bytes: usize,
+    /// Maximum bytes uploaded per write (default: 5 MiB)
+    pub max_write_bytes: usize,
+    /// Maximum keys returned per listing (default: 1000)
+    pub max_list_keys: usize,
+    /// Whether the write tool may modify objects (default: false)
+    pub allow_writes: bool,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            allowed_prefixes: Vec::new(),
+            region: None,
+            max_read_bytes: 1024 * 1024,
+            max_write_bytes: 5 * 1024 * 1024,
+            max_list_keys: 1000,
+            allow_writes: false,
+        }
+    }
+}
+
+impl S3Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow access to a bucket (`bucket`) or key prefix (`bucket/prefix/`)
+    pub fn allow(mut self, prefix: impl Into<String>) -> Self {
+        self.allowed_prefixes.push(prefix.into());
+        self
+    }
+
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    pub fn with_max_read_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_read_bytes = max_bytes;
+        self
+    }
+
+    pub fn with_max_write_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_write_bytes = max_bytes;
+        self
+    }
+
+    pub fn with_max_list_keys(mut self, max_keys: usize) -> Self {
+        self.max_list_keys = max_keys;
+        self
+    }
+
+    pub fn with_writes(mut self, allow_writes: bool) -> Self {
+        self.allow_writes = allow_writes;
+        self
+    }
+
+    /// Whether `bucket`/`key` falls within an allowed bucket or prefix
+    pub fn is_allowed(&self, bucket: &str, key: &str) -> bool {
+        self.allowed_prefixes
+            .iter()
+            .any(|entry| match entry.split_once('/') {
+                None => entry == bucket,
+                Some((allowed_bucket, prefix)) => {
+                    allowed_bucket == bucket && key.starts_with(prefix)
+                }
+            })
+    }
+
+    fn check_allowed(&self, bucket: &str, key: &str) -> Result<(), String> {
+        if self.is_allowed(bucket, key) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Access to s3://{}/{} is not allowed. Allowed locations: {}",
+                bucket,
+                key,
+                if self.allowed_prefixes.is_empty() {
+                    "none".to_string()
+                } else {
+                    self.allowed_prefixes
+                        .iter()
+                        .map(|p| format!("s3://{}", p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }
+            ))
+        }
+    }
+}
+
+/// Shared S3 client used by the S3 tools
+#[derive(Debug, Clone)]
+pub struct S3Connection {
+    client: Client,
+    config: Arc<S3Config>,
+}
+
+impl S3Connection {
+    /// Create a client using the default AWS credential chain
+    ///
+    /// Connects through the installed [proxy](crate::proxy), which must not be
+    /// a SOCKS proxy.
+    pub async fn connect(config: S3Config) -> Result<Self, LlmError> {
+        let mut loader = aws_config_loader()?;
+        if let Some(region) = &config.region {
+            loader = loader.region(aws_config::Region::new(region.clone()));
+        }
+        let sdk_config = loader.load().await;
+        Ok(Self::from_client(Client::new(&sdk_config), config))
+    }
+
+    /// Use an existing S3 client
+    pub fn from_client(client: Client, config: S3Config) -> Self {
+        Self {
+            client,
+            config: Arc::new(config),
+        }
+    }
+
+    pub fn config(&self) -> &S3Config {
+        &self.config
+    }
+
+    /// Create the `s3_read` tool for this connection
+    pub fn read_tool(&self) -> S3ReadTool {
+        S3ReadTool {
+            connection: self.clone(),
+        }
+    }
+
+    /// Create the `s3_write` tool for this connection
+    pub fn write_tool(&self) -> S3WriteTool {
+        S3WriteTool {
+            connection: self.clone(),
+        }
+    }
+
+    /// Create the `s3_list` tool for this connection
+    pub fn list_tool(&self) -> S3ListTool {
+        S3ListTool {
+            connection: self.clone(),
+        }
+    }
+
+    async fn read(
+        &self,
+        bucket: &str,
+        key: &str,
+        max_bytes: usize,
+    ) -> Result<serde_json::Value, String> {
+        self.config.check_allowed(bucket, key)?;
+        let max_bytes = max_bytes.min(self.config.max_read_bytes);
+
+        let output = self
+            .client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| aws_sdk_s3::error::DisplayErrorContext(e).to_string())?;
+        let size = output.content_length().unwrap_or_default();
+        let content_type = output.content_type().map(str::to_string);
+
+        // Stream chunks until the cap so large objects are never fully downloaded
+        let mut body = output.body;
+        let mut data = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = body.try_next().await.map_err(|e| e.to_string())? {
+            let remaining = max_bytes - data.len();
+            if chunk.len() > remaining {
+                data.extend_from_slice(&chunk[..remaining]);
+                truncated = true;
+                break;
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        let (content, binary) = decode_text(data, truncated);
+        if binary {
+            return Err(format!(
+                "s3://{}/{} is binary ({}, {} bytes) and cannot be returned as text",
+                bucket,
+                key,
+                content_type.as_deref().unwrap_or("unknown type"),
+                size
+            ));
+        }
+
+        Ok(serde_json::json!({
+            "bucket": bucket,
+            "key": key,
+            "content": content,
+            "size": size,
+            "content_type": content_type,
+            "truncated": truncated
+        }))
+    }
+
+    async fn write(
+        &self,
+        bucket: &str,
+        key: &str,
+        content: String,
+        content_type: Option<&str>,
+    ) -> Result<serde_json::Value, String> {
+        self.upload(
+            bucket,
+            key,
+            content.into_bytes(),
+            content_type.unwrap_or("text/plain; charset=utf-8"),
+        )
+        .await
+    }
+
+    /// Upload `data` to `bucket`/`key`, subject to the same checks as the write tool
+    pub(crate) async fn upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        data: Vec<u8>,
+        content_type: &str,
+    ) -> Result<serde_json::Value, String> {
+        if !self.config.allow_writes {
+            return Err("Writes are disabled for this S3 connection".to_string());
+        }
+        self.config.check_allowed(bucket, key)?;
+        if data.len() > self.config.max_write_bytes {
+            return Err(format!(
+                "Content is {} bytes; the maximum write size is {} bytes",
+                data.len(),
+                self.config.max_write_bytes
+            ));
+        }
+
+        let size = data.len();
+        let output = self
+            .client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .content_type(content_type)
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .map_err(|e| aws_sdk_s3::error::DisplayErrorContext(e).to_string())?;
+
+        Ok(serde_json::json!({
+            "bucket": bucket,
+            "key": key,
+            "size": size,
+            "etag": output.e_tag()
+        }))
+    }
+
+    /// Download `bucket`/`key`, subject to the allowlist and read size cap
+    pub(crate) async fn download(&self, bucket: &str, key: &str) -> Result<Vec<u8>, String> {
+        self.config.check_allowed(bucket, key)?;
+        let output = self
+            .client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| aws_sdk_s3::error::DisplayErrorContext(e).to_string())?;
+        let size = output.content_length().unwrap_or_default();
+        if size as usize > self.config.max_read_bytes {
+            return Err(format!(
+                "s3://{}/{} is {} bytes; the maximum read size is {} bytes",
+                bucket, key, size, self.config.max_read_bytes
+            ));
+        }
+        let data = output.body.collect().await.map_err(|e| e.to_string())?;
+        Ok(data.to_vec())
+    }
+
+    /// Delete `bucket`/`key`, subject to the same checks as uploads
+    pub(crate) async fn delete(&self, bucket: &str, key: &str) -> Result<(), String> {
+        if !self.config.allow_writes {
+            return Err("Writes are disabled for this S3 connection".to_string());
+        }
+        self.config.check_allowed(bucket, key)?;
+        self.client
+            .delete_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| aws_sdk_s3::error::DisplayErrorContext(e).to_string())?;
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        max_keys: usize,
+    ) -> Result<serde_json::Value, String> {
+        self.config.check_allowed(bucket, prefix)?;
+        let max_keys = max_keys.min(self.config.max_list_keys);
+
+        let mut objects = Vec::new();
+        let mut truncated = false;
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .into_paginator()
+            .send();
+        'pages: while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| aws_sdk_s3::error::DisplayErrorContext(e).to_string())?;
+            for object in page.contents() {
+                if objects.len() >= max_keys {
+                    truncated = true;
+                    break 'pages;
+                }
+                objects.push(serde_json::json!({
+                    "key": object.key(),
+                    "size": object.size(),
+                    "last_modified": object.last_modified().map(|t| t.to_string())
+                }));
+            }
+        }
+
+        Ok(serde_json::json!({
+            "bucket": bucket,
+            "prefix": prefix,
+            "objects": objects,
+            "count": objects.len(),
+            "truncated": truncated
+        }))
+    }
+}
+
+/// Decode bytes as UTF-8, tolerating a multi-byte character cut off by truncation.
+///
+/// Returns the text and whether the data looks binary.
+fn decode_text(mut data: Vec<u8>, truncated: bool) -> (String, bool) {
+    if truncated {
+        if let Err(e) = std::str::from_utf8(&data) {
+            if e.error_len().is_none() {
+                data.truncate(e.valid_up_to());
+            }
+        }
+    }
+    match String::from_utf8(data) {
+        Ok(text) => {
+            let binary = text.contains('\0');
+            (text, binary)
+        }
+        Err(_) => (String::new(), true),
+    }
+}
+
+fn required_str<'a>(params: &'a serde_json::Value, name: &str) -> Result<&'a str, ToolError> {
+    params
+        .get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidParameters {
+            message: format!("Missing required parameter: {}", name),
+        })
+}
+
+/// Reads S3 objects as text
+#[derive(Debug, Clone)]
+pub struct S3ReadTool {
+    connection: S3Connection,
+}
+
+impl S3ReadTool {
+    pub fn new(connection: S3Connection) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for S3ReadTool {
+    fn name(&self) -> &str {
+        "s3_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read a text object from Amazon S3. Large objects are truncated"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "bucket": {
+                    "type": "string",
+                    "description": "Bucket name"
+                },
+                "key": {
+                    "type": "string",
+                    "description": "Object key"
+                },
+                "max_bytes": {
+                    "type": "integer",
+                    "description": format!(
+                        "Maximum bytes to read (at most {})",
+                        self.connection.config.max_read_bytes
+                    )
+                }
+            },
+            "required": ["bucket", "key"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        _agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let bucket = required_str(&params, "bucket")?;
+        let key = required_str(&params, "key")?;
+        let max_bytes = params
+            .get("max_bytes")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(self.connection.config.max_read_bytes);
+
+        match self.connection.read(bucket, key, max_bytes).await {
+            Ok(result) => Ok(ToolResult::success(result)),
+            Err(e) => Ok(ToolResult::error(format!("S3 read failed: {}", e))),
+        }
+    }
+}
+
+/// Writes text to S3 objects
+#[derive(Debug, Clone)]
+pub struct S3WriteTool {
+    connection: S3Connection,
+}
+
+impl S3WriteTool {
+    pub fn new(connection: S3Connection) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for S3WriteTool {
+    fn name(&self) -> &str {
+        "s3_write"
+    }
+
+    fn description(&self) -> &str {
+        "Write text content to an object in Amazon S3, replacing any existing object"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "bucket": {
+                    "type": "string",
+                    "description": "Bucket name"
+                },
+                "key": {
+                    "type": "string",
+                    "description": "Object key"
+                },
+                "content": {
+                    "type": "string",
+                    "description": format!(
+                        "Text to write (at most {} bytes)",
+                        self.connection.config.max_write_bytes
+                    )
+                },
+                "content_type": {
+                    "type": "string",
+                    "description": "MIME type (default: text/plain; charset=utf-8)"
+                }
+            },
+            "required": ["bucket", "key", "content"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        _agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let bucket = required_str(&params, "bucket")?;
+        let key = required_str(&params, "key")?;
+        let content = required_str(&params, "content")?.to_string();
+        let content_type = params.get("content_type").and_then(|v| v.as_str());
+
+        match self
+            .connection
+            .write(bucket, key, content, content_type)
+            .await
+        {
+            Ok(result) => Ok(ToolResult::success(result)),
+            Err(e) => Ok(ToolResult::error(format!("S3 write failed: {}", e))),
+        }
+    }
+}
+
+/// Lists S3 objects under a prefix
+#[derive(Debug, Clone)]
+pub struct S3ListTool {
+    connection: S3Connection,
+}
+
+impl S3ListTool {
+    pub fn new(connection: S3Connection) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for S3ListTool {
+    fn name(&self) -> &str {
+        "s3_list"
+    }
+
+    fn description(&self) -> &str {
+        "List objects in an Amazon S3 bucket under a key prefix, with their sizes"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "bucket": {
+                    "type": "string",
+                    "description": "Bucket name"
+                },
+                "prefix": {
+                    "type": "string",
+                    "description": "Only list keys starting with this prefix"
+                },
+                "max_keys": {
+                    "type": "integer",
+                    "description": format!(
+                        "Maximum objects to return (at most {})",
+                        self.connection.config.max_list_keys
+                    )
+                }
+            },
+            "required": ["bucket"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<serde_json::Value>,
+        _agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let params = parameters.unwrap_or(serde_json::json!({}));
+        let bucket = required_str(&params, "bucket")?;
+        let prefix = params.get("prefix").and_then(|v| v.as_str()).unwrap_or("");
+        let max_keys = params
+            .get("max_keys")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(self.connection.config.max_list_keys);
+
+        match self.connection.list(bucket, prefix, max_keys).await {
+            Ok(result) => Ok(ToolResult::success(result)),
+            Err(e) => Ok(ToolResult::error(format!("S3 list failed: {}", e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offline_connection(config: S3Config) -> S3Connection {
+        let sdk_config = aws_sdk_s3::Config::builder()
+            .behavior_version(aws_sdk_s3::config::BehaviorVersion::latest())
+            .region(aws_sdk_s3::config::Region::new("us-east-1"))
+            .build();
+        S3Connection::from_client(Client::from_conf(sdk_config), config)
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let config = S3Config::new().allow("data/reports/").allow("scratch");
+
+        assert!(config.is_allowed("data", "reports/2024/q1.csv"));
+        assert!(!config.is_allowed("data", "secrets/keys.txt"));
+        assert!(!config.is_allowed("data-other", "reports/q1.csv"));
+        assert!(config.is_allowed("scratch", "anything"));
+        assert!(!S3Config::new().is_allowed("scratch", "anything"));
+    }
+
+    #[test]
+    fn test_decode_text() {
+        // "é" is two bytes; truncating after the first must not fail decoding
+        let (text, binary) = decode_text(vec![b'a', 0xC3], true);
+        assert_eq!((text.as_str(), binary), ("a", false));
+        assert!(decode_text(vec![0xFF, 0xFE, 0x00], false).1);
+    }
+
+    #[tokio::test]
+    async fn test_requests_outside_allowlist_are_rejected() {
+        let connection = offline_connection(S3Config::new().allow("data/reports/"));
+
+        let result = connection
+            .read_tool()
+            .execute(
+                Some(serde_json::json!({"bucket": "data", "key": "private/key.pem"})),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+
+        let result = connection
+            .write_tool()
+            .execute(
+                Some(serde_json::json!({"bucket": "data", "key": "reports/x", "content": "x"})),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Writes are disabled"));
+    }
+}
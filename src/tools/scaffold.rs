@@ -0,0 +1,241 @@
+//! Source generation for new `#[tool]` functions.
+//!
+//! Backs the `stood-cli new-tool` command, which writes the boilerplate of a
+//! new tool: the `#[tool]` function skeleton, a test checking its input
+//! schema, and the snippet registering it with an agent.
+//!
+//! ```text
+//! stood-cli new-tool weather --params "location:String,units:Option<String>"
+//! ```
+//!
+//! The same output is available programmatically:
+//!
+//! ```
+//! use stood::tools::scaffold::ToolScaffold;
+//!
+//! let scaffold = ToolScaffold::parse("weather", "location:String,units:Option<String>").unwrap();
+//! let source = scaffold.render();
+//! assert!(source.contains("async fn weather(location: String, units: Option<String>)"));
+//! ```
+
+use crate::{Result, StoodError};
+
+/// One parameter of a generated tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolParam {
+    /// Parameter name
+    pub name: String,
+    /// Rust type of the parameter, e.g. `String` or `Option<u32>`
+    pub ty: String,
+}
+
+impl ToolParam {
+    /// Whether the model may leave the parameter out
+    pub fn is_optional(&self) -> bool {
+        self.ty.starts_with("Option<")
+    }
+}
+
+/// A tool to generate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolScaffold {
+    /// Function and tool name
+    pub name: String,
+    /// Description used as the doc comment, read by the model
+    pub description: Option<String>,
+    /// Parameters in declaration order
+    pub params: Vec<ToolParam>,
+}
+
+impl ToolScaffold {
+    /// Parse a tool name and a `name:Type,name:Type` parameter list
+    ///
+    /// Commas inside generic types such as `HashMap<String, u32>` do not
+    /// split parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoodError::ValidationError`] if the tool name or a parameter
+    /// name is not a snake_case identifier, or a parameter lacks a type.
+    pub fn parse(name: &str, params: &str) -> Result<Self> {
+        validate_identifier(name, "tool name")?;
+
+        let params = split_top_level(params)
+            .into_iter()
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                let (name, ty) = param.split_once(':').ok_or_else(|| {
+                    StoodError::validation_error(format!(
+                        "Parameter '{}' must be written as name:Type",
+                        param
+                    ))
+                })?;
+                let (name, ty) = (name.trim(), ty.trim());
+                validate_identifier(name, "parameter name")?;
+                if ty.is_empty() {
+                    return Err(StoodError::validation_error(format!(
+                        "Parameter '{}' has no type",
+                        name
+                    )));
+                }
+                Ok(ToolParam {
+                    name: name.to_string(),
+                    ty: ty.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            name: name.to_string(),
+            description: None,
+            params,
+        })
+    }
+
+    /// Set the description
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Generate the source file: tool function, registration snippet and schema test
+    pub fn render(&self) -> String {
+        let name = &self.name;
+        let description = self
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("TODO: Describe what {} does and when to use it.", name));
+        let signature = self
+            .params
+            .iter()
+            .map(|p| format!("{}: {}", p.name, p.ty))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let unused = match self.params.len() {
+            0 => String::new(),
+            1 => format!("    let _ = &{};\n", self.params[0].name),
+            _ => format!(
+                "    let _ = ({});\n",
+                self.params
+                    .iter()
+                    .map(|p| format!("&{}", p.name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let required = self
+            .params
+            .iter()
+            .filter(|p| !p.is_optional())
+            .map(|p| format!("\"{}\"", p.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let properties = self
+            .params
+            .iter()
+            .map(|p| {
+                format!(
+                    "        assert!(schema[\"properties\"][\"{}\"].is_object());\n",
+                    p.name
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            r#"use stood::tool;
+
+#[tool]
+/// {description}
+async fn {name}({signature}) -> Result<String, String> {{
+{unused}    todo!("implement {name}")
+}}
+
+// Register the tool with an agent:
+//
+//     let agent = Agent::builder().tools(vec![{name}()]).build().await?;
+
+#[cfg(test)]
+mod {name}_tests {{
+    use super::*;
+    use stood::tools::Tool;
+
+    #[test]
+    fn test_{name}_schema() {{
+        let tool = {name}();
+        assert_eq!(tool.name(), "{name}");
+
+        let schema = tool.parameters_schema();
+{properties}        assert_eq!(schema["required"], serde_json::json!([{required}]));
+    }}
+}}
+"#
+        )
+    }
+}
+
+/// Split on commas that are not inside `<...>`, `(...)` or `[...]`
+fn split_top_level(input: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+fn validate_identifier(name: &str, what: &str) -> Result<()> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(StoodError::validation_error(format!(
+            "Invalid {} '{}': use a snake_case identifier",
+            what, name
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_render_tool() {
+        let scaffold = ToolScaffold::parse(
+            "weather",
+            "location:String, units:Option<String>, extra:HashMap<String, u32>",
+        )
+        .unwrap();
+        assert_eq!(scaffold.params.len(), 3);
+        assert_eq!(scaffold.params[2].ty, "HashMap<String, u32>");
+        assert!(scaffold.params[1].is_optional());
+
+        let source = scaffold.with_description("Get the weather").render();
+        assert!(source.contains("/// Get the weather\n"));
+        assert!(source.contains(
+            "async fn weather(location: String, units: Option<String>, extra: HashMap<String, u32>)"
+        ));
+        assert!(source.contains("serde_json::json!([\"location\", \"extra\"])"));
+        assert!(source.contains("tools(vec![weather()])"));
+
+        assert!(ToolScaffold::parse("Weather", "").is_err());
+        assert!(ToolScaffold::parse("weather", "location").is_err());
+    }
+}
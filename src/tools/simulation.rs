@@ -0,0 +1,229 @@
+//! Dry-run tool execution with simulated results.
+//!
+//! A registry returned by [`ToolRegistry::dry_run`](super::ToolRegistry::dry_run)
+//! never executes its tools. Each call is answered, in order, by:
+//!
+//! 1. a canned fixture registered in [`ToolFixtures`] for the tool,
+//! 2. the tool's own [`Tool::simulate`] implementation,
+//! 3. a generic result marked `"simulated": true` that echoes the input.
+//!
+//! Middleware and guardrails still run, so prompts and plans can be tested
+//! against destructive tools, approval flows included, without side effects.
+//! Agents use it through
+//! [`ExecuteOptions::dry_run_tools`](crate::agent::ExecuteOptions::dry_run_tools).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{Tool, ToolAnnotations, ToolError, ToolResult, ToolSource};
+
+type FixtureFn = Arc<dyn Fn(&Value) -> ToolResult + Send + Sync>;
+
+#[derive(Clone)]
+enum Fixture {
+    Result(ToolResult),
+    Fn(FixtureFn),
+}
+
+/// Canned results returned instead of executing tools
+#[derive(Clone, Default)]
+pub struct ToolFixtures {
+    fixtures: HashMap<String, Fixture>,
+}
+
+impl ToolFixtures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer every call of `tool` with a successful result of `content`
+    pub fn with_result(self, tool: impl Into<String>, content: Value) -> Self {
+        self.with_tool_result(tool, ToolResult::success(content))
+    }
+
+    /// Answer every call of `tool` with a failure
+    pub fn with_error(self, tool: impl Into<String>, message: impl Into<String>) -> Self {
+        self.with_tool_result(tool, ToolResult::error(message))
+    }
+
+    /// Answer every call of `tool` with `result`
+    pub fn with_tool_result(mut self, tool: impl Into<String>, result: ToolResult) -> Self {
+        self.fixtures.insert(tool.into(), Fixture::Result(result));
+        self
+    }
+
+    /// Answer calls of `tool` with a result computed from the call's input
+    pub fn with_fn<F>(mut self, tool: impl Into<String>, fixture: F) -> Self
+    where
+        F: Fn(&Value) -> ToolResult + Send + Sync + 'static,
+    {
+        self.fixtures
+            .insert(tool.into(), Fixture::Fn(Arc::new(fixture)));
+        self
+    }
+
+    /// Whether a fixture is registered for `tool`
+    pub fn contains(&self, tool: &str) -> bool {
+        self.fixtures.contains_key(tool)
+    }
+
+    /// The fixture result for a call of `tool` with `input`
+    pub fn resolve(&self, tool: &str, input: &Value) -> Option<ToolResult> {
+        match self.fixtures.get(tool)? {
+            Fixture::Result(result) => Some(result.clone()),
+            Fixture::Fn(fixture) => Some(fixture(input)),
+        }
+    }
+}
+
+impl fmt::Debug for ToolFixtures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tools: Vec<&String> = self.fixtures.keys().collect();
+        tools.sort();
+        f.debug_struct("ToolFixtures")
+            .field("tools", &tools)
+            .finish()
+    }
+}
+
+/// Generic result for a tool without a fixture or simulation
+pub fn simulated_result(tool: &str, input: &Value) -> ToolResult {
+    ToolResult::success(json!({
+        "simulated": true,
+        "tool": tool,
+        "input": input,
+        "message": format!("Dry run: '{}' was not executed", tool),
+    }))
+}
+
+/// A tool whose calls are simulated instead of executed
+#[derive(Debug)]
+pub struct SimulatedTool {
+    inner: Arc<dyn Tool>,
+    fixtures: ToolFixtures,
+}
+
+impl SimulatedTool {
+    pub fn new(inner: Arc<dyn Tool>, fixtures: ToolFixtures) -> Self {
+        Self { inner, fixtures }
+    }
+}
+
+#[async_trait]
+impl Tool for SimulatedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters_schema(&self) -> Value {
+        self.inner.parameters_schema()
+    }
+
+    async fn execute(
+        &self,
+        parameters: Option<Value>,
+        _agent_context: Option<&crate::agent::AgentContext>,
+    ) -> Result<ToolResult, ToolError> {
+        let name = self.inner.name();
+        let input = parameters.clone().unwrap_or(Value::Null);
+        if let Some(result) = self.fixtures.resolve(name, &input) {
+            tracing::debug!("Dry run: answered '{}' from fixture", name);
+            return Ok(result);
+        }
+        if let Some(result) = self.inner.simulate(parameters).await {
+            tracing::debug!("Dry run: answered '{}' from its simulation", name);
+            return Ok(result);
+        }
+        tracing::debug!("Dry run: answered '{}' with a generic result", name);
+        Ok(simulated_result(name, &input))
+    }
+
+    fn is_available(&self) -> bool {
+        self.inner.is_available()
+    }
+
+    fn source(&self) -> ToolSource {
+        self.inner.source()
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        self.inner.annotations()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+
+    #[derive(Debug)]
+    struct DeleteFiles;
+
+    #[async_trait]
+    impl Tool for DeleteFiles {
+        fn name(&self) -> &str {
+            "delete_files"
+        }
+
+        fn description(&self) -> &str {
+            "Delete files"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object", "properties": {"glob": {"type": "string"}}})
+        }
+
+        async fn execute(
+            &self,
+            _parameters: Option<Value>,
+            _agent_context: Option<&crate::agent::AgentContext>,
+        ) -> Result<ToolResult, ToolError> {
+            panic!("dry run must not execute the tool")
+        }
+
+        async fn simulate(&self, parameters: Option<Value>) -> Option<ToolResult> {
+            let glob = parameters?.get("glob")?.as_str()?.to_string();
+            Some(ToolResult::success(json!({ "deleted": [glob] })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_prefers_fixtures_then_simulation() {
+        let registry = ToolRegistry::new();
+        registry.register_tool(Box::new(DeleteFiles)).await.unwrap();
+
+        let simulated = registry.dry_run(ToolFixtures::new());
+        assert!(simulated.is_dry_run() && !registry.is_dry_run());
+        let result = simulated
+            .execute_tool("delete_files", Some(json!({"glob": "*.log"})), None)
+            .await
+            .unwrap();
+        assert_eq!(result.content, json!({ "deleted": ["*.log"] }));
+
+        let generic = simulated
+            .execute_tool("delete_files", Some(json!({})), None)
+            .await
+            .unwrap();
+        assert_eq!(generic.content["simulated"], true);
+
+        let fixtures = ToolFixtures::new().with_error("delete_files", "permission denied");
+        let tool = registry
+            .dry_run(fixtures)
+            .get_tool("delete_files")
+            .await
+            .unwrap();
+        let failed = tool
+            .execute(Some(json!({"glob": "*"})), None)
+            .await
+            .unwrap();
+        assert_eq!(failed.error.as_deref(), Some("permission denied"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Templated tool descriptions.
+//!
+//! Tool descriptions may contain Handlebars-style variables that are resolved
+//! each time the tool schemas are sent to the model, so a description can
+//! mention the current workspace, the user's locale or today's date without
+//! the tool being registered again:
+//!
+//! ```text
+//! Search the customer's files. Paths are relative to {{workspace}}.
+//! {{#if locale}}Return dates formatted for {{locale}}.{{else}}Return ISO 8601 dates.{{/if}}
+//! ```
+//!
+//! Variables come from the registry's [`DescriptionContext`] (see
+//! [`ToolRegistry::set_description_variable`](super::ToolRegistry::set_description_variable))
+//! and, during agent executions, from these built-in variables:
+//!
+//! - `date` - today's date (UTC), e.g. `2025-03-14`
+//! - `agent_id` and `agent_name` - the executing agent
+//! - `model` - the model ID of the execution
+//! - `workspace` - the execution's [`Workspace`](super::workspace::Workspace) directory, if enabled
+//!
+//! Variables set on the registry take precedence over built-in ones. Unknown
+//! variables are left as they are, so descriptions that happen to contain
+//! `{{` render unchanged.
+
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+static CONDITIONAL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)\{\{#if\s+([A-Za-z_][\w.]*)\s*\}\}(.*?)(?:\{\{else\}\}(.*?))?\{\{/if\}\}")
+        .expect("valid conditional regex")
+});
+
+static VARIABLE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_][\w.]*)\s*\}\}").expect("valid variable regex"));
+
+/// Variables for tool description templates
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DescriptionContext {
+    variables: BTreeMap<String, String>,
+}
+
+impl DescriptionContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set variable `name`
+    pub fn with(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Set variable `name`, replacing any previous value
+    pub fn set(&mut self, name: impl Into<String>, value: impl ToString) {
+        self.variables.insert(name.into(), value.to_string());
+    }
+
+    /// Set variable `name` unless it already has a value
+    pub fn set_default(&mut self, name: impl Into<String>, value: impl ToString) {
+        self.variables
+            .entry(name.into())
+            .or_insert_with(|| value.to_string());
+    }
+
+    /// Set the variables of `other`, replacing values set before
+    pub fn extend(&mut self, other: &DescriptionContext) {
+        self.variables.extend(other.variables.clone());
+    }
+
+    /// Add the variables of `defaults` that are not set in this context
+    pub fn with_defaults(mut self, defaults: &DescriptionContext) -> Self {
+        for (name, value) in &defaults.variables {
+            self.set_default(name.clone(), value);
+        }
+        self
+    }
+
+    /// Remove variable `name`, returning its value
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.variables.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+
+    /// Resolve the variables and conditionals in `template`
+    ///
+    /// `{{#if name}}` blocks are kept when `name` is set to a non-empty value;
+    /// they cannot be nested.
+    pub fn render(&self, template: &str) -> String {
+        if !template.contains("{{") {
+            return template.to_string();
+        }
+        let template = CONDITIONAL.replace_all(template, |caps: &Captures| {
+            let set = self.get(&caps[1]).is_some_and(|value| !value.is_empty());
+            match (set, caps.get(3)) {
+                (true, _) => caps[2].to_string(),
+                (false, Some(otherwise)) => otherwise.as_str().to_string(),
+                (false, None) => String::new(),
+            }
+        });
+        VARIABLE
+            .replace_all(&template, |caps: &Captures| match self.get(&caps[1]) {
+                Some(value) => value.to_string(),
+                None => caps[0].to_string(),
+            })
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_variables_and_conditionals() {
+        let template = "Files are in {{ workspace }}. \
+                        {{#if locale}}Use {{locale}} dates.{{else}}Use ISO dates.{{/if}} \
+                        Example: {{\"key\": 1}} {{unknown}}";
+        let mut context = DescriptionContext::new().with("workspace", "/tmp/ws");
+        assert_eq!(
+            context.render(template),
+            "Files are in /tmp/ws. Use ISO dates. Example: {{\"key\": 1}} {{unknown}}"
+        );
+
+        context.set("locale", "fr-FR");
+        let context = context.with_defaults(&DescriptionContext::new().with("locale", "en-US"));
+        assert!(context
+            .render(template)
+            .starts_with("Files are in /tmp/ws. Use fr-FR dates."));
+        assert_eq!(context.render("No variables"), "No variables");
+    }
+}
@@ -0,0 +1,225 @@
+//! Several versions of a tool under one name.
+//!
+//! As tool contracts evolve, a new version can be registered next to the old
+//! one instead of replacing it. Each tool reports its [`Tool::version`]; the
+//! [`ToolRegistry`] keeps every registered version of a name and offers the
+//! latest to models. An agent that still depends on an older contract pins it
+//! with [`AgentBuilder::with_tool_version`](crate::agent::AgentBuilder::with_tool_version)
+//! or [`ToolRegistry::pin_version`].
+//!
+//! Versions are compared by their dot-separated numeric components, so
+//! `"1.10"` is newer than `"1.9"`, and a leading `v` is ignored. Versions
+//! that are not numeric are compared as text.
+//!
+//! Versions that should no longer be used return a reason from
+//! [`Tool::deprecation`]. It is logged when the version is registered or
+//! pinned and shown in the [generated documentation](super::docs).
+//!
+//! ```no_run
+//! use stood::agent::Agent;
+//! # use stood::tools::Tool;
+//!
+//! # async fn example(search_v1: Box<dyn Tool>, search_v2: Box<dyn Tool>) -> Result<(), Box<dyn std::error::Error>> {
+//! // Both report the name "search", with versions "1.0.0" and "2.0.0"
+//! let agent = Agent::builder()
+//!     .tool(search_v1)
+//!     .tool(search_v2)
+//!     .with_tool_version("search", "1.0.0")
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use super::{Tool, ToolError, ToolRegistry};
+
+/// Version of tools that do not override [`Tool::version`]
+pub const DEFAULT_VERSION: &str = "1.0.0";
+
+/// Order two tool versions, oldest first
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (numeric_parts(a), numeric_parts(b)) {
+        (Some(a), Some(b)) => {
+            let len = a.len().max(b.len());
+            let component = |parts: &[u64], i: usize| parts.get(i).copied().unwrap_or(0);
+            (0..len)
+                .map(|i| component(&a, i).cmp(&component(&b, i)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        }
+        _ => a.cmp(b),
+    }
+}
+
+fn numeric_parts(version: &str) -> Option<Vec<u64>> {
+    version
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+impl ToolRegistry {
+    /// Registered versions of the tool `name`, oldest first
+    pub async fn tool_versions(&self, name: &str) -> Vec<String> {
+        let versions = self.versions.read().await;
+        versions
+            .get(name)
+            .map(|tools| {
+                tools
+                    .iter()
+                    .map(|tool| tool.version().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// A specific registered version of the tool `name`
+    pub async fn get_tool_version(&self, name: &str, version: &str) -> Option<Arc<dyn Tool>> {
+        let versions = self.versions.read().await;
+        versions
+            .get(name)?
+            .iter()
+            .find(|tool| tool.version() == version)
+            .map(|tool| self.for_execution(tool))
+    }
+
+    /// Offer `version` of the tool `name` to models instead of the latest
+    ///
+    /// The pin holds when newer versions are registered later. Fails with
+    /// [`ToolError::ToolNotFound`] if that version is not registered.
+    pub async fn pin_version(&self, name: &str, version: &str) -> Result<(), ToolError> {
+        let tool = {
+            let versions = self.versions.read().await;
+            versions
+                .get(name)
+                .and_then(|tools| tools.iter().find(|tool| tool.version() == version))
+                .cloned()
+                .ok_or_else(|| ToolError::ToolNotFound {
+                    name: format!("{}@{}", name, version),
+                })?
+        };
+
+        if let Some(reason) = tool.deprecation() {
+            tracing::warn!("Pinned deprecated tool {} {}: {}", name, version, reason);
+        }
+        self.pins
+            .write()
+            .await
+            .insert(name.to_string(), version.to_string());
+        self.tools.write().await.insert(name.to_string(), tool);
+        Ok(())
+    }
+
+    /// Offer the latest version of the tool `name` again
+    pub async fn unpin_version(&self, name: &str) {
+        self.pins.write().await.remove(name);
+        let latest = self
+            .versions
+            .read()
+            .await
+            .get(name)
+            .and_then(|tools| tools.last().cloned());
+        if let Some(latest) = latest {
+            self.tools.write().await.insert(name.to_string(), latest);
+        }
+    }
+
+    /// Versions pinned by tool name
+    pub async fn pinned_versions(&self) -> std::collections::HashMap<String, String> {
+        self.pins.read().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+
+    #[derive(Debug)]
+    struct SearchTool {
+        version: &'static str,
+        deprecation: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl Tool for SearchTool {
+        fn name(&self) -> &str {
+            "search"
+        }
+
+        fn description(&self) -> &str {
+            "Search the index"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object", "properties": {}})
+        }
+
+        fn version(&self) -> &str {
+            self.version
+        }
+
+        fn deprecation(&self) -> Option<&str> {
+            self.deprecation
+        }
+
+        async fn execute(
+            &self,
+            _parameters: Option<Value>,
+            _agent_context: Option<&crate::agent::AgentContext>,
+        ) -> Result<ToolResult, ToolError> {
+            Ok(ToolResult::success(json!(self.version)))
+        }
+    }
+
+    fn search(version: &'static str) -> Box<dyn Tool> {
+        Box::new(SearchTool {
+            version,
+            deprecation: None,
+        })
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v2", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0", "1.0.1"), Ordering::Less);
+        assert_eq!(compare_versions("beta", "alpha"), Ordering::Greater);
+    }
+
+    #[tokio::test]
+    async fn test_latest_version_offered_unless_pinned() {
+        let registry = ToolRegistry::new();
+        registry.register_tool(search("2.0.0")).await.unwrap();
+        registry.register_tool(search("1.0.0")).await.unwrap();
+        assert!(matches!(
+            registry.register_tool(search("2.0.0")).await,
+            Err(ToolError::DuplicateTool { .. })
+        ));
+
+        assert_eq!(registry.tool_versions("search").await, ["1.0.0", "2.0.0"]);
+        assert_eq!(registry.tool_names().await, ["search"]);
+        let result = registry.execute_tool("search", None, None).await.unwrap();
+        assert_eq!(result.content, json!("2.0.0"));
+
+        registry.pin_version("search", "1.0.0").await.unwrap();
+        registry.register_tool(search("3.0.0")).await.unwrap();
+        let result = registry.execute_tool("search", None, None).await.unwrap();
+        assert_eq!(result.content, json!("1.0.0"));
+        assert!(matches!(
+            registry.pin_version("search", "9.0.0").await,
+            Err(ToolError::ToolNotFound { name }) if name == "search@9.0.0"
+        ));
+
+        registry.unpin_version("search").await;
+        let tool = registry.get_tool("search").await.unwrap();
+        assert_eq!(tool.version(), "3.0.0");
+        assert!(registry.pinned_versions().await.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! Per-execution workspace shared by the tools of an agent.
+//!
+//! Tools that produce large intermediate data (downloaded pages, query
+//! results, generated files) should not return it through the conversation,
+//! where every byte costs tokens on every later model call. With
+//! [`AgentBuilder::with_workspace`](crate::agent::AgentBuilder::with_workspace),
+//! each execution gets a [`Workspace`]: a private temporary directory and a
+//! key-value scratchpad, available to tools through
+//! [`AgentContext::workspace`](crate::agent::AgentContext::workspace). A tool
+//! stores its output there and returns a short reference (a relative path or a
+//! key) that the model passes to the next tool.
+//!
+//! ```
+//! use serde_json::json;
+//! use stood::tools::workspace::Workspace;
+//!
+//! # fn example() -> std::io::Result<()> {
+//! let workspace = Workspace::new()?;
+//!
+//! // One tool writes a large result and returns its path
+//! let path = workspace.write_file("pages/report.html", "<html>...</html>")?;
+//! assert!(path.starts_with(workspace.path()));
+//!
+//! // Another tool reads it back from the reference the model passed on
+//! assert_eq!(workspace.read_to_string("pages/report.html")?, "<html>...</html>");
+//!
+//! workspace.put("row_count", json!(125_000));
+//! assert_eq!(workspace.get("row_count"), Some(json!(125_000)));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The directory and scratchpad are deleted when the execution ends (when the
+//! last clone of the workspace is dropped).
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value;
+
+#[derive(Debug)]
+struct WorkspaceInner {
+    id: String,
+    dir: PathBuf,
+    scratchpad: RwLock<HashMap<String, Value>>,
+}
+
+impl Drop for WorkspaceInner {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            if e.kind() != io::ErrorKind::NotFound {
+                tracing::warn!(
+                    "Failed to remove workspace directory {}: {}",
+                    self.dir.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Temporary directory and key-value scratchpad of one execution
+///
+/// Clones share the same directory and scratchpad.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    inner: Arc<WorkspaceInner>,
+}
+
+impl Workspace {
+    /// Create a workspace in the system temporary directory
+    pub fn new() -> io::Result<Self> {
+        Self::new_in(std::env::temp_dir())
+    }
+
+    /// Create a workspace in `parent`
+    pub fn new_in(parent: impl AsRef<Path>) -> io::Result<Self> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let dir = parent.as_ref().join(format!("stood-workspace-{}", id));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            inner: Arc::new(WorkspaceInner {
+                id,
+                dir,
+                scratchpad: RwLock::new(HashMap::new()),
+            }),
+        })
+    }
+
+    /// Unique id of the workspace
+    pub fn id(&self) -> &str {
+        &self.inner.id
+    }
+
+    /// Root of the workspace directory
+    pub fn path(&self) -> &Path {
+        &self.inner.dir
+    }
+
+    /// Absolute path of `relative` inside the workspace
+    ///
+    /// Fails for absolute paths and paths that would leave the workspace.
+    pub fn resolve(&self, relative: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let relative = relative.as_ref();
+        let mut depth = 0usize;
+        for component in relative.components() {
+            match component {
+                Component::Normal(_) => depth += 1,
+                Component::CurDir => {}
+                Component::ParentDir if depth > 0 => depth -= 1,
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("'{}' is outside the workspace", relative.display()),
+                    ))
+                }
+            }
+        }
+        Ok(self.inner.dir.join(relative))
+    }
+
+    /// Write `contents` to `relative`, creating parent directories; returns the
+    /// absolute path
+    pub fn write_file(
+        &self,
+        relative: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> io::Result<PathBuf> {
+        let path = self.resolve(relative)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, contents)?;
+        Ok(path)
+    }
+
+    /// Contents of the file at `relative`
+    pub fn read_file(&self, relative: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+        std::fs::read(self.resolve(relative)?)
+    }
+
+    /// Contents of the text file at `relative`
+    pub fn read_to_string(&self, relative: impl AsRef<Path>) -> io::Result<String> {
+        std::fs::read_to_string(self.resolve(relative)?)
+    }
+
+    /// Store `value` in the scratchpad under `key`, returning the previous value
+    pub fn put(&self, key: impl Into<String>, value: Value) -> Option<Value> {
+        self.scratchpad_mut().insert(key.into(), value)
+    }
+
+    /// Value stored under `key`
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.inner
+            .scratchpad
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .cloned()
+    }
+
+    /// Remove and return the value stored under `key`
+    pub fn remove(&self, key: &str) -> Option<Value> {
+        self.scratchpad_mut().remove(key)
+    }
+
+    /// Keys in the scratchpad, sorted
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .inner
+            .scratchpad
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    fn scratchpad_mut(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, Value>> {
+        self.inner
+            .scratchpad
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_workspace_files_scratchpad_and_cleanup() {
+        let workspace = Workspace::new().unwrap();
+        let dir = workspace.path().to_path_buf();
+        let shared = workspace.clone();
+
+        workspace.write_file("a/b.txt", "data").unwrap();
+        assert_eq!(shared.read_to_string("a/./b.txt").unwrap(), "data");
+        assert!(workspace.resolve("a/../b.txt").is_ok());
+        assert!(workspace.resolve("../escape.txt").is_err());
+        assert!(workspace.resolve("a/../../escape.txt").is_err());
+        assert!(workspace.resolve("/etc/passwd").is_err());
+
+        assert_eq!(workspace.put("k", json!(1)), None);
+        assert_eq!(shared.put("k", json!(2)), Some(json!(1)));
+        assert_eq!(workspace.keys(), vec!["k".to_string()]);
+        assert_eq!(workspace.remove("k"), Some(json!(2)));
+
+        drop(workspace);
+        assert!(dir.exists());
+        drop(shared);
+        assert!(!dir.exists());
+    }
+}
@@ -2,10 +2,23 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 use uuid::Uuid;
 
 use super::content::ContentBlock;
 
+/// Metadata key naming where a message came from (e.g. `"rag"`, `"memory"`)
+pub const SOURCE_METADATA_KEY: &str = "source";
+
+/// Metadata key holding a message's importance, higher is more important
+pub const IMPORTANCE_METADATA_KEY: &str = "importance";
+
+/// Metadata key marking a message that context policies must never trim
+pub const PINNED_METADATA_KEY: &str = "pinned";
+
+/// Metadata key holding a message's time to live in seconds
+pub const TTL_METADATA_KEY: &str = "ttl";
+
 /// Role of a message in the conversation
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -67,6 +80,75 @@ impl Message {
         self
     }
 
+    /// Record where this message came from
+    pub fn with_source(self, source: impl Into<String>) -> Self {
+        self.with_metadata(
+            SOURCE_METADATA_KEY.to_string(),
+            serde_json::Value::String(source.into()),
+        )
+    }
+
+    /// Where this message came from, if recorded
+    pub fn source(&self) -> Option<&str> {
+        self.metadata.get(SOURCE_METADATA_KEY)?.as_str()
+    }
+
+    /// Set the importance context policies weigh this message by
+    pub fn with_importance(self, importance: f64) -> Self {
+        self.with_metadata(IMPORTANCE_METADATA_KEY.to_string(), importance.into())
+    }
+
+    /// Importance of this message, if set
+    pub fn importance(&self) -> Option<f64> {
+        self.metadata.get(IMPORTANCE_METADATA_KEY)?.as_f64()
+    }
+
+    /// Pin this message so context policies never trim it
+    pub fn pinned(self) -> Self {
+        self.with_metadata(PINNED_METADATA_KEY.to_string(), true.into())
+    }
+
+    /// Whether this message is pinned
+    pub fn is_pinned(&self) -> bool {
+        self.metadata
+            .get(PINNED_METADATA_KEY)
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Expire this message `ttl` after its timestamp
+    ///
+    /// Stored in whole seconds. Expired messages are removed from the
+    /// conversation unless pinned.
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        self.with_metadata(TTL_METADATA_KEY.to_string(), ttl.as_secs().into())
+    }
+
+    /// Time to live of this message, if set
+    pub fn ttl(&self) -> Option<Duration> {
+        self.metadata
+            .get(TTL_METADATA_KEY)?
+            .as_u64()
+            .map(Duration::from_secs)
+    }
+
+    /// When this message expires, if it has a time to live
+    pub fn expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let ttl = chrono::Duration::from_std(self.ttl()?).ok()?;
+        self.timestamp.checked_add_signed(ttl)
+    }
+
+    /// Whether this message has expired at `now`
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at()
+            .is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Whether this message has expired
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(chrono::Utc::now())
+    }
+
     /// Get the text content of this message (if any)
     pub fn text(&self) -> Option<String> {
         self.content
@@ -277,4 +359,25 @@ mod tests {
 
         assert!(msg.metadata.contains_key("key"));
     }
+
+    #[test]
+    fn test_message_tags_survive_serialization() {
+        let msg = Message::user("Retrieved context")
+            .with_source("rag")
+            .with_importance(0.8)
+            .pinned()
+            .with_ttl(Duration::from_secs(60));
+
+        let json = serde_json::to_string(&msg).unwrap();
+        let restored: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, msg);
+        assert_eq!(restored.source(), Some("rag"));
+        assert_eq!(restored.importance(), Some(0.8));
+        assert!(restored.is_pinned());
+        assert_eq!(restored.ttl(), Some(Duration::from_secs(60)));
+
+        assert!(!restored.is_expired_at(msg.timestamp + chrono::Duration::seconds(59)));
+        assert!(restored.is_expired_at(msg.timestamp + chrono::Duration::seconds(60)));
+        assert!(!Message::user("no ttl").is_expired());
+    }
 }
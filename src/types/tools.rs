@@ -135,10 +135,11 @@ impl Tool {
 }
 
 /// How the LLM should choose which tools to use
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum ToolChoice {
     /// LLM can choose any available tool or no tool
+    #[default]
     Auto,
     /// LLM must use at least one tool
     Any,
@@ -146,6 +147,13 @@ pub enum ToolChoice {
     Tool { name: String },
 }
 
+impl ToolChoice {
+    /// Require a call of the tool `name`
+    pub fn tool(name: impl Into<String>) -> Self {
+        Self::Tool { name: name.into() }
+    }
+}
+
 /// A tool use request from the LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolUse {
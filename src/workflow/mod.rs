@@ -0,0 +1,647 @@
+//! Multi-step agent workflows defined in YAML.
+//!
+//! A workflow runs a sequence of steps, each executing a prompt on one of the
+//! agents it defines, so orchestrations can be changed without touching Rust
+//! code. Load one with [`from_yaml`] or [`from_yaml_file`] and run it with
+//! [`Workflow::run`]:
+//!
+//! ```yaml
+//! name: incident-report
+//! inputs: [ticket]
+//!
+//! agents:
+//!   triage:                          # inline agent definition
+//!     model:
+//!       id: us.anthropic.claude-haiku-4-5-20251001-v1:0
+//!     prompt:
+//!       system: You classify support tickets.
+//!   writer:
+//!     file: agents/writer.toml       # agent file, relative to the workflow
+//!
+//! steps:
+//!   - id: classify
+//!     agent: triage
+//!     prompt: "Answer URGENT or ROUTINE: {{ inputs.ticket }}"
+//!     output: severity               # also available as {{ severity }}
+//!   - id: report
+//!     agent: writer
+//!     when:
+//!       value: "{{ severity }}"
+//!       contains: URGENT
+//!     input:                         # names for this step's prompt
+//!       details: "{{ inputs.ticket }}"
+//!     prompt: "Write an incident report for: {{ details }}"
+//!     retries: 2
+//!
+//! outputs:
+//!   severity: "{{ steps.classify }}"
+//!   report: "{{ steps.report }}"
+//! ```
+//!
+//! Agents use the [agent file](crate::config::agent_file) format. Templates
+//! reference workflow inputs as `{{ inputs.name }}`, the responses of earlier
+//! steps as `{{ steps.id }}`, and the step's `input` mappings and earlier
+//! steps' `output` names by name alone. Steps skipped by their `when`
+//! condition have an empty response. Values may reference environment
+//! variables as in agent files.
+//!
+//! Loading validates the whole workflow and reports every problem found, e.g.
+//! a step using an undefined agent or referencing a later step.
+//!
+//! ```no_run
+//! use std::collections::HashMap;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let workflow = stood::workflow::from_yaml_file("workflows/incident.yaml")?;
+//! let inputs = HashMap::from([("ticket".to_string(), "Checkout is down".to_string())]);
+//! let result = workflow.run(inputs).await?;
+//! println!("{}", result.outputs["report"]);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::agent::{Agent, AgentBuilder};
+use crate::config::agent_file::interpolate_env;
+use crate::config::{AgentFileConfig, ConfigError};
+use crate::{Result, StoodError};
+
+/// Load a workflow from a YAML document
+///
+/// Agent and prompt files are resolved relative to the current directory.
+pub fn from_yaml(yaml: &str) -> std::result::Result<Workflow, ConfigError> {
+    Workflow::parse(yaml, Path::new("."))
+}
+
+/// Load a workflow from a YAML file
+///
+/// Agent and prompt files are resolved relative to the workflow file.
+pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> std::result::Result<Workflow, ConfigError> {
+    let path = path.as_ref();
+    let yaml = fs::read_to_string(path)?;
+    Workflow::parse(&yaml, path.parent().unwrap_or_else(|| Path::new(".")))
+}
+
+/// A validated multi-step agent workflow
+#[derive(Debug, Clone)]
+pub struct Workflow {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Names of the inputs the workflow is run with
+    pub inputs: Vec<String>,
+    /// Agent definitions by name
+    pub agents: BTreeMap<String, AgentFileConfig>,
+    /// Steps, in execution order
+    pub steps: Vec<WorkflowStep>,
+    /// Templates of the workflow's outputs, by name
+    pub outputs: BTreeMap<String, String>,
+}
+
+/// A step executing a prompt on one of the workflow's agents
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkflowStep {
+    /// Unique id, referenced as `{{ steps.id }}`
+    pub id: String,
+    /// Name of the agent executing the step
+    pub agent: String,
+    /// Prompt template
+    pub prompt: String,
+    /// Templates of names available to this step's prompt and condition
+    #[serde(default)]
+    pub input: BTreeMap<String, String>,
+    /// Name making the response available to later steps as `{{ name }}`
+    pub output: Option<String>,
+    /// Condition for running the step
+    pub when: Option<Condition>,
+    /// Extra attempts after a failed execution
+    #[serde(default)]
+    pub retries: u32,
+}
+
+/// Condition on a rendered template; every test set must hold
+///
+/// Without tests, the value must not be blank.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    /// Template to test, e.g. `{{ steps.classify }}`
+    pub value: String,
+    pub equals: Option<String>,
+    pub not_equals: Option<String>,
+    pub contains: Option<String>,
+    pub not_contains: Option<String>,
+}
+
+impl Condition {
+    /// Whether the condition holds for the rendered `value`
+    ///
+    /// Leading and trailing whitespace of the value is ignored.
+    pub fn holds(&self, value: &str) -> bool {
+        let value = value.trim();
+        let tests = [
+            self.equals.as_ref().map(|expected| value == expected),
+            self.not_equals.as_ref().map(|expected| value != expected),
+            self.contains
+                .as_ref()
+                .map(|text| value.contains(text.as_str())),
+            self.not_contains
+                .as_ref()
+                .map(|text| !value.contains(text.as_str())),
+        ];
+        if tests.iter().all(Option::is_none) {
+            return !value.is_empty();
+        }
+        tests.into_iter().flatten().all(|holds| holds)
+    }
+}
+
+/// Outcome of a workflow run
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowResult {
+    /// Rendered outputs, by name
+    pub outputs: BTreeMap<String, String>,
+    /// What each step did, in order
+    pub steps: Vec<StepRecord>,
+}
+
+/// What a step did during a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepRecord {
+    pub id: String,
+    pub status: StepStatus,
+    /// The agent's response, empty if skipped
+    pub response: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepStatus {
+    /// Completed after `attempts` executions
+    Completed { attempts: u32 },
+    /// Skipped because its condition did not hold
+    Skipped,
+}
+
+/// The document as written, before validation
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkflowDocument {
+    name: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    inputs: Vec<String>,
+    agents: BTreeMap<String, serde_yaml::Value>,
+    steps: Vec<WorkflowStep>,
+    #[serde(default)]
+    outputs: BTreeMap<String, String>,
+}
+
+/// An agent given as a reference to an agent file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AgentFileReference {
+    file: PathBuf,
+}
+
+impl Workflow {
+    fn parse(yaml: &str, base: &Path) -> std::result::Result<Self, ConfigError> {
+        let document: WorkflowDocument = serde_yaml::from_str(&interpolate_env(yaml)?)
+            .map_err(|e| ConfigError::FileParse(format!("Invalid workflow: {}", e)))?;
+
+        let agent_names: Vec<String> = document.agents.keys().cloned().collect();
+        let mut problems = Vec::new();
+        let mut agents = BTreeMap::new();
+        for (name, definition) in document.agents {
+            match load_agent(definition, base) {
+                Ok(agent) => {
+                    agents.insert(name, agent);
+                }
+                Err(e) => problems.push(format!("agent '{}': {}", name, e)),
+            }
+        }
+
+        let workflow = Self {
+            name: document.name,
+            description: document.description,
+            inputs: document.inputs,
+            agents,
+            steps: document.steps,
+            outputs: document.outputs,
+        };
+        problems.extend(workflow.problems(&agent_names));
+        if problems.is_empty() {
+            Ok(workflow)
+        } else {
+            Err(ConfigError::Validation(format!(
+                "Invalid workflow:\n  - {}",
+                problems.join("\n  - ")
+            )))
+        }
+    }
+
+    /// Everything wrong with the workflow's structure and references, given
+    /// the names of all agents defined (including ones that failed to load)
+    fn problems(&self, agent_names: &[String]) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.steps.is_empty() {
+            problems.push("the workflow has no steps".to_string());
+        }
+        let inputs: HashSet<&str> = self.inputs.iter().map(String::as_str).collect();
+        let all_steps: HashSet<&str> = self.steps.iter().map(|s| s.id.as_str()).collect();
+        let mut earlier_steps = HashSet::new();
+        let mut outputs = HashSet::new();
+
+        for (index, step) in self.steps.iter().enumerate() {
+            let at = format!("step '{}' (#{})", step.id, index + 1);
+            if step.id.is_empty() || step.id.contains('.') {
+                problems.push(format!("{}: ids must be non-empty without '.'", at));
+            }
+            if earlier_steps.contains(step.id.as_str()) {
+                problems.push(format!("{}: duplicate step id", at));
+            }
+            if !agent_names.contains(&step.agent) {
+                problems.push(format!(
+                    "{}: unknown agent '{}' (defined: {})",
+                    at,
+                    step.agent,
+                    agent_names.join(", ")
+                ));
+            }
+
+            let known = |locals: &HashSet<&str>, template: &str, problems: &mut Vec<String>| {
+                for reference in references(template) {
+                    let problem = match reference {
+                        Err(e) => Some(e),
+                        Ok(Reference::Input(name)) if !inputs.contains(name) => {
+                            Some(format!("input '{}' is not declared in `inputs`", name))
+                        }
+                        Ok(Reference::Step(id)) if !earlier_steps.contains(id) => {
+                            Some(if all_steps.contains(id) {
+                                format!("step '{}' has not run yet", id)
+                            } else {
+                                format!("unknown step '{}'", id)
+                            })
+                        }
+                        Ok(Reference::Name(name))
+                            if !locals.contains(name) && !outputs.contains(name) =>
+                        {
+                            Some(format!("unknown name '{}'", name))
+                        }
+                        Ok(_) => None,
+                    };
+                    if let Some(problem) = problem {
+                        problems.push(format!("{}: {}", at, problem));
+                    }
+                }
+            };
+            let no_locals = HashSet::new();
+            for template in step.input.values() {
+                known(&no_locals, template, &mut problems);
+            }
+            let locals: HashSet<&str> = step.input.keys().map(String::as_str).collect();
+            known(&locals, &step.prompt, &mut problems);
+            if let Some(condition) = &step.when {
+                known(&locals, &condition.value, &mut problems);
+            }
+
+            earlier_steps.insert(step.id.as_str());
+            if let Some(output) = &step.output {
+                if !outputs.insert(output.as_str()) {
+                    problems.push(format!("{}: output '{}' is already defined", at, output));
+                }
+            }
+        }
+
+        for (name, template) in &self.outputs {
+            for reference in references(template) {
+                let problem = match reference {
+                    Err(e) => Some(e),
+                    Ok(Reference::Input(input)) if !inputs.contains(input) => {
+                        Some(format!("input '{}' is not declared in `inputs`", input))
+                    }
+                    Ok(Reference::Step(id)) if !all_steps.contains(id) => {
+                        Some(format!("unknown step '{}'", id))
+                    }
+                    Ok(Reference::Name(output)) if !outputs.contains(output) => {
+                        Some(format!("unknown name '{}'", output))
+                    }
+                    Ok(_) => None,
+                };
+                if let Some(problem) = problem {
+                    problems.push(format!("output '{}': {}", name, problem));
+                }
+            }
+        }
+        problems
+    }
+
+    /// Run the steps in order with `inputs`
+    ///
+    /// Each step executes on a fresh copy of its agent, built once per run.
+    /// Fails if an input is missing or a step still fails after its retries.
+    pub async fn run(&self, inputs: HashMap<String, String>) -> Result<WorkflowResult> {
+        let missing: Vec<&str> = self
+            .inputs
+            .iter()
+            .filter(|name| !inputs.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(StoodError::invalid_input(format!(
+                "Missing workflow inputs: {}",
+                missing.join(", ")
+            )));
+        }
+
+        let mut agents: HashMap<&str, Agent> = HashMap::new();
+        for (name, definition) in &self.agents {
+            let agent = AgentBuilder::from_agent_config(definition.clone())?
+                .try_build()
+                .await
+                .map_err(|e| {
+                    StoodError::configuration_error(format!(
+                        "Failed to build workflow agent '{}': {}",
+                        name, e
+                    ))
+                })?;
+            agents.insert(name, agent);
+        }
+
+        let mut scope = Scope {
+            inputs: &inputs,
+            steps: HashMap::new(),
+            outputs: HashMap::new(),
+        };
+        let mut records = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            let mut locals = HashMap::new();
+            for (name, template) in &step.input {
+                locals.insert(name.as_str(), scope.render(template, &HashMap::new())?);
+            }
+
+            let runs = match &step.when {
+                Some(condition) => condition.holds(&scope.render(&condition.value, &locals)?),
+                None => true,
+            };
+            let (status, response) = if runs {
+                let prompt = scope.render(&step.prompt, &locals)?;
+                let (response, attempts) =
+                    run_step(step, &agents[step.agent.as_str()], &prompt).await?;
+                (StepStatus::Completed { attempts }, response)
+            } else {
+                tracing::debug!("Skipping workflow step '{}': condition not met", step.id);
+                (StepStatus::Skipped, String::new())
+            };
+
+            scope.steps.insert(step.id.as_str(), response.clone());
+            if let Some(output) = &step.output {
+                scope.outputs.insert(output.as_str(), response.clone());
+            }
+            records.push(StepRecord {
+                id: step.id.clone(),
+                status,
+                response,
+            });
+        }
+
+        let mut outputs = BTreeMap::new();
+        for (name, template) in &self.outputs {
+            outputs.insert(name.clone(), scope.render(template, &HashMap::new())?);
+        }
+        Ok(WorkflowResult {
+            outputs,
+            steps: records,
+        })
+    }
+}
+
+/// Execute `prompt` for `step` on a copy of `agent`, retrying failures
+///
+/// Returns the response and the number of attempts.
+async fn run_step(step: &WorkflowStep, agent: &Agent, prompt: &str) -> Result<(String, u32)> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match agent.clone().execute(prompt).await {
+            Ok(result) if result.success => return Ok((result.response, attempts)),
+            Ok(result) => result
+                .error
+                .unwrap_or_else(|| "execution did not succeed".to_string()),
+            Err(e) => e.to_string(),
+        };
+        if attempts > step.retries {
+            return Err(StoodError::model_error(format!(
+                "Workflow step '{}' failed after {} attempt(s): {}",
+                step.id, attempts, error
+            )));
+        }
+        tracing::warn!(
+            "Workflow step '{}' failed (attempt {}), retrying: {}",
+            step.id,
+            attempts,
+            error
+        );
+    }
+}
+
+fn load_agent(
+    definition: serde_yaml::Value,
+    base: &Path,
+) -> std::result::Result<AgentFileConfig, String> {
+    let is_reference = definition
+        .as_mapping()
+        .is_some_and(|mapping| mapping.len() == 1 && mapping.contains_key("file"));
+    if is_reference {
+        let reference: AgentFileReference =
+            serde_yaml::from_value(definition).map_err(|e| e.to_string())?;
+        return AgentFileConfig::from_file(base.join(&reference.file))
+            .map_err(|e| format!("failed to load {}: {}", reference.file.display(), e));
+    }
+    let mut config: AgentFileConfig =
+        serde_yaml::from_value(definition).map_err(|e| e.to_string())?;
+    config.load_system_file(base).map_err(|e| e.to_string())?;
+    config.provider().map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+/// A value referenced by a template
+#[derive(Debug, PartialEq)]
+enum Reference<'a> {
+    /// `inputs.name`
+    Input(&'a str),
+    /// `steps.id`
+    Step(&'a str),
+    /// A step input or an earlier step's output
+    Name(&'a str),
+}
+
+/// The references of `template`, or why one is malformed
+fn references(template: &str) -> Vec<std::result::Result<Reference<'_>, String>> {
+    let mut references = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            references.push(Err(format!("unterminated '{{{{' in '{}'", template)));
+            break;
+        };
+        let expression = after[..end].trim();
+        references.push(match expression.split_once('.') {
+            Some(("inputs", name)) => Ok(Reference::Input(name)),
+            Some(("steps", id)) => Ok(Reference::Step(id)),
+            Some(_) => Err(format!(
+                "'{}' is not `inputs.<name>`, `steps.<id>` or a name",
+                expression
+            )),
+            None if expression.is_empty() => Err("empty '{{ }}'".to_string()),
+            None => Ok(Reference::Name(expression)),
+        });
+        rest = &after[end + 2..];
+    }
+    references
+}
+
+/// Values templates can reference during a run
+struct Scope<'a> {
+    inputs: &'a HashMap<String, String>,
+    steps: HashMap<&'a str, String>,
+    outputs: HashMap<&'a str, String>,
+}
+
+impl Scope<'_> {
+    /// Replace the references of `template`, looking names up in `locals`
+    /// before step outputs
+    fn render(&self, template: &str, locals: &HashMap<&str, String>) -> Result<String> {
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        for reference in references(template) {
+            let value = match reference.map_err(StoodError::invalid_input)? {
+                Reference::Input(name) => self.inputs.get(name),
+                Reference::Step(id) => self.steps.get(id),
+                Reference::Name(name) => locals.get(name).or_else(|| self.outputs.get(name)),
+            };
+            let value = value.ok_or_else(|| {
+                StoodError::invalid_input(format!("Unresolved reference in '{}'", template))
+            })?;
+            let start = rest.find("{{").unwrap_or(0);
+            let end = rest[start..]
+                .find("}}")
+                .map_or(rest.len(), |end| start + end + 2);
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(value);
+            rest = &rest[end..];
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"
+name: incident-report
+inputs: [ticket]
+agents:
+  triage:
+    model:
+      id: us.anthropic.claude-haiku-4-5-20251001-v1:0
+    prompt:
+      system: You classify support tickets.
+steps:
+  - id: classify
+    agent: triage
+    prompt: "Answer URGENT or ROUTINE: {{ inputs.ticket }}"
+    output: severity
+  - id: report
+    agent: triage
+    when:
+      value: "{{ severity }}"
+      contains: URGENT
+    input:
+      details: "{{inputs.ticket}}"
+    prompt: "Report on {{ details }} ({{ steps.classify }})"
+    retries: 2
+outputs:
+  report: "{{ steps.report }}"
+"#;
+
+    #[test]
+    fn test_from_yaml() {
+        let workflow = from_yaml(WORKFLOW).unwrap();
+        assert_eq!(workflow.name.as_deref(), Some("incident-report"));
+        assert_eq!(
+            workflow.agents["triage"].prompt.system.as_deref(),
+            Some("You classify support tickets.")
+        );
+        assert_eq!(workflow.steps.len(), 2);
+        assert_eq!(workflow.steps[1].retries, 2);
+        assert_eq!(
+            workflow.steps[1].when.as_ref().unwrap().contains.as_deref(),
+            Some("URGENT")
+        );
+    }
+
+    #[test]
+    fn test_validation_reports_every_problem() {
+        let yaml = WORKFLOW
+            .replace("agent: triage\n    when", "agent: writer\n    when")
+            .replace("{{ steps.classify }}", "{{ steps.summary }}")
+            .replace("{{ inputs.ticket }}", "{{ inputs.tikcet }}");
+        let Err(ConfigError::Validation(message)) = from_yaml(&yaml) else {
+            panic!("expected a validation error");
+        };
+        assert!(message.contains("step 'classify' (#1): input 'tikcet' is not declared"));
+        assert!(message.contains("step 'report' (#2): unknown agent 'writer' (defined: triage)"));
+        assert!(message.contains("step 'report' (#2): unknown step 'summary'"));
+
+        let yaml = WORKFLOW.replace("prompt: \"Answer", "prompt: \"{{ steps.report }} Answer");
+        let Err(ConfigError::Validation(message)) = from_yaml(&yaml) else {
+            panic!("expected a validation error");
+        };
+        assert!(message.contains("step 'report' has not run yet"));
+
+        let yaml = WORKFLOW.replace("      id: us.anthropic", "      idd: us.anthropic");
+        let Err(ConfigError::Validation(message)) = from_yaml(&yaml) else {
+            panic!("expected a validation error");
+        };
+        assert!(message.contains("agent 'triage': unknown field `idd`"));
+    }
+
+    #[test]
+    fn test_render_and_conditions() {
+        let inputs = HashMap::from([("ticket".to_string(), "Checkout is down".to_string())]);
+        let mut scope = Scope {
+            inputs: &inputs,
+            steps: HashMap::from([("classify", "URGENT".to_string())]),
+            outputs: HashMap::from([("severity", "URGENT".to_string())]),
+        };
+        let locals = HashMap::from([("details", "x".to_string())]);
+        assert_eq!(
+            scope
+                .render("{{inputs.ticket}}: {{ severity }}/{{ details }}!", &locals)
+                .unwrap(),
+            "Checkout is down: URGENT/x!"
+        );
+        assert!(scope.render("{{ missing }}", &locals).is_err());
+
+        let condition = Condition {
+            value: String::new(),
+            contains: Some("URGENT".to_string()),
+            not_equals: Some("NOT URGENT".to_string()),
+            ..Default::default()
+        };
+        assert!(condition.holds(" URGENT\n"));
+        assert!(!condition.holds("NOT URGENT"));
+        assert!(!Condition::default().holds("  "));
+
+        scope.steps.clear();
+        assert!(scope.render("{{ steps.classify }}", &locals).is_err());
+    }
+}
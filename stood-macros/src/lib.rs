@@ -339,9 +339,10 @@ fn generate_tool_trait_impl(
                     Ok(value) => {
                         // Serialize the result to JSON
                         let json_value = serde_json::to_value(value)
-                            .map_err(|e| stood::tools::ToolError::ExecutionFailed {
-                                message: format!("Failed to serialize result: {}", e)
-                            })?;
+                            .map_err(|e| stood::tools::ToolError::with_source(
+                                format!("Failed to serialize result: {}", e),
+                                e,
+                            ))?;
                         Ok(stood::tools::ToolResult::success(json_value))
                     }
                     Err(e) => {
@@ -11,6 +11,8 @@
 //!
 //! Run with: cargo test --test telemetry_cloudwatch_integration
 
+#![cfg(feature = "telemetry")]
+
 use std::collections::HashMap;
 use std::env;
 use std::time::Duration;
@@ -61,6 +61,8 @@ async fn test_nova_direct_tool_call() -> Result<(), Box<dyn std::error::Error>>
         model_id: "us.amazon.nova-lite-v1:0".to_string(),
         provider: ProviderType::Bedrock,
         enable_thinking: false,
+        cache_strategy: Default::default(),
+        tool_choice: Default::default(),
         additional_params: std::collections::HashMap::new(),
     };
 
@@ -137,6 +139,8 @@ async fn test_claude_direct_tool_call() -> Result<(), Box<dyn std::error::Error>
         model_id: "anthropic.claude-3-5-haiku-20241022-v1:0".to_string(),
         provider: ProviderType::Bedrock,
         enable_thinking: false,
+        cache_strategy: Default::default(),
+        tool_choice: Default::default(),
         additional_params: std::collections::HashMap::new(),
     };
 
@@ -59,6 +59,8 @@ async fn test_nova_tool_result_format() -> Result<(), Box<dyn std::error::Error>
         model_id: "us.amazon.nova-lite-v1:0".to_string(),
         provider: ProviderType::Bedrock,
         enable_thinking: false,
+        cache_strategy: Default::default(),
+        tool_choice: Default::default(),
         additional_params: std::collections::HashMap::new(),
     };
 